@@ -8,7 +8,7 @@ pub use hindsight_client::types;
 use hindsight_client::{Client as AsyncClient, Error as ClientError};
 use serde::{Deserialize, Serialize};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Convert a progenitor client error into an anyhow error that includes the
 /// HTTP response body. Without this, errors render as
@@ -21,18 +21,16 @@ where
     match err {
         ClientError::ErrorResponse(rv) => {
             let status = rv.status();
+            let server_request_id = hindsight_client::server_request_id(rv.headers());
             let body = rv.into_inner();
             let body_str = serde_json::to_string(&body).unwrap_or_else(|_| format!("{:?}", body));
-            anyhow::anyhow!("API request failed ({}): {}", status, body_str)
+            ApiError::new(status, body_str, server_request_id).into()
         }
         ClientError::UnexpectedResponse(response) => {
             let status = response.status();
+            let server_request_id = hindsight_client::server_request_id(response.headers());
             let body = response.text().await.unwrap_or_default();
-            if body.is_empty() {
-                anyhow::anyhow!("API request failed ({})", status)
-            } else {
-                anyhow::anyhow!("API request failed ({}): {}", status, body)
-            }
+            ApiError::new(status, body, server_request_id).into()
         }
         ClientError::InvalidResponsePayload(bytes, src) => {
             let body = String::from_utf8_lossy(&bytes);
@@ -42,6 +40,48 @@ where
     }
 }
 
+/// A non-success HTTP response, kept structured so the error handler can report
+/// the status and request IDs without parsing the message
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: reqwest::StatusCode,
+    pub body: String,
+    /// The `X-Request-Id` the request was sent with
+    pub request_id: Option<String>,
+    /// The request or trace ID in the response headers
+    pub server_request_id: Option<String>,
+}
+
+impl ApiError {
+    /// An error for the current request, taking its ID from the enclosing scope
+    fn new(status: reqwest::StatusCode, body: String, server_request_id: Option<String>) -> Self {
+        let request_id = hindsight_client::current_request_id();
+        ApiError { status, body, request_id, server_request_id }
+    }
+}
+
+/// IDs of the last request that failed, for requests whose error doesn't carry them.
+/// Cleared when a request succeeds.
+static FAILED_REQUEST: std::sync::Mutex<Option<hindsight_client::RequestIds>> =
+    std::sync::Mutex::new(None);
+
+/// The IDs of the last request if it failed
+pub fn failed_request_ids() -> Option<hindsight_client::RequestIds> {
+    FAILED_REQUEST.lock().unwrap().clone()
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.body.is_empty() {
+            write!(f, "API request failed ({})", self.status)
+        } else {
+            write!(f, "API request failed ({}): {}", self.status, self.body)
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
 // Types not defined in OpenAPI spec (TODO: add to openapi.json)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AgentStats {
@@ -57,7 +97,7 @@ pub struct AgentStats {
     pub failed_operations: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Operation {
     pub id: String,
     pub task_type: String,
@@ -66,14 +106,233 @@ pub struct Operation {
     pub created_at: String,
     pub status: String,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub retry_count: Option<i32>,
+    #[serde(default)]
+    pub next_retry_at: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OperationsResponse {
     pub bank_id: String,
+    /// Operations matching the filter, including ones beyond `limit`
+    #[serde(default)]
+    pub total: i64,
     pub operations: Vec<Operation>,
 }
 
+/// A memory in the graph, joined from the graph endpoint's nodes and table rows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub text: String,
+    pub fact_type: Option<String>,
+    pub mentioned_at: Option<String>,
+    pub occurred_start: Option<String>,
+}
+
+/// A link between two memories
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphLink {
+    pub source: String,
+    pub target: String,
+    pub link_type: String,
+    pub weight: f64,
+    pub entity_name: Option<String>,
+}
+
+/// A link from a memory to one of its neighbours, as returned by [`ApiClient::memory_links`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkItem {
+    pub link_type: String,
+    pub target_memory_id: String,
+    pub weight: f64,
+    /// Not reported by the graph endpoint yet
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphExport {
+    pub nodes: Vec<GraphNode>,
+    pub links: Vec<GraphLink>,
+    /// Memories matching the type filter, including ones beyond the node limit
+    pub total_units: i64,
+}
+
+impl GraphExport {
+    /// The endpoint returns Cytoscape-style `{"data": {...}}` elements; the fact
+    /// type and dates only appear on the table rows.
+    pub fn from_response(response: types::GraphDataResponse) -> Self {
+        let str_field = |obj: &serde_json::Map<String, serde_json::Value>, key: &str| {
+            obj.get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let data = |element: &serde_json::Map<String, serde_json::Value>| {
+            element
+                .get("data")
+                .and_then(|d| d.as_object())
+                .cloned()
+                .unwrap_or_else(|| element.clone())
+        };
+
+        let rows: HashMap<String, &serde_json::Map<String, serde_json::Value>> = response
+            .table_rows
+            .iter()
+            .filter_map(|row| str_field(row, "id").map(|id| (id, row)))
+            .collect();
+
+        let nodes = response
+            .nodes
+            .iter()
+            .filter_map(|element| {
+                let node = data(element);
+                let id = str_field(&node, "id")?;
+                let row = rows.get(&id);
+                let row_field = |key: &str| row.and_then(|r| str_field(r, key));
+                Some(GraphNode {
+                    text: str_field(&node, "text")
+                        .or_else(|| row_field("text"))
+                        .or_else(|| str_field(&node, "label"))
+                        .unwrap_or_default(),
+                    fact_type: row_field("fact_type").or_else(|| str_field(&node, "type")),
+                    mentioned_at: row_field("mentioned_at"),
+                    occurred_start: row_field("occurred_start"),
+                    id,
+                })
+            })
+            .collect();
+
+        let links = response
+            .edges
+            .iter()
+            .filter_map(|element| {
+                let edge = data(element);
+                Some(GraphLink {
+                    source: str_field(&edge, "source").or_else(|| str_field(&edge, "from"))?,
+                    target: str_field(&edge, "target").or_else(|| str_field(&edge, "to"))?,
+                    link_type: str_field(&edge, "linkType")
+                        .or_else(|| str_field(&edge, "type"))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    weight: edge.get("weight").and_then(|v| v.as_f64()).unwrap_or(1.0),
+                    entity_name: str_field(&edge, "entityName"),
+                })
+            })
+            .collect();
+
+        GraphExport {
+            nodes,
+            links,
+            total_units: response.total_units,
+        }
+    }
+
+    /// Links touching `memory_id`, seen from its side and strongest first. Links are
+    /// stored one way but are symmetric for browsing, so both directions count.
+    pub fn links_of(&self, memory_id: &str) -> Vec<LinkItem> {
+        let mut links: Vec<LinkItem> = self
+            .links
+            .iter()
+            .filter_map(|link| {
+                let target = if link.source == memory_id {
+                    &link.target
+                } else if link.target == memory_id {
+                    &link.source
+                } else {
+                    return None;
+                };
+                Some(LinkItem {
+                    link_type: link.link_type.clone(),
+                    target_memory_id: target.clone(),
+                    weight: link.weight,
+                    created_at: None,
+                })
+            })
+            .collect();
+        links.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        links
+    }
+}
+
+/// An entity of the co-occurrence graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityGraphNode {
+    pub id: String,
+    pub name: String,
+    pub mention_count: i64,
+}
+
+/// Two entities mentioned together, `weight` being the number of memories they share
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityGraphEdge {
+    pub source: String,
+    pub target: String,
+    pub weight: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntityGraph {
+    pub nodes: Vec<EntityGraphNode>,
+    pub edges: Vec<EntityGraphEdge>,
+    /// Entities of the bank, including ones beyond the node limit
+    pub total_entities: i64,
+}
+
+impl EntityGraph {
+    /// Like the memory graph, the endpoint returns Cytoscape-style `{"data": {...}}` elements
+    pub fn from_response(response: types::EntityGraphResponse) -> Self {
+        let data = |element: &serde_json::Map<String, serde_json::Value>| {
+            element
+                .get("data")
+                .and_then(|d| d.as_object())
+                .cloned()
+                .unwrap_or_else(|| element.clone())
+        };
+        let str_field = |obj: &serde_json::Map<String, serde_json::Value>, key: &str| {
+            obj.get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+
+        let nodes = response
+            .nodes
+            .iter()
+            .filter_map(|element| {
+                let node = data(element);
+                let id = str_field(&node, "id")?;
+                Some(EntityGraphNode {
+                    name: str_field(&node, "label").unwrap_or_else(|| id.clone()),
+                    mention_count: node
+                        .get("mentionCount")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(0),
+                    id,
+                })
+            })
+            .collect();
+        let edges = response
+            .edges
+            .iter()
+            .filter_map(|element| {
+                let edge = data(element);
+                Some(EntityGraphEdge {
+                    source: str_field(&edge, "source")?,
+                    target: str_field(&edge, "target")?,
+                    weight: edge.get("weight").and_then(|v| v.as_i64()).unwrap_or(1),
+                })
+            })
+            .collect();
+
+        EntityGraph {
+            nodes,
+            edges,
+            total_entities: response.total_entities,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TraceInfo {
     pub total_time: Option<f64>,
@@ -95,21 +354,438 @@ pub struct FileRetainResult {
     pub operation_ids: Vec<String>,
 }
 
+/// Result of [`ApiClient::entity_merge`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityMergeResult {
+    /// The entity the others were merged into
+    pub entity_id: String,
+    /// The entities that were merged away
+    pub merged_ids: Vec<String>,
+    /// Memory mentions moved onto `entity_id`
+    pub mentions_relinked: i64,
+}
+
+/// Result of [`ApiClient::entity_rename`] and [`ApiClient::entity_add_alias`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityNameResult {
+    pub entity_id: String,
+    pub canonical_name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Mentions of other entities with the new name that were linked to this one
+    #[serde(default)]
+    pub mentions_relinked: i64,
+}
+
+/// Options for [`ApiClient::document_reprocess`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReprocessOptions {
+    /// Replace the memories already extracted from the document. The server always does
+    /// this, so `false` is refused rather than silently ignored.
+    pub delete_existing_memories: bool,
+}
+
+impl Default for ReprocessOptions {
+    fn default() -> Self {
+        Self {
+            delete_existing_memories: true,
+        }
+    }
+}
+
+/// Prefix of the document IDs `--skip-duplicates` imports records under
+pub const CONTENT_DOCUMENT_PREFIX: &str = "content-";
+
+/// Document ID for a record imported with `--skip-duplicates`, named by its text hash
+pub fn content_document_id(hash: &str) -> String {
+    format!("{}{}", CONTENT_DOCUMENT_PREFIX, hash)
+}
+
+/// Memories fetched from the graph endpoint when looking up a memory's links
+pub const LINK_GRAPH_LIMIT: i64 = 5000;
+
+/// Request timeout when none is configured; reflect and retain can be slow
+pub const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Retry behaviour for transient failures (`--retries`, `--retry-delay`, `--retry-writes`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub delay: std::time::Duration,
+    /// Also retry ingest requests (retain, file retain), which are not idempotent
+    pub retry_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            delay: std::time::Duration::from_millis(500),
+            retry_writes: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based), capped at 64x the base delay
+    fn backoff(&self, retry: u32) -> std::time::Duration {
+        self.delay.saturating_mul(1 << retry.saturating_sub(1).min(6))
+    }
+}
+
+/// Bytes read from disk per chunk of a streamed upload
+const UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+/// How often a streamed upload reports its progress
+const UPLOAD_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Slowest upload rate allowed for before the request times out, in bytes per second
+const UPLOAD_MIN_RATE: u64 = 256 * 1024;
+
+/// The request timeout covers sending the body too, so an upload gets the configured
+/// timeout plus time to send its bytes at `UPLOAD_MIN_RATE`
+fn upload_timeout(timeout: std::time::Duration, size: u64) -> std::time::Duration {
+    timeout + std::time::Duration::from_secs(size / UPLOAD_MIN_RATE)
+}
+
+/// `file` as a stream of chunks for a request body, adding each chunk's length to `sent`
+fn counted_chunks(
+    file: tokio::fs::File,
+    sent: std::sync::Arc<std::sync::atomic::AtomicU64>,
+) -> impl futures_util::Stream<Item = std::io::Result<Vec<u8>>> + Send + 'static {
+    futures_util::stream::try_unfold(file, move |mut file| {
+        let sent = sent.clone();
+        async move {
+            let mut chunk = vec![0u8; UPLOAD_CHUNK_SIZE];
+            let read = tokio::io::AsyncReadExt::read(&mut file, &mut chunk).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+            chunk.truncate(read);
+            sent.fetch_add(read as u64, std::sync::atomic::Ordering::Relaxed);
+            std::io::Result::Ok(Some((chunk, file)))
+        }
+    })
+}
+
+/// The `request` field of a file retain upload: per-file metadata, with each file's
+/// name stem as its document ID
+fn file_retain_request<'a>(
+    names: impl Iterator<Item = &'a str>,
+    context: Option<&str>,
+    strategy: Option<&str>,
+) -> serde_json::Value {
+    let files_metadata: Vec<serde_json::Value> = names
+        .map(|name| {
+            let mut meta = serde_json::json!({});
+            if let Some(ctx) = context {
+                meta["context"] = serde_json::Value::String(ctx.to_string());
+            }
+            if let Some(strat) = strategy {
+                meta["strategy"] = serde_json::Value::String(strat.to_string());
+            }
+            // Use filename stem as document_id for deduplication
+            if let Some(stem) = std::path::Path::new(name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+            {
+                meta["document_id"] = serde_json::Value::String(stem.to_string());
+            }
+            meta
+        })
+        .collect();
+    serde_json::json!({
+        "files_metadata": files_metadata,
+    })
+}
+
+/// Whether a failed request hit a route the server doesn't have: 405, or 404 with
+/// FastAPI's bare "Not Found" rather than a handler's "Entity ... not found"
+fn is_missing_route(status: reqwest::StatusCode, body: &str) -> bool {
+    status == reqwest::StatusCode::METHOD_NOT_ALLOWED
+        || (status == reqwest::StatusCode::NOT_FOUND
+            && serde_json::from_str::<serde_json::Value>(body)
+                .is_ok_and(|v| v["detail"] == "Not Found"))
+}
+
+/// Error for a rejected file upload. Uploads over the server's size limit come back as
+/// 413 from a proxy, or as 400 "exceeds maximum" from the API itself.
+fn file_retain_error(status: reqwest::StatusCode, body: &str, size: u64) -> anyhow::Error {
+    if status == reqwest::StatusCode::PAYLOAD_TOO_LARGE || body.contains("exceeds maximum") {
+        return anyhow::anyhow!(
+            "File retain failed ({}): the upload ({}) is larger than the server accepts. \
+             Split the file, or raise HINDSIGHT_API_FILE_CONVERSION_MAX_BATCH_SIZE_MB on the \
+             server (and any proxy body size limit in front of it). Server said: {}",
+            status,
+            crate::commands::document::format_size(size as i64),
+            body
+        );
+    }
+    anyhow::anyhow!("File retain failed ({}): {}", status, body)
+}
+
+/// How [`ApiClient::recall_multi`] orders the results of several banks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Each bank's next result in turn, in the order the banks were given
+    #[default]
+    Interleave,
+    /// By position relative to the length of each bank's list. The API returns no
+    /// relevance scores, so this is the closest stand-in: the top third of every bank
+    /// comes before the middle third of any.
+    Rank,
+}
+
+/// A recall result and the bank it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct BankRecallResult {
+    pub bank_id: String,
+    #[serde(flatten)]
+    pub result: types::RecallResult,
+}
+
+/// A bank whose part of a multi-bank recall failed
+#[derive(Debug, Clone, Serialize)]
+pub struct BankRecallError {
+    pub bank_id: String,
+    pub error: String,
+}
+
+/// Merged results of one recall sent to several banks
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiRecallResponse {
+    pub results: Vec<BankRecallResult>,
+    /// Banks that failed; the results of the others are still returned
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<BankRecallError>,
+}
+
+/// Merge per-bank result lists (each in rank order) and keep what fits in `max_tokens`.
+/// Every bank already filled its own budget, so together they would overshoot it.
+fn merge_recall_results(
+    ranked: Vec<Vec<BankRecallResult>>,
+    strategy: MergeStrategy,
+    max_tokens: i64,
+) -> Vec<BankRecallResult> {
+    let merged: Vec<BankRecallResult> = match strategy {
+        MergeStrategy::Interleave => {
+            let mut lists: Vec<_> = ranked.into_iter().map(Vec::into_iter).collect();
+            let mut merged = Vec::new();
+            loop {
+                let before = merged.len();
+                for list in lists.iter_mut() {
+                    merged.extend(list.next());
+                }
+                if merged.len() == before {
+                    break merged;
+                }
+            }
+        }
+        MergeStrategy::Rank => {
+            let mut keyed: Vec<(f64, usize, BankRecallResult)> = Vec::new();
+            for (bank, results) in ranked.into_iter().enumerate() {
+                let count = results.len() as f64;
+                for (rank, result) in results.into_iter().enumerate() {
+                    keyed.push(((rank + 1) as f64 / count, bank, result));
+                }
+            }
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            keyed.into_iter().map(|(_, _, result)| result).collect()
+        }
+    };
+
+    let budget = max_tokens.max(0) as usize;
+    let mut used = 0;
+    merged
+        .into_iter()
+        .take_while(|merged| {
+            let tokens = crate::utils::estimate_tokens(&merged.result.text);
+            // The best result is kept even if it alone is over budget
+            let fits = used == 0 || used + tokens <= budget;
+            used += tokens;
+            fits
+        })
+        .collect()
+}
+
+/// Hedged reads, turned on with [`ApiClient::with_hedging`]: when a read hasn't answered
+/// after `delay`, a duplicate is sent and whichever answers first is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HedgeConfig {
+    pub delay: std::time::Duration,
+    /// Duplicates sent at most per request, one per `delay` waited
+    pub max_hedges: u32,
+}
+
+/// How hedged reads went, for reports
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct HedgeStats {
+    /// Duplicate requests sent
+    pub issued: u64,
+    /// Requests answered by a duplicate rather than the original
+    pub won: u64,
+}
+
+/// Response cache for list requests, turned on with [`ApiClient::with_cache`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheConfig {
+    /// Responses kept at most; the oldest is dropped to make room for a new one
+    pub max_entries: usize,
+    /// How long a response is reused without asking the server again
+    pub ttl: std::time::Duration,
+}
+
+/// How cached requests were answered, for status displays
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CacheStats {
+    /// Served from the cache without a request
+    pub hits: u64,
+    /// Sent with If-None-Match and answered 304 Not Modified
+    pub revalidated: u64,
+    /// Fetched in full
+    pub misses: u64,
+}
+
+struct CachedResponse {
+    body: Vec<u8>,
+    etag: Option<String>,
+    stored: std::time::Instant,
+}
+
+/// GET response bodies keyed by URL, shared by all clones of an `ApiClient`
+struct ResponseCache {
+    config: CacheConfig,
+    entries: std::sync::Mutex<HashMap<String, CachedResponse>>,
+    stats: std::sync::Mutex<CacheStats>,
+}
+
+impl ResponseCache {
+    fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            entries: std::sync::Mutex::new(HashMap::new()),
+            stats: std::sync::Mutex::new(CacheStats::default()),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The cached body for `url` if it is younger than the TTL
+    fn fresh(&self, url: &str) -> Option<Vec<u8>> {
+        self.entries()
+            .get(url)
+            .filter(|entry| entry.stored.elapsed() < self.config.ttl)
+            .map(|entry| entry.body.clone())
+    }
+
+    fn etag(&self, url: &str) -> Option<String> {
+        self.entries().get(url).and_then(|entry| entry.etag.clone())
+    }
+
+    /// Mark the entry for `url` as confirmed by the server and return its body
+    fn renew(&self, url: &str) -> Option<Vec<u8>> {
+        let mut entries = self.entries();
+        let entry = entries.get_mut(url)?;
+        entry.stored = std::time::Instant::now();
+        Some(entry.body.clone())
+    }
+
+    fn store(&self, url: String, body: Vec<u8>, etag: Option<String>) {
+        if self.config.max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries();
+        if !entries.contains_key(&url) && entries.len() >= self.config.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        let stored = std::time::Instant::now();
+        entries.insert(url, CachedResponse { body, etag, stored });
+    }
+
+    fn clear(&self) {
+        self.entries().clear();
+    }
+
+    fn count(&self, update: impl FnOnce(&mut CacheStats)) {
+        update(&mut self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+    }
+
+    fn stats(&self) -> CacheStats {
+        *self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn is_transient_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+/// HTTP status from an error message: ours read "... failed (503 ...)", the
+/// generated client's "status: 503; ..."
+pub(crate) fn status_in_message(message: &str) -> Option<u16> {
+    ["failed (", "status: "].iter().find_map(|marker| {
+        let start = message.find(marker)? + marker.len();
+        message.get(start..start + 3)?.parse().ok()
+    })
+}
+
+/// Whether a failed request may succeed if sent again: connection failures,
+/// timeouts, 429 and 5xx responses. Anything else would fail the same way.
+fn is_transient(err: &anyhow::Error) -> bool {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() || e.is_connect() {
+                return true;
+            }
+            if let Some(status) = e.status() {
+                return is_transient_status(status.as_u16());
+            }
+        }
+    }
+    status_in_message(&err.to_string()).is_some_and(is_transient_status)
+}
+
 #[derive(Clone)]
 pub struct ApiClient {
     client: AsyncClient,
     http_client: reqwest::Client,
     base_url: String,
     runtime: std::sync::Arc<tokio::runtime::Runtime>,
+    timeout: std::time::Duration,
+    retry: RetryPolicy,
+    cache: Option<std::sync::Arc<ResponseCache>>,
+    /// Skip reusing cached responses, though they are still revalidated and updated
+    bypass_cache: bool,
+    hedge: Option<HedgeConfig>,
+    /// Shared by clones, like the cache
+    hedge_stats: std::sync::Arc<std::sync::Mutex<HedgeStats>>,
+    /// Print each request's IDs to stderr
+    verbose: bool,
 }
 
 impl ApiClient {
     pub fn new(base_url: String, api_key: Option<String>) -> Result<Self> {
+        Self::with_timeout(base_url, api_key, DEFAULT_TIMEOUT)
+    }
+
+    pub fn with_timeout(
+        base_url: String,
+        api_key: Option<String>,
+        timeout: std::time::Duration,
+    ) -> Result<Self> {
         let runtime = std::sync::Arc::new(tokio::runtime::Runtime::new()?);
 
-        // Create HTTP client with 2-minute timeout and optional auth header
-        let mut client_builder =
-            reqwest::Client::builder().timeout(std::time::Duration::from_secs(120));
+        // Create HTTP client with the request timeout and optional auth header
+        let mut client_builder = reqwest::Client::builder().timeout(timeout);
 
         if let Some(key) = api_key {
             let mut headers = reqwest::header::HeaderMap::new();
@@ -129,11 +805,237 @@ impl ApiClient {
             http_client,
             base_url,
             runtime,
+            timeout,
+            retry: RetryPolicy::default(),
+            cache: None,
+            bypass_cache: false,
+            hedge: None,
+            hedge_stats: Default::default(),
+            verbose: false,
         })
     }
 
-    pub fn list_agents(&self, _verbose: bool) -> Result<Vec<types::BankListItem>> {
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Print the request ID of every request to stderr (`--verbose`)
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Hedge reads (never writes) that are slow to answer
+    pub fn with_hedging(mut self, config: HedgeConfig) -> Self {
+        self.hedge = Some(config);
+        self
+    }
+
+    pub fn hedge_stats(&self) -> Option<HedgeStats> {
+        self.hedge.map(|_| *self.hedge_stats.lock().unwrap())
+    }
+
+    /// Cache list responses; clones of the returned client share the cache
+    pub fn with_cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(std::sync::Arc::new(ResponseCache::new(config)));
+        self
+    }
+
+    /// A client for explicit refreshes: cached responses are never reused without asking
+    /// the server, but a 304 still saves downloading the body again
+    pub fn bypassing_cache(&self) -> Self {
+        Self {
+            bypass_cache: true,
+            ..self.clone()
+        }
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    /// Refuse writes under `--dry-run`, and forget cached lists the write may change
+    fn allow_write(&self, action: &str) -> Result<()> {
+        crate::executor::ensure_writes_allowed(action)?;
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    /// GET `path` through the response cache, revalidating with If-None-Match when the
+    /// server sent an ETag
+    fn cached_get<T: serde::de::DeserializeOwned>(
+        &self,
+        cache: &ResponseCache,
+        path: &str,
+        query: &[(&str, Option<String>)],
+    ) -> Result<T> {
+        let request = self
+            .http_client
+            .get(format!("{}{}", self.base_url, path))
+            .query(&query.iter().filter(|(_, v)| v.is_some()).collect::<Vec<_>>())
+            .build()?;
+        let url = request.url().to_string();
+        if !self.bypass_cache {
+            if let Some(body) = cache.fresh(&url) {
+                cache.count(|stats| stats.hits += 1);
+                return Ok(serde_json::from_slice(&body)?);
+            }
+        }
+
+        let (request, url) = (&request, &url);
+        let body = self.read(move || async move {
+            let mut request = request.try_clone().expect("GET requests have no streaming body");
+            if let Some(etag) = cache.etag(url) {
+                request.headers_mut().insert(
+                    reqwest::header::IF_NONE_MATCH,
+                    reqwest::header::HeaderValue::from_str(&etag)?,
+                );
+            }
+            if let Some(id) = hindsight_client::current_request_id() {
+                request.headers_mut().insert(
+                    hindsight_client::REQUEST_ID_HEADER,
+                    reqwest::header::HeaderValue::from_str(&id)?,
+                );
+            }
+            let response = self.http_client.execute(request).await?;
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_MODIFIED {
+                if let Some(body) = cache.renew(url) {
+                    cache.count(|stats| stats.revalidated += 1);
+                    return Ok(body);
+                }
+                anyhow::bail!("API request failed ({}): no cached response for {}", status, url);
+            }
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let server_request_id = hindsight_client::server_request_id(response.headers());
+            let body = response.bytes().await?.to_vec();
+            if !status.is_success() {
+                let body = String::from_utf8_lossy(&body).into_owned();
+                return Err(ApiError::new(status, body, server_request_id).into());
+            }
+            cache.count(|stats| stats.misses += 1);
+            cache.store(url.clone(), body.clone(), etag);
+            Ok(body)
+        })?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Run an idempotent request, hedged if enabled, retrying transient failures per the
+    /// retry policy. Each retry is hedged again.
+    fn read<T, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let request = &request;
+        self.send(self.retry.retries, move || self.hedged(request))
+    }
+
+    /// Send `request`, and a duplicate each time `delay` passes without an answer, up to
+    /// `max_hedges`. The first success is returned and the requests still in flight are
+    /// dropped, which cancels them. An error only counts once nothing else is in flight.
+    async fn hedged<T, F, Fut>(&self, request: &F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let Some(hedge) = self.hedge else {
+            return request().await;
+        };
+        let start = |attempt: u32| {
+            let response = request();
+            async move { (attempt, response.await) }
+        };
+        let mut in_flight = futures_util::stream::FuturesUnordered::new();
+        in_flight.push(start(0));
+        let mut hedges = 0;
+        loop {
+            tokio::select! {
+                Some((attempt, result)) = futures_util::StreamExt::next(&mut in_flight) => {
+                    match result {
+                        Ok(value) => {
+                            if attempt > 0 {
+                                self.hedge_stats.lock().unwrap().won += 1;
+                            }
+                            return Ok(value);
+                        }
+                        Err(e) if in_flight.is_empty() => return Err(e),
+                        Err(_) => {}
+                    }
+                }
+                _ = tokio::time::sleep(hedge.delay), if hedges < hedge.max_hedges => {
+                    hedges += 1;
+                    self.hedge_stats.lock().unwrap().issued += 1;
+                    in_flight.push(start(hedges));
+                }
+            }
+        }
+    }
+
+    /// Run an ingest request; it is only retried with `--retry-writes`, since a
+    /// request that timed out may still have been applied
+    fn ingest<T, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let retries = if self.retry.retry_writes { self.retry.retries } else { 0 };
+        self.send(retries, request)
+    }
+
+    fn send<T, F, Fut>(&self, retries: u32, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
         self.runtime.block_on(async {
+            let mut retry = 0;
+            loop {
+                let (result, ids) = hindsight_client::with_request_id(None, request()).await;
+                if self.verbose {
+                    match &ids.server {
+                        Some(server) if *server != ids.sent => {
+                            eprintln!("Request ID: {} (server: {})", ids.sent, server)
+                        }
+                        _ => eprintln!("Request ID: {}", ids.sent),
+                    }
+                }
+                *FAILED_REQUEST.lock().unwrap() = result.is_err().then_some(ids);
+                match result {
+                    Ok(value) => return Ok(value),
+                    Err(e) if retry < retries && is_transient(&e) => {
+                        retry += 1;
+                        crate::ui::report_retry(&format!("Retrying ({}/{})...", retry, retries));
+                        tokio::time::sleep(self.retry.backoff(retry)).await;
+                    }
+                    Err(e) if retry > 0 => {
+                        let attempts = retry + 1;
+                        return Err(e.context(format!("Request failed after {} attempts", attempts)));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+    }
+
+    pub fn list_agents(&self, _verbose: bool) -> Result<Vec<types::BankListItem>> {
+        if let Some(cache) = &self.cache {
+            let response: types::BankListResponse =
+                self.cached_get(cache, "/v1/default/banks", &[])?;
+            return Ok(response.banks);
+        }
+        self.read(move || async move {
             let response = self.client.list_banks(None).await?;
             Ok(response.into_inner().banks)
         })
@@ -144,14 +1046,14 @@ impl ApiClient {
         agent_id: &str,
         _verbose: bool,
     ) -> Result<types::BankProfileResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_bank_profile(agent_id, None).await?;
             Ok(response.into_inner())
         })
     }
 
     pub fn get_stats(&self, agent_id: &str, _verbose: bool) -> Result<AgentStats> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_agent_stats(agent_id, None).await?;
             let value = response.into_inner();
             // Convert to JSON Value first, then parse into our type
@@ -167,6 +1069,7 @@ impl ApiClient {
         name: &str,
         _verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.allow_write("update_agent_name")?;
         self.runtime.block_on(async {
             let request = types::CreateBankRequest {
                 name: Some(name.to_string()),
@@ -190,6 +1093,7 @@ impl ApiClient {
         update_disposition: bool,
         _verbose: bool,
     ) -> Result<types::BackgroundResponse> {
+        self.allow_write("add_background")?;
         self.runtime.block_on(async {
             let request = types::AddBackgroundRequest {
                 content: content.to_string(),
@@ -215,7 +1119,7 @@ impl ApiClient {
                 serde_json::to_string_pretty(request).unwrap_or_default()
             );
         }
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = match self.client.recall_memories(agent_id, None, request).await {
                 Ok(r) => r,
                 Err(e) => return Err(humanize_client_error(e).await),
@@ -224,13 +1128,74 @@ impl ApiClient {
         })
     }
 
+    /// Send the same recall to several banks at once and merge the results.
+    ///
+    /// A bank whose recall fails is reported in `errors` while the other banks' results
+    /// are still returned; only when every bank fails is the first failure returned.
+    pub fn recall_multi(
+        &self,
+        bank_ids: &[String],
+        request: &types::RecallRequest,
+        strategy: MergeStrategy,
+        verbose: bool,
+    ) -> Result<MultiRecallResponse> {
+        let responses: Vec<Result<types::RecallResponse>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = bank_ids
+                .iter()
+                .map(|bank_id| scope.spawn(move || self.recall(bank_id, request, verbose)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| {
+                    h.join()
+                        .unwrap_or_else(|_| Err(anyhow::anyhow!("Recall thread panicked")))
+                })
+                .collect()
+        });
+
+        let mut ranked = Vec::new();
+        let mut errors = Vec::new();
+        let mut first_error = None;
+        for (bank_id, response) in bank_ids.iter().zip(responses) {
+            match response {
+                Ok(response) => ranked.push(
+                    response
+                        .results
+                        .into_iter()
+                        .map(|result| BankRecallResult {
+                            bank_id: bank_id.clone(),
+                            result,
+                        })
+                        .collect(),
+                ),
+                Err(e) => {
+                    errors.push(BankRecallError {
+                        bank_id: bank_id.clone(),
+                        error: format!("{:#}", e),
+                    });
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if ranked.is_empty() {
+            if let Some(e) = first_error {
+                return Err(e.context(format!("Recall failed in all {} banks", bank_ids.len())));
+            }
+        }
+
+        Ok(MultiRecallResponse {
+            results: merge_recall_results(ranked, strategy, request.max_tokens),
+            errors,
+        })
+    }
+
     pub fn reflect(
         &self,
         agent_id: &str,
         request: &types::ReflectRequest,
         _verbose: bool,
     ) -> Result<types::ReflectResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = match self.client.reflect(agent_id, None, request).await {
                 Ok(r) => r,
                 Err(e) => return Err(humanize_client_error(e).await),
@@ -246,7 +1211,8 @@ impl ApiClient {
         _async_mode: bool,
         _verbose: bool,
     ) -> Result<MemoryPutResult> {
-        self.runtime.block_on(async {
+        self.allow_write("retain")?;
+        self.ingest(move || async move {
             let response = match self.client.retain_memories(agent_id, None, request).await {
                 Ok(r) => r,
                 Err(e) => return Err(humanize_client_error(e).await),
@@ -263,53 +1229,35 @@ impl ApiClient {
     }
 
     /// Upload files to the file retain endpoint (multipart/form-data).
+    /// Each file is `(filename, bytes, content type)`.
     /// Returns a list of operation IDs for tracking. Always async server-side.
     pub fn file_retain(
         &self,
         bank_id: &str,
-        files: Vec<(String, Vec<u8>)>,
+        files: Vec<(String, Vec<u8>, String)>,
         context: Option<String>,
         strategy: Option<String>,
         verbose: bool,
     ) -> Result<FileRetainResult> {
-        self.runtime.block_on(async {
+        self.allow_write("file_retain")?;
+        // Borrowed so each retry can rebuild the multipart form
+        let (files, context, strategy) = (&files, &context, &strategy);
+        self.ingest(move || async move {
             let url = format!(
                 "{}/v1/default/banks/{}/files/retain",
                 self.base_url, bank_id
             );
 
-            let files_metadata: Vec<serde_json::Value> = files
-                .iter()
-                .map(|(name, _)| {
-                    let mut meta = serde_json::json!({});
-                    if let Some(ctx) = &context {
-                        meta["context"] = serde_json::Value::String(ctx.clone());
-                    }
-                    if let Some(strat) = &strategy {
-                        meta["strategy"] = serde_json::Value::String(strat.clone());
-                    }
-                    // Use filename stem as document_id for deduplication
-                    if let Some(stem) = std::path::Path::new(name)
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                    {
-                        meta["document_id"] = serde_json::Value::String(stem.to_string());
-                    }
-                    meta
-                })
-                .collect();
-
-            let request_json = serde_json::json!({
-                "files_metadata": files_metadata,
-            });
+            let names = files.iter().map(|(name, _, _)| name.as_str());
+            let request_json = file_retain_request(names, context.as_deref(), strategy.as_deref());
 
             let mut form =
                 reqwest::multipart::Form::new().text("request", request_json.to_string());
 
-            for (filename, content) in files {
-                let part = reqwest::multipart::Part::bytes(content)
-                    .file_name(filename)
-                    .mime_str("application/octet-stream")?;
+            for (filename, content, content_type) in files {
+                let part = reqwest::multipart::Part::bytes(content.clone())
+                    .file_name(filename.clone())
+                    .mime_str(content_type)?;
                 form = form.part("files", part);
             }
 
@@ -322,7 +1270,85 @@ impl ApiClient {
             if !response.status().is_success() {
                 let status = response.status();
                 let text = response.text().await.unwrap_or_default();
-                anyhow::bail!("File retain failed ({}): {}", status, text);
+                let size = files.iter().map(|(_, content, _)| content.len() as u64).sum();
+                return Err(file_retain_error(status, &text, size));
+            }
+
+            let result: FileRetainResult = response.json().await?;
+            Ok(result)
+        })
+    }
+
+    /// Upload one file to the file retain endpoint, streaming it from disk instead of
+    /// reading it into memory first. `progress` is called with (bytes sent, total bytes)
+    /// as the upload goes on; a retry starts it over from zero.
+    #[allow(clippy::too_many_arguments)]
+    pub fn file_retain_streaming(
+        &self,
+        bank_id: &str,
+        path: &std::path::Path,
+        content_type: &str,
+        context: Option<String>,
+        strategy: Option<String>,
+        progress: impl FnMut(u64, u64),
+        verbose: bool,
+    ) -> Result<FileRetainResult> {
+        self.allow_write("file_retain")?;
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string();
+        let total = std::fs::metadata(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read file {}: {}", path.display(), e))?
+            .len();
+        let request_json = file_retain_request(
+            std::iter::once(filename.as_str()),
+            context.as_deref(),
+            strategy.as_deref(),
+        );
+        let url = format!("{}/v1/default/banks/{}/files/retain", self.base_url, bank_id);
+        let timeout = upload_timeout(self.timeout, total);
+
+        // Called from this thread only, while the upload runs on the runtime
+        let progress = std::cell::RefCell::new(progress);
+        let (progress, request_json, filename, url) = (&progress, &request_json, &filename, &url);
+        self.ingest(move || async move {
+            (*progress.borrow_mut())(0, total);
+            let file = tokio::fs::File::open(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read file {}: {}", path.display(), e))?;
+            let sent = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+            let body = reqwest::Body::wrap_stream(counted_chunks(file, sent.clone()));
+            let part = reqwest::multipart::Part::stream_with_length(body, total)
+                .file_name(filename.clone())
+                .mime_str(content_type)?;
+            let form = reqwest::multipart::Form::new()
+                .text("request", request_json.to_string())
+                .part("files", part);
+
+            if verbose {
+                eprintln!("POST {} ({} bytes, streamed)", url, total);
+            }
+
+            let send = self.http_client.post(url).timeout(timeout).multipart(form).send();
+            tokio::pin!(send);
+            let mut tick = tokio::time::interval(UPLOAD_PROGRESS_INTERVAL);
+            let response = loop {
+                tokio::select! {
+                    response = &mut send => break response?,
+                    _ = tick.tick() => {
+                        let sent = sent.load(std::sync::atomic::Ordering::Relaxed);
+                        (*progress.borrow_mut())(sent, total);
+                    }
+                }
+            };
+            (*progress.borrow_mut())(sent.load(std::sync::atomic::Ordering::Relaxed), total);
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(file_retain_error(status, &text, total));
             }
 
             let result: FileRetainResult = response.json().await?;
@@ -338,7 +1364,7 @@ impl ApiClient {
         operation_id: &str,
         verbose: bool,
     ) -> Result<(bool, Option<String>)> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             loop {
                 let response = self
                     .client
@@ -393,6 +1419,7 @@ impl ApiClient {
         _unit_id: &str,
         _verbose: bool,
     ) -> Result<types::DeleteResponse> {
+        self.allow_write("delete_memory")?;
         // Note: Individual memory deletion is no longer supported in the API
         anyhow::bail!("Individual memory deletion is no longer supported. Use 'memory clear' to clear all memories.")
     }
@@ -403,6 +1430,7 @@ impl ApiClient {
         fact_type: Option<&str>,
         _verbose: bool,
     ) -> Result<types::DeleteResponse> {
+        self.allow_write("clear_memories")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -420,7 +1448,16 @@ impl ApiClient {
         offset: Option<i32>,
         _verbose: bool,
     ) -> Result<types::ListDocumentsResponse> {
-        self.runtime.block_on(async {
+        if let Some(cache) = &self.cache {
+            let path = format!("/v1/default/banks/{}/documents", agent_id);
+            let query = [
+                ("q", q.map(str::to_string)),
+                ("limit", limit.map(|l| l.to_string())),
+                ("offset", offset.map(|o| o.to_string())),
+            ];
+            return self.cached_get(cache, &path, &query);
+        }
+        self.read(move || async move {
             let response = self
                 .client
                 .list_documents(
@@ -443,7 +1480,7 @@ impl ApiClient {
         document_id: &str,
         _verbose: bool,
     ) -> Result<types::DocumentResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_document(agent_id, document_id, None)
@@ -452,12 +1489,83 @@ impl ApiClient {
         })
     }
 
+    /// Text hashes of records already imported with `--skip-duplicates`, read back from
+    /// their `content-<hash>` document IDs. The server has no hash lookup, so this pages
+    /// through those documents, one request per 500.
+    pub fn ingested_content_hashes(&self, bank_id: &str, verbose: bool) -> Result<HashSet<String>> {
+        const PAGE_SIZE: i32 = 500;
+        let mut hashes = HashSet::new();
+        let mut offset = 0;
+        loop {
+            let page = self.list_documents(
+                bank_id,
+                Some(CONTENT_DOCUMENT_PREFIX),
+                Some(PAGE_SIZE),
+                Some(offset),
+                verbose,
+            )?;
+            let fetched = page.items.len() as i32;
+            hashes.extend(page.items.into_iter().filter_map(|doc| {
+                doc.id.strip_prefix(CONTENT_DOCUMENT_PREFIX).map(str::to_string)
+            }));
+            offset += fetched;
+            if fetched < PAGE_SIZE || offset as i64 >= page.total {
+                break;
+            }
+        }
+        Ok(hashes)
+    }
+
+    pub fn list_document_chunks(
+        &self,
+        bank_id: &str,
+        document_id: &str,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        _verbose: bool,
+    ) -> Result<types::ListChunksResponse> {
+        self.read(move || async move {
+            let limit_nz = limit.and_then(std::num::NonZeroU64::new);
+            let response = self
+                .client
+                .list_document_chunks(bank_id, document_id, limit_nz, offset, None)
+                .await?;
+            Ok(response.into_inner())
+        })
+    }
+
+    /// Re-run extraction on a stored document without uploading it again. Returns the
+    /// queued operation; its memories are replaced once that operation completes.
+    pub fn document_reprocess(
+        &self,
+        bank_id: &str,
+        document_id: &str,
+        options: ReprocessOptions,
+        _verbose: bool,
+    ) -> Result<types::ReprocessDocumentResponse> {
+        self.allow_write("reprocess_document")?;
+        if !options.delete_existing_memories {
+            anyhow::bail!(
+                "Reprocessing always replaces a document's existing memories; keeping them \
+                 is not supported by the server"
+            );
+        }
+        self.runtime.block_on(async {
+            let response = self
+                .client
+                .reprocess_document(bank_id, document_id, None)
+                .await?;
+            Ok(response.into_inner())
+        })
+    }
+
     pub fn delete_document(
         &self,
         agent_id: &str,
         document_id: &str,
         _verbose: bool,
     ) -> Result<types::DeleteResponse> {
+        self.allow_write("delete_document")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -473,11 +1581,21 @@ impl ApiClient {
         })
     }
 
-    pub fn list_operations(&self, agent_id: &str, _verbose: bool) -> Result<OperationsResponse> {
-        self.runtime.block_on(async {
+    /// List operations, optionally filtered by status (pending, processing, completed,
+    /// failed, cancelled). The server caps `limit` at 100.
+    pub fn list_operations(
+        &self,
+        agent_id: &str,
+        status: Option<&str>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        _verbose: bool,
+    ) -> Result<OperationsResponse> {
+        self.read(move || async move {
+            let limit_nz = limit.and_then(std::num::NonZeroU64::new);
             let response = self
                 .client
-                .list_operations(agent_id, None, None, None, None, None, None)
+                .list_operations(agent_id, None, limit_nz, offset, status, None, None)
                 .await?;
             let value = response.into_inner();
             // Convert to JSON Value first, then parse into our type
@@ -493,6 +1611,7 @@ impl ApiClient {
         operation_id: &str,
         _verbose: bool,
     ) -> Result<types::DeleteResponse> {
+        self.allow_write("cancel_operation")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -517,7 +1636,17 @@ impl ApiClient {
         offset: Option<i64>,
         _verbose: bool,
     ) -> Result<types::ListMemoryUnitsResponse> {
-        self.runtime.block_on(async {
+        if let Some(cache) = &self.cache {
+            let path = format!("/v1/default/banks/{}/memories/list", bank_id);
+            let query = [
+                ("type", type_filter.map(str::to_string)),
+                ("q", q.map(str::to_string)),
+                ("limit", limit.map(|l| l.to_string())),
+                ("offset", offset.map(|o| o.to_string())),
+            ];
+            return self.cached_get(cache, &path, &query);
+        }
+        self.read(move || async move {
             let response = self
                 .client
                 .list_memories(bank_id, None, limit, offset, q, type_filter, None)
@@ -533,7 +1662,15 @@ impl ApiClient {
         offset: Option<i64>,
         _verbose: bool,
     ) -> Result<types::EntityListResponse> {
-        self.runtime.block_on(async {
+        if let Some(cache) = &self.cache {
+            let path = format!("/v1/default/banks/{}/entities", bank_id);
+            let query = [
+                ("limit", limit.map(|l| l.to_string())),
+                ("offset", offset.map(|o| o.to_string())),
+            ];
+            return self.cached_get(cache, &path, &query);
+        }
+        self.read(move || async move {
             let response = self
                 .client
                 .list_entities(bank_id, limit, offset, None)
@@ -548,7 +1685,7 @@ impl ApiClient {
         entity_id: &str,
         _verbose: bool,
     ) -> Result<types::EntityDetailResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_entity(bank_id, entity_id, None).await?;
             Ok(response.into_inner())
         })
@@ -560,6 +1697,7 @@ impl ApiClient {
         entity_id: &str,
         _verbose: bool,
     ) -> Result<types::EntityDetailResponse> {
+        self.allow_write("regenerate_entity")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -569,7 +1707,101 @@ impl ApiClient {
         })
     }
 
+    /// Merge `from_ids` into `into_id`, moving their mentions onto it
+    pub fn entity_merge(
+        &self,
+        bank_id: &str,
+        into_id: &str,
+        from_ids: &[String],
+        verbose: bool,
+    ) -> Result<EntityMergeResult> {
+        let path = format!("/v1/default/banks/{}/entities/{}/merge", bank_id, into_id);
+        let body = serde_json::json!({ "source_ids": from_ids });
+        self.entity_write("entity_merge", reqwest::Method::POST, &path, &body, verbose)
+    }
+
+    /// Record `alias` as another name for an entity
+    pub fn entity_add_alias(
+        &self,
+        bank_id: &str,
+        entity_id: &str,
+        alias: &str,
+        verbose: bool,
+    ) -> Result<EntityNameResult> {
+        let path = format!(
+            "/v1/default/banks/{}/entities/{}/aliases",
+            bank_id, entity_id
+        );
+        let body = serde_json::json!({ "alias": alias });
+        self.entity_write(
+            "entity_add_alias",
+            reqwest::Method::POST,
+            &path,
+            &body,
+            verbose,
+        )
+    }
+
+    /// Change an entity's canonical name
+    pub fn entity_rename(
+        &self,
+        bank_id: &str,
+        entity_id: &str,
+        new_canonical: &str,
+        verbose: bool,
+    ) -> Result<EntityNameResult> {
+        let path = format!("/v1/default/banks/{}/entities/{}", bank_id, entity_id);
+        let body = serde_json::json!({ "canonical_name": new_canonical });
+        self.entity_write(
+            "entity_rename",
+            reqwest::Method::PATCH,
+            &path,
+            &body,
+            verbose,
+        )
+    }
+
+    /// Send an entity write through raw HTTP: the OpenAPI spec has no merge, alias or
+    /// rename routes, so the generated client has no methods for them
+    fn entity_write<T: serde::de::DeserializeOwned>(
+        &self,
+        action: &str,
+        method: reqwest::Method,
+        path: &str,
+        body: &serde_json::Value,
+        verbose: bool,
+    ) -> Result<T> {
+        self.allow_write(action)?;
+        self.runtime.block_on(async {
+            let url = format!("{}{}", self.base_url, path);
+            if verbose {
+                eprintln!("{} {}", method, url);
+            }
+            let mut request = self.http_client.request(method, &url).json(body);
+            if let Some(id) = hindsight_client::current_request_id() {
+                request = request.header(hindsight_client::REQUEST_ID_HEADER, id);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response.json().await?);
+            }
+            let server_request_id = hindsight_client::server_request_id(response.headers());
+            let text = response.text().await.unwrap_or_default();
+            if is_missing_route(status, &text) {
+                anyhow::bail!(
+                    "This server does not support {} yet: {} answered {}",
+                    action.replace('_', " "),
+                    path,
+                    status
+                );
+            }
+            Err(ApiError::new(status, text, server_request_id).into())
+        })
+    }
+
     pub fn delete_bank(&self, bank_id: &str, _verbose: bool) -> Result<types::DeleteResponse> {
+        self.allow_write("delete_bank")?;
         self.runtime.block_on(async {
             let response = self.client.delete_bank(bank_id, None).await?;
             Ok(response.into_inner())
@@ -590,7 +1822,7 @@ impl ApiClient {
         memory_id: &str,
         _verbose: bool,
     ) -> Result<serde_json::Value> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_memory(bank_id, memory_id, None).await?;
             Ok(response.into_inner())
         })
@@ -604,6 +1836,7 @@ impl ApiClient {
         request: &types::CreateBankRequest,
         _verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.allow_write("create_bank")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -619,6 +1852,7 @@ impl ApiClient {
         request: &types::CreateBankRequest,
         _verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.allow_write("update_bank")?;
         self.runtime.block_on(async {
             let response = self.client.update_bank(bank_id, None, request).await?;
             Ok(response.into_inner())
@@ -631,6 +1865,7 @@ impl ApiClient {
         mission: &str,
         _verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.allow_write("set_mission")?;
         self.runtime.block_on(async {
             let request = types::CreateBankRequest {
                 name: None,
@@ -651,7 +1886,7 @@ impl ApiClient {
         limit: Option<i64>,
         _verbose: bool,
     ) -> Result<types::GraphDataResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_graph(bank_id, None, None, limit, None, None, None, type_filter, None)
@@ -660,12 +1895,56 @@ impl ApiClient {
         })
     }
 
+    /// Nodes and links for graph export. The graph endpoint has no offset, so
+    /// this is a single request returning at most `limit` memories.
+    pub fn get_graph_export(
+        &self,
+        bank_id: &str,
+        type_filter: Option<&str>,
+        limit: i64,
+        verbose: bool,
+    ) -> Result<GraphExport> {
+        let response = self.get_graph(bank_id, type_filter, Some(limit), verbose)?;
+        Ok(GraphExport::from_response(response))
+    }
+
+    /// Links from one memory to its neighbours. The graph endpoint has no per-memory
+    /// filter, so this reads links among the `LINK_GRAPH_LIMIT` most recent memories.
+    pub fn memory_links(
+        &self,
+        bank_id: &str,
+        memory_id: &str,
+        verbose: bool,
+    ) -> Result<Vec<LinkItem>> {
+        let graph = self.get_graph_export(bank_id, None, LINK_GRAPH_LIMIT, verbose)?;
+        Ok(graph.links_of(memory_id))
+    }
+
+    /// Entities and how often each pair is mentioned in the same memories: the
+    /// `limit` most-mentioned entities, and edges mentioned together at least
+    /// `min_count` times
+    pub fn entity_cooccurrence(
+        &self,
+        bank_id: &str,
+        limit: Option<i64>,
+        min_count: Option<i64>,
+        _verbose: bool,
+    ) -> Result<EntityGraph> {
+        self.read(move || async move {
+            let response = self
+                .client
+                .get_entity_graph(bank_id, limit, min_count, None)
+                .await?;
+            Ok(EntityGraph::from_response(response.into_inner()))
+        })
+    }
+
     pub fn get_bank_config(
         &self,
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::BankConfigResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_bank_config(bank_id, None).await?;
             Ok(response.into_inner())
         })
@@ -677,6 +1956,7 @@ impl ApiClient {
         updates: std::collections::HashMap<String, serde_json::Value>,
         _verbose: bool,
     ) -> Result<types::BankConfigResponse> {
+        self.allow_write("update_bank_config")?;
         self.runtime.block_on(async {
             // Convert HashMap to serde_json::Map
             let updates_map: serde_json::Map<String, serde_json::Value> =
@@ -697,6 +1977,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::BankConfigResponse> {
+        self.allow_write("reset_bank_config")?;
         self.runtime.block_on(async {
             let response = self.client.reset_bank_config(bank_id, None).await?;
             Ok(response.into_inner())
@@ -713,7 +1994,7 @@ impl ApiClient {
         offset: Option<i64>,
         _verbose: bool,
     ) -> Result<types::ListTagsResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .list_tags(bank_id, limit, offset, q, None, None)
@@ -725,7 +2006,7 @@ impl ApiClient {
     // --- Chunk Methods ---
 
     pub fn get_chunk(&self, chunk_id: &str, _verbose: bool) -> Result<types::ChunkResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_chunk(chunk_id, None).await?;
             Ok(response.into_inner())
         })
@@ -739,7 +2020,7 @@ impl ApiClient {
         operation_id: &str,
         _verbose: bool,
     ) -> Result<types::OperationStatusResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_operation_status(bank_id, operation_id, None, None)
@@ -751,14 +2032,14 @@ impl ApiClient {
     // --- Health Methods ---
 
     pub fn health(&self, _verbose: bool) -> Result<serde_json::Value> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.health_endpoint_health_get().await?;
             Ok(response.into_inner())
         })
     }
 
     pub fn metrics(&self, _verbose: bool) -> Result<serde_json::Value> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.metrics_endpoint_metrics_get().await?;
             Ok(response.into_inner())
         })
@@ -771,7 +2052,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::MentalModelListResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .list_mental_models(bank_id, None, None, None, None, None, None)
@@ -786,7 +2067,7 @@ impl ApiClient {
         mental_model_id: &str,
         _verbose: bool,
     ) -> Result<types::MentalModelResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_mental_model(bank_id, mental_model_id, None, None)
@@ -801,6 +2082,7 @@ impl ApiClient {
         request: &types::CreateMentalModelRequest,
         _verbose: bool,
     ) -> Result<types::CreateMentalModelResponse> {
+        self.allow_write("create_mental_model")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -817,6 +2099,7 @@ impl ApiClient {
         request: &types::UpdateMentalModelRequest,
         _verbose: bool,
     ) -> Result<types::MentalModelResponse> {
+        self.allow_write("update_mental_model")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -832,6 +2115,7 @@ impl ApiClient {
         mental_model_id: &str,
         _verbose: bool,
     ) -> Result<serde_json::Value> {
+        self.allow_write("delete_mental_model")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -847,6 +2131,7 @@ impl ApiClient {
         mental_model_id: &str,
         _verbose: bool,
     ) -> Result<types::AsyncOperationSubmitResponse> {
+        self.allow_write("refresh_mental_model")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -862,7 +2147,7 @@ impl ApiClient {
         mental_model_id: &str,
         _verbose: bool,
     ) -> Result<serde_json::Value> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_mental_model_history(bank_id, mental_model_id, None)
@@ -878,7 +2163,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::DirectiveListResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .list_directives(bank_id, None, None, None, None, None, None)
@@ -893,7 +2178,7 @@ impl ApiClient {
         directive_id: &str,
         _verbose: bool,
     ) -> Result<types::DirectiveResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_directive(bank_id, directive_id, None)
@@ -908,6 +2193,7 @@ impl ApiClient {
         request: &types::CreateDirectiveRequest,
         _verbose: bool,
     ) -> Result<types::DirectiveResponse> {
+        self.allow_write("create_directive")?;
         self.runtime.block_on(async {
             let response = self.client.create_directive(bank_id, None, request).await?;
             Ok(response.into_inner())
@@ -921,6 +2207,7 @@ impl ApiClient {
         request: &types::UpdateDirectiveRequest,
         _verbose: bool,
     ) -> Result<types::DirectiveResponse> {
+        self.allow_write("update_directive")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -936,6 +2223,7 @@ impl ApiClient {
         directive_id: &str,
         _verbose: bool,
     ) -> Result<serde_json::Value> {
+        self.allow_write("delete_directive")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -952,6 +2240,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::ConsolidationResponse> {
+        self.allow_write("trigger_consolidation")?;
         self.runtime.block_on(async {
             let response = self.client.trigger_consolidation(bank_id, None).await?;
             Ok(response.into_inner())
@@ -963,6 +2252,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::DeleteResponse> {
+        self.allow_write("clear_observations")?;
         self.runtime.block_on(async {
             let response = self.client.clear_observations(bank_id, None).await?;
             Ok(response.into_inner())
@@ -972,7 +2262,7 @@ impl ApiClient {
     // --- Version Methods ---
 
     pub fn get_version(&self, _verbose: bool) -> Result<types::VersionResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_version().await?;
             Ok(response.into_inner())
         })
@@ -992,7 +2282,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::WebhookListResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.list_webhooks(bank_id, None).await?;
             Ok(response.into_inner())
         })
@@ -1004,6 +2294,7 @@ impl ApiClient {
         request: &types::CreateWebhookRequest,
         _verbose: bool,
     ) -> Result<types::WebhookResponse> {
+        self.allow_write("create_webhook")?;
         self.runtime.block_on(async {
             let response = self.client.create_webhook(bank_id, None, request).await?;
             Ok(response.into_inner())
@@ -1017,6 +2308,7 @@ impl ApiClient {
         request: &types::UpdateWebhookRequest,
         _verbose: bool,
     ) -> Result<types::WebhookResponse> {
+        self.allow_write("update_webhook")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -1032,6 +2324,7 @@ impl ApiClient {
         webhook_id: &str,
         _verbose: bool,
     ) -> Result<types::DeleteResponse> {
+        self.allow_write("delete_webhook")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -1049,7 +2342,7 @@ impl ApiClient {
         limit: Option<i64>,
         _verbose: bool,
     ) -> Result<types::WebhookDeliveryListResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .list_webhook_deliveries(bank_id, webhook_id, cursor, limit, None)
@@ -1071,7 +2364,7 @@ impl ApiClient {
         offset: Option<u64>,
         _verbose: bool,
     ) -> Result<types::AuditLogListResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let limit_nz = limit.and_then(std::num::NonZeroU64::new);
             let response = self
                 .client
@@ -1090,7 +2383,7 @@ impl ApiClient {
         period: Option<&str>,
         _verbose: bool,
     ) -> Result<types::AuditLogStatsResponse> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .audit_log_stats(bank_id, action, period, None)
@@ -1102,7 +2395,7 @@ impl ApiClient {
     // --- Bank Template Methods ---
 
     pub fn get_bank_template_schema(&self, _verbose: bool) -> Result<serde_json::Value> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.get_bank_template_schema().await?;
             Ok(response.into_inner())
         })
@@ -1113,7 +2406,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::BankTemplateManifest> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self.client.export_bank_template(bank_id, None).await?;
             Ok(response.into_inner())
         })
@@ -1129,6 +2422,9 @@ impl ApiClient {
         dry_run: bool,
         verbose: bool,
     ) -> Result<types::BankTemplateImportResponse> {
+        if !dry_run {
+            self.allow_write("import_bank_template")?;
+        }
         self.runtime.block_on(async {
             let mut url = format!("{}/v1/default/banks/{}/import", self.base_url, bank_id);
             if dry_run {
@@ -1157,6 +2453,7 @@ impl ApiClient {
         tags: Option<Vec<String>>,
         _verbose: bool,
     ) -> Result<types::UpdateDocumentResponse> {
+        self.allow_write("update_document")?;
         self.runtime.block_on(async {
             let request = types::UpdateDocumentRequest { tags };
             let response = self
@@ -1175,7 +2472,7 @@ impl ApiClient {
         memory_id: &str,
         _verbose: bool,
     ) -> Result<serde_json::Value> {
-        self.runtime.block_on(async {
+        self.read(move || async move {
             let response = self
                 .client
                 .get_observation_history(bank_id, memory_id, None)
@@ -1190,6 +2487,7 @@ impl ApiClient {
         memory_id: &str,
         _verbose: bool,
     ) -> Result<types::ClearMemoryObservationsResponse> {
+        self.allow_write("clear_memory_observations")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -1207,6 +2505,7 @@ impl ApiClient {
         operation_id: &str,
         _verbose: bool,
     ) -> Result<types::RetryOperationResponse> {
+        self.allow_write("retry_operation")?;
         self.runtime.block_on(async {
             let response = self
                 .client
@@ -1223,6 +2522,7 @@ impl ApiClient {
         bank_id: &str,
         _verbose: bool,
     ) -> Result<types::RecoverConsolidationResponse> {
+        self.allow_write("recover_consolidation")?;
         self.runtime.block_on(async {
             let response = self.client.recover_consolidation(bank_id, None).await?;
             Ok(response.into_inner())
@@ -1239,6 +2539,7 @@ impl ApiClient {
         empathy: u64,
         _verbose: bool,
     ) -> Result<types::BankProfileResponse> {
+        self.allow_write("update_bank_disposition")?;
         self.runtime.block_on(async {
             let to_nz = |v: u64| -> Result<std::num::NonZeroU64> {
                 std::num::NonZeroU64::new(v)
@@ -1258,18 +2559,120 @@ impl ApiClient {
             Ok(response.into_inner())
         })
     }
+
+    /// Write a bank's name, mission and disposition in one step: the name and mission
+    /// through PATCH /banks/{id}, then the traits through PUT /profile
+    pub fn update_profile(
+        &self,
+        bank_id: &str,
+        name: &str,
+        mission: &str,
+        disposition: &types::DispositionTraits,
+        _verbose: bool,
+    ) -> Result<types::BankProfileResponse> {
+        self.allow_write("update_profile")?;
+        self.runtime.block_on(async {
+            let request = types::CreateBankRequest {
+                name: Some(name.to_string()),
+                mission: Some(mission.to_string()),
+                ..Default::default()
+            };
+            self.client.update_bank(bank_id, None, &request).await?;
+            let request = types::UpdateDispositionRequest { disposition: disposition.clone() };
+            let response = self
+                .client
+                .update_bank_disposition(bank_id, None, &request)
+                .await?;
+            Ok(response.into_inner())
+        })
+    }
 }
 
 // Re-export types from the generated client for use in commands
 pub use types::{
-    BankProfileResponse, MemoryItem, RecallRequest, RecallResponse, RecallResult, ReflectRequest,
-    ReflectResponse, RetainRequest,
+    BankProfileResponse, MemoryItem, MemoryListItem, RecallRequest, RecallResponse, RecallResult,
+    ReflectRequest, ReflectResponse, RetainRequest,
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_graph_export_from_cytoscape_elements() {
+        let response: types::GraphDataResponse = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                {"data": {"id": "m1", "label": "Alice works...", "text": "Alice works at Google"}},
+                {"data": {"id": "m2", "label": "Bob hikes", "text": "Bob hikes"}}
+            ],
+            "edges": [
+                {"data": {"source": "m1", "target": "m2", "linkType": "entity", "weight": 0.5, "entityName": "Alice"}},
+                {"from": "m2", "to": "m1", "type": "temporal"}
+            ],
+            "table_rows": [
+                {"id": "m1", "fact_type": "world", "mentioned_at": "2024-01-15T10:00:00+00:00"}
+            ],
+            "total_units": 7,
+            "limit": 2
+        }))
+        .unwrap();
+        let graph = GraphExport::from_response(response);
+        assert_eq!(graph.total_units, 7);
+        assert_eq!(graph.nodes[0].text, "Alice works at Google");
+        assert_eq!(graph.nodes[0].fact_type.as_deref(), Some("world"));
+        assert_eq!(graph.nodes[1].fact_type, None);
+        assert_eq!(graph.links[0].link_type, "entity");
+        assert_eq!(graph.links[0].entity_name.as_deref(), Some("Alice"));
+        assert_eq!(graph.links[1].source, "m2");
+        assert_eq!(graph.links[1].weight, 1.0);
+    }
+
+    #[test]
+    fn test_entity_graph_from_cytoscape_elements() {
+        let response: types::EntityGraphResponse = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                {"data": {"id": "e1", "label": "Alice", "mentionCount": 12}},
+                {"data": {"id": "e2", "mentionCount": 8}}
+            ],
+            "edges": [
+                {"data": {"source": "e1", "target": "e2", "weight": 5, "linkType": "cooccurrence"}},
+                {"data": {"source": "e1"}}
+            ],
+            "total_entities": 40,
+            "total_edges": 2,
+            "limit": 2
+        }))
+        .unwrap();
+        let graph = EntityGraph::from_response(response);
+        assert_eq!(graph.total_entities, 40);
+        assert_eq!(graph.nodes[0].name, "Alice");
+        assert_eq!(graph.nodes[1].name, "e2");
+        assert_eq!(graph.nodes[1].mention_count, 8);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].weight, 5);
+    }
+
+    #[test]
+    fn test_transient_errors_are_retried() {
+        assert!(is_transient(&anyhow::anyhow!("API request failed (503 Service Unavailable)")));
+        assert!(is_transient(&anyhow::anyhow!("Error Response: status: 429 Too Many Requests; x")));
+        assert!(is_transient(&anyhow::anyhow!("File retain failed (502 Bad Gateway): ")));
+        assert!(!is_transient(&anyhow::anyhow!("API request failed (422 Unprocessable Entity)")));
+        assert!(!is_transient(&anyhow::anyhow!("Invalid response payload (EOF)")));
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            retries: 10,
+            delay: std::time::Duration::from_millis(100),
+            retry_writes: false,
+        };
+        assert_eq!(policy.backoff(1).as_millis(), 100);
+        assert_eq!(policy.backoff(3).as_millis(), 400);
+        assert_eq!(policy.backoff(10).as_millis(), 6400);
+    }
+
     #[test]
     fn test_operation_deserialize() {
         let json = r#"{
@@ -1366,4 +2769,175 @@ mod tests {
         assert_eq!(ops.operations[0].status, "pending");
         assert_eq!(ops.operations[1].status, "completed");
     }
+
+    /// A one-request-per-connection HTTP server answering with `responses` in turn;
+    /// joining it returns the request heads it received
+    fn serve(responses: Vec<String>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buf[..n]);
+                }
+                requests.push(String::from_utf8_lossy(&head).to_lowercase());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_hedged_read_wins_over_slow_first_request() {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        // The first request is answered after two seconds, the hedge straight away
+        std::thread::spawn(move || {
+            for delay in [2000, 0] {
+                let (mut stream, _) = listener.accept().unwrap();
+                std::thread::spawn(move || {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf);
+                    std::thread::sleep(std::time::Duration::from_millis(delay));
+                    let body = r#"{"banks": []}"#;
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                });
+            }
+        });
+
+        let client = ApiClient::new(url, None).unwrap().with_hedging(HedgeConfig {
+            delay: std::time::Duration::from_millis(100),
+            max_hedges: 1,
+        });
+        let started = std::time::Instant::now();
+        assert!(client.list_agents(false).unwrap().is_empty());
+        assert!(started.elapsed() < std::time::Duration::from_millis(1500));
+        assert_eq!(client.hedge_stats(), Some(HedgeStats { issued: 1, won: 1 }));
+    }
+
+    #[test]
+    fn test_cached_list_revalidates_with_etag() {
+        let body = r#"{"banks": []}"#;
+        let ok = format!(
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let not_modified = "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n";
+        let (url, server) = serve(vec![ok.clone(), not_modified.to_string(), ok]);
+        let client = ApiClient::new(url, None).unwrap().with_cache(CacheConfig {
+            max_entries: 10,
+            ttl: std::time::Duration::from_secs(60),
+        });
+
+        assert!(client.list_agents(false).unwrap().is_empty());
+        // Within the TTL the cached body answers without a request
+        assert!(client.list_agents(false).unwrap().is_empty());
+        // An explicit refresh asks the server, which confirms the cached copy
+        assert!(client.bypassing_cache().list_agents(false).unwrap().is_empty());
+        // A write forgets everything cached
+        client.allow_write("test").unwrap();
+        assert!(client.list_agents(false).unwrap().is_empty());
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+        assert!(!requests[2].contains("if-none-match"));
+        let stats = CacheStats {
+            hits: 1,
+            revalidated: 1,
+            misses: 2,
+        };
+        assert_eq!(client.cache_stats(), Some(stats));
+    }
+
+    #[test]
+    fn test_missing_entity_routes_are_told_apart_from_missing_entities() {
+        use reqwest::StatusCode;
+        assert!(is_missing_route(
+            StatusCode::METHOD_NOT_ALLOWED,
+            r#"{"detail":"Method Not Allowed"}"#
+        ));
+        assert!(is_missing_route(
+            StatusCode::NOT_FOUND,
+            r#"{"detail":"Not Found"}"#
+        ));
+        assert!(!is_missing_route(
+            StatusCode::NOT_FOUND,
+            r#"{"detail":"Entity e1 not found"}"#
+        ));
+        assert!(!is_missing_route(StatusCode::NOT_FOUND, "Not Found"));
+        assert!(!is_missing_route(
+            StatusCode::BAD_REQUEST,
+            r#"{"detail":"Not Found"}"#
+        ));
+    }
+
+    #[test]
+    fn test_file_retain_too_large_is_explained() {
+        let err = file_retain_error(
+            reqwest::StatusCode::BAD_REQUEST,
+            "Total file size 150.0MB exceeds maximum of 100MB",
+            150 * 1024 * 1024,
+        );
+        assert!(err.to_string().contains("larger than the server accepts"));
+        let err = file_retain_error(reqwest::StatusCode::BAD_REQUEST, "Invalid JSON", 10);
+        assert!(!err.to_string().contains("larger than the server accepts"));
+
+        let timeout = std::time::Duration::from_secs(30);
+        assert_eq!(upload_timeout(timeout, 0), timeout);
+        assert_eq!(
+            upload_timeout(timeout, 10 * UPLOAD_MIN_RATE),
+            std::time::Duration::from_secs(40)
+        );
+    }
+
+    #[test]
+    fn test_merge_recall_results_interleaves_and_fits_budget() {
+        let listed = |bank_id: &str, ids: &[&str]| -> Vec<BankRecallResult> {
+            ids.iter()
+                .map(|id| BankRecallResult {
+                    bank_id: bank_id.to_string(),
+                    result: serde_json::from_value(
+                        serde_json::json!({"id": id, "text": "x".repeat(40)}),
+                    )
+                    .unwrap(),
+                })
+                .collect()
+        };
+        let ids = |merged: &[BankRecallResult]| -> Vec<String> {
+            merged.iter().map(|m| format!("{}:{}", m.bank_id, m.result.id)).collect()
+        };
+        let ranked = || vec![listed("a", &["a1", "a2", "a3", "a4"]), listed("b", &["b1", "b2"])];
+
+        let merged = merge_recall_results(ranked(), MergeStrategy::Interleave, 4096);
+        assert_eq!(ids(&merged), vec!["a:a1", "b:b1", "a:a2", "b:b2", "a:a3", "a:a4"]);
+
+        // b2 is last in its bank, so it ranks with a4 rather than a2
+        let merged = merge_recall_results(ranked(), MergeStrategy::Rank, 4096);
+        assert_eq!(ids(&merged), vec!["a:a1", "a:a2", "b:b1", "a:a3", "a:a4", "b:b2"]);
+
+        // Each result is about 10 tokens
+        let merged = merge_recall_results(ranked(), MergeStrategy::Interleave, 25);
+        assert_eq!(ids(&merged), vec!["a:a1", "b:b1"]);
+    }
 }
@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::io::{self, BufRead, IsTerminal};
+
+use crate::api::{self, ApiClient};
+use crate::config::Config;
+use crate::credentials::{self, Backend};
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+/// Backend name and file path, for JSON output
+fn backend_fields(backend: &Backend) -> (&'static str, Option<String>) {
+    match backend {
+        Backend::Keyring => ("keyring", None),
+        Backend::ObfuscatedFile(path) => ("obfuscated_file", Some(path.display().to_string())),
+    }
+}
+
+/// Prompt for the key without echoing it, or read one line when stdin is piped
+fn read_api_key() -> Result<String> {
+    let key = if io::stdin().is_terminal() {
+        rpassword::prompt_password("API key: ")?
+    } else {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        line
+    };
+    let key = key.trim().to_string();
+    if key.is_empty() {
+        anyhow::bail!("No API key entered");
+    }
+    Ok(key)
+}
+
+/// Verify an API key against the configured server and store it for the profile
+pub fn login(profile: Option<&str>, output_format: OutputFormat) -> Result<()> {
+    let profile = Config::active_profile(profile);
+    let account = credentials::account_name(profile.as_deref()).to_string();
+    let config = Config::load_with_profile(profile.as_deref())?;
+    let api_key = read_api_key()?;
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Verifying API key..."))
+    } else {
+        None
+    };
+    let client = ApiClient::with_timeout(
+        config.api_url().to_string(),
+        Some(api_key.clone()),
+        config.timeout.unwrap_or(api::DEFAULT_TIMEOUT),
+    )?;
+    // Health confirms the server is reachable; listing banks needs a valid key
+    let verified = client.health(false).and_then(|_| client.list_agents(false));
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    verified.with_context(|| format!("Could not verify the API key against {}", config.api_url()))?;
+
+    let backend = credentials::store(&account, &api_key)?;
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!(
+            "Logged in to {} (profile '{}')",
+            config.api_url(),
+            account
+        ));
+        println!("  {} {}", ui::dim("Key stored in:"), backend);
+        if matches!(backend, Backend::ObfuscatedFile(_)) {
+            ui::print_warning(
+                "No OS keyring available; the key is only obfuscated in a file readable by you",
+            );
+        }
+    } else {
+        let (name, path) = backend_fields(&backend);
+        output::print_output(
+            &json!({
+                "profile": account,
+                "api_url": config.api_url(),
+                "backend": name,
+                "path": path,
+            }),
+            output_format,
+        )?;
+    }
+    Ok(())
+}
+
+/// Remove the stored key for the profile from the keyring and the fallback file
+pub fn logout(profile: Option<&str>, output_format: OutputFormat) -> Result<()> {
+    let profile = Config::active_profile(profile);
+    let account = credentials::account_name(profile.as_deref());
+    let removed = credentials::delete(account)?;
+
+    if output_format == OutputFormat::Pretty {
+        if removed.is_empty() {
+            ui::print_info(&format!("No stored API key for profile '{}'", account));
+        }
+        for backend in &removed {
+            ui::print_success(&format!(
+                "Removed API key for profile '{}' from {}",
+                account, backend
+            ));
+        }
+    } else {
+        let removed: Vec<&str> = removed.iter().map(|b| backend_fields(b).0).collect();
+        output::print_output(&json!({ "profile": account, "removed": removed }), output_format)?;
+    }
+    Ok(())
+}
+
+/// Show whether a key is stored for the profile, where, and whether the environment overrides it
+pub fn status(profile: Option<&str>, output_format: OutputFormat) -> Result<()> {
+    let profile = Config::active_profile(profile);
+    let account = credentials::account_name(profile.as_deref());
+    let stored = credentials::load(account)?;
+    let env_override = std::env::var("HINDSIGHT_API_KEY").is_ok_and(|k| !k.trim().is_empty());
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_section_header(&format!("Auth: profile '{}'", account));
+        match &stored {
+            Some((key, backend)) => {
+                println!("  {} {}", ui::dim("API key:"), crate::mask_api_key(key));
+                println!("  {} {}", ui::dim("Stored in:"), backend);
+            }
+            None => println!(
+                "  {} none (run: hindsight auth login)",
+                ui::dim("API key:")
+            ),
+        }
+        if env_override {
+            ui::print_warning("HINDSIGHT_API_KEY is set and takes precedence over the stored key");
+        }
+        println!();
+    } else {
+        let (backend, path) = match &stored {
+            Some((_, backend)) => {
+                let (name, path) = backend_fields(backend);
+                (Some(name), path)
+            }
+            None => (None, None),
+        };
+        output::print_output(
+            &json!({
+                "profile": account,
+                "stored": stored.is_some(),
+                "backend": backend,
+                "path": path,
+                "env_override": env_override,
+            }),
+            output_format,
+        )?;
+    }
+    Ok(())
+}
@@ -1,7 +1,28 @@
-use crate::api::ApiClient;
+use crate::api::{AgentStats, ApiClient, BankProfileResponse, MemoryListItem};
+use crate::commands::document::{fetch_all_chunks, fetch_all_documents};
+use crate::commands::entity::{entity_aliases, fetch_all_entities};
+use crate::commands::memory::{
+    build_recall_request, fetch_matching_memories, memory_document_id, MemoryFilter,
+};
+use crate::executor::{self, PlannedWrite};
 use crate::output::{self, OutputFormat};
+use crate::pager;
+use crate::redact::{EntityTerm, Redactor};
 use crate::ui;
+use crate::utils;
 use anyhow::{anyhow, Result};
+use chrono::{Local, Utc};
+use colored::Colorize;
+use hindsight_client::types::{ChunkResponse, DispositionTraits, EntityListItem};
+use crossterm::cursor;
+use crossterm::execute;
+use crossterm::terminal::{Clear, ClearType};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -18,7 +39,9 @@ pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> R
 
     match response {
         Ok(banks_list) => {
-            if output_format == OutputFormat::Pretty {
+            if output_format == OutputFormat::Table {
+                output::print_table(&banks_list, &["id", "name"])?;
+            } else if output_format == OutputFormat::Pretty {
                 if banks_list.is_empty() {
                     ui::print_warning("No banks found");
                 } else {
@@ -88,94 +111,727 @@ pub fn stats(
     match response {
         Ok(stats) => {
             if output_format == OutputFormat::Pretty {
-                ui::print_section_header(&format!("Statistics: {}", bank_id));
+                pager::page(|| {
+                    print_stats(bank_id, &stats, None);
+                    Ok(())
+                })?;
+            } else {
+                output::print_output(&stats, output_format)?;
+            }
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
 
+/// Signed change between two samples, e.g. "+134"; `None` when unchanged
+fn format_delta(current: i32, previous: i32) -> Option<String> {
+    match current - previous {
+        0 => None,
+        d if d > 0 => Some(format!("+{}", d)),
+        d => Some(d.to_string()),
+    }
+}
+
+/// Colored delta suffix shown next to a counter in watch mode
+fn delta_suffix(current: i32, previous: Option<i32>) -> String {
+    let Some(previous) = previous else {
+        return String::new();
+    };
+    match format_delta(current, previous) {
+        Some(d) if current > previous => format!(" {}", d.bright_green()),
+        Some(d) => format!(" {}", d.bright_red()),
+        None => String::new(),
+    }
+}
+
+/// Render bank statistics; `previous` highlights changes since the last sample
+fn print_stats(bank_id: &str, stats: &AgentStats, previous: Option<&AgentStats>) {
+    ui::print_section_header(&format!("Statistics: {}", bank_id));
+
+    println!(
+        "  {} {}{}",
+        ui::dim("memory units:"),
+        ui::gradient_start(&stats.total_nodes.to_string()),
+        delta_suffix(stats.total_nodes, previous.map(|p| p.total_nodes))
+    );
+    println!(
+        "  {} {}{}",
+        ui::dim("links:"),
+        ui::gradient_mid(&stats.total_links.to_string()),
+        delta_suffix(stats.total_links, previous.map(|p| p.total_links))
+    );
+    println!(
+        "  {} {}{}",
+        ui::dim("documents:"),
+        ui::gradient_end(&stats.total_documents.to_string()),
+        delta_suffix(stats.total_documents, previous.map(|p| p.total_documents))
+    );
+    println!();
+
+    print_counts(
+        "─── Memory Units by Type ───",
+        &stats.nodes_by_fact_type,
+        previous.map(|p| &p.nodes_by_fact_type),
+    );
+    print_counts(
+        "─── Links by Type ───",
+        &stats.links_by_link_type,
+        previous.map(|p| &p.links_by_link_type),
+    );
+    print_counts(
+        "─── Links by Fact Type ───",
+        &stats.links_by_fact_type,
+        previous.map(|p| &p.links_by_fact_type),
+    );
+
+    if !stats.links_breakdown.is_empty() {
+        println!("{}", ui::gradient_text("─── Detailed Link Breakdown ───"));
+        let mut fact_types: Vec<_> = stats.links_breakdown.iter().collect();
+        fact_types.sort_by_key(|(k, _)| *k);
+        for (fact_type, link_types) in fact_types {
+            println!("  {}", fact_type);
+            let mut sorted_links: Vec<_> = link_types.iter().collect();
+            sorted_links.sort_by_key(|(k, _)| *k);
+            for (link_type, count) in sorted_links {
+                let prev = previous
+                    .and_then(|p| p.links_breakdown.get(fact_type))
+                    .map(|m| m.get(link_type).copied().unwrap_or(0));
+                println!("    {:<10} {}{}", ui::dim(link_type), count, delta_suffix(*count, prev));
+            }
+        }
+        println!();
+    }
+
+    if stats.pending_operations > 0 || stats.failed_operations > 0 {
+        println!("{}", ui::gradient_text("─── Operations ───"));
+        if stats.pending_operations > 0 {
+            println!(
+                "  {} {}{}",
+                ui::dim("pending:"),
+                stats.pending_operations,
+                delta_suffix(stats.pending_operations, previous.map(|p| p.pending_operations))
+            );
+        }
+        if stats.failed_operations > 0 {
+            println!(
+                "  {} {}{}",
+                ui::dim("failed:"),
+                stats.failed_operations,
+                delta_suffix(stats.failed_operations, previous.map(|p| p.failed_operations))
+            );
+        }
+    }
+}
+
+fn print_counts(
+    title: &str,
+    counts: &HashMap<String, i32>,
+    previous: Option<&HashMap<String, i32>>,
+) {
+    println!("{}", ui::gradient_text(title));
+    let mut sorted: Vec<_> = counts.iter().collect();
+    sorted.sort_by_key(|(k, _)| *k);
+    for (i, (key, count)) in sorted.iter().enumerate() {
+        let t = i as f32 / sorted.len().max(1) as f32;
+        let prev = previous.map(|p| p.get(*key).copied().unwrap_or(0));
+        println!(
+            "  {:<10} {}{}",
+            key,
+            ui::gradient(&count.to_string(), t),
+            delta_suffix(**count, prev)
+        );
+    }
+    println!();
+}
+
+/// Re-sample bank statistics every `interval_secs` seconds.
+///
+/// On a terminal with pretty output the screen is redrawn with deltas since the
+/// previous sample; otherwise one JSON object is printed per sample (NDJSON).
+pub fn stats_watch(
+    client: &ApiClient,
+    bank_id: &str,
+    interval_secs: u64,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let redraw = output_format == OutputFormat::Pretty && std::io::stdout().is_terminal();
+    let stop = utils::install_interrupt_flag();
+    let mut stdout = std::io::stdout();
+    let mut previous: Option<AgentStats> = None;
+
+    if redraw {
+        execute!(stdout, cursor::Hide)?;
+    }
+
+    while !stop.load(Ordering::SeqCst) {
+        match client.get_stats(bank_id, verbose) {
+            Ok(stats) if redraw => {
+                execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                print_stats(bank_id, &stats, previous.as_ref());
+                println!();
                 println!(
-                    "  {} {}",
-                    ui::dim("memory units:"),
-                    ui::gradient_start(&stats.total_nodes.to_string())
-                );
-                println!(
-                    "  {} {}",
-                    ui::dim("links:"),
-                    ui::gradient_mid(&stats.total_links.to_string())
-                );
-                println!(
-                    "  {} {}",
-                    ui::dim("documents:"),
-                    ui::gradient_end(&stats.total_documents.to_string())
+                    "  {}",
+                    ui::dim(&format!(
+                        "{} · every {}s · Ctrl+C to exit",
+                        Local::now().format("%H:%M:%S"),
+                        interval.as_secs()
+                    ))
                 );
-                println!();
-
-                println!("{}", ui::gradient_text("─── Memory Units by Type ───"));
-                let mut fact_types: Vec<_> = stats.nodes_by_fact_type.iter().collect();
-                fact_types.sort_by_key(|(k, _)| *k);
-                for (i, (fact_type, count)) in fact_types.iter().enumerate() {
-                    let t = i as f32 / fact_types.len().max(1) as f32;
-                    println!(
-                        "  {:<10} {}",
-                        fact_type,
-                        ui::gradient(&count.to_string(), t)
-                    );
+                previous = Some(stats);
+            }
+            Ok(stats) => {
+                let mut sample = serde_json::to_value(&stats)?;
+                if let Some(obj) = sample.as_object_mut() {
+                    obj.insert("sampled_at".to_string(), json!(Utc::now().to_rfc3339()));
                 }
-                println!();
+                writeln!(stdout, "{}", serde_json::to_string(&sample)?)?;
+                stdout.flush()?;
+            }
+            // Keep watching through transient failures (e.g. a server restart)
+            Err(e) if redraw => ui::print_warning(&format!("Failed to fetch statistics: {:#}", e)),
+            Err(e) => eprintln!("Failed to fetch statistics: {:#}", e),
+        }
+        utils::sleep_unless_stopped(interval, &stop);
+    }
 
-                println!("{}", ui::gradient_text("─── Links by Type ───"));
-                let mut link_types: Vec<_> = stats.links_by_link_type.iter().collect();
-                link_types.sort_by_key(|(k, _)| *k);
-                for (i, (link_type, count)) in link_types.iter().enumerate() {
-                    let t = i as f32 / link_types.len().max(1) as f32;
-                    println!(
-                        "  {:<10} {}",
-                        link_type,
-                        ui::gradient(&count.to_string(), t)
-                    );
-                }
-                println!();
+    if redraw {
+        execute!(stdout, cursor::Show)?;
+        println!();
+    }
+    Ok(())
+}
 
-                println!("{}", ui::gradient_text("─── Links by Fact Type ───"));
-                let mut fact_type_links: Vec<_> = stats.links_by_fact_type.iter().collect();
-                fact_type_links.sort_by_key(|(k, _)| *k);
-                for (i, (fact_type, count)) in fact_type_links.iter().enumerate() {
-                    let t = i as f32 / fact_type_links.len().max(1) as f32;
-                    println!(
-                        "  {:<10} {}",
-                        fact_type,
-                        ui::gradient(&count.to_string(), t)
-                    );
-                }
-                println!();
+#[derive(Debug, Serialize)]
+struct CountDiff {
+    key: String,
+    a: i64,
+    b: i64,
+    delta: i64,
+}
 
-                if !stats.links_breakdown.is_empty() {
-                    println!("{}", ui::gradient_text("─── Detailed Link Breakdown ───"));
-                    let mut fact_types: Vec<_> = stats.links_breakdown.iter().collect();
-                    fact_types.sort_by_key(|(k, _)| *k);
-                    for (fact_type, link_types) in fact_types {
-                        println!("  {}", fact_type);
-                        let mut sorted_links: Vec<_> = link_types.iter().collect();
-                        sorted_links.sort_by_key(|(k, _)| *k);
-                        for (link_type, count) in sorted_links {
-                            println!("    {:<10} {}", ui::dim(link_type), count);
-                        }
-                    }
-                    println!();
-                }
+#[derive(Debug, Serialize)]
+struct RecallOverlap {
+    query: String,
+    a_count: usize,
+    b_count: usize,
+    shared: usize,
+    /// Overlap of returned memory IDs
+    jaccard: f64,
+    /// Overlap of normalized memory text; IDs differ between re-ingested banks
+    text_jaccard: f64,
+}
 
-                if stats.pending_operations > 0 || stats.failed_operations > 0 {
-                    println!("{}", ui::gradient_text("─── Operations ───"));
-                    if stats.pending_operations > 0 {
-                        println!("  {} {}", ui::dim("pending:"), stats.pending_operations);
-                    }
-                    if stats.failed_operations > 0 {
-                        println!("  {} {}", ui::dim("failed:"), stats.failed_operations);
-                    }
+#[derive(Debug, Serialize)]
+struct BankComparison {
+    bank_a: String,
+    bank_b: String,
+    totals: Vec<CountDiff>,
+    nodes_by_fact_type: Vec<CountDiff>,
+    links_by_fact_type: Vec<CountDiff>,
+    links_by_link_type: Vec<CountDiff>,
+    disposition: Vec<CountDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recall: Option<RecallOverlap>,
+}
+
+fn count_diff(key: &str, a: i64, b: i64) -> CountDiff {
+    CountDiff {
+        key: key.to_string(),
+        a,
+        b,
+        delta: b - a,
+    }
+}
+
+/// Trait-by-trait change from disposition `a` to `b`
+fn disposition_diff(a: &DispositionTraits, b: &DispositionTraits) -> Vec<CountDiff> {
+    let traits = |d: &DispositionTraits| {
+        [
+            ("skepticism", d.skepticism.get() as i64),
+            ("literalism", d.literalism.get() as i64),
+            ("empathy", d.empathy.get() as i64),
+        ]
+    };
+    traits(a)
+        .iter()
+        .zip(traits(b).iter())
+        .map(|((name, a), (_, b))| count_diff(name, *a, *b))
+        .collect()
+}
+
+/// One line per trait: old → new with an arrow for the direction of change
+fn print_disposition_diff(diffs: &[CountDiff]) {
+    println!("{}", ui::gradient_text("─── Disposition ───"));
+    for diff in diffs {
+        let arrow = match diff.delta {
+            d if d > 0 => "↑".bright_green().to_string(),
+            d if d < 0 => "↓".bright_red().to_string(),
+            _ => ui::dim("="),
+        };
+        println!("  {:<16} {} → {} {}", diff.key, diff.a, diff.b, arrow);
+    }
+    println!();
+}
+
+/// Per-key differences over the union of both maps, sorted by key
+fn diff_counts(a: &HashMap<String, i32>, b: &HashMap<String, i32>) -> Vec<CountDiff> {
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    keys.into_iter()
+        .map(|k| {
+            count_diff(
+                k,
+                a.get(k).copied().unwrap_or(0) as i64,
+                b.get(k).copied().unwrap_or(0) as i64,
+            )
+        })
+        .collect()
+}
+
+/// Jaccard similarity of two sets; two empty sets are identical
+fn jaccard<T: Ord>(a: &BTreeSet<T>, b: &BTreeSet<T>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn print_diff_section(title: &str, diffs: &[CountDiff], bank_a: &str, bank_b: &str) {
+    println!("{}", ui::gradient_text(&format!("─── {} ───", title)));
+    if diffs.is_empty() {
+        println!("  {}", ui::dim("(none)"));
+        println!();
+        return;
+    }
+    println!(
+        "  {:<16} {:>12} {:>12} {:>10}",
+        ui::dim(""),
+        ui::dim(&output::truncate_to_width(bank_a, 12)),
+        ui::dim(&output::truncate_to_width(bank_b, 12)),
+        ui::dim("Δ")
+    );
+    for diff in diffs {
+        let delta = match format_delta(diff.b as i32, diff.a as i32) {
+            Some(d) if diff.delta > 0 => format!("{:>10}", d).bright_green().to_string(),
+            Some(d) => format!("{:>10}", d).bright_red().to_string(),
+            None => format!("{:>10}", "="),
+        };
+        println!("  {:<16} {:>12} {:>12} {}", diff.key, diff.a, diff.b, delta);
+    }
+    println!();
+}
+
+/// Compare statistics and disposition of two banks, optionally with a shared recall
+pub fn compare(
+    client: &ApiClient,
+    bank_a: &str,
+    bank_b: &str,
+    recall_query: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Comparing banks..."))
+    } else {
+        None
+    };
+
+    let result = build_comparison(client, bank_a, bank_b, recall_query, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let comparison = result?;
+
+    if output_format != OutputFormat::Pretty {
+        return output::print_output(&comparison, output_format);
+    }
+
+    ui::print_section_header(&format!("Compare: {} → {}", bank_a, bank_b));
+    print_diff_section("Totals", &comparison.totals, bank_a, bank_b);
+    print_diff_section("Memory Units by Type", &comparison.nodes_by_fact_type, bank_a, bank_b);
+    print_diff_section("Links by Fact Type", &comparison.links_by_fact_type, bank_a, bank_b);
+    print_diff_section("Links by Type", &comparison.links_by_link_type, bank_a, bank_b);
+
+    print_disposition_diff(&comparison.disposition);
+
+    if let Some(overlap) = &comparison.recall {
+        println!("{}", ui::gradient_text("─── Recall Overlap ───"));
+        println!("  {} {}", ui::dim("query:"), overlap.query);
+        println!(
+            "  {} {} / {} ({} shared)",
+            ui::dim("results:"),
+            overlap.a_count,
+            overlap.b_count,
+            overlap.shared
+        );
+        println!("  {} {:.3}", ui::dim("jaccard (ids):"), overlap.jaccard);
+        println!("  {} {:.3}", ui::dim("jaccard (text):"), overlap.text_jaccard);
+        println!();
+    }
+
+    Ok(())
+}
+
+fn build_comparison(
+    client: &ApiClient,
+    bank_a: &str,
+    bank_b: &str,
+    recall_query: Option<String>,
+    verbose: bool,
+) -> Result<BankComparison> {
+    let stats_a = client.get_stats(bank_a, verbose)?;
+    let stats_b = client.get_stats(bank_b, verbose)?;
+    let profile_a = client.get_profile(bank_a, verbose)?;
+    let profile_b = client.get_profile(bank_b, verbose)?;
+    let disposition = disposition_diff(&profile_a.disposition, &profile_b.disposition);
+
+    let recall = match recall_query {
+        Some(query) => {
+            let request = build_recall_request(
+                query.clone(),
+                Vec::new(),
+                "mid",
+                4096,
+                false,
+                false,
+                0,
+                Vec::new(),
+                None,
+                None,
+            );
+            let results_a = client.recall(bank_a, &request, verbose)?.results;
+            let results_b = client.recall(bank_b, &request, verbose)?.results;
+            let ids_a: BTreeSet<&str> = results_a.iter().map(|r| r.id.as_str()).collect();
+            let ids_b: BTreeSet<&str> = results_b.iter().map(|r| r.id.as_str()).collect();
+            let normalize = |t: &str| t.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+            let texts_a: BTreeSet<String> = results_a.iter().map(|r| normalize(&r.text)).collect();
+            let texts_b: BTreeSet<String> = results_b.iter().map(|r| normalize(&r.text)).collect();
+            Some(RecallOverlap {
+                query,
+                a_count: results_a.len(),
+                b_count: results_b.len(),
+                shared: ids_a.intersection(&ids_b).count(),
+                jaccard: jaccard(&ids_a, &ids_b),
+                text_jaccard: jaccard(&texts_a, &texts_b),
+            })
+        }
+        None => None,
+    };
+
+    Ok(BankComparison {
+        bank_a: bank_a.to_string(),
+        bank_b: bank_b.to_string(),
+        totals: vec![
+            count_diff("memory units", stats_a.total_nodes as i64, stats_b.total_nodes as i64),
+            count_diff("links", stats_a.total_links as i64, stats_b.total_links as i64),
+            count_diff("documents", stats_a.total_documents as i64, stats_b.total_documents as i64),
+        ],
+        nodes_by_fact_type: diff_counts(&stats_a.nodes_by_fact_type, &stats_b.nodes_by_fact_type),
+        links_by_fact_type: diff_counts(&stats_a.links_by_fact_type, &stats_b.links_by_fact_type),
+        links_by_link_type: diff_counts(&stats_a.links_by_link_type, &stats_b.links_by_link_type),
+        disposition,
+        recall,
+    })
+}
+
+/// Memories shown before asking for prune confirmation
+const PRUNE_SAMPLE_SIZE: usize = 10;
+
+/// A document whose memories all match the prune filters
+#[derive(Debug, Serialize)]
+pub(crate) struct PruneDocument {
+    document_id: String,
+    memories: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct PrunePlan {
+    pub(crate) matched: usize,
+    pub(crate) documents: Vec<PruneDocument>,
+    /// Matching memories that share a document with memories that don't match
+    pub(crate) kept_partial_documents: usize,
+    /// Matching memories with no source document (e.g. observations)
+    pub(crate) kept_no_document: usize,
+}
+
+impl PrunePlan {
+    /// Memories in the documents that will be deleted
+    pub(crate) fn memories(&self) -> usize {
+        self.documents.iter().map(|d| d.memories).sum()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct PruneSummary {
+    documents_deleted: usize,
+    memories_deleted: i64,
+    pub(crate) failed: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
+/// Group prune candidates by source document. The API deletes memories per
+/// document, so a document is only pruned when every one of its memories matched.
+pub(crate) fn plan_prune(
+    client: &ApiClient,
+    bank_id: &str,
+    candidates: &[MemoryListItem],
+    verbose: bool,
+) -> Result<PrunePlan> {
+    let mut by_document: BTreeMap<String, usize> = BTreeMap::new();
+    let mut plan = PrunePlan {
+        matched: candidates.len(),
+        ..Default::default()
+    };
+    for item in candidates {
+        match memory_document_id(bank_id, item) {
+            Some(doc_id) => *by_document.entry(doc_id).or_insert(0) += 1,
+            None => plan.kept_no_document += 1,
+        }
+    }
+    for (document_id, matched) in by_document {
+        let total = client.get_document(bank_id, &document_id, verbose)?.memory_unit_count as usize;
+        if matched >= total {
+            plan.documents.push(PruneDocument {
+                document_id,
+                memories: matched,
+            });
+        } else {
+            plan.kept_partial_documents += matched;
+        }
+    }
+    Ok(plan)
+}
+
+/// Delete old or unwanted memories from a bank.
+///
+/// With only `--type`, the bank's bulk clear endpoint removes exactly that fact
+/// type. Otherwise matching memories are removed by deleting their source
+/// documents, which only happens for documents whose memories all match.
+#[allow(clippy::too_many_arguments)]
+pub fn prune(
+    client: &ApiClient,
+    bank_id: &str,
+    older_than: Option<String>,
+    fact_type: Option<String>,
+    document_id: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if older_than.is_none() && fact_type.is_none() && document_id.is_none() {
+        anyhow::bail!("Specify at least one of --older-than, --type, or --document-id");
+    }
+    let filter = MemoryFilter {
+        until: older_than.as_deref().map(utils::parse_datetime_arg).transpose()?,
+        document_id,
+        ..Default::default()
+    };
+    let whole_type = filter.is_empty();
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Finding memories to prune..."))
+    } else {
+        None
+    };
+    let scanned = fetch_matching_memories(client, bank_id, fact_type.as_deref(), &filter, verbose)
+        .and_then(|candidates| {
+            let plan = if whole_type {
+                PrunePlan {
+                    matched: candidates.len(),
+                    ..Default::default()
                 }
             } else {
-                output::print_output(&stats, output_format)?;
+                plan_prune(client, bank_id, &candidates, verbose)?
+            };
+            Ok((candidates, plan))
+        });
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let (candidates, plan) = scanned?;
+
+    let deletable: Vec<&MemoryListItem> = if whole_type {
+        candidates.iter().collect()
+    } else {
+        let docs: BTreeSet<&str> = plan.documents.iter().map(|d| d.document_id.as_str()).collect();
+        candidates
+            .iter()
+            .filter(|item| {
+                memory_document_id(bank_id, item).is_some_and(|d| docs.contains(d.as_str()))
+            })
+            .collect()
+    };
+
+    if deletable.is_empty() {
+        if output_format == OutputFormat::Pretty {
+            ui::print_warning(&format!("Nothing to prune ({} matching memories)", plan.matched));
+            print_prune_kept(&plan);
+        } else {
+            output::print_output(&PruneSummary::default(), output_format)?;
+        }
+        return Ok(());
+    }
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_section_header(&format!("Prune: {}", bank_id));
+        println!("  {} {}", ui::dim("Matching memories:"), plan.matched);
+        println!("  {} {}", ui::dim("Will delete:      "), deletable.len());
+        print_prune_kept(&plan);
+        println!();
+        for item in deletable.iter().take(PRUNE_SAMPLE_SIZE) {
+            let fact_type = item.fact_type.as_deref().unwrap_or("unknown");
+            let text = output::truncate_to_width(&item.text, 100);
+            println!("  [{}] {}", fact_type.to_uppercase(), text);
+        }
+        if deletable.len() > PRUNE_SAMPLE_SIZE {
+            println!("  {}", ui::dim(&format!("... and {} more", deletable.len() - PRUNE_SAMPLE_SIZE)));
+        }
+        println!();
+    }
+
+    // Deleting is irreversible, so confirm even outside pretty mode unless --yes was given.
+    // A dry run deletes nothing, so there is nothing to confirm.
+    let executor = executor::get();
+    let message = format!(
+        "Delete {} memories from bank '{}'? This cannot be undone.",
+        deletable.len(),
+        bank_id
+    );
+    if !executor.is_dry_run() && !ui::prompt_confirmation(&message)? {
+        ui::print_info("Operation cancelled");
+        return Ok(());
+    }
+
+    let mut summary = PruneSummary::default();
+    if whole_type {
+        let fact_type = fact_type.as_deref();
+        let ids = deletable.iter().map(|item| item.id.clone()).collect();
+        let plan = PlannedWrite::new("clear_memories", bank_id)
+            .target(fact_type.unwrap_or_default())
+            .count(deletable.len())
+            .ids(ids);
+        match executor.execute(plan, || client.clear_memories(bank_id, fact_type, verbose)) {
+            Ok(None) => {}
+            Ok(Some(result)) => {
+                summary.memories_deleted = result.deleted_count.unwrap_or(deletable.len() as i64);
+            }
+            Err(e) => {
+                summary.failed = deletable.len();
+                summary.errors.push(format!("{:#}", e));
             }
-            Ok(())
         }
-        Err(e) => Err(e),
+    } else {
+        summary = delete_prune_documents(
+            client,
+            bank_id,
+            &plan.documents,
+            &deletable,
+            "Pruning documents",
+            verbose,
+            output_format,
+        );
+    }
+
+    // The planned deletions are printed instead
+    if executor.is_dry_run() {
+        return Ok(());
+    }
+
+    if output_format == OutputFormat::Pretty {
+        print_prune_summary(&summary);
+    } else {
+        output::print_output(&summary, output_format)?;
+    }
+
+    if summary.failed > 0 {
+        anyhow::bail!("{} memories could not be deleted", summary.failed);
+    }
+    Ok(())
+}
+
+/// Delete each planned document, recording the memories in `items` it held as the
+/// write's IDs. A failed delete is counted and the rest carry on.
+pub(crate) fn delete_prune_documents(
+    client: &ApiClient,
+    bank_id: &str,
+    documents: &[PruneDocument],
+    items: &[&MemoryListItem],
+    progress: &'static str,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> PruneSummary {
+    let mut summary = PruneSummary::default();
+    let pb = if output_format == OutputFormat::Pretty {
+        ui::create_progress_bar(documents.len() as u64, progress)
+    } else {
+        indicatif::ProgressBar::hidden()
+    };
+    for doc in documents {
+        let ids = items
+            .iter()
+            .filter(|item| memory_document_id(bank_id, item).as_ref() == Some(&doc.document_id))
+            .map(|item| item.id.clone())
+            .collect();
+        let write = PlannedWrite::new("delete_document", bank_id)
+            .target(&doc.document_id)
+            .count(doc.memories)
+            .ids(ids);
+        let deleted = executor::get().execute(write, || {
+            client.delete_document(bank_id, &doc.document_id, verbose)
+        });
+        match deleted {
+            Ok(None) => {}
+            Ok(Some(result)) if result.success => {
+                summary.documents_deleted += 1;
+                summary.memories_deleted += result.deleted_count.unwrap_or(doc.memories as i64);
+            }
+            Ok(Some(result)) => {
+                summary.failed += doc.memories;
+                summary.errors.push(format!(
+                    "{}: {}",
+                    doc.document_id,
+                    result.message.unwrap_or_default()
+                ));
+            }
+            Err(e) => {
+                summary.failed += doc.memories;
+                summary.errors.push(format!("{}: {:#}", doc.document_id, e));
+            }
+        }
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+    summary
+}
+
+pub(crate) fn print_prune_summary(summary: &PruneSummary) {
+    ui::print_success(&format!(
+        "Deleted {} memories ({} documents), {} failed",
+        summary.memories_deleted, summary.documents_deleted, summary.failed
+    ));
+    for error in &summary.errors {
+        ui::print_error(error);
+    }
+}
+
+fn print_prune_kept(plan: &PrunePlan) {
+    if plan.kept_partial_documents > 0 {
+        println!(
+            "  {} {} (their documents also contain newer or non-matching memories)",
+            ui::dim("Kept:             "),
+            plan.kept_partial_documents
+        );
+    }
+    if plan.kept_no_document > 0 {
+        println!(
+            "  {} {} (no source document to delete)",
+            ui::dim("Kept:             "),
+            plan.kept_no_document
+        );
     }
 }
 
@@ -219,7 +875,8 @@ pub fn update_background(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let current_profile = if !no_update_disposition {
+    // The prior profile feeds the background diff and the disposition changes
+    let current_profile = if output_format == OutputFormat::Pretty || !no_update_disposition {
         client.get_profile(bank_id, verbose).ok()
     } else {
         None
@@ -241,7 +898,11 @@ pub fn update_background(
         Ok(profile) => {
             if output_format == OutputFormat::Pretty {
                 ui::print_success("Background updated successfully");
-                println!("\n{}", profile.mission);
+                println!();
+                match &current_profile {
+                    Some(old) => ui::print_text_diff(&old.mission, &profile.mission),
+                    None => println!("{}", profile.mission),
+                }
 
                 if !no_update_disposition {
                     if let (Some(old_p), Some(new_p)) = (
@@ -263,6 +924,218 @@ pub fn update_background(
     }
 }
 
+/// `bank background diff`: the changes that would turn the bank's current background
+/// into the text of `path`
+#[derive(Debug, Serialize)]
+struct BackgroundDiff {
+    bank_id: String,
+    file: String,
+    changed: bool,
+    /// Unified diff from the live background to the file
+    diff: String,
+}
+
+/// Compare a bank's live background with a local file
+pub fn background_diff(
+    client: &ApiClient,
+    bank_id: &str,
+    path: &std::path::Path,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let local = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let profile = client.get_profile(bank_id, verbose)?;
+
+    if output_format == OutputFormat::Pretty {
+        println!("{}", ui::dim(&format!("--- bank {}", bank_id)));
+        println!("{}", ui::dim(&format!("+++ {}", path.display())));
+        ui::print_text_diff(&profile.mission, &local);
+    } else {
+        let lines = ui::text_diff(&profile.mission, &local);
+        let diff = BackgroundDiff {
+            bank_id: bank_id.to_string(),
+            file: path.display().to_string(),
+            changed: !lines.is_empty(),
+            diff: lines.iter().map(|line| format!("{}\n", line)).collect(),
+        };
+        output::print_output(&diff, output_format)?;
+    }
+    Ok(())
+}
+
+/// Save a bank's full profile (name, mission, disposition) to a JSON file
+pub fn profile_snapshot(
+    client: &ApiClient,
+    bank_id: &str,
+    out_path: Option<std::path::PathBuf>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let profile = client.get_profile(bank_id, verbose)?;
+
+    if let Some(path) = out_path {
+        let json = serde_json::to_string_pretty(&profile)?;
+        std::fs::write(&path, json)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        if output_format == OutputFormat::Pretty {
+            ui::print_success(&format!("Profile of '{}' saved to {}", bank_id, path.display()));
+        }
+    } else if output_format == OutputFormat::Pretty {
+        println!("{}", serde_json::to_string_pretty(&profile)?);
+    } else {
+        output::print_output(&profile, output_format)?;
+    }
+    Ok(())
+}
+
+/// A name that differs between the live bank and a snapshot
+#[derive(Debug, Serialize)]
+struct NameChange {
+    from: String,
+    to: String,
+}
+
+/// `bank profile restore`: what writing a snapshot back would change
+#[derive(Debug, Serialize)]
+struct ProfileDiff {
+    bank_id: String,
+    file: String,
+    changed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<NameChange>,
+    /// Unified diff from the live mission to the snapshot's
+    mission: String,
+    /// Live trait values as `a`, snapshot values as `b`
+    disposition: Vec<CountDiff>,
+}
+
+/// The mission a snapshot restores; snapshots from older servers may only
+/// carry the deprecated `background`
+fn snapshot_mission(profile: &BankProfileResponse) -> &str {
+    match profile.background.as_deref() {
+        Some(background) if profile.mission.is_empty() => background,
+        _ => &profile.mission,
+    }
+}
+
+fn profile_diff(
+    live: &BankProfileResponse,
+    snapshot: &BankProfileResponse,
+    file: &str,
+) -> ProfileDiff {
+    let name = (live.name != snapshot.name).then(|| NameChange {
+        from: live.name.clone(),
+        to: snapshot.name.clone(),
+    });
+    let mission: String = ui::text_diff(&live.mission, snapshot_mission(snapshot))
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let disposition = disposition_diff(&live.disposition, &snapshot.disposition);
+    ProfileDiff {
+        bank_id: live.bank_id.clone(),
+        file: file.to_string(),
+        changed: name.is_some()
+            || !mission.is_empty()
+            || disposition.iter().any(|d| d.delta != 0),
+        name,
+        mission,
+        disposition,
+    }
+}
+
+fn print_profile_diff(
+    diff: &ProfileDiff,
+    live: &BankProfileResponse,
+    snapshot: &BankProfileResponse,
+) {
+    println!("{}", ui::dim(&format!("--- bank {}", diff.bank_id)));
+    println!("{}", ui::dim(&format!("+++ {}", diff.file)));
+    println!();
+    if let Some(name) = &diff.name {
+        println!("  {:<16} {} → {}", "name", name.from, name.to);
+        println!();
+    }
+    if !diff.mission.is_empty() {
+        println!("{}", ui::gradient_text("─── Mission ───"));
+        ui::print_text_diff(&live.mission, snapshot_mission(snapshot));
+        println!();
+    }
+    print_disposition_diff(&diff.disposition);
+}
+
+/// Write a profile snapshot back to a bank after showing the changes and confirming.
+/// With `compare` only the differences between the snapshot and the live bank are shown.
+pub fn profile_restore(
+    client: &ApiClient,
+    bank_id: &str,
+    path: &std::path::Path,
+    compare: bool,
+    yes: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let snapshot: BankProfileResponse = serde_json::from_str(&raw)
+        .map_err(|e| anyhow!("Invalid profile snapshot in {}: {}", path.display(), e))?;
+    let live = client.get_profile(bank_id, verbose)?;
+    let diff = profile_diff(&live, &snapshot, &path.display().to_string());
+
+    if output_format == OutputFormat::Pretty {
+        if snapshot.bank_id != bank_id {
+            ui::print_info(&format!(
+                "Snapshot was taken from bank '{}', comparing with '{}'",
+                snapshot.bank_id, bank_id
+            ));
+        }
+        if !diff.changed {
+            ui::print_success(&format!("Bank '{}' already matches {}", bank_id, path.display()));
+            return Ok(());
+        }
+        print_profile_diff(&diff, &live, &snapshot);
+    }
+
+    if compare {
+        if output_format != OutputFormat::Pretty {
+            output::print_output(&diff, output_format)?;
+        }
+        return Ok(());
+    }
+    if !diff.changed {
+        return output::print_output(&live, output_format);
+    }
+
+    if !yes && output_format == OutputFormat::Pretty {
+        let confirmed =
+            ui::prompt_confirmation(&format!("Restore this profile to bank '{}'?", bank_id))?;
+        if !confirmed {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let response = executor::get().execute(PlannedWrite::new("update_profile", bank_id), || {
+        let mission = snapshot_mission(&snapshot);
+        client.update_profile(bank_id, &snapshot.name, mission, &snapshot.disposition, verbose)
+    });
+
+    match response? {
+        None => Ok(()),
+        Some(profile) => {
+            if output_format == OutputFormat::Pretty {
+                ui::print_success(&format!("Profile restored to bank '{}'", bank_id));
+                println!();
+                ui::print_disposition(&profile);
+                Ok(())
+            } else {
+                output::print_output(&profile, output_format)
+            }
+        }
+    }
+}
+
 /// Set bank mission
 pub fn mission(
     client: &ApiClient,
@@ -531,14 +1404,17 @@ pub fn delete(
         None
     };
 
-    let response = client.delete_bank(bank_id, verbose);
+    let response = executor::get().execute(PlannedWrite::new("delete_bank", bank_id), || {
+        client.delete_bank(bank_id, verbose)
+    });
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
     match response {
-        Ok(result) => {
+        Ok(None) => Ok(()),
+        Ok(Some(result)) => {
             if output_format == OutputFormat::Pretty {
                 if result.success {
                     ui::print_success(&format!("Bank '{}' deleted successfully", bank_id));
@@ -621,7 +1497,7 @@ pub fn consolidate(
                 std::thread::sleep(std::time::Duration::from_secs(poll_interval));
                 let elapsed = start.elapsed().as_secs();
 
-                let ops_result = client.list_operations(bank_id, verbose);
+                let ops_result = client.list_operations(bank_id, None, None, None, verbose);
                 match ops_result {
                     Ok(ops) => {
                         // Find the operation by ID
@@ -652,7 +1528,7 @@ pub fn consolidate(
                             }
                             Some(status) => {
                                 if output_format == OutputFormat::Pretty {
-                                    println!("  ⏳ {} ({}s elapsed)", status, elapsed);
+                                    println!("  {} {} ({}s elapsed)", ui::icon("⏳", "..."), status, elapsed);
                                 }
                             }
                             None => {
@@ -805,8 +1681,6 @@ pub fn set_config(
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    use std::collections::HashMap;
-
     let mut updates: HashMap<String, serde_json::Value> = HashMap::new();
 
     if let Some(provider) = llm_provider {
@@ -1019,6 +1893,164 @@ pub fn consolidation_recover(
     Ok(())
 }
 
+/// Everything retained in a bank, as written by `bank export`
+#[derive(Debug, Serialize)]
+struct BankArchive {
+    bank_id: String,
+    exported_at: String,
+    redacted: bool,
+    memories: Vec<serde_json::Map<String, serde_json::Value>>,
+    documents: Vec<serde_json::Map<String, serde_json::Value>>,
+    chunks: Vec<ChunkResponse>,
+    entities: Vec<EntityListItem>,
+}
+
+/// Memory fields that may carry names or other PII
+const REDACTED_MEMORY_FIELDS: &[&str] = &["text", "context", "entities"];
+
+/// Fetch memories, entities, and every document with its full content and chunks
+fn export_bank(client: &ApiClient, bank_id: &str, verbose: bool) -> Result<BankArchive> {
+    let memories =
+        fetch_matching_memories(client, bank_id, None, &MemoryFilter::default(), verbose)?;
+    // Kept as JSON so redaction can rewrite any field, including ones this CLI doesn't model
+    let memories = serde_json::from_value(serde_json::to_value(memories)?)?;
+    let entities = fetch_all_entities(client, bank_id, verbose)?;
+
+    let mut documents = Vec::new();
+    let mut chunks = Vec::new();
+    for listed in fetch_all_documents(client, bank_id, verbose)? {
+        let document_id = listed.id.as_str();
+        // Listings omit the original text, so fetch each document in full
+        let document = client.get_document(bank_id, document_id, verbose)?;
+        if let serde_json::Value::Object(map) = serde_json::to_value(&document)? {
+            documents.push(map);
+        }
+        chunks.extend(fetch_all_chunks(client, bank_id, document_id, verbose)?);
+    }
+
+    Ok(BankArchive {
+        bank_id: bank_id.to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+        redacted: false,
+        memories,
+        documents,
+        chunks,
+        entities,
+    })
+}
+
+/// Entity type recorded in metadata, used to pick the placeholder prefix
+fn entity_kind(entity: &EntityListItem) -> Option<String> {
+    let metadata = entity.metadata.as_ref()?;
+    ["type", "entity_type", "label"]
+        .iter()
+        .find_map(|key| metadata.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+}
+
+fn redact_archive(archive: &mut BankArchive, redactor: &mut Redactor) {
+    for memory in &mut archive.memories {
+        redactor.redact_fields(memory, REDACTED_MEMORY_FIELDS);
+    }
+    for document in &mut archive.documents {
+        redactor.redact_fields(document, &["original_text"]);
+    }
+    for chunk in &mut archive.chunks {
+        chunk.chunk_text = redactor.redact(&chunk.chunk_text);
+    }
+    for entity in &mut archive.entities {
+        entity.canonical_name = redactor.redact(&entity.canonical_name);
+        if let Some(metadata) = entity.metadata.as_mut() {
+            redactor.redact_fields(metadata, &["aliases"]);
+        }
+    }
+    archive.redacted = true;
+}
+
+/// Export a bank's memories, documents, chunks and entities as one JSON archive.
+///
+/// With `redact_entities` every entity name and alias is replaced by a placeholder
+/// such as `PERSON_1`; `redact_patterns` (`[LABEL=]REGEX`) do the same for custom
+/// patterns. `mapping_out` receives the placeholder table needed to reverse it.
+#[allow(clippy::too_many_arguments)]
+pub fn export(
+    client: &ApiClient,
+    bank_id: &str,
+    out_path: Option<std::path::PathBuf>,
+    redact_entities: bool,
+    redact_patterns: Vec<String>,
+    mapping_out: Option<std::path::PathBuf>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if mapping_out.is_some() && !redact_entities && redact_patterns.is_empty() {
+        anyhow::bail!("--mapping-out requires --redact entities or --redact-regex");
+    }
+    // Spinners and messages would corrupt an archive written to stdout
+    let show_progress = output_format == OutputFormat::Pretty && out_path.is_some();
+
+    let spinner = show_progress.then(|| ui::create_spinner("Exporting bank..."));
+    let response = export_bank(client, bank_id, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let mut archive = response?;
+
+    let mut redactor = None;
+    if redact_entities || !redact_patterns.is_empty() {
+        let terms: Vec<EntityTerm> = if redact_entities {
+            archive
+                .entities
+                .iter()
+                .map(|e| EntityTerm {
+                    canonical_name: e.canonical_name.clone(),
+                    aliases: entity_aliases(e.metadata.as_ref()),
+                    kind: entity_kind(e),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut r = Redactor::new(&terms, &redact_patterns)?;
+        redact_archive(&mut archive, &mut r);
+        redactor = Some(r);
+    }
+
+    let json = serde_json::to_string_pretty(&archive)?;
+    match &out_path {
+        Some(path) => std::fs::write(path, &json)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?,
+        None => println!("{}", json),
+    }
+
+    if let (Some(path), Some(redactor)) = (&mapping_out, &redactor) {
+        std::fs::write(path, serde_json::to_string_pretty(redactor.mapping())?)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    if let Some(path) = out_path.as_ref().filter(|_| show_progress) {
+        ui::print_success(&format!(
+            "Exported {} memories, {} documents and {} chunks to {}",
+            archive.memories.len(),
+            archive.documents.len(),
+            archive.chunks.len(),
+            path.display()
+        ));
+        if let Some(redactor) = &redactor {
+            println!(
+                "  {} {} placeholder(s){}",
+                ui::dim("Redacted:"),
+                redactor.mapping().len(),
+                mapping_out
+                    .as_ref()
+                    .map(|p| format!(", mapping written to {}", p.display()))
+                    .unwrap_or_default()
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Export a bank template manifest (bank config + mental models + directives)
 pub fn export_template(
     client: &ApiClient,
@@ -1137,3 +2169,66 @@ pub fn template_schema(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_counts_union_of_keys() {
+        let a = HashMap::from([("world".to_string(), 10), ("opinion".to_string(), 2)]);
+        let b = HashMap::from([("world".to_string(), 12), ("experience".to_string(), 5)]);
+        let diffs = diff_counts(&a, &b);
+        let keys: Vec<&str> = diffs.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, vec!["experience", "opinion", "world"]);
+        assert_eq!(diffs[0].delta, 5);
+        assert_eq!(diffs[1].delta, -2);
+        assert_eq!(diffs[2].delta, 2);
+    }
+
+    #[test]
+    fn test_jaccard() {
+        let a: BTreeSet<&str> = ["m1", "m2", "m3"].into_iter().collect();
+        let b: BTreeSet<&str> = ["m2", "m3", "m4"].into_iter().collect();
+        assert!((jaccard(&a, &b) - 0.5).abs() < f64::EPSILON);
+        assert_eq!(jaccard(&BTreeSet::<&str>::new(), &BTreeSet::new()), 1.0);
+    }
+
+    #[test]
+    fn test_profile_diff_reports_changed_fields() {
+        let traits = |s, l, e| DispositionTraits {
+            skepticism: std::num::NonZeroU64::new(s).unwrap(),
+            literalism: std::num::NonZeroU64::new(l).unwrap(),
+            empathy: std::num::NonZeroU64::new(e).unwrap(),
+        };
+        let live = BankProfileResponse {
+            bank_id: "alice".to_string(),
+            name: "Alice".to_string(),
+            mission: "Helps the team ship".to_string(),
+            background: None,
+            disposition: traits(3, 3, 3),
+        };
+        let snapshot = BankProfileResponse {
+            name: "Alice v1".to_string(),
+            mission: String::new(),
+            background: Some("Helps the team ship".to_string()),
+            disposition: traits(4, 3, 2),
+            ..live.clone()
+        };
+
+        let diff = profile_diff(&live, &snapshot, "profile.json");
+        assert!(diff.changed);
+        assert_eq!(diff.name.as_ref().map(|n| n.to.as_str()), Some("Alice v1"));
+        assert!(diff.mission.is_empty(), "background stands in for an empty mission");
+        let deltas: Vec<i64> = diff.disposition.iter().map(|d| d.delta).collect();
+        assert_eq!(deltas, vec![1, 0, -1]);
+        assert!(!profile_diff(&live, &live, "profile.json").changed);
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(1134, 1000), Some("+134".to_string()));
+        assert_eq!(format_delta(7, 10), Some("-3".to_string()));
+        assert_eq!(format_delta(5, 5), None);
+    }
+}
@@ -0,0 +1,306 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::api::{ApiClient, HedgeStats};
+use crate::commands::memory::build_recall_request;
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+/// A single timed recall request
+#[derive(Debug, Clone, Serialize)]
+struct Sample {
+    budget: String,
+    query: String,
+    iteration: usize,
+    latency_ms: f64,
+    results: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Latency and result-count statistics over a group of samples
+#[derive(Debug, Serialize)]
+struct LatencyStats {
+    budget: String,
+    /// `None` for the aggregate over every query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    requests: usize,
+    errors: usize,
+    min_ms: f64,
+    p50_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+    mean_results: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    samples: Vec<Sample>,
+    summary: Vec<LatencyStats>,
+    /// Only with `--hedge-after`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hedges: Option<HedgeStats>,
+}
+
+/// Read one query per line, skipping blank lines and `#` comments
+fn read_queries(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read query file {}", path.display()))?;
+    let queries: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    if queries.is_empty() {
+        anyhow::bail!("No queries found in {}", path.display());
+    }
+    Ok(queries)
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn latency_stats(budget: &str, query: Option<&str>, samples: &[&Sample]) -> LatencyStats {
+    let ok: Vec<&&Sample> = samples.iter().filter(|s| s.error.is_none()).collect();
+    let mut latencies: Vec<f64> = ok.iter().map(|s| s.latency_ms).collect();
+    latencies.sort_by(|a, b| a.total_cmp(b));
+    let mean_results = if ok.is_empty() {
+        0.0
+    } else {
+        ok.iter().map(|s| s.results as f64).sum::<f64>() / ok.len() as f64
+    };
+    LatencyStats {
+        budget: budget.to_string(),
+        query: query.map(str::to_string),
+        requests: samples.len(),
+        errors: samples.len() - ok.len(),
+        min_ms: latencies.first().copied().unwrap_or(0.0),
+        p50_ms: percentile(&latencies, 50.0),
+        p95_ms: percentile(&latencies, 95.0),
+        max_ms: latencies.last().copied().unwrap_or(0.0),
+        mean_results,
+    }
+}
+
+/// Per-query statistics followed by one aggregate row, for each budget in order
+fn summarize(samples: &[Sample], budgets: &[String], queries: &[String]) -> Vec<LatencyStats> {
+    let mut summary = Vec::new();
+    for budget in budgets {
+        let for_budget: Vec<&Sample> = samples.iter().filter(|s| &s.budget == budget).collect();
+        for query in queries {
+            let group: Vec<&Sample> = for_budget.iter().copied().filter(|s| &s.query == query).collect();
+            summary.push(latency_stats(budget, Some(query), &group));
+        }
+        summary.push(latency_stats(budget, None, &for_budget));
+    }
+    summary
+}
+
+fn print_stats_row(label: &str, stats: &LatencyStats) {
+    println!(
+        "  {:<32} {:>5} {:>9.1} {:>9.1} {:>9.1} {:>9.1} {:>8.1} {:>6}",
+        output::truncate_to_width(label, 32),
+        stats.requests,
+        stats.min_ms,
+        stats.p50_ms,
+        stats.p95_ms,
+        stats.max_ms,
+        stats.mean_results,
+        stats.errors
+    );
+}
+
+fn print_stats_header(first: &str) {
+    println!(
+        "  {}",
+        ui::dim(&format!(
+            "{:<32} {:>5} {:>9} {:>9} {:>9} {:>9} {:>8} {:>6}",
+            first, "n", "min ms", "p50 ms", "p95 ms", "max ms", "results", "errors"
+        ))
+    );
+}
+
+/// Benchmark recall latency over a file of queries
+#[allow(clippy::too_many_arguments)]
+pub fn recall(
+    client: &ApiClient,
+    bank_id: &str,
+    query_file: &Path,
+    budget: String,
+    compare_budgets: Vec<String>,
+    iterations: usize,
+    concurrency: usize,
+    max_tokens: i64,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let queries = read_queries(query_file)?;
+    let budgets = if compare_budgets.is_empty() {
+        vec![budget]
+    } else {
+        compare_budgets
+    };
+    let iterations = iterations.max(1);
+
+    let jobs: Vec<(String, String, usize)> = budgets
+        .iter()
+        .flat_map(|b| {
+            queries
+                .iter()
+                .flat_map(move |q| (0..iterations).map(move |i| (b.clone(), q.clone(), i)))
+        })
+        .collect();
+
+    let pb = if output_format == OutputFormat::Pretty {
+        ui::create_progress_bar(jobs.len() as u64, "Benchmarking recall")
+    } else {
+        indicatif::ProgressBar::hidden()
+    };
+
+    let pending = Mutex::new(jobs.into_iter());
+    let samples = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.max(1) {
+            scope.spawn(|| loop {
+                let Some((budget, query, iteration)) = pending.lock().unwrap().next() else {
+                    break;
+                };
+                let request = build_recall_request(
+                    query.clone(),
+                    Vec::new(),
+                    &budget,
+                    max_tokens,
+                    false,
+                    false,
+                    0,
+                    Vec::new(),
+                    None,
+                    None,
+                );
+                let started = Instant::now();
+                let outcome = client.recall(bank_id, &request, verbose);
+                let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+                let (results, error) = match outcome {
+                    Ok(response) => (response.results.len(), None),
+                    Err(e) => (0, Some(format!("{:#}", e))),
+                };
+                samples.lock().unwrap().push(Sample {
+                    budget,
+                    query,
+                    iteration,
+                    latency_ms,
+                    results,
+                    error,
+                });
+                pb.inc(1);
+            });
+        }
+    });
+
+    pb.finish_and_clear();
+    let mut samples = samples.into_inner().unwrap();
+    samples.sort_by(|a, b| {
+        (&a.budget, &a.query, a.iteration).cmp(&(&b.budget, &b.query, b.iteration))
+    });
+    let summary = summarize(&samples, &budgets, &queries);
+
+    if output_format != OutputFormat::Pretty {
+        let hedges = client.hedge_stats();
+        return output::print_output(&BenchReport { samples, summary, hedges }, output_format);
+    }
+
+    for budget in &budgets {
+        ui::print_section_header(&format!(
+            "Recall Benchmark: {} (budget {}, {} iterations)",
+            bank_id, budget, iterations
+        ));
+        print_stats_header("query");
+        for stats in summary.iter().filter(|s| &s.budget == budget) {
+            match &stats.query {
+                Some(query) => print_stats_row(query, stats),
+                None => {
+                    println!();
+                    print_stats_row("all queries", stats);
+                }
+            }
+        }
+    }
+
+    if budgets.len() > 1 {
+        ui::print_section_header("Budget Comparison");
+        print_stats_header("budget");
+        for stats in summary.iter().filter(|s| s.query.is_none()) {
+            print_stats_row(&stats.budget, stats);
+        }
+    }
+    println!();
+
+    if let Some(hedges) = client.hedge_stats() {
+        println!(
+            "  {} {} sent, {} answered first",
+            ui::dim("Hedged requests:"),
+            hedges.issued,
+            hedges.won
+        );
+        println!();
+    }
+
+    let errors = samples.iter().filter(|s| s.error.is_some()).count();
+    if errors > 0 {
+        let first = samples.iter().find_map(|s| s.error.as_deref()).unwrap_or_default();
+        ui::print_warning(&format!("{} request(s) failed; first error: {}", errors, first));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<f64> = (1..=20).map(|v| v as f64).collect();
+        assert_eq!(percentile(&values, 50.0), 10.0);
+        assert_eq!(percentile(&values, 95.0), 19.0);
+        assert_eq!(percentile(&values, 100.0), 20.0);
+        assert_eq!(percentile(&[42.0], 95.0), 42.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn test_summarize_excludes_errors_from_latency() {
+        let sample = |query: &str, latency_ms: f64, error: Option<&str>| Sample {
+            budget: "mid".to_string(),
+            query: query.to_string(),
+            iteration: 0,
+            latency_ms,
+            results: 3,
+            error: error.map(str::to_string),
+        };
+        let samples = vec![
+            sample("a", 10.0, None),
+            sample("a", 30.0, None),
+            sample("b", 500.0, Some("timeout")),
+        ];
+        let summary = summarize(&samples, &["mid".to_string()], &["a".to_string(), "b".to_string()]);
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].max_ms, 30.0);
+        assert_eq!(summary[1].errors, 1);
+        let aggregate = &summary[2];
+        assert!(aggregate.query.is_none());
+        assert_eq!(aggregate.requests, 3);
+        assert_eq!(aggregate.max_ms, 30.0);
+    }
+}
@@ -0,0 +1,274 @@
+use anyhow::{Context, Result};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::ApiClient;
+use crate::commands::memory;
+use crate::output::OutputFormat;
+use crate::ui;
+use crate::utils::estimate_tokens;
+
+const CHAT_HELP: &str = "\
+  <message>          ask the bank
+  :reset             forget the conversation so far
+  :save <file>       write the transcript as markdown
+  :quit              exit (or Ctrl+D)";
+
+#[derive(Debug, Clone, PartialEq)]
+struct Turn {
+    role: &'static str,
+    content: String,
+}
+
+impl Turn {
+    fn line(&self) -> String {
+        format!("{}: {}", self.role, self.content.trim())
+    }
+}
+
+/// Chat history sent to reflect as context, trimmed to a token budget
+#[derive(Debug)]
+struct Conversation {
+    system: Option<String>,
+    turns: Vec<Turn>,
+    context_tokens: usize,
+}
+
+impl Conversation {
+    fn new(system: Option<String>, context_tokens: usize) -> Self {
+        Self {
+            system,
+            turns: Vec::new(),
+            context_tokens,
+        }
+    }
+
+    fn push(&mut self, role: &'static str, content: &str) {
+        self.turns.push(Turn {
+            role,
+            content: content.to_string(),
+        });
+    }
+
+    fn reset(&mut self) {
+        self.turns.clear();
+    }
+
+    /// Context for the next reflect: the system instruction followed by as many of the
+    /// most recent turns as fit in the budget. The system instruction is never dropped.
+    fn context(&self) -> Option<String> {
+        let mut budget = self.context_tokens;
+        if let Some(system) = &self.system {
+            budget = budget.saturating_sub(estimate_tokens(system));
+        }
+
+        let mut recent = Vec::new();
+        for turn in self.turns.iter().rev() {
+            let line = turn.line();
+            let cost = estimate_tokens(&line);
+            if cost > budget {
+                break;
+            }
+            budget -= cost;
+            recent.push(line);
+        }
+        recent.reverse();
+
+        let mut sections = Vec::new();
+        if let Some(system) = &self.system {
+            sections.push(system.trim().to_string());
+        }
+        if !recent.is_empty() {
+            sections.push(recent.join("\n"));
+        }
+        if sections.is_empty() {
+            None
+        } else {
+            Some(sections.join("\n\n"))
+        }
+    }
+
+    /// The full, untrimmed transcript as markdown
+    fn to_markdown(&self, bank_id: &str) -> String {
+        let mut out = format!("# Chat with {}\n\n", bank_id);
+        if let Some(system) = &self.system {
+            out.push_str(&format!("> {}\n\n", system.trim().replace('\n', "\n> ")));
+        }
+        for turn in &self.turns {
+            out.push_str(&format!("**{}:** {}\n\n", turn.role, turn.content.trim()));
+        }
+        out
+    }
+}
+
+fn save_transcript(conversation: &Conversation, bank_id: &str, path: &Path) -> Result<()> {
+    fs::write(path, conversation.to_markdown(bank_id))
+        .with_context(|| format!("Failed to write transcript to {}", path.display()))
+}
+
+/// Ask one question with the current conversation as context and record both turns
+#[allow(clippy::too_many_arguments)]
+fn ask(
+    client: &ApiClient,
+    bank_id: &str,
+    conversation: &mut Conversation,
+    message: &str,
+    budget: &str,
+    max_tokens: Option<i64>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let request = memory::build_reflect_request(
+        message.to_string(),
+        budget,
+        conversation.context(),
+        max_tokens,
+        None,
+        Vec::new(),
+        None,
+        &[],
+        None,
+        false,
+        None,
+    )?;
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Thinking..."))
+    } else {
+        None
+    };
+    let response = client.reflect(bank_id, &request, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let result = response?;
+
+    println!("{}", result.text.trim());
+    if output_format == OutputFormat::Pretty {
+        if let Some(based_on) = result.based_on.as_ref().filter(|b| !b.memories.is_empty()) {
+            println!("{}", ui::dim(&format!("Based on {} memory units", based_on.memories.len())));
+        }
+    }
+    println!();
+
+    conversation.push("user", message);
+    conversation.push("assistant", &result.text);
+    Ok(())
+}
+
+/// Conversational loop over reflect for `hindsight chat <bank>`
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    client: &ApiClient,
+    bank_id: &str,
+    budget: String,
+    max_tokens: Option<i64>,
+    system_file: Option<PathBuf>,
+    context_tokens: usize,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let system = system_file
+        .map(|path| {
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read system file: {}", path.display()))
+        })
+        .transpose()?;
+    let mut conversation = Conversation::new(system, context_tokens);
+    let mut editor = DefaultEditor::new()?;
+
+    ui::print_info(&format!(
+        "Chatting with bank '{}'. Type :help for commands, Ctrl+D to exit.",
+        bank_id
+    ));
+
+    loop {
+        let line = match editor.readline("you> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        match line.split_once(' ').unwrap_or((line, "")) {
+            (":quit" | ":q" | ":exit", _) => break,
+            (":help" | ":h", _) => println!("{}", CHAT_HELP),
+            (":reset", _) => {
+                conversation.reset();
+                ui::print_info("Conversation cleared");
+            }
+            (":save", path) if !path.trim().is_empty() => {
+                let path = PathBuf::from(path.trim());
+                match save_transcript(&conversation, bank_id, &path) {
+                    Ok(()) => ui::print_success(&format!("Saved transcript to {}", path.display())),
+                    Err(e) => ui::print_error(&format!("{:#}", e)),
+                }
+            }
+            (":save", _) => ui::print_error("Usage: :save <file>"),
+            (command, _) if command.starts_with(':') => {
+                ui::print_error(&format!("Unknown command '{}' (try :help)", command))
+            }
+            _ => {
+                if let Err(e) = ask(
+                    client,
+                    bank_id,
+                    &mut conversation,
+                    line,
+                    &budget,
+                    max_tokens,
+                    verbose,
+                    output_format,
+                ) {
+                    ui::print_error(&format!("{:#}", e));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_keeps_recent_turns_within_budget() {
+        let mut conversation = Conversation::new(None, 10);
+        conversation.push("user", "first question here");
+        conversation.push("assistant", "first answer");
+        conversation.push("user", "second");
+        // "assistant: first answer" (6) + "user: second" (3) fit; the first turn does not
+        assert_eq!(
+            conversation.context().unwrap(),
+            "assistant: first answer\nuser: second"
+        );
+
+        conversation.reset();
+        assert_eq!(conversation.context(), None);
+    }
+
+    #[test]
+    fn test_context_always_keeps_system_instruction() {
+        let mut conversation = Conversation::new(Some("Answer briefly.\n".to_string()), 4);
+        conversation.push("user", "a long message that will not fit");
+        assert_eq!(conversation.context().unwrap(), "Answer briefly.");
+    }
+
+    #[test]
+    fn test_transcript_markdown_is_untrimmed() {
+        let mut conversation = Conversation::new(None, 1);
+        conversation.push("user", "Hi");
+        conversation.push("assistant", "Hello");
+        assert_eq!(
+            conversation.to_markdown("alice"),
+            "# Chat with alice\n\n**user:** Hi\n\n**assistant:** Hello\n\n"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! `hindsight config`: show, read and change the CLI's settings.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::config::{self, Config, EnvSettings, SettingKind};
+use crate::credentials;
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+/// Settings given as global flags, which rank above everything else
+#[derive(Debug, Default)]
+pub struct FlagValues {
+    /// `-p/--profile`, or `$HINDSIGHT_PROFILE` (clap reads both into one value)
+    pub profile: Option<String>,
+    pub output: Option<clap::builder::PossibleValue>,
+    pub timeout: Option<u64>,
+    pub dates: Option<clap::builder::PossibleValue>,
+}
+
+/// Where a resolved value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Source {
+    Flag,
+    Env,
+    Keyring,
+    Profile,
+    ConfigFile,
+    Default,
+}
+
+impl Source {
+    fn label(self) -> &'static str {
+        match self {
+            Source::Flag => "flag",
+            Source::Env => "env",
+            Source::Keyring => "keyring",
+            Source::Profile => "profile",
+            Source::ConfigFile => "config file",
+            Source::Default => "default",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ResolvedSetting {
+    key: &'static str,
+    /// `None` when the key is set nowhere and has no default
+    value: Option<String>,
+    source: Source,
+}
+
+/// Keys `show` and `get` know besides the settable ones
+const RUNTIME_KEYS: [&str; 3] = ["profile", "timeout", "output"];
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Every setting with its value and source, in a fixed order
+fn resolve(flags: &FlagValues) -> Result<(Option<String>, Vec<ResolvedSetting>)> {
+    let env = EnvSettings::load()?;
+    let mut settings = Vec::new();
+
+    let (profile, profile_source) = match &flags.profile {
+        Some(name) if env_value("HINDSIGHT_PROFILE").as_deref() == Some(name) => {
+            (Some(name.clone()), Source::Env)
+        }
+        Some(name) => (Some(name.clone()), Source::Flag),
+        None => match Config::current_profile() {
+            Some(name) => (Some(name), Source::ConfigFile),
+            None => (None, Source::Default),
+        },
+    };
+    let value = profile.clone();
+    settings.push(ResolvedSetting { key: "profile", value, source: profile_source });
+    let file_source = if profile.is_some() { Source::Profile } else { Source::ConfigFile };
+    let from_file = |key: &'static str| {
+        Config::file_setting(profile.as_deref(), key).map(|value| (value, file_source))
+    };
+
+    for key in config::SETTING_KEYS {
+        let found = match key.name {
+            "api_url" => env
+                .api_url
+                .clone()
+                .map(|v| (v, Source::Env))
+                .or_else(|| from_file(key.name)),
+            "api_key" => env
+                .api_key
+                .clone()
+                .map(|v| (v, Source::Env))
+                .or_else(|| {
+                    let account = credentials::account_name(profile.as_deref());
+                    let stored = credentials::load(account).ok().flatten();
+                    stored.map(|(key, _)| (key, Source::Keyring))
+                })
+                .or_else(|| from_file(key.name)),
+            "default_bank" => env
+                .default_bank
+                .clone()
+                .map(|v| (v, Source::Env))
+                .or_else(|| from_file(key.name)),
+            "dates" => flags
+                .dates
+                .as_ref()
+                .map(|v| (v.get_name().to_string(), Source::Flag))
+                .or_else(|| from_file(key.name)),
+            _ => from_file(key.name),
+        };
+        let (value, source) = match found {
+            Some((value, source)) => (Some(value), source),
+            None => (key.default.map(str::to_string), Source::Default),
+        };
+        let value = match key.kind {
+            SettingKind::Secret => value.map(|v| crate::mask_api_key(&v)),
+            _ => value,
+        };
+        settings.push(ResolvedSetting { key: key.name, value, source });
+    }
+
+    let (timeout, source) = match (flags.timeout, env.timeout) {
+        (Some(secs), _) => (secs, Source::Flag),
+        (None, Some(timeout)) => (timeout.as_secs(), Source::Env),
+        (None, None) => (crate::api::DEFAULT_TIMEOUT.as_secs(), Source::Default),
+    };
+    settings.push(ResolvedSetting { key: "timeout", value: Some(timeout.to_string()), source });
+
+    let (output, source) = match (&flags.output, env_value("HINDSIGHT_OUTPUT")) {
+        (Some(format), _) => (format.get_name().to_string(), Source::Flag),
+        (None, Some(format)) => (format, Source::Env),
+        (None, None) => ("pretty".to_string(), Source::Default),
+    };
+    settings.push(ResolvedSetting { key: "output", value: Some(output), source });
+
+    Ok((profile, settings))
+}
+
+/// Show every setting as resolved, with where it came from; secrets are masked
+pub fn show(flags: &FlagValues, output_format: OutputFormat) -> Result<()> {
+    let (profile, settings) = resolve(flags)?;
+    let path = Config::settings_path(profile.as_deref())?;
+
+    if output_format != OutputFormat::Pretty {
+        let report = json!({
+            "profile": profile,
+            "path": path.display().to_string(),
+            "settings": settings,
+        });
+        return output::print_output(&report, output_format);
+    }
+
+    ui::print_section_header("Configuration");
+    println!("  {} {}", ui::dim("File:"), path.display());
+    println!();
+    let width = settings.iter().map(|s| s.key.len()).max().unwrap_or(0);
+    for setting in &settings {
+        let value = setting.value.as_deref().unwrap_or("(not set)");
+        println!(
+            "  {:width$}  {}  {}",
+            setting.key,
+            value,
+            ui::dim(&format!("({})", setting.source.label())),
+            width = width
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// Print one resolved setting
+pub fn get(flags: &FlagValues, key: &str, output_format: OutputFormat) -> Result<()> {
+    if !RUNTIME_KEYS.contains(&key) {
+        config::setting_key(key)?;
+    }
+    let (_, settings) = resolve(flags)?;
+    let setting = settings
+        .into_iter()
+        .find(|setting| setting.key == key)
+        .expect("every known key is resolved");
+
+    if output_format == OutputFormat::Pretty {
+        match &setting.value {
+            Some(value) => println!("{}", value),
+            None => ui::print_info(&format!("{} is not set", key)),
+        }
+        Ok(())
+    } else {
+        output::print_output(&setting, output_format)
+    }
+}
+
+/// Validate and write one setting to the active profile or the local config file
+pub fn set(
+    profile: Option<&str>,
+    key: &str,
+    value: &str,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let path = Config::set_setting(profile, key, value)?;
+    let secret = config::setting_key(key)?.kind == SettingKind::Secret;
+    let shown = if secret { crate::mask_api_key(value.trim()) } else { value.trim().to_string() };
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Set {} = {} in {}", key, shown, path.display()));
+    } else {
+        let result = json!({ "key": key, "value": shown, "path": path.display().to_string() });
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
+/// Make a profile the default for later commands, or clear the choice
+pub fn use_context(profile: Option<&str>, output_format: OutputFormat) -> Result<()> {
+    let path = Config::use_profile(profile)?;
+
+    if output_format == OutputFormat::Pretty {
+        match profile {
+            Some(name) => ui::print_success(&format!("Now using profile '{}'", name)),
+            None => ui::print_success("Cleared the default profile"),
+        }
+        if env_value("HINDSIGHT_PROFILE").is_some() {
+            ui::print_warning("HINDSIGHT_PROFILE is set and takes precedence in this shell");
+        }
+    } else {
+        let result = json!({ "profile": profile, "path": path.display().to_string() });
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
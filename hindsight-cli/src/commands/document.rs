@@ -1,9 +1,18 @@
-use crate::api::ApiClient;
+use crate::api::{ApiClient, ReprocessOptions};
+use crate::commands::memory::{content_type_for, is_supported_file};
+use crate::errors;
+use crate::executor::{self, PlannedWrite};
 use crate::output::{self, OutputFormat};
 use crate::ui;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
-use std::collections::BTreeMap;
+use hindsight_client::types::{ChunkData, ChunkResponse, DocumentListItem};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use walkdir::WalkDir;
 
 pub fn list(
     client: &ApiClient,
@@ -40,32 +49,32 @@ pub fn list(
 
     match response {
         Ok(docs_response) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_info(&format!(
-                    "Documents for bank '{}' (total: {})",
-                    agent_id, docs_response.total
-                ));
-                for doc in &docs_response.items {
-                    let id = doc.get("id").and_then(|v| v.as_str()).unwrap_or("unknown");
-                    let created = doc
-                        .get("created_at")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-                    let updated = doc
-                        .get("updated_at")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-                    let text_len = doc.get("text_length").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let mem_count = doc
-                        .get("memory_unit_count")
-                        .and_then(|v| v.as_i64())
-                        .unwrap_or(0);
-
-                    println!("\n  Document ID: {}", id);
-                    println!("    Created: {}", created);
-                    println!("    Updated: {}", updated);
-                    println!("    Text Length: {}", text_len);
-                    println!("    Memory Units: {}", mem_count);
+            if output_format == OutputFormat::Pretty || output_format == OutputFormat::Table {
+                let rows: Vec<serde_json::Value> =
+                    docs_response.items.iter().map(document_row).collect();
+                if output_format == OutputFormat::Pretty {
+                    ui::print_info(&format!(
+                        "Documents for bank '{}' (total: {})",
+                        agent_id, docs_response.total
+                    ));
+                }
+                output::print_table(&rows, DOCUMENT_COLUMNS)?;
+                if output_format == OutputFormat::Pretty {
+                    let shown = docs_response.items.len() as i64;
+                    let next = offset as i64 + shown;
+                    println!();
+                    if shown > 0 {
+                        println!(
+                            "  {} {}-{} of {}",
+                            ui::dim("Showing"),
+                            offset as i64 + 1,
+                            next,
+                            docs_response.total
+                        );
+                    }
+                    if next < docs_response.total {
+                        println!("  {} --offset {}", ui::dim("Next page:"), next);
+                    }
                 }
             } else {
                 output::print_output(&docs_response, output_format)?;
@@ -76,6 +85,57 @@ pub fn list(
     }
 }
 
+/// Default columns for `document list` table output
+const DOCUMENT_COLUMNS: &[&str] = &["id", "content_type", "size", "created", "memories"];
+
+/// Content type recorded for a listed document, if the retain call carried one
+pub(crate) fn document_content_type(doc: &DocumentListItem) -> Option<String> {
+    recorded_content_type(doc.document_metadata.as_ref(), doc.retain_params.as_ref())
+}
+
+/// Content type in a document's metadata or in the parameters of its retain call
+pub(crate) fn recorded_content_type(
+    document_metadata: Option<&serde_json::Map<String, serde_json::Value>>,
+    retain_params: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Option<String> {
+    [document_metadata, retain_params]
+        .into_iter()
+        .flatten()
+        .find_map(|m| {
+            m.get("content_type")
+                .or_else(|| m.get("file_content_type"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        })
+}
+
+/// Human-readable size for a character count (documents report text length)
+pub(crate) fn format_size(len: i64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if len < 1024 {
+        return format!("{} B", len);
+    }
+    let mut size = len as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Flatten a document list item into the columns shown by table output
+fn document_row(doc: &DocumentListItem) -> serde_json::Value {
+    serde_json::json!({
+        "id": doc.id,
+        "content_type": document_content_type(doc).unwrap_or_else(|| "-".to_string()),
+        "size": format_size(doc.text_length.unwrap_or(0)),
+        "created": doc.created_at.as_deref().unwrap_or(""),
+        "memories": doc.memory_unit_count.unwrap_or(0),
+        "tags": doc.tags.clone().unwrap_or_default(),
+    })
+}
+
 /// List documents with date filtering
 fn list_with_date(
     client: &ApiClient,
@@ -101,14 +161,13 @@ fn list_with_date(
     let target_date = parse_date_filter(date_filter)?;
 
     // Filter and group documents by date
-    let mut by_date: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+    let mut by_date: BTreeMap<String, Vec<DocumentListItem>> = BTreeMap::new();
     let mut filtered_count = 0;
 
     for doc in all_docs {
-        let created_at = doc.get("created_at").and_then(|v| v.as_str()).unwrap_or("");
-
         // Parse the date part (YYYY-MM-DD) from created_at
-        let doc_date = created_at.split('T').next().unwrap_or("");
+        let created_at = doc.created_at.as_deref().unwrap_or("");
+        let doc_date = created_at.split('T').next().unwrap_or("").to_string();
 
         // Apply date filter if specified
         if let Some(ref target) = target_date {
@@ -119,7 +178,7 @@ fn list_with_date(
         }
 
         filtered_count += 1;
-        by_date.entry(doc_date.to_string()).or_default().push(doc);
+        by_date.entry(doc_date).or_default().push(doc);
     }
 
     // Output
@@ -141,18 +200,14 @@ fn list_with_date(
         for (date_str, docs) in by_date.iter().rev() {
             println!("  {} ({} documents)", date_str, docs.len());
             for doc in docs {
-                let id = doc.get("id").and_then(|v| v.as_str()).unwrap_or("unknown");
-                let mem_count = doc
-                    .get("memory_unit_count")
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0);
-                println!("    - {} ({} memories)", id, mem_count);
+                let mem_count = doc.memory_unit_count.unwrap_or(0);
+                println!("    - {} ({} memories)", doc.id, mem_count);
             }
             println!();
         }
     } else {
         // JSON/YAML output - convert to a list structure
-        let output: Vec<serde_json::Value> = by_date.values().flatten().cloned().collect();
+        let output: Vec<&DocumentListItem> = by_date.values().flatten().collect();
         output::print_output(&output, output_format)?;
     }
 
@@ -160,11 +215,11 @@ fn list_with_date(
 }
 
 /// Fetch all documents with pagination
-fn fetch_all_documents(
+pub(crate) fn fetch_all_documents(
     client: &ApiClient,
     bank_id: &str,
     verbose: bool,
-) -> Result<Vec<serde_json::Value>> {
+) -> Result<Vec<DocumentListItem>> {
     let mut all_docs = Vec::new();
     let mut offset = 0;
     let limit = 500;
@@ -176,10 +231,7 @@ fn fetch_all_documents(
             break;
         }
 
-        // Convert Map<String, Value> to Value for each item
-        for item in response.items {
-            all_docs.push(serde_json::Value::Object(item));
-        }
+        all_docs.extend(response.items);
 
         offset += limit;
 
@@ -210,10 +262,44 @@ fn parse_date_filter(filter: Option<&str>) -> Result<Option<NaiveDate>> {
     }
 }
 
+/// Page size used when fetching every chunk of a document
+const CHUNK_PAGE_SIZE: u64 = 500;
+
+/// Fetch every chunk of a document, in chunk order
+pub(crate) fn fetch_all_chunks(
+    client: &ApiClient,
+    bank_id: &str,
+    document_id: &str,
+    verbose: bool,
+) -> Result<Vec<ChunkResponse>> {
+    let mut chunks = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = client.list_document_chunks(
+            bank_id,
+            document_id,
+            Some(CHUNK_PAGE_SIZE),
+            Some(offset),
+            verbose,
+        )?;
+        let fetched = page.items.len() as u64;
+        chunks.extend(page.items);
+        offset += fetched;
+        if fetched < CHUNK_PAGE_SIZE || offset as i64 >= page.total {
+            break;
+        }
+    }
+    chunks.sort_by_key(|c| c.chunk_index);
+    Ok(chunks)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn get(
     client: &ApiClient,
     agent_id: &str,
     document_id: &str,
+    chunks: bool,
+    out: Option<PathBuf>,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
@@ -223,64 +309,200 @@ pub fn get(
         None
     };
 
-    let response = client.get_document(agent_id, document_id, verbose);
+    let response = client.get_document(agent_id, document_id, verbose).and_then(|doc| {
+        let doc_chunks = if chunks {
+            fetch_all_chunks(client, agent_id, document_id, verbose)?
+        } else {
+            Vec::new()
+        };
+        Ok((doc, doc_chunks))
+    });
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
-    match response {
-        Ok(doc) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_info(&format!("Document: {}", doc.id));
-                println!("  Bank ID: {}", doc.bank_id);
-                println!("  Created: {}", doc.created_at);
-                println!("  Updated: {}", doc.updated_at);
-                println!("  Memory Units: {}", doc.memory_unit_count);
-                println!("\n  Text:\n{}", doc.original_text);
-            } else {
-                output::print_output(&doc, output_format)?;
+    let (doc, doc_chunks) = match response {
+        Ok(result) => result,
+        Err(e) if errors::is_not_found(&e) => {
+            ui::print_error(&format!("Document '{}' not found in bank '{}'", document_id, agent_id));
+            std::process::exit(errors::EXIT_NOT_FOUND);
+        }
+        Err(e) => return Err(e),
+    };
+
+    // With --out the original content goes to the file and stdout only gets a summary
+    if let Some(path) = &out {
+        std::fs::write(path, &doc.original_text)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        if output_format == OutputFormat::Pretty {
+            ui::print_success(&format!(
+                "Saved document '{}' to {} ({} bytes)",
+                doc.id,
+                path.display(),
+                doc.original_text.len()
+            ));
+        }
+    }
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_info(&format!("Document: {}", doc.id));
+        println!("  Bank ID: {}", doc.bank_id);
+        println!("  Created: {}", doc.created_at);
+        println!("  Updated: {}", doc.updated_at);
+        println!("  Memory Units: {}", doc.memory_unit_count);
+        if out.is_none() {
+            println!("\n  Text:\n{}", doc.original_text);
+        }
+        if chunks {
+            ui::print_section_header(&format!("Chunks ({})", doc_chunks.len()));
+            for chunk in &doc_chunks {
+                ui::print_chunk(&ChunkData {
+                    id: chunk.chunk_id.clone(),
+                    text: chunk.chunk_text.clone(),
+                    chunk_index: chunk.chunk_index,
+                    truncated: false,
+                });
             }
-            Ok(())
         }
-        Err(e) => Err(e),
+    } else if chunks {
+        let value = serde_json::json!({ "document": doc, "chunks": doc_chunks });
+        output::print_output(&value, output_format)?;
+    } else {
+        output::print_output(&doc, output_format)?;
     }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct DeletedDocument {
+    document_id: String,
+    memory_units_deleted: i64,
 }
 
+#[derive(Debug, Serialize)]
+struct DeleteSummary {
+    deleted: Vec<DeletedDocument>,
+    not_found: Vec<String>,
+}
+
+/// Delete one or more documents.
+///
+/// The server always removes a document's derived memories along with it, so
+/// documents that still have memories are only deleted with `--with-memories`.
+/// Missing documents are reported and exit with `EXIT_NOT_FOUND`.
 pub fn delete(
     client: &ApiClient,
     agent_id: &str,
-    document_id: &str,
+    document_ids: &[String],
+    yes: bool,
+    with_memories: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting document..."))
+        Some(ui::create_spinner("Checking documents..."))
     } else {
         None
     };
 
-    let response = client.delete_document(agent_id, document_id, verbose);
+    // Look every document up first so nothing is deleted if one of them is blocked
+    let mut found = Vec::new();
+    let mut not_found = Vec::new();
+    let mut lookup_error = None;
+    for document_id in document_ids {
+        match client.get_document(agent_id, document_id, verbose) {
+            Ok(doc) => found.push(doc),
+            Err(e) if errors::is_not_found(&e) => not_found.push(document_id.clone()),
+            Err(e) => {
+                lookup_error = Some(e);
+                break;
+            }
+        }
+    }
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
+    if let Some(e) = lookup_error {
+        return Err(e);
+    }
 
-    match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
+    let with_derived: Vec<String> = found
+        .iter()
+        .filter(|d| d.memory_unit_count > 0)
+        .map(|d| format!("{} ({} memories)", d.id, d.memory_unit_count))
+        .collect();
+    if !with_memories && !with_derived.is_empty() {
+        anyhow::bail!(
+            "Deleting a document also deletes its derived memories. Re-run with --with-memories to delete:\n  {}",
+            with_derived.join("\n  ")
+        );
+    }
+
+    if !found.is_empty() && !yes && output_format == OutputFormat::Pretty {
+        let memory_total: i64 = found.iter().map(|d| d.memory_unit_count).sum();
+        let message = format!(
+            "Delete {} document(s) and {} derived memories from bank '{}'? This cannot be undone.",
+            found.len(),
+            memory_total,
+            agent_id
+        );
+        if !ui::prompt_confirmation(&message)? {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let mut summary = DeleteSummary {
+        deleted: Vec::new(),
+        not_found,
+    };
+    for doc in &found {
+        let plan = PlannedWrite::new("delete_document", agent_id)
+            .target(&doc.id)
+            .count(doc.memory_unit_count.max(0) as usize);
+        match executor::get().execute(plan, || client.delete_document(agent_id, &doc.id, verbose)) {
+            Ok(None) => {}
+            Ok(Some(result)) => {
+                if output_format == OutputFormat::Pretty {
+                    if result.success {
+                        ui::print_success(&format!(
+                            "Deleted document '{}' ({} memories)",
+                            doc.id,
+                            result.deleted_count.unwrap_or(0)
+                        ));
+                    } else {
+                        ui::print_error(&format!("Failed to delete document '{}'", doc.id));
+                    }
+                }
                 if result.success {
-                    ui::print_success("Document deleted successfully");
-                } else {
-                    ui::print_error("Failed to delete document");
+                    summary.deleted.push(DeletedDocument {
+                        document_id: doc.id.clone(),
+                        memory_units_deleted: result.deleted_count.unwrap_or(0),
+                    });
                 }
-            } else {
-                output::print_output(&result, output_format)?;
             }
-            Ok(())
+            // Removed concurrently between the lookup and the delete
+            Err(e) if errors::is_not_found(&e) => summary.not_found.push(doc.id.clone()),
+            Err(e) => return Err(e),
         }
-        Err(e) => Err(e),
     }
+
+    // Under --dry-run the planned deletes are reported instead
+    if output_format != OutputFormat::Pretty && !executor::get().is_dry_run() {
+        output::print_output(&summary, output_format)?;
+    }
+
+    if !summary.not_found.is_empty() {
+        ui::print_error(&format!(
+            "Document(s) not found in bank '{}': {}",
+            agent_id,
+            summary.not_found.join(", ")
+        ));
+        std::process::exit(errors::EXIT_NOT_FOUND);
+    }
+    Ok(())
 }
 
 /// Update a document (currently only supports replacing tags)
@@ -321,3 +543,530 @@ pub fn update(
     }
     Ok(())
 }
+
+#[derive(Debug, Serialize)]
+struct ReprocessedDocument {
+    document_id: String,
+    operation_id: String,
+    memories_before: i64,
+    /// Only known with `--wait`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memories_after: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct FailedDocument {
+    document_id: String,
+    error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ReprocessSummary {
+    reprocessed: Vec<ReprocessedDocument>,
+    failed: Vec<FailedDocument>,
+}
+
+impl ReprocessSummary {
+    fn fail(&mut self, document_id: &str, error: String) {
+        self.failed.push(FailedDocument {
+            document_id: document_id.to_string(),
+            error,
+        });
+    }
+}
+
+/// How a reprocessed document's memory count changed, e.g. "12 removed, 15 new"
+fn memory_change(before: i64, after: Option<i64>) -> String {
+    match after {
+        Some(after) => format!("{} removed, {} new", before, after),
+        None => format!("{} memories to be replaced", before),
+    }
+}
+
+/// Re-run extraction on stored documents, or every document in the bank with `all`.
+///
+/// A failing document is reported and the rest carry on, unless `fail_fast`. With
+/// `wait`, each operation is polled to completion and the new memory counts fetched.
+#[allow(clippy::too_many_arguments)]
+pub fn reprocess(
+    client: &ApiClient,
+    bank_id: &str,
+    document_ids: &[String],
+    all: bool,
+    wait: bool,
+    fail_fast: bool,
+    yes: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let pretty = output_format == OutputFormat::Pretty;
+    let mut summary = ReprocessSummary::default();
+
+    // Each target with its memory count before reprocessing
+    let targets: Vec<(String, i64)> = if all {
+        let docs = fetch_all_documents(client, bank_id, verbose)?;
+        if docs.is_empty() {
+            ui::print_info(&format!("Bank '{}' has no documents", bank_id));
+            return Ok(());
+        }
+        if !yes && pretty {
+            let message = format!(
+                "Reprocess all {} documents in bank '{}'? Their memories will be replaced.",
+                docs.len(),
+                bank_id
+            );
+            if !ui::prompt_confirmation(&message)? {
+                ui::print_info("Operation cancelled");
+                return Ok(());
+            }
+        }
+        docs.into_iter()
+            .map(|d| (d.id, d.memory_unit_count.unwrap_or(0)))
+            .collect()
+    } else {
+        let mut targets = Vec::new();
+        for document_id in document_ids {
+            match client.get_document(bank_id, document_id, verbose) {
+                Ok(doc) => targets.push((doc.id, doc.memory_unit_count)),
+                Err(e) if fail_fast => return Err(e),
+                Err(e) => summary.fail(document_id, format!("{:#}", e)),
+            }
+        }
+        targets
+    };
+
+    let mut queued = Vec::new();
+    for (document_id, before) in targets {
+        let plan = PlannedWrite::new("reprocess_document", bank_id)
+            .target(&document_id)
+            .count(before.max(0) as usize);
+        let outcome = executor::get().execute(plan, || {
+            client.document_reprocess(bank_id, &document_id, ReprocessOptions::default(), verbose)
+        });
+        match outcome {
+            Ok(None) => {}
+            Ok(Some(response)) => {
+                if pretty {
+                    ui::print_success(&format!(
+                        "Reprocessing '{}' ({})",
+                        document_id, response.operation_id
+                    ));
+                }
+                queued.push(ReprocessedDocument {
+                    document_id,
+                    operation_id: response.operation_id,
+                    memories_before: before,
+                    memories_after: None,
+                });
+            }
+            Err(e) if fail_fast => return Err(e),
+            Err(e) => summary.fail(&document_id, format!("{:#}", e)),
+        }
+    }
+
+    if wait && !queued.is_empty() {
+        let spinner = if pretty {
+            Some(ui::create_spinner(&format!(
+                "Waiting for {} document(s) to reprocess...",
+                queued.len()
+            )))
+        } else {
+            None
+        };
+        for doc in queued {
+            let waited = client
+                .poll_operation(bank_id, &doc.operation_id, verbose)
+                .and_then(|(success, error)| {
+                    if !success {
+                        anyhow::bail!(
+                            "Operation {} failed: {}",
+                            doc.operation_id,
+                            error.unwrap_or_else(|| "Unknown error".to_string())
+                        );
+                    }
+                    client.get_document(bank_id, &doc.document_id, verbose)
+                });
+            match waited {
+                Ok(updated) => summary.reprocessed.push(ReprocessedDocument {
+                    memories_after: Some(updated.memory_unit_count),
+                    ..doc
+                }),
+                Err(e) if fail_fast => return Err(e),
+                Err(e) => summary.fail(&doc.document_id, format!("{:#}", e)),
+            }
+        }
+        if let Some(mut sp) = spinner {
+            sp.finish();
+        }
+    } else {
+        summary.reprocessed = queued;
+    }
+
+    if pretty {
+        if !summary.reprocessed.is_empty() {
+            println!();
+        }
+        for doc in &summary.reprocessed {
+            println!(
+                "  {}  {}",
+                doc.document_id,
+                ui::dim(&memory_change(doc.memories_before, doc.memories_after))
+            );
+        }
+        for doc in &summary.failed {
+            ui::print_error(&format!("{}: {}", doc.document_id, doc.error));
+        }
+        if !wait && !summary.reprocessed.is_empty() {
+            println!();
+            println!(
+                "{}",
+                ui::dim(
+                    "Use --wait to wait for the new memories, or 'hindsight operation get' \
+                     to check status."
+                )
+            );
+        }
+    } else if !executor::get().is_dry_run() {
+        output::print_output(&summary, output_format)?;
+    }
+
+    if !summary.failed.is_empty() {
+        anyhow::bail!("{} document(s) failed to reprocess", summary.failed.len());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct IngestedFile {
+    path: String,
+    operation_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FailedFile {
+    path: String,
+    error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct IngestSummary {
+    succeeded: Vec<IngestedFile>,
+    skipped: Vec<String>,
+    failed: Vec<FailedFile>,
+}
+
+/// Match `text` against a shell-style wildcard pattern.
+///
+/// `*` and `?` never cross a `/`; `**` matches across directory levels.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    fn matches(p: &[char], t: &[char]) -> bool {
+        match p.first() {
+            None => t.is_empty(),
+            Some('*') if p.get(1) == Some(&'*') => {
+                let rest = &p[2..];
+                // "**/" may also match zero directories
+                if rest.first() == Some(&'/') && matches(&rest[1..], t) {
+                    return true;
+                }
+                (0..=t.len()).any(|i| matches(rest, &t[i..]))
+            }
+            Some('*') => {
+                let max = t.iter().position(|c| *c == '/').unwrap_or(t.len());
+                (0..=max).any(|i| matches(&p[1..], &t[i..]))
+            }
+            Some('?') => !t.is_empty() && t[0] != '/' && matches(&p[1..], &t[1..]),
+            Some(c) => t.first() == Some(c) && matches(&p[1..], &t[1..]),
+        }
+    }
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    matches(&p, &t)
+}
+
+fn has_wildcard(s: &str) -> bool {
+    s.contains('*') || s.contains('?')
+}
+
+/// Split a glob argument into the directory to walk and the pattern relative to it
+fn split_glob(arg: &str) -> (PathBuf, String) {
+    let parts: Vec<&str> = arg.split('/').collect();
+    let first_wild = parts.iter().position(|p| has_wildcard(p)).unwrap_or(parts.len());
+    let base = parts[..first_wild].join("/");
+    let base = if base.is_empty() {
+        if arg.starts_with('/') { PathBuf::from("/") } else { PathBuf::from(".") }
+    } else {
+        PathBuf::from(base)
+    };
+    (base, parts[first_wild..].join("/"))
+}
+
+fn relative_slash_path(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Expand file, directory, and glob arguments into a sorted list of files
+fn collect_ingest_paths(args: &[String], recursive: bool) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    for arg in args {
+        let path = Path::new(arg);
+        if has_wildcard(arg) && !path.exists() {
+            let (base, pattern) = split_glob(arg);
+            let walker = if pattern.contains("**") {
+                WalkDir::new(&base)
+            } else {
+                WalkDir::new(&base).max_depth(pattern.split('/').count())
+            };
+            for entry in walker.into_iter().filter_map(|e| e.ok()) {
+                if entry.file_type().is_file()
+                    && wildcard_match(&pattern, &relative_slash_path(entry.path(), &base))
+                {
+                    files.insert(entry.path().to_path_buf());
+                }
+            }
+        } else if path.is_dir() {
+            let walker = if recursive {
+                WalkDir::new(path)
+            } else {
+                WalkDir::new(path).max_depth(1)
+            };
+            for entry in walker.into_iter().filter_map(|e| e.ok()) {
+                if entry.file_type().is_file() {
+                    files.insert(entry.path().to_path_buf());
+                }
+            }
+        } else if path.is_file() {
+            files.insert(path.to_path_buf());
+        } else {
+            anyhow::bail!("Path does not exist: {}", arg);
+        }
+    }
+    Ok(files)
+}
+
+/// Patterns containing `/` match the whole path, others just the file name
+fn pattern_matches(pattern: &str, path: &Path) -> bool {
+    if pattern.contains('/') {
+        wildcard_match(pattern, &path.to_string_lossy().replace('\\', "/"))
+    } else {
+        path.file_name()
+            .map(|n| wildcard_match(pattern, &n.to_string_lossy()))
+            .unwrap_or(false)
+    }
+}
+
+/// Stream one file to the server, reporting bytes sent on the worker's progress line
+#[allow(clippy::too_many_arguments)]
+fn upload_file(
+    client: &ApiClient,
+    bank_id: &str,
+    path: &Path,
+    content_type: Option<&str>,
+    context: Option<&str>,
+    progress: &ui::TransferProgress,
+    worker: &ui::TransferWorker,
+    verbose: bool,
+) -> Result<Vec<String>> {
+    let content_type = content_type.unwrap_or_else(|| content_type_for(path));
+    let result = client.file_retain_streaming(
+        bank_id,
+        path,
+        content_type,
+        context.map(|c| c.to_string()),
+        None,
+        |sent, total| progress.bytes_sent(worker, sent, total),
+        verbose,
+    )?;
+    Ok(result.operation_ids)
+}
+
+/// Ingest files, directories, and globs as documents with a bounded worker pool
+#[allow(clippy::too_many_arguments)]
+pub fn ingest(
+    client: &ApiClient,
+    bank_id: &str,
+    paths: Vec<String>,
+    recursive: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    jobs: usize,
+    content_type: Option<String>,
+    context: Option<String>,
+    continue_on_error: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut summary = IngestSummary::default();
+    let mut queue = Vec::new();
+    for path in collect_ingest_paths(&paths, recursive)? {
+        if !include.is_empty() && !include.iter().any(|p| pattern_matches(p, &path)) {
+            continue;
+        }
+        if exclude.iter().any(|p| pattern_matches(p, &path)) {
+            continue;
+        }
+        // An explicit --content-type means the caller vouches for the format
+        if content_type.is_none() && !is_supported_file(&path) {
+            summary.skipped.push(path.display().to_string());
+            continue;
+        }
+        queue.push(path);
+    }
+
+    if queue.is_empty() && summary.skipped.is_empty() {
+        ui::print_warning("No files matched");
+        return Ok(());
+    }
+
+    let progress = ui::create_transfer_progress(
+        queue.len() as u64,
+        "Ingesting",
+        output_format == OutputFormat::Pretty,
+    );
+
+    let pending = Mutex::new(queue.into_iter());
+    let aborted = AtomicBool::new(false);
+    let results = Mutex::new(summary);
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| loop {
+                if aborted.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Some(path) = pending.lock().unwrap().next() else {
+                    break;
+                };
+                let worker = progress.start(&path.display().to_string());
+                let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let outcome = upload_file(
+                    client,
+                    bank_id,
+                    &path,
+                    content_type.as_deref(),
+                    context.as_deref(),
+                    &progress,
+                    &worker,
+                    verbose,
+                );
+                let mut results = results.lock().unwrap();
+                match outcome {
+                    Ok(operation_ids) => results.succeeded.push(IngestedFile {
+                        path: path.display().to_string(),
+                        operation_ids,
+                    }),
+                    Err(e) => {
+                        progress.println(&format!("failed: {}: {:#}", path.display(), e));
+                        results.failed.push(FailedFile {
+                            path: path.display().to_string(),
+                            error: format!("{:#}", e),
+                        });
+                        if !continue_on_error {
+                            aborted.store(true, Ordering::Relaxed);
+                        }
+                    }
+                }
+                drop(results);
+                progress.complete(worker, 1, size);
+            });
+        }
+    });
+
+    progress.finish();
+    let summary = results.into_inner().unwrap();
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_section_header("Ingest Summary");
+        println!("  {} {}", ui::dim("Succeeded:"), summary.succeeded.len());
+        println!("  {} {}", ui::dim("Skipped:  "), summary.skipped.len());
+        println!("  {} {}", ui::dim("Failed:   "), summary.failed.len());
+        if !summary.skipped.is_empty() {
+            println!();
+            println!("  {}", ui::dim("Skipped (unsupported type):"));
+            for path in &summary.skipped {
+                println!("    {}", path);
+            }
+        }
+        if !summary.failed.is_empty() {
+            println!();
+            println!("  {}", ui::dim("Failed:"));
+            for failure in &summary.failed {
+                println!("    {} - {}", failure.path, failure.error);
+            }
+        }
+        println!();
+    } else {
+        output::print_output(&summary, output_format)?;
+    }
+
+    if !summary.failed.is_empty() {
+        anyhow::bail!("{} file(s) failed to ingest", summary.failed.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_change() {
+        assert_eq!(memory_change(12, Some(15)), "12 removed, 15 new");
+        assert_eq!(memory_change(3, None), "3 memories to be replaced");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn test_document_row_content_type() {
+        let doc = serde_json::json!({
+            "id": "report",
+            "text_length": 2048,
+            "memory_unit_count": 3,
+            "created_at": "2024-01-15T10:30:00Z",
+            "retain_params": {"content_type": "application/pdf"},
+        });
+        let row = document_row(&serde_json::from_value(doc).unwrap());
+        assert_eq!(row["content_type"], "application/pdf");
+        assert_eq!(row["size"], "2.0 KB");
+        assert_eq!(row["memories"], 3);
+
+        let bare: DocumentListItem =
+            serde_json::from_value(serde_json::json!({"id": "note"})).unwrap();
+        assert_eq!(document_row(&bare)["content_type"], "-");
+        assert_eq!(document_row(&bare)["size"], "0 B");
+    }
+
+    #[test]
+    fn test_wildcard_match_basic() {
+        assert!(wildcard_match("*.md", "notes.md"));
+        assert!(!wildcard_match("*.md", "notes.txt"));
+        assert!(wildcard_match("file?.txt", "file1.txt"));
+        assert!(!wildcard_match("file?.txt", "file10.txt"));
+    }
+
+    #[test]
+    fn test_wildcard_match_directories() {
+        assert!(!wildcard_match("*.md", "sub/notes.md"));
+        assert!(wildcard_match("*/*.md", "sub/notes.md"));
+        assert!(wildcard_match("**/*.md", "a/b/notes.md"));
+        assert!(wildcard_match("**/*.md", "notes.md"));
+    }
+
+    #[test]
+    fn test_split_glob() {
+        assert_eq!(split_glob("docs/**/*.md"), (PathBuf::from("docs"), "**/*.md".to_string()));
+        assert_eq!(split_glob("*.txt"), (PathBuf::from("."), "*.txt".to_string()));
+        assert_eq!(split_glob("/data/in/*.csv"), (PathBuf::from("/data/in"), "*.csv".to_string()));
+    }
+}
@@ -1,12 +1,63 @@
 use anyhow::Result;
-use crate::api::ApiClient;
+use crate::api::{ApiClient, EntityGraph, EntityGraphEdge, EntityGraphNode, RecallResult};
+use crate::commands::memory::build_recall_request;
+use crate::executor::{self, PlannedWrite};
 use crate::output::{self, OutputFormat};
+use crate::pager;
 use crate::ui;
+use hindsight_client::types::{EntityDetailResponse, EntityListItem};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
+/// Page size used when fetching every entity of a bank
+const ENTITY_PAGE_SIZE: i64 = 500;
+
+/// Fetch every entity in a bank, page by page
+pub(crate) fn fetch_all_entities(
+    client: &ApiClient,
+    bank_id: &str,
+    verbose: bool,
+) -> Result<Vec<EntityListItem>> {
+    let mut all = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = client.list_entities(bank_id, Some(ENTITY_PAGE_SIZE), Some(offset), verbose)?;
+        let fetched = page.items.len() as i64;
+        all.extend(page.items);
+        offset += fetched;
+        if fetched < ENTITY_PAGE_SIZE || offset >= page.total {
+            break;
+        }
+    }
+    Ok(all)
+}
+
+/// Sort entities by mention count (descending) or name, dropping rarely-mentioned ones
+fn rank_entities(
+    mut entities: Vec<EntityListItem>,
+    sort: &str,
+    min_mentions: i64,
+) -> Vec<EntityListItem> {
+    entities.retain(|e| e.mention_count >= min_mentions);
+    match sort {
+        "name" => entities.sort_by_key(|e| e.canonical_name.to_lowercase()),
+        _ => entities.sort_by(|a, b| {
+            b.mention_count
+                .cmp(&a.mention_count)
+                .then_with(|| a.canonical_name.cmp(&b.canonical_name))
+        }),
+    }
+    entities
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn list(
     client: &ApiClient,
     bank_id: &str,
     limit: i64,
+    sort: &str,
+    min_mentions: i64,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
@@ -16,36 +67,312 @@ pub fn list(
         None
     };
 
-    let response = client.list_entities(bank_id, Some(limit), None, verbose)?;
+    // Sorting and filtering need the whole set, not just the first page
+    let response = fetch_all_entities(client, bank_id, verbose);
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
-    if output_format == OutputFormat::Pretty {
-        ui::print_section_header(&format!("Entities for Bank: {}", bank_id));
+    let total = response.as_ref().map(|r| r.len()).unwrap_or(0);
+    let mut entities = rank_entities(response?, sort, min_mentions);
+    entities.truncate(limit.max(0) as usize);
+
+    if output_format == OutputFormat::Table {
+        output::print_table(&entities, &["name", "mentions", "first_seen", "last_seen"])?;
+    } else if output_format == OutputFormat::Pretty {
+        pager::page(|| {
+            ui::print_section_header(&format!("Entities for Bank: {}", bank_id));
+
+            if entities.is_empty() {
+                ui::print_warning("No entities found");
+                return Ok(());
+            }
+
+            output::print_table(&entities, &["name", "mentions", "first_seen", "last_seen"])?;
+            println!();
+            println!(
+                "  {} showing {} of {} entities",
+                ui::dim("Total:"),
+                entities.len(),
+                total
+            );
+            Ok(())
+        })?;
+    } else {
+        output::print_output(&entities, output_format)?;
+    }
+
+    Ok(())
+}
+
+/// Quick leaderboard of the most-mentioned entities
+pub fn top(
+    client: &ApiClient,
+    bank_id: &str,
+    count: usize,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Fetching entities..."))
+    } else {
+        None
+    };
 
-        if response.items.is_empty() {
+    let response = fetch_all_entities(client, bank_id, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let mut entities = rank_entities(response?, "mentions", 0);
+    entities.truncate(count);
+
+    if output_format == OutputFormat::Table {
+        output::print_table(&entities, &["name", "mentions"])?;
+    } else if output_format == OutputFormat::Pretty {
+        ui::print_section_header(&format!("Top Entities: {}", bank_id));
+        if entities.is_empty() {
             ui::print_warning("No entities found");
             return Ok(());
         }
 
-        println!("Total entities: {}\n", response.items.len());
+        let max = entities.first().map(|e| e.mention_count).unwrap_or(1).max(1);
+        let name_width = entities
+            .iter()
+            .map(|e| e.canonical_name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(30);
+        for (i, entity) in entities.iter().enumerate() {
+            let bar_len = ((entity.mention_count as f64 / max as f64) * 30.0).round() as usize;
+            let name = output::truncate_to_width(&entity.canonical_name, name_width);
+            println!(
+                "  {:>3}. {:<width$}  {} {}",
+                i + 1,
+                name,
+                ui::gradient(&"█".repeat(bar_len.max(1)), i as f32 / entities.len().max(1) as f32),
+                entity.mention_count,
+                width = name_width
+            );
+        }
+        println!();
+    } else {
+        output::print_output(&entities, output_format)?;
+    }
+
+    Ok(())
+}
+
+/// Resolve an entity by ID or canonical name; ambiguous names list the candidates
+fn resolve_entity<'a>(entities: &'a [EntityListItem], name_or_id: &str) -> Result<&'a EntityListItem> {
+    resolve_by_name(entities, name_or_id, |e| {
+        (e.id.as_str(), e.canonical_name.as_str(), e.mention_count)
+    })
+}
+
+/// Find the item whose ID is `name_or_id`, else the one whose name matches it exactly or,
+/// failing that, contains it, ignoring case. `key` gives an item's ID, name and mentions.
+fn resolve_by_name<'a, T>(
+    items: &'a [T],
+    name_or_id: &str,
+    key: impl Fn(&T) -> (&str, &str, i64),
+) -> Result<&'a T> {
+    if let Some(item) = items.iter().find(|item| key(item).0 == name_or_id) {
+        return Ok(item);
+    }
+    let needle = name_or_id.to_lowercase();
+    let exact: Vec<&T> = items
+        .iter()
+        .filter(|item| key(item).1.to_lowercase() == needle)
+        .collect();
+    let candidates = if exact.is_empty() {
+        items
+            .iter()
+            .filter(|item| key(item).1.to_lowercase().contains(&needle))
+            .collect()
+    } else {
+        exact
+    };
+    match candidates.as_slice() {
+        [] => anyhow::bail!("No entity matches '{}'", name_or_id),
+        [single] => Ok(single),
+        many => {
+            let list: Vec<String> = many
+                .iter()
+                .take(20)
+                .map(|item| {
+                    let (id, name, mentions) = key(item);
+                    format!("  {} ({} mentions) - {}", name, mentions, id)
+                })
+                .collect();
+            anyhow::bail!(
+                "'{}' matches {} entities; pass an ID instead:\n{}",
+                name_or_id,
+                many.len(),
+                list.join("\n")
+            )
+        }
+    }
+}
+
+/// Aliases stored in entity metadata, if the server recorded any
+pub(crate) fn entity_aliases(metadata: Option<&serde_json::Map<String, Value>>) -> Vec<String> {
+    metadata
+        .and_then(|m| m.get("aliases"))
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Count related memories per month (YYYY-MM) for a simple mention timeline
+fn mention_timeline(memories: &[RecallResult]) -> BTreeMap<String, usize> {
+    let mut timeline = BTreeMap::new();
+    for memory in memories {
+        let date = memory
+            .occurred_start
+            .as_deref()
+            .or(memory.mentioned_at.as_deref());
+        if let Some(month) = date.and_then(|d| d.get(..7)) {
+            *timeline.entry(month.to_string()).or_insert(0) += 1;
+        }
+    }
+    timeline
+}
+
+/// Memories that mention an entity under its canonical name or one of its aliases
+pub(crate) fn related_memories(
+    client: &ApiClient,
+    bank_id: &str,
+    canonical_name: &str,
+    aliases: &[String],
+    verbose: bool,
+) -> Result<Vec<RecallResult>> {
+    // There is no entity filter on recall, so search by name and keep results
+    // that were tagged with the entity.
+    let request = build_recall_request(
+        canonical_name.to_string(),
+        Vec::new(),
+        "mid",
+        4096,
+        false,
+        false,
+        0,
+        Vec::new(),
+        None,
+        None,
+    );
+    let names: Vec<String> = std::iter::once(canonical_name)
+        .chain(aliases.iter().map(String::as_str))
+        .map(str::to_lowercase)
+        .collect();
+    Ok(client
+        .recall(bank_id, &request, verbose)?
+        .results
+        .into_iter()
+        .filter(|r| {
+            r.entities
+                .as_ref()
+                .is_some_and(|es| es.iter().any(|e| names.contains(&e.to_lowercase())))
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize)]
+struct EntityShow {
+    entity: EntityDetailResponse,
+    aliases: Vec<String>,
+    related_memories: Vec<RecallResult>,
+    timeline: BTreeMap<String, usize>,
+}
+
+/// Resolve the entity and gather everything `entity show` displays
+fn load_entity_show(
+    client: &ApiClient,
+    bank_id: &str,
+    name_or_id: &str,
+    limit: usize,
+    verbose: bool,
+) -> Result<EntityShow> {
+    let entities = fetch_all_entities(client, bank_id, verbose)?;
+    let id = resolve_entity(&entities, name_or_id)?.id.clone();
+    let entity = client.get_entity(bank_id, &id, verbose)?;
+    let aliases = entity_aliases(entity.metadata.as_ref());
+    let mut related = related_memories(client, bank_id, &entity.canonical_name, &aliases, verbose)?;
+    related.truncate(limit);
+
+    Ok(EntityShow {
+        aliases,
+        timeline: mention_timeline(&related),
+        related_memories: related,
+        entity,
+    })
+}
+
+/// Show an entity with its aliases, related memories and mention timeline
+pub fn show(
+    client: &ApiClient,
+    bank_id: &str,
+    name_or_id: &str,
+    limit: usize,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Fetching entity..."))
+    } else {
+        None
+    };
+
+    let result = load_entity_show(client, bank_id, name_or_id, limit, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let shown = result?;
+
+    if output_format == OutputFormat::Pretty {
+        let entity = &shown.entity;
+        ui::print_section_header(&format!("Entity: {}", entity.canonical_name));
+        println!("  {} {}", ui::dim("ID:"), entity.id);
+        println!("  {} {}", ui::dim("Mentions:"), entity.mention_count);
+        if let Some(first_seen) = &entity.first_seen {
+            println!("  {} {}", ui::dim("First seen:"), first_seen);
+        }
+        if let Some(last_seen) = &entity.last_seen {
+            println!("  {} {}", ui::dim("Last seen:"), last_seen);
+        }
+        if !shown.aliases.is_empty() {
+            println!("  {} {}", ui::dim("Aliases:"), shown.aliases.join(", "));
+        }
 
-        for entity in &response.items {
-            println!("ID: {}", entity.id);
-            println!("  Name: {}", entity.canonical_name);
-            println!("  Mentions: {}", entity.mention_count);
-            if let Some(first_seen) = &entity.first_seen {
-                println!("  First seen: {}", first_seen);
+        if !entity.observations.is_empty() {
+            ui::print_section_header("Observations");
+            for obs in &entity.observations {
+                println!("  • {}", obs.text);
             }
-            if let Some(last_seen) = &entity.last_seen {
-                println!("  Last seen: {}", last_seen);
+        }
+
+        ui::print_section_header(&format!("Related Memories ({})", shown.related_memories.len()));
+        if shown.related_memories.is_empty() {
+            println!("  {}", ui::dim("No related memories found."));
+        }
+        for memory in &shown.related_memories {
+            ui::print_fact(memory, false);
+        }
+
+        if !shown.timeline.is_empty() {
+            ui::print_section_header("Mention Timeline");
+            let max = shown.timeline.values().copied().max().unwrap_or(1).max(1);
+            for (month, count) in &shown.timeline {
+                let bar_len = (count * 30).div_ceil(max);
+                println!("  {}  {} {}", month, ui::gradient_mid(&"█".repeat(bar_len)), count);
             }
             println!();
         }
     } else {
-        output::print_output(&response, output_format)?;
+        output::print_output(&shown, output_format)?;
     }
 
     Ok(())
@@ -123,3 +450,531 @@ pub fn regenerate(
 
     Ok(())
 }
+
+/// The target of a merge and its distinct sources, refusing the target as a source
+fn merge_plan<'a>(
+    entities: &'a [EntityListItem],
+    target: &str,
+    sources: &[String],
+) -> Result<(&'a EntityListItem, Vec<&'a EntityListItem>)> {
+    let into = resolve_entity(entities, target)?;
+    let mut from: Vec<&EntityListItem> = Vec::new();
+    for source in sources {
+        let entity = resolve_entity(entities, source)?;
+        if entity.id == into.id {
+            anyhow::bail!(
+                "'{}' is the merge target and can't also be a source",
+                source
+            );
+        }
+        if !from.iter().any(|e| e.id == entity.id) {
+            from.push(entity);
+        }
+    }
+    Ok((into, from))
+}
+
+/// Merge entities into one, e.g. "Bob" and "B. Smith" into "Bob Smith"
+pub fn merge(
+    client: &ApiClient,
+    bank_id: &str,
+    target: &str,
+    sources: &[String],
+    yes: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Resolving entities..."))
+    } else {
+        None
+    };
+    let entities = fetch_all_entities(client, bank_id, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let entities = entities?;
+    let (into, from) = merge_plan(&entities, target, sources)?;
+
+    if !yes && output_format == OutputFormat::Pretty {
+        let names: Vec<String> = from
+            .iter()
+            .map(|e| format!("'{}' ({} mentions)", e.canonical_name, e.mention_count))
+            .collect();
+        let message = format!(
+            "Merge {} into '{}' in bank '{}'? This cannot be undone.",
+            names.join(", "),
+            into.canonical_name,
+            bank_id
+        );
+        if !ui::prompt_confirmation(&message)? {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let from_ids: Vec<String> = from.iter().map(|e| e.id.clone()).collect();
+    let plan = PlannedWrite::new("entity_merge", bank_id)
+        .target(&into.id)
+        .ids(from_ids.clone());
+    let outcome = executor::get().execute(plan, || {
+        client.entity_merge(bank_id, &into.id, &from_ids, verbose)
+    })?;
+    let Some(result) = outcome else {
+        return Ok(());
+    };
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!(
+            "Merged {} entities into '{}' ({} mentions re-linked)",
+            result.merged_ids.len(),
+            into.canonical_name,
+            result.mentions_relinked
+        ));
+    } else {
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
+/// Rename an entity, or with `alias` add the new name as an alias and keep the old one
+#[allow(clippy::too_many_arguments)]
+pub fn rename(
+    client: &ApiClient,
+    bank_id: &str,
+    name_or_id: &str,
+    new_name: &str,
+    alias: bool,
+    yes: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        anyhow::bail!("The new name can't be empty");
+    }
+    let entities = fetch_all_entities(client, bank_id, verbose)?;
+    let entity = resolve_entity(&entities, name_or_id)?;
+    if entity.canonical_name == new_name {
+        anyhow::bail!("'{}' is already the entity's name", new_name);
+    }
+
+    if !yes && output_format == OutputFormat::Pretty {
+        let message = if alias {
+            format!(
+                "Add '{}' as an alias of '{}'?",
+                new_name, entity.canonical_name
+            )
+        } else {
+            format!(
+                "Rename '{}' ({} mentions) to '{}'?",
+                entity.canonical_name, entity.mention_count, new_name
+            )
+        };
+        if !ui::prompt_confirmation(&message)? {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let action = if alias {
+        "entity_add_alias"
+    } else {
+        "entity_rename"
+    };
+    let plan = PlannedWrite::new(action, bank_id).target(&entity.id);
+    let outcome = executor::get().execute(plan, || {
+        if alias {
+            client.entity_add_alias(bank_id, &entity.id, new_name, verbose)
+        } else {
+            client.entity_rename(bank_id, &entity.id, new_name, verbose)
+        }
+    })?;
+    let Some(result) = outcome else {
+        return Ok(());
+    };
+
+    if output_format == OutputFormat::Pretty {
+        if alias {
+            ui::print_success(&format!(
+                "'{}' is now an alias of '{}'",
+                new_name, result.canonical_name
+            ));
+        } else {
+            ui::print_success(&format!(
+                "Renamed '{}' to '{}'",
+                entity.canonical_name, result.canonical_name
+            ));
+        }
+        if result.mentions_relinked > 0 {
+            println!("  {} mentions re-linked", result.mentions_relinked);
+        }
+    } else {
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
+/// How `entity graph` prints in pretty output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityGraphFormat {
+    Ascii,
+    Dot,
+}
+
+/// A line of the ASCII tree: an entity under its parent, or a root without a weight
+#[derive(Debug, Clone, PartialEq)]
+struct TreeRow {
+    prefix: String,
+    name: String,
+    /// Memories shared with the parent
+    weight: Option<i64>,
+    mention_count: i64,
+}
+
+/// Neighbours of every entity, strongest co-mention first
+fn adjacency(graph: &EntityGraph) -> HashMap<&str, Vec<(&str, i64)>> {
+    let names: HashMap<&str, &str> = graph
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), n.name.as_str()))
+        .collect();
+    let mut neighbours: HashMap<&str, Vec<(&str, i64)>> = HashMap::new();
+    for edge in &graph.edges {
+        let (source, target) = (edge.source.as_str(), edge.target.as_str());
+        if source == target {
+            continue;
+        }
+        neighbours
+            .entry(source)
+            .or_default()
+            .push((target, edge.weight));
+        neighbours
+            .entry(target)
+            .or_default()
+            .push((source, edge.weight));
+    }
+    for list in neighbours.values_mut() {
+        list.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| names.get(a.0).cmp(&names.get(b.0)))
+        });
+    }
+    neighbours
+}
+
+/// Trees of co-mentioned entities, from `root` or else from each entity not yet shown,
+/// most mentioned first. Each entity appears once: it hangs under the first entity that
+/// reaches it breadth-first, so cycles end at the visited set. Returns the rows and the
+/// IDs of the entities shown.
+fn entity_forest(
+    graph: &EntityGraph,
+    root: Option<&str>,
+    depth: usize,
+) -> (Vec<TreeRow>, HashSet<String>) {
+    let neighbours = adjacency(graph);
+    let nodes: HashMap<&str, &EntityGraphNode> =
+        graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut ranked: Vec<&EntityGraphNode> = graph.nodes.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.mention_count
+            .cmp(&a.mention_count)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    let roots: Vec<&str> = match root {
+        Some(root) => vec![root],
+        None => ranked.iter().map(|n| n.id.as_str()).collect(),
+    };
+
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut rows = Vec::new();
+    for root in roots {
+        if !visited.insert(root) {
+            continue;
+        }
+        // Breadth-first, so every entity hangs at its shortest distance from the root
+        let mut children: HashMap<&str, Vec<(&str, i64)>> = HashMap::new();
+        let mut queue = VecDeque::from([(root, 0)]);
+        while let Some((id, level)) = queue.pop_front() {
+            if level == depth {
+                continue;
+            }
+            for &(next, weight) in neighbours.get(id).into_iter().flatten() {
+                if nodes.contains_key(next) && visited.insert(next) {
+                    children.entry(id).or_default().push((next, weight));
+                    queue.push_back((next, level + 1));
+                }
+            }
+        }
+
+        let mut stack = vec![(root, String::new(), None)];
+        while let Some((id, prefix, weight)) = stack.pop() {
+            let node = nodes.get(id);
+            rows.push(TreeRow {
+                prefix: prefix.clone(),
+                name: node.map_or_else(|| id.to_string(), |n| n.name.clone()),
+                weight: weight.map(|(w, _)| w),
+                mention_count: node.map_or(0, |n| n.mention_count),
+            });
+            // Children continue the parent's guides: a bar below a branch, blank after the last
+            let guide = match weight {
+                Some((_, true)) => format!("{}    ", &prefix[..prefix.len() - "└── ".len()]),
+                Some((_, false)) => format!("{}│   ", &prefix[..prefix.len() - "├── ".len()]),
+                None => String::new(),
+            };
+            let kids = children.get(id).map(Vec::as_slice).unwrap_or_default();
+            for (i, &(child, w)) in kids.iter().enumerate().rev() {
+                let last = i + 1 == kids.len();
+                let branch = if last { "└── " } else { "├── " };
+                stack.push((child, format!("{}{}", guide, branch), Some((w, last))));
+            }
+        }
+    }
+    let shown = visited.into_iter().map(str::to_string).collect();
+    (rows, shown)
+}
+
+/// The entity graph as Graphviz DOT: undirected, with co-mention counts on the edges
+fn entity_graph_dot(bank_id: &str, graph: &EntityGraph) -> String {
+    use crate::commands::graph::dot_escape;
+    let mut out = format!("graph \"{}\" {{\n", dot_escape(bank_id));
+    out.push_str("  node [shape=ellipse, fontname=\"Helvetica\"];\n");
+    for node in &graph.nodes {
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\", mentions={}];\n",
+            dot_escape(&node.id),
+            dot_escape(&node.name),
+            node.mention_count
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -- \"{}\" [label=\"{}\", weight={}];\n",
+            dot_escape(&edge.source),
+            dot_escape(&edge.target),
+            edge.weight,
+            edge.weight
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Entities co-mentioned with `root` (or with every entity) out to `depth` hops, as an
+/// ASCII tree, DOT, or nodes and weighted edges in the structured formats
+#[allow(clippy::too_many_arguments)]
+pub fn graph(
+    client: &ApiClient,
+    bank_id: &str,
+    root: Option<&str>,
+    depth: usize,
+    min_count: i64,
+    limit: i64,
+    format: EntityGraphFormat,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Fetching entity graph..."))
+    } else {
+        None
+    };
+
+    let response = client.entity_cooccurrence(bank_id, Some(limit), Some(min_count), verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let mut graph = response?;
+    // Older servers may ignore min_count
+    graph.edges.retain(|edge| edge.weight >= min_count);
+    let root_id = match root {
+        Some(name) => {
+            let node = resolve_by_name(&graph.nodes, name, |n| {
+                (n.id.as_str(), n.name.as_str(), n.mention_count)
+            })?;
+            Some(node.id.clone())
+        }
+        None => None,
+    };
+    let (rows, shown) = entity_forest(&graph, root_id.as_deref(), depth);
+    graph.nodes.retain(|node| shown.contains(&node.id));
+    graph
+        .edges
+        .retain(|edge| shown.contains(&edge.source) && shown.contains(&edge.target));
+
+    if output_format != OutputFormat::Pretty {
+        output::print_output(&EntityGraphOutput::from(graph), output_format)?;
+    } else if format == EntityGraphFormat::Dot {
+        print!("{}", entity_graph_dot(bank_id, &graph));
+    } else {
+        pager::page(|| {
+            ui::print_section_header(&format!("Entity Graph: {}", bank_id));
+            if rows.is_empty() {
+                ui::print_warning("No entities found");
+                return Ok(());
+            }
+            for row in &rows {
+                let count = match row.weight {
+                    Some(weight) => ui::dim(&format!("×{}", weight)),
+                    None => ui::dim(&format!("{} mentions", row.mention_count)),
+                };
+                let name = match row.weight {
+                    Some(_) => row.name.clone(),
+                    None => ui::gradient_start(&row.name),
+                };
+                println!("  {}{}  {}", ui::dim(&row.prefix), name, count);
+            }
+            println!();
+            println!(
+                "  {} {} entities, {} edges (×N: memories mentioning both)",
+                ui::dim("Shown:"),
+                graph.nodes.len(),
+                graph.edges.len()
+            );
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Structured output of `entity graph`
+#[derive(Debug, Serialize)]
+struct EntityGraphOutput {
+    nodes: Vec<EntityGraphNode>,
+    edges: Vec<EntityGraphEdge>,
+}
+
+impl From<EntityGraph> for EntityGraphOutput {
+    fn from(graph: EntityGraph) -> Self {
+        EntityGraphOutput {
+            nodes: graph.nodes,
+            edges: graph.edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(id: &str, name: &str, mentions: i64) -> EntityListItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "canonical_name": name,
+            "mention_count": mentions,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_plan_resolves_names_and_refuses_the_target_as_source() {
+        let entities = vec![
+            entity("1", "Bob Smith", 10),
+            entity("2", "Bob", 4),
+            entity("3", "B. Smith", 2),
+        ];
+        let sources = vec!["bob".to_string(), "3".to_string(), "2".to_string()];
+        let (into, from) = merge_plan(&entities, "Bob Smith", &sources).unwrap();
+        assert_eq!(into.id, "1");
+        let ids: Vec<&str> = from.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+
+        let error = merge_plan(&entities, "1", &["bob smith".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("merge target"));
+        assert!(merge_plan(&entities, "1", &["Carol".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_rank_entities_sort_and_min_mentions() {
+        let entities = vec![entity("1", "bob", 3), entity("2", "Alice", 10), entity("3", "carol", 1)];
+        let ranked = rank_entities(entities.clone(), "mentions", 2);
+        let names: Vec<&str> = ranked.iter().map(|e| e.canonical_name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "bob"]);
+
+        let ranked = rank_entities(entities, "name", 0);
+        let names: Vec<&str> = ranked.iter().map(|e| e.canonical_name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "bob", "carol"]);
+    }
+
+    #[test]
+    fn test_entity_forest_handles_cycles_and_depth() {
+        let node = |id: &str, name: &str, mentions: i64| EntityGraphNode {
+            id: id.to_string(),
+            name: name.to_string(),
+            mention_count: mentions,
+        };
+        let edge = |source: &str, target: &str, weight: i64| EntityGraphEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            weight,
+        };
+        // alice, google and bob form a cycle; dave hangs two hops from alice
+        let graph = EntityGraph {
+            nodes: vec![
+                node("a", "Alice", 12),
+                node("g", "Google", 8),
+                node("b", "Bob", 5),
+                node("d", "Dave", 2),
+                node("z", "Zed", 1),
+            ],
+            edges: vec![
+                edge("a", "g", 5),
+                edge("g", "b", 4),
+                edge("b", "a", 2),
+                edge("b", "d", 1),
+            ],
+            total_entities: 5,
+        };
+        let lines = |rows: &[TreeRow]| -> Vec<String> {
+            rows.iter()
+                .map(|r| format!("{}{} {:?}", r.prefix, r.name, r.weight))
+                .collect()
+        };
+
+        let (rows, shown) = entity_forest(&graph, Some("a"), 2);
+        assert_eq!(
+            lines(&rows),
+            vec![
+                "Alice None",
+                "├── Google Some(5)",
+                "└── Bob Some(2)",
+                "    └── Dave Some(1)",
+            ]
+        );
+        assert_eq!(shown.len(), 4);
+
+        let (rows, _) = entity_forest(&graph, Some("g"), 1);
+        assert_eq!(
+            lines(&rows),
+            vec!["Google None", "├── Alice Some(5)", "└── Bob Some(4)"]
+        );
+
+        // Without a root every entity shows up once, the unconnected ones as their own trees
+        let (rows, shown) = entity_forest(&graph, None, 3);
+        assert_eq!(rows.len(), 5);
+        assert_eq!(shown.len(), 5);
+        assert_eq!(lines(&rows).last().unwrap(), "Zed None");
+
+        let dot = entity_graph_dot("bank", &graph);
+        assert!(dot.starts_with("graph \"bank\" {"));
+        assert!(dot.contains("  \"a\" -- \"g\" [label=\"5\", weight=5];"));
+    }
+
+    #[test]
+    fn test_resolve_entity_by_id_name_and_ambiguity() {
+        let entities = vec![
+            entity("e1", "Bob Smith", 5),
+            entity("e2", "Bob", 3),
+            entity("e3", "B. Smith", 1),
+        ];
+        assert_eq!(resolve_entity(&entities, "e3").unwrap().id, "e3");
+        // Exact name wins over substring matches
+        assert_eq!(resolve_entity(&entities, "bob").unwrap().id, "e2");
+        let err = resolve_entity(&entities, "smith").unwrap_err().to_string();
+        assert!(err.contains("matches 2 entities"));
+        assert!(resolve_entity(&entities, "carol").is_err());
+    }
+}
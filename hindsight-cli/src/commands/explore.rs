@@ -1,38 +1,257 @@
-use crate::api::{ApiClient, RecallRequest, ReflectRequest};
-use anyhow::Result;
+use crate::api::{
+    AgentStats, ApiClient, CacheConfig, CacheStats, MemoryPutResult, Operation,
+    OperationsResponse, RecallRequest, ReflectRequest,
+};
+use crate::commands::entity::{entity_aliases, related_memories};
+use crate::commands::document::{document_content_type, format_size, recorded_content_type};
+use crate::commands::entity::fetch_all_entities;
+use crate::commands::memory::{
+    build_memory_item, fetch_matching_memories, memory_document_id, memory_timestamp,
+    parse_timestamp, MemoryFilter, FACT_TYPES,
+};
+use crate::config;
+use crate::errors::is_unreachable;
+use crate::executor::{self, PlannedWrite};
+use crate::keymap::{Action, KeyChord, Keymap, Lookup, Scope};
+use crate::trace::TraceReport;
+use crate::ui::format_date;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use hindsight_client::types::{BankListItem, RecallResult, EntityListItem, Budget, TagsMatch};
+use hindsight_client::enums::{Budget, FactTypesItem};
+use hindsight_client::types::{
+    BankListItem, BankProfileResponse, ChunkData, ChunkIncludeOptions, ChunkResponse,
+    CreateBankRequest, DeleteResponse, DispositionTraits, DocumentListItem, DocumentResponse,
+    EntityDetailResponse, EntityListItem, EntityListResponse, FactsIncludeOptions, IncludeOptions,
+    ListChunksResponse, ListDocumentsResponse, ListMemoryUnitsResponse, MemoryListItem,
+    RecallResponse, RecallResult, ReflectFact, ReflectIncludeOptions, ReflectResponse,
+    RetainRequest, TagsMatch, VersionResponse,
+};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        BarChart, Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row,
+        Sparkline, Table, TableState, Wrap,
+    },
     Frame, Terminal,
 };
-use std::io;
-use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 use std::time::{Duration, Instant};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 // Brand gradient colors: #0074d9 -> #009296
 const BRAND_START: Color = Color::Rgb(0, 116, 217);  // #0074d9
 const BRAND_END: Color = Color::Rgb(0, 146, 150);    // #009296
 const BRAND_MID: Color = Color::Rgb(0, 131, 183);    // Midpoint
 
+/// Built-in explorer themes (`--theme`, or `theme` in the config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ThemeName {
+    /// Brand colors on a dark background
+    #[default]
+    Dark,
+    /// Darker colors that stay readable on a light background
+    Light,
+    /// Bold and reverse video only, for terminals with few colors
+    Monochrome,
+}
+
+/// File formats `E` writes the current list in (`--export-format`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// The rows as a pretty-printed JSON array
+    #[default]
+    Json,
+    /// A section per row, for reading or pasting into notes
+    Md,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Md => "md",
+        }
+    }
+
+    fn toggled(self) -> Self {
+        match self {
+            ExportFormat::Json => ExportFormat::Md,
+            ExportFormat::Md => ExportFormat::Json,
+        }
+    }
+}
+
+/// Styles the explorer draws with. Every widget takes its colors from here.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Theme {
+    name: ThemeName,
+    /// Headings, borders and primary keys (the brand gradient start)
+    accent: Style,
+    secondary: Style,
+    tertiary: Style,
+    /// Body text
+    text: Style,
+    /// Hints, labels and other de-emphasised text
+    muted: Style,
+    /// The selected row of a list
+    highlight: Style,
+    /// Query terms found in source chunks
+    search_match: Style,
+    error: Style,
+    warning: Style,
+    /// Status line confirmations
+    success: Style,
+}
+
+impl Theme {
+    const DARK: Theme = Theme {
+        name: ThemeName::Dark,
+        accent: Style::new().fg(BRAND_START),
+        secondary: Style::new().fg(BRAND_MID),
+        tertiary: Style::new().fg(BRAND_END),
+        text: Style::new().fg(Color::White),
+        muted: Style::new().fg(Color::DarkGray),
+        highlight: Style::new().bg(Color::DarkGray).add_modifier(Modifier::BOLD),
+        search_match: Style::new().fg(Color::Black).bg(BRAND_MID).add_modifier(Modifier::BOLD),
+        error: Style::new().fg(Color::Red),
+        warning: Style::new().fg(Color::Yellow),
+        success: Style::new().fg(BRAND_MID),
+    };
+
+    const LIGHT: Theme = Theme {
+        name: ThemeName::Light,
+        accent: Style::new().fg(Color::Rgb(0, 82, 155)),
+        secondary: Style::new().fg(Color::Rgb(0, 96, 135)),
+        tertiary: Style::new().fg(Color::Rgb(0, 105, 108)),
+        text: Style::new().fg(Color::Black),
+        muted: Style::new().fg(Color::Rgb(100, 100, 100)),
+        highlight: Style::new().bg(Color::Rgb(205, 225, 245)).add_modifier(Modifier::BOLD),
+        search_match: Style::new().fg(Color::Black).bg(Color::Rgb(255, 220, 110)),
+        error: Style::new().fg(Color::Rgb(180, 0, 0)),
+        warning: Style::new().fg(Color::Rgb(150, 90, 0)),
+        success: Style::new().fg(Color::Rgb(0, 110, 40)),
+    };
+
+    /// Uses the terminal's own colors throughout
+    const MONOCHROME: Theme = Theme {
+        name: ThemeName::Monochrome,
+        accent: Style::new().add_modifier(Modifier::BOLD),
+        secondary: Style::new(),
+        tertiary: Style::new(),
+        text: Style::new(),
+        muted: Style::new(),
+        highlight: Style::new().add_modifier(Modifier::REVERSED),
+        search_match: Style::new().add_modifier(Modifier::REVERSED),
+        error: Style::new().add_modifier(Modifier::BOLD),
+        warning: Style::new().add_modifier(Modifier::BOLD),
+        success: Style::new(),
+    };
+
+    fn named(name: ThemeName) -> Theme {
+        match name {
+            ThemeName::Dark => Theme::DARK,
+            ThemeName::Light => Theme::LIGHT,
+            ThemeName::Monochrome => Theme::MONOCHROME,
+        }
+    }
+
+    /// The next built-in theme, for `t`
+    fn next(&self) -> Theme {
+        Theme::named(match self.name {
+            ThemeName::Dark => ThemeName::Light,
+            ThemeName::Light => ThemeName::Monochrome,
+            ThemeName::Monochrome => ThemeName::Dark,
+        })
+    }
+}
+
+/// Two clicks on the same row within this long count as a double click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// List responses the explorer reuses when revisiting a view or page. Auto-refresh and
+/// `R` always ask the server, and any write clears the cache.
+const EXPLORER_CACHE: CacheConfig = CacheConfig {
+    max_entries: 200,
+    ttl: Duration::from_secs(30),
+};
+
+/// Auto-refresh waits this long after the last key press or click
+const REFRESH_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Rows fetched per page in the Memories, Entities and Documents views, unless changed
+pub const LIST_PAGE_SIZE: i64 = 100;
+
+/// Page sizes the options popup and `page_size` in the config allow, and its step
+const PAGE_SIZE_RANGE: std::ops::RangeInclusive<i64> = 25..=500;
+const PAGE_SIZE_STEP: i64 = 25;
+
+/// A page size brought into the range the explorer loads
+pub fn clamp_page_size(size: i64) -> i64 {
+    size.clamp(*PAGE_SIZE_RANGE.start(), *PAGE_SIZE_RANGE.end())
+}
+
+/// Chunks fetched per request in the document detail view
+const DOCUMENT_CHUNK_PAGE_SIZE: u64 = 25;
+
+/// Queries kept in the explorer's query history
+const QUERY_HISTORY_LIMIT: usize = 200;
+
+const QUERY_HISTORY_FILE_NAME: &str = "explorer_history.json";
+
+const SESSION_FILE_NAME: &str = "explorer_session.json";
+
+/// Layout of the session file; files of any other version are ignored
+const SESSION_VERSION: u32 = 1;
+
+/// Width of the longest stage bar in the trace pane
+const TRACE_BAR_WIDTH: usize = 30;
+
+/// Token budget for the source chunks returned with explorer recalls
+const RECALL_CHUNK_MAX_TOKENS: i64 = 8192;
+
+/// Memory totals kept for the Stats view's sparkline, one per refresh
+const STATS_HISTORY_LIMIT: usize = 120;
+
+/// Fact type hints the new-memory popup offers, as `memory add --type` accepts
+const MEMORY_TYPE_HINTS: &[&str] = &["world", "experience", "observation"];
+
+/// ID of the row standing in for a memory being added, until the list reloads
+const SAVING_ID: &str = "(saving)";
+
+/// Untyped memory fields shown in the detail popup's header block; the other untyped ones
+/// are listed as metadata
+const MEMORY_DETAIL_FIELDS: &[&str] = &["tags"];
+
 /// Main view types (like k9s contexts)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "bank_id", rename_all = "lowercase")]
 enum View {
     Banks,
     Memories(String),  // bank_id
     Entities(String),  // bank_id
     Documents(String), // bank_id
     Query(String),     // bank_id - combines recall and reflect
+    Operations(String), // bank_id - background ingestion and processing
+    Profile(String),    // bank_id - name, mission and disposition
+    Stats(String),      // bank_id - counts dashboard
 }
 
 impl View {
@@ -43,19 +262,43 @@ impl View {
             View::Entities(_) => "Entities",
             View::Documents(_) => "Documents",
             View::Query(_) => "Query",
+            View::Operations(_) => "Operations",
+            View::Profile(_) => "Profile",
+            View::Stats(_) => "Stats",
         }
     }
 
     fn bank_id(&self) -> Option<&str> {
         match self {
             View::Banks => None,
-            View::Memories(id) | View::Entities(id) | View::Documents(id) | View::Query(id) => Some(id),
+            View::Memories(id)
+            | View::Entities(id)
+            | View::Documents(id)
+            | View::Query(id)
+            | View::Operations(id)
+            | View::Profile(id)
+            | View::Stats(id) => Some(id),
+        }
+    }
+
+    /// Which of the key bindings apply here
+    fn scope(&self) -> Scope {
+        match self {
+            View::Banks => Scope::BANKS,
+            View::Memories(_) => Scope::MEMORIES,
+            View::Entities(_) => Scope::ENTITIES,
+            View::Documents(_) => Scope::DOCUMENTS,
+            View::Query(_) => Scope::QUERY,
+            View::Operations(_) => Scope::OPERATIONS,
+            View::Profile(_) => Scope::PROFILE,
+            View::Stats(_) => Scope::STATS,
         }
     }
 }
 
 /// Query mode for the Query view
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum QueryMode {
     Recall,
     Reflect,
@@ -66,787 +309,5156 @@ enum QueryMode {
 enum InputMode {
     Normal,
     Query,
+    Filter,
+    /// Picking a past query (Ctrl+R while typing one)
+    History,
+    /// Filling in the bank create, rename or delete popup
+    Bank,
+    /// Naming the file `E` exports the current list to
+    Export,
+    /// Writing a new memory in the Memories view
+    Memory,
 }
 
-/// Query result from background thread
-enum QueryResult {
-    Recall(Result<Vec<RecallResult>, String>),
-    Reflect(Result<String, String>),
+/// Background request slots. A new request replaces the one in flight in the
+/// same slot, so a slow page load never overwrites a newer one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RequestSlot {
+    List,
+    Detail,
+    Chunks,
+    Query,
+    /// The server version, fetched once at startup
+    Version,
+    /// Writing an export file; runs locally, so it says nothing about the server
+    Export,
+    /// Retaining a memory typed in the Memories view
+    Retain,
+    /// Deleting the documents confirmed in the delete modal
+    Delete,
+    /// Looking up the documents of memories picked for deletion
+    DeleteLookup,
+    /// Creating, renaming or deleting a bank, or editing its profile
+    Bank,
 }
 
-/// Application state
-struct App {
-    client: ApiClient,
-    view: View,
-    view_history: Vec<View>,
-
-    // List states
-    banks: Vec<BankListItem>,
-    banks_state: ListState,
-    selected_bank_id: Option<String>,
-
-    memories: Vec<Map<String, Value>>,
-    memories_state: ListState,
-    viewing_memory: Option<Map<String, Value>>,
-    memories_limit: i64,
-    memories_offset: i64,
-    horizontal_scroll: usize,
-
-    entities: Vec<EntityListItem>,
-    entities_state: ListState,
-    viewing_entity: Option<EntityListItem>,
-
-    documents: Vec<Map<String, Value>>,
-    documents_state: ListState,
-    viewing_document: Option<Map<String, Value>>,
-
-    // Query state (unified recall/reflect)
-    query_mode: QueryMode,
-    query_text: String,
-    query_budget: Budget,
-    query_max_tokens: i64,
-    query_results: Vec<RecallResult>,
-    query_results_state: ListState,
-    query_response: String,
-    viewing_recall_result: Option<RecallResult>,
+impl RequestSlot {
+    /// Requests that change something, so quitting asks first while they run. None
+    /// can be taken back once started: the request has been sent and the file opened.
+    fn writes(self) -> bool {
+        matches!(
+            self,
+            RequestSlot::Retain | RequestSlot::Export | RequestSlot::Delete | RequestSlot::Bank
+        )
+    }
+}
 
-    // Input mode
-    input_mode: InputMode,
+/// A request running on a background thread
+struct InFlight {
+    generation: u64,
+    label: &'static str,
+    /// Shown in the footer and cancellable with Esc; auto-refreshes run quietly
+    foreground: bool,
+}
 
-    // Status messages
-    status_message: String,
-    error_message: String,
+/// Where the list selection lands once a page arrives
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Landing {
+    Keep,
+    First,
+    Last,
+}
 
-    // Help visibility
-    show_help: bool,
+/// A list load: the view and page requested, and where to put the selection
+struct ListRequest {
+    view: View,
+    paging: Paging,
+    landing: Landing,
+}
 
-    // Loading state
-    loading: bool,
+enum ListPage {
+    Banks(Vec<BankListItem>),
+    Memories(ListMemoryUnitsResponse),
+    Entities(EntityListResponse),
+    Documents(ListDocumentsResponse),
+    Operations(OperationsResponse),
+    Profile(BankProfileResponse),
+    Stats(AgentStats),
+}
 
-    // Auto-refresh
-    auto_refresh_enabled: bool,
-    last_refresh: Instant,
-    refresh_interval: Duration,
+/// Result of a background request, sent back to the event loop
+enum Response {
+    List {
+        request: ListRequest,
+        result: Result<ListPage>,
+    },
+    Entity(Result<EntityDrilldown>),
+    Document(Result<(DocumentResponse, ListChunksResponse)>),
+    Chunks {
+        document_id: String,
+        result: Result<ListChunksResponse>,
+    },
+    Memory(Result<Value>),
+    Chunk(Result<ChunkResponse>),
+    Recall(Result<RecallResponse>),
+    Reflect(Result<ReflectResponse>),
+    Version(Result<VersionResponse>),
+    /// Where an export was written and how many rows it holds
+    Export(Result<(PathBuf, usize)>),
+    /// A retained memory; `None` under dry-run. The draft reopens if it failed.
+    Retained {
+        bank_id: String,
+        draft: MemoryDraft,
+        result: Result<Option<MemoryPutResult>>,
+    },
+    /// One result per confirmed document, in order; `false` under dry-run
+    Deleted {
+        pending: PendingDelete,
+        results: Vec<Result<bool>>,
+    },
+    /// The source documents of memories picked for deletion, each with how many of
+    /// the picked memories it holds
+    DeleteLookup {
+        bank_id: String,
+        documents: Vec<(String, usize, Result<DocumentResponse>)>,
+        without_document: usize,
+    },
+    /// A bank popup submission; `false` under dry-run. The popup reopens if it failed.
+    Bank {
+        dialog: BankDialog,
+        verb: &'static str,
+        done: String,
+        result: Result<bool>,
+    },
+}
 
-    // Background query receiver
-    query_receiver: Option<Receiver<QueryResult>>,
+impl Response {
+    fn error(&self) -> Option<&anyhow::Error> {
+        match self {
+            Response::List { result, .. } => result.as_ref().err(),
+            Response::Entity(result) => result.as_ref().err(),
+            Response::Document(result) => result.as_ref().err(),
+            Response::Chunks { result, .. } => result.as_ref().err(),
+            Response::Memory(result) => result.as_ref().err(),
+            Response::Chunk(result) => result.as_ref().err(),
+            Response::Recall(result) => result.as_ref().err(),
+            Response::Reflect(result) => result.as_ref().err(),
+            Response::Version(result) => result.as_ref().err(),
+            Response::Export(result) => result.as_ref().err(),
+            Response::Retained { result, .. } => result.as_ref().err(),
+            Response::Deleted { results, .. } => results.iter().find_map(|r| r.as_ref().err()),
+            Response::DeleteLookup { documents, .. } => {
+                documents.iter().find_map(|(_, _, r)| r.as_ref().err())
+            }
+            Response::Bank { result, .. } => result.as_ref().err(),
+        }
+    }
 }
 
-impl App {
-    fn new(client: ApiClient) -> Self {
-        let mut app = Self {
-            client,
-            view: View::Banks,
-            view_history: Vec::new(),
+/// The server the explorer talks to and how the last call went, shown in the header
+#[derive(Debug, Clone, Default)]
+struct ConnectionStatus {
+    base_url: String,
+    server_version: Option<String>,
+    last_latency: Option<Duration>,
+    /// Set when a call could not reach the server, until one succeeds again
+    disconnected: bool,
+    cache: Option<CacheStats>,
+}
 
-            banks: Vec::new(),
-            banks_state: ListState::default(),
-            selected_bank_id: None,
+impl ConnectionStatus {
+    fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            ..Default::default()
+        }
+    }
 
-            memories: Vec::new(),
-            memories_state: ListState::default(),
-            viewing_memory: None,
-            memories_limit: 500,
-            memories_offset: 0,
-            horizontal_scroll: 0,
+    /// Record a finished call; API errors still prove the server is reachable
+    fn record(&mut self, elapsed: Duration, error: Option<&anyhow::Error>) {
+        self.last_latency = Some(elapsed);
+        self.disconnected = error.is_some_and(is_unreachable);
+    }
 
-            entities: Vec::new(),
-            entities_state: ListState::default(),
-            viewing_entity: None,
+    /// Run a call on this thread and record it
+    fn time<T>(&mut self, call: impl FnOnce() -> Result<T>) -> Result<T> {
+        let started = Instant::now();
+        let result = call();
+        self.record(started.elapsed(), result.as_ref().err());
+        result
+    }
 
-            documents: Vec::new(),
-            documents_state: ListState::default(),
-            viewing_document: None,
+    /// Header segment such as "http://localhost:8888 · v0.4.2 · 35ms"
+    fn describe(&self) -> String {
+        let mut parts = vec![self.base_url.clone()];
+        if let Some(version) = &self.server_version {
+            parts.push(format!("v{}", version.trim_start_matches('v')));
+        }
+        if let Some(latency) = self.last_latency {
+            parts.push(format!("{}ms", latency.as_millis()));
+        }
+        // Lists answered from the cache, with or without a 304 from the server
+        if let Some(cache) = self.cache {
+            let cached = cache.hits + cache.revalidated;
+            let total = cached + cache.misses;
+            if total > 0 {
+                parts.push(format!("cache {}/{}", cached, total));
+            }
+        }
+        if self.disconnected {
+            parts.insert(0, "DISCONNECTED".to_string());
+        }
+        parts.join(" · ")
+    }
+}
 
-            query_mode: QueryMode::Recall,
-            query_text: String::new(),
-            query_budget: Budget::Mid,
-            query_max_tokens: 4096,
-            query_results: Vec::new(),
-            query_results_state: ListState::default(),
-            query_response: String::new(),
-            viewing_recall_result: None,
+/// The query history popup: typing narrows the entries, newest first
+struct HistoryPicker {
+    filter: TextInput,
+    state: ListState,
+}
 
-            input_mode: InputMode::Normal,
-            status_message: String::from("Select a bank to start. Press ? for help"),
-            error_message: String::new(),
-            show_help: false,
-            loading: false,
+/// A bordered list as last drawn: the rows its items occupy and the item on the first row
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ListArea {
+    rows: Rect,
+    first: isize,
+    header_rows: usize,
+}
 
-            auto_refresh_enabled: true,
-            last_refresh: Instant::now(),
-            refresh_interval: Duration::from_secs(5),
+impl ListArea {
+    /// `header_rows` counts rows drawn as list items above the data, such as column titles
+    fn new(area: Rect, offset: usize, header_rows: usize) -> Self {
+        Self {
+            rows: area.inner(Margin::new(1, 1)),
+            first: offset as isize - header_rows as isize,
+            header_rows,
+        }
+    }
 
-            query_receiver: None,
-        };
+    /// The rows of a table, below its column header that stays in place
+    fn below_header(area: Rect, offset: usize) -> Self {
+        let mut list = Self::new(area, offset, 0);
+        list.rows.y += 1;
+        list.rows.height = list.rows.height.saturating_sub(1);
+        list
+    }
 
-        // Select first item by default
-        app.banks_state.select(Some(0));
-        app.memories_state.select(Some(0));
-        app.entities_state.select(Some(0));
-        app.documents_state.select(Some(0));
-        app.query_results_state.select(Some(0));
+    /// Data rows that fit on screen at once
+    fn page_len(&self) -> usize {
+        (self.rows.height as usize).saturating_sub(self.header_rows).max(1)
+    }
 
-        app
+    /// Index of the item drawn at a screen position, if one is
+    fn item_at(&self, column: u16, row: u16, len: usize) -> Option<usize> {
+        if !self.rows.contains(Position::new(column, row)) {
+            return None;
+        }
+        let index = self.first + (row - self.rows.y) as isize;
+        (index >= 0 && (index as usize) < len).then_some(index as usize)
     }
+}
 
-    fn refresh(&mut self) -> Result<()> {
-        self.loading = true;
-        self.error_message.clear();
+/// A move of a list selection. Single steps wrap around the ends; pages and jumps stop there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ListNav {
+    Next,
+    Previous,
+    Down(usize),
+    Up(usize),
+    First,
+    Last,
+    /// A zero-based row, clamped to the list
+    To(usize),
+}
 
-        let result = match self.view.clone() {
-            View::Banks => self.load_banks(),
-            View::Memories(bank_id) => self.load_memories(&bank_id),
-            View::Entities(bank_id) => self.load_entities(&bank_id),
-            View::Documents(bank_id) => self.load_documents(&bank_id),
-            View::Query(_) => Ok(()), // Query is query-driven
-        };
+impl ListNav {
+    /// The selection after this move in a list of `len` rows; none in an empty list
+    fn target(self, selected: Option<usize>, len: usize) -> Option<usize> {
+        let last = len.checked_sub(1)?;
+        let current = selected.unwrap_or(0).min(last);
+        Some(match self {
+            ListNav::Next | ListNav::Previous if selected.is_none() => 0,
+            ListNav::Next if current == last => 0,
+            ListNav::Next => current + 1,
+            ListNav::Previous if current == 0 => last,
+            ListNav::Previous => current - 1,
+            ListNav::Down(rows) => current.saturating_add(rows).min(last),
+            ListNav::Up(rows) => current.saturating_sub(rows),
+            ListNav::First => 0,
+            ListNav::Last => last,
+            ListNav::To(row) => row.min(last),
+        })
+    }
 
-        self.loading = false;
+    fn apply(self, state: &mut ListState, len: usize) {
+        state.select(self.target(state.selected(), len));
+    }
+}
 
-        if let Err(e) = result {
-            self.error_message = format!("Error: {}", e);
-        }
+/// Where the last draw put what the mouse can act on
+#[derive(Debug, Default)]
+struct MouseAreas {
+    /// The list that has the selection in the current view
+    list: Option<ListArea>,
+    trace: Option<Rect>,
+    /// Each shortcut in the shortcuts bar and the key it stands for
+    shortcuts: Vec<(Rect, KeyEvent)>,
+}
 
-        Ok(())
+impl MouseAreas {
+    fn shortcut_at(&self, column: u16, row: u16) -> Option<KeyEvent> {
+        self.shortcuts
+            .iter()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, key)| *key)
     }
+}
 
-    fn toggle_auto_refresh(&mut self) {
-        self.auto_refresh_enabled = !self.auto_refresh_enabled;
-        if self.auto_refresh_enabled {
-            self.status_message = "Auto-refresh enabled (5s)".to_string();
-            self.last_refresh = Instant::now();
-        } else {
-            self.status_message = "Auto-refresh disabled".to_string();
-        }
+/// The key a shortcuts bar label stands for: the first of alternatives like "n/→",
+/// nothing for direction pairs like "↑↓"
+fn shortcut_key(label: &str) -> Option<KeyEvent> {
+    let label = label.split('/').next().unwrap_or(label);
+    if label.starts_with(['↑', '↓', '←', '→']) {
+        return None;
     }
+    KeyChord::parse(label).ok().map(|chord| chord.event())
+}
 
-    fn should_refresh(&self) -> bool {
-        self.auto_refresh_enabled && self.last_refresh.elapsed() >= self.refresh_interval
+/// Rename a bank and merge text into its background; false when a dry-run sent nothing
+fn update_profile(
+    client: &ApiClient,
+    bank_id: &str,
+    name: Option<&str>,
+    background: &str,
+) -> Result<bool> {
+    let mut sent = true;
+    if let Some(name) = name {
+        let plan = PlannedWrite::new("update_agent_name", bank_id);
+        sent &= executor::get()
+            .execute(plan, || client.update_agent_name(bank_id, name, false))?
+            .is_some();
     }
-
-    fn do_auto_refresh(&mut self) -> Result<()> {
-        if self.should_refresh() {
-            self.last_refresh = Instant::now();
-            self.refresh()?;
-        }
-        Ok(())
+    if !background.is_empty() {
+        let plan = PlannedWrite::new("add_background", bank_id);
+        sent &= executor::get()
+            .execute(plan, || {
+                client.add_background(bank_id, background, true, false)
+            })?
+            .is_some();
     }
+    Ok(sent)
+}
+
+/// What the bank popup in the Banks view does when submitted
+#[derive(Debug, Clone, PartialEq)]
+enum BankAction {
+    Create,
+    Rename(String),
+    Delete(String),
+    /// Rename and add background from the Profile view
+    EditProfile(String),
+}
 
-    fn load_banks(&mut self) -> Result<()> {
-        self.banks = self.client.list_agents(false)?;
+/// The bank popup. Create asks for an ID and a name, rename for the new name,
+/// delete for the bank ID typed out as confirmation, and the profile edit for a
+/// name and text to merge into the background.
+struct BankDialog {
+    action: BankAction,
+    fields: Vec<TextInput>,
+    focus: usize,
+}
 
-        if !self.banks.is_empty() && self.banks_state.selected().is_none() {
-            self.banks_state.select(Some(0));
+impl BankDialog {
+    fn labels(&self) -> &'static [&'static str] {
+        match self.action {
+            BankAction::Create => &["Bank ID", "Name (optional)"],
+            BankAction::Rename(_) => &["New name"],
+            BankAction::Delete(_) => &["Type the bank ID to confirm"],
+            BankAction::EditProfile(_) => &["Name", "Add to background"],
         }
-
-        self.status_message = format!("Loaded {} banks", self.banks.len());
-        Ok(())
     }
+}
 
-    fn load_memories(&mut self, bank_id: &str) -> Result<()> {
-        let response = self.client.list_memories(
-            bank_id,
-            None,
-            None,
-            Some(self.memories_limit),
-            Some(self.memories_offset),
-            false
-        )?;
-        self.memories = response.items;
+/// Fields of the new-memory popup, in Tab order
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum DraftField {
+    #[default]
+    Text,
+    Type,
+    Occurred,
+}
 
-        if !self.memories.is_empty() && self.memories_state.selected().is_none() {
-            self.memories_state.select(Some(0));
-        }
+/// The new-memory popup: the text, and optionally a type hint and when it happened
+#[derive(Debug, Clone, Default, PartialEq)]
+struct MemoryDraft {
+    text: TextInput,
+    /// Index into `MEMORY_TYPE_HINTS`; none leaves the type to extraction
+    fact_type: Option<usize>,
+    occurred: TextInput,
+    focus: DraftField,
+}
 
-        self.status_message = format!("Loaded {} memories (limit: {}, offset: {})",
-            self.memories.len(), self.memories_limit, self.memories_offset);
-        Ok(())
+impl MemoryDraft {
+    fn fact_type(&self) -> Option<&'static str> {
+        self.fact_type.map(|i| MEMORY_TYPE_HINTS[i])
     }
 
-    fn load_more_memories(&mut self) -> Result<()> {
-        if let View::Memories(bank_id) = &self.view {
-            let bank_id = bank_id.clone();
-            self.memories_offset += self.memories_limit;
-            self.load_memories(&bank_id)?;
-        }
-        Ok(())
+    /// Step the type hint through none and each of `MEMORY_TYPE_HINTS`
+    fn cycle_type(&mut self, forward: bool) {
+        let options = MEMORY_TYPE_HINTS.len() + 1;
+        let current = self.fact_type.map_or(0, |i| i + 1);
+        let step = if forward { 1 } else { options - 1 };
+        let next = (current + step) % options;
+        self.fact_type = next.checked_sub(1);
     }
+}
 
-    fn load_prev_memories(&mut self) -> Result<()> {
-        if let View::Memories(bank_id) = &self.view {
-            let bank_id = bank_id.clone();
-            self.memories_offset = (self.memories_offset - self.memories_limit).max(0);
-            self.load_memories(&bank_id)?;
-        }
-        Ok(())
-    }
+/// The `J` popup: the selected item as pretty-printed JSON
+struct JsonInspector {
+    title: String,
+    text: String,
+    scroll: u16,
+    /// Rows the popup showed when last drawn, for paging
+    height: u16,
+}
 
-    fn load_entities(&mut self, bank_id: &str) -> Result<()> {
-        let response = self.client.list_entities(bank_id, Some(100), None, false)?;
-        self.entities = response.items;
+/// A change to the open tabs, made by `Tabs` after a key press
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TabRequest {
+    Open,
+    Next,
+    Previous,
+    Select(usize),
+    /// Close the tab, asking first while a query is running unless `confirmed`
+    Close { confirmed: bool },
+}
 
-        if !self.entities.is_empty() && self.entities_state.selected().is_none() {
-            self.entities_state.select(Some(0));
-        }
+/// The answer to the quit confirmation, applied by `Tabs` across all tabs
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QuitChoice {
+    Now,
+    /// Quit once every write in progress has finished, unless one fails
+    WhenDone,
+    Stay,
+}
 
-        self.status_message = format!("Loaded {} entities", self.entities.len());
-        Ok(())
-    }
+/// Which text input `Ctrl+E` hands to the external editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditorTarget {
+    Query,
+    Memory,
+}
+
+/// The export popup: the file to write and its format
+struct ExportPrompt {
+    path: TextInput,
+    format: ExportFormat,
+    /// Set once the user was told the file exists; the next Enter replaces it
+    overwrite: bool,
+}
 
-    fn load_documents(&mut self, bank_id: &str) -> Result<()> {
-        let response = self.client.list_documents(bank_id, None, Some(100), Some(0), false)?;
-        self.documents = response.items;
+/// The rows of the current view, as `E` writes them
+#[derive(Debug, Clone)]
+struct ExportData {
+    /// Names the default file, like `recall-<bank>-<timestamp>.json`
+    kind: &'static str,
+    title: String,
+    items: Vec<Value>,
+}
 
-        if !self.documents.is_empty() && self.documents_state.selected().is_none() {
-            self.documents_state.select(Some(0));
+impl ExportData {
+    fn new<T: Serialize>(kind: &'static str, title: String, items: &[T]) -> Self {
+        Self {
+            kind,
+            title,
+            items: items.iter().filter_map(|item| serde_json::to_value(item).ok()).collect(),
         }
+    }
 
-        self.status_message = format!("Loaded {} documents", self.documents.len());
-        Ok(())
+    /// `<kind>-<bank>-<timestamp>.<ext>` in the working directory
+    fn default_path(&self, bank_id: &str, format: ExportFormat) -> String {
+        let bank: String = bank_id
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        format!("{}-{}-{}.{}", self.kind, bank, stamp, format.extension())
     }
 
-    fn execute_query(&mut self) {
-        if let View::Query(bank_id) = &self.view {
-            if self.query_text.is_empty() {
-                self.error_message = "Query cannot be empty".to_string();
-                return;
+    /// A section per row: its name or ID as the heading, its text, then its other fields
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title);
+        for (i, item) in self.items.iter().enumerate() {
+            let Some(fields) = item.as_object() else {
+                continue;
+            };
+            let heading_key = ["canonical_name", "id"]
+                .into_iter()
+                .find(|key| fields.get(*key).is_some_and(Value::is_string));
+            match heading_key.and_then(|key| fields[key].as_str()) {
+                Some(heading) => out.push_str(&format!("\n## {}. {}\n", i + 1, heading)),
+                None => out.push_str(&format!("\n## {}\n", i + 1)),
+            }
+            if let Some(text) = fields.get("text").and_then(Value::as_str) {
+                out.push_str(&format!("\n{}\n", text.trim_end()));
             }
 
-            self.loading = true;
-            self.error_message.clear();
-            self.input_mode = InputMode::Normal;
-
-            // Create channel for receiving results
-            let (tx, rx) = mpsc::channel();
-            self.query_receiver = Some(rx);
-
-            // Clone data for the thread
-            let client = self.client.clone();
-            let bank_id = bank_id.clone();
-            let query_mode = self.query_mode.clone();
-            let query_text = self.query_text.clone();
-            let query_budget = self.query_budget.clone();
-            let query_max_tokens = self.query_max_tokens;
-
-            // Spawn background thread
-            thread::spawn(move || {
-                match query_mode {
-                    QueryMode::Recall => {
-                        let request = RecallRequest {
-                            query: query_text,
-                            types: None,
-                            budget: Some(query_budget),
-                            max_tokens: query_max_tokens,
-                            trace: false,
-                            query_timestamp: None,
-                            include: None,
-                            tags: None,
-                            tags_match: TagsMatch::Any,
-                            tag_groups: None,
-                        };
-
-                        let result = client.recall(&bank_id, &request, false)
-                            .map(|r| r.results)
-                            .map_err(|e| e.to_string());
-
-                        let _ = tx.send(QueryResult::Recall(result));
-                    }
-                    QueryMode::Reflect => {
-                        let request = ReflectRequest {
-                            query: query_text,
-                            budget: Some(query_budget),
-                            context: None,
-                            max_tokens: 4096,
-                            include: None,
-                            response_schema: None,
-                            tags: None,
-                            tags_match: TagsMatch::Any,
-                            tag_groups: None,
-                            fact_types: None,
-                            exclude_mental_models: false,
-                            exclude_mental_model_ids: None,
-                        };
-
-                        let result = client.reflect(&bank_id, &request, false)
-                            .map(|r| r.text)
-                            .map_err(|e| e.to_string());
-
-                        let _ = tx.send(QueryResult::Reflect(result));
-                    }
+            let mut bullets = String::new();
+            for (key, value) in fields {
+                if key == "text" || Some(key.as_str()) == heading_key {
+                    continue;
                 }
-            });
+                let value = match value {
+                    Value::Null => continue,
+                    Value::Array(values) if values.is_empty() => continue,
+                    Value::String(s) => s.clone(),
+                    Value::Array(values) if values.iter().all(Value::is_string) => values
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    other => other.to_string(),
+                };
+                bullets.push_str(&format!("- **{}**: {}\n", key, value));
+            }
+            if !bullets.is_empty() {
+                out.push('\n');
+                out.push_str(&bullets);
+            }
         }
+        out
     }
 
-    fn check_query_result(&mut self) {
-        if let Some(receiver) = &self.query_receiver {
-            match receiver.try_recv() {
-                Ok(QueryResult::Recall(Ok(results))) => {
-                    self.query_results = results;
-                    if !self.query_results.is_empty() {
-                        self.query_results_state.select(Some(0));
-                    }
-                    self.loading = false;
-                    self.status_message = format!("Found {} results", self.query_results.len());
-                    self.query_receiver = None;
-                }
-                Ok(QueryResult::Recall(Err(e))) => {
-                    self.error_message = format!("Recall failed: {}", e);
-                    self.loading = false;
-                    self.query_receiver = None;
-                }
-                Ok(QueryResult::Reflect(Ok(text))) => {
-                    self.query_response = text;
-                    self.loading = false;
-                    self.status_message = "Reflection complete".to_string();
-                    self.query_receiver = None;
-                }
-                Ok(QueryResult::Reflect(Err(e))) => {
-                    self.error_message = format!("Reflect failed: {}", e);
-                    self.loading = false;
-                    self.query_receiver = None;
-                }
-                Err(TryRecvError::Empty) => {
-                    // Still waiting for result
-                }
-                Err(TryRecvError::Disconnected) => {
-                    self.error_message = "Query thread disconnected".to_string();
-                    self.loading = false;
-                    self.query_receiver = None;
-                }
+    /// A bullet per row with its type and date, for pasting into notes and chats
+    fn to_bullets(&self) -> String {
+        let mut out = String::new();
+        for item in &self.items {
+            let field = |keys: &[&str]| {
+                keys.iter().find_map(|key| item.get(*key).and_then(Value::as_str))
+            };
+            let text = field(&["text", "canonical_name", "id"]).unwrap_or_default();
+            let kind = field(&["fact_type", "type", "task_type", "content_type"]);
+            let date = field(&["occurred_start", "mentioned_at", "created_at", "last_seen"])
+                .map(|date| date.get(..10).unwrap_or(date));
+            let label: Vec<&str> = kind.into_iter().chain(date).collect();
+            out.push_str("- ");
+            if !label.is_empty() {
+                out.push_str(&format!("**{}** ", label.join(", ")));
             }
+            // Later lines of the text stay inside the bullet
+            out.push_str(&text.trim_end().replace('\n', "\n  "));
+            out.push('\n');
         }
+        out
     }
 
-    fn toggle_query_mode(&mut self) {
-        self.query_mode = match self.query_mode {
-            QueryMode::Recall => QueryMode::Reflect,
-            QueryMode::Reflect => QueryMode::Recall,
+    /// Write the rows to `path`, refusing to replace an existing file unless `overwrite`
+    fn write(&self, path: &Path, format: ExportFormat, overwrite: bool) -> Result<()> {
+        let contents = match format {
+            ExportFormat::Json => serde_json::to_string_pretty(&self.items)? + "\n",
+            ExportFormat::Md => self.to_markdown(),
         };
-        self.status_message = format!("Switched to {} mode", match self.query_mode {
-            QueryMode::Recall => "Recall",
-            QueryMode::Reflect => "Reflect",
-        });
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .create_new(!overwrite)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("Could not create {}", path.display()))?;
+        file.write_all(contents.as_bytes())
+            .with_context(|| format!("Could not write {}", path.display()))
     }
+}
 
-    fn cycle_budget(&mut self) {
-        self.query_budget = match self.query_budget {
-            Budget::Low => Budget::Mid,
-            Budget::Mid => Budget::High,
-            Budget::High => Budget::Low,
-        };
-        self.status_message = format!("Budget: {:?}", self.query_budget);
-    }
+/// An entity opened from the Entities view, with the memories that mention it
+struct EntityDrilldown {
+    entity: EntityDetailResponse,
+    aliases: Vec<String>,
+    memories: Vec<RecallResult>,
+    memories_state: ListState,
+}
 
-    fn adjust_max_tokens(&mut self, increase: bool) {
-        if increase {
-            self.query_max_tokens = (self.query_max_tokens + 1024).min(16384);
-        } else {
-            self.query_max_tokens = (self.query_max_tokens - 1024).max(512);
+/// A document opened from the Documents view; chunks are fetched a page at a time
+struct DocumentDetail {
+    document: DocumentResponse,
+    chunks: Vec<ChunkResponse>,
+    chunks_total: i64,
+    selected_chunk: usize,
+    chunk_scroll: u16,
+    show_full_text: bool,
+    text_scroll: u16,
+    /// Visible rows of the chunk or text panel, set on render
+    page_height: u16,
+    /// Chunk to select once the page being fetched for it arrives
+    chunk_target: Option<usize>,
+}
+
+/// Offset paging for a list view, with the total reported by the list endpoint
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Paging {
+    limit: i64,
+    offset: i64,
+    total: i64,
+}
+
+impl Paging {
+    fn new() -> Self {
+        Self {
+            limit: LIST_PAGE_SIZE,
+            offset: 0,
+            total: 0,
         }
-        self.status_message = format!("Max tokens: {}", self.query_max_tokens);
     }
 
-    fn scroll_left(&mut self) {
-        self.horizontal_scroll = self.horizontal_scroll.saturating_sub(10);
+    fn has_next(&self) -> bool {
+        self.offset + self.limit < self.total
     }
 
-    fn scroll_right(&mut self) {
-        self.horizontal_scroll = (self.horizontal_scroll + 10).min(200);
+    fn has_prev(&self) -> bool {
+        self.offset > 0
     }
 
-    fn reset_horizontal_scroll(&mut self) {
-        self.horizontal_scroll = 0;
+    /// Title fragment such as "showing 101–200 of 430"
+    fn describe(&self, shown: usize) -> String {
+        if shown == 0 {
+            return format!("showing 0 of {}", self.total);
+        }
+        let first = self.offset + 1;
+        let last = self.offset + shown as i64;
+        format!("showing {}–{} of {}", first, last, self.total.max(last))
     }
+}
 
-    fn next_item(&mut self) {
-        match &self.view {
-            View::Banks => {
-                let i = match self.banks_state.selected() {
-                    Some(i) => {
-                        if i >= self.banks.len().saturating_sub(1) {
-                            0
-                        } else {
-                            i + 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.banks_state.select(Some(i));
-            }
-            View::Memories(_) => {
-                let i = match self.memories_state.selected() {
-                    Some(i) => {
-                        if i >= self.memories.len().saturating_sub(1) {
-                            0
-                        } else {
-                            i + 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.memories_state.select(Some(i));
-            }
-            View::Entities(_) => {
-                let i = match self.entities_state.selected() {
-                    Some(i) => {
-                        if i >= self.entities.len().saturating_sub(1) {
-                            0
-                        } else {
-                            i + 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.entities_state.select(Some(i));
-            }
-            View::Documents(_) => {
-                let i = match self.documents_state.selected() {
-                    Some(i) => {
-                        if i >= self.documents.len().saturating_sub(1) {
-                            0
-                        } else {
-                            i + 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.documents_state.select(Some(i));
-            }
-            View::Query(_) => {
-                if self.query_mode == QueryMode::Recall {
-                    let i = match self.query_results_state.selected() {
-                        Some(i) => {
-                            if i >= self.query_results.len().saturating_sub(1) {
-                                0
-                            } else {
-                                i + 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.query_results_state.select(Some(i));
-                }
-            }
+/// Values `+`/`-` step the max tokens of a recall through, and the options popup those of a reflect
+const MAX_TOKEN_STEPS: &[i64] = &[512, 1024, 2048, 4096, 8192, 16384, 32768];
+
+/// The next value of `MAX_TOKEN_STEPS` up or down from `tokens`, which may lie between steps;
+/// `tokens` itself past the last step
+fn step_tokens(tokens: i64, increase: bool) -> i64 {
+    let next = if increase {
+        MAX_TOKEN_STEPS.iter().find(|&&step| step > tokens)
+    } else {
+        MAX_TOKEN_STEPS.iter().rev().find(|&&step| step < tokens)
+    };
+    next.copied().unwrap_or(tokens)
+}
+
+/// Budget, token limit and fact types for queries against one bank, kept for the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QuerySettings {
+    budget: Budget,
+    max_tokens: i64,
+    /// Fact types recall searches; empty leaves the choice to the server
+    #[serde(default)]
+    types: Vec<FactTypesItem>,
+}
+
+impl Default for QuerySettings {
+    fn default() -> Self {
+        Self {
+            budget: Budget::Mid,
+            max_tokens: 4096,
+            types: Vec::new(),
         }
     }
+}
 
-    fn previous_item(&mut self) {
-        match &self.view {
-            View::Banks => {
-                let i = match self.banks_state.selected() {
-                    Some(i) => {
-                        if i == 0 {
-                            self.banks.len().saturating_sub(1)
-                        } else {
-                            i - 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.banks_state.select(Some(i));
-            }
-            View::Memories(_) => {
-                let i = match self.memories_state.selected() {
-                    Some(i) => {
-                        if i == 0 {
-                            self.memories.len().saturating_sub(1)
-                        } else {
-                            i - 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.memories_state.select(Some(i));
-            }
-            View::Entities(_) => {
-                let i = match self.entities_state.selected() {
-                    Some(i) => {
-                        if i == 0 {
-                            self.entities.len().saturating_sub(1)
-                        } else {
-                            i - 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.entities_state.select(Some(i));
-            }
-            View::Documents(_) => {
-                let i = match self.documents_state.selected() {
-                    Some(i) => {
-                        if i == 0 {
-                            self.documents.len().saturating_sub(1)
-                        } else {
-                            i - 1
-                        }
-                    }
-                    None => 0,
-                };
-                self.documents_state.select(Some(i));
-            }
-            View::Query(_) => {
-                if self.query_mode == QueryMode::Recall {
-                    let i = match self.query_results_state.selected() {
-                        Some(i) => {
-                            if i == 0 {
-                                self.query_results.len().saturating_sub(1)
-                            } else {
-                                i - 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.query_results_state.select(Some(i));
-                }
-            }
-        }
+impl QuerySettings {
+    fn cycle_budget(&mut self) {
+        self.budget = match self.budget {
+            Budget::Low => Budget::Mid,
+            Budget::Mid => Budget::High,
+            Budget::High | Budget::Unknown(_) => Budget::Low,
+        };
     }
 
-    fn enter_view(&mut self) -> Result<()> {
-        match &self.view {
-            View::Banks => {
-                if let Some(i) = self.banks_state.selected() {
-                    if let Some(bank) = self.banks.get(i) {
-                        let bank_id = bank.bank_id.clone();
-                        self.selected_bank_id = Some(bank_id.clone());
-                        self.view_history.push(self.view.clone());
-                        self.view = View::Memories(bank_id.clone());
-                        self.load_memories(&bank_id)?;
-                    }
-                }
-            }
-            View::Memories(_) => {
-                if let Some(i) = self.memories_state.selected() {
-                    if let Some(memory) = self.memories.get(i) {
-                        self.viewing_memory = Some(memory.clone());
-                        self.status_message = "Viewing memory details (Esc to close)".to_string();
-                    }
-                }
-            }
-            View::Entities(_) => {
-                if let Some(i) = self.entities_state.selected() {
-                    if let Some(entity) = self.entities.get(i).cloned() {
-                        self.viewing_entity = Some(entity);
-                        self.status_message = "Viewing entity details (Esc to close)".to_string();
-                    }
-                }
-            }
-            View::Documents(bank_id) => {
-                if let Some(i) = self.documents_state.selected() {
-                    if let Some(doc) = self.documents.get(i) {
-                        // Fetch full document content
-                        let doc_id = doc.get("id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
-
-                        if !doc_id.is_empty() {
-                            match self.client.get_document(bank_id, doc_id, false) {
-                                Ok(full_doc) => {
-                                    // Convert to Map for display
-                                    let doc_map: Map<String, Value> = serde_json::from_value(
-                                        serde_json::to_value(full_doc)?
-                                    )?;
-                                    self.viewing_document = Some(doc_map);
-                                    self.status_message = format!("Viewing document: {}", doc_id);
-                                }
-                                Err(e) => {
-                                    self.error_message = format!("Failed to load document: {}", e);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            View::Query(_) => {
-                // View recall result details if in recall mode
-                if self.query_mode == QueryMode::Recall {
-                    if let Some(i) = self.query_results_state.selected() {
-                        if let Some(result) = self.query_results.get(i).cloned() {
-                            self.viewing_recall_result = Some(result);
-                            self.status_message = "Viewing recall result (Esc to close)".to_string();
-                        }
-                    }
-                }
-            }
-        }
-        Ok(())
+    /// Move max tokens to the next step up or down, snapping values between steps
+    fn step_max_tokens(&mut self, increase: bool) {
+        self.max_tokens = step_tokens(self.max_tokens, increase);
     }
 
-    fn go_back(&mut self) {
-        // If viewing a detail view, close it first
-        if self.viewing_memory.is_some() {
-            self.viewing_memory = None;
-            self.status_message = "Closed memory view".to_string();
-            return;
-        }
-        if self.viewing_entity.is_some() {
-            self.viewing_entity = None;
-            self.status_message = "Closed entity view".to_string();
-            return;
-        }
-        if self.viewing_document.is_some() {
-            self.viewing_document = None;
-            self.status_message = "Closed document view".to_string();
-            return;
+    /// Server default → world → experience → observation → all types
+    fn cycle_types(&mut self) {
+        self.types = match self.types.as_slice() {
+            [] => vec![FactTypesItem::World],
+            [FactTypesItem::World] => vec![FactTypesItem::Experience],
+            [FactTypesItem::Experience] => vec![FactTypesItem::Observation],
+            [FactTypesItem::Observation] => FACT_TYPES.to_vec(),
+            _ => Vec::new(),
+        };
+    }
+
+    /// The type filter as shown in the Query title: "default", "all" or the types
+    fn describe_types(&self) -> String {
+        if self.types.is_empty() {
+            "default".to_string()
+        } else if FACT_TYPES.iter().all(|t| self.types.contains(t)) {
+            "all".to_string()
+        } else {
+            let types: Vec<&str> = self.types.iter().map(|t| t.as_str()).collect();
+            types.join(",")
         }
-        if self.viewing_recall_result.is_some() {
-            self.viewing_recall_result = None;
-            self.status_message = "Closed recall result view".to_string();
-            return;
+    }
+
+    /// Title fragment such as "Types: world · Budget: Mid · Max tokens: 4096"
+    fn describe(&self, mode: &QueryMode) -> String {
+        match mode {
+            QueryMode::Recall => format!(
+                "Types: {} · Budget: {:?} · Max tokens: {}",
+                self.describe_types(),
+                self.budget,
+                self.max_tokens
+            ),
+            QueryMode::Reflect => format!("Budget: {:?}", self.budget),
         }
+    }
+}
 
-        // Otherwise go back to previous view
-        if let Some(prev_view) = self.view_history.pop() {
-            self.view = prev_view;
-            let _ = self.refresh();
+/// What the confirmation modal in the Operations view does
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OperationAction {
+    Retry,
+    Cancel,
+}
+
+/// An operation awaiting confirmation of a retry or cancel
+struct PendingOperation {
+    action: OperationAction,
+    bank_id: String,
+    operation: Operation,
+}
+
+/// Documents awaiting confirmation in the delete modal
+struct PendingDelete {
+    bank_id: String,
+    /// ID and a short preview of each row
+    items: Vec<(String, String)>,
+    /// Memories picked in the Memories view that these documents hold; 0 when the
+    /// documents were picked themselves
+    memories: usize,
+    /// Picked memories with no source document, which can't be deleted
+    without_document: usize,
+}
+
+/// A list narrowed to the items related to another, by a jump from a detail popup
+#[derive(Debug, Clone, PartialEq)]
+enum Related {
+    /// `m` in a document: the memories extracted from it
+    DocumentMemories(String),
+    /// `e` in a memory: the entities it mentions, by name
+    MemoryEntities { memory_id: String, names: Vec<String> },
+}
+
+impl Related {
+    /// Title fragment naming what the list is narrowed to
+    fn describe(&self) -> String {
+        match self {
+            Related::DocumentMemories(document_id) => format!(" of document {}", document_id),
+            Related::MemoryEntities { memory_id, .. } => format!(" in memory {}", memory_id),
         }
     }
+}
 
-    fn switch_to_view(&mut self, new_view: View) -> Result<()> {
-        if self.view != new_view {
-            self.view_history.push(self.view.clone());
-            self.view = new_view;
-            self.refresh()?;
+/// Entity names listed on a memory, as `"Alice (PERSON), Google (ORGANIZATION)"` or as a list
+fn memory_entity_names(memory: &MemoryListItem) -> Vec<String> {
+    let strip_type = |name: &str| match name.trim().rsplit_once(" (") {
+        Some((name, kind)) if kind.ends_with(')') => name.trim().to_string(),
+        _ => name.trim().to_string(),
+    };
+    let names: Vec<String> = match memory.extra.get("entities") {
+        Some(Value::String(list)) => list.split(',').map(strip_type).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(name) => Some(strip_type(name)),
+                Value::Object(entity) => ["canonical_name", "name", "text"]
+                    .iter()
+                    .find_map(|key| entity.get(*key).and_then(Value::as_str))
+                    .map(strip_type),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    names.into_iter().filter(|name| !name.is_empty()).collect()
+}
+
+/// How one of the Memories, Entities and Documents views loads its rows
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LoadOptions {
+    page_size: i64,
+    /// Passed on as the API client's verbose flag
+    verbose: bool,
+}
+
+/// A row of the options popup
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OptionField {
+    PageSize,
+    Verbose,
+    RecallChunks,
+    ReflectMaxTokens,
+}
+
+/// The options popup (`O`): how the current list loads, whether recalls bring chunks and
+/// how long reflect answers may be
+#[derive(Debug, Clone)]
+struct OptionsPopup {
+    /// The current list's options; `None` in views without one
+    load: Option<LoadOptions>,
+    recall_chunks: bool,
+    reflect_max_tokens: i64,
+    field: usize,
+}
+
+impl OptionsPopup {
+    fn fields(&self) -> &'static [OptionField] {
+        if self.load.is_some() {
+            &[
+                OptionField::PageSize,
+                OptionField::Verbose,
+                OptionField::RecallChunks,
+                OptionField::ReflectMaxTokens,
+            ]
+        } else {
+            &[OptionField::RecallChunks, OptionField::ReflectMaxTokens]
         }
-        Ok(())
     }
 
-    fn delete_selected_document(&mut self) -> Result<()> {
-        if let View::Documents(bank_id) = &self.view {
-            if let Some(i) = self.documents_state.selected() {
-                if let Some(doc) = self.documents.get(i) {
-                    let doc_id = doc.get("id")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
-
-                    if !doc_id.is_empty() {
-                        match self.client.delete_document(bank_id, doc_id, false) {
-                            Ok(_) => {
-                                self.status_message = format!("Deleted document: {}", doc_id);
-                                self.refresh()?;
-                            }
-                            Err(e) => {
-                                self.error_message = format!("Failed to delete document: {}", e);
-                            }
-                        }
-                    }
-                }
+    /// `←`/`→` on the focused row: step the page size or max tokens, or flip a switch
+    fn adjust(&mut self, forward: bool) {
+        let field = self.fields()[self.field];
+        match (field, self.load.as_mut()) {
+            (OptionField::PageSize, Some(load)) => {
+                let step = if forward { PAGE_SIZE_STEP } else { -PAGE_SIZE_STEP };
+                load.page_size = clamp_page_size(load.page_size + step);
             }
+            (OptionField::Verbose, Some(load)) => load.verbose = !load.verbose,
+            (OptionField::RecallChunks, _) => self.recall_chunks = !self.recall_chunks,
+            (OptionField::ReflectMaxTokens, _) => {
+                self.reflect_max_tokens = step_tokens(self.reflect_max_tokens, forward);
+            }
+            _ => {}
         }
-        Ok(())
     }
 }
 
-fn ui(f: &mut Frame, app: &mut App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(5),  // Shortcuts bar (context + shortcuts, max 3 rows + 2 border)
-            Constraint::Length(3),  // Header
-            Constraint::Min(0),     // Main content
-            Constraint::Length(1),  // Footer/status only (no border)
-        ])
-        .split(f.area());
+/// Follow mode of the Memories view (`F`): the newest memories reload like `tail -f`
+#[derive(Debug, Default)]
+struct Follow {
+    /// Set once the selection leaves the newest memory; `F` resumes
+    paused: bool,
+    /// IDs of the memories shown so far; `None` until the first load
+    seen: Option<HashSet<String>>,
+    /// Memories that came with the latest load, highlighted until the next one
+    arrived: HashSet<String>,
+}
 
-    // Control bar
-    render_control_bar(f, app, chunks[0]);
+/// Newest first by creation time; memories without one go last
+fn sort_newest_first(memories: &mut [MemoryListItem]) {
+    let created = |memory: &MemoryListItem| {
+        let created_at = memory.created_at.as_deref();
+        created_at.and_then(parse_timestamp).or_else(|| memory_timestamp(memory))
+    };
+    memories.sort_by_key(|memory| std::cmp::Reverse(created(memory)));
+}
 
-    // Header
-    render_header(f, app, chunks[1]);
+/// A memory fetched by ID, read like a listed one; that endpoint calls the fact type `type`
+fn fetched_memory(mut memory: Value) -> Option<MemoryListItem> {
+    let fields = memory.as_object_mut()?;
+    if let Some(fact_type) = fields.remove("type") {
+        fields.entry("fact_type").or_insert(fact_type);
+    }
+    serde_json::from_value(memory).ok()
+}
 
-    // Main content
-    if app.show_help {
-        render_help(f, chunks[2]);
-    } else {
-        match &app.view {
-            View::Banks => render_banks(f, app, chunks[2]),
-            View::Memories(_) => render_memories(f, app, chunks[2]),
-            View::Entities(_) => render_entities(f, app, chunks[2]),
-            View::Documents(_) => render_documents(f, app, chunks[2]),
-            View::Query(_) => render_query(f, app, chunks[2]),
+/// A query as it was sent, to send again from the error popup
+#[derive(Clone)]
+struct SentQuery {
+    bank_id: String,
+    mode: QueryMode,
+    text: String,
+}
+
+/// What `r` in the error popup runs again
+enum Retry {
+    List(ListRequest),
+    Query(SentQuery),
+    /// The rows that failed to delete; they were confirmed already
+    Delete(PendingDelete),
+}
+
+/// A failure shown in full in a popup, rather than cut short in the footer
+struct ErrorReport {
+    title: String,
+    /// Method and path of the call that failed
+    endpoint: String,
+    bank_id: Option<String>,
+    message: String,
+    retry: Option<Retry>,
+    scroll: u16,
+    /// Rows the popup showed when last drawn, for paging
+    height: u16,
+}
+
+impl ErrorReport {
+    fn new(title: &str, endpoint: String, bank_id: Option<&str>, message: String) -> Self {
+        Self {
+            title: title.to_string(),
+            endpoint,
+            bank_id: bank_id.map(str::to_string),
+            message,
+            retry: None,
+            scroll: 0,
+            height: 0,
         }
     }
 
-    // Footer
-    render_footer(f, app, chunks[3]);
+    fn retry(mut self, retry: Retry) -> Self {
+        self.retry = Some(retry);
+        self
+    }
 }
 
-fn render_control_bar(f: &mut Frame, app: &App, area: Rect) {
-    // Build contextual shortcuts based on view and input mode
-    let shortcuts = match (&app.view, &app.input_mode) {
-        (View::Banks, InputMode::Normal) => vec![
-            ("Enter", "Select", BRAND_START),
-            ("R", "Refresh", BRAND_MID),
-            ("?", "Help", BRAND_END),
-            ("q", "Quit", Color::Red),
-        ],
-        (View::Memories(_), InputMode::Normal) => vec![
-            ("Enter", "View", BRAND_START),
-            ("/", "Query", BRAND_MID),
-            ("←→", "Scroll", BRAND_START),
-            ("n", "Next", BRAND_MID),
-            ("p", "Prev", BRAND_MID),
-            ("Esc", "Back", BRAND_END),
-            ("R", "Refresh", BRAND_END),
-            ("?", "Help", BRAND_END),
-            ("q", "Quit", Color::Red),
-        ],
-        (View::Entities(_), InputMode::Normal) => vec![
-            ("Enter", "View", BRAND_START),
-            ("/", "Query", BRAND_MID),
-            ("←→", "Scroll", BRAND_START),
-            ("Esc", "Back", BRAND_END),
-            ("R", "Refresh", BRAND_END),
-            ("?", "Help", BRAND_END),
-            ("q", "Quit", Color::Red),
-        ],
-        (View::Documents(_), InputMode::Normal) => vec![
-            ("Enter", "View", BRAND_START),
-            ("/", "Query", BRAND_MID),
-            ("←→", "Scroll", BRAND_START),
-            ("Del", "Delete", Color::Red),
-            ("Esc", "Back", BRAND_END),
-            ("R", "Refresh", BRAND_END),
-            ("?", "Help", BRAND_END),
-            ("q", "Quit", Color::Red),
-        ],
-        (View::Query(_), InputMode::Normal) => {
-            let mut shortcuts = vec![
-                ("/", "Query", BRAND_MID),
-                ("m", "Mode", BRAND_START),
-            ];
-            if app.query_mode == QueryMode::Recall {
-                shortcuts.push(("←→", "Scroll", BRAND_START));
-            }
-            shortcuts.extend_from_slice(&[
-                ("b", "Budget", BRAND_END),
-                ("+/-", "Tokens", BRAND_END),
-                ("Esc", "Back", BRAND_END),
-                ("?", "Help", BRAND_END),
-                ("q", "Quit", Color::Red),
-            ]);
-            shortcuts
-        },
-        (View::Query(_), InputMode::Query) => vec![
-            ("Enter", "Execute", BRAND_MID),
-            ("Esc", "Cancel", Color::Red),
-        ],
-        _ => vec![
-            ("?", "Help", BRAND_END),
-            ("q", "Quit", Color::Red),
-        ],
+/// Method and path of the API call that loads `view`
+fn list_endpoint(view: &View) -> String {
+    let path = match view {
+        View::Banks => return "GET /v1/default/banks".to_string(),
+        View::Memories(_) => "memories/list",
+        View::Entities(_) => "entities",
+        View::Documents(_) => "documents",
+        View::Operations(_) => "operations",
+        View::Profile(_) => "profile",
+        View::Stats(_) => "stats",
+        View::Query(_) => "",
     };
+    format!("GET /v1/default/banks/{}/{}", view.bank_id().unwrap_or_default(), path)
+}
 
-    // Split into left (context) and right (shortcuts) sections
-    let columns = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(30),  // Context on left
-            Constraint::Percentage(70),  // Shortcuts on right
-        ])
-        .split(area);
+/// Application state
+struct App {
+    client: ApiClient,
+    view: View,
+    view_history: Vec<View>,
+    /// Follow mode of the Memories view, while on
+    follow: Option<Follow>,
+    /// How the Memories, Entities and Documents views load, set with `O`
+    memories_load: LoadOptions,
+    entities_load: LoadOptions,
+    documents_load: LoadOptions,
+    /// Whether recalls ask for the source chunks of their results
+    include_recall_chunks: bool,
+    /// Max tokens of a reflect answer, from the config or set with `O`
+    reflect_max_tokens: i64,
+    options_popup: Option<OptionsPopup>,
+    /// Column of `MEMORY_COLUMNS` the loaded memories are sorted by, if any
+    memories_sort: Option<usize>,
+    /// Column of `DOCUMENT_COLUMNS` the loaded documents are sorted by, if any
+    documents_sort: Option<usize>,
+    /// What the current list is narrowed to after a jump from a related item
+    related: Option<Related>,
+    /// Narrowings of views further back in `view_history`, with the history depth they are at
+    related_history: Vec<(usize, Related)>,
+
+    // List states
+    banks: Vec<BankListItem>,
+    banks_loaded: Vec<BankListItem>,
+    banks_state: ListState,
+    selected_bank_id: Option<String>,
+
+    memories: Vec<MemoryListItem>,
+    memories_loaded: Vec<MemoryListItem>,
+    memories_state: ListState,
+    viewing_memory: Option<MemoryListItem>,
+    memory_detail_scroll: u16,
+    memory_detail_height: u16, // visible rows in the detail popup, set on render
+    memories_page: Paging,
+    horizontal_scroll: usize,
+
+    entities: Vec<EntityListItem>,
+    entities_loaded: Vec<EntityListItem>,
+    entities_state: ListState,
+    entities_page: Paging,
+    viewing_entity: Option<EntityDrilldown>,
+
+    documents: Vec<DocumentListItem>,
+    documents_loaded: Vec<DocumentListItem>,
+    documents_state: ListState,
+    documents_page: Paging,
+    viewing_document: Option<DocumentDetail>,
+
+    operations: Vec<Operation>,
+    operations_loaded: Vec<Operation>,
+    operations_state: ListState,
+    operations_page: Paging,
+
+    profile: Option<BankProfileResponse>,
+    /// Disposition before the last edit of the profile, to show what it changed
+    profile_before: Option<DispositionTraits>,
+    profile_scroll: u16,
+    profile_height: u16, // visible rows of the mission, set on render
+
+    stats: Option<AgentStats>,
+    /// Memory totals of the stats bank, sampled on each load this session
+    stats_history: Vec<u64>,
+
+    // Query state (unified recall/reflect)
+    query_mode: QueryMode,
+    query_text: TextInput,
+    /// Budget and max tokens chosen per bank
+    query_settings: HashMap<String, QuerySettings>,
+    query_results: Vec<RecallResult>,
+    query_results_state: ListState,
+    query_response: String,
+    viewing_recall_result: Option<RecallResult>,
+    /// Source chunks of the recall results by chunk ID, including ones fetched later
+    recall_chunks: HashMap<String, ChunkData>,
+    /// Lowercased words of the query that produced the recall results
+    recall_terms: Vec<String>,
+    recall_chunk_visible: bool,
+
+    // Recall trace pane: requested with the next recall while enabled
+    trace_enabled: bool,
+    recall_trace: Option<Map<String, Value>>,
+    trace_collapsed: bool,
+    /// Show the trace as sent instead of the parsed report
+    trace_raw: bool,
+    trace_scroll: u16,
+    trace_height: u16, // visible rows of the trace pane, set on render
+
+    // Reflect response pane: scroll offset, and the memories the answer was based on
+    reflect_scroll: u16,
+    reflect_height: u16, // visible rows of the response text, set on render
+    reflect_sources: Vec<ReflectFact>,
+    reflect_sources_state: ListState,
+    reflect_sources_focused: bool,
+
+    query_history: QueryHistory,
+    history_picker: Option<HistoryPicker>,
+
+    /// Where the last session left off, restored once the bank list has loaded
+    pending_session: Option<ExplorerSession>,
+
+    // Input mode
+    input_mode: InputMode,
+
+    // List filter for the current view; the visible rows are the loaded rows that match
+    filter_text: TextInput,
+
+    // Deletion: rows marked with Space, and the confirmation modal when open
+    marked_memories: HashSet<String>,
+    marked_documents: HashSet<String>,
+    pending_delete: Option<PendingDelete>,
+    /// A failed load, query or delete, shown until dismissed
+    error_report: Option<ErrorReport>,
+    /// The last recall or reflect sent, for retrying it
+    sent_query: Option<SentQuery>,
+    pending_operation: Option<PendingOperation>,
+
+    /// Bank create/rename/delete popup, open in `InputMode::Bank`
+    bank_dialog: Option<BankDialog>,
+
+    /// Export popup, open in `InputMode::Export`
+    export_prompt: Option<ExportPrompt>,
+    /// New-memory popup, open in `InputMode::Memory`
+    memory_draft: Option<MemoryDraft>,
+    /// Input to open in `$VISUAL`/`$EDITOR` once the key handler returns
+    editor_request: Option<EditorTarget>,
+    /// Whether the mouse is captured, to capture it again after the editor
+    mouse_capture: bool,
+    /// JSON of the selected item, open over any view
+    json_inspector: Option<JsonInspector>,
+    /// Opened on the first copy and kept, since on X11 the copied text lives
+    /// only as long as the clipboard handle
+    clipboard: Option<arboard::Clipboard>,
+    /// Tab change to make once the key handler returns
+    tab_request: Option<TabRequest>,
+    /// Asking whether to close this tab while a query is still running
+    pending_close_tab: bool,
+    /// Titles of all open tabs and which one this is, for the header
+    tab_titles: Vec<String>,
+    tab_index: usize,
+    export_format: ExportFormat,
+
+    connection: ConnectionStatus,
+    theme: Theme,
+
+    // Mouse: areas from the last draw, and the last click for spotting double clicks
+    mouse: MouseAreas,
+    last_click: Option<(Instant, usize)>,
+
+    /// Digits typed before `g`/`G`, as in `42G`
+    jump_count: Option<usize>,
+
+    /// Key bindings of the views, and the start of a multi-key binding typed so far
+    keys: Keymap,
+    pending_keys: Vec<KeyChord>,
+
+    // Status messages
+    status_message: String,
+    error_message: String,
+
+    // Help visibility
+    show_help: bool,
+
+    // Auto-refresh
+    auto_refresh_enabled: bool,
+    last_refresh: Instant,
+    refresh_interval: Duration,
+    /// Last key press or click, which holds auto-refresh off for a moment
+    last_input: Instant,
+
+    // Background requests: responses arrive tagged with their slot and generation,
+    // and only the generation still in flight for the slot is applied
+    in_flight: HashMap<RequestSlot, InFlight>,
+    generation: u64,
+    response_sender: Sender<Delivery>,
+    response_receiver: Receiver<Delivery>,
+    /// Background writes that came back with an error, for a quit waiting on them
+    failed_writes: usize,
+    /// Writes in progress in any tab when quit was pressed, asking whether to quit anyway
+    pending_quit: Option<Vec<&'static str>>,
+    quit_choice: Option<QuitChoice>,
+}
+
+/// A finished background request: its slot, generation, how long it took, and the response
+type Delivery = (RequestSlot, u64, Duration, Response);
+
+impl App {
+    fn new(client: ApiClient) -> Self {
+        let (response_sender, response_receiver) = mpsc::channel();
+        let connection = ConnectionStatus::new(client.base_url());
+        let mut app = Self {
+            client,
+            view: View::Banks,
+            view_history: Vec::new(),
+            follow: None,
+            memories_load: LoadOptions {
+                page_size: LIST_PAGE_SIZE,
+                verbose: false,
+            },
+            entities_load: LoadOptions {
+                page_size: LIST_PAGE_SIZE,
+                verbose: false,
+            },
+            documents_load: LoadOptions {
+                page_size: LIST_PAGE_SIZE,
+                verbose: false,
+            },
+            include_recall_chunks: true,
+            reflect_max_tokens: 4096,
+            options_popup: None,
+            memories_sort: None,
+            documents_sort: None,
+            related: None,
+            related_history: Vec::new(),
+
+            banks: Vec::new(),
+            banks_loaded: Vec::new(),
+            banks_state: ListState::default(),
+            selected_bank_id: None,
+
+            memories: Vec::new(),
+            memories_loaded: Vec::new(),
+            memories_state: ListState::default(),
+            viewing_memory: None,
+            memory_detail_scroll: 0,
+            memory_detail_height: 0,
+            memories_page: Paging::new(),
+            horizontal_scroll: 0,
+
+            entities: Vec::new(),
+            entities_loaded: Vec::new(),
+            entities_state: ListState::default(),
+            entities_page: Paging::new(),
+            viewing_entity: None,
+
+            documents: Vec::new(),
+            documents_loaded: Vec::new(),
+            documents_state: ListState::default(),
+            documents_page: Paging::new(),
+            viewing_document: None,
+
+            operations: Vec::new(),
+            operations_loaded: Vec::new(),
+            operations_state: ListState::default(),
+            operations_page: Paging::new(),
+
+            profile: None,
+            profile_before: None,
+            profile_scroll: 0,
+            profile_height: 0,
+
+            stats: None,
+            stats_history: Vec::new(),
+
+            query_mode: QueryMode::Recall,
+            query_text: TextInput::default(),
+            query_settings: HashMap::new(),
+            query_results: Vec::new(),
+            query_results_state: ListState::default(),
+            query_response: String::new(),
+            viewing_recall_result: None,
+            recall_chunks: HashMap::new(),
+            recall_terms: Vec::new(),
+            recall_chunk_visible: true,
+
+            trace_enabled: false,
+            recall_trace: None,
+            trace_collapsed: false,
+            trace_raw: false,
+            trace_scroll: 0,
+            trace_height: 0,
+
+            reflect_scroll: 0,
+            reflect_height: 0,
+            reflect_sources: Vec::new(),
+            reflect_sources_state: ListState::default(),
+            reflect_sources_focused: false,
+
+            query_history: QueryHistory::default(),
+            history_picker: None,
+
+            pending_session: None,
+
+            input_mode: InputMode::Normal,
+            filter_text: TextInput::default(),
+            marked_memories: HashSet::new(),
+            marked_documents: HashSet::new(),
+            pending_delete: None,
+            error_report: None,
+            sent_query: None,
+            pending_operation: None,
+
+            bank_dialog: None,
+
+            export_prompt: None,
+            memory_draft: None,
+            editor_request: None,
+            mouse_capture: false,
+            json_inspector: None,
+            clipboard: None,
+            tab_request: None,
+            pending_close_tab: false,
+            tab_titles: Vec::new(),
+            tab_index: 0,
+            export_format: ExportFormat::Json,
+
+            connection,
+            theme: Theme::DARK,
+
+            mouse: MouseAreas::default(),
+            last_click: None,
+
+            jump_count: None,
+
+            keys: Keymap::default(),
+            pending_keys: Vec::new(),
+
+            status_message: String::from("Select a bank to start. Press ? for help"),
+            error_message: String::new(),
+            show_help: false,
+
+            auto_refresh_enabled: true,
+            last_refresh: Instant::now(),
+            refresh_interval: Duration::from_secs(5),
+            last_input: Instant::now(),
+
+            in_flight: HashMap::new(),
+            generation: 0,
+            response_sender,
+            response_receiver,
+            failed_writes: 0,
+            pending_quit: None,
+            quit_choice: None,
+        };
+
+        // Select first item by default
+        app.banks_state.select(Some(0));
+        app.memories_state.select(Some(0));
+        app.entities_state.select(Some(0));
+        app.documents_state.select(Some(0));
+        app.operations_state.select(Some(0));
+        app.query_results_state.select(Some(0));
+
+        app
+    }
+
+    /// Reload the current list in the background, keeping its page and selection.
+    /// A list fetched moments ago may come from the response cache.
+    fn refresh(&mut self) {
+        self.error_message.clear();
+        self.load_current_list(true, false);
+    }
+
+    /// `R`: reload the current list from the server, whatever the cache holds
+    fn force_refresh(&mut self) {
+        self.error_message.clear();
+        self.reload_list(true);
+    }
+
+    /// Reload the current list from the server, as after a write or for auto-refresh
+    fn reload_list(&mut self, foreground: bool) {
+        self.load_current_list(foreground, true);
+    }
+
+    fn load_current_list(&mut self, foreground: bool, fresh: bool) {
+        let paging = match &self.view {
+            View::Memories(_) => self.memories_page,
+            View::Entities(_) => self.entities_page,
+            View::Documents(_) => self.documents_page,
+            View::Operations(_) => self.operations_page,
+            View::Banks | View::Query(_) | View::Profile(_) | View::Stats(_) => Paging::new(),
+        };
+        self.start_list_load(self.view.clone(), paging, Landing::Keep, foreground, fresh);
+    }
+
+    fn toggle_auto_refresh(&mut self) {
+        self.auto_refresh_enabled = !self.auto_refresh_enabled;
+        if self.auto_refresh_enabled {
+            self.status_message =
+                format!("Auto-refresh enabled ({}s)", self.refresh_interval.as_secs());
+            self.last_refresh = Instant::now();
+        } else {
+            self.status_message = "Auto-refresh disabled".to_string();
+        }
+    }
+
+    fn should_refresh(&self) -> bool {
+        (self.auto_refresh_enabled || self.following())
+            && self.last_refresh.elapsed() >= self.refresh_interval
+            && !self.refresh_paused()
+    }
+
+    /// Whether the user is busy with something a reload would disturb: typing, a popup,
+    /// or keys pressed just now
+    fn refresh_paused(&self) -> bool {
+        self.input_mode != InputMode::Normal
+            || self.modal_open()
+            || self.show_help
+            || self.viewing_memory.is_some()
+            || self.json_inspector.is_some()
+            || self.last_input.elapsed() < REFRESH_DEBOUNCE
+    }
+
+    /// Header note on auto-refresh: seconds to the next one, or why it waits
+    fn refresh_countdown(&self) -> Option<String> {
+        let enabled = self.auto_refresh_enabled || self.following();
+        if !enabled || matches!(self.view, View::Query(_)) {
+            return None;
+        }
+        if self.refresh_paused() {
+            return Some("↻ paused".to_string());
+        }
+        let left = self.refresh_interval.saturating_sub(self.last_refresh.elapsed());
+        Some(format!("↻ {}s", left.as_secs_f32().ceil() as u64))
+    }
+
+    fn do_auto_refresh(&mut self) {
+        // Against a slow server, wait for the load in flight rather than stacking another
+        if self.should_refresh() && !self.is_loading(RequestSlot::List) {
+            self.last_refresh = Instant::now();
+            self.reload_list(false);
+        }
+    }
+
+    /// Run `request` on a background thread, replacing whatever is in flight in `slot`
+    fn spawn_request<F>(
+        &mut self,
+        slot: RequestSlot,
+        label: &'static str,
+        foreground: bool,
+        request: F,
+    ) where
+        F: FnOnce(&ApiClient) -> Response + Send + 'static,
+    {
+        self.generation += 1;
+        let generation = self.generation;
+        self.in_flight.insert(
+            slot,
+            InFlight {
+                generation,
+                label,
+                foreground,
+            },
+        );
+
+        let client = self.client.clone();
+        let sender = self.response_sender.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            let response = request(&client);
+            // The app holds the receiver until exit; a failed send means nobody is listening
+            let _ = sender.send((slot, generation, started.elapsed(), response));
+        });
+    }
+
+    fn is_loading(&self, slot: RequestSlot) -> bool {
+        self.in_flight.contains_key(&slot)
+    }
+
+    /// Labels of the writes still running, such as "Adding memory"
+    fn writes_in_flight(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.in_flight
+            .iter()
+            .filter(|(slot, _)| slot.writes())
+            .map(|(_, request)| request.label)
+    }
+
+    /// Label of a request the user is waiting on, for the footer
+    fn foreground_request(&self) -> Option<&'static str> {
+        self.in_flight.values().find(|r| r.foreground).map(|r| r.label)
+    }
+
+    /// Forget the requests the user is waiting on; their responses are dropped on arrival.
+    /// Returns false when there was nothing to cancel.
+    fn cancel_requests(&mut self) -> bool {
+        let before = self.in_flight.len();
+        self.in_flight.retain(|_, request| !request.foreground);
+        if self.in_flight.len() == before {
+            return false;
+        }
+        if let Some(detail) = self.viewing_document.as_mut() {
+            detail.chunk_target = None;
+        }
+        self.status_message = "Request cancelled".to_string();
+        true
+    }
+
+    fn cancel_slot(&mut self, slot: RequestSlot) {
+        self.in_flight.remove(&slot);
+    }
+
+    /// Apply responses that have arrived, dropping those for cancelled or replaced requests
+    fn poll_responses(&mut self) {
+        while let Ok((slot, generation, elapsed, response)) = self.response_receiver.try_recv() {
+            // Stale responses still tell whether the server is reachable
+            if slot != RequestSlot::Export {
+                self.connection.record(elapsed, response.error());
+                self.connection.cache = self.client.cache_stats();
+            }
+            if self.in_flight.get(&slot).map(|r| r.generation) != Some(generation) {
+                continue;
+            }
+            let foreground = self.in_flight.remove(&slot).is_some_and(|r| r.foreground);
+            if slot.writes() && response.error().is_some() {
+                self.failed_writes += 1;
+            }
+            self.handle_response(response, foreground);
+        }
+    }
+
+    fn handle_response(&mut self, response: Response, foreground: bool) {
+        match response {
+            Response::List {
+                request,
+                result: Ok(page),
+            } => self.apply_list_page(request, page),
+            // Auto-refresh failures stay in the footer rather than popping up every few seconds
+            Response::List { result: Err(e), .. } if !foreground => {
+                self.error_message = format!("Error: {}", e);
+            }
+            Response::List {
+                request,
+                result: Err(e),
+            } => {
+                let report = ErrorReport::new(
+                    "Loading failed",
+                    list_endpoint(&request.view),
+                    request.view.bank_id(),
+                    format!("{:#}", e),
+                );
+                self.error_report = Some(report.retry(Retry::List(request)));
+            }
+            Response::Entity(Ok(drilldown)) => {
+                if matches!(self.view, View::Entities(_)) {
+                    self.status_message = format!(
+                        "{} memories mention {} (Esc to go back)",
+                        drilldown.memories.len(),
+                        drilldown.entity.canonical_name
+                    );
+                    self.viewing_entity = Some(drilldown);
+                }
+            }
+            Response::Entity(Err(e)) => {
+                self.error_message = format!("Failed to load entity: {}", e);
+            }
+            Response::Document(Ok((document, page))) => {
+                if matches!(self.view, View::Documents(_)) {
+                    self.status_message =
+                        format!("Viewing document: {} (t toggles full text)", document.id);
+                    self.viewing_document = Some(DocumentDetail {
+                        document,
+                        chunks: page.items,
+                        chunks_total: page.total,
+                        selected_chunk: 0,
+                        chunk_scroll: 0,
+                        show_full_text: false,
+                        text_scroll: 0,
+                        page_height: 0,
+                        chunk_target: None,
+                    });
+                }
+            }
+            Response::Document(Err(e)) => {
+                self.error_message = format!("Failed to load document: {}", e);
+            }
+            Response::Chunks {
+                document_id,
+                result,
+            } => self.apply_chunks(&document_id, result),
+            Response::Memory(Ok(memory)) => match fetched_memory(memory) {
+                Some(memory) if matches!(self.view, View::Query(_)) => {
+                    self.viewing_memory = Some(memory);
+                    self.memory_detail_scroll = 0;
+                    self.status_message = format!(
+                        "Viewing source {}/{} (j/k scroll, n/p next/prev, Esc to close)",
+                        self.reflect_sources_state.selected().map_or(0, |i| i + 1),
+                        self.reflect_sources.len()
+                    );
+                }
+                Some(_) => {}
+                None => self.error_message = "Unexpected memory response".to_string(),
+            },
+            Response::Memory(Err(e)) => {
+                self.error_message = format!("Failed to load memory: {}", e);
+            }
+            Response::Chunk(Ok(chunk)) => {
+                let data = ChunkData {
+                    id: chunk.chunk_id.clone(),
+                    text: chunk.chunk_text,
+                    chunk_index: chunk.chunk_index,
+                    truncated: false,
+                };
+                self.recall_chunks.insert(chunk.chunk_id, data);
+            }
+            Response::Chunk(Err(e)) => {
+                self.error_message = format!("Failed to load chunk: {}", e);
+            }
+            Response::Recall(Ok(response)) => {
+                self.recall_chunks = response.chunks.unwrap_or_default().into_iter().collect();
+                self.query_results = response.results;
+                self.recall_trace = response.trace;
+                self.trace_scroll = 0;
+                if !self.query_results.is_empty() {
+                    self.query_results_state.select(Some(0));
+                }
+                self.status_message = format!("Found {} results", self.query_results.len());
+            }
+            Response::Recall(Err(e)) => self.report_query_error("Recall failed", e),
+            Response::Reflect(Ok(response)) => {
+                self.query_response = response.text;
+                self.reflect_sources = response.based_on.map(|b| b.memories).unwrap_or_default();
+                self.reflect_sources_state
+                    .select((!self.reflect_sources.is_empty()).then_some(0));
+                self.reflect_scroll = 0;
+                self.reflect_sources_focused = false;
+                self.status_message = format!(
+                    "Reflection complete, based on {} memories",
+                    self.reflect_sources.len()
+                );
+            }
+            Response::Reflect(Err(e)) => self.report_query_error("Reflect failed", e),
+            // Older servers without the version endpoint just leave it out of the header
+            Response::Version(result) => {
+                self.connection.server_version = result.ok().map(|v| v.api_version);
+            }
+            Response::Export(Ok((path, count))) => {
+                self.status_message = format!("Exported {} rows to {}", count, path.display());
+            }
+            Response::Export(Err(e)) => {
+                self.error_message = format!("Export failed: {:#}", e);
+            }
+            Response::Retained {
+                bank_id,
+                draft,
+                result,
+            } => self.apply_retained(&bank_id, draft, result),
+            Response::Deleted { pending, results } => self.apply_deleted(pending, results),
+            Response::DeleteLookup {
+                bank_id,
+                documents,
+                without_document,
+            } => self.apply_delete_lookup(bank_id, documents, without_document),
+            Response::Bank {
+                dialog,
+                verb,
+                done,
+                result,
+            } => self.apply_bank_write(dialog, verb, done, result),
+        }
+    }
+
+    /// The list whose selection the keys move in the current view, and its length
+    fn focused_list(&mut self) -> Option<(&mut ListState, usize)> {
+        if self.viewing_memory.is_some() || self.modal_open() {
+            return None;
+        }
+        match &self.view {
+            View::Banks => Some((&mut self.banks_state, self.banks.len())),
+            View::Memories(_) => Some((&mut self.memories_state, self.memories.len())),
+            View::Entities(_) => match self.viewing_entity.as_mut() {
+                Some(drilldown) => Some((&mut drilldown.memories_state, drilldown.memories.len())),
+                None => Some((&mut self.entities_state, self.entities.len())),
+            },
+            View::Documents(_) if self.viewing_document.is_none() => {
+                Some((&mut self.documents_state, self.documents.len()))
+            }
+            View::Documents(_) => None,
+            View::Operations(_) => Some((&mut self.operations_state, self.operations.len())),
+            View::Profile(_) | View::Stats(_) => None,
+            View::Query(_) => match self.query_mode {
+                QueryMode::Recall if self.viewing_recall_result.is_none() => {
+                    Some((&mut self.query_results_state, self.query_results.len()))
+                }
+                QueryMode::Recall => None,
+                QueryMode::Reflect => {
+                    Some((&mut self.reflect_sources_state, self.reflect_sources.len()))
+                }
+            },
+        }
+    }
+
+    /// Select the clicked row; a second click on it soon after opens it like Enter
+    fn click_row(&mut self, column: u16, row: u16) {
+        let Some(area) = self.mouse.list else {
+            return;
+        };
+        let Some((state, len)) = self.focused_list() else {
+            return;
+        };
+        let Some(index) = area.item_at(column, row, len) else {
+            return;
+        };
+        state.select(Some(index));
+        if matches!(self.view, View::Query(_)) && self.query_mode == QueryMode::Reflect {
+            self.reflect_sources_focused = true;
+        }
+
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(at, clicked)| {
+            clicked == index && now.duration_since(at) <= DOUBLE_CLICK
+        });
+        self.last_click = if double { None } else { Some((now, index)) };
+        if double {
+            self.reset_horizontal_scroll();
+            self.enter_view();
+        }
+    }
+
+    /// Scroll the pane under the pointer: the open detail, the trace or reflect text, or the list
+    fn scroll_wheel(&mut self, column: u16, row: u16, down: bool) {
+        let step = if down { 1 } else { -1 };
+        let over_trace = self.mouse.trace.is_some_and(|r| r.contains(Position::new(column, row)));
+        if self.viewing_memory.is_some() {
+            self.scroll_memory_detail(3 * step);
+        } else if self.viewing_document.is_some() {
+            self.move_in_document(step);
+        } else if over_trace {
+            self.scroll_trace(3 * step);
+        } else if matches!(self.view, View::Query(_)) && self.query_mode == QueryMode::Reflect {
+            self.move_in_reflect(if self.reflect_sources_focused { step } else { 3 * step });
+        } else if down {
+            self.next_item();
+        } else {
+            self.previous_item();
+        }
+    }
+
+    fn cycle_theme(&mut self) {
+        self.theme = self.theme.next();
+        let name = clap::ValueEnum::to_possible_value(&self.theme.name)
+            .map(|v| v.get_name().to_string());
+        self.status_message = format!("Theme: {}", name.unwrap_or_default());
+    }
+
+    /// Fetch the server version for the header, in the background
+    fn load_server_version(&mut self) {
+        self.spawn_request(RequestSlot::Version, "Connecting", false, |client| {
+            Response::Version(client.get_version(false))
+        });
+    }
+
+    fn report_query_error(&mut self, title: &str, error: anyhow::Error) {
+        let Some(sent) = self.sent_query.clone() else {
+            self.error_message = format!("{}: {}", title, error);
+            return;
+        };
+        let path = match sent.mode {
+            QueryMode::Recall => "memories/recall",
+            QueryMode::Reflect => "reflect",
+        };
+        let endpoint = format!("POST /v1/default/banks/{}/{}", sent.bank_id, path);
+        let report = ErrorReport::new(title, endpoint, Some(&sent.bank_id), format!("{:#}", error));
+        self.error_report = Some(report.retry(Retry::Query(sent)));
+    }
+
+    /// `r` in the error popup: close it and run the failed request again
+    fn retry_failed(&mut self) {
+        let Some(report) = self.error_report.take() else {
+            return;
+        };
+        match report.retry {
+            Some(Retry::List(request)) => {
+                self.error_message.clear();
+                self.start_list_load(request.view, request.paging, request.landing, true, true);
+            }
+            Some(Retry::Query(sent)) => {
+                if self.view != View::Query(sent.bank_id.clone()) {
+                    self.switch_to_view(View::Query(sent.bank_id.clone()));
+                }
+                self.query_mode = sent.mode;
+                self.query_text.set(sent.text);
+                self.execute_query();
+            }
+            Some(Retry::Delete(pending)) => {
+                self.pending_delete = Some(pending);
+                self.confirm_delete();
+            }
+            None => {}
+        }
+    }
+
+    fn scroll_error_report(&mut self, rows: i32) {
+        if let Some(report) = self.error_report.as_mut() {
+            let scrolled = (report.scroll as i32).saturating_add(rows).max(0);
+            // Clamped to the content height when the popup is next rendered
+            report.scroll = scrolled.min(u16::MAX as i32) as u16;
+        }
+    }
+
+    /// Fetch a page of `view`'s list in the background
+    /// Load a page of `view`; unless `fresh`, a recently cached response may answer it
+    fn start_list_load(
+        &mut self,
+        view: View,
+        paging: Paging,
+        landing: Landing,
+        foreground: bool,
+        fresh: bool,
+    ) {
+        let label = match &view {
+            View::Banks => "Loading banks",
+            View::Memories(_) => "Loading memories",
+            View::Entities(_) => "Loading entities",
+            View::Documents(_) => "Loading documents",
+            View::Operations(_) => "Loading operations",
+            View::Profile(_) => "Loading profile",
+            View::Stats(_) => "Loading statistics",
+            View::Query(_) => return, // Query is query-driven
+        };
+        let load = self.load_options(&view);
+        let paging = Paging {
+            limit: load.map_or(paging.limit, |load| load.page_size),
+            ..paging
+        };
+        let verbose = load.is_some_and(|load| load.verbose);
+        let request = ListRequest {
+            view,
+            paging,
+            landing,
+        };
+        let related = self.related.clone();
+        self.spawn_request(RequestSlot::List, label, foreground, move |client| {
+            let fresh_client;
+            let client = if fresh {
+                fresh_client = client.bypassing_cache();
+                &fresh_client
+            } else {
+                client
+            };
+            let (limit, offset) = (Some(paging.limit), Some(paging.offset));
+            // Narrowed lists are filtered here, then paged like the others
+            let page_of = |total: usize| {
+                let start = (paging.offset.max(0) as usize).min(total);
+                start..(start + paging.limit.max(0) as usize).min(total)
+            };
+            let result = match (&request.view, related) {
+                (View::Memories(bank_id), Some(Related::DocumentMemories(document_id))) => {
+                    let filter = MemoryFilter {
+                        document_id: Some(document_id),
+                        ..MemoryFilter::default()
+                    };
+                    let memories =
+                        fetch_matching_memories(client, bank_id, None, &filter, verbose);
+                    memories.map(|mut items| {
+                        let total = items.len();
+                        ListPage::Memories(ListMemoryUnitsResponse {
+                            items: items.drain(page_of(total)).collect(),
+                            total: total as i64,
+                            limit: paging.limit,
+                            offset: paging.offset,
+                        })
+                    })
+                }
+                (View::Entities(bank_id), Some(Related::MemoryEntities { names, .. })) => {
+                    let names: HashSet<String> = names.iter().map(|n| n.to_lowercase()).collect();
+                    fetch_all_entities(client, bank_id, verbose).map(|entities| {
+                        let mut items: Vec<EntityListItem> = entities
+                            .into_iter()
+                            .filter(|entity| {
+                                let mut known = entity_aliases(entity.metadata.as_ref());
+                                known.push(entity.canonical_name.clone());
+                                known.iter().any(|name| names.contains(&name.to_lowercase()))
+                            })
+                            .collect();
+                        let total = items.len();
+                        ListPage::Entities(EntityListResponse {
+                            items: items.drain(page_of(total)).collect(),
+                            total: total as i64,
+                            limit: paging.limit,
+                            offset: paging.offset,
+                        })
+                    })
+                }
+                (View::Banks, _) => client.list_agents(false).map(ListPage::Banks),
+                (View::Memories(bank_id), _) => client
+                    .list_memories(bank_id, None, None, limit, offset, verbose)
+                    .map(ListPage::Memories),
+                (View::Entities(bank_id), _) => client
+                    .list_entities(bank_id, limit, offset, verbose)
+                    .map(ListPage::Entities),
+                (View::Documents(bank_id), _) => client
+                    .list_documents(
+                        bank_id,
+                        None,
+                        limit.map(|l| l as i32),
+                        offset.map(|o| o as i32),
+                        verbose,
+                    )
+                    .map(ListPage::Documents),
+                (View::Operations(bank_id), _) => client
+                    .list_operations(
+                        bank_id,
+                        None,
+                        limit.map(|l| l as u64),
+                        offset.map(|o| o as u64),
+                        false,
+                    )
+                    .map(ListPage::Operations),
+                (View::Profile(bank_id), _) => {
+                    client.get_profile(bank_id, false).map(ListPage::Profile)
+                }
+                (View::Stats(bank_id), _) => client.get_stats(bank_id, false).map(ListPage::Stats),
+                (View::Query(_), _) => unreachable!("the Query view has no list"),
+            };
+            Response::List { request, result }
+        });
+    }
+
+    fn apply_list_page(&mut self, request: ListRequest, page: ListPage) {
+        let ListRequest {
+            view,
+            mut paging,
+            landing,
+        } = request;
+        let total = match &page {
+            ListPage::Banks(_) => 0,
+            ListPage::Memories(response) => response.total,
+            ListPage::Entities(response) => response.total,
+            ListPage::Documents(response) => response.total,
+            ListPage::Operations(response) => response.total,
+            ListPage::Profile(_) | ListPage::Stats(_) => 0,
+        };
+        let empty = match &page {
+            ListPage::Banks(_) | ListPage::Profile(_) | ListPage::Stats(_) => false,
+            ListPage::Memories(response) => response.items.is_empty(),
+            ListPage::Entities(response) => response.items.is_empty(),
+            ListPage::Documents(response) => response.items.is_empty(),
+            ListPage::Operations(response) => response.operations.is_empty(),
+        };
+        paging.total = total;
+        // Rows were deleted since the page was chosen; step back to the last one
+        if empty && page_past_end(&mut paging) {
+            self.start_list_load(view, paging, landing, true, true);
+            return;
+        }
+
+        match page {
+            ListPage::Banks(banks) => {
+                let kept = KeptSelection::new(&self.banks, &self.banks_state, bank_row_id);
+                self.banks_loaded = banks;
+                self.apply_filter();
+                kept.land(&mut self.banks_state, &self.banks, landing, bank_row_id);
+                self.status_message = format!("Loaded {} banks", self.banks.len());
+                if let Some(session) = self.pending_session.take() {
+                    self.resume_session(session);
+                }
+            }
+            ListPage::Memories(response) => {
+                let kept = KeptSelection::new(&self.memories, &self.memories_state, memory_row_id);
+                self.memories_loaded = response.items;
+                if self.follow.is_some() {
+                    sort_newest_first(&mut self.memories_loaded);
+                }
+                self.memories_page = paging;
+                self.apply_filter();
+                kept.land(&mut self.memories_state, &self.memories, landing, memory_row_id);
+                self.status_message = format!(
+                    "Loaded memories, {} (page size {})",
+                    paging.describe(self.memories_loaded.len()),
+                    paging.limit
+                );
+                self.track_arrivals();
+            }
+            ListPage::Entities(response) => {
+                let kept = KeptSelection::new(&self.entities, &self.entities_state, entity_row_id);
+                self.entities_loaded = response.items;
+                self.entities_page = paging;
+                self.apply_filter();
+                kept.land(&mut self.entities_state, &self.entities, landing, entity_row_id);
+                self.status_message = format!(
+                    "Loaded entities, {} (page size {})",
+                    paging.describe(self.entities_loaded.len()),
+                    paging.limit
+                );
+            }
+            ListPage::Documents(response) => {
+                let kept =
+                    KeptSelection::new(&self.documents, &self.documents_state, document_row_id);
+                self.documents_loaded = response.items;
+                self.documents_page = paging;
+                self.apply_filter();
+                kept.land(&mut self.documents_state, &self.documents, landing, document_row_id);
+                self.status_message = format!(
+                    "Loaded documents, {} (page size {})",
+                    paging.describe(self.documents_loaded.len()),
+                    paging.limit
+                );
+            }
+            ListPage::Operations(response) => {
+                let kept =
+                    KeptSelection::new(&self.operations, &self.operations_state, operation_row_id);
+                self.operations_loaded = response.operations;
+                self.operations_page = paging;
+                self.apply_filter();
+                kept.land(&mut self.operations_state, &self.operations, landing, operation_row_id);
+                let shown = paging.describe(self.operations_loaded.len());
+                self.status_message = format!("Loaded operations, {}", shown);
+            }
+            ListPage::Profile(profile) => {
+                self.status_message = match &self.profile_before {
+                    Some(before) => {
+                        let changes: Vec<String> = disposition_traits(before)
+                            .iter()
+                            .zip(disposition_traits(&profile.disposition))
+                            .filter(|(old, new)| old.1 != new.1)
+                            .map(|(old, new)| format!("{} {} → {}", new.0, old.1, new.1))
+                            .collect();
+                        if changes.is_empty() {
+                            "Profile updated; the disposition is unchanged".to_string()
+                        } else {
+                            format!("Profile updated: {}", changes.join(", "))
+                        }
+                    }
+                    None => format!("Loaded profile of {}", profile.bank_id),
+                };
+                self.profile = Some(profile);
+            }
+            ListPage::Stats(stats) => {
+                if self.stats.as_ref().is_some_and(|s| s.bank_id != stats.bank_id) {
+                    self.stats_history.clear();
+                }
+                self.stats_history.push(stats.total_nodes.max(0) as u64);
+                let excess = self.stats_history.len().saturating_sub(STATS_HISTORY_LIMIT);
+                self.stats_history.drain(..excess);
+                self.status_message = format!("Loaded statistics of {}", stats.bank_id);
+                self.stats = Some(stats);
+            }
+        }
+    }
+
+    /// Narrow every list to the loaded rows matching the filter
+    fn apply_filter(&mut self) {
+        let filter = ListFilter::new(self.filter_text.as_str());
+        self.banks = filter.apply(&self.banks_loaded, |bank| {
+            vec![bank.bank_id.clone(), bank.name.clone().unwrap_or_default()]
+        });
+        self.memories = filter.apply(&self.memories_loaded, |memory| {
+            let mut fields = vec![memory.id.clone(), memory.text.clone()];
+            fields.extend(memory.fact_type.clone());
+            fields.extend(memory.context.clone());
+            fields
+        });
+        self.entities = filter.apply(&self.entities_loaded, |entity| {
+            let mut fields = vec![entity.id.clone(), entity.canonical_name.clone()];
+            fields.extend(entity_aliases(entity.metadata.as_ref()));
+            fields
+        });
+        self.documents = filter.apply(&self.documents_loaded, |doc| {
+            let mut fields = vec![doc.id.clone()];
+            fields.extend(document_content_type(doc));
+            fields
+        });
+        self.operations = filter.apply(&self.operations_loaded, |op| {
+            let mut fields = vec![op.id.clone(), op.task_type.clone(), op.status.clone()];
+            fields.extend(op.error_message.clone());
+            fields
+        });
+        if let Some(column) = self.memories_sort {
+            MEMORY_COLUMNS[column].sort(&mut self.memories);
+        }
+        if let Some(column) = self.documents_sort {
+            DOCUMENT_COLUMNS[column].sort(&mut self.documents);
+        }
+
+        clamp_selection(&mut self.banks_state, self.banks.len());
+        clamp_selection(&mut self.memories_state, self.memories.len());
+        clamp_selection(&mut self.entities_state, self.entities.len());
+        clamp_selection(&mut self.documents_state, self.documents.len());
+        clamp_selection(&mut self.operations_state, self.operations.len());
+    }
+
+    fn clear_filter(&mut self) {
+        if !self.filter_text.is_empty() {
+            self.filter_text.clear();
+            self.apply_filter();
+        }
+    }
+
+    /// Whether the current view is a list that can be filtered
+    fn filterable(&self) -> bool {
+        match &self.view {
+            View::Banks => true,
+            View::Memories(_) => self.viewing_memory.is_none(),
+            View::Entities(_) => self.viewing_entity.is_none(),
+            View::Documents(_) => self.viewing_document.is_none(),
+            View::Operations(_) => true,
+            View::Query(_) | View::Profile(_) | View::Stats(_) => false,
+        }
+    }
+
+    /// Title fragment for a paged list: its page position, or the filter count
+    fn list_position(&self, visible: usize, loaded: usize, page: &Paging) -> String {
+        if self.filter_text.is_empty() {
+            return page.describe(loaded);
+        }
+        let more = if page.has_next() || page.has_prev() {
+            ", other pages not searched: ]/["
+        } else {
+            ""
+        };
+        format!("filtered: {}/{}{}", visible, loaded, more)
+    }
+
+    /// Paging state and row count of the current list view
+    fn current_page(&mut self) -> Option<(&mut Paging, usize, &mut ListState)> {
+        match &self.view {
+            View::Memories(_) if self.viewing_memory.is_none() => {
+                Some((&mut self.memories_page, self.memories.len(), &mut self.memories_state))
+            }
+            View::Entities(_) if self.viewing_entity.is_none() => {
+                Some((&mut self.entities_page, self.entities.len(), &mut self.entities_state))
+            }
+            View::Documents(_) if self.viewing_document.is_none() => {
+                Some((&mut self.documents_page, self.documents.len(), &mut self.documents_state))
+            }
+            View::Operations(_) => Some((
+                &mut self.operations_page,
+                self.operations.len(),
+                &mut self.operations_state,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Start loading the next or previous page of the current list view. The
+    /// selection lands on the first row, or the last one when `select_last` is set.
+    /// Returns false when there is no such page.
+    fn turn_page(&mut self, forward: bool, select_last: bool) -> bool {
+        let Some((page, _, _)) = self.current_page() else {
+            return false;
+        };
+        let mut next = *page;
+        if forward && next.has_next() {
+            next.offset += next.limit;
+        } else if !forward && next.has_prev() {
+            next.offset = (next.offset - next.limit).max(0);
+        } else {
+            return false;
+        }
+
+        let landing = if select_last { Landing::Last } else { Landing::First };
+        self.start_list_load(self.view.clone(), next, landing, true, false);
+        true
+    }
+
+    /// Whether the selection sits on the last (or first) row of a page that has a neighbour
+    fn at_page_edge(&mut self, forward: bool) -> bool {
+        let Some((page, rows, state)) = self.current_page() else {
+            return false;
+        };
+        let selected = state.selected().unwrap_or(0);
+        if forward {
+            page.has_next() && selected + 1 >= rows
+        } else {
+            page.has_prev() && selected == 0
+        }
+    }
+
+    fn execute_query(&mut self) {
+        let View::Query(bank_id) = &self.view else {
+            return;
+        };
+        if self.query_text.is_empty() {
+            self.error_message = "Query cannot be empty".to_string();
+            return;
+        }
+
+        self.error_message.clear();
+        self.input_mode = InputMode::Normal;
+
+        let bank_id = bank_id.clone();
+        self.query_history.push(self.query_mode.clone(), self.query_text.as_str());
+        let query_text = self.query_text.as_str().to_string();
+        self.sent_query = Some(SentQuery {
+            bank_id: bank_id.clone(),
+            mode: self.query_mode.clone(),
+            text: query_text.clone(),
+        });
+        let settings = self.query_settings();
+        let query_budget = settings.budget.clone();
+        let query_max_tokens = settings.max_tokens;
+        let query_types = (!settings.types.is_empty())
+            .then(|| settings.types.iter().map(ToString::to_string).collect());
+
+        match self.query_mode {
+            QueryMode::Recall => {
+                self.recall_terms = query_terms(&query_text);
+                // Without chunks in the response, opening a result fetches its chunk
+                let chunks = ChunkIncludeOptions {
+                    max_tokens: RECALL_CHUNK_MAX_TOKENS,
+                };
+                let include = IncludeOptions {
+                    chunks: self.include_recall_chunks.then_some(chunks),
+                    entities: None,
+                    source_facts: None,
+                };
+                let request = RecallRequest {
+                    query: query_text,
+                    types: query_types,
+                    budget: Some(query_budget),
+                    max_tokens: query_max_tokens,
+                    trace: self.trace_enabled,
+                    query_timestamp: None,
+                    include: Some(include),
+                    tags: None,
+                    tags_match: TagsMatch::Any,
+                    tag_groups: None,
+                };
+                self.spawn_request(RequestSlot::Query, "Searching memories", true, move |client| {
+                    Response::Recall(client.recall(&bank_id, &request, false))
+                });
+            }
+            QueryMode::Reflect => {
+                self.query_response.clear();
+                self.reflect_sources.clear();
+                self.reflect_scroll = 0;
+                self.reflect_sources_focused = false;
+                // Ask for the facts behind the answer to list them under it
+                let include = ReflectIncludeOptions {
+                    facts: Some(FactsIncludeOptions(Map::new())),
+                    tool_calls: None,
+                };
+                let request = ReflectRequest {
+                    query: query_text,
+                    budget: Some(query_budget),
+                    context: None,
+                    max_tokens: self.reflect_max_tokens,
+                    include: Some(include),
+                    response_schema: None,
+                    tags: None,
+                    tags_match: TagsMatch::Any,
+                    tag_groups: None,
+                    fact_types: None,
+                    exclude_mental_models: false,
+                    exclude_mental_model_ids: None,
+                };
+                self.spawn_request(RequestSlot::Query, "Reflecting", true, move |client| {
+                    Response::Reflect(client.reflect(&bank_id, &request, false))
+                });
+            }
+        }
+    }
+
+    /// Start typing a query, switching to the Query view of the current bank
+    /// and to `mode` when given
+    fn start_query(&mut self, mode: Option<QueryMode>) {
+        match &self.view {
+            View::Banks => {
+                self.error_message = "Select a bank first".to_string();
+                return;
+            }
+            View::Query(_) => {}
+            view => {
+                // Switch to Query view using current bank
+                let bank_id = view.bank_id().map(str::to_string);
+                let Some(bank_id) = bank_id.or_else(|| self.selected_bank_id.clone()) else {
+                    self.error_message = "No bank selected".to_string();
+                    return;
+                };
+                self.switch_to_view(View::Query(bank_id));
+            }
+        }
+        if mode.is_some_and(|mode| mode != self.query_mode) {
+            self.toggle_query_mode();
+        }
+        self.input_mode = InputMode::Query;
+    }
+
+    fn toggle_query_mode(&mut self) {
+        self.query_mode = match self.query_mode {
+            QueryMode::Recall => QueryMode::Reflect,
+            QueryMode::Reflect => QueryMode::Recall,
+        };
+        self.status_message = format!("Switched to {} mode", match self.query_mode {
+            QueryMode::Recall => "Recall",
+            QueryMode::Reflect => "Reflect",
+        });
+    }
+
+    /// Query settings of the selected bank
+    fn query_settings(&self) -> QuerySettings {
+        self.selected_bank_id
+            .as_ref()
+            .and_then(|bank_id| self.query_settings.get(bank_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn query_settings_mut(&mut self) -> Option<&mut QuerySettings> {
+        let bank_id = self.selected_bank_id.clone()?;
+        Some(self.query_settings.entry(bank_id).or_default())
+    }
+
+    fn cycle_budget(&mut self) {
+        let Some(settings) = self.query_settings_mut() else {
+            return;
+        };
+        settings.cycle_budget();
+        let budget = settings.budget.clone();
+        self.status_message = format!("Budget set to {:?} for this bank", budget);
+    }
+
+    /// Cycle the fact types recall searches; re-runs a shown recall so results match the title
+    fn cycle_fact_types(&mut self) {
+        if self.query_mode != QueryMode::Recall {
+            self.status_message = "Fact types apply to recall only".to_string();
+            return;
+        }
+        let Some(settings) = self.query_settings_mut() else {
+            return;
+        };
+        settings.cycle_types();
+        let types = settings.describe_types();
+        self.status_message = format!("Fact types set to {} for this bank", types);
+        if !self.query_text.is_empty() && !self.is_loading(RequestSlot::Query) {
+            self.execute_query();
+        }
+    }
+
+    fn adjust_max_tokens(&mut self, increase: bool) {
+        if self.query_mode != QueryMode::Recall {
+            self.status_message = "Max tokens apply to recall only".to_string();
+            return;
+        }
+        let Some(settings) = self.query_settings_mut() else {
+            return;
+        };
+        let before = settings.max_tokens;
+        settings.step_max_tokens(increase);
+        let after = settings.max_tokens;
+        self.status_message = if after == before {
+            let limit = if increase { "maximum" } else { "minimum" };
+            format!("Max tokens already at the {} of {}", limit, after)
+        } else {
+            format!("Max tokens set to {} for this bank", after)
+        };
+    }
+
+    /// Load the saved query history; an unreadable file is ignored and reported in the status line
+    fn load_query_history(&mut self) {
+        let Some(path) = QueryHistory::path() else {
+            return;
+        };
+        match QueryHistory::load(&path) {
+            Ok(history) => self.query_history = history,
+            Err(e) => {
+                self.status_message =
+                    format!("Ignored unreadable query history {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    fn save_query_history(&self) -> Result<()> {
+        let Some(path) = QueryHistory::path() else {
+            return Ok(());
+        };
+        self.query_history
+            .save(&path)
+            .with_context(|| format!("Could not save query history to {}", path.display()))
+    }
+
+    /// Read the last session to restore once the banks load. A missing, corrupt or
+    /// outdated session file is ignored without a word.
+    fn load_session(&mut self) {
+        let Some(session) = ExplorerSession::path().and_then(|p| ExplorerSession::load(&p)) else {
+            return;
+        };
+        self.query_mode = session.query_mode.clone();
+        self.query_settings = session.query_settings.clone();
+        self.pending_session = Some(session);
+    }
+
+    fn save_session(&self) -> Result<()> {
+        let Some(path) = ExplorerSession::path() else {
+            return Ok(());
+        };
+        self.session()
+            .save(&path)
+            .with_context(|| format!("Could not save explorer session to {}", path.display()))
+    }
+
+    /// The view, selections, filter and query settings to pick up from next time
+    fn session(&self) -> ExplorerSession {
+        let offset = match &self.view {
+            View::Memories(_) => self.memories_page.offset,
+            View::Entities(_) => self.entities_page.offset,
+            View::Documents(_) => self.documents_page.offset,
+            View::Operations(_) => self.operations_page.offset,
+            View::Banks | View::Query(_) | View::Profile(_) | View::Stats(_) => 0,
+        };
+        ExplorerSession {
+            version: SESSION_VERSION,
+            view: self.view.clone(),
+            bank_selected: self.banks_state.selected(),
+            selected: match &self.view {
+                View::Banks | View::Profile(_) | View::Stats(_) => None,
+                View::Memories(_) => self.memories_state.selected(),
+                View::Entities(_) => self.entities_state.selected(),
+                View::Documents(_) => self.documents_state.selected(),
+                View::Operations(_) => self.operations_state.selected(),
+                View::Query(_) => self.query_results_state.selected(),
+            },
+            offset,
+            filter: self.filter_text.as_str().to_string(),
+            query_mode: self.query_mode.clone(),
+            query_settings: self.query_settings.clone(),
+        }
+    }
+
+    /// Go back to the view of the last session, or stay on the Banks list when its bank
+    /// is gone
+    fn resume_session(&mut self, session: ExplorerSession) {
+        if let Some(i) = session.bank_selected {
+            self.banks_state.select(Some(i));
+            clamp_selection(&mut self.banks_state, self.banks.len());
+        }
+        let Some(bank_id) = session.view.bank_id().map(str::to_string) else {
+            self.filter_text.set(&session.filter);
+            self.apply_filter();
+            return;
+        };
+        if !self.banks_loaded.iter().any(|bank| bank.bank_id == bank_id) {
+            self.status_message =
+                format!("Bank '{}' from the last session no longer exists", bank_id);
+            return;
+        }
+
+        self.select_bank(&bank_id);
+        if let Some(i) = self.banks.iter().position(|bank| bank.bank_id == bank_id) {
+            self.banks_state.select(Some(i));
+        }
+        let state = match &session.view {
+            View::Memories(_) => {
+                self.memories_page.offset = session.offset;
+                &mut self.memories_state
+            }
+            View::Entities(_) => {
+                self.entities_page.offset = session.offset;
+                &mut self.entities_state
+            }
+            View::Documents(_) => {
+                self.documents_page.offset = session.offset;
+                &mut self.documents_state
+            }
+            View::Operations(_) => {
+                self.operations_page.offset = session.offset;
+                &mut self.operations_state
+            }
+            View::Banks | View::Query(_) | View::Profile(_) | View::Stats(_) => {
+                &mut self.query_results_state
+            }
+        };
+        state.select(session.selected.or(Some(0)));
+
+        self.view_history = vec![View::Banks];
+        self.view = session.view;
+        self.filter_text.set(&session.filter);
+        self.refresh();
+        self.status_message = format!("Resumed {} of {}", self.view.title(), bank_id);
+    }
+
+    /// Replace the query input with the previous (or next) query of the current mode
+    fn step_query_history(&mut self, older: bool) {
+        let text = if older {
+            self.query_history.older(&self.query_mode, self.query_text.as_str())
+        } else {
+            self.query_history.newer(&self.query_mode)
+        };
+        if let Some(text) = text {
+            self.query_text.set(text);
+        }
+    }
+
+    fn open_history_picker(&mut self) {
+        if self.query_history.entries.is_empty() {
+            self.status_message = "No query history yet".to_string();
+            return;
+        }
+        let mut state = ListState::default();
+        state.select(Some(0));
+        self.history_picker = Some(HistoryPicker {
+            filter: TextInput::default(),
+            state,
+        });
+        self.input_mode = InputMode::History;
+    }
+
+    fn move_in_history_picker(&mut self, forward: bool) {
+        let Some(picker) = self.history_picker.as_mut() else {
+            return;
+        };
+        let count = self.query_history.matching(picker.filter.as_str()).len();
+        if count == 0 {
+            return;
+        }
+        let i = picker.state.selected().unwrap_or(0);
+        let i = if forward { (i + 1) % count } else { (i + count - 1) % count };
+        picker.state.select(Some(i));
+    }
+
+    fn edit_history_filter(&mut self, key: &KeyEvent) {
+        if let Some(picker) = self.history_picker.as_mut() {
+            if picker.filter.handle_key(key) {
+                picker.state.select(Some(0));
+            }
+        }
+    }
+
+    /// Put the picked query in the input, switching to the mode it was run in
+    fn pick_history(&mut self) {
+        if let Some(picker) = self.history_picker.take() {
+            let matching = self.query_history.matching(picker.filter.as_str());
+            let picked = picker.state.selected().and_then(|i| matching.get(i).copied()).cloned();
+            if let Some(entry) = picked {
+                self.query_text.set(entry.query);
+                self.query_mode = entry.mode;
+            }
+        }
+        self.query_history.reset_cursor();
+        self.input_mode = InputMode::Query;
+    }
+
+    fn close_history_picker(&mut self) {
+        self.history_picker = None;
+        self.input_mode = InputMode::Query;
+    }
+
+    fn scroll_left(&mut self) {
+        self.horizontal_scroll = self.horizontal_scroll.saturating_sub(10);
+    }
+
+    fn scroll_right(&mut self) {
+        self.horizontal_scroll = (self.horizontal_scroll + 10).min(200);
+    }
+
+    fn reset_horizontal_scroll(&mut self) {
+        self.horizontal_scroll = 0;
+    }
+
+    fn next_item(&mut self) {
+        if self.at_page_edge(true) && self.turn_page(true, false) {
+            return;
+        }
+        if matches!(self.view, View::Query(_)) && self.query_mode == QueryMode::Reflect {
+            self.move_in_reflect(1);
+        } else {
+            self.navigate(ListNav::Next);
+        }
+    }
+
+    fn previous_item(&mut self) {
+        if self.at_page_edge(false) && self.turn_page(false, true) {
+            return;
+        }
+        if matches!(self.view, View::Query(_)) && self.query_mode == QueryMode::Reflect {
+            self.move_in_reflect(-1);
+        } else {
+            self.navigate(ListNav::Previous);
+        }
+    }
+
+    /// Move the selection of the list the current view shows
+    fn navigate(&mut self, nav: ListNav) {
+        if let Some((state, len)) = self.focused_list() {
+            nav.apply(state, len);
+        }
+    }
+
+    /// Rows of the current list visible at once, as last drawn
+    fn list_page_len(&self) -> usize {
+        self.mouse.list.map_or(1, |list| list.page_len())
+    }
+
+    fn enter_view(&mut self) {
+        match &self.view {
+            View::Banks => {
+                if let Some(bank_id) = self.selected_bank() {
+                    self.select_bank(&bank_id);
+                    self.view_history.push(self.view.clone());
+                    self.view = View::Memories(bank_id);
+                    self.clear_filter();
+                    self.refresh();
+                }
+            }
+            View::Memories(_) => {
+                if let Some(i) = self.memories_state.selected() {
+                    self.open_memory_detail(i);
+                }
+            }
+            View::Entities(_) if self.viewing_entity.is_some() => {}
+            View::Entities(bank_id) => {
+                if let Some(i) = self.entities_state.selected() {
+                    if let Some(entity) = self.entities.get(i) {
+                        let bank_id = bank_id.clone();
+                        let entity_id = entity.id.clone();
+                        self.open_entity_drilldown(&bank_id, &entity_id);
+                    }
+                }
+            }
+            View::Documents(_) if self.viewing_document.is_some() => {}
+            View::Documents(bank_id) => {
+                if let Some(i) = self.documents_state.selected() {
+                    if let Some(doc) = self.documents.get(i) {
+                        let (bank_id, doc_id) = (bank_id.clone(), doc.id.clone());
+                        self.open_document_detail(&bank_id, doc_id);
+                    }
+                }
+            }
+            // Operations have no detail view; Enter shows the full error in the footer
+            View::Operations(_) => {
+                let selected = self.operations_state.selected();
+                if let Some(op) = selected.and_then(|i| self.operations.get(i)) {
+                    match &op.error_message {
+                        Some(error) => self.error_message = format!("{}: {}", op.id, error),
+                        None => {
+                            self.status_message = format!("Operation {} is {}", op.id, op.status)
+                        }
+                    }
+                }
+            }
+            View::Profile(_) | View::Stats(_) => {}
+            View::Query(_) => {
+                // View recall result details if in recall mode
+                if self.query_mode == QueryMode::Recall {
+                    if let Some(i) = self.query_results_state.selected() {
+                        if let Some(result) = self.query_results.get(i).cloned() {
+                            self.viewing_recall_result = Some(result);
+                            self.status_message =
+                                "Viewing recall result (c toggles source chunk, Esc to close)"
+                                    .to_string();
+                            self.load_recall_chunk();
+                        }
+                    }
+                } else if self.reflect_sources_focused {
+                    if let Some(i) = self.reflect_sources_state.selected() {
+                        self.open_reflect_source(i);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fetch an entity and the memories mentioning it for the drill-down view
+    fn open_entity_drilldown(&mut self, bank_id: &str, entity_id: &str) {
+        let bank_id = bank_id.to_string();
+        let entity_id = entity_id.to_string();
+        self.spawn_request(RequestSlot::Detail, "Loading entity", true, move |client| {
+            Response::Entity(client.get_entity(&bank_id, &entity_id, false).and_then(|entity| {
+                let aliases = entity_aliases(entity.metadata.as_ref());
+                let memories =
+                    related_memories(client, &bank_id, &entity.canonical_name, &aliases, false)?;
+                let mut memories_state = ListState::default();
+                if !memories.is_empty() {
+                    memories_state.select(Some(0));
+                }
+                Ok(EntityDrilldown {
+                    entity,
+                    aliases,
+                    memories,
+                    memories_state,
+                })
+            }))
+        });
+    }
+
+    /// Fetch a document and the first page of its chunks for the detail view
+    fn open_document_detail(&mut self, bank_id: &str, doc_id: String) {
+        let bank_id = bank_id.to_string();
+        self.spawn_request(RequestSlot::Detail, "Loading document", true, move |client| {
+            Response::Document(client.get_document(&bank_id, &doc_id, false).and_then(|document| {
+                let page = client.list_document_chunks(
+                    &bank_id,
+                    &doc_id,
+                    Some(DOCUMENT_CHUNK_PAGE_SIZE),
+                    Some(0),
+                    false,
+                )?;
+                Ok((document, page))
+            }))
+        });
+    }
+
+    /// Start fetching the next page of chunks for the open document; false when all
+    /// are loaded
+    fn load_more_chunks(&mut self) -> bool {
+        let Some(detail) = self.viewing_document.as_ref() else {
+            return false;
+        };
+        if detail.chunks.len() as i64 >= detail.chunks_total {
+            return false;
+        }
+        if self.is_loading(RequestSlot::Chunks) {
+            return true;
+        }
+        let bank_id = detail.document.bank_id.clone();
+        let document_id = detail.document.id.clone();
+        let offset = detail.chunks.len() as u64;
+        self.spawn_request(RequestSlot::Chunks, "Loading chunks", true, move |client| {
+            let result = client.list_document_chunks(
+                &bank_id,
+                &document_id,
+                Some(DOCUMENT_CHUNK_PAGE_SIZE),
+                Some(offset),
+                false,
+            );
+            Response::Chunks {
+                document_id,
+                result,
+            }
+        });
+        true
+    }
+
+    /// Append a page of chunks and move to the chunk that was waiting for it
+    fn apply_chunks(&mut self, document_id: &str, result: Result<ListChunksResponse>) {
+        let Some(detail) = self.viewing_document.as_mut() else {
+            return;
+        };
+        if detail.document.id != document_id {
+            return;
+        }
+        let page = match result {
+            Ok(page) => page,
+            Err(e) => {
+                detail.chunk_target = None;
+                self.error_message = format!("Failed to load chunks: {}", e);
+                return;
+            }
+        };
+        let fetched = !page.items.is_empty();
+        detail.chunks.extend(page.items);
+        detail.chunks_total = page.total;
+        self.status_message =
+            format!("Loaded {} of {} chunks", detail.chunks.len(), detail.chunks_total);
+
+        let Some(target) = detail.chunk_target else {
+            return;
+        };
+        if fetched && target >= detail.chunks.len() && self.load_more_chunks() {
+            return;
+        }
+        if let Some(detail) = self.viewing_document.as_mut() {
+            detail.selected_chunk = target.min(detail.chunks.len().saturating_sub(1));
+            detail.chunk_target = None;
+        }
+    }
+
+    /// Move the chunk selection, or scroll the full text, by `rows`
+    fn move_in_document(&mut self, rows: i32) {
+        let Some(detail) = self.viewing_document.as_mut() else {
+            return;
+        };
+        if detail.show_full_text {
+            detail.text_scroll = (detail.text_scroll as i32).saturating_add(rows).max(0) as u16;
+            return;
+        }
+        let target = (detail.selected_chunk as i64 + rows as i64).max(0) as usize;
+        detail.selected_chunk = target.min(detail.chunks.len().saturating_sub(1));
+        detail.chunk_target = None;
+        // Fetch the next page on demand when moving past the last loaded chunk
+        if target >= detail.chunks.len() && self.load_more_chunks() {
+            if let Some(detail) = self.viewing_document.as_mut() {
+                detail.chunk_target = Some(target);
+            }
+        }
+    }
+
+    fn document_top(&mut self) {
+        if let Some(detail) = self.viewing_document.as_mut() {
+            detail.selected_chunk = 0;
+            detail.text_scroll = 0;
+        }
+    }
+
+    /// Jump to the end of the full text, or to the last chunk (fetching remaining pages
+    /// in the background)
+    fn document_bottom(&mut self) {
+        if self.viewing_document.as_ref().is_some_and(|d| d.show_full_text) {
+            if let Some(detail) = self.viewing_document.as_mut() {
+                // Clamped to the content height on render
+                detail.text_scroll = u16::MAX;
+            }
+            return;
+        }
+        if let Some(detail) = self.viewing_document.as_mut() {
+            detail.selected_chunk = detail.chunks.len().saturating_sub(1);
+            detail.chunk_target = None;
+        }
+        if self.load_more_chunks() {
+            if let Some(detail) = self.viewing_document.as_mut() {
+                detail.chunk_target = Some(usize::MAX);
+            }
+        }
+    }
+
+    fn toggle_document_text(&mut self) {
+        if let Some(detail) = self.viewing_document.as_mut() {
+            detail.show_full_text = !detail.show_full_text;
+            self.status_message = if detail.show_full_text {
+                "Showing full text (t for chunks)".to_string()
+            } else {
+                "Showing chunks (t for full text)".to_string()
+            };
+        }
+    }
+
+    fn open_memory_detail(&mut self, index: usize) {
+        if let Some(memory) = self.memories.get(index) {
+            self.viewing_memory = Some(memory.clone());
+            self.memories_state.select(Some(index));
+            self.memory_detail_scroll = 0;
+            self.status_message = format!(
+                "Viewing memory {}/{} (j/k scroll, n/p next/prev, Esc to close)",
+                index + 1,
+                self.memories.len()
+            );
+        }
+    }
+
+    /// Move the detail popup to the next or previous memory on the current page
+    /// (or among the reflect response's sources)
+    fn step_memory_detail(&mut self, forward: bool) {
+        if matches!(self.view, View::Query(_)) {
+            let current = self.reflect_sources_state.selected().unwrap_or(0);
+            let target = if forward { current + 1 } else { current.wrapping_sub(1) };
+            if target < self.reflect_sources.len() {
+                self.open_reflect_source(target);
+            } else {
+                self.status_message = "No more sources".to_string();
+            }
+            return;
+        }
+        let current = self.memories_state.selected().unwrap_or(0);
+        let target = if forward {
+            current + 1
+        } else {
+            match current.checked_sub(1) {
+                Some(i) => i,
+                None => {
+                    self.status_message = "Already at the first memory on this page".to_string();
+                    return;
+                }
+            }
+        };
+        if target >= self.memories.len() {
+            self.status_message = "Already at the last memory on this page".to_string();
+            return;
+        }
+        self.open_memory_detail(target);
+    }
+
+    /// Show or hide the source chunk pane, opening the selected recall result if needed
+    fn toggle_recall_chunk(&mut self) {
+        if self.viewing_recall_result.is_none() {
+            self.recall_chunk_visible = true;
+            self.enter_view();
+            return;
+        }
+        self.recall_chunk_visible = !self.recall_chunk_visible;
+        self.load_recall_chunk();
+    }
+
+    /// Turn recall tracing on or off; turning it on re-runs the current query with a trace
+    fn toggle_trace(&mut self) {
+        self.trace_enabled = !self.trace_enabled;
+        if !self.trace_enabled {
+            self.status_message = "Trace off".to_string();
+            return;
+        }
+        self.trace_scroll = 0;
+        if self.query_text.is_empty() || self.is_loading(RequestSlot::Query) {
+            self.status_message = "Trace on: the next recall includes a trace".to_string();
+        } else {
+            self.execute_query();
+            self.status_message = "Trace on: re-running the query with a trace".to_string();
+        }
+    }
+
+    fn toggle_trace_collapsed(&mut self) {
+        if self.trace_enabled {
+            self.trace_collapsed = !self.trace_collapsed;
+        }
+    }
+
+    /// Switch the trace pane between the parsed report and the raw JSON
+    fn toggle_trace_raw(&mut self) {
+        if self.trace_enabled {
+            self.trace_raw = !self.trace_raw;
+            self.trace_collapsed = false;
+            self.trace_scroll = 0;
+        }
+    }
+
+    fn scroll_trace(&mut self, rows: i32) {
+        self.trace_scroll = (self.trace_scroll as i32 + rows).max(0) as u16;
+    }
+
+    /// Fetch the open recall result's chunk when the response did not include it
+    fn load_recall_chunk(&mut self) {
+        if !self.recall_chunk_visible {
+            return;
+        }
+        let Some(chunk_id) = self.viewing_recall_result.as_ref().and_then(|r| r.chunk_id.clone())
+        else {
+            return;
+        };
+        if self.recall_chunks.contains_key(&chunk_id) {
+            return;
+        }
+        self.spawn_request(RequestSlot::Detail, "Loading chunk", true, move |client| {
+            Response::Chunk(client.get_chunk(&chunk_id, false))
+        });
+    }
+
+    /// Scroll the reflect text, or move in its source list when that has focus
+    fn move_in_reflect(&mut self, rows: i32) {
+        if !self.reflect_sources_focused {
+            let scrolled = (self.reflect_scroll as i32).saturating_add(rows).max(0);
+            // Clamped to the wrapped text height when next rendered
+            self.reflect_scroll = scrolled.min(u16::MAX as i32) as u16;
+            return;
+        }
+        if self.reflect_sources.is_empty() {
+            return;
+        }
+        let last = self.reflect_sources.len() as i64 - 1;
+        let current = self.reflect_sources_state.selected().unwrap_or(0) as i64;
+        let target = (current + rows as i64).clamp(0, last);
+        self.reflect_sources_state.select(Some(target as usize));
+    }
+
+    /// Tab between the reflect text and its source list
+    fn toggle_reflect_focus(&mut self) {
+        if self.reflect_sources.is_empty() {
+            self.reflect_sources_focused = false;
+            return;
+        }
+        self.reflect_sources_focused = !self.reflect_sources_focused;
+        self.status_message = if self.reflect_sources_focused {
+            "Sources: j/k to move, Enter to open (Tab for text)".to_string()
+        } else {
+            "Response text: j/k to scroll (Tab for sources)".to_string()
+        };
+    }
+
+    /// Fetch a source memory of the reflect response for the detail popup
+    fn open_reflect_source(&mut self, index: usize) {
+        let Some(bank_id) = self.view.bank_id().map(str::to_string) else {
+            return;
+        };
+        let Some(memory_id) = self.reflect_sources.get(index).and_then(|f| f.id.clone()) else {
+            self.error_message = "This source has no memory ID".to_string();
+            return;
+        };
+        self.reflect_sources_state.select(Some(index));
+        self.spawn_request(RequestSlot::Detail, "Loading memory", true, move |client| {
+            Response::Memory(client.get_memory(&bank_id, &memory_id, false))
+        });
+    }
+
+    fn scroll_memory_detail(&mut self, rows: i32) {
+        let scrolled = (self.memory_detail_scroll as i32).saturating_add(rows).max(0);
+        // Clamped to the content height when the popup is next rendered
+        self.memory_detail_scroll = scrolled.min(u16::MAX as i32) as u16;
+    }
+
+    fn go_back(&mut self) {
+        // If viewing a detail view, close it first
+        if self.viewing_memory.is_some() {
+            self.viewing_memory = None;
+            self.status_message = "Closed memory view".to_string();
+            return;
+        }
+        // A drill-down stays open under views jumped to from it, and closes once back there
+        if self.viewing_entity.is_some() && matches!(self.view, View::Entities(_)) {
+            self.viewing_entity = None;
+            self.cancel_slot(RequestSlot::Detail);
+            self.status_message = "Back to entities".to_string();
+            return;
+        }
+        if self.viewing_document.is_some() {
+            self.viewing_document = None;
+            self.cancel_slot(RequestSlot::Chunks);
+            self.status_message = "Closed document view".to_string();
+            return;
+        }
+        if self.viewing_recall_result.is_some() {
+            self.viewing_recall_result = None;
+            self.status_message = "Closed recall result view".to_string();
+            return;
+        }
+
+        // Otherwise go back to previous view
+        if let Some(prev_view) = self.view_history.pop() {
+            self.view = prev_view;
+            self.follow = None;
+            self.related = None;
+            let depth = self.view_history.len();
+            if self.related_history.last().is_some_and(|(at, _)| *at == depth) {
+                self.related = self.related_history.pop().map(|(_, related)| related);
+            }
+            self.clear_filter();
+            self.refresh();
+        }
+    }
+
+    /// Whether follow mode is on and not paused
+    fn following(&self) -> bool {
+        self.follow.as_ref().is_some_and(|follow| !follow.paused)
+            && matches!(self.view, View::Memories(_))
+    }
+
+    /// `F`: start following new memories, resume after a pause, or stop
+    fn toggle_follow(&mut self) {
+        match self.follow.as_mut() {
+            Some(follow) if follow.paused => {
+                follow.paused = false;
+                self.status_message = "Following new memories again".to_string();
+            }
+            Some(_) => {
+                self.follow = None;
+                self.status_message = "Stopped following".to_string();
+                return;
+            }
+            None => {
+                self.follow = Some(Follow::default());
+                self.memories_sort = None;
+                self.status_message = "Following new memories (F to stop)".to_string();
+            }
+        }
+        // Newest first, on the first page
+        self.memories_page = Paging::new();
+        self.memories_state.select(Some(0));
+        *self.memories_state.offset_mut() = 0;
+        self.refresh();
+    }
+
+    /// After a load in follow mode: note which memories are new, and stay on the newest
+    fn track_arrivals(&mut self) {
+        let Some(follow) = self.follow.as_mut() else {
+            return;
+        };
+        let ids: HashSet<String> =
+            self.memories_loaded.iter().map(|memory| memory.id.clone()).collect();
+        // Only IDs not seen before count, so rows moving around do not flash
+        follow.arrived = match &follow.seen {
+            Some(seen) => ids.difference(seen).cloned().collect(),
+            None => HashSet::new(),
+        };
+        follow.seen.get_or_insert_with(HashSet::new).extend(ids);
+        if !follow.arrived.is_empty() {
+            self.status_message = format!("{} new memories", follow.arrived.len());
+        }
+        if !follow.paused && !self.memories.is_empty() {
+            self.memories_state.select(Some(0));
+            *self.memories_state.offset_mut() = 0;
+        }
+    }
+
+    /// Moving off the newest memory pauses follow mode, as scrolling does in `less +F`
+    fn pause_follow_if_moved(&mut self) {
+        let moved = self.memories_state.selected().is_some_and(|row| row > 0)
+            || self.memories_page.offset > 0;
+        if let Some(follow) = self.follow.as_mut() {
+            if !follow.paused && moved && matches!(self.view, View::Memories(_)) {
+                follow.paused = true;
+                self.status_message = "Follow paused (F to resume)".to_string();
+            }
+        }
+    }
+
+    /// How `view` loads its rows, for the views with pages of their own
+    fn load_options(&self, view: &View) -> Option<LoadOptions> {
+        match view {
+            View::Memories(_) => Some(self.memories_load),
+            View::Entities(_) => Some(self.entities_load),
+            View::Documents(_) => Some(self.documents_load),
+            _ => None,
+        }
+    }
+
+    /// `O`: open the options of the current list and of recall
+    fn open_options(&mut self) {
+        self.options_popup = Some(OptionsPopup {
+            load: self.load_options(&self.view),
+            recall_chunks: self.include_recall_chunks,
+            reflect_max_tokens: self.reflect_max_tokens,
+            field: 0,
+        });
+    }
+
+    /// Keep the options edited in the popup; a new page size reloads from the first page
+    fn apply_options(&mut self) {
+        let Some(popup) = self.options_popup.take() else {
+            return;
+        };
+        self.include_recall_chunks = popup.recall_chunks;
+        self.reflect_max_tokens = popup.reflect_max_tokens;
+        let (Some(load), Some(before)) = (popup.load, self.load_options(&self.view)) else {
+            self.status_message = "Options saved".to_string();
+            return;
+        };
+        if load == before {
+            self.status_message = "Options unchanged".to_string();
+            return;
+        }
+        let (options, page) = match &self.view {
+            View::Memories(_) => (&mut self.memories_load, &mut self.memories_page),
+            View::Entities(_) => (&mut self.entities_load, &mut self.entities_page),
+            _ => (&mut self.documents_load, &mut self.documents_page),
+        };
+        *options = load;
+        if load.page_size != before.page_size {
+            let first = Paging {
+                limit: load.page_size,
+                ..Paging::new()
+            };
+            *page = first;
+            self.start_list_load(self.view.clone(), first, Landing::First, true, false);
+        } else {
+            self.reload_list(true);
+        }
+    }
+
+    /// `s`: sort the loaded page by the next column; after the last one, back to the server's order
+    fn cycle_sort(&mut self) {
+        let (sort, titles): (_, Vec<&str>) = match &self.view {
+            View::Memories(_) => {
+                (self.memories_sort, MEMORY_COLUMNS.iter().map(|c| c.title).collect())
+            }
+            View::Documents(_) if self.viewing_document.is_none() => {
+                (self.documents_sort, DOCUMENT_COLUMNS.iter().map(|c| c.title).collect())
+            }
+            _ => return,
+        };
+        let sort = match sort {
+            None => Some(0),
+            Some(column) if column + 1 < titles.len() => Some(column + 1),
+            Some(_) => None,
+        };
+        self.status_message = match sort {
+            Some(column) => format!("Sorted by {} (loaded rows only)", titles[column]),
+            None => "Back to the server's order".to_string(),
+        };
+        if matches!(self.view, View::Memories(_)) {
+            // Follow mode keeps its own order, newest first
+            self.follow = None;
+            self.memories_sort = sort;
+        } else {
+            self.documents_sort = sort;
+        }
+
+        let memories = KeptSelection::new(&self.memories, &self.memories_state, memory_row_id);
+        let documents = KeptSelection::new(&self.documents, &self.documents_state, document_row_id);
+        self.apply_filter();
+        memories.land(&mut self.memories_state, &self.memories, Landing::Keep, memory_row_id);
+        documents.land(&mut self.documents_state, &self.documents, Landing::Keep, document_row_id);
+    }
+
+    /// Remember the current view, and what it is narrowed to, for Esc to return to
+    fn push_view_history(&mut self) {
+        self.follow = None;
+        if let Some(related) = self.related.take() {
+            self.related_history.push((self.view_history.len(), related));
+        }
+        self.view_history.push(self.view.clone());
+    }
+
+    /// Leave a detail popup for the list of related items in `view`
+    fn jump(&mut self, view: View, related: Option<Related>) {
+        self.viewing_memory = None;
+        if matches!(self.view, View::Documents(_)) {
+            self.viewing_document = None;
+            self.cancel_slot(RequestSlot::Chunks);
+        }
+        self.push_view_history();
+        // The list itself is the destination, not a detail left open there earlier
+        if matches!(view, View::Entities(_)) {
+            self.viewing_entity = None;
+        }
+        self.view = view;
+        self.related = related;
+        self.clear_filter();
+        self.refresh();
+    }
+
+    /// `d` in the memory popup: open the document the memory was extracted from
+    fn jump_to_memory_document(&mut self) {
+        let (Some(bank_id), Some(memory)) = (self.view.bank_id(), self.viewing_memory.as_ref())
+        else {
+            return;
+        };
+        let bank_id = bank_id.to_string();
+        let Some(document_id) = memory_document_id(&bank_id, memory) else {
+            self.status_message = "This memory has no source document".to_string();
+            return;
+        };
+        self.jump(View::Documents(bank_id.clone()), None);
+        self.open_document_detail(&bank_id, document_id);
+    }
+
+    /// `e` in the memory popup: list the entities the memory mentions
+    fn jump_to_memory_entities(&mut self) {
+        let (Some(bank_id), Some(memory)) = (self.view.bank_id(), self.viewing_memory.as_ref())
+        else {
+            return;
+        };
+        let bank_id = bank_id.to_string();
+        let names = memory_entity_names(memory);
+        let memory_id = memory.id.clone();
+        if names.is_empty() {
+            self.status_message = "This memory mentions no entities".to_string();
+            return;
+        }
+        self.entities_page = Paging::new();
+        let related = Related::MemoryEntities { memory_id, names };
+        self.jump(View::Entities(bank_id), Some(related));
+    }
+
+    /// `m` in the document detail: list the memories extracted from the document
+    fn jump_to_document_memories(&mut self) {
+        let Some(detail) = self.viewing_document.as_ref() else {
+            return;
+        };
+        let bank_id = detail.document.bank_id.clone();
+        let related = Related::DocumentMemories(detail.document.id.clone());
+        self.memories_page = Paging::new();
+        self.jump(View::Memories(bank_id), Some(related));
+    }
+
+    /// Make `bank_id` the current bank, dropping the previous bank's rows and pages
+    fn select_bank(&mut self, bank_id: &str) {
+        self.selected_bank_id = Some(bank_id.to_string());
+        self.follow = None;
+        self.related = None;
+        self.memories_page = Paging::new();
+        self.entities_page = Paging::new();
+        self.documents_page = Paging::new();
+        self.operations_page = Paging::new();
+        // Don't show the previous bank's rows while this one loads
+        self.memories_loaded.clear();
+        self.entities_loaded.clear();
+        self.documents_loaded.clear();
+        self.operations_loaded.clear();
+        self.apply_filter();
+    }
+
+    /// The current bank, or the highlighted one in the Banks view, made current
+    fn bank_to_open(&mut self) -> Option<String> {
+        match &self.view {
+            View::Banks => {
+                let bank_id = self.selected_bank()?;
+                if self.selected_bank_id.as_deref() != Some(bank_id.as_str()) {
+                    self.select_bank(&bank_id);
+                }
+                Some(bank_id)
+            }
+            view => view.bank_id().map(str::to_string),
+        }
+    }
+
+    /// Show the operations of the current bank, or of the highlighted one in the Banks view
+    fn open_operations(&mut self) {
+        let Some(bank_id) = self.bank_to_open() else {
+            return;
+        };
+        self.viewing_memory = None;
+        self.switch_to_view(View::Operations(bank_id));
+    }
+
+    /// Show the profile of the current bank, or of the highlighted one in the Banks view
+    fn open_profile(&mut self) {
+        let Some(bank_id) = self.bank_to_open() else {
+            return;
+        };
+        if self.profile.as_ref().map(|p| p.bank_id.as_str()) != Some(bank_id.as_str()) {
+            self.profile = None;
+        }
+        self.profile_before = None;
+        self.profile_scroll = 0;
+        self.viewing_memory = None;
+        self.switch_to_view(View::Profile(bank_id));
+    }
+
+    /// Show the statistics dashboard of the current bank, or of the highlighted one
+    fn open_stats(&mut self) {
+        let Some(bank_id) = self.bank_to_open() else {
+            return;
+        };
+        if self.stats.as_ref().map(|s| s.bank_id.as_str()) != Some(bank_id.as_str()) {
+            self.stats = None;
+            self.stats_history.clear();
+        }
+        self.viewing_memory = None;
+        self.switch_to_view(View::Stats(bank_id));
+    }
+
+    /// Show the memories, entities or documents of the current bank, or of the highlighted
+    /// one in the Banks view. Asking for the view already shown does nothing.
+    fn open_bank_list(&mut self, view: fn(String) -> View) {
+        let Some(bank_id) = self.bank_to_open() else {
+            return;
+        };
+        let view = view(bank_id);
+        if self.view == view {
+            return;
+        }
+        self.viewing_memory = None;
+        // The list itself is the destination, not a detail left open there earlier
+        if matches!(view, View::Entities(_)) {
+            self.viewing_entity = None;
+        }
+        self.switch_to_view(view);
+    }
+
+    fn move_in_profile(&mut self, rows: i32) {
+        let scrolled = (self.profile_scroll as i32).saturating_add(rows).max(0);
+        // Clamped to the wrapped mission height when next rendered
+        self.profile_scroll = scrolled.min(u16::MAX as i32) as u16;
+    }
+
+    /// Rename the bank and merge `background` into its mission, skipping either when
+    /// not given. False under dry-run.
+    /// Ask to retry the selected failed operation or cancel the selected pending one
+    fn request_operation_action(&mut self, action: OperationAction) {
+        let View::Operations(bank_id) = &self.view else {
+            return;
+        };
+        let selected = self.operations_state.selected();
+        let Some(op) = selected.and_then(|i| self.operations.get(i)) else {
+            return;
+        };
+        let allowed = match action {
+            OperationAction::Retry => op.status == "failed",
+            OperationAction::Cancel => op.status == "pending",
+        };
+        if !allowed {
+            self.error_message = match action {
+                OperationAction::Retry => "Only failed operations can be retried".to_string(),
+                OperationAction::Cancel => "Only pending operations can be cancelled".to_string(),
+            };
+            return;
+        }
+        self.pending_operation = Some(PendingOperation {
+            action,
+            bank_id: bank_id.clone(),
+            operation: op.clone(),
+        });
+    }
+
+    /// Send the confirmed retry or cancel, then reload the list to show the new status
+    fn confirm_operation_action(&mut self) {
+        let Some(pending) = self.pending_operation.take() else {
+            return;
+        };
+        let (bank_id, id) = (&pending.bank_id, &pending.operation.id);
+        let (verb, result) = match pending.action {
+            OperationAction::Retry => {
+                let plan = PlannedWrite::new("retry_operation", bank_id).target(id);
+                let result = executor::get().execute(plan, || {
+                    self.connection.time(|| self.client.retry_operation(bank_id, id, false))
+                });
+                ("retry", result.map(|r| r.map(|r| (r.success, r.message))))
+            }
+            OperationAction::Cancel => {
+                let plan = PlannedWrite::new("cancel_operation", bank_id).target(id);
+                let result = executor::get().execute(plan, || {
+                    self.connection.time(|| self.client.cancel_operation(bank_id, id, false))
+                });
+                let outcome = |r: DeleteResponse| (r.success, r.message.unwrap_or_default());
+                ("cancel", result.map(|r| r.map(outcome)))
+            }
+        };
+        match result {
+            Ok(None) => self.status_message = format!("Dry run: would {} operation {}", verb, id),
+            Ok(Some((true, _))) => {
+                self.error_message.clear();
+                self.status_message = format!("Operation {}: {} requested", id, verb);
+                self.reload_list(true);
+            }
+            Ok(Some((false, message))) => {
+                self.error_message = format!("Failed to {} operation {}: {}", verb, id, message)
+            }
+            Err(e) => self.error_message = format!("Failed to {} operation {}: {}", verb, id, e),
+        }
+    }
+
+    fn open_memory_draft(&mut self, draft: MemoryDraft) {
+        self.memory_draft = Some(draft);
+        self.input_mode = InputMode::Memory;
+    }
+
+    fn close_memory_draft(&mut self) {
+        self.memory_draft = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn edit_memory_draft(&mut self, key: &KeyEvent) {
+        let Some(draft) = self.memory_draft.as_mut() else {
+            return;
+        };
+        let newline = match key.code {
+            KeyCode::Enter => !key.modifiers.is_empty(),
+            KeyCode::Char('j') => key.modifiers.contains(KeyModifiers::CONTROL),
+            _ => false,
+        };
+        draft.focus = match (key.code, draft.focus) {
+            (KeyCode::Tab, DraftField::Text) | (KeyCode::BackTab, DraftField::Occurred) => {
+                DraftField::Type
+            }
+            (KeyCode::Tab, DraftField::Type) | (KeyCode::BackTab, DraftField::Text) => {
+                DraftField::Occurred
+            }
+            (KeyCode::Tab, DraftField::Occurred) | (KeyCode::BackTab, DraftField::Type) => {
+                DraftField::Text
+            }
+            (_, focus) => {
+                match focus {
+                    DraftField::Text if newline => draft.text.insert('\n'),
+                    DraftField::Text => {
+                        draft.text.handle_key(key);
+                    }
+                    DraftField::Type => match key.code {
+                        KeyCode::Left => draft.cycle_type(false),
+                        KeyCode::Right | KeyCode::Char(' ') => draft.cycle_type(true),
+                        _ => {}
+                    },
+                    DraftField::Occurred => {
+                        draft.occurred.handle_key(key);
+                    }
+                }
+                focus
+            }
+        };
+    }
+
+    /// Check the draft and retain it in the background, showing it at the top of the list
+    /// until the reload after it lands
+    fn submit_memory_draft(&mut self) {
+        let View::Memories(bank_id) = &self.view else {
+            return;
+        };
+        let bank_id = bank_id.clone();
+        let Some(draft) = self.memory_draft.clone() else {
+            return;
+        };
+        let text = draft.text.as_str().trim().to_string();
+        if text.is_empty() {
+            self.error_message = "Memory text cannot be empty".to_string();
+            return;
+        }
+        let occurred = match draft.occurred.as_str().trim() {
+            "" => None,
+            raw => match crate::utils::parse_datetime_arg(raw) {
+                Ok(time) => Some(time.to_rfc3339()),
+                Err(e) => {
+                    self.error_message = format!("Occurred: {}", e);
+                    return;
+                }
+            },
+        };
+        let document_id = config::generate_doc_id();
+        let item = build_memory_item(
+            &text,
+            None,
+            occurred.as_deref(),
+            &document_id,
+            draft.fact_type(),
+        );
+        let request = match item {
+            Ok(item) => RetainRequest {
+                items: vec![item],
+                async_: false,
+                document_tags: None,
+            },
+            Err(e) => {
+                self.error_message = format!("{:#}", e);
+                return;
+            }
+        };
+
+        let row = MemoryListItem {
+            id: SAVING_ID.to_string(),
+            text,
+            fact_type: Some(draft.fact_type().unwrap_or("new").to_string()),
+            mentioned_at: Some(chrono::Utc::now().to_rfc3339()),
+            occurred_start: occurred,
+            occurred_end: None,
+            created_at: None,
+            date: None,
+            context: None,
+            document_id: None,
+            chunk_id: None,
+            metadata: None,
+            extra: Map::new(),
+        };
+        self.memories_loaded.insert(0, row);
+        self.apply_filter();
+        self.memories_state.select(Some(0));
+
+        self.close_memory_draft();
+        self.error_message.clear();
+        self.status_message = "Adding memory...".to_string();
+        self.spawn_request(RequestSlot::Retain, "Adding memory", false, move |client| {
+            let plan = PlannedWrite::new("retain", &bank_id).target(&document_id);
+            let result = executor::get()
+                .execute(plan, || client.retain(&bank_id, &request, false, false));
+            Response::Retained {
+                bank_id,
+                draft,
+                result,
+            }
+        });
+    }
+
+    /// Reload to replace the stand-in row with the stored memory, or drop it and reopen
+    /// the draft when the memory was not added
+    fn apply_retained(
+        &mut self,
+        bank_id: &str,
+        draft: MemoryDraft,
+        result: Result<Option<MemoryPutResult>>,
+    ) {
+        let here = self.view == View::Memories(bank_id.to_string());
+        if let Ok(Some(stored)) = &result {
+            self.status_message = format!("Added memory ({} units stored)", stored.items_count);
+            if here {
+                self.reload_list(false);
+            }
+            return;
+        }
+
+        let text = draft.text.as_str().trim();
+        let stand_in = |row: &MemoryListItem| row.id == SAVING_ID && row.text == text;
+        if let Some(i) = self.memories_loaded.iter().position(stand_in) {
+            self.memories_loaded.remove(i);
+            self.apply_filter();
+        }
+        match result {
+            Ok(_) => self.status_message = "Dry run: the memory would be added".to_string(),
+            Err(e) => {
+                self.error_message = format!("Failed to add memory: {:#}", e);
+                if here && self.input_mode == InputMode::Normal {
+                    self.open_memory_draft(draft);
+                }
+            }
+        }
+    }
+
+    /// Whether a confirmation modal is waiting for an answer
+    fn modal_open(&self) -> bool {
+        self.pending_delete.is_some()
+            || self.pending_operation.is_some()
+            || self.pending_close_tab
+            || self.pending_quit.is_some()
+            || self.options_popup.is_some()
+    }
+
+    /// The item selected in the current view, or open in a detail popup, as JSON
+    /// with a title naming it
+    fn selected_json(&self) -> Option<(String, Value)> {
+        fn pick<T: Serialize>(items: &[T], state: &ListState) -> Option<Value> {
+            let item = items.get(state.selected()?)?;
+            serde_json::to_value(item).ok()
+        }
+        fn whole<T: Serialize>(item: Option<&T>) -> Option<Value> {
+            serde_json::to_value(item?).ok()
+        }
+
+        let (kind, value) = match &self.view {
+            _ if self.viewing_memory.is_some() => ("Memory", whole(self.viewing_memory.as_ref())),
+            View::Banks => ("Bank", pick(&self.banks, &self.banks_state)),
+            View::Memories(_) => ("Memory", pick(&self.memories, &self.memories_state)),
+            View::Entities(_) => match &self.viewing_entity {
+                Some(drilldown) => ("Memory", pick(&drilldown.memories, &drilldown.memories_state)),
+                None => ("Entity", pick(&self.entities, &self.entities_state)),
+            },
+            View::Documents(_) => match &self.viewing_document {
+                Some(detail) => ("Document", whole(Some(&detail.document))),
+                None => ("Document", pick(&self.documents, &self.documents_state)),
+            },
+            View::Operations(_) => ("Operation", pick(&self.operations, &self.operations_state)),
+            View::Query(_) if self.query_mode == QueryMode::Recall => {
+                ("Recall result", pick(&self.query_results, &self.query_results_state))
+            }
+            View::Query(_) => ("Source", pick(&self.reflect_sources, &self.reflect_sources_state)),
+            View::Profile(_) => ("Profile", whole(self.profile.as_ref())),
+            View::Stats(_) => ("Stats", whole(self.stats.as_ref())),
+        };
+        let value = value?;
+        let name = ["id", "bank_id", "canonical_name"]
+            .iter()
+            .find_map(|key| value.get(*key).and_then(|v| v.as_str()));
+        let title = match name {
+            Some(name) => format!("{} {}", kind, name),
+            None => kind.to_string(),
+        };
+        Some((title, value))
+    }
+
+    fn open_json_inspector(&mut self) {
+        let Some((title, value)) = self.selected_json() else {
+            self.error_message = "Nothing selected to inspect".to_string();
+            return;
+        };
+        let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+        self.json_inspector = Some(JsonInspector {
+            title,
+            text,
+            scroll: 0,
+            height: 0,
+        });
+    }
+
+    fn scroll_json_inspector(&mut self, rows: i32) {
+        if let Some(inspector) = self.json_inspector.as_mut() {
+            let scrolled = (inspector.scroll as i32).saturating_add(rows).max(0);
+            // Clamped to the content height when the popup is next rendered
+            inspector.scroll = scrolled.min(u16::MAX as i32) as u16;
+        }
+    }
+
+    /// Put the inspected JSON on the system clipboard
+    fn copy_json_inspector(&mut self) {
+        if let Some(inspector) = &self.json_inspector {
+            let text = inspector.text.clone();
+            self.copy_to_clipboard(&text, "of JSON");
+        }
+    }
+
+    /// `y`: the main text of the selected item, such as a memory's text or an entity's name
+    fn copy_selected(&mut self) {
+        let text = self.selected_json().and_then(|(_, value)| {
+            ["text", "canonical_name", "id", "bank_id"]
+                .iter()
+                .find_map(|key| value.get(*key).and_then(Value::as_str).map(str::to_string))
+        });
+        match text {
+            Some(text) => self.copy_to_clipboard(&text, ""),
+            None => self.error_message = "Nothing selected to copy".to_string(),
+        }
+    }
+
+    /// `Y`: every row of the current list as Markdown bullets
+    fn copy_list(&mut self) {
+        let Some((data, _)) = self.export_data() else {
+            self.error_message = "Copying a list works in the lists of a bank and in recall results"
+                .to_string();
+            return;
+        };
+        if data.items.is_empty() {
+            self.error_message = "Nothing to copy".to_string();
+            return;
+        }
+        let what = format!("of {} rows as Markdown", data.items.len());
+        self.copy_to_clipboard(&data.to_bullets(), &what);
+    }
+
+    /// Copy through the system clipboard, or over SSH through the terminal with OSC 52
+    fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+        let copied = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(text).map_err(|e| e.to_string()),
+            None => Err("no clipboard provider".to_string()),
+        };
+        let copied = match copied {
+            Err(_) if is_ssh_session() => write_osc52(text).map_err(|e| e.to_string()),
+            other => other,
+        };
+        let chars = text.chars().count();
+        match copied {
+            Ok(()) => {
+                let what = if what.is_empty() { String::new() } else { format!(" {}", what) };
+                self.status_message = format!("Copied {} chars{}", chars, what);
+            }
+            Err(e) => {
+                self.error_message = format!(
+                    "Could not copy ({}); start with --no-mouse to select the text in the terminal",
+                    e
+                )
+            }
+        }
+    }
+
+    /// Label of the tab showing this explorer, like `team:memories`
+    fn tab_title(&self) -> String {
+        let view = self.view.title().to_lowercase();
+        match self.view.bank_id() {
+            Some(bank_id) => format!("{}:{}", bank_id, view),
+            None => view,
+        }
+    }
+
+    fn switch_to_view(&mut self, new_view: View) {
+        if self.view != new_view {
+            self.push_view_history();
+            self.view = new_view;
+            self.clear_filter();
+            self.refresh();
+        }
+    }
+
+    /// Rows of the Documents list, as (ID, preview) pairs
+    fn delete_candidates(&self) -> Option<(String, Vec<(String, String)>)> {
+        match &self.view {
+            View::Documents(bank_id) if self.viewing_document.is_none() => {
+                let rows = self.documents.iter().map(|d| {
+                    let memories = d.memory_unit_count.unwrap_or(0);
+                    let size = format_size(d.text_length.unwrap_or(0));
+                    (d.id.clone(), format!("{}, {} memories", size, memories))
+                });
+                Some((bank_id.clone(), rows.collect()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Toggle the deletion mark on the selected row and move to the next one
+    fn toggle_mark(&mut self) {
+        let (marks, id) = match &self.view {
+            View::Memories(_) if self.viewing_memory.is_none() => {
+                let selected = self
+                    .memories_state
+                    .selected()
+                    .and_then(|i| self.memories.get(i));
+                (&mut self.marked_memories, selected.and_then(memory_row_id))
+            }
+            View::Documents(_) if self.viewing_document.is_none() => {
+                let selected = self
+                    .documents_state
+                    .selected()
+                    .and_then(|i| self.documents.get(i));
+                (
+                    &mut self.marked_documents,
+                    selected.and_then(document_row_id),
+                )
+            }
+            _ => return,
+        };
+        let Some(id) = id else {
+            return;
+        };
+        if !marks.remove(id) {
+            marks.insert(id.to_string());
+        }
+        self.status_message = format!("{} marked for deletion", marks.len());
+        self.next_item();
+    }
+
+    /// Open the confirmation modal for the marked rows, or the selected row if none are marked
+    fn request_delete(&mut self) {
+        let memories_bank = match &self.view {
+            View::Memories(bank_id) if self.viewing_memory.is_none() => Some(bank_id.clone()),
+            _ => None,
+        };
+        let candidates = self.delete_candidates();
+        if memories_bank.is_none() && candidates.is_none() {
+            return;
+        }
+        if self.is_loading(RequestSlot::Delete) || self.is_loading(RequestSlot::DeleteLookup) {
+            self.error_message = "Wait for the deletion in progress to finish".to_string();
+            return;
+        }
+        if let Some(bank_id) = memories_bank {
+            self.request_memory_delete(bank_id);
+            return;
+        }
+        let Some((bank_id, rows)) = candidates else {
+            return;
+        };
+        let marks = &self.marked_documents;
+        let items: Vec<(String, String)> = if marks.is_empty() {
+            let selected = self.documents_state.selected();
+            selected.and_then(|i| rows.get(i).cloned()).into_iter().collect()
+        } else {
+            rows.into_iter().filter(|(id, _)| marks.contains(id)).collect()
+        };
+        if items.is_empty() {
+            return;
+        }
+        self.pending_delete = Some(PendingDelete {
+            bank_id,
+            items,
+            memories: 0,
+            without_document: 0,
+        });
+    }
+
+    /// Look up the source documents of the marked memories, or the selected one. The API
+    /// only deletes memories together with their document, so those are what the
+    /// confirmation lists.
+    fn request_memory_delete(&mut self, bank_id: String) {
+        let marks = &self.marked_memories;
+        let picked: Vec<&MemoryListItem> = if marks.is_empty() {
+            let selected = self.memories_state.selected();
+            selected
+                .and_then(|i| self.memories.get(i))
+                .into_iter()
+                .collect()
+        } else {
+            self.memories
+                .iter()
+                .filter(|m| marks.contains(&m.id))
+                .collect()
+        };
+        if picked.is_empty() {
+            return;
+        }
+        let mut by_document: Vec<(String, usize)> = Vec::new();
+        let mut without_document = 0;
+        for memory in picked {
+            let Some(document_id) = memory_document_id(&bank_id, memory) else {
+                without_document += 1;
+                continue;
+            };
+            match by_document.iter_mut().find(|(id, _)| *id == document_id) {
+                Some((_, count)) => *count += 1,
+                None => by_document.push((document_id, 1)),
+            }
+        }
+        if by_document.is_empty() {
+            self.error_message = "Nothing deleted: memories are deleted with their source \
+                                  document, and these have none"
+                .to_string();
+            return;
+        }
+
+        self.error_message.clear();
+        self.status_message = format!("Looking up {} source documents...", by_document.len());
+        self.spawn_request(
+            RequestSlot::DeleteLookup,
+            "Looking up documents",
+            true,
+            move |client| {
+                let documents = by_document
+                    .into_iter()
+                    .map(|(id, picked)| {
+                        let document = client.get_document(&bank_id, &id, false);
+                        (id, picked, document)
+                    })
+                    .collect();
+                Response::DeleteLookup {
+                    bank_id,
+                    documents,
+                    without_document,
+                }
+            },
+        );
+    }
+
+    /// Confirm deleting the documents of the picked memories, with each one's memory count
+    fn apply_delete_lookup(
+        &mut self,
+        bank_id: String,
+        documents: Vec<(String, usize, Result<DocumentResponse>)>,
+        without_document: usize,
+    ) {
+        let mut items = Vec::new();
+        let mut memories = 0;
+        for (id, picked, document) in documents {
+            match document {
+                Ok(document) => {
+                    let total = document.memory_unit_count;
+                    items.push((id, format!("{} memories, {} of them picked", total, picked)));
+                    memories += picked;
+                }
+                Err(e) => {
+                    self.error_message = format!("Failed to look up document {}: {:#}", id, e);
+                    return;
+                }
+            }
+        }
+        self.status_message.clear();
+        self.pending_delete = Some(PendingDelete {
+            bank_id,
+            items,
+            memories,
+            without_document,
+        });
+    }
+
+    /// Delete the documents in the confirmation modal in the background
+    fn confirm_delete(&mut self) {
+        let Some(pending) = self.pending_delete.take() else {
+            return;
+        };
+        self.error_message.clear();
+        self.status_message = format!("Deleting {} documents...", pending.items.len());
+        self.spawn_request(RequestSlot::Delete, "Deleting documents", false, move |client| {
+            let results = pending
+                .items
+                .iter()
+                .map(|(id, _)| {
+                    let plan = PlannedWrite::new("delete_document", &pending.bank_id).target(id);
+                    executor::get()
+                        .execute(plan, || client.delete_document(&pending.bank_id, id, false))
+                        .map(|r| r.is_some())
+                })
+                .collect();
+            Response::Deleted { pending, results }
+        });
+    }
+
+    /// Drop the deleted documents from the list and offer to retry the ones that failed
+    fn apply_deleted(&mut self, pending: PendingDelete, results: Vec<Result<bool>>) {
+        let mut deleted = HashSet::new();
+        let mut failures = Vec::new();
+        let mut failed = Vec::new();
+        let mut planned = 0;
+        for ((id, preview), result) in pending.items.iter().zip(results) {
+            match result {
+                Ok(true) => {
+                    deleted.insert(id.clone());
+                }
+                Ok(false) => planned += 1,
+                Err(e) => {
+                    failures.push(format!("{}: {:#}", id, e));
+                    failed.push((id.clone(), preview.clone()));
+                }
+            }
+        }
+
+        let kept = |row: &DocumentListItem| !deleted.contains(&row.id);
+        self.documents.retain(kept);
+        self.documents_loaded.retain(kept);
+        self.marked_documents.retain(|id| !deleted.contains(id));
+        if let Some(i) = self.documents_state.selected() {
+            let last = self.documents.len().saturating_sub(1);
+            self.documents_state.select(Some(i.min(last)));
+        }
+        // Their memories went with them
+        let bank_id = &pending.bank_id;
+        let gone = |memory: &MemoryListItem| {
+            memory_document_id(bank_id, memory).is_some_and(|id| deleted.contains(&id))
+        };
+        let gone_ids: HashSet<String> = self
+            .memories_loaded
+            .iter()
+            .filter(|m| gone(m))
+            .map(|m| m.id.clone())
+            .collect();
+        self.memories.retain(|m| !gone_ids.contains(&m.id));
+        self.memories_loaded.retain(|m| !gone_ids.contains(&m.id));
+        self.marked_memories.retain(|id| !gone_ids.contains(id));
+        if let Some(i) = self.memories_state.selected() {
+            let last = self.memories.len().saturating_sub(1);
+            self.memories_state.select(Some(i.min(last)));
+        }
+
+        self.status_message = if planned > 0 {
+            format!("Dry run: {} documents would be deleted", planned)
+        } else {
+            format!("Deleted {} documents", deleted.len())
+        };
+        if !failures.is_empty() {
+            let title = format!(
+                "Failed to delete {} of {} documents",
+                failures.len(),
+                pending.items.len()
+            );
+            let endpoint = format!("DELETE /v1/default/banks/{}/documents/{{id}}", pending.bank_id);
+            let report =
+                ErrorReport::new(&title, endpoint, Some(&pending.bank_id), failures.join("\n"));
+            let retry = PendingDelete {
+                items: failed,
+                ..pending
+            };
+            self.error_report = Some(report.retry(Retry::Delete(retry)));
+        }
+    }
+
+    /// Open the bank popup; rename and delete apply to the selected bank
+    fn open_bank_dialog(&mut self, action: BankAction) {
+        let name = match &action {
+            BankAction::Rename(bank_id) => self
+                .banks
+                .iter()
+                .find(|b| &b.bank_id == bank_id)
+                .and_then(|b| b.name.clone())
+                .unwrap_or_default(),
+            BankAction::EditProfile(bank_id) => self
+                .profile
+                .as_ref()
+                .filter(|p| &p.bank_id == bank_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+        let mut dialog = BankDialog {
+            action,
+            fields: Vec::new(),
+            focus: 0,
+        };
+        dialog.fields = dialog.labels().iter().map(|_| TextInput::default()).collect();
+        dialog.fields[0].set(&name);
+        self.bank_dialog = Some(dialog);
+        self.input_mode = InputMode::Bank;
+    }
+
+    fn selected_bank(&self) -> Option<String> {
+        let i = self.banks_state.selected()?;
+        self.banks.get(i).map(|b| b.bank_id.clone())
+    }
+
+    fn close_bank_dialog(&mut self) {
+        self.bank_dialog = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn edit_bank_dialog(&mut self, key: &KeyEvent) {
+        let Some(dialog) = self.bank_dialog.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Tab | KeyCode::Down => dialog.focus = (dialog.focus + 1) % dialog.fields.len(),
+            KeyCode::BackTab | KeyCode::Up => {
+                dialog.focus = (dialog.focus + dialog.fields.len() - 1) % dialog.fields.len()
+            }
+            _ => {
+                dialog.fields[dialog.focus].handle_key(key);
+            }
+        }
+    }
+
+    /// Send the popup's create, rename or delete, then reload the bank list
+    /// Validate the bank popup and send its write in the background
+    fn submit_bank_dialog(&mut self) {
+        let Some(dialog) = self.bank_dialog.as_ref() else {
+            return;
+        };
+        if self.is_loading(RequestSlot::Bank) {
+            self.error_message = "Wait for the bank change in progress to finish".to_string();
+            return;
+        }
+        let field = |i: usize| dialog.fields[i].as_str().trim().to_string();
+        type BankWrite = Box<dyn FnOnce(&ApiClient) -> Result<bool> + Send>;
+        let (verb, label, done, write) = match dialog.action.clone() {
+            BankAction::Create => {
+                let bank_id = field(0);
+                if bank_id.is_empty() {
+                    self.error_message = "Bank ID cannot be empty".to_string();
+                    return;
+                }
+                let name = field(1);
+                let request = CreateBankRequest {
+                    name: (!name.is_empty()).then_some(name),
+                    ..Default::default()
+                };
+                let done = format!("Created bank '{}'", bank_id);
+                let write: BankWrite = Box::new(move |client: &ApiClient| {
+                    let plan = PlannedWrite::new("create_bank", &bank_id);
+                    executor::get()
+                        .execute(plan, || client.create_bank(&bank_id, &request, false))
+                        .map(|r| r.is_some())
+                });
+                ("create", "Creating bank", done, write)
+            }
+            BankAction::Rename(bank_id) => {
+                let name = field(0);
+                if name.is_empty() {
+                    self.error_message = "Name cannot be empty".to_string();
+                    return;
+                }
+                let done = format!("Renamed bank '{}' to '{}'", bank_id, name);
+                let write: BankWrite = Box::new(move |client: &ApiClient| {
+                    let plan = PlannedWrite::new("update_agent_name", &bank_id);
+                    executor::get()
+                        .execute(plan, || client.update_agent_name(&bank_id, &name, false))
+                        .map(|r| r.is_some())
+                });
+                ("rename", "Renaming bank", done, write)
+            }
+            BankAction::Delete(bank_id) => {
+                if field(0) != bank_id {
+                    self.error_message = format!("Type '{}' to delete this bank", bank_id);
+                    return;
+                }
+                let done = format!("Deleted bank '{}'", bank_id);
+                let write: BankWrite = Box::new(move |client: &ApiClient| {
+                    let plan = PlannedWrite::new("delete_bank", &bank_id);
+                    executor::get()
+                        .execute(plan, || client.delete_bank(&bank_id, false))
+                        .map(|r| r.is_some())
+                });
+                ("delete", "Deleting bank", done, write)
+            }
+            BankAction::EditProfile(bank_id) => {
+                let name = field(0);
+                let background = field(1);
+                let current = self.profile.as_ref().filter(|p| p.bank_id == bank_id);
+                let rename = !name.is_empty() && !current.is_some_and(|p| p.name == name);
+                if !rename && background.is_empty() {
+                    self.error_message = "Change the name or add background text".to_string();
+                    return;
+                }
+                self.profile_before = current.map(|p| p.disposition.clone());
+                let done = format!("Updated the profile of '{}'", bank_id);
+                let write: BankWrite = Box::new(move |client: &ApiClient| {
+                    let name = rename.then_some(name.as_str());
+                    update_profile(client, &bank_id, name, &background)
+                });
+                ("update", "Updating profile", done, write)
+            }
+        };
+
+        let Some(dialog) = self.bank_dialog.take() else {
+            return;
+        };
+        self.input_mode = InputMode::Normal;
+        self.error_message.clear();
+        self.status_message = format!("{}...", label);
+        self.spawn_request(RequestSlot::Bank, label, false, move |client| {
+            let result = write(client);
+            Response::Bank {
+                dialog,
+                verb,
+                done,
+                result,
+            }
+        });
+    }
+
+    /// Reload after a bank write, or reopen the popup with what was typed when it failed
+    fn apply_bank_write(
+        &mut self,
+        dialog: BankDialog,
+        verb: &str,
+        done: String,
+        result: Result<bool>,
+    ) {
+        match result {
+            Ok(sent) => {
+                if !sent {
+                    self.profile_before = None;
+                }
+                self.status_message = if sent {
+                    done
+                } else {
+                    format!("Dry run: would {} the bank", verb)
+                };
+                self.reload_list(true);
+            }
+            Err(e) => {
+                self.profile_before = None;
+                self.error_message = format!("Failed to {} bank: {}", verb, e);
+                if self.input_mode == InputMode::Normal && self.bank_dialog.is_none() {
+                    self.bank_dialog = Some(dialog);
+                    self.input_mode = InputMode::Bank;
+                }
+            }
+        }
+    }
+
+    /// The rows the current view shows, after filtering, and the bank they come from
+    fn export_data(&self) -> Option<(ExportData, String)> {
+        let data = match &self.view {
+            View::Memories(bank_id) => {
+                let title = format!("Memories in {}", bank_id);
+                (ExportData::new("memories", title, &self.memories), bank_id)
+            }
+            View::Entities(bank_id) => match &self.viewing_entity {
+                Some(drilldown) => {
+                    let name = &drilldown.entity.canonical_name;
+                    let title = format!("Memories mentioning {} in {}", name, bank_id);
+                    (ExportData::new("entity", title, &drilldown.memories), bank_id)
+                }
+                None => {
+                    let title = format!("Entities in {}", bank_id);
+                    (ExportData::new("entities", title, &self.entities), bank_id)
+                }
+            },
+            View::Documents(bank_id) => {
+                let title = format!("Documents in {}", bank_id);
+                (ExportData::new("documents", title, &self.documents), bank_id)
+            }
+            View::Query(bank_id) if self.query_mode == QueryMode::Recall => {
+                let title = format!("Recall results from {}", bank_id);
+                (ExportData::new("recall", title, &self.query_results), bank_id)
+            }
+            View::Operations(bank_id) => {
+                let title = format!("Operations in {}", bank_id);
+                (ExportData::new("operations", title, &self.operations), bank_id)
+            }
+            View::Banks | View::Query(_) | View::Profile(_) | View::Stats(_) => return None,
+        };
+        Some((data.0, data.1.clone()))
+    }
+
+    fn open_export_prompt(&mut self) {
+        let Some((data, bank_id)) = self.export_data() else {
+            self.error_message = "Export works in the lists of a bank and in recall results"
+                .to_string();
+            return;
+        };
+        if data.items.is_empty() {
+            self.error_message = "Nothing to export".to_string();
+            return;
+        }
+        let mut path = TextInput::default();
+        path.set(data.default_path(&bank_id, self.export_format));
+        self.export_prompt = Some(ExportPrompt {
+            path,
+            format: self.export_format,
+            overwrite: false,
+        });
+        self.input_mode = InputMode::Export;
+    }
+
+    fn close_export_prompt(&mut self) {
+        self.export_prompt = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Tab switches the format, renaming the file's extension along with it
+    fn edit_export_prompt(&mut self, key: &KeyEvent) {
+        let Some(prompt) = self.export_prompt.as_mut() else {
+            return;
+        };
+        if key.code == KeyCode::Tab {
+            let from = format!(".{}", prompt.format.extension());
+            prompt.format = prompt.format.toggled();
+            if let Some(stem) = prompt.path.as_str().strip_suffix(&from) {
+                let path = format!("{}.{}", stem, prompt.format.extension());
+                prompt.path.set(path);
+            }
+            self.export_format = prompt.format;
+        } else if prompt.path.handle_key(key) {
+            prompt.overwrite = false;
+        }
+    }
+
+    /// Write the current rows on a background thread; an existing file needs a second Enter
+    fn submit_export_prompt(&mut self) {
+        let Some(prompt) = self.export_prompt.as_mut() else {
+            return;
+        };
+        let path = PathBuf::from(prompt.path.as_str().trim());
+        if path.as_os_str().is_empty() {
+            self.error_message = "File name cannot be empty".to_string();
+            return;
+        }
+        if path.exists() && !prompt.overwrite {
+            prompt.overwrite = true;
+            self.error_message =
+                format!("{} exists; press Enter again to overwrite it", path.display());
+            return;
+        }
+        let (format, overwrite) = (prompt.format, prompt.overwrite);
+        let Some((data, _)) = self.export_data() else {
+            return;
+        };
+        self.close_export_prompt();
+        self.error_message.clear();
+        self.status_message =
+            format!("Exporting {} rows to {}...", data.items.len(), path.display());
+        self.spawn_request(RequestSlot::Export, "Exporting", false, move |_| {
+            let count = data.items.len();
+            Response::Export(data.write(&path, format, overwrite).map(|()| (path, count)))
+        });
+    }
+}
+
+fn ui(f: &mut Frame, app: &mut App) {
+    app.mouse = MouseAreas::default();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(5),  // Shortcuts bar (context + shortcuts, max 3 rows + 2 border)
+            Constraint::Length(3),  // Header
+            Constraint::Min(0),     // Main content
+            Constraint::Length(1),  // Footer/status only (no border)
+        ])
+        .split(f.area());
+
+    // Control bar
+    render_control_bar(f, app, chunks[0]);
+
+    // Header
+    render_header(f, app, chunks[1]);
+
+    // Main content
+    if app.show_help {
+        render_help(f, &app.theme, &app.keys, chunks[2]);
+    } else {
+        match &app.view {
+            View::Banks => render_banks(f, app, chunks[2]),
+            View::Memories(_) => render_memories(f, app, chunks[2]),
+            View::Entities(_) => render_entities(f, app, chunks[2]),
+            View::Documents(_) => render_documents(f, app, chunks[2]),
+            View::Query(_) => render_query(f, app, chunks[2]),
+            View::Operations(_) => render_operations(f, app, chunks[2]),
+            View::Profile(_) => render_profile(f, app, chunks[2]),
+            View::Stats(_) => render_stats(f, app, chunks[2]),
+        }
+    }
+
+    if app.pending_delete.is_some() {
+        render_delete_confirmation(f, app, chunks[2]);
+    }
+    if app.pending_operation.is_some() {
+        render_operation_confirmation(f, app, chunks[2]);
+    }
+    if app.pending_close_tab {
+        render_close_tab_confirmation(f, app, chunks[2]);
+    }
+    if app.pending_quit.is_some() {
+        render_quit_confirmation(f, app, chunks[2]);
+    }
+    if app.history_picker.is_some() {
+        render_history_picker(f, app, chunks[2]);
+    }
+    if app.bank_dialog.is_some() {
+        render_bank_dialog(f, app, chunks[2]);
+    }
+    if app.export_prompt.is_some() {
+        render_export_prompt(f, app, chunks[2]);
+    }
+    if app.memory_draft.is_some() {
+        render_memory_draft(f, app, chunks[2]);
+    }
+    if app.options_popup.is_some() {
+        render_options_popup(f, app, chunks[2]);
+    }
+    if app.json_inspector.is_some() {
+        render_json_inspector(f, app, chunks[2]);
+    }
+    if app.error_report.is_some() {
+        render_error_report(f, app, chunks[2]);
+    }
+
+    // Footer
+    render_footer(f, app, chunks[3]);
+}
+
+fn render_history_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let Some(picker) = app.history_picker.as_mut() else {
+        return;
+    };
+    let entries = app.query_history.matching(picker.filter.as_str());
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| {
+            let mode = match entry.mode {
+                QueryMode::Recall => "recall ",
+                QueryMode::Reflect => "reflect",
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", mode), theme.secondary),
+                Span::styled(entry.query.clone(), theme.text),
+            ]))
+        })
+        .collect();
+    const TITLE_PREFIX: &str = " Query History: ";
+    let title = format!("{}{} ({}) ", TITLE_PREFIX, picker.filter.as_str(), entries.len());
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.secondary)
+                .title(title)
+                .title_bottom(" Enter use · Esc close · type to filter "),
+        )
+        .highlight_style(theme.highlight)
+        .highlight_symbol(">> ");
+
+    let popup = centered_rect(70, 60, area);
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut picker.state);
+    f.set_cursor_position((
+        popup.x + 1 + TITLE_PREFIX.len() as u16 + picker.filter.cursor_column(),
+        popup.y,
+    ));
+}
+
+fn render_bank_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(dialog) = &app.bank_dialog else {
+        return;
+    };
+    let (title, border) = match &dialog.action {
+        BankAction::Create => (" New Bank ".to_string(), theme.secondary),
+        BankAction::Rename(bank_id) => (format!(" Rename {} ", bank_id), theme.secondary),
+        BankAction::Delete(bank_id) => (format!(" Delete {} ", bank_id), theme.error),
+        BankAction::EditProfile(bank_id) => (format!(" Edit {} ", bank_id), theme.secondary),
+    };
+    let mut lines = Vec::new();
+    if let BankAction::Delete(_) = dialog.action {
+        lines.push(Line::from(Span::styled(
+            "Every memory, document and entity in this bank is deleted with it.",
+            theme.warning,
+        )));
+        lines.push(Line::from(""));
+    }
+    let first_field_row = lines.len() as u16;
+    for (i, (label, input)) in dialog.labels().iter().zip(&dialog.fields).enumerate() {
+        let style = if i == dialog.focus {
+            theme.tertiary.add_modifier(Modifier::BOLD)
+        } else {
+            theme.muted
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}: ", label), style),
+            Span::raw(input.as_str().to_string()),
+        ]));
+    }
+    lines.push(Line::from(""));
+    let mut hints = vec![
+        Span::styled("Enter", border.add_modifier(Modifier::BOLD)),
+        Span::raw(" confirm   "),
+        Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+        Span::raw(" cancel"),
+    ];
+    if dialog.fields.len() > 1 {
+        hints.push(Span::raw("   "));
+        hints.push(Span::styled("Tab", theme.accent));
+        hints.push(Span::raw(" next field"));
+    }
+    lines.push(Line::from(hints));
+
+    let popup = centered_rect(60, 40, area);
+    let modal = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title(title),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+
+    let label = dialog.labels()[dialog.focus];
+    let input = &dialog.fields[dialog.focus];
+    f.set_cursor_position((
+        popup.x + 1 + label.width() as u16 + 2 + input.cursor_column(),
+        popup.y + 1 + first_field_row + dialog.focus as u16,
+    ));
+}
+
+fn render_operation_confirmation(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(pending) = &app.pending_operation else {
+        return;
+    };
+    let (question, verb, style) = match pending.action {
+        OperationAction::Retry => ("Retry this failed operation?", " retry   ", theme.warning),
+        OperationAction::Cancel => ("Cancel this pending operation?", " cancel it   ", theme.error),
+    };
+    let op = &pending.operation;
+    let mut lines = vec![
+        Line::from(Span::styled(question, style.add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(format!("  {} ", op.id), theme.accent),
+            Span::styled(format!("{}, {} items", op.task_type, op.items_count), theme.muted),
+        ]),
+    ];
+    if let Some(error) = &op.error_message {
+        lines.push(Line::from(Span::styled(format!("  {}", error), theme.error)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("y/Enter", style.add_modifier(Modifier::BOLD)),
+        Span::raw(verb),
+        Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+        Span::raw(" keep it"),
+    ]));
+
+    let popup = centered_rect(60, 40, area);
+    let modal = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(style)
+                .title(" Confirm "),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+}
+
+/// The JSON inspector popup, scrolled within its wrapped content
+fn render_json_inspector(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let Some(inspector) = app.json_inspector.as_mut() else {
+        return;
+    };
+    let popup = centered_rect(80, 80, area);
+    let inner_width = popup.width.saturating_sub(2).max(1) as usize;
+    let inner_height = popup.height.saturating_sub(2);
+    let content_rows: usize =
+        inspector.text.lines().map(|line| wrapped_rows(line, inner_width)).sum();
+    let max_scroll = content_rows
+        .saturating_sub(inner_height as usize)
+        .min(u16::MAX as usize) as u16;
+    inspector.scroll = inspector.scroll.min(max_scroll);
+    inspector.height = inner_height;
+
+    let lines: Vec<Line> = inspector.text.lines().map(|line| json_line(line, &theme)).collect();
+    let scroll_hint = if max_scroll > 0 {
+        format!(" {}/{} ", inspector.scroll, max_scroll)
+    } else {
+        String::new()
+    };
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.accent)
+                .title(format!(" {} (y to copy, Esc to close) ", inspector.title))
+                .title_bottom(Line::from(scroll_hint).alignment(Alignment::Right)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((inspector.scroll, 0));
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+/// Whether the explorer runs over SSH, where only the terminal can reach the user's clipboard
+fn is_ssh_session() -> bool {
+    std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some()
+}
+
+/// The OSC 52 escape that asks the terminal to put `text` on its clipboard
+fn osc52_sequence(text: &str) -> String {
+    use base64::Engine;
+    format!("\x1b]52;c;{}\x07", base64::engine::general_purpose::STANDARD.encode(text))
+}
+
+fn write_osc52(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+/// The error popup: what was called, the full message, and how to go on
+fn render_error_report(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let Some(report) = app.error_report.as_mut() else {
+        return;
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Endpoint: ", theme.muted),
+        Span::styled(report.endpoint.clone(), theme.accent),
+    ])];
+    if let Some(bank_id) = &report.bank_id {
+        lines.push(Line::from(vec![
+            Span::styled("Bank:     ", theme.muted),
+            Span::styled(bank_id.clone(), theme.accent),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.extend(report.message.lines().map(|line| Line::from(line.to_string())));
+
+    let popup = centered_rect(70, 60, area);
+    let inner_width = popup.width.saturating_sub(2).max(1) as usize;
+    let inner_height = popup.height.saturating_sub(2);
+    let content_rows: usize = lines
+        .iter()
+        .map(|line| wrapped_rows(&line.to_string(), inner_width))
+        .sum();
+    let max_scroll = content_rows
+        .saturating_sub(inner_height as usize)
+        .min(u16::MAX as usize) as u16;
+    report.scroll = report.scroll.min(max_scroll);
+    report.height = inner_height;
+
+    let mut hints = Vec::new();
+    if report.retry.is_some() {
+        hints.push(Span::styled(" r", theme.tertiary.add_modifier(Modifier::BOLD)));
+        hints.push(Span::raw(" retry  "));
+    }
+    hints.push(Span::styled(" Esc", theme.tertiary.add_modifier(Modifier::BOLD)));
+    hints.push(Span::raw(" dismiss "));
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.error)
+                .title(Span::styled(
+                    format!(" {} ", report.title),
+                    theme.error.add_modifier(Modifier::BOLD),
+                ))
+                .title_bottom(Line::from(hints).alignment(Alignment::Right)),
+        )
+        .style(theme.text)
+        .wrap(Wrap { trim: false })
+        .scroll((report.scroll, 0));
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+/// A line of pretty-printed JSON with keys, strings, numbers and literals colored
+fn json_line(line: &str, theme: &Theme) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut rest = line;
+    while let Some(first) = rest.chars().next() {
+        let (len, style) = match first {
+            '"' => {
+                // Up to the closing quote, skipping escaped ones
+                let mut escaped = false;
+                let end = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(_, c)| {
+                        let closes = c == '"' && !escaped;
+                        escaped = c == '\\' && !escaped;
+                        closes
+                    })
+                    .map_or(rest.len(), |(i, _)| i + 1);
+                let is_key = rest[end..].trim_start().starts_with(':');
+                (end, if is_key { theme.accent } else { theme.secondary })
+            }
+            '-' | '0'..='9' => {
+                let numeric =
+                    |c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E');
+                let end = rest.find(|c: char| !numeric(c)).unwrap_or(rest.len());
+                (end, theme.warning)
+            }
+            't' | 'f' | 'n' => {
+                let end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+                (end, theme.tertiary)
+            }
+            _ => {
+                // Indentation and punctuation, up to the next value
+                let end = rest
+                    .find(|c: char| matches!(c, '"' | '-' | '0'..='9' | 't' | 'f' | 'n'))
+                    .unwrap_or(rest.len());
+                (end, theme.muted)
+            }
+        };
+        spans.push(Span::styled(rest[..len].to_string(), style));
+        rest = &rest[len..];
+    }
+    Line::from(spans)
+}
+
+fn render_options_popup(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(popup) = &app.options_popup else {
+        return;
+    };
+    let on_off = |on: bool| if on { "on" } else { "off" };
+    let mut lines = Vec::new();
+    for (i, field) in popup.fields().iter().enumerate() {
+        let (label, value) = match (field, popup.load) {
+            (OptionField::PageSize, Some(load)) => ("Page size", load.page_size.to_string()),
+            (OptionField::Verbose, Some(load)) => ("Verbose loads", on_off(load.verbose).into()),
+            (OptionField::ReflectMaxTokens, _) => {
+                ("Reflect tokens", popup.reflect_max_tokens.to_string())
+            }
+            _ => ("Recall chunks", on_off(popup.recall_chunks).to_string()),
+        };
+        let focused = i == popup.field;
+        let style = if focused { theme.highlight } else { theme.text };
+        lines.push(Line::from(vec![
+            Span::styled(if focused { "> " } else { "  " }, theme.accent),
+            Span::styled(format!("{:<15} ◀ {} ▶", label, value), style),
+        ]));
+    }
+    if popup.load.is_none() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "  Page size and verbose loads are set in the Memories, Entities and Documents views",
+            theme.muted,
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("j/k", theme.accent.add_modifier(Modifier::BOLD)),
+        Span::raw(" move   "),
+        Span::styled("←/→", theme.accent.add_modifier(Modifier::BOLD)),
+        Span::raw(" change   "),
+        Span::styled("Enter", theme.accent.add_modifier(Modifier::BOLD)),
+        Span::raw(" apply   "),
+        Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+        Span::raw(" cancel"),
+    ]));
+
+    let title = match popup.load {
+        Some(_) => format!(" Options: {} ", app.view.title()),
+        None => " Options ".to_string(),
+    };
+    let popup_area = centered_rect(60, 40, area);
+    let modal = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.accent)
+                .title(title),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, popup_area);
+    f.render_widget(modal, popup_area);
+}
+
+fn render_close_tab_confirmation(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let lines = vec![
+        Line::from(Span::styled(
+            "Close this tab?",
+            theme.warning.add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "  A query is still running in {}; its answer will be lost.",
+            app.tab_title()
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y/Enter", theme.warning.add_modifier(Modifier::BOLD)),
+            Span::raw(" close   "),
+            Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+            Span::raw(" keep it"),
+        ]),
+    ];
+
+    let popup = centered_rect(60, 30, area);
+    let modal = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.warning)
+                .title(" Confirm "),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+}
+
+fn render_quit_confirmation(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let writes = app.pending_quit.as_deref().unwrap_or_default();
+    let title = match writes.len() {
+        1 => "1 operation in progress — quit anyway?".to_string(),
+        n => format!("{} operations in progress — quit anyway?", n),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(title, theme.warning.add_modifier(Modifier::BOLD))),
+        Line::from(""),
+    ];
+    lines.extend(writes.iter().map(|label| Line::from(format!("  • {}", label))));
+    lines.extend([
+        Line::from(""),
+        Line::from("  Quitting now leaves it unknown whether they completed."),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y/Enter", theme.warning.add_modifier(Modifier::BOLD)),
+            Span::raw(" quit   "),
+            Span::styled("w", theme.accent.add_modifier(Modifier::BOLD)),
+            Span::raw(" wait, then quit   "),
+            Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+            Span::raw(" stay"),
+        ]),
+    ]);
+
+    let popup = centered_rect(60, 30, area);
+    let modal = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.warning)
+                .title(" Confirm "),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+}
+
+fn render_memory_draft(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(draft) = &app.memory_draft else {
+        return;
+    };
+    let label = |field: DraftField, text: &'static str| {
+        let style = if draft.focus == field {
+            theme.tertiary.add_modifier(Modifier::BOLD)
+        } else {
+            theme.muted
+        };
+        Span::styled(text, style)
+    };
+
+    let popup = centered_rect(70, 60, area);
+    let inner_width = popup.width.saturating_sub(2).max(1) as usize;
+    // Rows left for the text once the label, type, date and hints are drawn
+    let text_height = popup.height.saturating_sub(2 + 6).max(1) as usize;
+    let (rows, (cursor_row, cursor_column)) =
+        hard_wrap(draft.text.as_str(), draft.text.cursor, inner_width);
+    let first_row = (cursor_row as usize + 1).saturating_sub(text_height);
+
+    let mut lines = vec![Line::from(label(DraftField::Text, "Text:"))];
+    lines.extend(
+        rows.iter()
+            .skip(first_row)
+            .take(text_height)
+            .map(|row| Line::from(Span::styled(row.clone(), theme.text))),
+    );
+    lines.resize(1 + text_height, Line::from(""));
+
+    let mut types = vec![label(DraftField::Type, "Type: ")];
+    for (i, name) in std::iter::once("auto").chain(MEMORY_TYPE_HINTS.iter().copied()).enumerate() {
+        if draft.fact_type.map_or(0, |t| t + 1) == i {
+            let style = theme.accent.add_modifier(Modifier::BOLD);
+            types.push(Span::styled(format!("[{}]", name), style));
+        } else {
+            types.push(Span::styled(format!(" {} ", name), theme.muted));
+        }
+    }
+    lines.push(Line::from(types));
+    lines.push(Line::from(vec![
+        label(DraftField::Occurred, "Occurred: "),
+        Span::raw(draft.occurred.as_str().to_string()),
+        Span::styled("  (YYYY-MM-DD, RFC 3339 or 7d)", theme.muted),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("Enter", theme.secondary.add_modifier(Modifier::BOLD)),
+        Span::raw(" save   "),
+        Span::styled("Ctrl+J", theme.accent),
+        Span::raw(" new line   "),
+        Span::styled("Ctrl+E", theme.accent),
+        Span::raw(" editor   "),
+        Span::styled("Tab", theme.accent),
+        Span::raw(" next field   "),
+        Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+        Span::raw(" cancel"),
+    ]));
+
+    let modal = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.secondary)
+            .title(" New Memory "),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+
+    let (x, y) = match draft.focus {
+        DraftField::Text => (cursor_column, 1 + cursor_row - first_row as u16),
+        DraftField::Type => return,
+        DraftField::Occurred => (
+            "Occurred: ".width() as u16 + draft.occurred.cursor_column(),
+            1 + text_height as u16 + 1,
+        ),
+    };
+    f.set_cursor_position((popup.x + 1 + x, popup.y + 1 + y));
+}
+
+/// `text` cut into rows of at most `width` columns at any character, and the row and
+/// column of the byte offset `cursor` in them
+fn hard_wrap(text: &str, cursor: usize, width: usize) -> (Vec<String>, (u16, u16)) {
+    let mut rows = vec![String::new()];
+    let mut column = 0;
+    let mut at_cursor = None;
+    for (i, c) in text.char_indices() {
+        let c_width = c.width().unwrap_or(0);
+        if c != '\n' && column + c_width > width {
+            rows.push(String::new());
+            column = 0;
+        }
+        if i == cursor {
+            at_cursor = Some((rows.len() - 1, column));
+        }
+        if c == '\n' {
+            rows.push(String::new());
+            column = 0;
+        } else {
+            rows.last_mut().unwrap().push(c);
+            column += c_width;
+        }
+    }
+    let (row, column) = at_cursor.unwrap_or((rows.len() - 1, column));
+    (rows, (row as u16, column as u16))
+}
+
+fn render_export_prompt(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(prompt) = &app.export_prompt else {
+        return;
+    };
+    let format = |format: ExportFormat, label: &'static str| {
+        if prompt.format == format {
+            Span::styled(format!("[{}]", label), theme.tertiary.add_modifier(Modifier::BOLD))
+        } else {
+            Span::styled(format!(" {} ", label), theme.muted)
+        }
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("File: ", theme.tertiary.add_modifier(Modifier::BOLD)),
+            Span::raw(prompt.path.as_str().to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("Format: ", theme.muted),
+            format(ExportFormat::Json, "JSON"),
+            Span::raw(" "),
+            format(ExportFormat::Md, "Markdown"),
+        ]),
+        Line::from(""),
+    ];
+    if prompt.overwrite {
+        lines.push(Line::from(Span::styled(
+            "The file exists. Enter replaces it.",
+            theme.warning,
+        )));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(vec![
+        Span::styled("Enter", theme.secondary.add_modifier(Modifier::BOLD)),
+        Span::raw(" export   "),
+        Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+        Span::raw(" cancel   "),
+        Span::styled("Tab", theme.accent),
+        Span::raw(" format"),
+    ]));
+
+    let popup = centered_rect(60, 40, area);
+    let modal = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.secondary)
+            .title(" Export "),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+    f.set_cursor_position((
+        popup.x + 1 + "File: ".width() as u16 + prompt.path.cursor_column(),
+        popup.y + 1,
+    ));
+}
+
+fn render_delete_confirmation(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(pending) = &app.pending_delete else {
+        return;
+    };
+    let noun = if pending.items.len() == 1 { "document" } else { "documents" };
+    let title = if pending.memories > 0 {
+        format!(
+            "Delete {} {} holding the {} picked memories?",
+            pending.items.len(),
+            noun,
+            pending.memories
+        )
+    } else {
+        format!("Delete {} {}?", pending.items.len(), noun)
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            title,
+            theme.error.add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    const SHOWN: usize = 8;
+    for (id, preview) in pending.items.iter().take(SHOWN) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", id), theme.accent),
+            Span::styled(preview.clone(), theme.muted),
+        ]));
+    }
+    if pending.items.len() > SHOWN {
+        lines.push(Line::from(format!("  … and {} more", pending.items.len() - SHOWN)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Memories extracted from these documents are deleted with them.",
+        theme.warning,
+    )));
+    if pending.memories > 0 {
+        lines.push(Line::from(Span::styled(
+            "The API deletes memories only with their document, so that is every memory \
+             counted above, not just the picked ones.",
+            theme.warning,
+        )));
+    }
+    if pending.without_document > 0 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{} picked memories have no source document and are kept.",
+                pending.without_document
+            ),
+            theme.muted,
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("y/Enter", theme.error.add_modifier(Modifier::BOLD)),
+        Span::raw(" delete   "),
+        Span::styled("Esc", theme.tertiary.add_modifier(Modifier::BOLD)),
+        Span::raw(" cancel"),
+    ]));
+
+    let popup = centered_rect(60, 50, area);
+    let modal = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.error)
+                .title(" Confirm Delete "),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, popup);
+    f.render_widget(modal, popup);
+}
+
+fn render_control_bar(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let keys = &app.keys;
+    let k = |action| keys.label(action);
+    // Two actions under one label, "←→" for the arrows and "j/k" otherwise
+    let pair = |a, b| {
+        let (a, b) = (keys.label(a), keys.label(b));
+        let arrows = [&a, &b].iter().all(|label| label.starts_with(['↑', '↓', '←', '→']));
+        match (a.is_empty(), b.is_empty()) {
+            (false, false) if arrows => format!("{}{}", a, b),
+            (false, false) => format!("{}/{}", a, b),
+            _ => a + &b,
+        }
+    };
+    // Keys of popups and text inputs, which are not configurable
+    let fixed = |shortcuts: &[(&str, &'static str, Style)]| -> Vec<(String, &'static str, Style)> {
+        shortcuts.iter().map(|(key, desc, style)| (key.to_string(), *desc, *style)).collect()
+    };
+    // Build contextual shortcuts based on view and input mode
+    let mut shortcuts = match (&app.view, &app.input_mode) {
+        (_, InputMode::Normal) if app.viewing_memory.is_some() => fixed(&[
+            ("j/k", "Scroll", theme.accent),
+            ("PgUp/Dn", "Page", theme.accent),
+            ("n/→", "Next", theme.secondary),
+            ("p/←", "Prev", theme.secondary),
+            ("d", "Document", theme.secondary),
+            ("e", "Entities", theme.secondary),
+            ("Esc", "Close", theme.tertiary),
+            ("q", "Quit", theme.error),
+        ]),
+        (View::Entities(_), InputMode::Normal) if app.viewing_entity.is_some() => vec![
+            (pair(Action::Down, Action::Up), "Navigate", theme.accent),
+            (pair(Action::Left, Action::Right), "Scroll", theme.accent),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Documents(_), InputMode::Normal) if app.viewing_document.is_some() => fixed(&[
+            ("j/k", "Move", theme.accent),
+            ("g/G", "Top/End", theme.accent),
+            ("t", "Text/Chunks", theme.secondary),
+            ("m", "Memories", theme.secondary),
+            ("Esc", "Back", theme.tertiary),
+            ("q", "Quit", theme.error),
+        ]),
+        (View::Banks, InputMode::Normal) => vec![
+            (k(Action::Open), "Select", theme.accent),
+            (k(Action::Operations), "Operations", theme.accent),
+            (k(Action::Profile), "Profile", theme.accent),
+            (k(Action::Stats), "Stats", theme.accent),
+            (k(Action::Filter), "Filter", theme.secondary),
+            (k(Action::NewBank), "New", theme.secondary),
+            (k(Action::RenameBank), "Rename", theme.secondary),
+            (k(Action::DeleteBank), "Delete", theme.error),
+            (k(Action::Refresh), "Refresh", theme.secondary),
+            (k(Action::Help), "Help", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Memories(_), InputMode::Normal) => vec![
+            (k(Action::Open), "View", theme.accent),
+            (k(Action::Entities), "Entities", theme.accent),
+            (k(Action::Documents), "Documents", theme.accent),
+            (k(Action::AddMemory), "Add", theme.secondary),
+            (k(Action::Follow), "Follow", theme.secondary),
+            (k(Action::Sort), "Sort", theme.secondary),
+            (k(Action::Query), "Query", theme.secondary),
+            (k(Action::Filter), "Filter", theme.secondary),
+            (pair(Action::Left, Action::Right), "Scroll", theme.accent),
+            (k(Action::Mark), "Mark", theme.secondary),
+            (keys.labels(Action::Delete), "Delete", theme.error),
+            (k(Action::NextPage), "Next Page", theme.secondary),
+            (k(Action::PrevPage), "Prev Page", theme.secondary),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Help), "Help", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Entities(_), InputMode::Normal) => vec![
+            (k(Action::Open), "View", theme.accent),
+            (k(Action::Memories), "Memories", theme.accent),
+            (k(Action::Documents), "Documents", theme.accent),
+            (k(Action::Query), "Query", theme.secondary),
+            (k(Action::Filter), "Filter", theme.secondary),
+            (pair(Action::Left, Action::Right), "Scroll", theme.accent),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Help), "Help", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Operations(_), InputMode::Normal) => vec![
+            (k(Action::Open), "Error", theme.accent),
+            (k(Action::RetryOperation), "Retry", theme.warning),
+            (k(Action::CancelOperation), "Cancel", theme.error),
+            (k(Action::Filter), "Filter", theme.secondary),
+            (k(Action::NextPage), "Next Page", theme.secondary),
+            (k(Action::PrevPage), "Prev Page", theme.secondary),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Stats(_), InputMode::Normal) => vec![
+            (k(Action::Operations), "Operations", theme.accent),
+            (k(Action::Profile), "Profile", theme.accent),
+            (k(Action::Query), "Query", theme.secondary),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Profile(_), InputMode::Normal) => vec![
+            (pair(Action::Down, Action::Up), "Scroll", theme.accent),
+            (k(Action::EditProfile), "Edit", theme.secondary),
+            (k(Action::Operations), "Operations", theme.accent),
+            (k(Action::Query), "Query", theme.secondary),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Documents(_), InputMode::Normal) => vec![
+            (k(Action::Open), "View", theme.accent),
+            (k(Action::Memories), "Memories", theme.accent),
+            (k(Action::Entities), "Entities", theme.accent),
+            (k(Action::Sort), "Sort", theme.secondary),
+            (k(Action::Query), "Query", theme.secondary),
+            (pair(Action::Left, Action::Right), "Scroll", theme.accent),
+            (k(Action::Filter), "Filter", theme.secondary),
+            (k(Action::Mark), "Mark", theme.secondary),
+            (keys.labels(Action::Delete), "Delete", theme.error),
+            (k(Action::Back), "Back", theme.tertiary),
+            (k(Action::Refresh), "Refresh", theme.tertiary),
+            (k(Action::Help), "Help", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+        (View::Query(_), InputMode::Normal) => {
+            let mut shortcuts = vec![
+                (k(Action::Query), "Query", theme.secondary),
+                (k(Action::Mode), "Mode", theme.accent),
+            ];
+            if app.query_mode == QueryMode::Recall {
+                shortcuts.push((pair(Action::Left, Action::Right), "Scroll", theme.accent));
+                shortcuts.push((k(Action::Chunk), "Chunk", theme.accent));
+                shortcuts.push((k(Action::Types), "Types", theme.tertiary));
+                let tokens = pair(Action::MoreTokens, Action::FewerTokens);
+                shortcuts.push((tokens, "Tokens", theme.tertiary));
+                if app.trace_enabled {
+                    shortcuts.push((k(Action::Trace), "Trace: on", theme.secondary));
+                    shortcuts.push((k(Action::CollapseTrace), "Collapse", theme.secondary));
+                    shortcuts.push((k(Action::RawTrace), "Raw", theme.secondary));
+                } else {
+                    shortcuts.push((k(Action::Trace), "Trace: off", theme.secondary));
+                }
+            } else {
+                shortcuts.push((pair(Action::Down, Action::Up), "Scroll", theme.accent));
+                shortcuts.push((k(Action::Sources), "Sources", theme.accent));
+            }
+            shortcuts.extend([
+                (k(Action::Budget), "Budget", theme.tertiary),
+                (k(Action::Back), "Back", theme.tertiary),
+                (k(Action::Help), "Help", theme.tertiary),
+                (k(Action::Quit), "Quit", theme.error),
+            ]);
+            shortcuts
+        },
+        (_, InputMode::Filter) => fixed(&[
+            ("Enter", "Keep", theme.secondary),
+            ("Esc", "Clear", theme.error),
+        ]),
+        (View::Query(_), InputMode::Query) => fixed(&[
+            ("Enter", "Execute", theme.secondary),
+            ("↑↓", "History", theme.accent),
+            ("Ctrl+R", "Search History", theme.accent),
+            ("Ctrl+E", "Editor", theme.accent),
+            ("Esc", "Cancel", theme.error),
+        ]),
+        (_, InputMode::History) => fixed(&[
+            ("Enter", "Use", theme.secondary),
+            ("↑↓", "Move", theme.accent),
+            ("Esc", "Close", theme.error),
+        ]),
+        (_, InputMode::Bank) => fixed(&[
+            ("Enter", "Confirm", theme.secondary),
+            ("Tab", "Next Field", theme.accent),
+            ("Esc", "Cancel", theme.error),
+        ]),
+        (_, InputMode::Memory) => fixed(&[
+            ("Enter", "Save", theme.secondary),
+            ("Ctrl+J", "New Line", theme.accent),
+            ("Ctrl+E", "Editor", theme.accent),
+            ("Tab", "Next Field", theme.accent),
+            ("Esc", "Cancel", theme.error),
+        ]),
+        (_, InputMode::Export) => fixed(&[
+            ("Enter", "Export", theme.secondary),
+            ("Tab", "Format", theme.accent),
+            ("Esc", "Cancel", theme.error),
+        ]),
+        _ => vec![
+            (k(Action::Help), "Help", theme.tertiary),
+            (k(Action::Quit), "Quit", theme.error),
+        ],
+    };
+    // Unbound actions have no shortcut to show
+    shortcuts.retain(|(key, _, _)| !key.is_empty());
+
+    // Split into left (context) and right (shortcuts) sections
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),  // Context on left
+            Constraint::Percentage(70),  // Shortcuts on right
+        ])
+        .split(area);
 
     // Left: Context info
     let context_info = match &app.view {
@@ -854,715 +5466,4431 @@ fn render_control_bar(f: &mut Frame, app: &App, area: Rect) {
         View::Memories(bank_id) => format!("Context: Memories\nBank: {}", bank_id),
         View::Entities(bank_id) => format!("Context: Entities\nBank: {}", bank_id),
         View::Documents(bank_id) => format!("Context: Documents\nBank: {}", bank_id),
+        View::Operations(bank_id) => format!("Context: Operations\nBank: {}", bank_id),
+        View::Profile(bank_id) => format!("Context: Profile\nBank: {}", bank_id),
+        View::Stats(bank_id) => format!("Context: Stats\nBank: {}", bank_id),
         View::Query(_bank_id) => {
             let mode = match app.query_mode {
                 QueryMode::Recall => "Recall",
                 QueryMode::Reflect => "Reflect",
             };
-            format!("Mode: {}\nBudget: {:?} | Tokens: {}", mode, app.query_budget, app.query_max_tokens)
+            format!("Mode: {}\n{}", mode, app.query_settings().describe(&app.query_mode))
+        }
+    };
+
+    let context_widget = Paragraph::new(context_info)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.accent)
+            .title(" Context "))
+        .style(theme.tertiary.add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Left);
+    f.render_widget(context_widget, columns[0]);
+
+    // Right: Shortcuts in columns if many
+    // Calculate shortcuts per column (max 3 lines of shortcuts)
+    let max_shortcuts_per_col = 3;
+    let num_cols = (shortcuts.len() + max_shortcuts_per_col - 1) / max_shortcuts_per_col;
+
+    let mut shortcut_lines = vec![];
+    let inner = columns[1].inner(Margin::new(1, 1));
+    for row in 0..max_shortcuts_per_col {
+        let mut line_spans = vec![];
+        let mut x = inner.x;
+
+        for col in 0..num_cols {
+            let idx = col * max_shortcuts_per_col + row;
+            if idx < shortcuts.len() {
+                let (key, desc, style) = &shortcuts[idx];
+
+                // Each shortcut with proper alignment
+                let shortcut_text = format!("<{}> {:<10}", key, desc);
+                let span = Span::styled(shortcut_text, style.add_modifier(Modifier::BOLD));
+
+                // Remember where it lands so clicking it acts like pressing the key
+                let width = span.width() as u16;
+                if let Some(key) = shortcut_key(key) {
+                    let target = Rect::new(x, inner.y + row as u16, width, 1).intersection(inner);
+                    app.mouse.shortcuts.push((target, key));
+                }
+                x = x.saturating_add(width);
+                line_spans.push(span);
+            }
+        }
+
+        if !line_spans.is_empty() {
+            shortcut_lines.push(Line::from(line_spans));
+        }
+    }
+
+    let shortcuts_widget = Paragraph::new(shortcut_lines)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.accent)
+            .title(" Shortcuts "))
+        .alignment(Alignment::Left);
+
+    f.render_widget(shortcuts_widget, columns[1]);
+}
+
+fn render_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let bank_info = if let Some(bank_id) = app.view.bank_id() {
+        format!(" [{}]", bank_id)
+    } else {
+        String::new()
+    };
+
+    let title = format!("Hindsight Explorer - {}{}", app.view.title(), bank_info);
+
+    // The server, its version and the last call's latency; an error while unreachable
+    let connection_style = if app.connection.disconnected {
+        theme.error
+    } else {
+        theme.muted
+    };
+    let connection = Line::from(Span::styled(
+        format!(" {} ", app.connection.describe()),
+        connection_style.add_modifier(Modifier::BOLD),
+    ))
+    .right_aligned();
+    let mut block = Block::default().borders(Borders::ALL).title_bottom(connection);
+    if let Some(countdown) = app.refresh_countdown() {
+        block = block.title_bottom(Span::styled(format!(" {} ", countdown), theme.muted));
+    }
+    if app.connection.disconnected {
+        block = block.border_style(theme.error);
+    }
+
+    // With several tabs open, the tabs take the place of the title
+    let header = if app.tab_titles.is_empty() {
+        Paragraph::new(title)
+    } else {
+        block = block.title(" Hindsight Explorer ");
+        let mut spans = Vec::new();
+        for (i, tab) in app.tab_titles.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled("│", theme.muted));
+            }
+            let style = if i == app.tab_index { theme.highlight } else { theme.muted };
+            spans.push(Span::styled(format!(" {} {} ", i + 1, tab), style));
+        }
+        Paragraph::new(Line::from(spans))
+    };
+    let header = header
+        .style(theme.accent.add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(block);
+
+    f.render_widget(header, area);
+}
+
+fn render_footer(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    const FILTER_PROMPT: &str = " Filter: ";
+    // Simple status line only (shortcuts are now at the top, no border)
+    let status_line = if app.input_mode == InputMode::Filter {
+        f.set_cursor_position((
+            area.x + FILTER_PROMPT.len() as u16 + app.filter_text.cursor_column(),
+            area.y,
+        ));
+        Line::from(vec![
+            Span::styled(
+                FILTER_PROMPT,
+                theme.tertiary.add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(app.filter_text.as_str()),
+            Span::styled(
+                "  (Enter to keep, Esc to clear)",
+                theme.muted,
+            ),
+        ])
+    } else if !app.error_message.is_empty() {
+        Line::from(vec![
+            Span::styled(" Error: ", theme.error.add_modifier(Modifier::BOLD)),
+            Span::raw(&app.error_message),
+        ])
+    } else if let Some(label) = app.foreground_request() {
+        Line::from(vec![
+            Span::styled(
+                format!(" {} {}...", spinner_frame(), label),
+                theme.tertiary.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  (Esc to cancel)", theme.muted),
+        ])
+    } else if !app.status_message.is_empty() {
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled(&app.status_message, theme.success),
+        ])
+    } else {
+        Line::from("")
+    };
+
+    let footer = Paragraph::new(status_line).alignment(Alignment::Left);
+    f.render_widget(footer, area);
+}
+
+fn render_banks(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let items: Vec<ListItem> = app
+        .banks
+        .iter()
+        .map(|bank| {
+            let name = bank.name.as_deref().filter(|s| !s.is_empty()).unwrap_or("Unnamed");
+            let content = format!("{} - {}", bank.bank_id, name);
+            ListItem::new(content).style(theme.text)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(if app.filter_text.is_empty() {
+            "Banks".to_string()
+        } else {
+            format!("Banks (filtered: {}/{})", app.banks.len(), app.banks_loaded.len())
+        }))
+        .highlight_style(theme.highlight)
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, area, &mut app.banks_state);
+    app.mouse.list = Some(ListArea::new(area, app.banks_state.offset(), 0));
+}
+
+fn render_memories(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    // Show the memories as a table; the detail popup is drawn over it
+    let rows = app.memories.iter().map(|memory| {
+        let mem_type = memory.fact_type.as_deref().unwrap_or("unknown");
+        let created = memory_created(memory).map(format_date).unwrap_or_else(|| "-".to_string());
+
+        // Apply horizontal scroll
+        let scrolled_text: String = memory.text.chars().skip(app.horizontal_scroll).collect();
+
+        let id = &memory.id;
+        // Memories that just arrived in follow mode stand out until the next refresh
+        let arrived = app
+            .follow
+            .as_ref()
+            .is_some_and(|follow| follow.arrived.contains(id));
+        let style = if arrived {
+            theme.search_match
+        } else {
+            theme.text
+        };
+        let mark = if app.marked_memories.contains(id) {
+            "*"
+        } else {
+            " "
+        };
+        Row::new([format!("{}{}", mark, mem_type), created, scrolled_text]).style(style)
+    });
+
+    let mut block = Block::default().borders(Borders::ALL).title(format!(
+        "Memories{} ({}) - Press Enter to view full text",
+        app.related.as_ref().map(Related::describe).unwrap_or_default(),
+        app.list_position(app.memories.len(), app.memories_loaded.len(), &app.memories_page)
+    ));
+    if let Some(follow) = &app.follow {
+        let (badge, style) = if follow.paused {
+            (" FOLLOW PAUSED ", theme.warning)
+        } else {
+            (" FOLLOW ", theme.success)
+        };
+        let badge = Span::styled(badge, style.add_modifier(Modifier::REVERSED | Modifier::BOLD));
+        block = block.title(Line::from(badge).right_aligned());
+    }
+    let table = Table::new(rows, memory_column_widths(area.width))
+        .header(table_header(&MEMORY_COLUMNS, app.memories_sort, &theme))
+        .block(block)
+        .row_highlight_style(theme.highlight)
+        .highlight_symbol(">> ");
+
+    app.mouse.list = Some(render_table(f, table, area, &mut app.memories_state));
+
+    if app.viewing_memory.is_some() {
+        render_memory_detail(f, app, area);
+    }
+}
+
+/// Full-detail popup for the selected memory, drawn over the memory list
+fn render_memory_detail(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let Some(memory) = &app.viewing_memory else {
+        return;
+    };
+    let popup = centered_rect(80, 80, area);
+    let lines = memory_detail_lines(memory, app.view.bank_id().unwrap_or(""), &theme);
+
+    // Keep the scroll offset within the wrapped content
+    let inner_width = popup.width.saturating_sub(2).max(1) as usize;
+    let inner_height = popup.height.saturating_sub(2);
+    let content_rows: usize = lines
+        .iter()
+        .map(|line| {
+            let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            wrapped_rows(&text, inner_width)
+        })
+        .sum();
+    let max_scroll = content_rows
+        .saturating_sub(inner_height as usize)
+        .min(u16::MAX as usize) as u16;
+    app.memory_detail_scroll = app.memory_detail_scroll.min(max_scroll);
+    app.memory_detail_height = inner_height;
+
+    let position = app.memories_state.selected().map(|i| i + 1).unwrap_or(0);
+    let scroll_hint = if max_scroll > 0 {
+        format!(" {}/{} ", app.memory_detail_scroll, max_scroll)
+    } else {
+        String::new()
+    };
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.accent)
+                .title(format!(" Memory {}/{} (Esc to close) ", position, app.memories.len()))
+                .title_bottom(Line::from(scroll_hint).alignment(Alignment::Right)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.memory_detail_scroll, 0));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(detail, popup);
+}
+
+/// Header fields, full text and remaining metadata of a listed memory
+fn memory_detail_lines(
+    memory: &MemoryListItem,
+    bank_id: &str,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let label_style = theme.accent.add_modifier(Modifier::BOLD);
+    let present = |field: &Option<String>| field.clone().filter(|s| !s.is_empty());
+    let date = |field: &Option<String>| present(field).as_deref().map(format_date);
+
+    let mut header: Vec<(&str, String)> = vec![
+        ("Type", present(&memory.fact_type).unwrap_or_else(|| "unknown".to_string())),
+        ("Mentioned", date(&memory.mentioned_at).unwrap_or_else(|| "unknown".to_string())),
+    ];
+    match (date(&memory.occurred_start), date(&memory.occurred_end)) {
+        (Some(start), Some(end)) if start != end => {
+            header.push(("Occurred", format!("{} → {}", start, end)))
+        }
+        (Some(start), _) => header.push(("Occurred", start)),
+        (None, Some(end)) => header.push(("Occurred", format!("until {}", end))),
+        (None, None) => {}
+    }
+    if let Some(created) = date(&memory.created_at) {
+        header.push(("Created", created));
+    }
+    if let Some(context) = present(&memory.context) {
+        header.push(("Context", context));
+    }
+    if let Some(document_id) = memory_document_id(bank_id, memory) {
+        header.push(("Document", document_id));
+    }
+    let tags: Vec<&str> = memory
+        .extra
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .map(|tags| tags.iter().filter_map(|t| t.as_str()).collect())
+        .unwrap_or_default();
+    if !tags.is_empty() {
+        header.push(("Tags", tags.join(", ")));
+    }
+    if !memory.id.is_empty() {
+        header.push(("ID", memory.id.clone()));
+    }
+
+    let mut lines: Vec<Line<'static>> = header
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{:<10} ", format!("{}:", label)), label_style),
+                Span::raw(value),
+            ])
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    let text = Some(memory.text.as_str()).filter(|text| !text.is_empty());
+    lines.extend(
+        text.unwrap_or("No text available")
+            .lines()
+            .map(|line| Line::styled(line.to_string(), theme.text)),
+    );
+
+    // Explicit metadata first, then the event date and any fields the header doesn't cover
+    let mut extra: Vec<(String, Value)> = memory
+        .metadata
+        .iter()
+        .flatten()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    extra.extend(memory.date.clone().map(|date| ("date".to_string(), Value::from(date))));
+    extra.extend(
+        memory
+            .extra
+            .iter()
+            .filter(|(key, value)| {
+                !MEMORY_DETAIL_FIELDS.contains(&key.as_str()) && !value.is_null()
+            })
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+    if !extra.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::styled("Metadata", label_style));
+        for (key, value) in extra {
+            let value = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}: ", key), theme.secondary),
+                Span::raw(value),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Rows `text` takes when word-wrapped at `width` columns
+fn wrapped_rows(text: &str, width: usize) -> usize {
+    let width = width.max(1);
+    let mut rows = 1;
+    let mut column = 0;
+    for word in text.split(' ') {
+        let word_width = UnicodeWidthStr::width(word);
+        let needed = if column == 0 { word_width } else { column + 1 + word_width };
+        if needed <= width {
+            column = needed;
+        } else if word_width <= width {
+            rows += 1;
+            column = word_width;
+        } else {
+            // Words longer than a row are broken across rows
+            if column > 0 {
+                rows += 1;
+            }
+            rows += (word_width - 1) / width;
+            column = (word_width - 1) % width + 1;
+        }
+    }
+    rows
+}
+
+/// Editable text with a cursor, shared by the query, filter and history picker
+/// inputs and the popups. Only the new-memory popup puts newlines in it.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TextInput {
+    text: String,
+    /// Byte offset of the cursor, always on a char boundary
+    cursor: usize,
+}
+
+impl TextInput {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Replace the text and put the cursor at the end
+    fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    fn clear(&mut self) {
+        self.set(String::new());
+    }
+
+    /// Apply an editing or cursor key; false when the key is not one
+    fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.text.len(),
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Char('u') if ctrl => self.clear(),
+            KeyCode::Char(_) if ctrl || key.modifiers.contains(KeyModifiers::ALT) => return false,
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.cursor = self.prev_boundary(),
+            KeyCode::Right => self.cursor = self.next_boundary(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    fn backspace(&mut self) {
+        let start = self.prev_boundary();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    fn delete(&mut self) {
+        let end = self.next_boundary();
+        self.text.replace_range(self.cursor..end, "");
+    }
+
+    /// Delete the word before the cursor, along with any spaces after it
+    fn delete_word(&mut self) {
+        let before = self.text[..self.cursor].trim_end();
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .chars()
+            .next_back()
+            .map_or(0, |c| self.cursor - c.len_utf8())
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    /// Terminal columns before the cursor
+    fn cursor_column(&self) -> u16 {
+        self.text[..self.cursor].width() as u16
+    }
+}
+
+/// Case-insensitive list filter: every whitespace-separated term must match a
+/// field, either as a substring or fuzzily (its letters in order within one word)
+struct ListFilter {
+    terms: Vec<String>,
+}
+
+impl ListFilter {
+    fn new(text: &str) -> Self {
+        Self {
+            terms: text.split_whitespace().map(str::to_lowercase).collect(),
+        }
+    }
+
+    fn matches(&self, fields: &[String]) -> bool {
+        let fields: Vec<String> = fields.iter().map(|f| f.to_lowercase()).collect();
+        self.terms.iter().all(|term| {
+            fields.iter().any(|field| {
+                field.contains(term.as_str())
+                    || field.split_whitespace().any(|word| is_subsequence(term, word))
+            })
+        })
+    }
+
+    fn apply<T: Clone>(&self, rows: &[T], fields: impl Fn(&T) -> Vec<String>) -> Vec<T> {
+        if self.terms.is_empty() {
+            return rows.to_vec();
+        }
+        rows.iter().filter(|row| self.matches(&fields(row))).cloned().collect()
+    }
+}
+
+/// Whether the characters of `needle` appear in order in `haystack`
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut remaining = haystack.chars();
+    needle.chars().all(|c| remaining.any(|h| h == c))
+}
+
+/// Submitted recall and reflect queries, oldest first, persisted between sessions
+#[derive(Debug, Clone, Default)]
+struct QueryHistory {
+    entries: Vec<HistoryEntry>,
+    /// Entry shown while cycling with Up/Down; `None` while editing a new query
+    cursor: Option<usize>,
+    /// What was typed before cycling started, restored past the newest entry
+    draft: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HistoryEntry {
+    mode: QueryMode,
+    query: String,
+}
+
+impl QueryHistory {
+    /// `~/.local/share/hindsight/explorer_history.json`, next to the recall REPL history
+    fn path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".local").join("share").join("hindsight").join(QUERY_HISTORY_FILE_NAME)
+        })
+    }
+
+    /// Read the history file; a missing file is an empty history
+    fn load(path: &Path) -> Result<Self> {
+        let mut history = Self::default();
+        if !path.exists() {
+            return Ok(history);
+        }
+        let raw = fs::read_to_string(path)?;
+        let entries: Vec<HistoryEntry> = serde_json::from_str(&raw)?;
+        for entry in entries {
+            history.push(entry.mode, &entry.query);
+        }
+        Ok(history)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    /// Record a submitted query as the newest entry, dropping an earlier copy
+    fn push(&mut self, mode: QueryMode, query: &str) {
+        self.cursor = None;
+        self.draft.clear();
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.entries.retain(|e| !(e.mode == mode && e.query == query));
+        self.entries.push(HistoryEntry {
+            mode,
+            query: query.to_string(),
+        });
+        let excess = self.entries.len().saturating_sub(QUERY_HISTORY_LIMIT);
+        self.entries.drain(..excess);
+    }
+
+    /// The next older query in `mode`, remembering `current` when cycling starts
+    fn older(&mut self, mode: &QueryMode, current: &str) -> Option<String> {
+        let end = match self.cursor {
+            Some(i) => i,
+            None => self.entries.len(),
+        };
+        let i = self.entries[..end].iter().rposition(|e| &e.mode == mode)?;
+        if self.cursor.is_none() {
+            self.draft = current.to_string();
+        }
+        self.cursor = Some(i);
+        Some(self.entries[i].query.clone())
+    }
+
+    /// The next newer query in `mode`, or the draft once past the newest entry
+    fn newer(&mut self, mode: &QueryMode) -> Option<String> {
+        let current = self.cursor?;
+        match self.entries[current + 1..].iter().position(|e| &e.mode == mode) {
+            Some(offset) => {
+                self.cursor = Some(current + 1 + offset);
+                Some(self.entries[current + 1 + offset].query.clone())
+            }
+            None => {
+                self.cursor = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+        }
+    }
+
+    /// Stop cycling, keeping the query as edited
+    fn reset_cursor(&mut self) {
+        self.cursor = None;
+        self.draft.clear();
+    }
+
+    /// Entries matching `filter`, newest first
+    fn matching(&self, filter: &str) -> Vec<&HistoryEntry> {
+        let filter = ListFilter::new(filter);
+        self.entries
+            .iter()
+            .rev()
+            .filter(|e| filter.matches(std::slice::from_ref(&e.query)))
+            .collect()
+    }
+}
+
+/// Where the explorer was when it last exited, restored on the next start
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExplorerSession {
+    version: u32,
+    view: View,
+    /// Selected row of the Banks list
+    bank_selected: Option<usize>,
+    /// Selected row of the view's list, within the page at `offset`
+    selected: Option<usize>,
+    offset: i64,
+    filter: String,
+    query_mode: QueryMode,
+    /// Budget and max tokens per bank
+    query_settings: HashMap<String, QuerySettings>,
+}
+
+impl ExplorerSession {
+    /// `~/.local/share/hindsight/explorer_session.json`, next to the query history
+    fn path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".local").join("share").join("hindsight").join(SESSION_FILE_NAME)
+        })
+    }
+
+    /// Read the session file; `None` when it is missing, unreadable or another version
+    fn load(path: &Path) -> Option<Self> {
+        let raw = fs::read_to_string(path).ok()?;
+        let session: Self = serde_json::from_str(&raw).ok()?;
+        (session.version == SESSION_VERSION).then_some(session)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Keep the selection on a row after the list was reloaded
+fn clamp_selection(state: &mut ListState, rows: usize) {
+    match (state.selected(), rows) {
+        (_, 0) => state.select(None),
+        (None, _) => state.select(Some(0)),
+        (Some(i), _) if i >= rows => state.select(Some(rows - 1)),
+        _ => {}
+    }
+}
+
+/// Move back to the last page when the offset is past the end (e.g. after
+/// deletions); true if the page changed and should be reloaded
+fn page_past_end(page: &mut Paging) -> bool {
+    if page.offset == 0 || page.offset < page.total {
+        return false;
+    }
+    page.offset = ((page.total - 1).max(0) / page.limit) * page.limit;
+    true
+}
+
+/// Braille spinner frame for the current time, advancing every 100ms
+fn spinner_frame() -> &'static str {
+    const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    FRAMES[(millis / 100 % FRAMES.len() as u128) as usize]
+}
+
+/// The selected row of a list before a reload, remembered by ID
+#[derive(Debug, PartialEq)]
+struct KeptSelection {
+    id: Option<String>,
+    row: Option<usize>,
+    offset: usize,
+}
+
+impl KeptSelection {
+    fn new<T>(items: &[T], state: &ListState, id: impl Fn(&T) -> Option<&str>) -> Self {
+        let row = state.selected();
+        Self {
+            id: row.and_then(|row| items.get(row)).and_then(id).map(str::to_string),
+            row,
+            offset: state.offset(),
+        }
+    }
+
+    /// Land the selection in the reloaded `items`. `Landing::Keep` follows the remembered row
+    /// to wherever it moved, at the same height on screen; if it is gone the index stays.
+    fn land<T>(
+        &self,
+        state: &mut ListState,
+        items: &[T],
+        landing: Landing,
+        id: impl Fn(&T) -> Option<&str>,
+    ) {
+        if landing != Landing::Keep {
+            land_selection(state, items.len(), landing);
+            return;
+        }
+        let Some(wanted) = self.id.as_deref() else {
+            return;
+        };
+        let Some(found) = items.iter().position(|item| id(item) == Some(wanted)) else {
+            return;
+        };
+        let above = self.row.map_or(0, |row| row.saturating_sub(self.offset));
+        state.select(Some(found));
+        *state.offset_mut() = found.saturating_sub(above);
+    }
+}
+
+fn bank_row_id(bank: &BankListItem) -> Option<&str> {
+    Some(&bank.bank_id)
+}
+
+fn document_row_id(document: &DocumentListItem) -> Option<&str> {
+    Some(&document.id)
+}
+
+fn memory_row_id(memory: &MemoryListItem) -> Option<&str> {
+    Some(&memory.id)
+}
+
+fn entity_row_id(entity: &EntityListItem) -> Option<&str> {
+    Some(&entity.id)
+}
+
+fn operation_row_id(operation: &Operation) -> Option<&str> {
+    Some(&operation.id)
+}
+
+fn land_selection(state: &mut ListState, rows: usize, landing: Landing) {
+    let row = match landing {
+        Landing::Keep => return,
+        Landing::First => 0,
+        Landing::Last => rows.saturating_sub(1),
+    };
+    state.select(Some(row));
+    *state.offset_mut() = 0;
+}
+
+/// Render a table list whose first item is the column header; `state` indexes the data rows
+fn render_with_header_row(
+    f: &mut Frame,
+    list: List,
+    area: Rect,
+    state: &mut ListState,
+) -> ListArea {
+    let mut shifted = state.clone();
+    shifted.select(state.selected().map(|i| i + 1));
+    f.render_stateful_widget(list, area, &mut shifted);
+    *state.offset_mut() = shifted.offset();
+    ListArea::new(area, shifted.offset(), 1)
+}
+
+/// Render a table below its column header; `state` indexes the data rows
+fn render_table(f: &mut Frame, table: Table, area: Rect, state: &mut ListState) -> ListArea {
+    let mut table_state =
+        TableState::default().with_offset(state.offset()).with_selected(state.selected());
+    f.render_stateful_widget(table, area, &mut table_state);
+    *state.offset_mut() = table_state.offset();
+    ListArea::below_header(area, table_state.offset())
+}
+
+/// What rows of a table are ordered by when sorted by one of its columns
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Text(String),
+    Time(Option<DateTime<Utc>>),
+    Number(i64),
+}
+
+/// A sortable column of the memory and document tables
+struct Column<T> {
+    title: &'static str,
+    /// Newest or largest first, which is what one looks for in dates and sizes
+    descending: bool,
+    key: fn(&T) -> SortKey,
+}
+
+impl<T> Column<T> {
+    /// Sort rows by this column, keeping the server's order among equal rows
+    fn sort(&self, rows: &mut [T]) {
+        rows.sort_by(|a, b| {
+            let order = (self.key)(a).cmp(&(self.key)(b));
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+}
+
+const MEMORY_COLUMNS: [Column<MemoryListItem>; 3] = [
+    Column {
+        title: "TYPE",
+        descending: false,
+        key: |memory| SortKey::Text(memory.fact_type.clone().unwrap_or_default()),
+    },
+    Column {
+        title: "CREATED",
+        descending: true,
+        key: |memory| SortKey::Time(memory_created(memory).and_then(parse_timestamp)),
+    },
+    Column {
+        title: "TEXT",
+        descending: false,
+        key: |memory| SortKey::Text(memory.text.to_lowercase()),
+    },
+];
+
+const DOCUMENT_COLUMNS: [Column<DocumentListItem>; 4] = [
+    Column {
+        title: "ID",
+        descending: false,
+        key: |doc| SortKey::Text(doc.id.clone()),
+    },
+    Column {
+        title: "TYPE",
+        descending: false,
+        key: |doc| SortKey::Text(document_content_type(doc).unwrap_or_default()),
+    },
+    Column {
+        title: "CREATED",
+        descending: true,
+        key: |doc| SortKey::Time(doc.created_at.as_deref().and_then(parse_timestamp)),
+    },
+    Column {
+        title: "SIZE",
+        descending: true,
+        key: |doc| SortKey::Number(doc.text_length.unwrap_or(0)),
+    },
+];
+
+/// When a memory was stored, or the closest date the list gives
+fn memory_created(memory: &MemoryListItem) -> Option<&str> {
+    [&memory.created_at, &memory.mentioned_at, &memory.date]
+        .into_iter()
+        .find_map(|field| field.as_deref())
+}
+
+/// The column titles, with an arrow on the one the rows are sorted by
+fn table_header<T>(columns: &[Column<T>], sort: Option<usize>, theme: &Theme) -> Row<'static> {
+    let titles = columns.iter().enumerate().map(|(i, column)| {
+        let arrow = match sort {
+            Some(sorted) if sorted == i && column.descending => " ▼",
+            Some(sorted) if sorted == i => " ▲",
+            _ => "",
+        };
+        // The first column starts with the mark of marked rows
+        let mark = if i == 0 { " " } else { "" };
+        Cell::from(format!("{}{}{}", mark, column.title, arrow))
+    });
+    Row::new(titles).style(theme.accent.add_modifier(Modifier::BOLD))
+}
+
+/// Column widths of the memory table; on narrow terminals dates shrink to the day
+fn memory_column_widths(width: u16) -> [Constraint; 3] {
+    if width < 80 {
+        [Constraint::Length(9), Constraint::Length(11), Constraint::Fill(1)]
+    } else {
+        [Constraint::Length(11), Constraint::Length(20), Constraint::Fill(1)]
+    }
+}
+
+/// Column widths of the document table; the ID takes what the others leave
+fn document_column_widths(width: u16) -> [Constraint; 4] {
+    let (type_width, date_width, size_width) = if width < 100 { (12, 11, 9) } else { (24, 20, 10) };
+    [
+        Constraint::Fill(1),
+        Constraint::Length(type_width),
+        Constraint::Length(date_width),
+        Constraint::Length(size_width),
+    ]
+}
+
+/// A rectangle of `percent_x` by `percent_y` of `area`, centered in it
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+fn render_entities(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    // If an entity is opened, show it with the memories that mention it
+    if let Some(drilldown) = app.viewing_entity.as_mut() {
+        let list = render_entity_drilldown(f, drilldown, app.horizontal_scroll, &theme, area);
+        app.mouse.list = Some(list);
+    } else {
+        // Show entity list as table
+        let mut items = vec![
+            // Header row
+            ListItem::new(format!("{:<40} {:<15} {:<10}", "NAME", "TYPE", "MENTIONS"))
+                .style(theme.accent.add_modifier(Modifier::BOLD))
+        ];
+
+        // Data rows
+        for entity in &app.entities {
+            let name = &entity.canonical_name;
+            // Apply horizontal scroll to name
+            let scrolled_name: String = name.chars().skip(app.horizontal_scroll).take(40).collect();
+            let entity_type = entity.metadata.as_ref()
+                .and_then(|m| m.get("type"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            let mentions = entity.mention_count;
+
+            let content = format!("{:<40} {:<15} {:<10}", scrolled_name, entity_type, mentions);
+            items.push(ListItem::new(content).style(theme.text));
+        }
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Entities{} ({}) - Press Enter to view details",
+                app.related.as_ref().map(Related::describe).unwrap_or_default(),
+                app.list_position(app.entities.len(), app.entities_loaded.len(), &app.entities_page)
+            )))
+            .highlight_style(theme.highlight)
+            .highlight_symbol(">> ");
+
+        app.mouse.list = Some(render_with_header_row(f, list, area, &mut app.entities_state));
+    }
+}
+
+fn render_entity_drilldown(
+    f: &mut Frame,
+    drilldown: &mut EntityDrilldown,
+    horizontal_scroll: usize,
+    theme: &Theme,
+    area: Rect,
+) -> ListArea {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),  // Entity header
+            Constraint::Min(0),     // Related memories
+        ])
+        .split(area);
+
+    let entity = &drilldown.entity;
+    let date = |d: Option<&str>| d.map(format_date).unwrap_or_else(|| "unknown".to_string());
+    let aliases = if drilldown.aliases.is_empty() {
+        "none".to_string()
+    } else {
+        drilldown.aliases.join(", ")
+    };
+    let header_text = format!(
+        "Name: {}\nAliases: {}\nMentions: {}\nFirst Mention: {}\nLast Mention: {}",
+        entity.canonical_name,
+        aliases,
+        entity.mention_count,
+        date(entity.first_seen.as_deref()),
+        date(entity.last_seen.as_deref())
+    );
+    let header = Paragraph::new(header_text)
+        .block(Block::default().borders(Borders::ALL).title("Entity (Esc to go back)"))
+        .style(theme.accent)
+        .wrap(Wrap { trim: false });
+    f.render_widget(header, chunks[0]);
+
+    if drilldown.memories.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  No memories mentioning {} could be retrieved.", entity.canonical_name),
+                theme.secondary.add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  Related memories are found by recalling the entity name; mentions that recall \
+                 does not surface are not listed.",
+                theme.muted,
+            )),
+        ])
+        .block(Block::default().borders(Borders::ALL).title("Memories (0)"))
+        .wrap(Wrap { trim: false });
+        f.render_widget(empty, chunks[1]);
+        return;
+    }
+
+    let mut items = vec![
+        // Header row
+        ListItem::new(format!("{:<10} {:<18} {:<18} {}", "TYPE", "MENTIONED AT", "OCCURRED AT", "TEXT"))
+            .style(theme.accent.add_modifier(Modifier::BOLD))
+    ];
+    for memory in &drilldown.memories {
+        let mem_type = memory.type_.as_deref().unwrap_or("unknown");
+        let date = |d: Option<&str>| d.map(format_date).unwrap_or_else(|| "-".to_string());
+        let scrolled_text: String = memory.text.chars().skip(horizontal_scroll).take(80).collect();
+        let content = format!(
+            "{:<10} {:<18} {:<18} {}",
+            mem_type,
+            date(memory.mentioned_at.as_deref()),
+            date(memory.occurred_start.as_deref()),
+            scrolled_text
+        );
+        items.push(ListItem::new(content).style(theme.text));
+    }
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Memories ({})", drilldown.memories.len())),
+        )
+        .highlight_style(theme.highlight)
+        .highlight_symbol(">> ");
+
+    render_with_header_row(f, list, chunks[1], &mut drilldown.memories_state)
+}
+
+fn render_documents(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    // If a document is opened, show its metadata with its chunks or full text
+    if let Some(detail) = app.viewing_document.as_mut() {
+        render_document_detail(f, detail, &theme, area);
+    } else {
+        // Show the documents as a table
+        let rows = app.documents.iter().map(|doc| {
+            // Apply horizontal scroll to id
+            let scrolled_id: String = doc.id.chars().skip(app.horizontal_scroll).collect();
+            let content_type = document_content_type(doc).unwrap_or_else(|| "-".to_string());
+            let created = doc.created_at
+                .as_deref()
+                .map(format_date)
+                .unwrap_or_else(|| "unknown".to_string());
+            let size = doc.text_length.map(format_size);
+
+            let mark = if app.marked_documents.contains(&doc.id) { "*" } else { " " };
+            let cells = [format!("{}{}", mark, scrolled_id), content_type, created];
+            Row::new(cells.into_iter().chain(size)).style(theme.text)
+        });
+
+        let table = Table::new(rows, document_column_widths(area.width))
+            .header(table_header(&DOCUMENT_COLUMNS, app.documents_sort, &theme))
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Documents ({}) - Press Enter to view content",
+                app.list_position(
+                app.documents.len(),
+                app.documents_loaded.len(),
+                &app.documents_page
+            )
+            )))
+            .row_highlight_style(theme.highlight)
+            .highlight_symbol(">> ");
+
+        app.mouse.list = Some(render_table(f, table, area, &mut app.documents_state));
+    }
+}
+
+/// Row color of an operation by its status
+fn operation_style(theme: &Theme, status: &str) -> Style {
+    match status {
+        "failed" => theme.error,
+        "pending" => theme.warning,
+        "processing" => theme.accent,
+        "completed" => theme.success,
+        _ => theme.muted,
+    }
+}
+
+fn render_operations(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let mut items = vec![ListItem::new(format!(
+        " {:<11} {:<24} {:<16} {:>5}  {}",
+        "STATUS", "KIND", "CREATED", "ITEMS", "ERROR"
+    ))
+    .style(theme.accent.add_modifier(Modifier::BOLD))];
+
+    for op in &app.operations {
+        // Only the first line of an error fits; Enter shows all of it
+        let error: String = op
+            .error_message
+            .as_deref()
+            .and_then(|e| e.lines().next())
+            .unwrap_or("")
+            .chars()
+            .skip(app.horizontal_scroll)
+            .collect();
+        let content = format!(
+            " {:<11} {:<24} {:<16} {:>5}  {}",
+            op.status,
+            op.task_type.chars().take(24).collect::<String>(),
+            format_date(&op.created_at),
+            op.items_count,
+            error
+        );
+        items.push(ListItem::new(content).style(operation_style(&theme, &op.status)));
+    }
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Operations ({}) - r retries a failed one, x cancels a pending one",
+            app.list_position(
+                app.operations.len(),
+                app.operations_loaded.len(),
+                &app.operations_page
+            )
+        )))
+        .highlight_style(theme.highlight)
+        .highlight_symbol(">> ");
+
+    app.mouse.list = Some(render_with_header_row(f, list, area, &mut app.operations_state));
+}
+
+fn render_stats(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let Some(stats) = &app.stats else {
+        let loading = Paragraph::new("Loading statistics...")
+            .style(theme.muted)
+            .block(Block::default().borders(Borders::ALL).title("Statistics"));
+        f.render_widget(loading, area);
+        return;
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),  // Totals and operations
+            Constraint::Min(0),     // Bar charts
+            Constraint::Length(5),  // Memory units over the session
+        ])
+        .split(area);
+
+    let tiles = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 4); 4])
+        .split(rows[0]);
+    let totals = [
+        ("Memory units", stats.total_nodes),
+        ("Links", stats.total_links),
+        ("Documents", stats.total_documents),
+    ];
+    for (tile, (label, value)) in tiles.iter().zip(totals) {
+        let number = Paragraph::new(Span::styled(
+            value.to_string(),
+            theme.accent.add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(label));
+        f.render_widget(number, *tile);
+    }
+    let badge = |count: i32, label: &str, style: Style| {
+        let style = if count > 0 { style.add_modifier(Modifier::REVERSED) } else { theme.muted };
+        Span::styled(format!(" {} {} ", count, label), style)
+    };
+    let operations = Paragraph::new(Line::from(vec![
+        badge(stats.pending_operations, "pending", theme.warning),
+        Span::raw(" "),
+        badge(stats.failed_operations, "failed", theme.error),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).title("Operations (o)"));
+    f.render_widget(operations, tiles[3]);
+
+    let charts = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    render_count_chart(f, "Memory units by type", &stats.nodes_by_fact_type, &theme, charts[0]);
+    render_count_chart(f, "Links by type", &stats.links_by_link_type, &theme, charts[1]);
+
+    let history = &app.stats_history;
+    let (low, high) = (
+        history.iter().min().copied().unwrap_or(0),
+        history.iter().max().copied().unwrap_or(0),
+    );
+    let title = format!(
+        "Memory units this session ({} samples, {}–{})",
+        history.len(),
+        low,
+        high
+    );
+    // Only the latest samples fit; the sparkline draws one column per sample
+    let width = rows[2].width.saturating_sub(2) as usize;
+    let sparkline = Sparkline::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .data(&history[history.len().saturating_sub(width)..])
+        .style(theme.secondary);
+    f.render_widget(sparkline, rows[2]);
+}
+
+/// A bar chart of `counts`, largest first, with bars narrowed to fit the area
+fn render_count_chart(
+    f: &mut Frame,
+    title: &str,
+    counts: &HashMap<String, i32>,
+    theme: &Theme,
+    area: Rect,
+) {
+    let mut bars: Vec<(&str, u64)> =
+        counts.iter().map(|(name, count)| (name.as_str(), (*count).max(0) as u64)).collect();
+    bars.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let block = Block::default().borders(Borders::ALL).title(title.to_string());
+    if bars.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("None", theme.muted)).block(block), area);
+        return;
+    }
+    let inner_width = area.width.saturating_sub(2);
+    let slots = bars.len() as u16;
+    let bar_width = (inner_width.saturating_sub(slots - 1) / slots).clamp(1, 12);
+    let chart = BarChart::default()
+        .block(block)
+        .data(bars.as_slice())
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(theme.accent)
+        .value_style(theme.accent.add_modifier(Modifier::REVERSED))
+        .label_style(theme.muted);
+    f.render_widget(chart, area);
+}
+
+/// Name, value (1-5) and scale of each disposition trait, as `bank disposition` prints them
+fn disposition_traits(disposition: &DispositionTraits) -> [(&'static str, i64, &'static str); 3] {
+    [
+        ("Skepticism", disposition.skepticism.get() as i64, "1=trusting, 5=skeptical"),
+        ("Literalism", disposition.literalism.get() as i64, "1=flexible, 5=literal"),
+        ("Empathy", disposition.empathy.get() as i64, "1=detached, 5=empathetic"),
+    ]
+}
+
+fn render_profile(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let Some(profile) = &app.profile else {
+        let loading = Paragraph::new("Loading profile...")
+            .style(theme.muted)
+            .block(Block::default().borders(Borders::ALL).title("Profile"));
+        f.render_widget(loading, area);
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),  // Name and bank ID
+            Constraint::Min(3),     // Mission
+            Constraint::Length(8),  // Disposition gauges
+        ])
+        .split(area);
+
+    let header = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Name: ", theme.muted),
+            Span::styled(profile.name.clone(), theme.accent.add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(vec![
+            Span::styled("Bank: ", theme.muted),
+            Span::raw(profile.bank_id.clone()),
+        ]),
+    ])
+    .block(Block::default().borders(Borders::ALL).title("Profile (e to edit)"));
+    f.render_widget(header, chunks[0]);
+
+    // Servers predating missions only fill in the background
+    let mission = match profile.background.as_deref() {
+        Some(background) if profile.mission.is_empty() => background,
+        _ => profile.mission.as_str(),
+    };
+    let inner_width = chunks[1].width.saturating_sub(2).max(1) as usize;
+    let inner_height = chunks[1].height.saturating_sub(2);
+    app.profile_height = inner_height;
+    let rows: usize = mission.lines().map(|line| wrapped_rows(line, inner_width)).sum();
+    let max_scroll = rows.saturating_sub(inner_height as usize).min(u16::MAX as usize) as u16;
+    app.profile_scroll = app.profile_scroll.min(max_scroll);
+    let text = if mission.is_empty() {
+        Line::from(Span::styled("No mission set", theme.muted)).into()
+    } else {
+        ratatui::text::Text::styled(mission, theme.text)
+    };
+    let mission_pane = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title("Mission"))
+        .wrap(Wrap { trim: false })
+        .scroll((app.profile_scroll, 0));
+    f.render_widget(mission_pane, chunks[1]);
+
+    let block = Block::default().borders(Borders::ALL).title("Disposition");
+    let inner = block.inner(chunks[2]);
+    f.render_widget(block, chunks[2]);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2); 3])
+        .split(inner);
+    let before = app.profile_before.as_ref().map(disposition_traits);
+    let traits = disposition_traits(&profile.disposition);
+    for (i, (name, value, scale)) in traits.into_iter().enumerate() {
+        // The gauge on the first row, the scale below it
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(12), Constraint::Min(10), Constraint::Length(10)])
+            .split(Rect {
+                y: rows[i].y,
+                height: 1,
+                ..rows[i]
+            });
+        let label = Span::styled(name, theme.tertiary.add_modifier(Modifier::BOLD));
+        f.render_widget(Paragraph::new(Line::from(label)), columns[0]);
+        let gauge = Gauge::default()
+            .gauge_style(theme.accent)
+            .ratio(((value - 1) as f64 / 4.0).clamp(0.0, 1.0))
+            .label(format!("{}/5", value));
+        f.render_widget(gauge, columns[1]);
+        // What the last edit changed
+        if let Some(old) = before.map(|traits| traits[i].1).filter(|old| *old != value) {
+            let change = Span::styled(format!(" was {}", old), theme.warning);
+            f.render_widget(Paragraph::new(Line::from(change)), columns[2]);
+        }
+        let scale_row = Rect {
+            x: columns[1].x,
+            y: rows[i].y + 1,
+            width: columns[1].width,
+            height: rows[i].height.saturating_sub(1),
+        };
+        f.render_widget(Paragraph::new(Span::styled(scale, theme.muted)), scale_row);
+    }
+}
+
+fn render_document_detail(f: &mut Frame, detail: &mut DocumentDetail, theme: &Theme, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),  // Document metadata
+            Constraint::Min(0),     // Chunks or full text
+        ])
+        .split(area);
+
+    let document = &detail.document;
+    // Listings report the size in characters, so count them here too
+    let size = document.original_text.chars().count() as i64;
+    let content_type =
+        recorded_content_type(document.document_metadata.as_ref(), document.retain_params.as_ref());
+    let status = if document.memory_unit_count > 0 {
+        format!("processed, {} memories", document.memory_unit_count)
+    } else {
+        "no memories extracted yet".to_string()
+    };
+    let metadata_text = format!(
+        "ID: {}\nType: {} | Size: {}\nCreated: {} | Updated: {}\nStatus: {}\nChunks: {}",
+        document.id,
+        content_type.unwrap_or_else(|| "unknown".to_string()),
+        format_size(size),
+        format_date(&document.created_at),
+        format_date(&document.updated_at),
+        status,
+        detail.chunks_total
+    );
+    let metadata = Paragraph::new(metadata_text)
+        .block(Block::default().borders(Borders::ALL).title("Document Metadata (Esc to close)"))
+        .style(theme.accent);
+    f.render_widget(metadata, chunks[0]);
+
+    let inner_width = chunks[1].width.saturating_sub(2).max(1) as usize;
+    let inner_height = chunks[1].height.saturating_sub(2);
+    detail.page_height = inner_height;
+
+    if detail.show_full_text {
+        let text = document.original_text.as_str();
+        let rows: usize = text.lines().map(|line| wrapped_rows(line, inner_width)).sum();
+        let max_scroll = rows.saturating_sub(inner_height as usize).min(u16::MAX as usize) as u16;
+        detail.text_scroll = detail.text_scroll.min(max_scroll);
+        let content = Paragraph::new(text)
+            .block(Block::default().borders(Borders::ALL).title("Full Text (t for chunks)"))
+            .wrap(Wrap { trim: false })
+            .scroll((detail.text_scroll, 0))
+            .style(theme.text);
+        f.render_widget(content, chunks[1]);
+        return;
+    }
+
+    let title = format!(
+        "Chunks ({} of {} loaded, t for full text)",
+        detail.chunks.len(),
+        detail.chunks_total
+    );
+    if detail.chunks.is_empty() {
+        let empty = Paragraph::new("  This document has no chunks.")
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(theme.muted);
+        f.render_widget(empty, chunks[1]);
+        return;
+    }
+
+    // One block per chunk, laid out like `ui::print_chunk`
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_rows = (0, 0);
+    let mut row = 0;
+    for (i, chunk) in detail.chunks.iter().enumerate() {
+        let selected = i == detail.selected_chunk;
+        let start = row;
+        let heading = format!(
+            "{}─── Chunk {} ───",
+            if selected { ">> " } else { "" },
+            chunk.chunk_index
+        );
+        let heading_style = if selected {
+            theme.tertiary.patch(theme.highlight)
+        } else {
+            theme.secondary
+        };
+        lines.push(Line::styled(heading, heading_style));
+        row += 1;
+        for text_line in chunk.chunk_text.lines() {
+            row += wrapped_rows(text_line, inner_width);
+            lines.push(Line::styled(text_line, theme.text));
+        }
+        lines.push(Line::styled(
+            format!("Chunk ID: {} | Index: {}", chunk.chunk_id, chunk.chunk_index),
+            theme.muted,
+        ));
+        lines.push(Line::from(""));
+        row += 2;
+        if selected {
+            selected_rows = (start, row);
+        }
+    }
+
+    // Keep the selected chunk in view, showing its start when it is taller than the panel
+    let height = inner_height as usize;
+    let mut scroll = detail.chunk_scroll as usize;
+    if selected_rows.0 < scroll {
+        scroll = selected_rows.0;
+    } else if selected_rows.1 > scroll + height {
+        scroll = selected_rows.1.saturating_sub(height).min(selected_rows.0);
+    }
+    detail.chunk_scroll = scroll.min(u16::MAX as usize) as u16;
+
+    let content = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((detail.chunk_scroll, 0));
+    f.render_widget(content, chunks[1]);
+}
+
+fn render_query(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Query input
+            Constraint::Min(0),     // Results or Response
+        ])
+        .split(area);
+
+    // Query input
+    let query_style = if app.input_mode == InputMode::Query {
+        theme.tertiary
+    } else {
+        Style::default()
+    };
+
+    let mode_label = match app.query_mode {
+        QueryMode::Recall => "Recall",
+        QueryMode::Reflect => "Reflect",
+    };
+    let title = format!(
+        "{} Query [{}] (/ edit, m mode, b budget{})",
+        mode_label,
+        app.query_settings().describe(&app.query_mode),
+        if app.query_mode == QueryMode::Recall {
+            ", t types, +/- tokens"
+        } else {
+            ""
+        },
+    );
+
+    // Scroll sideways so the cursor stays inside the box
+    let inner_width = chunks[0].width.saturating_sub(2).max(1);
+    let cursor = app.query_text.cursor_column();
+    let scroll = cursor.saturating_sub(inner_width - 1);
+    let query = Paragraph::new(app.query_text.as_str())
+        .style(query_style)
+        .scroll((0, scroll))
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(query, chunks[0]);
+    if app.input_mode == InputMode::Query {
+        f.set_cursor_position((chunks[0].x + 1 + cursor - scroll, chunks[0].y + 1));
+    }
+
+    // Show loading indicator if loading
+    if app.is_loading(RequestSlot::Query) {
+        let loading_text = match app.query_mode {
+            QueryMode::Recall => "Searching memories...",
+            QueryMode::Reflect => "Reflecting on memories...",
+        };
+
+        // Create animated dots based on time
+        let dots = ".".repeat(((std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() / 500) % 4) as usize);
+
+        let loading_lines = vec![
+            Line::from(""),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("    ", Style::default()),
+                Span::styled(format!("{}{}", loading_text, dots), theme.secondary.add_modifier(Modifier::BOLD)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                "    Please wait while we process your query... (Esc to cancel)",
+                theme.muted,
+            )),
+        ];
+
+        let loading_widget = Paragraph::new(loading_lines)
+            .block(Block::default().borders(Borders::ALL).title(format!("{} in progress", mode_label)))
+            .alignment(Alignment::Left);
+
+        f.render_widget(loading_widget, chunks[1]);
+        return;
+    }
+
+    // Results or Response based on mode
+    match app.query_mode {
+        QueryMode::Recall => {
+            let results_area = if app.trace_enabled {
+                let trace_height = if app.trace_collapsed {
+                    Constraint::Length(3)
+                } else {
+                    Constraint::Percentage(45)
+                };
+                let panes = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), trace_height])
+                    .split(chunks[1]);
+                render_trace_pane(f, app, panes[1]);
+                panes[0]
+            } else {
+                chunks[1]
+            };
+
+            // If viewing a recall result, show its details
+            if let Some(result) = &app.viewing_recall_result {
+                let recall_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(7),  // Metadata
+                        Constraint::Min(0),     // Full text
+                    ])
+                    .split(results_area);
+
+                // Metadata section
+                let mem_type = result.type_.as_deref().unwrap_or("unknown");
+                let date = |d: Option<&str>| {
+                    d.map(format_date).unwrap_or_else(|| "unknown".to_string())
+                };
+                let occurred_start = date(result.occurred_start.as_deref());
+                let occurred_end = date(result.occurred_end.as_deref());
+                let mentioned_at = date(result.mentioned_at.as_deref());
+
+                let metadata_text = format!(
+                    "Type: {}\nMentioned At: {}\nOccurred: {} to {}",
+                    mem_type, mentioned_at, occurred_start, occurred_end
+                );
+
+                let metadata = Paragraph::new(metadata_text)
+                    .block(Block::default().borders(Borders::ALL).title("Recall Result Metadata"))
+                    .style(theme.accent);
+
+                f.render_widget(metadata, recall_chunks[0]);
+
+                let text_area = if app.recall_chunk_visible {
+                    let panes = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                        .split(recall_chunks[1]);
+                    render_recall_chunk(f, app, result, panes[1]);
+                    panes[0]
+                } else {
+                    recall_chunks[1]
+                };
+
+                // Full text content, with the query terms highlighted
+                let text: Vec<Line> = result
+                    .text
+                    .lines()
+                    .map(|line| highlight_terms(line, &app.recall_terms, &theme))
+                    .collect();
+                let content_widget = Paragraph::new(text)
+                    .block(Block::default().borders(Borders::ALL).title("Full Text (Esc to close)"))
+                    .wrap(Wrap { trim: false })
+                    .style(theme.text);
+
+                f.render_widget(content_widget, text_area);
+            } else {
+                // Show results as a table like memories
+                let mut items = vec![
+                    // Header row
+                    ListItem::new(format!("{:<10} {:<18} {:<18} {}", "TYPE", "OCCURRED START", "OCCURRED END", "TEXT"))
+                        .style(theme.accent.add_modifier(Modifier::BOLD))
+                ];
+
+                // Data rows
+                for result in &app.query_results {
+                    let mem_type = result.type_.as_deref().unwrap_or("unknown");
+                    let occurred_start = result.occurred_start.as_deref()
+                        .map(format_date)
+                        .unwrap_or_else(|| "-".to_string());
+                    let occurred_end = result.occurred_end.as_deref()
+                        .map(format_date)
+                        .unwrap_or_else(|| "-".to_string());
+                    let text = &result.text;
+
+                    // Apply horizontal scroll
+                    let scrolled_text: String = text.chars().skip(app.horizontal_scroll).take(80).collect();
+
+                    let columns =
+                        format!("{:<10} {:<18} {:<18} ", mem_type, occurred_start, occurred_end);
+                    let mut line = highlight_terms(&scrolled_text, &app.recall_terms, &theme);
+                    line.spans.insert(0, Span::raw(columns));
+                    items.push(ListItem::new(line).style(theme.text));
+                }
+
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL).title(format!("Recall Results ({}) - Press Enter to view full text", app.query_results.len())))
+                    .highlight_style(theme.highlight)
+                    .highlight_symbol(">> ");
+
+                let state = &mut app.query_results_state;
+                app.mouse.list = Some(render_with_header_row(f, list, results_area, state));
+            }
+        }
+        QueryMode::Reflect => render_reflect_response(f, app, chunks[1]),
+    }
+
+    if app.viewing_memory.is_some() {
+        render_memory_detail(f, app, area);
+    }
+}
+
+/// Trace of the last recall: the parsed report, the raw JSON, or a one-line summary when collapsed
+fn render_trace_pane(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    app.mouse.trace = Some(area);
+    let title = if app.trace_collapsed {
+        "Trace (T off, z expand)"
+    } else if app.trace_raw {
+        "Trace JSON (T off, z collapse, r report)"
+    } else {
+        "Trace (T off, z collapse, r raw JSON)"
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.secondary)
+        .title(title);
+    let Some(trace) = &app.recall_trace else {
+        let note = if app.query_results.is_empty() {
+            "Run a recall to see its trace"
+        } else {
+            "These results were fetched without a trace; run the query again"
+        };
+        let empty = Paragraph::new(note).style(theme.muted).block(block);
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let report = TraceReport::from_trace(trace);
+    if app.trace_collapsed {
+        let summary = Paragraph::new(trace_summary(&report))
+            .style(theme.text)
+            .block(block);
+        f.render_widget(summary, area);
+        return;
+    }
+
+    let lines: Vec<Line> = if app.trace_raw {
+        serde_json::to_string_pretty(trace)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect()
+    } else {
+        trace_lines(&report, &theme)
+    };
+    app.trace_height = area.height.saturating_sub(2);
+    let max_scroll = (lines.len() as u16).saturating_sub(app.trace_height);
+    app.trace_scroll = app.trace_scroll.min(max_scroll);
+
+    let widget = Paragraph::new(lines)
+        .style(theme.text)
+        .scroll((app.trace_scroll, 0))
+        .block(block);
+    f.render_widget(widget, area);
+}
+
+/// One line for the collapsed trace pane, such as "total 250.0ms · 5 stages · 12 returned"
+fn trace_summary(report: &TraceReport) -> String {
+    let mut parts = Vec::new();
+    if let Some(total) = report.total_ms {
+        parts.push(format!("total {:.1}ms", total));
+    }
+    let stages = report.stages.len();
+    parts.push(format!("{} stage{}", stages, if stages == 1 { "" } else { "s" }));
+    if let Some(returned) = report.candidates.last() {
+        parts.push(format!("{} {}", returned.count, returned.stage));
+    }
+    parts.join(" · ")
+}
+
+/// The trace report laid out like `memory recall --trace`
+fn trace_lines(report: &TraceReport, theme: &Theme) -> Vec<Line<'static>> {
+    let heading = |text: &str| {
+        Line::from(Span::styled(
+            format!("─── {} ───", text),
+            theme.secondary.add_modifier(Modifier::BOLD),
+        ))
+    };
+    let dim = theme.muted;
+    let mut lines = Vec::new();
+
+    if let Some(total) = report.total_ms {
+        lines.push(Line::from(vec![
+            Span::styled("total time: ", dim),
+            Span::styled(format!("{:.2}ms", total), theme.accent),
+        ]));
+    }
+
+    if !report.stages.is_empty() {
+        let name_width = report.stages.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        let slowest = report.stages.iter().map(|s| s.duration_ms).fold(0.0_f64, f64::max);
+        lines.push(heading("Stages"));
+        for stage in &report.stages {
+            let bar_len = if slowest > 0.0 {
+                ((stage.duration_ms / slowest) * TRACE_BAR_WIDTH as f64).round() as usize
+            } else {
+                0
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "  {:<width$}  {:>10}  ",
+                    stage.name,
+                    format!("{:.1}ms", stage.duration_ms),
+                    width = name_width
+                )),
+                Span::styled("█".repeat(bar_len.max(1)), theme.secondary),
+            ]));
+        }
+    }
+
+    if !report.candidates.is_empty() {
+        let stage_width = report.candidates.iter().map(|c| c.stage.len()).max().unwrap_or(0);
+        lines.push(heading("Candidates"));
+        for candidate in &report.candidates {
+            lines.push(Line::from(format!(
+                "  {:<width$}  {:>6}",
+                candidate.stage,
+                candidate.count,
+                width = stage_width
+            )));
+        }
+    }
+
+    let search = &report.search;
+    let counts = [
+        ("nodes visited", search.nodes_visited),
+        ("nodes pruned", search.nodes_pruned),
+        ("entry points", search.entry_points),
+        ("temporal links", search.temporal_links_followed),
+        ("semantic links", search.semantic_links_followed),
+        ("entity links", search.entity_links_followed),
+    ];
+    if counts.iter().any(|(_, count)| count.is_some()) {
+        lines.push(heading("Search"));
+        for (label, count) in counts {
+            if let Some(count) = count {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {}: ", label), dim),
+                    Span::raw(count.to_string()),
+                ]));
+            }
+        }
+    }
+
+    let budget = &report.budget;
+    if budget.budget.is_some() || budget.max_tokens.is_some() {
+        lines.push(heading("Budget"));
+        if let Some(limit) = budget.budget {
+            let usage = match (budget.budget_used, budget.budget_remaining) {
+                (Some(used), Some(remaining)) => format!(" (used {}, {} left)", used, remaining),
+                (Some(used), None) => format!(" (used {})", used),
+                _ => String::new(),
+            };
+            lines.push(Line::from(format!("  budget: {}{}", limit, usage)));
+        }
+        if let Some(max_tokens) = budget.max_tokens {
+            lines.push(Line::from(format!("  max tokens: {}", max_tokens)));
+        }
+    }
+
+    if !report.other.is_empty() {
+        let keys: Vec<&str> = report.other.keys().map(String::as_str).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Not shown: {} (r for raw JSON)", keys.join(", ")),
+            theme.warning,
+        )));
+    }
+    lines
+}
+
+/// Source chunk of a recall result, laid out like `print_chunk` with the query terms highlighted
+fn render_recall_chunk(f: &mut Frame, app: &App, result: &RecallResult, area: Rect) {
+    let theme = app.theme;
+    let chunk = result.chunk_id.as_ref().and_then(|id| app.recall_chunks.get(id));
+    let lines = match (chunk, &result.chunk_id) {
+        (Some(chunk), _) => {
+            let mut lines = vec![Line::from(Span::styled(
+                "─── Source Chunk ───",
+                theme.secondary.add_modifier(Modifier::BOLD),
+            ))];
+            let terms = &app.recall_terms;
+            lines.extend(chunk.text.lines().map(|line| highlight_terms(line, terms, &theme)));
+            if chunk.truncated {
+                lines.push(Line::from(Span::styled(
+                    "[Truncated due to token limit]",
+                    theme.warning,
+                )));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Chunk ID: {} | Index: {}", chunk.id, chunk.chunk_index),
+                theme.muted,
+            )));
+            lines
+        }
+        (None, Some(_)) => vec![Line::from("Loading chunk…")],
+        (None, None) => vec![Line::from("No source chunk for this result")],
+    };
+
+    let widget = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Source Chunk (c to hide)"))
+        .wrap(Wrap { trim: false })
+        .style(theme.text);
+    f.render_widget(widget, area);
+}
+
+/// Lowercased words of a query, used to highlight matches in recall results; words
+/// shorter than three characters ("a", "in", "of") are skipped
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query.split_whitespace() {
+        let term = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+        if term.chars().count() >= 3 && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// A line with every case-insensitive occurrence of `terms` highlighted, overlapping
+/// occurrences merged into one span
+fn highlight_terms(line: &str, terms: &[String], theme: &Theme) -> Line<'static> {
+    // Lowercasing can change byte lengths (İ becomes i̇), so remember the char of
+    // `line` each byte of the lowercased copy came from
+    let mut lower = String::with_capacity(line.len());
+    let mut origin = Vec::with_capacity(line.len());
+    for (offset, c) in line.char_indices() {
+        for lowered in c.to_lowercase() {
+            lower.push(lowered);
+            origin.resize(lower.len(), offset);
+        }
+    }
+
+    let mut marked = vec![false; line.len()];
+    for term in terms.iter().filter(|term| !term.is_empty()) {
+        let mut from = 0;
+        while let Some(found) = lower[from..].find(term.as_str()) {
+            let start = from + found;
+            let last = origin[start + term.len() - 1];
+            let end = last + line[last..].chars().next().map_or(0, char::len_utf8);
+            marked[origin[start]..end].fill(true);
+            // Step one char, so occurrences that overlap are found too
+            from = start + lower[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    let highlight = theme.search_match;
+    let mut spans = Vec::new();
+    let mut start = 0;
+    while start < line.len() {
+        let is_marked = marked[start];
+        let mut end = start;
+        while end < line.len() && marked[end] == is_marked {
+            end += 1;
+        }
+        // Whole chars are marked, so runs always end on char boundaries
+        let text = line[start..end].to_string();
+        spans.push(if is_marked {
+            Span::styled(text, highlight)
+        } else {
+            Span::raw(text)
+        });
+        start = end;
+    }
+    Line::from(spans)
+}
+
+/// Reflect answer with a scroll position, above the memories it was based on
+fn render_reflect_response(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    if app.query_response.is_empty() {
+        let empty = Paragraph::new(
+            "No response yet. Enter a query and press Enter to get a reflection.",
+        )
+        .style(theme.text)
+        .block(Block::default().borders(Borders::ALL).title("Reflect Response"))
+        .wrap(Wrap { trim: false });
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let sources_height = if app.reflect_sources.is_empty() {
+        0
+    } else {
+        (app.reflect_sources.len() as u16 + 2).min(10)
+    };
+    let panes = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(sources_height)])
+        .split(area);
+    let focused = theme.tertiary;
+    let unfocused = Style::default();
+
+    // Keep the scroll offset within the wrapped text
+    let inner_width = panes[0].width.saturating_sub(2).max(1) as usize;
+    let inner_height = panes[0].height.saturating_sub(2);
+    app.reflect_height = inner_height;
+    let rows: usize = app.query_response.lines().map(|line| wrapped_rows(line, inner_width)).sum();
+    let max_scroll = rows.saturating_sub(inner_height as usize).min(u16::MAX as usize) as u16;
+    app.reflect_scroll = app.reflect_scroll.min(max_scroll);
+    let position = format!(
+        " line {}/{} ",
+        (app.reflect_scroll as usize + 1).min(rows.max(1)),
+        rows.max(1)
+    );
+
+    let response = Paragraph::new(app.query_response.as_str())
+        .style(theme.text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(if app.reflect_sources_focused { unfocused } else { focused })
+                .title("Reflect Response")
+                .title_bottom(Line::from(position).alignment(Alignment::Right)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.reflect_scroll, 0));
+    f.render_widget(response, panes[0]);
+
+    if app.reflect_sources.is_empty() {
+        return;
+    }
+    let items: Vec<ListItem> = app
+        .reflect_sources
+        .iter()
+        .map(|fact| {
+            let fact_type = fact.type_.as_deref().unwrap_or("unknown");
+            let text: String = fact.text.lines().next().unwrap_or("").chars().take(200).collect();
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<12}", fact_type), theme.secondary),
+                Span::styled(text, theme.text),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(if app.reflect_sources_focused { focused } else { unfocused })
+                .title(format!(
+                    "Based on {} memories (Tab to focus, Enter to open)",
+                    app.reflect_sources.len()
+                )),
+        )
+        .highlight_style(theme.highlight)
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, panes[1], &mut app.reflect_sources_state);
+    app.mouse.list = Some(ListArea::new(panes[1], app.reflect_sources_state.offset(), 0));
+}
+
+/// A help screen line for keys bound to `actions`
+fn help_line(keys: &Keymap, actions: &[Action], text: &str) -> Line<'static> {
+    let labels: Vec<String> = actions
+        .iter()
+        .map(|action| keys.labels(*action))
+        .filter(|label| !label.is_empty())
+        .collect();
+    let labels = if labels.is_empty() { "unbound".to_string() } else { labels.join(", ") };
+    Line::from(format!("  {:<11} - {}", labels, text))
+}
+
+fn render_help(f: &mut Frame, theme: &Theme, keys: &Keymap, area: Rect) {
+    let k = |action| keys.label(action);
+    let help_text = vec![
+        Line::from(Span::styled("Hindsight Explorer - Keyboard Shortcuts", theme.accent.add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Navigation Flow", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(format!("  1. Start by selecting a bank ({})", k(Action::Open))),
+        Line::from("  2. View memories, entities, or documents for that bank"),
+        Line::from(format!(
+            "  3. Press {} from any view to query (recall/reflect)",
+            k(Action::Query)
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Basic Navigation", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        help_line(keys, &[Action::Up, Action::Down], "Navigate up/down in lists"),
+        help_line(keys, &[Action::Left, Action::Right], "Scroll text left/right in tables"),
+        help_line(keys, &[Action::Open], "Select item / view details"),
+        help_line(keys, &[Action::Back], "Go back / close detail view"),
+        help_line(keys, &[Action::Filter], "Filter the loaded rows of a list (Esc clears)"),
+        help_line(keys, &[Action::PageUp, Action::PageDown], "Move a screen up/down"),
+        help_line(keys, &[Action::HalfPageUp, Action::HalfPageDown], "Move half a screen up/down"),
+        help_line(
+            keys,
+            &[Action::First, Action::Last],
+            &format!("First / last row; 42{} jumps to row 42", k(Action::Last)),
+        ),
+        help_line(keys, &[Action::NextPage, Action::PrevPage], "Next / previous page"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Memory Detail", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from("  j/k, ↑/↓    - Scroll the full text"),
+        Line::from("  PgUp/PgDn   - Scroll a page (g/G for top/bottom)"),
+        Line::from("  n/p, →/←    - Next / previous memory"),
+        Line::from("  y           - Copy the memory's text"),
+        Line::from("  d           - Open the memory's source document"),
+        Line::from("  e           - List the entities the memory mentions"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Error Popup", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from("  r           - Retry the failed request"),
+        Line::from("  Esc/Enter   - Dismiss (j/k scroll long messages)"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Document Detail", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from("  j/k, g/G    - Move between chunks / jump to first or last"),
+        Line::from("  t           - Toggle chunk list and full text"),
+        Line::from("  m           - List the memories extracted from the document"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Deleting", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        help_line(keys, &[Action::Mark], "Mark / unmark a memory or document"),
+        help_line(
+            keys,
+            &[Action::Delete],
+            "Delete marked rows, or the selected one (memories go with their document)",
+        ),
+        Line::from("  y/Enter     - Confirm, Esc to cancel"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Banks View", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        help_line(keys, &[Action::NewBank], "Create a bank (ID and optional name)"),
+        help_line(keys, &[Action::RenameBank], "Rename the selected bank"),
+        help_line(keys, &[Action::DeleteBank], "Delete the selected bank (type its ID to confirm)"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Query View", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        help_line(keys, &[Action::Query], "Start or edit query (from any non-bank view)"),
+        help_line(keys, &[Action::Recall, Action::Reflect], "Start a recall / reflect query"),
+        help_line(keys, &[Action::Mode], "Toggle mode (Recall ↔ Reflect)"),
+        help_line(keys, &[Action::Budget], "Cycle budget (Low → Mid → High), kept per bank"),
+        help_line(
+            keys,
+            &[Action::Types],
+            "Cycle recall fact types (default → world → experience → observation → all)",
+        ),
+        help_line(
+            keys,
+            &[Action::MoreTokens, Action::FewerTokens],
+            "Step recall max tokens (512 to 32768), kept per bank",
+        ),
+        Line::from("  Enter       - Execute query"),
+        Line::from("  ↑/↓         - Previous / next query from history (while typing)"),
+        Line::from("  Ctrl+R      - Search query history"),
+        Line::from("  Ctrl+E      - Write the query or a new memory in $VISUAL/$EDITOR"),
+        help_line(
+            keys,
+            &[Action::Down, Action::Up, Action::PageUp, Action::PageDown],
+            "Scroll the reflect response",
+        ),
+        help_line(keys, &[Action::Sources], "Switch between reflect text and its sources"),
+        help_line(keys, &[Action::Chunk], "Show / hide the source chunk of a recall result"),
+        help_line(keys, &[Action::Trace], "Trace recalls (re-runs the query)"),
+        help_line(keys, &[Action::CollapseTrace], "Collapse / expand the trace pane"),
+        help_line(keys, &[Action::RawTrace], "Switch the trace between the report and raw JSON"),
+        help_line(
+            keys,
+            &[Action::PageUp, Action::PageDown],
+            "Scroll the trace pane while it is open",
+        ),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("General", theme.tertiary.add_modifier(Modifier::BOLD)),
+        ]),
+        help_line(keys, &[Action::Refresh], "Refresh current view"),
+        help_line(
+            keys,
+            &[Action::Export],
+            "Export the rows of the current list (JSON or Markdown)",
+        ),
+        help_line(
+            keys,
+            &[Action::AddMemory],
+            "Add a memory (Memories view; Ctrl+J for a new line)",
+        ),
+        help_line(keys, &[Action::Follow], "Follow new memories as they arrive, like tail -f"),
+        help_line(
+            keys,
+            &[Action::Sort],
+            "Sort memories or documents by the next column (loaded page only)",
+        ),
+        help_line(
+            keys,
+            &[Action::Operations],
+            &format!(
+                "Background operations of the bank ({} retry, {} cancel)",
+                k(Action::RetryOperation),
+                k(Action::CancelOperation)
+            ),
+        ),
+        help_line(
+            keys,
+            &[Action::Profile],
+            &format!(
+                "Bank profile: name, mission and disposition ({} edits)",
+                k(Action::EditProfile)
+            ),
+        ),
+        help_line(keys, &[Action::Stats], "Statistics dashboard of the bank"),
+        help_line(
+            keys,
+            &[Action::Memories, Action::Entities, Action::Documents],
+            "Memories / entities / documents of the bank",
+        ),
+        help_line(keys, &[Action::Options], "Options: page size, verbose loads, recall chunks"),
+        help_line(keys, &[Action::Inspect], "Selected item as JSON (y copies it)"),
+        help_line(keys, &[Action::Copy], "Copy the selected item's text"),
+        help_line(keys, &[Action::CopyAll], "Copy the current list as Markdown"),
+        help_line(keys, &[Action::NewTab], "Open a tab at the Banks list"),
+        help_line(keys, &[Action::NextTab, Action::PrevTab], "Next / previous tab"),
+        Line::from("  Alt+1..9    - Go to a tab"),
+        help_line(keys, &[Action::CloseTab], "Close the tab (asks while a query runs)"),
+        help_line(
+            keys,
+            &[Action::Theme],
+            "Cycle color theme (dark → light → monochrome), outside the Query view",
+        ),
+        Line::from("  Mouse       - Click selects, double-click opens, wheel scrolls"),
+        help_line(keys, &[Action::Help], "Toggle this help screen"),
+        help_line(keys, &[Action::Quit], "Quit"),
+        Line::from(""),
+        Line::from(Span::styled(format!("Press {} to close help", k(Action::Help)), theme.muted)),
+    ];
+
+    let help = Paragraph::new(help_text)
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .alignment(Alignment::Left);
+
+    f.render_widget(help, area);
+}
+
+/// The external editor command: `$VISUAL`, then `$EDITOR`, then a platform default
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string())
+}
+
+/// Open `text` in `editor` (a command, possibly with arguments like `code --wait`)
+/// through a temporary file and return the saved text. An editor that cannot
+/// start or exits with an error leaves the text as it was.
+fn edit_text(editor: &str, text: &str) -> Result<String> {
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("The editor command is empty")?;
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let path = std::env::temp_dir()
+        .join(format!("hindsight-explore-{}-{}.md", std::process::id(), nanos));
+    fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let status = std::process::Command::new(program).args(words).arg(&path).status();
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    let status = status.with_context(|| format!("Could not start editor '{}'", program))?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}; kept the original text", program, status);
+    }
+    let edited = edited.with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(edited.trim_end().to_string())
+}
+
+/// Suspend the explorer, edit the query or the memory text in the external
+/// editor, and resume with the result in the input
+fn edit_externally<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    target: EditorTarget,
+) -> Result<()> {
+    let original = match (target, app.memory_draft.as_ref()) {
+        (EditorTarget::Query, _) => app.query_text.as_str().to_string(),
+        (EditorTarget::Memory, Some(draft)) => draft.text.as_str().to_string(),
+        (EditorTarget::Memory, None) => return Ok(()),
+    };
+    let editor = editor_command();
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    // Whatever happened to the editor, the terminal goes back to the explorer
+    let edited = edit_text(&editor, &original);
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    if app.mouse_capture {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    terminal.clear()?;
+
+    match edited {
+        Ok(text) if text == original => {
+            app.status_message = "Editor closed; the text is unchanged".to_string();
+        }
+        Ok(text) => {
+            let chars = text.chars().count();
+            match target {
+                // Queries are one line; lines typed in the editor become sentences
+                EditorTarget::Query => {
+                    let lines: Vec<&str> =
+                        text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+                    app.query_text.set(lines.join(" "));
+                    app.query_history.reset_cursor();
+                }
+                EditorTarget::Memory => {
+                    if let Some(draft) = app.memory_draft.as_mut() {
+                        draft.text.set(text);
+                    }
+                }
+            }
+            app.status_message = format!("Loaded {} characters from {}", chars, editor);
+        }
+        Err(err) => app.error_message = format!("Error: {:#}", err),
+    }
+    Ok(())
+}
+
+/// The open tabs, each an explorer with its own views, lists and queries. They
+/// share the client, and only the tab in view auto-refreshes.
+struct Tabs {
+    tabs: Vec<App>,
+    active: usize,
+    /// Quitting once the writes in progress finish; holds the failed write count
+    /// when that was chosen, so a new failure calls it off
+    quit_when_done: Option<usize>,
+}
+
+impl Tabs {
+    fn new(app: App) -> Self {
+        Self {
+            tabs: vec![app],
+            active: 0,
+            quit_when_done: None,
         }
-    };
+    }
 
-    let context_widget = Paragraph::new(context_info)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(BRAND_START))
-            .title(" Context "))
-        .style(Style::default().fg(BRAND_END).add_modifier(Modifier::BOLD))
-        .alignment(Alignment::Left);
-    f.render_widget(context_widget, columns[0]);
+    fn active(&mut self) -> &mut App {
+        &mut self.tabs[self.active]
+    }
 
-    // Right: Shortcuts in columns if many
-    // Calculate shortcuts per column (max 3 lines of shortcuts)
-    let max_shortcuts_per_col = 3;
-    let num_cols = (shortcuts.len() + max_shortcuts_per_col - 1) / max_shortcuts_per_col;
+    /// Hand the tab titles to the tab in view, which draws them in its header
+    fn label_active(&mut self) {
+        let titles = if self.tabs.len() > 1 {
+            self.tabs.iter().map(App::tab_title).collect()
+        } else {
+            Vec::new()
+        };
+        let active = self.active;
+        let app = self.active();
+        app.tab_titles = titles;
+        app.tab_index = active;
+    }
 
-    let mut shortcut_lines = vec![];
-    for row in 0..max_shortcuts_per_col {
-        let mut line_spans = vec![];
+    fn apply(&mut self, request: TabRequest) {
+        match request {
+            TabRequest::Open => {
+                let current = &self.tabs[self.active];
+                let mut app = App::new(current.client.clone());
+                app.connection = current.connection.clone();
+                app.keys = current.keys.clone();
+                app.mouse_capture = current.mouse_capture;
+                app.export_format = current.export_format;
+                app.auto_refresh_enabled = current.auto_refresh_enabled;
+                app.refresh_interval = current.refresh_interval;
+                app.query_history = current.query_history.clone();
+                app.theme = current.theme;
+                app.refresh();
+                self.tabs.insert(self.active + 1, app);
+                self.select(self.active + 1);
+            }
+            TabRequest::Next => self.select((self.active + 1) % self.tabs.len()),
+            TabRequest::Previous => {
+                self.select((self.active + self.tabs.len() - 1) % self.tabs.len())
+            }
+            TabRequest::Select(index) if index < self.tabs.len() => self.select(index),
+            TabRequest::Select(index) => {
+                self.active().status_message = format!("There is no tab {}", index + 1);
+            }
+            TabRequest::Close { .. } if self.tabs.len() == 1 => {
+                let quit = self.active().keys.label(Action::Quit);
+                self.active().status_message = format!("This is the only tab; {} quits", quit);
+            }
+            TabRequest::Close { confirmed: false }
+                if self.tabs[self.active].is_loading(RequestSlot::Query) =>
+            {
+                self.active().pending_close_tab = true;
+            }
+            TabRequest::Close { .. } => {
+                let closed = self.tabs.remove(self.active);
+                self.active = self.active.min(self.tabs.len() - 1);
+                self.show(closed.theme, closed.query_history, closed.connection);
+            }
+        }
+    }
 
-        for col in 0..num_cols {
-            let idx = col * max_shortcuts_per_col + row;
-            if idx < shortcuts.len() {
-                let (key, desc, color) = &shortcuts[idx];
+    /// Labels of the writes running in any tab
+    fn writes_in_flight(&self) -> Vec<&'static str> {
+        self.tabs.iter().flat_map(App::writes_in_flight).collect()
+    }
 
-                // Each shortcut with proper alignment
-                let shortcut_text = format!("<{}> {:<10}", key, desc);
+    fn failed_writes(&self) -> usize {
+        self.tabs.iter().map(|app| app.failed_writes).sum()
+    }
 
-                line_spans.push(Span::styled(
-                    shortcut_text,
-                    Style::default().fg(*color).add_modifier(Modifier::BOLD)
-                ));
+    /// Act on a quit key and on the answer to the quit confirmation. Quitting while
+    /// writes are in progress asks first. Returns true when the explorer should exit.
+    fn settle_quit(&mut self, quit: bool) -> bool {
+        match self.active().quit_choice.take() {
+            Some(QuitChoice::Now) => return true,
+            Some(QuitChoice::WhenDone) => {
+                self.quit_when_done = Some(self.failed_writes());
+                self.active().status_message =
+                    "Quitting once the writes in progress finish".to_string();
             }
+            Some(QuitChoice::Stay) => {
+                self.quit_when_done = None;
+                self.active().status_message = "Not quitting".to_string();
+            }
+            None => {}
+        }
+        if !quit {
+            return false;
         }
+        let writes = self.writes_in_flight();
+        if writes.is_empty() {
+            return true;
+        }
+        self.active().pending_quit = Some(writes);
+        false
+    }
 
-        if !line_spans.is_empty() {
-            shortcut_lines.push(Line::from(line_spans));
+    /// Whether a quit waiting on writes can go ahead. A failed write calls it off and
+    /// leaves its error on screen.
+    fn ready_to_quit(&mut self) -> bool {
+        let Some(failed_before) = self.quit_when_done else {
+            return false;
+        };
+        if self.failed_writes() > failed_before {
+            self.quit_when_done = None;
+            self.active().status_message = "Not quitting: a write failed".to_string();
+            return false;
         }
+        self.writes_in_flight().is_empty()
     }
 
-    let shortcuts_widget = Paragraph::new(shortcut_lines)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(BRAND_START))
-            .title(" Shortcuts "))
-        .alignment(Alignment::Left);
+    /// Bring tab `index` into view
+    fn select(&mut self, index: usize) {
+        let left = &self.tabs[self.active];
+        let (theme, history, connection) =
+            (left.theme, left.query_history.clone(), left.connection.clone());
+        self.active = index;
+        self.show(theme, history, connection);
+    }
 
-    f.render_widget(shortcuts_widget, columns[1]);
+    /// Give the tab in view what the tabs share: the theme, the query history and
+    /// the connection status of the tab that was in view before
+    fn show(&mut self, theme: Theme, history: QueryHistory, connection: ConnectionStatus) {
+        let (index, count) = (self.active, self.tabs.len());
+        let app = self.active();
+        app.theme = theme;
+        app.query_history = history;
+        app.connection = connection;
+        app.status_message = format!("Tab {}/{}: {}", index + 1, count, app.tab_title());
+    }
 }
 
-fn render_header(f: &mut Frame, app: &App, area: Rect) {
-    let bank_info = if let Some(bank_id) = app.view.bank_id() {
-        format!(" [{}]", bank_id)
-    } else {
-        String::new()
-    };
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, tabs: &mut Tabs) -> Result<()> {
+    // Initial load
+    let app = tabs.active();
+    app.load_server_version();
+    app.refresh();
 
-    let title = format!("Hindsight Explorer - {}{}", app.view.title(), bank_info);
+    loop {
+        tabs.label_active();
+        let app = tabs.active();
+        terminal.draw(|f| ui(f, app))?;
 
-    let header = Paragraph::new(title)
-        .style(Style::default().fg(BRAND_START).add_modifier(Modifier::BOLD))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        if event::poll(Duration::from_millis(100))? {
+            let quit = match event::read()? {
+                Event::Key(key) => {
+                    app.last_input = Instant::now();
+                    handle_key(app, key)
+                }
+                Event::Mouse(mouse) => {
+                    if mouse.kind != MouseEventKind::Moved {
+                        app.last_input = Instant::now();
+                    }
+                    handle_mouse(app, mouse)
+                }
+                _ => false,
+            };
+            if tabs.settle_quit(quit) {
+                return Ok(());
+            }
+            let app = tabs.active();
+            if let Some(target) = app.editor_request.take() {
+                edit_externally(terminal, app, target)?;
+            }
+            if let Some(request) = app.tab_request.take() {
+                tabs.apply(request);
+            }
+        }
 
-    f.render_widget(header, area);
-}
+        // Apply responses from background requests, in every tab
+        for app in &mut tabs.tabs {
+            app.poll_responses();
+        }
+        if tabs.ready_to_quit() {
+            return Ok(());
+        }
 
-fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    // Simple status line only (shortcuts are now at the top, no border)
-    let status_line = if !app.error_message.is_empty() {
-        Line::from(vec![
-            Span::styled(" Error: ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::raw(&app.error_message),
-        ])
-    } else if app.loading {
-        Line::from(Span::styled(" Loading...", Style::default().fg(BRAND_END).add_modifier(Modifier::BOLD)))
-    } else if !app.status_message.is_empty() {
-        Line::from(vec![
-            Span::raw(" "),
-            Span::styled(&app.status_message, Style::default().fg(BRAND_MID)),
-        ])
-    } else {
-        Line::from("")
-    };
+        // Auto-refresh check, for the tab in view only
+        tabs.active().do_auto_refresh();
+    }
+}
 
-    let footer = Paragraph::new(status_line).alignment(Alignment::Left);
-    f.render_widget(footer, area);
+/// The digit of a plain number key press
+fn key_digit(key: &KeyEvent) -> Option<usize> {
+    match key.code {
+        KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+            c.to_digit(10).map(|d| d as usize)
+        }
+        _ => None,
+    }
 }
 
-fn render_banks(f: &mut Frame, app: &mut App, area: Rect) {
-    let items: Vec<ListItem> = app
-        .banks
-        .iter()
-        .map(|bank| {
-            let name = bank.name.as_deref().filter(|s| !s.is_empty()).unwrap_or("Unnamed");
-            let content = format!("{} - {}", bank.bank_id, name);
-            ListItem::new(content).style(Style::default().fg(Color::White))
-        })
-        .collect();
+/// Apply a key press; returns true when the explorer should quit
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    // Handle Ctrl+C to exit; pressed again at the quit confirmation, it quits at once
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) {
+        if app.pending_quit.take().is_some() {
+            app.quit_choice = Some(QuitChoice::Now);
+        }
+        return true;
+    }
 
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Banks"))
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">> ");
+    match app.input_mode {
+        // Asked on quit while writes run; takes every key until answered
+        _ if app.pending_quit.is_some() => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Char('q') | KeyCode::Enter => {
+                app.pending_quit = None;
+                app.quit_choice = Some(QuitChoice::Now);
+                return true;
+            }
+            KeyCode::Char('w') | KeyCode::Char('W') => {
+                app.pending_quit = None;
+                app.quit_choice = Some(QuitChoice::WhenDone);
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.pending_quit = None;
+                app.quit_choice = Some(QuitChoice::Stay);
+            }
+            _ => {}
+        },
+        // The error popup sits above everything, prompts included, until dismissed
+        _ if app.error_report.is_some() => {
+            let page = app
+                .error_report
+                .as_ref()
+                .map_or(1, |r| r.height.saturating_sub(1).max(1) as i32);
+            match key.code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Esc | KeyCode::Enter => app.error_report = None,
+                KeyCode::Char('r') => app.retry_failed(),
+                KeyCode::Down | KeyCode::Char('j') => app.scroll_error_report(1),
+                KeyCode::Up | KeyCode::Char('k') => app.scroll_error_report(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_error_report(page),
+                KeyCode::PageUp => app.scroll_error_report(-page),
+                KeyCode::Home | KeyCode::Char('g') => app.scroll_error_report(i32::MIN),
+                KeyCode::End | KeyCode::Char('G') => app.scroll_error_report(i32::MAX),
+                _ => {}
+            }
+        }
+        // The delete confirmation modal takes every key until answered
+        InputMode::Normal if app.pending_delete.is_some() => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                app.confirm_delete()
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.pending_delete = None;
+                app.status_message = "Deletion cancelled".to_string();
+            }
+            _ => {}
+        },
+        InputMode::Normal if app.pending_close_tab => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                app.pending_close_tab = false;
+                app.tab_request = Some(TabRequest::Close { confirmed: true });
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.pending_close_tab = false;
+                app.status_message = "Tab kept open".to_string();
+            }
+            _ => {}
+        },
+        InputMode::Normal if app.pending_operation.is_some() => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                app.confirm_operation_action()
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                app.pending_operation = None;
+                app.status_message = "Operation left as it was".to_string();
+            }
+            _ => {}
+        },
+        InputMode::Normal if app.options_popup.is_some() => {
+            let Some(popup) = app.options_popup.as_mut() else {
+                return false;
+            };
+            let last = popup.fields().len() - 1;
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                    popup.field = (popup.field + 1).min(last)
+                }
+                KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+                    popup.field = popup.field.saturating_sub(1)
+                }
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => popup.adjust(true),
+                KeyCode::Left | KeyCode::Char('h') => popup.adjust(false),
+                KeyCode::Enter => app.apply_options(),
+                KeyCode::Esc | KeyCode::Char('O') => {
+                    app.options_popup = None;
+                    app.status_message = "Options unchanged".to_string();
+                }
+                _ => {}
+            }
+        }
+        // The JSON inspector scrolls, copies, and closes back to what is under it
+        InputMode::Normal if app.json_inspector.is_some() => {
+            let page = app
+                .json_inspector
+                .as_ref()
+                .map_or(1, |i| i.height.saturating_sub(1).max(1) as i32);
+            match key.code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Esc | KeyCode::Char('J') => app.json_inspector = None,
+                KeyCode::Down | KeyCode::Char('j') => app.scroll_json_inspector(1),
+                KeyCode::Up | KeyCode::Char('k') => app.scroll_json_inspector(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_json_inspector(page),
+                KeyCode::PageUp => app.scroll_json_inspector(-page),
+                KeyCode::Home | KeyCode::Char('g') => app.scroll_json_inspector(i32::MIN),
+                KeyCode::End | KeyCode::Char('G') => app.scroll_json_inspector(i32::MAX),
+                KeyCode::Char('y') => app.copy_json_inspector(),
+                _ => {}
+            }
+        }
+        // Esc cancels what the user is waiting on before it navigates
+        InputMode::Normal if key.code == KeyCode::Esc && app.cancel_requests() => {}
+        // The memory detail popup has its own scrolling and paging keys
+        InputMode::Normal if app.viewing_memory.is_some() && !app.show_help => {
+            let page = app.memory_detail_height.saturating_sub(1).max(1) as i32;
+            match key.code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Char('?') => app.show_help = true,
+                KeyCode::Esc => app.go_back(),
+                KeyCode::Down | KeyCode::Char('j') => app.scroll_memory_detail(1),
+                KeyCode::Up | KeyCode::Char('k') => app.scroll_memory_detail(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_memory_detail(page),
+                KeyCode::PageUp => app.scroll_memory_detail(-page),
+                KeyCode::Home | KeyCode::Char('g') => app.memory_detail_scroll = 0,
+                KeyCode::End | KeyCode::Char('G') => app.scroll_memory_detail(i32::MAX),
+                KeyCode::Right | KeyCode::Char('n') => app.step_memory_detail(true),
+                KeyCode::Left | KeyCode::Char('p') => app.step_memory_detail(false),
+                KeyCode::Char('d') => app.jump_to_memory_document(),
+                KeyCode::Char('e') => app.jump_to_memory_entities(),
+                KeyCode::Char('J') => app.open_json_inspector(),
+                KeyCode::Char('y') => app.copy_selected(),
+                _ => {}
+            }
+        }
+        InputMode::Normal if app.viewing_document.is_some() && !app.show_help => {
+            let page = app
+                .viewing_document
+                .as_ref()
+                .map_or(1, |d| d.page_height.saturating_sub(1).max(1) as i32);
+            match key.code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Char('?') => app.show_help = true,
+                KeyCode::Esc => app.go_back(),
+                KeyCode::Down | KeyCode::Char('j') => app.move_in_document(1),
+                KeyCode::Up | KeyCode::Char('k') => app.move_in_document(-1),
+                KeyCode::PageDown => app.move_in_document(page),
+                KeyCode::PageUp => app.move_in_document(-page),
+                KeyCode::Home | KeyCode::Char('g') => app.document_top(),
+                KeyCode::End | KeyCode::Char('G') => app.document_bottom(),
+                KeyCode::Char('t') => app.toggle_document_text(),
+                KeyCode::Char('m') => app.jump_to_document_memories(),
+                KeyCode::Char('J') => app.open_json_inspector(),
+                KeyCode::Char('y') => app.copy_selected(),
+                _ => {}
+            }
+        }
+        InputMode::Normal => {
+            // Alt+1..Alt+9 go to a tab; plain digits are counts
+            if let KeyCode::Char(c @ '1'..='9') = key.code {
+                if key.modifiers.contains(KeyModifiers::ALT) {
+                    app.tab_request = Some(TabRequest::Select(c as usize - '1' as usize));
+                    return false;
+                }
+            }
+            let count = app.jump_count.take();
+            if let Some(digit) = key_digit(&key) {
+                if digit > 0 || count.is_some() {
+                    let count = count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                    app.jump_count = Some(count);
+                    app.pending_keys.clear();
+                    app.status_message = format!(
+                        "Go to row {} ({} or {})",
+                        count,
+                        app.keys.label(Action::First),
+                        app.keys.label(Action::Last)
+                    );
+                    return false;
+                }
+            }
 
-    f.render_stateful_widget(list, area, &mut app.banks_state);
+            app.pending_keys.push(KeyChord::from_event(&key));
+            match app.keys.lookup(&app.pending_keys, app.view.scope()) {
+                Lookup::Action(action) => {
+                    app.pending_keys.clear();
+                    let quit = run_action(app, action, count);
+                    app.pause_follow_if_moved();
+                    return quit;
+                }
+                // Wait for the rest of a binding like `dd`, keeping the count
+                Lookup::Prefix => {
+                    app.jump_count = count;
+                    let typed: Vec<String> =
+                        app.pending_keys.iter().map(KeyChord::to_string).collect();
+                    app.status_message = format!("{} - waiting for the next key", typed.join(" "));
+                }
+                // A key that breaks off a binding counts on its own
+                Lookup::None => {
+                    let broken_off = app.pending_keys.len() > 1;
+                    app.pending_keys.clear();
+                    if broken_off {
+                        app.jump_count = count;
+                        return handle_key(app, key);
+                    }
+                }
+            }
+        }
+        InputMode::Filter => match key.code {
+            KeyCode::Enter => app.input_mode = InputMode::Normal,
+            KeyCode::Esc => {
+                app.input_mode = InputMode::Normal;
+                app.clear_filter();
+            }
+            _ => {
+                if app.filter_text.handle_key(&key) {
+                    app.apply_filter();
+                }
+            }
+        },
+        InputMode::Query => {
+            match key.code {
+                KeyCode::Enter => {
+                    if matches!(app.view, View::Query(_)) {
+                        app.execute_query();
+                    }
+                }
+                KeyCode::Esc => {
+                    app.query_history.reset_cursor();
+                    app.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app.open_history_picker();
+                }
+                KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app.editor_request = Some(EditorTarget::Query);
+                }
+                // Cycle through earlier queries of the current mode
+                KeyCode::Up => app.step_query_history(true),
+                KeyCode::Down => app.step_query_history(false),
+                _ => {
+                    if app.query_text.handle_key(&key) {
+                        app.query_history.reset_cursor();
+                    }
+                }
+            }
+        }
+        InputMode::History => match key.code {
+            KeyCode::Enter => app.pick_history(),
+            KeyCode::Esc => app.close_history_picker(),
+            KeyCode::Down => app.move_in_history_picker(true),
+            KeyCode::Up => app.move_in_history_picker(false),
+            _ => app.edit_history_filter(&key),
+        },
+        InputMode::Bank => match key.code {
+            KeyCode::Enter => app.submit_bank_dialog(),
+            KeyCode::Esc => app.close_bank_dialog(),
+            _ => app.edit_bank_dialog(&key),
+        },
+        InputMode::Memory => match key.code {
+            KeyCode::Enter if key.modifiers.is_empty() => app.submit_memory_draft(),
+            KeyCode::Esc => app.close_memory_draft(),
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.editor_request = Some(EditorTarget::Memory);
+            }
+            _ => app.edit_memory_draft(&key),
+        },
+        InputMode::Export => match key.code {
+            KeyCode::Enter => app.submit_export_prompt(),
+            KeyCode::Esc => app.close_export_prompt(),
+            _ => app.edit_export_prompt(&key),
+        },
+    }
+    false
 }
 
-fn render_memories(f: &mut Frame, app: &mut App, area: Rect) {
-    // If viewing a memory, show its details
-    if let Some(memory) = &app.viewing_memory {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(7),  // Memory metadata
-                Constraint::Min(0),     // Full text content
-            ])
-            .split(area);
-
-        // Metadata section
-        let mem_type = memory.get("fact_type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let mentioned_at = memory.get("mentioned_at")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let occurred_start = memory.get("occurred_start")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let occurred_end = memory.get("occurred_end")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-
-        let metadata_text = format!(
-            "Type: {}\nMentioned At: {}\nOccurred: {} to {}",
-            mem_type, mentioned_at, occurred_start, occurred_end
-        );
+/// Run what a key bound in the views does; returns true when the explorer should quit
+fn run_action(app: &mut App, action: Action, count: Option<usize>) -> bool {
+    let in_query = matches!(app.view, View::Query(_));
+    let reflecting = in_query && app.query_mode == QueryMode::Reflect;
+    let recalling = in_query && app.query_mode == QueryMode::Recall;
+    let tracing = recalling && app.trace_enabled && !app.trace_collapsed;
+
+    match action {
+        Action::Quit => return true,
+        Action::Help => app.show_help = !app.show_help,
+
+        // Profile: scroll the mission
+        Action::Down
+        | Action::Up
+        | Action::PageDown
+        | Action::PageUp
+        | Action::First
+        | Action::Last
+            if matches!(app.view, View::Profile(_)) =>
+        {
+            let page = app.profile_height.saturating_sub(1).max(1) as i32;
+            match action {
+                Action::Down => app.move_in_profile(1),
+                Action::Up => app.move_in_profile(-1),
+                Action::PageDown => app.move_in_profile(page),
+                Action::PageUp => app.move_in_profile(-page),
+                Action::First => app.profile_scroll = 0,
+                _ => app.move_in_profile(i32::MAX),
+            }
+        }
+        Action::EditProfile => {
+            if let View::Profile(bank_id) = &app.view {
+                let bank_id = bank_id.clone();
+                app.open_bank_dialog(BankAction::EditProfile(bank_id));
+            }
+        }
 
-        let metadata = Paragraph::new(metadata_text)
-            .block(Block::default().borders(Borders::ALL).title("Memory Metadata"))
-            .style(Style::default().fg(BRAND_START));
+        // Reflect response: scroll the text, switch to its sources
+        Action::Sources
+        | Action::PageDown
+        | Action::PageUp
+        | Action::First
+        | Action::Last
+            if reflecting =>
+        {
+            let page = app.reflect_height.saturating_sub(1).max(1) as i32;
+            match action {
+                Action::Sources => app.toggle_reflect_focus(),
+                Action::PageDown => app.move_in_reflect(page),
+                Action::PageUp => app.move_in_reflect(-page),
+                Action::First => app.move_in_reflect(i32::MIN),
+                _ => app.move_in_reflect(i32::MAX),
+            }
+        }
 
-        f.render_widget(metadata, chunks[0]);
+        // Recall trace pane; paging scrolls it while it is open
+        Action::Trace if recalling => app.toggle_trace(),
+        Action::CollapseTrace if recalling => app.toggle_trace_collapsed(),
+        Action::RawTrace if recalling => app.toggle_trace_raw(),
+        Action::PageDown | Action::PageUp if tracing => {
+            let page = app.trace_height.saturating_sub(1).max(1) as i32;
+            app.scroll_trace(if action == Action::PageDown { page } else { -page });
+        }
 
-        // Full text content
-        let text = memory.get("text").and_then(|v| v.as_str()).unwrap_or("No text available");
+        // Navigation
+        Action::Down => app.next_item(),
+        Action::Up => app.previous_item(),
+        Action::Left => app.scroll_left(),
+        Action::Right => app.scroll_right(),
+        Action::Open => {
+            app.reset_horizontal_scroll();
+            app.enter_view();
+        }
+        Action::Back => {
+            app.reset_horizontal_scroll();
+            // An applied filter is cleared before leaving the list
+            if app.filter_text.is_empty() || !app.filterable() {
+                app.go_back();
+            } else {
+                app.clear_filter();
+            }
+        }
 
-        let content_widget = Paragraph::new(text)
-            .block(Block::default().borders(Borders::ALL).title("Full Text (Esc to close)"))
-            .wrap(Wrap { trim: false })
-            .style(Style::default().fg(Color::White));
+        // Page through the loaded rows; a count picks a row, as in 42G
+        Action::PageDown => app.navigate(ListNav::Down(app.list_page_len())),
+        Action::PageUp => app.navigate(ListNav::Up(app.list_page_len())),
+        Action::HalfPageDown => app.navigate(ListNav::Down((app.list_page_len() / 2).max(1))),
+        Action::HalfPageUp => app.navigate(ListNav::Up((app.list_page_len() / 2).max(1))),
+        Action::First => {
+            app.navigate(count.map_or(ListNav::First, |n| ListNav::To(n.saturating_sub(1))))
+        }
+        Action::Last => {
+            app.navigate(count.map_or(ListNav::Last, |n| ListNav::To(n.saturating_sub(1))))
+        }
 
-        f.render_widget(content_widget, chunks[1]);
-    } else {
-        // Show memory list as table
-        let mut items = vec![
-            // Header row
-            ListItem::new(format!("{:<10} {:<18} {:<18} {}", "TYPE", "MENTIONED AT", "OCCURRED AT", "TEXT"))
-                .style(Style::default().fg(BRAND_START).add_modifier(Modifier::BOLD))
-        ];
+        // Pagination for memories, entities, documents and operations
+        Action::NextPage => {
+            app.turn_page(true, false);
+        }
+        Action::PrevPage => {
+            app.turn_page(false, false);
+        }
 
-        // Data rows
-        for memory in &app.memories {
-            let mem_type = memory.get("fact_type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown");
-            let mentioned = memory.get("mentioned_at")
-                .and_then(|v| v.as_str())
-                .and_then(|s| s.split('T').next())
-                .unwrap_or("-");
-            let occurred = memory.get("occurred_start")
-                .and_then(|v| v.as_str())
-                .and_then(|s| s.split('T').next())
-                .unwrap_or("-");
-            let text = memory.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        // Filter the loaded rows of the current list
+        Action::Filter => {
+            if app.filterable() {
+                app.input_mode = InputMode::Filter;
+            }
+        }
+
+        Action::Refresh => app.force_refresh(),
+
+        // Write the rows of the current list to a file
+        Action::Export => app.open_export_prompt(),
+
+        // Query input - start query from any non-bank view
+        Action::Query => app.start_query(None),
+        Action::Recall => app.start_query(Some(QueryMode::Recall)),
+        Action::Reflect => app.start_query(Some(QueryMode::Reflect)),
+
+        Action::Inspect => app.open_json_inspector(),
+        Action::Copy => app.copy_selected(),
+        Action::CopyAll => app.copy_list(),
+        Action::Theme => app.cycle_theme(),
+
+        // Tabs, each with its own views
+        Action::NewTab => app.tab_request = Some(TabRequest::Open),
+        Action::NextTab => app.tab_request = Some(TabRequest::Next),
+        Action::PrevTab => app.tab_request = Some(TabRequest::Previous),
+        Action::CloseTab => app.tab_request = Some(TabRequest::Close { confirmed: false }),
+
+        // Background operations, profile and statistics of the bank
+        Action::Operations => app.open_operations(),
+        Action::Profile => app.open_profile(),
+        Action::Stats => app.open_stats(),
+        Action::Memories => app.open_bank_list(View::Memories),
+        Action::Entities => app.open_bank_list(View::Entities),
+        Action::Documents => app.open_bank_list(View::Documents),
+        Action::Options => app.open_options(),
+
+        // Bank management
+        Action::NewBank => app.open_bank_dialog(BankAction::Create),
+        Action::RenameBank | Action::DeleteBank => {
+            if let Some(bank_id) = app.selected_bank() {
+                app.open_bank_dialog(if action == Action::RenameBank {
+                    BankAction::Rename(bank_id)
+                } else {
+                    BankAction::Delete(bank_id)
+                });
+            }
+        }
 
-            // Apply horizontal scroll
-            let scrolled_text: String = text.chars().skip(app.horizontal_scroll).take(80).collect();
+        // Type a new memory into the bank
+        Action::AddMemory => app.open_memory_draft(MemoryDraft::default()),
+        Action::Follow => app.toggle_follow(),
+        Action::Sort => app.cycle_sort(),
+
+        // Delete the selected or marked memories and documents
+        Action::Mark => app.toggle_mark(),
+        Action::Delete => app.request_delete(),
+
+        Action::RetryOperation => app.request_operation_action(OperationAction::Retry),
+        Action::CancelOperation => app.request_operation_action(OperationAction::Cancel),
+
+        // Query view controls
+        Action::Mode => app.toggle_query_mode(),
+        Action::Budget => app.cycle_budget(),
+        Action::Types => app.cycle_fact_types(),
+        Action::MoreTokens => app.adjust_max_tokens(true),
+        Action::FewerTokens => app.adjust_max_tokens(false),
+        Action::Chunk if recalling => app.toggle_recall_chunk(),
+        // Keys of the other query mode
+        Action::Chunk
+        | Action::Trace
+        | Action::CollapseTrace
+        | Action::RawTrace
+        | Action::Sources => {}
+    }
+    false
+}
 
-            let content = format!("{:<10} {:<18} {:<18} {}", mem_type, mentioned, occurred, scrolled_text);
-            items.push(ListItem::new(content).style(Style::default().fg(Color::White)));
+/// Apply a mouse event; returns true when the explorer should quit
+fn handle_mouse(app: &mut App, mouse: MouseEvent) -> bool {
+    if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+        if let Some(key) = app.mouse.shortcut_at(mouse.column, mouse.row) {
+            return handle_key(app, key);
         }
+    }
+    // Rows and panes only take the mouse when no prompt or popup is open
+    let modal = app.show_help || app.modal_open() || app.error_report.is_some();
+    if app.input_mode != InputMode::Normal || modal {
+        return false;
+    }
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => app.click_row(mouse.column, mouse.row),
+        MouseEventKind::ScrollDown => app.scroll_wheel(mouse.column, mouse.row, true),
+        MouseEventKind::ScrollUp => app.scroll_wheel(mouse.column, mouse.row, false),
+        _ => {}
+    }
+    app.pause_follow_if_moved();
+    false
+}
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title(format!("Memories ({}) - Press Enter to view full text", app.memories.len())))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">> ");
+/// How `hindsight explore` was started
+#[derive(Debug, Clone)]
+pub struct ExploreOptions {
+    pub theme: ThemeName,
+    /// Capture the mouse; off leaves the terminal's own text selection working
+    pub mouse: bool,
+    /// Format the export prompt starts in
+    pub export_format: ExportFormat,
+    /// Start at the Banks list instead of where the last session left off
+    pub fresh: bool,
+    /// Key bindings, with the `[explorer.keys]` overrides of the config file
+    pub keys: Keymap,
+    /// Seconds between auto-refreshes of the current list; 0 turns auto-refresh off
+    pub refresh_secs: u64,
+    /// Rows per page of the Memories, Entities and Documents views, until changed with `O`
+    pub page_size: i64,
+    /// Load those lists with the API client's verbose flag
+    pub verbose_loads: bool,
+    /// Ask recalls for the source chunks of their results
+    pub recall_chunks: bool,
+    /// Max tokens of a reflect answer, until changed with `O`
+    pub reflect_max_tokens: i64,
+}
+
+pub fn run(client: &ApiClient, options: ExploreOptions) -> Result<()> {
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    if options.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create app and run it
+    let mut app = App::new(client.clone().with_cache(EXPLORER_CACHE));
+    app.theme = Theme::named(options.theme);
+    app.export_format = options.export_format;
+    app.status_message =
+        format!("Select a bank to start. Press {} for help", options.keys.label(Action::Help));
+    app.keys = options.keys;
+    app.mouse_capture = options.mouse;
+    app.auto_refresh_enabled = options.refresh_secs > 0;
+    // Follow mode still polls at the default interval when auto-refresh is off
+    if options.refresh_secs > 0 {
+        app.refresh_interval = Duration::from_secs(options.refresh_secs);
+    }
+    let load = LoadOptions {
+        page_size: clamp_page_size(options.page_size),
+        verbose: options.verbose_loads,
+    };
+    app.memories_load = load;
+    app.entities_load = load;
+    app.documents_load = load;
+    app.include_recall_chunks = options.recall_chunks;
+    app.reflect_max_tokens = options.reflect_max_tokens;
+    app.load_query_history();
+    if !options.fresh {
+        app.load_session();
+    }
+    let mut tabs = Tabs::new(app);
+    let res = run_app(&mut terminal, &mut tabs);
+    let app = tabs.active();
 
-        f.render_stateful_widget(list, area, &mut app.memories_state);
+    // Restore terminal
+    disable_raw_mode()?;
+    if options.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    if let Err(err) = app.save_query_history() {
+        crate::ui::print_warning(&format!("{:#}", err));
+    }
+    if let Err(err) = app.save_session() {
+        crate::ui::print_warning(&format!("{:#}", err));
+    }
+    if let Err(err) = res {
+        println!("Error: {:?}", err);
     }
+
+    Ok(())
 }
 
-fn render_entities(f: &mut Frame, app: &mut App, area: Rect) {
-    // If viewing an entity, show its details
-    if let Some(entity) = &app.viewing_entity {
-        let entity_type = entity.metadata.as_ref()
-            .and_then(|m| m.get("type"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let metadata_text = format!(
-            "Name: {}\nType: {}\nMentions: {}\nFirst Seen: {}\nLast Seen: {}",
-            entity.canonical_name,
-            entity_type,
-            entity.mention_count,
-            entity.first_seen.as_deref().unwrap_or("unknown"),
-            entity.last_seen.as_deref().unwrap_or("unknown")
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_filter_matches_substrings_and_fuzzy_words() {
+        let fields = vec!["Alice met Bob in Berlin".to_string(), "mem-42".to_string()];
+        assert!(ListFilter::new("").matches(&fields));
+        assert!(ListFilter::new("BERLIN").matches(&fields));
+        assert!(ListFilter::new("alce brln").matches(&fields));
+        assert!(ListFilter::new("mem-4").matches(&fields));
+        assert!(!ListFilter::new("alice paris").matches(&fields));
+        // Fuzzy matches stay within one word
+        assert!(!ListFilter::new("ab").matches(&["a b".to_string()]));
+    }
 
-        let metadata = Paragraph::new(metadata_text)
-            .block(Block::default().borders(Borders::ALL).title("Entity Details (Esc to close)"))
-            .style(Style::default().fg(BRAND_START))
-            .wrap(Wrap { trim: false });
+    #[test]
+    fn test_stale_and_cancelled_responses_are_dropped() {
+        let client = ApiClient::new("http://localhost:0".to_string(), None).unwrap();
+        let mut app = App::new(client);
+        let expect = |app: &mut App, foreground: bool| {
+            app.generation += 1;
+            let generation = app.generation;
+            app.in_flight.insert(
+                RequestSlot::Query,
+                InFlight {
+                    generation,
+                    label: "Reflecting",
+                    foreground,
+                },
+            );
+            generation
+        };
+        let send = |app: &App, generation: u64, text: &str| {
+            let reflected = serde_json::from_value(serde_json::json!({ "text": text })).unwrap();
+            let response = Response::Reflect(Ok(reflected));
+            let delivery = (RequestSlot::Query, generation, Duration::ZERO, response);
+            app.response_sender.send(delivery).unwrap();
+        };
 
-        f.render_widget(metadata, area);
-    } else {
-        // Show entity list as table
-        let mut items = vec![
-            // Header row
-            ListItem::new(format!("{:<40} {:<15} {:<10}", "NAME", "TYPE", "MENTIONS"))
-                .style(Style::default().fg(BRAND_START).add_modifier(Modifier::BOLD))
-        ];
+        let superseded = expect(&mut app, true);
+        let current = expect(&mut app, true);
+        send(&app, current, "current");
+        send(&app, superseded, "superseded");
+        app.poll_responses();
+        assert_eq!(app.query_response, "current");
+        assert!(!app.is_loading(RequestSlot::Query));
+
+        let cancelled = expect(&mut app, true);
+        assert!(app.cancel_requests());
+        send(&app, cancelled, "cancelled");
+        app.poll_responses();
+        assert_eq!(app.query_response, "current");
+
+        // Quiet requests such as auto-refreshes are not cancelled by Esc
+        expect(&mut app, false);
+        assert!(!app.cancel_requests());
+        assert!(app.is_loading(RequestSlot::Query));
+    }
 
-        // Data rows
-        for entity in &app.entities {
-            let name = &entity.canonical_name;
-            // Apply horizontal scroll to name
-            let scrolled_name: String = name.chars().skip(app.horizontal_scroll).take(40).collect();
-            let entity_type = entity.metadata.as_ref()
-                .and_then(|m| m.get("type"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown");
-            let mentions = entity.mention_count;
+    #[test]
+    fn test_failed_loads_open_the_error_popup_with_retry() {
+        let mut app = banks_app(Theme::DARK);
+        let failure = |view: View| Response::List {
+            request: ListRequest {
+                view,
+                paging: Paging::new(),
+                landing: Landing::Keep,
+            },
+            result: Err(anyhow::anyhow!("500 Internal Server Error\nbank index is rebuilding")),
+        };
 
-            let content = format!("{:<40} {:<15} {:<10}", scrolled_name, entity_type, mentions);
-            items.push(ListItem::new(content).style(Style::default().fg(Color::White)));
-        }
+        // Auto-refresh failures stay in the footer
+        app.handle_response(failure(View::Memories("team".to_string())), false);
+        assert!(app.error_report.is_none());
+        assert!(app.error_message.contains("500 Internal Server Error"));
+
+        app.handle_response(failure(View::Memories("team".to_string())), true);
+        let report = app.error_report.as_ref().expect("error popup open");
+        assert_eq!(report.endpoint, "GET /v1/default/banks/team/memories/list");
+        assert_eq!(report.bank_id.as_deref(), Some("team"));
+        assert!(report.message.ends_with("bank index is rebuilding"));
+
+        // Keys go to the popup: Esc dismisses, r loads again
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert_eq!(app.banks_state.selected(), Some(0));
+        handle_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.error_report.is_none());
+
+        app.handle_response(failure(View::Banks), true);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        assert!(app.error_report.is_none());
+        assert!(app.is_loading(RequestSlot::List));
+    }
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title(format!("Entities ({}) - Press Enter to view details", app.entities.len())))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">> ");
+    fn press_esc(app: &mut App) {
+        handle_key(app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    }
 
-        f.render_stateful_widget(list, area, &mut app.entities_state);
+    #[test]
+    fn test_jumps_between_related_items_unwind_with_esc() {
+        let mut app = banks_app(Theme::DARK);
+        let press = |app: &mut App, c: char| {
+            handle_key(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        };
+        let memory = |value: Value| serde_json::from_value(value).ok();
+        app.view_history = vec![View::Banks];
+        app.view = View::Memories("alpha".to_string());
+        app.related = Some(Related::DocumentMemories("notes".to_string()));
+
+        app.viewing_memory = memory(serde_json::json!({"id": "m1", "text": "no links"}));
+        press(&mut app, 'd');
+        assert_eq!(app.status_message, "This memory has no source document");
+        press(&mut app, 'e');
+        assert_eq!(app.view, View::Memories("alpha".to_string()));
+        assert_eq!(app.status_message, "This memory mentions no entities");
+
+        app.viewing_memory = memory(serde_json::json!({
+            "id": "m2",
+            "text": "Alice joined Google",
+            "entities": "Alice (PERSON), Google (ORGANIZATION)",
+            "chunk_id": "alpha_notes_3"
+        }));
+        press(&mut app, 'e');
+        assert_eq!(app.view, View::Entities("alpha".to_string()));
+        assert!(app.viewing_memory.is_none());
+        let names = vec!["Alice".to_string(), "Google".to_string()];
+        let mentioned = Related::MemoryEntities {
+            memory_id: "m2".to_string(),
+            names,
+        };
+        assert_eq!(app.related, Some(mentioned));
+
+        // Esc returns to the memories of the document, narrowed as before
+        press_esc(&mut app);
+        assert_eq!(app.view, View::Memories("alpha".to_string()));
+        assert_eq!(app.related, Some(Related::DocumentMemories("notes".to_string())));
+        press_esc(&mut app);
+        assert_eq!(app.view, View::Banks);
+        assert_eq!(app.related, None);
     }
-}
 
-fn render_documents(f: &mut Frame, app: &mut App, area: Rect) {
-    // If viewing a document, show its content
-    if let Some(doc) = &app.viewing_document {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(5),  // Document metadata
-                Constraint::Min(0),     // Content
-            ])
-            .split(area);
-
-        // Metadata section
-        let doc_id = doc.get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let content_type = doc.get("content_type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        let created_at = doc.get("created_at")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-
-        let metadata_text = format!(
-            "ID: {}\nType: {}\nCreated: {}\n",
-            doc_id, content_type, created_at
-        );
+    #[test]
+    fn test_view_keys_switch_between_lists_of_the_same_bank() {
+        let mut app = banks_app(Theme::DARK);
+        let press = |app: &mut App, c: char| {
+            handle_key(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        };
+        app.view_history = vec![View::Banks];
+        app.view = View::Memories("alpha".to_string());
+
+        press(&mut app, 'd');
+        assert_eq!(app.view, View::Documents("alpha".to_string()));
+        press(&mut app, 'e');
+        assert_eq!(app.view, View::Entities("alpha".to_string()));
+        // The view already shown is not added to the history again
+        press(&mut app, 'e');
+        assert_eq!(app.view_history.len(), 3);
+
+        press_esc(&mut app);
+        assert_eq!(app.view, View::Documents("alpha".to_string()));
+        press_esc(&mut app);
+        assert_eq!(app.view, View::Memories("alpha".to_string()));
+    }
 
-        let metadata = Paragraph::new(metadata_text)
-            .block(Block::default().borders(Borders::ALL).title("Document Metadata"))
-            .style(Style::default().fg(BRAND_START));
+    #[test]
+    fn test_follow_mode_flashes_new_memories_and_pauses_when_moving() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Memories("alpha".to_string());
+        let load = |app: &mut App, ids: &[&str]| {
+            let items = ids.iter().map(|id| {
+                let created_at = format!("2024-05-0{}T10:00:00Z", id.as_bytes()[1] - b'0');
+                let memory = serde_json::json!({"id": id, "text": id, "created_at": created_at});
+                serde_json::from_value(memory).unwrap()
+            });
+            let request = ListRequest {
+                view: View::Memories("alpha".to_string()),
+                paging: Paging::new(),
+                landing: Landing::Keep,
+            };
+            let page = ListPage::Memories(ListMemoryUnitsResponse {
+                items: items.collect(),
+                total: ids.len() as i64,
+                limit: request.paging.limit,
+                offset: 0,
+            });
+            app.apply_list_page(request, page);
+        };
+        let shown = |app: &App| -> Vec<String> {
+            app.memories.iter().map(|memory| memory.id.clone()).collect()
+        };
+        let arrived = |app: &App| {
+            let follow = app.follow.as_ref().unwrap();
+            let mut ids: Vec<String> = follow.arrived.iter().cloned().collect();
+            ids.sort();
+            ids
+        };
 
-        f.render_widget(metadata, chunks[0]);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT));
+        assert!(app.following());
+        load(&mut app, &["m1", "m2"]);
+        assert_eq!(shown(&app), vec!["m2", "m1"]);
+        assert!(arrived(&app).is_empty());
+
+        // A new memory is flashed and selected; reordering the rest flashes nothing
+        load(&mut app, &["m1", "m3", "m2"]);
+        assert_eq!(shown(&app), vec!["m3", "m2", "m1"]);
+        assert_eq!(arrived(&app), vec!["m3"]);
+        assert_eq!(app.memories_state.selected(), Some(0));
+        load(&mut app, &["m2", "m3", "m1"]);
+        assert!(arrived(&app).is_empty());
+
+        // Moving down pauses: new memories no longer pull the selection to the top
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert!(app.follow.as_ref().unwrap().paused);
+        assert!(!app.following());
+        load(&mut app, &["m1", "m2", "m3", "m4"]);
+        assert_eq!(arrived(&app), vec!["m4"]);
+        assert_eq!(app.memories_state.selected(), Some(2));
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT));
+        assert!(app.following());
+        assert_eq!(app.memories_state.selected(), Some(0));
+    }
 
-        // Content section
-        let content = doc.get("content")
-            .and_then(|v| v.as_str())
-            .unwrap_or("No content available");
+    #[test]
+    fn test_query_history_cycles_dedupes_and_persists() {
+        let mut history = QueryHistory::default();
+        history.push(QueryMode::Recall, "alice");
+        history.push(QueryMode::Reflect, "why alice");
+        history.push(QueryMode::Recall, "bob");
+        history.push(QueryMode::Recall, " alice ");
+        let queries: Vec<&str> = history.entries.iter().map(|e| e.query.as_str()).collect();
+        assert_eq!(queries, vec!["why alice", "bob", "alice"]);
+
+        // Up/Down only visit the current mode and restore the draft at the end
+        assert_eq!(history.older(&QueryMode::Recall, "dra").as_deref(), Some("alice"));
+        assert_eq!(history.older(&QueryMode::Recall, "alice").as_deref(), Some("bob"));
+        assert_eq!(history.older(&QueryMode::Recall, "bob"), None);
+        assert_eq!(history.newer(&QueryMode::Recall).as_deref(), Some("alice"));
+        assert_eq!(history.newer(&QueryMode::Recall).as_deref(), Some("dra"));
+        assert_eq!(history.newer(&QueryMode::Recall), None);
+
+        for i in 0..QUERY_HISTORY_LIMIT {
+            history.push(QueryMode::Recall, &format!("query {}", i));
+        }
+        assert_eq!(history.entries.len(), QUERY_HISTORY_LIMIT);
+        assert_eq!(history.entries[0].query, "query 0");
+
+        let path = std::env::temp_dir()
+            .join(format!("hindsight-explorer-history-{}", std::process::id()))
+            .join(QUERY_HISTORY_FILE_NAME);
+        history.save(&path).unwrap();
+        assert_eq!(QueryHistory::load(&path).unwrap().entries, history.entries);
+        fs::write(&path, "{not json").unwrap();
+        assert!(QueryHistory::load(&path).is_err());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
 
-        let content_widget = Paragraph::new(content)
-            .block(Block::default().borders(Borders::ALL).title("Content (Esc to close)"))
-            .wrap(Wrap { trim: false })
-            .style(Style::default().fg(Color::White));
+    fn press(input: &mut TextInput, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        input.handle_key(&KeyEvent::new(code, modifiers))
+    }
 
-        f.render_widget(content_widget, chunks[1]);
-    } else {
-        // Show document list as table
-        let mut items = vec![
-            // Header row
-            ListItem::new(format!("{:<40} {:<20} {}", "ID", "TYPE", "CREATED"))
-                .style(Style::default().fg(BRAND_START).add_modifier(Modifier::BOLD))
-        ];
+    #[test]
+    fn test_text_input_edits_multibyte_text_at_the_cursor() {
+        let mut input = TextInput::default();
+        for c in "héllo wörld".chars() {
+            press(&mut input, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        assert_eq!(input.as_str(), "héllo wörld");
 
-        // Data rows
-        for doc in &app.documents {
-            let id = doc.get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown");
-            // Apply horizontal scroll to id
-            let scrolled_id: String = id.chars().skip(app.horizontal_scroll).take(40).collect();
-            let content_type = doc.get("content_type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown");
-            let created = doc.get("created_at")
-                .and_then(|v| v.as_str())
-                .and_then(|s| s.split('T').next())
-                .unwrap_or("unknown");
+        // Move onto the "ö" and replace it
+        for _ in 0..3 {
+            press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        }
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Delete, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Char('日'), KeyModifiers::SHIFT);
+        assert_eq!(input.as_str(), "héllo w日ld");
+        assert_eq!(input.cursor_column(), 9);
+
+        press(&mut input, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut input, KeyCode::Right, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Right, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Char('ß'), KeyModifiers::NONE);
+        assert_eq!(input.as_str(), "héßllo w日ld");
+
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(input.as_str(), "héßllo w日ld");
+        press(&mut input, KeyCode::End, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Delete, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(input.cursor, input.as_str().len());
+
+        // Ctrl+W removes the previous word and the spaces after it
+        input.set("ask über  ");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.as_str(), "ask ");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.as_str(), "");
+
+        input.set("ünïcode");
+        press(&mut input, KeyCode::Char('e'), KeyModifiers::CONTROL);
+        press(&mut input, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert!(input.is_empty());
+        assert!(!press(&mut input, KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert!(!press(&mut input, KeyCode::Enter, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_query_settings_are_kept_per_bank() {
+        let client = ApiClient::new("http://localhost:0".to_string(), None).unwrap();
+        let mut app = App::new(client);
+        app.selected_bank_id = Some("a".to_string());
+        app.cycle_budget();
+        app.adjust_max_tokens(true);
+        app.adjust_max_tokens(true);
+        assert!(matches!(app.query_settings().budget, Budget::High));
+        assert_eq!(app.query_settings().max_tokens, 16384);
+
+        app.selected_bank_id = Some("b".to_string());
+        assert!(matches!(app.query_settings().budget, Budget::Mid));
+        assert_eq!(app.query_settings().max_tokens, 4096);
+
+        app.selected_bank_id = Some("a".to_string());
+        app.adjust_max_tokens(true);
+        app.adjust_max_tokens(true);
+        assert_eq!(app.query_settings().max_tokens, 32768);
+        assert!(app.status_message.contains("maximum"));
+
+        let mut settings = QuerySettings {
+            budget: Budget::Low,
+            max_tokens: 3000,
+            types: Vec::new(),
+        };
+        settings.step_max_tokens(false);
+        assert_eq!(settings.max_tokens, 2048);
+        app.query_mode = QueryMode::Reflect;
+        app.adjust_max_tokens(false);
+        assert_eq!(app.query_settings().max_tokens, 32768);
+    }
 
-            let content = format!("{:<40} {:<20} {}", scrolled_id, content_type, created);
-            items.push(ListItem::new(content).style(Style::default().fg(Color::White)));
+    #[test]
+    fn test_fact_type_filter_cycles_and_shows_in_the_title() {
+        let client = ApiClient::new("http://localhost:0".to_string(), None).unwrap();
+        let mut app = App::new(client);
+        app.selected_bank_id = Some("a".to_string());
+        let title = |app: &App| app.query_settings().describe(&QueryMode::Recall);
+        assert!(title(&app).starts_with("Types: default · "));
+
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            app.cycle_fact_types();
+            seen.push(app.query_settings().describe_types());
         }
+        assert_eq!(
+            seen,
+            ["world", "experience", "observation", "all", "default"]
+        );
+        app.cycle_fact_types();
+        assert!(title(&app).starts_with("Types: world · "));
+        assert_eq!(app.status_message, "Fact types set to world for this bank");
+
+        app.query_mode = QueryMode::Reflect;
+        app.cycle_fact_types();
+        assert_eq!(app.query_settings().types, [FactTypesItem::World]);
+
+        // Sessions saved before the filter existed still load
+        let saved: QuerySettings =
+            serde_json::from_str(r#"{"budget": "high", "max_tokens": 1024}"#).unwrap();
+        assert!(saved.types.is_empty());
+    }
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title(format!("Documents ({}) - Press Enter to view content", app.documents.len())))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">> ");
+    #[test]
+    fn test_trace_pane_lists_stages_and_unknown_fields() {
+        let trace = serde_json::json!({
+            "retrieval_results": [{"method_name": "semantic", "results": [{}, {}]}],
+            "summary": {
+                "total_duration_seconds": 0.5,
+                "results_returned": 1,
+                "phase_metrics": [{"phase_name": "rerank", "duration_seconds": 0.25}]
+            },
+            "experimental": true
+        });
+        let report = TraceReport::from_trace(trace.as_object().unwrap());
+        assert_eq!(trace_summary(&report), "total 500.0ms · 1 stage · 1 returned");
+
+        let text: Vec<String> = trace_lines(&report, &Theme::DARK)
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert!(text.contains(&"─── Stages ───".to_string()));
+        assert!(text.iter().any(|line| line.starts_with("  rerank") && line.contains("250.0ms")));
+        assert!(text.contains(&"  semantic       2".to_string()));
+        assert_eq!(text.last().unwrap(), "Not shown: experimental (r for raw JSON)");
+    }
 
-        f.render_stateful_widget(list, area, &mut app.documents_state);
+    #[test]
+    fn test_deleting_memories_confirms_their_documents() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Memories("alpha".to_string());
+        app.memories_loaded = [("m1", Some("notes")), ("m2", Some("notes")), ("m3", None)]
+            .iter()
+            .map(|(id, doc)| {
+                let memory = serde_json::json!({"id": id, "text": "fact", "document_id": doc});
+                serde_json::from_value(memory).unwrap()
+            })
+            .collect();
+        app.apply_filter();
+
+        // A memory without a document can't go, and nothing is looked up
+        app.memories_state.select(Some(2));
+        app.request_delete();
+        assert!(app.error_message.contains("Nothing deleted"));
+        assert!(!app.is_loading(RequestSlot::DeleteLookup));
+        assert!(app.pending_delete.is_none());
+
+        let document = serde_json::json!({
+            "id": "notes",
+            "bank_id": "alpha",
+            "original_text": "",
+            "content_hash": null,
+            "created_at": "2024-01-15T10:30:00Z",
+            "updated_at": "2024-01-15T10:30:00Z",
+            "memory_unit_count": 5,
+        });
+        let documents = vec![(
+            "notes".to_string(),
+            2,
+            serde_json::from_value(document).map_err(anyhow::Error::from),
+        )];
+        let lookup = Response::DeleteLookup {
+            bank_id: "alpha".to_string(),
+            documents,
+            without_document: 1,
+        };
+        app.handle_response(lookup, true);
+        let pending = app.pending_delete.take().unwrap();
+        assert_eq!(
+            pending.items,
+            [(
+                "notes".to_string(),
+                "5 memories, 2 of them picked".to_string()
+            )]
+        );
+        assert_eq!((pending.memories, pending.without_document), (2, 1));
+
+        app.handle_response(
+            Response::Deleted {
+                pending,
+                results: vec![Ok(true)],
+            },
+            false,
+        );
+        let ids: Vec<&str> = app.memories.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["m3"]);
     }
-}
 
-fn render_query(f: &mut Frame, app: &mut App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Query input
-            Constraint::Min(0),     // Results or Response
-        ])
-        .split(area);
+    #[test]
+    fn test_deleted_documents_leave_the_list_and_failures_can_be_retried() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Documents("alpha".to_string());
+        app.documents_loaded = ["notes", "report", "draft"]
+            .iter()
+            .map(|id| serde_json::from_value(serde_json::json!({"id": id})).unwrap())
+            .collect();
+        app.apply_filter();
+        app.documents_state.select(Some(0));
+        app.toggle_mark();
+        app.toggle_mark();
+        app.request_delete();
+        let pending = app.pending_delete.take().unwrap();
+        assert_eq!(pending.items.len(), 2);
+
+        let results = vec![Ok(true), Err(anyhow::anyhow!("server error"))];
+        app.handle_response(Response::Deleted { pending, results }, false);
+        let ids: Vec<&str> = app.documents.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["report", "draft"]);
+        assert_eq!(app.status_message, "Deleted 1 documents");
+        assert_eq!(app.marked_documents.len(), 1);
+        let report = app.error_report.as_ref().unwrap();
+        match &report.retry {
+            Some(Retry::Delete(retry)) => assert_eq!(retry.items[0].0, "report"),
+            _ => panic!("expected a delete retry"),
+        }
+        assert!(RequestSlot::Delete.writes());
+    }
 
-    // Query input
-    let query_style = if app.input_mode == InputMode::Query {
-        Style::default().fg(BRAND_END)
-    } else {
-        Style::default()
-    };
+    #[test]
+    fn test_bank_dialog_validates_before_sending() {
+        let client = ApiClient::new("http://localhost:0".to_string(), None).unwrap();
+        let mut app = App::new(client);
+
+        app.open_bank_dialog(BankAction::Create);
+        assert_eq!(app.input_mode, InputMode::Bank);
+        app.edit_bank_dialog(&KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        app.edit_bank_dialog(&KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        app.submit_bank_dialog();
+        assert_eq!(app.error_message, "Bank ID cannot be empty");
+        let dialog = app.bank_dialog.as_ref().unwrap();
+        assert_eq!(dialog.focus, 1);
+        assert_eq!(dialog.fields[1].as_str(), "x");
+
+        app.open_bank_dialog(BankAction::Delete("alpha".to_string()));
+        for c in "alph".chars() {
+            app.edit_bank_dialog(&KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        app.submit_bank_dialog();
+        assert_eq!(app.error_message, "Type 'alpha' to delete this bank");
+        assert!(app.bank_dialog.is_some());
+
+        app.close_bank_dialog();
+        assert!(app.bank_dialog.is_none());
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        // The write runs in the background; a failure brings the popup back as typed
+        app.open_bank_dialog(BankAction::Rename("alpha".to_string()));
+        let dialog = app.bank_dialog.take().unwrap();
+        app.input_mode = InputMode::Normal;
+        let response = Response::Bank {
+            dialog,
+            verb: "rename",
+            done: "Renamed".to_string(),
+            result: Err(anyhow::anyhow!("server unavailable")),
+        };
+        app.handle_response(response, false);
+        assert!(app.error_message.ends_with("server unavailable"));
+        assert_eq!(app.input_mode, InputMode::Bank);
+        let reopened = app.bank_dialog.as_ref().unwrap();
+        assert_eq!(reopened.action, BankAction::Rename("alpha".into()));
+        assert!(RequestSlot::Bank.writes());
+    }
 
-    let mode_label = match app.query_mode {
-        QueryMode::Recall => "Recall",
-        QueryMode::Reflect => "Reflect",
-    };
-    let title = format!("{} Query (press / to edit, m to toggle mode)", mode_label);
+    #[test]
+    fn test_connection_status_tracks_latency_and_reachability() {
+        let mut status = ConnectionStatus::new("http://localhost:8888");
+        assert_eq!(status.describe(), "http://localhost:8888");
+
+        let refused =
+            anyhow::anyhow!("error sending request: tcp connect error: Connection refused");
+        status.record(Duration::from_millis(12), Some(&refused));
+        assert!(status.disconnected);
+        assert_eq!(status.describe(), "DISCONNECTED · http://localhost:8888 · 12ms");
+
+        // A server that answers with an error is reachable
+        status.server_version = Some("0.4.2".to_string());
+        let missing = anyhow::anyhow!("API error (404 Not Found): bank not found");
+        status.record(Duration::from_millis(35), Some(&missing));
+        assert!(!status.disconnected);
+        assert_eq!(status.describe(), "http://localhost:8888 · v0.4.2 · 35ms");
+
+        status.cache = Some(CacheStats {
+            hits: 3,
+            revalidated: 1,
+            misses: 2,
+        });
+        assert_eq!(status.describe(), "http://localhost:8888 · v0.4.2 · 35ms · cache 4/6");
 
-    let query = Paragraph::new(app.query_text.as_str())
-        .style(query_style)
-        .block(Block::default().borders(Borders::ALL).title(title));
+        let result: Result<()> = status.time(|| Err(refused));
+        assert!(result.is_err());
+        assert!(status.disconnected);
+    }
 
-    f.render_widget(query, chunks[0]);
+    /// The Banks view with two banks, the first selected, drawn in `theme`
+    fn banks_app(theme: Theme) -> App {
+        let client = ApiClient::new("http://localhost:0".to_string(), None).unwrap();
+        let mut app = App::new(client);
+        app.theme = theme;
+        app.banks_loaded = ["alpha", "beta"]
+            .iter()
+            .map(|id| {
+                serde_json::from_value(serde_json::json!({
+                    "bank_id": id,
+                    "name": id.to_uppercase(),
+                    "disposition": {"skepticism": 3, "literalism": 3, "empathy": 3}
+                }))
+                .unwrap()
+            })
+            .collect();
+        app.apply_filter();
+        app.banks_state.select(Some(0));
+        app
+    }
 
-    // Show loading indicator if loading
-    if app.loading {
-        let loading_text = match app.query_mode {
-            QueryMode::Recall => "Searching memories...",
-            QueryMode::Reflect => "Reflecting on memories...",
-        };
+    fn render_banks_view(theme: Theme) -> ratatui::buffer::Buffer {
+        let mut app = banks_app(theme);
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 16)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        terminal.backend().buffer().clone()
+    }
 
-        // Create animated dots based on time
-        let dots = ".".repeat(((std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() / 500) % 4) as usize);
+    fn buffer_rows(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
 
-        let loading_lines = vec![
-            Line::from(""),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("    ", Style::default()),
-                Span::styled(format!("{}{}", loading_text, dots), Style::default().fg(BRAND_MID).add_modifier(Modifier::BOLD)),
-            ]),
-            Line::from(""),
-            Line::from(Span::styled("    Please wait while we process your query...", Style::default().fg(Color::DarkGray))),
-        ];
+    #[test]
+    fn test_themes_render_the_same_text_in_their_own_styles() {
+        let dark = render_banks_view(Theme::DARK);
+        let mono = render_banks_view(Theme::MONOCHROME);
+        let rows = buffer_rows(&dark);
+        assert_eq!(rows, buffer_rows(&mono));
+        assert!(rows.iter().any(|row| row.contains("Hindsight Explorer - Banks")));
+
+        let selected_y = rows.iter().position(|row| row.contains(">> alpha - ALPHA")).unwrap();
+        let row = &rows[selected_y];
+        let x = row[..row.find(">> alpha").unwrap()].chars().count() as u16 + 3;
+        let y = selected_y as u16;
+        assert_eq!(dark[(x, y)].bg, Color::DarkGray);
+        assert_eq!(mono[(x, y)].bg, Color::Reset);
+        assert!(mono[(x, y)].modifier.contains(Modifier::REVERSED));
+
+        // Monochrome leaves every color to the terminal
+        assert!(mono.content.iter().all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+        assert!(dark.content.iter().any(|cell| cell.fg == BRAND_START));
+        assert_eq!(Theme::MONOCHROME.next(), Theme::DARK);
+    }
 
-        let loading_widget = Paragraph::new(loading_lines)
-            .block(Block::default().borders(Borders::ALL).title(format!("{} in progress", mode_label)))
-            .alignment(Alignment::Left);
+    #[test]
+    fn test_document_table_sorts_by_column_and_keeps_the_header_out_of_the_selection() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Documents("alpha".to_string());
+        app.documents_loaded = [("notes", 300, "2024-02-01"), ("a-big-pdf", 9000, "2024-01-01")]
+            .iter()
+            .map(|(id, size, day)| {
+                let created_at = format!("{}T10:00:00Z", day);
+                let doc =
+                    serde_json::json!({"id": id, "text_length": size, "created_at": created_at});
+                serde_json::from_value(doc).unwrap()
+            })
+            .collect();
+        app.apply_filter();
+        app.documents_state.select(Some(0));
+        let draw = |app: &mut App| {
+            let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(100, 12)).unwrap();
+            terminal.draw(|f| ui(f, app)).unwrap();
+            buffer_rows(terminal.backend().buffer())
+        };
+        let order = |app: &App| -> Vec<String> {
+            app.documents.iter().map(|doc| doc.id.clone()).collect()
+        };
 
-        f.render_widget(loading_widget, chunks[1]);
-        return;
+        let rows = draw(&mut app);
+        let header = rows.iter().position(|row| row.contains("SIZE")).unwrap();
+        assert!(rows[header + 1].contains(">> notes"));
+        assert!(rows[header + 1].contains("300 B"));
+
+        let press_s = |app: &mut App| {
+            handle_key(app, KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+        };
+        press_s(&mut app);
+        assert_eq!(order(&app), vec!["a-big-pdf", "notes"]);
+        assert!(draw(&mut app)[header].contains("ID ▲"));
+        // The selected document stays selected wherever it moves
+        assert_eq!(app.documents_state.selected(), Some(1));
+
+        press_s(&mut app);
+        press_s(&mut app);
+        assert_eq!(order(&app), vec!["notes", "a-big-pdf"]);
+        assert!(draw(&mut app)[header].contains("CREATED ▼"));
+        press_s(&mut app);
+        assert_eq!(order(&app), vec!["a-big-pdf", "notes"]);
+        press_s(&mut app);
+        assert_eq!(app.documents_sort, None);
+        assert_eq!(order(&app), vec!["notes", "a-big-pdf"]);
     }
 
-    // Results or Response based on mode
-    match app.query_mode {
-        QueryMode::Recall => {
-            // If viewing a recall result, show its details
-            if let Some(result) = &app.viewing_recall_result {
-                let recall_chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Length(7),  // Metadata
-                        Constraint::Min(0),     // Full text
-                    ])
-                    .split(chunks[1]);
+    #[test]
+    fn test_options_popup_steps_the_reflect_max_tokens() {
+        let mut app = banks_app(Theme::DARK);
+        app.reflect_max_tokens = 3000;
+        let press = |app: &mut App, code: KeyCode| {
+            handle_key(app, KeyEvent::new(code, KeyModifiers::NONE));
+        };
 
-                // Metadata section
-                let mem_type = result.type_.as_deref().unwrap_or("unknown");
-                let occurred_start = result.occurred_start.as_deref().unwrap_or("unknown");
-                let occurred_end = result.occurred_end.as_deref().unwrap_or("unknown");
-                let mentioned_at = result.mentioned_at.as_deref().unwrap_or("unknown");
+        press(&mut app, KeyCode::Char('O'));
+        press(&mut app, KeyCode::Char('j'));
+        // A configured value between steps snaps to the next one
+        press(&mut app, KeyCode::Right);
+        assert_eq!(app.options_popup.as_ref().unwrap().reflect_max_tokens, 4096);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.reflect_max_tokens, 1024);
+
+        assert_eq!(step_tokens(32768, true), 32768);
+        assert_eq!(step_tokens(512, false), 512);
+    }
 
-                let metadata_text = format!(
-                    "Type: {}\nMentioned At: {}\nOccurred: {} to {}",
-                    mem_type, mentioned_at, occurred_start, occurred_end
-                );
+    #[test]
+    fn test_options_popup_changes_the_page_size_and_reloads() {
+        let mut app = banks_app(Theme::DARK);
+        let press = |app: &mut App, code: KeyCode| {
+            handle_key(app, KeyEvent::new(code, KeyModifiers::NONE));
+        };
 
-                let metadata = Paragraph::new(metadata_text)
-                    .block(Block::default().borders(Borders::ALL).title("Recall Result Metadata"))
-                    .style(Style::default().fg(BRAND_START));
+        // Outside a paged list only the query options are offered
+        press(&mut app, KeyCode::Char('O'));
+        assert_eq!(
+            app.options_popup.as_ref().unwrap().fields(),
+            &[OptionField::RecallChunks, OptionField::ReflectMaxTokens]
+        );
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.include_recall_chunks);
+
+        app.view = View::Entities("alpha".to_string());
+        app.entities_page.offset = 200;
+        press(&mut app, KeyCode::Char('O'));
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.entities_load.page_size, LIST_PAGE_SIZE);
+
+        press(&mut app, KeyCode::Char('O'));
+        for _ in 0..30 {
+            press(&mut app, KeyCode::Right);
+        }
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.options_popup.is_none());
+        let load = LoadOptions {
+            page_size: 500,
+            verbose: true,
+        };
+        assert_eq!(app.entities_load, load);
+        assert_eq!((app.entities_page.limit, app.entities_page.offset), (500, 0));
+        assert!(app.is_loading(RequestSlot::List));
+        // The other lists keep their own options
+        assert_eq!(app.memories_load.page_size, LIST_PAGE_SIZE);
+
+        let request = ListRequest {
+            view: app.view.clone(),
+            paging: app.entities_page,
+            landing: Landing::First,
+        };
+        let page = EntityListResponse {
+            items: Vec::new(),
+            total: 0,
+            limit: 500,
+            offset: 0,
+        };
+        app.apply_list_page(request, ListPage::Entities(page));
+        assert!(app.status_message.ends_with("(page size 500)"));
+    }
 
-                f.render_widget(metadata, recall_chunks[0]);
+    #[test]
+    fn test_profile_shows_what_an_edit_changed() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Profile("alpha".to_string());
+        app.profile_before = serde_json::from_value(
+            serde_json::json!({"skepticism": 2, "literalism": 3, "empathy": 3}),
+        )
+        .unwrap();
+        let profile: BankProfileResponse = serde_json::from_value(serde_json::json!({
+            "bank_id": "alpha",
+            "name": "Alpha",
+            "mission": "Keeps track of the team's decisions",
+            "disposition": {"skepticism": 4, "literalism": 3, "empathy": 3}
+        }))
+        .unwrap();
+        let request = ListRequest {
+            view: app.view.clone(),
+            paging: Paging::new(),
+            landing: Landing::Keep,
+        };
+        app.apply_list_page(request, ListPage::Profile(profile));
+        assert_eq!(app.status_message, "Profile updated: Skepticism 2 → 4");
+
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rows = buffer_rows(terminal.backend().buffer());
+        let skepticism = rows.iter().find(|row| row.contains("Skepticism")).unwrap();
+        assert!(skepticism.contains("4/5") && skepticism.contains("was 2"));
+        let empathy = rows.iter().find(|row| row.contains("Empathy")).unwrap();
+        assert!(empathy.contains("3/5") && !empathy.contains("was"));
+        assert!(rows.iter().any(|row| row.contains("Keeps track of the team's decisions")));
+    }
 
-                // Full text content
-                let content_widget = Paragraph::new(result.text.as_str())
-                    .block(Block::default().borders(Borders::ALL).title("Full Text (Esc to close)"))
-                    .wrap(Wrap { trim: false })
-                    .style(Style::default().fg(Color::White));
+    #[test]
+    fn test_stats_dashboard_samples_totals_and_fits_any_size() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Stats("alpha".to_string());
+        for total in [40, 42] {
+            let stats: AgentStats = serde_json::from_value(serde_json::json!({
+                "bank_id": "alpha",
+                "total_nodes": total,
+                "total_links": 120,
+                "total_documents": 3,
+                "nodes_by_fact_type": {"world": 30, "experience": total - 30},
+                "links_by_link_type": {"semantic": 100, "temporal": 20},
+                "links_by_fact_type": {},
+                "links_breakdown": {},
+                "pending_operations": 2,
+                "failed_operations": 0
+            }))
+            .unwrap();
+            let request = ListRequest {
+                view: app.view.clone(),
+                paging: Paging::new(),
+                landing: Landing::Keep,
+            };
+            app.apply_list_page(request, ListPage::Stats(stats));
+        }
+        assert_eq!(app.stats_history, vec![40, 42]);
+
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rows = buffer_rows(terminal.backend().buffer());
+        assert!(rows.iter().any(|row| row.contains("42") && row.contains("120")));
+        assert!(rows.iter().any(|row| row.contains("2 pending")));
+        assert!(rows.iter().any(|row| row.contains("40–42")));
+
+        for (width, height) in [(20, 8), (3, 2), (1, 1)] {
+            let backend = ratatui::backend::TestBackend::new(width, height);
+            let mut terminal = Terminal::new(backend).unwrap();
+            terminal.draw(|f| render_stats(f, &app, f.area())).unwrap();
+        }
+    }
 
-                f.render_widget(content_widget, recall_chunks[1]);
-            } else {
-                // Show results as a table like memories
-                let mut items = vec![
-                    // Header row
-                    ListItem::new(format!("{:<10} {:<18} {:<18} {}", "TYPE", "OCCURRED START", "OCCURRED END", "TEXT"))
-                        .style(Style::default().fg(BRAND_START).add_modifier(Modifier::BOLD))
-                ];
+    #[test]
+    fn test_operation_actions_match_the_operation_status() {
+        let client = ApiClient::new("http://localhost:0".to_string(), None).unwrap();
+        let mut app = App::new(client);
+        app.view = View::Operations("team".to_string());
+        app.operations_loaded = ["failed", "pending"]
+            .iter()
+            .map(|status| {
+                serde_json::from_value(serde_json::json!({
+                    "id": format!("op-{}", status),
+                    "task_type": "retain",
+                    "items_count": 2,
+                    "created_at": "2024-01-15T10:00:00Z",
+                    "status": status,
+                    "error_message": (*status == "failed").then_some("LLM timeout"),
+                }))
+                .unwrap()
+            })
+            .collect();
+        app.apply_filter();
+
+        app.operations_state.select(Some(0));
+        app.request_operation_action(OperationAction::Cancel);
+        assert_eq!(app.error_message, "Only pending operations can be cancelled");
+        app.request_operation_action(OperationAction::Retry);
+        let pending = app.pending_operation.take().unwrap();
+        assert_eq!(pending.action, OperationAction::Retry);
+        assert_eq!(pending.operation.id, "op-failed");
+
+        app.operations_state.select(Some(1));
+        app.request_operation_action(OperationAction::Retry);
+        assert_eq!(app.error_message, "Only failed operations can be retried");
+        assert!(app.pending_operation.is_none());
+        app.request_operation_action(OperationAction::Cancel);
+        assert_eq!(app.pending_operation.unwrap().bank_id, "team");
+
+        // Filtering narrows by status as well as ID and kind
+        app.filter_text.set("failed");
+        app.apply_filter();
+        assert_eq!(app.operations.len(), 1);
+    }
 
-                // Data rows
-                for result in &app.query_results {
-                    let mem_type = result.type_.as_deref().unwrap_or("unknown");
-                    let occurred_start = result.occurred_start.as_deref()
-                        .and_then(|s| s.split('T').next())
-                        .unwrap_or("-");
-                    let occurred_end = result.occurred_end.as_deref()
-                        .and_then(|s| s.split('T').next())
-                        .unwrap_or("-");
-                    let text = &result.text;
+    #[test]
+    fn test_list_nav_wraps_single_steps_and_clamps_pages() {
+        assert_eq!(ListNav::Next.target(Some(2), 3), Some(0));
+        assert_eq!(ListNav::Previous.target(Some(0), 3), Some(2));
+        assert_eq!(ListNav::Next.target(None, 3), Some(0));
+        assert_eq!(ListNav::Previous.target(None, 3), Some(0));
+        assert_eq!(ListNav::Down(10).target(Some(1), 3), Some(2));
+        assert_eq!(ListNav::Up(10).target(Some(1), 3), Some(0));
+        assert_eq!(ListNav::Last.target(Some(0), 3), Some(2));
+        assert_eq!(ListNav::To(41).target(None, 100), Some(41));
+        assert_eq!(ListNav::To(41).target(None, 5), Some(4));
+        // A selection left over from a longer list still moves from the end
+        assert_eq!(ListNav::Up(1).target(Some(9), 3), Some(1));
+        for nav in [ListNav::Next, ListNav::Previous, ListNav::First, ListNav::Last] {
+            assert_eq!(nav.target(Some(0), 0), None);
+        }
 
-                    // Apply horizontal scroll
-                    let scrolled_text: String = text.chars().skip(app.horizontal_scroll).take(80).collect();
+        let area = ListArea::new(Rect::new(0, 0, 40, 12), 0, 1);
+        assert_eq!(area.page_len(), 9);
+    }
 
-                    let content = format!("{:<10} {:<18} {:<18} {}", mem_type, occurred_start, occurred_end, scrolled_text);
-                    items.push(ListItem::new(content).style(Style::default().fg(Color::White)));
-                }
+    #[test]
+    fn test_mouse_selects_rows_and_triggers_shortcuts() {
+        let mut app = banks_app(Theme::DARK);
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 16)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rows = buffer_rows(terminal.backend().buffer());
+        let beta_y = rows.iter().position(|row| row.contains("beta - BETA")).unwrap() as u16;
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
 
-                let list = List::new(items)
-                    .block(Block::default().borders(Borders::ALL).title(format!("Recall Results ({}) - Press Enter to view full text", app.query_results.len())))
-                    .highlight_style(
-                        Style::default()
-                            .bg(Color::DarkGray)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                    .highlight_symbol(">> ");
+        assert!(!handle_mouse(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 5, beta_y)));
+        assert_eq!(app.banks_state.selected(), Some(1));
+        handle_mouse(&mut app, mouse(MouseEventKind::ScrollUp, 5, beta_y));
+        assert_eq!(app.banks_state.selected(), Some(0));
+        // Below the last bank there is nothing to select
+        handle_mouse(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 5, beta_y + 1));
+        assert_eq!(app.banks_state.selected(), Some(0));
+
+        let help_y = rows.iter().position(|row| row.contains("<?> Help")).unwrap();
+        let help_x = rows[help_y][..rows[help_y].find("<?>").unwrap()].chars().count() as u16;
+        let click = mouse(MouseEventKind::Down(MouseButton::Left), help_x + 1, help_y as u16);
+        handle_mouse(&mut app, click);
+        assert!(app.show_help);
+
+        let key = |code| Some(KeyEvent::new(code, KeyModifiers::NONE));
+        assert_eq!(shortcut_key("Del/x"), key(KeyCode::Delete));
+        assert_eq!(shortcut_key("n/→"), key(KeyCode::Char('n')));
+        assert_eq!(shortcut_key("F2"), key(KeyCode::F(2)));
+        assert_eq!(shortcut_key("↑↓"), None);
+        assert_eq!(
+            shortcut_key("Ctrl+R"),
+            Some(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL))
+        );
+    }
 
-                f.render_stateful_widget(list, chunks[1], &mut app.query_results_state);
-            }
-        }
-        QueryMode::Reflect => {
-            let response_text = if app.query_response.is_empty() {
-                "No response yet. Enter a query and press Enter to get a reflection."
-            } else {
-                app.query_response.as_str()
-            };
+    #[cfg(unix)]
+    #[test]
+    fn test_external_editor_keeps_the_text_when_it_fails() {
+        // `true` saves the file as it was given, with the editor's trailing newline dropped
+        assert_eq!(edit_text("true", "Plan the offsite\n").unwrap(), "Plan the offsite");
+        let failed = edit_text("false", "draft").unwrap_err().to_string();
+        assert!(failed.contains("kept the original text"), "{}", failed);
+        assert!(edit_text("hindsight-no-such-editor", "draft").is_err());
+        assert!(edit_text("  ", "draft").is_err());
+
+        let mut app = banks_app(Theme::DARK);
+        app.input_mode = InputMode::Query;
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL));
+        assert_eq!(app.editor_request, Some(EditorTarget::Query));
+    }
 
-            let response = Paragraph::new(response_text)
-                .style(Style::default().fg(Color::White))
-                .block(Block::default().borders(Borders::ALL).title("Reflect Response"))
-                .wrap(Wrap { trim: false });
+    #[test]
+    fn test_quit_asks_while_writes_run() {
+        let mut tabs = Tabs::new(banks_app(Theme::DARK));
+        let running = |label| InFlight {
+            generation: 1,
+            label,
+            foreground: false,
+        };
+        let press = |tabs: &mut Tabs, c: char| {
+            let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            let quit = handle_key(tabs.active(), key);
+            tabs.settle_quit(quit)
+        };
 
-            f.render_widget(response, chunks[1]);
-        }
+        // Reads in flight don't hold up quitting
+        tabs.active().in_flight.insert(RequestSlot::Query, running("Searching memories"));
+        assert!(tabs.settle_quit(true));
+
+        tabs.active().in_flight.insert(RequestSlot::Retain, running("Adding memory"));
+        assert!(!press(&mut tabs, 'q'));
+        assert_eq!(tabs.active().pending_quit, Some(vec!["Adding memory"]));
+        assert!(!press(&mut tabs, 'n'));
+        assert!(tabs.active().pending_quit.is_none());
+
+        // Waiting quits once the write is done, unless it failed
+        press(&mut tabs, 'q');
+        assert!(!press(&mut tabs, 'w'));
+        assert!(!tabs.ready_to_quit());
+        tabs.active().in_flight.remove(&RequestSlot::Retain);
+        tabs.active().failed_writes += 1;
+        assert!(!tabs.ready_to_quit());
+        assert_eq!(tabs.quit_when_done, None);
+
+        tabs.active().in_flight.insert(RequestSlot::Export, running("Exporting"));
+        press(&mut tabs, 'q');
+        assert!(press(&mut tabs, 'y'));
     }
-}
 
-fn render_help(f: &mut Frame, area: Rect) {
-    let help_text = vec![
-        Line::from(Span::styled("Hindsight Explorer - Keyboard Shortcuts", Style::default().fg(BRAND_START).add_modifier(Modifier::BOLD))),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Navigation Flow", Style::default().fg(BRAND_END).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from("  1. Start by selecting a bank (Enter)"),
-        Line::from("  2. View memories, entities, or documents for that bank"),
-        Line::from("  3. Press / from any view to query (recall/reflect)"),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Basic Navigation", Style::default().fg(BRAND_END).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from("  ↑/↓, j/k    - Navigate up/down in lists"),
-        Line::from("  ←/→, h/l    - Scroll text left/right in tables"),
-        Line::from("  Enter       - Select item / view details"),
-        Line::from("  Esc         - Go back / close detail view"),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Query View", Style::default().fg(BRAND_END).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from("  /           - Start or edit query (from any non-bank view)"),
-        Line::from("  m           - Toggle mode (Recall ↔ Reflect)"),
-        Line::from("  b           - Cycle budget (Low → Mid → High)"),
-        Line::from("  +/-         - Adjust max tokens"),
-        Line::from("  Enter       - Execute query"),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("General", Style::default().fg(BRAND_END).add_modifier(Modifier::BOLD)),
-        ]),
-        Line::from("  R           - Refresh current view"),
-        Line::from("  ?           - Toggle this help screen"),
-        Line::from("  q           - Quit"),
-        Line::from(""),
-        Line::from(Span::styled("Press ? to close help", Style::default().fg(Color::DarkGray))),
-    ];
+    #[test]
+    fn test_tabs_keep_their_own_views_and_share_the_theme() {
+        let mut tabs = Tabs::new(banks_app(Theme::DARK));
+        tabs.active().view = View::Memories("alpha".to_string());
+        let press = |tabs: &mut Tabs, code, modifiers| {
+            handle_key(tabs.active(), KeyEvent::new(code, modifiers));
+            if let Some(request) = tabs.active().tab_request.take() {
+                tabs.apply(request);
+            }
+        };
 
-    let help = Paragraph::new(help_text)
-        .block(Block::default().borders(Borders::ALL).title("Help"))
-        .alignment(Alignment::Left);
+        press(&mut tabs, KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert_eq!((tabs.tabs.len(), tabs.active), (2, 1));
+        assert_eq!(tabs.active().view, View::Banks);
+        press(&mut tabs, KeyCode::Char('t'), KeyModifiers::NONE);
+        let theme = tabs.active().theme;
+        assert_ne!(theme, Theme::DARK);
+        press(&mut tabs, KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(tabs.active, 0);
+        assert_eq!(tabs.active().theme, theme);
+        tabs.label_active();
+        assert_eq!(tabs.active().tab_titles, vec!["alpha:memories", "banks"]);
+        press(&mut tabs, KeyCode::Char('5'), KeyModifiers::ALT);
+        assert_eq!(tabs.active().status_message, "There is no tab 5");
+
+        // A running query makes closing ask first
+        tabs.active().in_flight.insert(
+            RequestSlot::Query,
+            InFlight {
+                generation: 1,
+                label: "Recalling",
+                foreground: true,
+            },
+        );
+        press(&mut tabs, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert!(tabs.active().pending_close_tab);
+        assert_eq!(tabs.tabs.len(), 2);
+        press(&mut tabs, KeyCode::Char('y'), KeyModifiers::NONE);
+        assert_eq!((tabs.tabs.len(), tabs.active), (1, 0));
+        assert_eq!(tabs.active().view, View::Banks);
+        press(&mut tabs, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(tabs.tabs.len(), 1);
+    }
 
-    f.render_widget(help, area);
-}
+    #[test]
+    fn test_custom_keys_drive_the_views_and_the_shortcuts_bar() {
+        let mut app = banks_app(Theme::DARK);
+        let overrides = [
+            ("new_bank".to_string(), "\"nb\"".to_string()),
+            ("help".to_string(), "\"F1\"".to_string()),
+        ];
+        app.keys = Keymap::new(&overrides).unwrap();
+        let press = |app: &mut App, code| handle_key(app, KeyEvent::new(code, KeyModifiers::NONE));
+
+        // The old key no longer does anything
+        press(&mut app, KeyCode::Char('?'));
+        assert!(!app.show_help);
+        // A key that breaks off a sequence still counts on its own
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.banks_state.selected(), Some(1));
+        press(&mut app, KeyCode::Char('n'));
+        press(&mut app, KeyCode::Char('b'));
+        assert_eq!(app.input_mode, InputMode::Bank);
+
+        app.close_bank_dialog();
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(100, 16)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rows = buffer_rows(terminal.backend().buffer()).join("\n");
+        assert!(rows.contains("<nb> New"));
+        assert!(rows.contains("<F1> Help"));
+        assert!(!rows.contains("<?>"));
+    }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
-    // Initial load
-    app.refresh()?;
+    #[test]
+    fn test_reload_keeps_the_selected_row_by_id() {
+        let id = |row: &&str| Some(*row);
+        let mut state = ListState::default();
+        state.select(Some(2));
+        *state.offset_mut() = 1;
+        let kept = KeptSelection::new(&["a", "b", "c", "d"], &state, id);
+
+        // A new row above moves "c" down; it stays selected at the same height on screen
+        kept.land(&mut state, &["new", "a", "b", "c", "d"], Landing::Keep, id);
+        assert_eq!((state.selected(), state.offset()), (Some(3), 2));
+
+        // A row that is gone leaves the index alone
+        let gone = KeptSelection::new(&["new", "a", "b", "x"], &state, id);
+        gone.land(&mut state, &["new", "a", "b"], Landing::Keep, id);
+        assert_eq!(state.selected(), Some(3));
+
+        kept.land(&mut state, &["a", "b"], Landing::First, id);
+        assert_eq!((state.selected(), state.offset()), (Some(0), 0));
+    }
 
-    loop {
-        terminal.draw(|f| ui(f, &mut app))?;
+    #[test]
+    fn test_auto_refresh_waits_while_typing_and_after_key_presses() {
+        let mut app = banks_app(Theme::DARK);
+        app.last_refresh = Instant::now() - Duration::from_secs(60);
+        app.last_input = Instant::now() - Duration::from_secs(60);
+        assert!(app.should_refresh());
+        assert_eq!(app.refresh_countdown().as_deref(), Some("↻ 0s"));
+
+        app.input_mode = InputMode::Query;
+        assert!(!app.should_refresh());
+        assert_eq!(app.refresh_countdown().as_deref(), Some("↻ paused"));
+        app.input_mode = InputMode::Normal;
+
+        app.last_input = Instant::now();
+        assert!(!app.should_refresh());
+    }
 
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                // Handle Ctrl+C to exit
-                if key.code == KeyCode::Char('c') && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) {
-                    return Ok(());
-                }
+    #[test]
+    fn test_copied_lists_are_markdown_bullets_and_osc52_is_base64() {
+        let memories = vec![
+            serde_json::json!({
+                "id": "m1",
+                "text": "Alice moved\nto Berlin",
+                "fact_type": "world",
+                "occurred_start": "2024-03-01T10:00:00Z"
+            }),
+            serde_json::json!({"id": "m2", "text": "No date"}),
+        ];
+        let data = ExportData::new("memories", "Memories in team".to_string(), &memories);
+        assert_eq!(
+            data.to_bullets(),
+            "- **world, 2024-03-01** Alice moved\n  to Berlin\n- No date\n"
+        );
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
 
-                match app.input_mode {
-                    InputMode::Normal => {
-                        match key.code {
-                            KeyCode::Char('q') => return Ok(()),
-                            KeyCode::Char('?') => app.show_help = !app.show_help,
-
-                            // Navigation
-                            KeyCode::Down | KeyCode::Char('j') => app.next_item(),
-                            KeyCode::Up | KeyCode::Char('k') => app.previous_item(),
-                            KeyCode::Left | KeyCode::Char('h') => app.scroll_left(),
-                            KeyCode::Right | KeyCode::Char('l') => app.scroll_right(),
-                            KeyCode::Enter => {
-                                app.reset_horizontal_scroll();
-                                app.enter_view()?;
-                            }
-                            KeyCode::Esc => {
-                                app.reset_horizontal_scroll();
-                                app.go_back();
-                            }
-
-                            // Refresh
-                            KeyCode::Char('R') => {
-                                app.refresh()?;
-                            }
-
-                            // Query input - start query from any non-bank view
-                            KeyCode::Char('/') => {
-                                match &app.view {
-                                    View::Banks => {
-                                        app.error_message = "Select a bank first".to_string();
-                                    }
-                                    View::Query(_) => {
-                                        app.input_mode = InputMode::Query;
-                                    }
-                                    _ => {
-                                        // Switch to Query view using current bank
-                                        if let Some(bank_id) = app.selected_bank_id.clone() {
-                                            app.switch_to_view(View::Query(bank_id))?;
-                                            app.input_mode = InputMode::Query;
-                                        } else {
-                                            app.error_message = "No bank selected".to_string();
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Query view controls
-                            KeyCode::Char('m') => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.toggle_query_mode();
-                                }
-                            }
-                            KeyCode::Char('b') => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.cycle_budget();
-                                }
-                            }
-                            KeyCode::Char('+') | KeyCode::Char('=') => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.adjust_max_tokens(true);
-                                }
-                            }
-                            KeyCode::Char('-') => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.adjust_max_tokens(false);
-                                }
-                            }
-
-                            // Delete document
-                            KeyCode::Delete => {
-                                if matches!(app.view, View::Documents(_)) {
-                                    app.delete_selected_document()?;
-                                }
-                            }
-
-                            // Pagination for memories
-                            KeyCode::Char('n') => {
-                                if matches!(app.view, View::Memories(_)) {
-                                    app.load_more_memories()?;
-                                }
-                            }
-                            KeyCode::Char('p') => {
-                                if matches!(app.view, View::Memories(_)) {
-                                    app.load_prev_memories()?;
-                                }
-                            }
-
-                            _ => {}
-                        }
-                    }
-                    InputMode::Query => {
-                        match key.code {
-                            KeyCode::Enter => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.execute_query();
-                                }
-                            }
-                            KeyCode::Esc => {
-                                app.input_mode = InputMode::Normal;
-                            }
-                            KeyCode::Char(c) => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.query_text.push(c);
-                                }
-                            }
-                            KeyCode::Backspace => {
-                                if matches!(app.view, View::Query(_)) {
-                                    app.query_text.pop();
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_export_writes_json_and_markdown_without_replacing_files() {
+        let data = ExportData::new(
+            "recall",
+            "Recall results from team".to_string(),
+            &[
+                serde_json::json!({
+                    "id": "m1",
+                    "text": "Alice moved to Berlin",
+                    "type": "world",
+                    "tags": ["move", "alice"],
+                    "context": null
+                }),
+                serde_json::json!({"canonical_name": "Alice", "mention_count": 3}),
+            ],
+        );
+        assert_eq!(
+            data.to_markdown(),
+            "# Recall results from team\n\n## 1. m1\n\nAlice moved to Berlin\n\n\
+             - **tags**: move, alice\n- **type**: world\n\n## 2. Alice\n\n- **mention_count**: 3\n"
+        );
+        assert!(data.default_path("team/a b", ExportFormat::Md).starts_with("recall-team_a_b-"));
+
+        let dir = std::env::temp_dir()
+            .join(format!("hindsight-explorer-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("recall.json");
+        data.write(&path, ExportFormat::Json, false).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Value>>(&written).unwrap(), data.items);
+        // An existing file is only replaced once the user confirmed it
+        assert!(data.write(&path, ExportFormat::Md, false).is_err());
+        data.write(&path, ExportFormat::Md, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), data.to_markdown());
+        let _ = fs::remove_dir_all(&dir);
+    }
 
-        // Check for query results from background thread
-        app.check_query_result();
+    #[test]
+    fn test_session_restores_view_and_ignores_bad_files() {
+        let mut app = banks_app(Theme::DARK);
+        app.view = View::Entities("beta".to_string());
+        app.entities_state.select(Some(7));
+        app.entities_page.offset = 100;
+        app.filter_text.set("alice");
+        app.query_settings.insert("beta".to_string(), QuerySettings::default());
+
+        let dir = std::env::temp_dir()
+            .join(format!("hindsight-explorer-session-{}", std::process::id()));
+        let path = dir.join(SESSION_FILE_NAME);
+        app.session().save(&path).unwrap();
+        let session = ExplorerSession::load(&path).unwrap();
+
+        let mut restored = banks_app(Theme::DARK);
+        restored.resume_session(session.clone());
+        assert_eq!(restored.view, View::Entities("beta".to_string()));
+        assert_eq!(restored.view_history, vec![View::Banks]);
+        assert_eq!(restored.selected_bank_id.as_deref(), Some("beta"));
+        assert_eq!(restored.entities_page.offset, 100);
+        assert_eq!(restored.entities_state.selected(), Some(7));
+        assert_eq!(restored.filter_text.as_str(), "alice");
+
+        // A bank deleted since then leaves the explorer on the Banks list
+        let mut gone = banks_app(Theme::DARK);
+        gone.banks_loaded.retain(|bank| bank.bank_id != "beta");
+        gone.apply_filter();
+        gone.resume_session(session);
+        assert_eq!(gone.view, View::Banks);
+        assert!(gone.status_message.contains("no longer exists"));
+
+        fs::write(&path, "{not json").unwrap();
+        assert!(ExplorerSession::load(&path).is_none());
+        let mut outdated: Value = serde_json::to_value(app.session()).unwrap();
+        outdated["version"] = Value::from(SESSION_VERSION + 1);
+        fs::write(&path, outdated.to_string()).unwrap();
+        assert!(ExplorerSession::load(&path).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
 
-        // Auto-refresh check
-        app.do_auto_refresh()?;
+    #[test]
+    fn test_highlight_terms_marks_query_words_case_insensitively() {
+        let terms = query_terms("Where did Alice move? a  alice");
+        assert_eq!(terms, vec!["where", "did", "alice", "move"]);
+
+        let line = highlight_terms("ALICE moved to Zürich; alice's move", &terms, &Theme::DARK);
+        let spans: Vec<(&str, bool)> = line
+            .spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style.bg.is_some()))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("ALICE", true),
+                (" ", false),
+                ("move", true),
+                ("d to Zürich; ", false),
+                ("alice", true),
+                ("'s ", false),
+                ("move", true),
+            ]
+        );
+        assert!(highlight_terms("", &terms, &Theme::DARK).spans.is_empty());
     }
-}
 
-pub fn run(client: &ApiClient) -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    #[test]
+    fn test_highlight_terms_merges_overlaps_and_handles_unicode() {
+        let marked = |line: &str, query: &str| -> Vec<String> {
+            highlight_terms(line, &query_terms(query), &Theme::DARK)
+                .spans
+                .iter()
+                .filter(|s| s.style.bg.is_some())
+                .map(|s| s.content.to_string())
+                .collect()
+        };
+        // Overlapping terms and repeats become one span
+        assert_eq!(marked("the Berliner Ensemble", "berlin liner"), vec!["Berliner"]);
+        assert_eq!(marked("anananas", "anan"), vec!["ananan"]);
+        // Case folding beyond ASCII, including a char that grows when lowercased
+        assert_eq!(
+            marked("ÜBER das Straßenfest in ZÜRICH", "über zürich"),
+            vec!["ÜBER", "ZÜRICH"]
+        );
+        assert_eq!(marked("İstanbul trip", "i̇stanbul"), vec!["İstanbul"]);
+        assert_eq!(marked("東京で会議", "東京で"), vec!["東京で"]);
+        // Short words are not terms
+        assert!(query_terms("a to of").is_empty());
+    }
 
-    // Create app and run it
-    let app = App::new(client.clone());
-    let res = run_app(&mut terminal, app);
+    #[test]
+    fn test_wrapped_rows_breaks_words_and_long_tokens() {
+        assert_eq!(wrapped_rows("", 10), 1);
+        assert_eq!(wrapped_rows("one two three", 13), 1);
+        assert_eq!(wrapped_rows("one two three", 8), 2);
+        assert_eq!(wrapped_rows("abcdefghijklmnopqrstuvwxy", 10), 3);
+        assert_eq!(wrapped_rows("ab abcdefghijkl", 10), 3);
+    }
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    #[test]
+    fn test_memory_draft_wraps_lines_and_cycles_types() {
+        let (rows, cursor) = hard_wrap("abcdef\nxy", 8, 4);
+        assert_eq!(rows, vec!["abcd", "ef", "xy"]);
+        assert_eq!(cursor, (2, 1));
+        assert_eq!(hard_wrap("abcd", 4, 4).1, (0, 4));
+        assert_eq!(hard_wrap("ab\n", 3, 4), (vec!["ab".to_string(), String::new()], (1, 0)));
+
+        let mut draft = MemoryDraft::default();
+        assert_eq!(draft.fact_type(), None);
+        draft.cycle_type(true);
+        assert_eq!(draft.fact_type(), Some("world"));
+        draft.cycle_type(false);
+        draft.cycle_type(false);
+        assert_eq!(draft.fact_type(), Some("observation"));
+    }
 
-    if let Err(err) = res {
-        println!("Error: {:?}", err);
+    #[test]
+    fn test_memory_detail_lists_unknown_fields_as_metadata() {
+        let memory = serde_json::json!({
+            "id": "m1",
+            "text": "first line\nsecond line",
+            "fact_type": "world",
+            "chunk_id": "bank_notes_3",
+            "metadata": {"source": "slack"},
+            "proof_count": 2
+        });
+        let memory = serde_json::from_value(memory).unwrap();
+        let lines = memory_detail_lines(&memory, "bank", &Theme::DARK);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect();
+        assert!(text.iter().any(|l| l.starts_with("Document:") && l.ends_with("notes")));
+        assert!(text.contains(&"second line".to_string()));
+        assert!(text.contains(&"  source: slack".to_string()));
+        assert!(text.contains(&"  proof_count: 2".to_string()));
     }
 
-    Ok(())
+    #[test]
+    fn test_json_inspector_shows_the_selected_item_and_colors_it() {
+        let mut app = banks_app(Theme::DARK);
+        app.banks_state.select(Some(1));
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT));
+        let inspector = app.json_inspector.as_ref().expect("inspector open");
+        assert_eq!(inspector.title, "Bank beta");
+        assert!(inspector.text.contains("\"bank_id\": \"beta\""));
+        handle_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.json_inspector.is_none());
+
+        let theme = Theme::DARK;
+        let line = json_line(r#"  "say \"hé\"": [-1.5e3, null],"#, &theme);
+        let spans: Vec<(&str, Style)> =
+            line.spans.iter().map(|span| (span.content.as_ref(), span.style)).collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("  ", theme.muted),
+                (r#""say \"hé\"""#, theme.accent),
+                (": [", theme.muted),
+                ("-1.5e3", theme.warning),
+                (", ", theme.muted),
+                ("null", theme.tertiary),
+                ("],", theme.muted),
+            ]
+        );
+    }
 }
@@ -0,0 +1,314 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::api::{ApiClient, GraphLink, GraphNode};
+use crate::commands::memory::parse_timestamp;
+use crate::output::{self, OutputFormat};
+use crate::ui;
+use crate::utils;
+
+/// Characters of memory text kept in node labels
+const LABEL_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Graphml,
+}
+
+/// Node fill color per fact type
+fn type_color(fact_type: Option<&str>) -> &'static str {
+    match fact_type {
+        Some("world") => "#4e79a7",
+        Some("experience") => "#f28e2b",
+        Some("opinion") => "#e15759",
+        Some("observation") => "#59a14f",
+        _ => "#bab0ac",
+    }
+}
+
+pub(crate) fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn node_label(node: &GraphNode) -> String {
+    output::truncate_to_width(&node.text.replace(['\n', '\r'], " "), LABEL_WIDTH)
+}
+
+impl GraphFormat {
+    fn write_header(self, out: &mut dyn Write, bank_id: &str) -> io::Result<()> {
+        match self {
+            GraphFormat::Dot => {
+                writeln!(out, "digraph \"{}\" {{", dot_escape(bank_id))?;
+                writeln!(out, "  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];")
+            }
+            GraphFormat::Graphml => {
+                writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+                writeln!(out, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+                for (id, target, name, kind) in [
+                    ("label", "node", "label", "string"),
+                    ("text", "node", "text", "string"),
+                    ("fact_type", "node", "fact_type", "string"),
+                    ("color", "node", "color", "string"),
+                    ("link_type", "edge", "link_type", "string"),
+                    ("weight", "edge", "weight", "double"),
+                    ("entity", "edge", "entity", "string"),
+                ] {
+                    writeln!(
+                        out,
+                        "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+                        id, target, name, kind
+                    )?;
+                }
+                writeln!(out, "  <graph id=\"{}\" edgedefault=\"directed\">", xml_escape(bank_id))
+            }
+        }
+    }
+
+    fn write_node(self, out: &mut dyn Write, node: &GraphNode) -> io::Result<()> {
+        let fact_type = node.fact_type.as_deref();
+        let color = type_color(fact_type);
+        match self {
+            GraphFormat::Dot => writeln!(
+                out,
+                "  \"{}\" [label=\"{}\", fillcolor=\"{}\", fact_type=\"{}\"];",
+                dot_escape(&node.id),
+                dot_escape(&node_label(node)),
+                color,
+                fact_type.unwrap_or("unknown")
+            ),
+            GraphFormat::Graphml => {
+                writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id))?;
+                writeln!(out, "      <data key=\"label\">{}</data>", xml_escape(&node_label(node)))?;
+                writeln!(out, "      <data key=\"text\">{}</data>", xml_escape(&node.text))?;
+                writeln!(out, "      <data key=\"fact_type\">{}</data>", fact_type.unwrap_or("unknown"))?;
+                writeln!(out, "      <data key=\"color\">{}</data>", color)?;
+                writeln!(out, "    </node>")
+            }
+        }
+    }
+
+    fn write_edge(self, out: &mut dyn Write, index: usize, link: &GraphLink) -> io::Result<()> {
+        match self {
+            GraphFormat::Dot => {
+                let label = match &link.entity_name {
+                    Some(entity) => format!("{}: {}", link.link_type, entity),
+                    None => link.link_type.clone(),
+                };
+                writeln!(
+                    out,
+                    "  \"{}\" -> \"{}\" [label=\"{}\", link_type=\"{}\", weight={}];",
+                    dot_escape(&link.source),
+                    dot_escape(&link.target),
+                    dot_escape(&label),
+                    dot_escape(&link.link_type),
+                    link.weight
+                )
+            }
+            GraphFormat::Graphml => {
+                writeln!(
+                    out,
+                    "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">",
+                    index,
+                    xml_escape(&link.source),
+                    xml_escape(&link.target)
+                )?;
+                writeln!(out, "      <data key=\"link_type\">{}</data>", xml_escape(&link.link_type))?;
+                writeln!(out, "      <data key=\"weight\">{}</data>", link.weight)?;
+                if let Some(entity) = &link.entity_name {
+                    writeln!(out, "      <data key=\"entity\">{}</data>", xml_escape(entity))?;
+                }
+                writeln!(out, "    </edge>")
+            }
+        }
+    }
+
+    fn write_footer(self, out: &mut dyn Write) -> io::Result<()> {
+        match self {
+            GraphFormat::Dot => writeln!(out, "}}"),
+            GraphFormat::Graphml => {
+                writeln!(out, "  </graph>")?;
+                writeln!(out, "</graphml>")
+            }
+        }
+    }
+}
+
+/// Write `nodes` and the links between them, one element at a time.
+/// Returns the number of nodes and edges written.
+fn write_graph(
+    out: &mut dyn Write,
+    format: GraphFormat,
+    bank_id: &str,
+    nodes: &[&GraphNode],
+    links: &[GraphLink],
+    link_types: &[String],
+) -> io::Result<(usize, usize)> {
+    format.write_header(out, bank_id)?;
+    let mut ids: HashSet<&str> = HashSet::with_capacity(nodes.len());
+    for node in nodes {
+        format.write_node(out, node)?;
+        ids.insert(node.id.as_str());
+    }
+
+    let mut edges = 0;
+    for link in links {
+        let wanted = link_types.is_empty() || link_types.iter().any(|t| t == &link.link_type);
+        if wanted && ids.contains(link.source.as_str()) && ids.contains(link.target.as_str()) {
+            format.write_edge(out, edges, link)?;
+            edges += 1;
+        }
+    }
+    format.write_footer(out)?;
+    Ok((nodes.len(), edges))
+}
+
+/// Export a bank's memory graph as Graphviz DOT or GraphML
+#[allow(clippy::too_many_arguments)]
+pub fn export(
+    client: &ApiClient,
+    bank_id: &str,
+    format: GraphFormat,
+    out: Option<PathBuf>,
+    fact_type: Option<String>,
+    link_types: Vec<String>,
+    max_nodes: i64,
+    since: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let since = since.as_deref().map(utils::parse_datetime_arg).transpose()?;
+    // Spinners and messages would corrupt a graph written to stdout
+    let show_progress = output_format == OutputFormat::Pretty && out.is_some();
+
+    let spinner = show_progress.then(|| ui::create_spinner("Fetching graph data..."));
+    let response = client.get_graph_export(bank_id, fact_type.as_deref(), max_nodes, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let graph = response?;
+
+    let nodes: Vec<&GraphNode> = graph
+        .nodes
+        .iter()
+        .filter(|node| match since {
+            Some(since) => [&node.mentioned_at, &node.occurred_start]
+                .into_iter()
+                .flatten()
+                .find_map(|ts| parse_timestamp(ts))
+                .is_some_and(|ts| ts >= since),
+            None => true,
+        })
+        .collect();
+
+    let (written_nodes, written_edges) = match &out {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let mut writer = BufWriter::new(file);
+            let counts = write_graph(&mut writer, format, bank_id, &nodes, &graph.links, &link_types)?;
+            writer.flush()?;
+            counts
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            let counts = write_graph(&mut writer, format, bank_id, &nodes, &graph.links, &link_types)?;
+            writer.flush()?;
+            counts
+        }
+    };
+
+    if graph.total_units > graph.nodes.len() as i64 {
+        // stderr, so a graph written to stdout stays valid
+        eprintln!(
+            "warning: --max-nodes {} truncated the graph; {} of {} memories exported",
+            max_nodes,
+            graph.nodes.len(),
+            graph.total_units
+        );
+    }
+    if let Some(path) = out.as_ref().filter(|_| show_progress) {
+        ui::print_success(&format!(
+            "Wrote {} nodes and {} edges to {}",
+            written_nodes,
+            written_edges,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, text: &str, fact_type: Option<&str>) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            text: text.to_string(),
+            fact_type: fact_type.map(str::to_string),
+            mentioned_at: None,
+            occurred_start: None,
+        }
+    }
+
+    fn link(source: &str, target: &str, link_type: &str) -> GraphLink {
+        GraphLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            link_type: link_type.to_string(),
+            weight: 0.5,
+            entity_name: None,
+        }
+    }
+
+    #[test]
+    fn test_dot_export_filters_links() {
+        let a = node("a", "Alice said \"hi\"", Some("world"));
+        let b = node("b", "Bob", Some("opinion"));
+        let links = vec![link("a", "b", "semantic"), link("a", "b", "temporal"), link("a", "zz", "semantic")];
+        let mut out = Vec::new();
+        let counts = write_graph(
+            &mut out,
+            GraphFormat::Dot,
+            "bank",
+            &[&a, &b],
+            &links,
+            &["semantic".to_string()],
+        )
+        .unwrap();
+        assert_eq!(counts, (2, 1));
+        let dot = String::from_utf8(out).unwrap();
+        assert!(dot.starts_with("digraph \"bank\" {"));
+        assert!(dot.contains("label=\"Alice said \\\"hi\\\"\", fillcolor=\"#4e79a7\""));
+        assert!(dot.contains("\"a\" -> \"b\" [label=\"semantic\""));
+        assert!(!dot.contains("temporal"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_graphml_export_escapes_xml() {
+        let a = node("a", "R&D <team>", None);
+        let mut out = Vec::new();
+        write_graph(&mut out, GraphFormat::Graphml, "bank", &[&a], &[], &[]).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<data key=\"text\">R&amp;D &lt;team&gt;</data>"));
+        assert!(xml.contains("<data key=\"fact_type\">unknown</data>"));
+        assert!(xml.trim_end().ends_with("</graphml>"));
+    }
+}
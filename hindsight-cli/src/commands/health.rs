@@ -1,7 +1,8 @@
 //! Health and metrics commands.
 
-use anyhow::Result;
-use serde::Deserialize;
+use anyhow::{Context, Result};
+use hindsight_client::types::FeaturesInfo;
+use serde::{Deserialize, Serialize};
 
 use crate::api::ApiClient;
 use crate::output::{self, OutputFormat};
@@ -75,43 +76,122 @@ pub fn health(
     }
 }
 
-/// Get API version information
+/// How the server's version relates to the spec this CLI was built against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Compatibility {
+    Compatible,
+    /// The server may have features and fields this CLI doesn't know about
+    ServerNewer,
+    /// Commands using newer endpoints may fail
+    ServerOlder,
+    Unknown,
+}
+
+impl Compatibility {
+    /// Compare `major.minor`; patch releases don't change the API. Before 1.0 each
+    /// minor release may, so that's the level compared.
+    fn check(spec: &str, server: &str) -> Self {
+        let major_minor = |version: &str| -> Option<(u64, u64)> {
+            let mut parts = version.trim().trim_start_matches('v').split('.');
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next()?.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()?;
+            Some((major, minor))
+        };
+        match (major_minor(spec), major_minor(server)) {
+            (Some(spec), Some(server)) => match server.cmp(&spec) {
+                std::cmp::Ordering::Equal => Compatibility::Compatible,
+                std::cmp::Ordering::Greater => Compatibility::ServerNewer,
+                std::cmp::Ordering::Less => Compatibility::ServerOlder,
+            },
+            _ => Compatibility::Unknown,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Compatibility::Compatible => "compatible",
+            Compatibility::ServerNewer => "server is newer; some features may be missing here",
+            Compatibility::ServerOlder => "server is older; newer commands may fail",
+            Compatibility::Unknown => "unknown (unrecognized version string)",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct VersionReport {
+    cli_version: &'static str,
+    client_version: &'static str,
+    spec_version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    server_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    features: Option<FeaturesInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compatibility: Option<Compatibility>,
+}
+
+/// Show the CLI, client library and spec versions, and unless `client_only` the
+/// server's version with a compatibility verdict
 pub fn version(
     client: &ApiClient,
+    client_only: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Fetching version..."))
-    } else {
-        None
+    let mut report = VersionReport {
+        cli_version: env!("CARGO_PKG_VERSION"),
+        client_version: hindsight_client::CLIENT_VERSION,
+        spec_version: hindsight_client::SPEC_VERSION,
+        server_version: None,
+        features: None,
+        compatibility: None,
     };
 
-    let response = client.get_version(verbose);
-
-    if let Some(mut sp) = spinner {
-        sp.finish();
+    if !client_only {
+        let spinner = if output_format == OutputFormat::Pretty {
+            Some(ui::create_spinner("Fetching version..."))
+        } else {
+            None
+        };
+        let response = client.get_version(verbose);
+        if let Some(mut sp) = spinner {
+            sp.finish();
+        }
+        let server = response.context(
+            "Could not get the server's version; pass --client-only to show the local versions",
+        )?;
+        report.compatibility = Some(Compatibility::check(report.spec_version, &server.api_version));
+        report.server_version = Some(server.api_version);
+        report.features = Some(server.features);
     }
 
-    match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_section_header("API Version");
-                println!("  {} {}", ui::dim("Version:"), result.api_version);
+    if output_format != OutputFormat::Pretty {
+        return output::print_output(&report, output_format);
+    }
 
-                println!();
-                println!("  {}", ui::dim("Features:"));
-                println!("    {} MCP Server: {}", ui::gradient_start("•"), if result.features.mcp { "enabled" } else { "disabled" });
-                println!("    {} Observations: {}", ui::gradient_start("•"), if result.features.observations { "enabled" } else { "disabled" });
-                println!("    {} Background Worker: {}", ui::gradient_start("•"), if result.features.worker { "enabled" } else { "disabled" });
-                println!();
-            } else {
-                output::print_output(&result, output_format)?;
-            }
-            Ok(())
-        }
-        Err(e) => Err(e),
+    ui::print_section_header("Versions");
+    println!("  {} {}", ui::dim("CLI:        "), report.cli_version);
+    println!("  {} {}", ui::dim("Client:     "), report.client_version);
+    println!("  {} {}", ui::dim("API spec:   "), report.spec_version);
+    if let (Some(server), Some(compatibility)) = (&report.server_version, report.compatibility) {
+        let verdict = match compatibility {
+            Compatibility::Compatible => ui::gradient_start(compatibility.describe()),
+            _ => ui::gradient_end(compatibility.describe()),
+        };
+        println!("  {} {} ({})", ui::dim("Server:     "), server, verdict);
     }
+    if let Some(features) = &report.features {
+        let state = |on: bool| if on { "enabled" } else { "disabled" };
+        println!();
+        println!("  {}", ui::dim("Server features:"));
+        println!("    {} MCP Server: {}", ui::gradient_start("•"), state(features.mcp));
+        let observations = state(features.observations);
+        println!("    {} Observations: {}", ui::gradient_start("•"), observations);
+        println!("    {} Background Worker: {}", ui::gradient_start("•"), state(features.worker));
+    }
+    println!();
+    Ok(())
 }
 
 /// Get Prometheus metrics
@@ -152,6 +232,15 @@ pub fn metrics(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_compatibility_compares_major_minor() {
+        assert_eq!(Compatibility::check("0.6.1", "0.6.4"), Compatibility::Compatible);
+        assert_eq!(Compatibility::check("0.6.1", "v0.7.0"), Compatibility::ServerNewer);
+        assert_eq!(Compatibility::check("0.6.1", "0.5.9"), Compatibility::ServerOlder);
+        assert_eq!(Compatibility::check("0.6.1", "0.6.0rc1"), Compatibility::Compatible);
+        assert_eq!(Compatibility::check("0.6.1", "dev"), Compatibility::Unknown);
+    }
+
     #[test]
     fn test_health_response_deserialization() {
         let json = r#"{
@@ -0,0 +1,753 @@
+use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::api::{content_document_id, ApiClient, MemoryItem, RetainRequest};
+use crate::executor::{self, PlannedWrite};
+use crate::output::{self, csv_escape, OutputFormat};
+use crate::ui;
+
+/// Progress of an import, persisted next to the input so a crashed run can resume
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ImportState {
+    /// Last input line (1-based) whose batch was accepted by the server
+    last_committed_line: u64,
+    /// Records ingested so far
+    committed: u64,
+}
+
+impl ImportState {
+    fn path_for(input: &Path) -> PathBuf {
+        let mut name = input.as_os_str().to_owned();
+        name.push(".hindsight-state");
+        PathBuf::from(name)
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file: {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Corrupt state file: {}", path.display()))
+    }
+
+    /// Write via temp file + rename so a crash never leaves a half-written state
+    fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("hindsight-state.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to write state file: {}", path.display()))
+    }
+}
+
+/// A rejected input record with its line number
+#[derive(Debug, Serialize)]
+struct Rejected {
+    line: u64,
+    reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ImportSummary {
+    imported: u64,
+    /// Records already in the bank, with `--skip-duplicates`
+    skipped_duplicates: u64,
+    rejected: Vec<Rejected>,
+    resumed_from_line: u64,
+    operation_ids: Vec<String>,
+}
+
+/// Convert one JSONL record into a retain item.
+///
+/// Accepts `text` (or `content`) plus optional `context`, `occurred` (or
+/// `timestamp`), `document_id`, `tags`, `metadata` and a `type` hint.
+fn record_to_item(record: &Map<String, Value>) -> std::result::Result<MemoryItem, String> {
+    let text = record
+        .get("text")
+        .or_else(|| record.get("content"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| "missing or empty \"text\" field".to_string())?;
+
+    let occurred = match record.get("occurred").or_else(|| record.get("timestamp")) {
+        Some(Value::String(s)) => Some(
+            crate::utils::parse_datetime_arg(s)
+                .map_err(|e| e.to_string())?
+                .to_rfc3339(),
+        ),
+        Some(Value::Null) | None => None,
+        Some(other) => return Err(format!("invalid occurred value: {}", other)),
+    };
+
+    let mut metadata: Map<String, Value> = match record.get("metadata") {
+        Some(Value::Object(m)) => m
+            .iter()
+            .map(|(k, v)| {
+                let v = v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                (k.clone(), Value::String(v))
+            })
+            .collect(),
+        Some(Value::Null) | None => Map::new(),
+        Some(_) => return Err("\"metadata\" must be an object".to_string()),
+    };
+    if let Some(t) = record.get("type").or_else(|| record.get("fact_type")).and_then(|v| v.as_str()) {
+        metadata.insert("fact_type".to_string(), Value::String(t.to_string()));
+    }
+
+    serde_json::from_value(serde_json::json!({
+        "content": text,
+        "context": record.get("context").and_then(|v| v.as_str()),
+        "timestamp": occurred,
+        "document_id": record.get("document_id").and_then(|v| v.as_str()),
+        "tags": record.get("tags").filter(|v| v.is_array()),
+        "metadata": if metadata.is_empty() { None } else { Some(metadata) },
+    }))
+    .map_err(|e| e.to_string())
+}
+
+fn throughput_bar(total_bytes: u64, output_format: OutputFormat) -> ProgressBar {
+    if output_format != OutputFormat::Pretty || ui::context().quiet {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new(total_bytes);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+            .unwrap()
+            .progress_chars("█▓▒░ "),
+    );
+    pb
+}
+
+/// How import batches are sent
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IngestOptions {
+    /// Skip records whose normalized text was already imported with this option. Each
+    /// record without a document ID is stored under `content-<sha256>`, which later runs
+    /// look up.
+    pub skip_duplicates: bool,
+}
+
+/// Hashes of the records already imported, when skipping duplicates
+fn known_hashes(
+    client: &ApiClient,
+    bank_id: &str,
+    options: IngestOptions,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<Option<HashSet<String>>> {
+    if !options.skip_duplicates {
+        return Ok(None);
+    }
+    let pretty = output_format == OutputFormat::Pretty;
+    let spinner = pretty.then(|| ui::create_spinner("Reading previously imported records..."));
+    let known = client.ingested_content_hashes(bank_id, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let known = known?;
+    if pretty && !known.is_empty() {
+        ui::print_warning(&format!(
+            "The server can't look up records by hash, so all {} previously imported record(s) \
+             were listed first; this gets slower as the bank grows",
+            known.len()
+        ));
+    }
+    Ok(Some(known))
+}
+
+/// Drop records whose hash is in `known` (adding the rest), and name each remaining
+/// record without a document ID after its hash. Records with their own document ID
+/// are left alone; the server replaces that document on a re-import.
+fn skip_duplicates(batch: &mut Vec<MemoryItem>, known: &mut HashSet<String>) -> u64 {
+    let before = batch.len();
+    batch.retain_mut(|item| {
+        if item.document_id.is_some() {
+            return true;
+        }
+        let hash = crate::utils::text_hash(&item.content);
+        let fresh = known.insert(hash.clone());
+        item.document_id = Some(content_document_id(&hash));
+        fresh
+    });
+    (before - batch.len()) as u64
+}
+
+fn send_batch(
+    client: &ApiClient,
+    bank_id: &str,
+    batch: &mut Vec<MemoryItem>,
+    known: Option<&mut HashSet<String>>,
+    r#async: bool,
+    verbose: bool,
+    summary: &mut ImportSummary,
+) -> Result<()> {
+    if let Some(known) = known {
+        summary.skipped_duplicates += skip_duplicates(batch, known);
+        if batch.is_empty() {
+            return Ok(());
+        }
+    }
+    let request = RetainRequest {
+        items: std::mem::take(batch),
+        async_: r#async,
+        document_tags: None,
+    };
+    let count = request.items.len() as u64;
+    let plan = PlannedWrite::new("retain", bank_id).count(count as usize);
+    let written = executor::get().execute(plan, || {
+        client.retain(bank_id, &request, r#async, verbose)
+    })?;
+    // Skipped under --dry-run
+    let Some(result) = written else {
+        return Ok(());
+    };
+    summary.imported += count;
+    if let Some(op) = result.operation_id {
+        summary.operation_ids.push(op);
+    }
+    Ok(())
+}
+
+/// Stream a JSONL file into a bank in batches, checkpointing after each batch
+#[allow(clippy::too_many_arguments)]
+pub fn jsonl(
+    client: &ApiClient,
+    bank_id: &str,
+    path: &Path,
+    batch_size: usize,
+    resume: bool,
+    rejects: Option<PathBuf>,
+    r#async: bool,
+    options: IngestOptions,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let dry_run = executor::get().is_dry_run();
+    let state_path = ImportState::path_for(path);
+    let mut state = if state_path.exists() {
+        if !resume {
+            anyhow::bail!(
+                "Found state from a previous run at {}. Pass --resume to continue it, or delete the file to start over.",
+                state_path.display()
+            );
+        }
+        ImportState::load(&state_path)?
+    } else {
+        ImportState::default()
+    };
+
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let reader = BufReader::new(file);
+
+    let mut rejects_file = match &rejects {
+        Some(p) => Some(
+            OpenOptions::new()
+                .create(true)
+                .append(resume)
+                .write(true)
+                .truncate(!resume)
+                .open(p)
+                .with_context(|| format!("Failed to open rejects file: {}", p.display()))?,
+        ),
+        None => None,
+    };
+
+    let already_committed = state.committed;
+    let mut summary = ImportSummary {
+        resumed_from_line: state.last_committed_line,
+        ..Default::default()
+    };
+    if resume && state.last_committed_line > 0 && output_format == OutputFormat::Pretty {
+        ui::print_info(&format!(
+            "Resuming after line {} ({} records already imported)",
+            state.last_committed_line, state.committed
+        ));
+    }
+
+    let mut known = known_hashes(client, bank_id, options, verbose, output_format)?;
+    let pb = throughput_bar(total_bytes, output_format);
+    let started = Instant::now();
+    let batch_size = batch_size.max(1);
+    let mut batch: Vec<MemoryItem> = Vec::with_capacity(batch_size);
+    let mut line_no: u64 = 0;
+
+    for line in reader.lines() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        line_no += 1;
+        pb.inc(line.len() as u64 + 1);
+        if line_no <= state.last_committed_line || line.trim().is_empty() {
+            continue;
+        }
+
+        let parsed = serde_json::from_str::<Value>(&line)
+            .map_err(|e| format!("invalid JSON: {}", e))
+            .and_then(|v| match v {
+                Value::Object(record) => record_to_item(&record),
+                _ => Err("expected a JSON object".to_string()),
+            });
+
+        match parsed {
+            Ok(item) => batch.push(item),
+            Err(reason) => {
+                pb.println(format!("line {}: {}", line_no, reason));
+                if let Some(f) = rejects_file.as_mut() {
+                    writeln!(f, "{}", line)?;
+                }
+                summary.rejected.push(Rejected { line: line_no, reason });
+            }
+        }
+
+        if batch.len() >= batch_size {
+            send_batch(
+                client,
+                bank_id,
+                &mut batch,
+                known.as_mut(),
+                r#async,
+                verbose,
+                &mut summary,
+            )?;
+            state.last_committed_line = line_no;
+            state.committed = already_committed + summary.imported;
+            if !dry_run {
+                state.save(&state_path)?;
+            }
+            let rate = summary.imported as f64 / started.elapsed().as_secs_f64().max(0.001);
+            pb.set_message(format!("{} records ({:.0}/s)", summary.imported, rate));
+        }
+    }
+
+    if !batch.is_empty() {
+        send_batch(
+            client,
+            bank_id,
+            &mut batch,
+            known.as_mut(),
+            r#async,
+            verbose,
+            &mut summary,
+        )?;
+    }
+    pb.finish_and_clear();
+
+    // The planned retains are printed instead
+    if dry_run {
+        return Ok(());
+    }
+
+    // Completed runs don't need a checkpoint anymore
+    if state_path.exists() {
+        fs::remove_file(&state_path).ok();
+    }
+
+    print_summary(&summary, rejects.as_deref(), started, output_format)
+}
+
+fn print_summary(
+    summary: &ImportSummary,
+    rejects: Option<&Path>,
+    started: Instant,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if output_format == OutputFormat::Pretty {
+        let secs = started.elapsed().as_secs_f64().max(0.001);
+        ui::print_success(&format!(
+            "Imported {} record(s) in {:.1}s ({:.0} records/s)",
+            summary.imported,
+            secs,
+            summary.imported as f64 / secs
+        ));
+        if summary.skipped_duplicates > 0 {
+            ui::print_info(&format!(
+                "Skipped {} record(s) already in the bank",
+                summary.skipped_duplicates
+            ));
+        }
+        if !summary.rejected.is_empty() {
+            ui::print_warning(&format!("{} record(s) rejected", summary.rejected.len()));
+            if let Some(path) = rejects {
+                println!("  {} {}", ui::dim("Rejects written to:"), path.display());
+            }
+        }
+    } else {
+        output::print_output(summary, output_format)?;
+    }
+    Ok(())
+}
+
+/// Memory fields a CSV column can be mapped onto with `--map field=column`
+const CSV_FIELDS: &[&str] = &["text", "context", "occurred", "type", "document_id", "tags"];
+
+/// Parse RFC 4180 CSV into records, each tagged with the line it starts on.
+///
+/// Quoted fields may contain commas, doubled quotes and line breaks.
+fn parse_csv(input: &str) -> Vec<(u64, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line: u64 = 1;
+    let mut record_line: u64 = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                if !(record.len() == 1 && record[0].is_empty()) {
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    records
+}
+
+/// Parse `field=column` mappings, validating field names
+fn parse_csv_mappings(maps: &[String]) -> Result<Vec<(String, String)>> {
+    let mut mappings = Vec::new();
+    for m in maps {
+        let (field, column) = m
+            .split_once('=')
+            .with_context(|| format!("Invalid --map '{}': expected field=column", m))?;
+        let field = field.trim().to_lowercase();
+        if !CSV_FIELDS.contains(&field.as_str()) {
+            anyhow::bail!(
+                "Unknown field '{}' in --map. Valid fields: {}",
+                field,
+                CSV_FIELDS.join(", ")
+            );
+        }
+        mappings.push((field, column.trim().to_string()));
+    }
+    if !mappings.iter().any(|(f, _)| f == "text") {
+        anyhow::bail!("A text column is required: pass --map text=<column>");
+    }
+    Ok(mappings)
+}
+
+/// Resolve mapped columns to indexes. Columns are header names, or 1-based
+/// positions for files without a header row. Returns whether the first
+/// record is a header.
+fn resolve_csv_columns(
+    mappings: &[(String, String)],
+    first: &[String],
+) -> Result<(Vec<(String, usize)>, bool)> {
+    let all_numeric = mappings.iter().all(|(_, c)| c.parse::<usize>().is_ok());
+    let header_matches = mappings
+        .iter()
+        .all(|(_, c)| first.iter().any(|h| h.trim() == c));
+    if header_matches {
+        let resolved = mappings
+            .iter()
+            .map(|(f, c)| (f.clone(), first.iter().position(|h| h.trim() == c).unwrap()))
+            .collect();
+        return Ok((resolved, true));
+    }
+    if all_numeric {
+        let resolved = mappings
+            .iter()
+            .map(|(f, c)| {
+                let idx: usize = c.parse().unwrap();
+                if idx == 0 {
+                    anyhow::bail!("Column positions are 1-based (got 0 for '{}')", f);
+                }
+                Ok((f.clone(), idx - 1))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return Ok((resolved, false));
+    }
+    anyhow::bail!(
+        "Mapped columns not found in the header row. Available columns: {}",
+        first.join(", ")
+    )
+}
+
+fn parse_csv_date(value: &str, date_format: Option<&str>) -> std::result::Result<String, String> {
+    if let Some(fmt) = date_format {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, fmt) {
+            return Ok(dt.and_utc().to_rfc3339());
+        }
+        return chrono::NaiveDate::parse_from_str(value, fmt)
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc().to_rfc3339())
+            .map_err(|_| format!("date '{}' does not match format '{}'", value, fmt));
+    }
+    crate::utils::parse_datetime_arg(value)
+        .map(|dt| dt.to_rfc3339())
+        .map_err(|_| format!("unparseable date '{}'", value))
+}
+
+/// Map one CSV row onto a memory record and validate it
+fn csv_row_to_item(
+    row: &[String],
+    columns: &[(String, usize)],
+    date_format: Option<&str>,
+    default_type: Option<&str>,
+) -> std::result::Result<MemoryItem, String> {
+    let mut record = Map::new();
+    for (field, idx) in columns {
+        let value = row.get(*idx).map(|v| v.trim()).unwrap_or("");
+        if value.is_empty() {
+            continue;
+        }
+        let value = match field.as_str() {
+            "occurred" => Value::String(parse_csv_date(value, date_format)?),
+            "tags" => Value::Array(
+                value
+                    .split([';', ','])
+                    .map(|t| t.trim())
+                    .filter(|t| !t.is_empty())
+                    .map(|t| Value::String(t.to_string()))
+                    .collect(),
+            ),
+            _ => Value::String(value.to_string()),
+        };
+        record.insert(field.clone(), value);
+    }
+    if !record.contains_key("type") {
+        if let Some(t) = default_type {
+            record.insert("type".to_string(), Value::String(t.to_string()));
+        }
+    }
+    record_to_item(&record)
+}
+
+/// Import memories from a CSV file with `--map field=column` mappings
+#[allow(clippy::too_many_arguments)]
+pub fn csv(
+    client: &ApiClient,
+    bank_id: &str,
+    path: &Path,
+    maps: &[String],
+    date_format: Option<String>,
+    default_type: Option<String>,
+    batch_size: usize,
+    rejects: Option<PathBuf>,
+    r#async: bool,
+    options: IngestOptions,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mappings = parse_csv_mappings(maps)?;
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let records = parse_csv(&content);
+    let Some((_, first)) = records.first() else {
+        ui::print_warning("CSV file is empty");
+        return Ok(());
+    };
+    let (columns, has_header) = resolve_csv_columns(&mappings, first)?;
+
+    let mut rejects_file = match &rejects {
+        Some(p) => {
+            let mut f = File::create(p)
+                .with_context(|| format!("Failed to create rejects file: {}", p.display()))?;
+            if has_header {
+                let header: Vec<String> = first.iter().map(|h| csv_escape(h)).collect();
+                writeln!(f, "{}", header.join(","))?;
+            }
+            Some(f)
+        }
+        None => None,
+    };
+
+    let rows = if has_header { &records[1..] } else { &records[..] };
+    let mut summary = ImportSummary::default();
+    let mut valid: Vec<MemoryItem> = Vec::new();
+    for (line, row) in rows {
+        match csv_row_to_item(row, &columns, date_format.as_deref(), default_type.as_deref()) {
+            Ok(item) => valid.push(item),
+            Err(reason) => {
+                if output_format == OutputFormat::Pretty {
+                    ui::print_warning(&format!("row at line {}: {}", line, reason));
+                }
+                if let Some(f) = rejects_file.as_mut() {
+                    let escaped: Vec<String> = row.iter().map(|v| csv_escape(v)).collect();
+                    writeln!(f, "{}", escaped.join(","))?;
+                }
+                summary.rejected.push(Rejected { line: *line, reason });
+            }
+        }
+    }
+
+    let started = Instant::now();
+    if executor::get().is_dry_run() && output_format == OutputFormat::Pretty {
+        ui::print_info(&format!(
+            "Dry run: {} valid row(s), {} rejected",
+            valid.len(),
+            summary.rejected.len()
+        ));
+    }
+
+    let mut known = known_hashes(client, bank_id, options, verbose, output_format)?;
+    let pb = if output_format == OutputFormat::Pretty {
+        ui::create_progress_bar(valid.len() as u64, "Importing")
+    } else {
+        ProgressBar::hidden()
+    };
+    for chunk in valid.chunks(batch_size.max(1)) {
+        let mut batch = chunk.to_vec();
+        send_batch(
+            client,
+            bank_id,
+            &mut batch,
+            known.as_mut(),
+            r#async,
+            verbose,
+            &mut summary,
+        )?;
+        pb.inc(chunk.len() as u64);
+    }
+    pb.finish_and_clear();
+
+    // The planned retains are printed instead
+    if executor::get().is_dry_run() {
+        return Ok(());
+    }
+
+    print_summary(&summary, rejects.as_deref(), started, output_format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(json: Value) -> Map<String, Value> {
+        json.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_record_to_item_minimal() {
+        let item = record_to_item(&record(serde_json::json!({"text": "Alice likes tea"}))).unwrap();
+        assert_eq!(item.content, "Alice likes tea");
+        assert!(item.metadata.is_none());
+    }
+
+    #[test]
+    fn test_record_to_item_rejects_missing_text() {
+        let err = record_to_item(&record(serde_json::json!({"context": "chat"}))).unwrap_err();
+        assert!(err.contains("text"));
+        assert!(record_to_item(&record(serde_json::json!({"text": "   "}))).is_err());
+    }
+
+    #[test]
+    fn test_record_to_item_type_hint_and_bad_date() {
+        let item = record_to_item(&record(serde_json::json!({
+            "content": "note",
+            "type": "opinion",
+        })))
+        .unwrap();
+        assert_eq!(
+            item.metadata.unwrap().get("fact_type").map(String::as_str),
+            Some("opinion")
+        );
+        assert!(record_to_item(&record(serde_json::json!({
+            "text": "note",
+            "occurred": "not a date",
+        })))
+        .is_err());
+    }
+
+    #[test]
+    fn test_skip_duplicates_names_records_by_hash() {
+        let item = |json: Value| record_to_item(&record(json)).unwrap();
+        let mut known = HashSet::from([crate::utils::text_hash("Alice likes tea")]);
+        let mut batch = vec![
+            item(serde_json::json!({"text": "alice  LIKES tea"})),
+            item(serde_json::json!({"text": "Bob likes coffee"})),
+            item(serde_json::json!({"text": "Bob likes  coffee"})),
+            item(serde_json::json!({"text": "Alice likes tea", "document_id": "d1"})),
+        ];
+        assert_eq!(skip_duplicates(&mut batch, &mut known), 2);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(
+            batch[0].document_id,
+            Some(content_document_id(&crate::utils::text_hash("Bob likes coffee")))
+        );
+        assert_eq!(batch[1].document_id.as_deref(), Some("d1"));
+    }
+
+    #[test]
+    fn test_state_path() {
+        assert_eq!(
+            ImportState::path_for(Path::new("/tmp/data.jsonl")),
+            PathBuf::from("/tmp/data.jsonl.hindsight-state")
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let input = "name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\nBob,plain\n";
+        let records = parse_csv(input);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].0, 2);
+        assert_eq!(records[1].1, vec!["Smith, J", "said \"hi\"\nthen left"]);
+        assert_eq!(records[2].0, 4);
+        assert_eq!(records[2].1, vec!["Bob", "plain"]);
+    }
+
+    #[test]
+    fn test_resolve_csv_columns_header_and_positions() {
+        let mappings = parse_csv_mappings(&["text=note_body".to_string(), "occurred=date".to_string()]).unwrap();
+        let header = vec!["date".to_string(), "note_body".to_string()];
+        let (cols, has_header) = resolve_csv_columns(&mappings, &header).unwrap();
+        assert!(has_header);
+        assert_eq!(cols, vec![("text".to_string(), 1), ("occurred".to_string(), 0)]);
+
+        let mappings = parse_csv_mappings(&["text=2".to_string()]).unwrap();
+        let (cols, has_header) = resolve_csv_columns(&mappings, &["x".to_string(), "y".to_string()]).unwrap();
+        assert!(!has_header);
+        assert_eq!(cols, vec![("text".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_parse_csv_mappings_validation() {
+        assert!(parse_csv_mappings(&["context=c".to_string()]).is_err());
+        assert!(parse_csv_mappings(&["body=c".to_string(), "text=t".to_string()]).is_err());
+        assert!(parse_csv_mappings(&["text".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_csv_row_to_item_date_format() {
+        let cols = vec![("text".to_string(), 0), ("occurred".to_string(), 1)];
+        let row = vec!["note".to_string(), "15/06/2024".to_string()];
+        assert!(csv_row_to_item(&row, &cols, Some("%d/%m/%Y"), None).is_ok());
+        assert!(csv_row_to_item(&row, &cols, None, None).is_err());
+        let empty = vec!["".to_string(), "".to_string()];
+        assert!(csv_row_to_item(&empty, &cols, None, None).is_err());
+    }
+}
@@ -1,19 +1,30 @@
 use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{IsTerminal, Read};
 use std::path::PathBuf;
 use walkdir::WalkDir;
 
-use crate::api::{ApiClient, MemoryItem, RecallRequest, ReflectRequest, RetainRequest};
+use crate::api::{
+    ApiClient, GraphExport, GraphNode, LinkItem, MemoryItem, MemoryListItem, RecallRequest,
+    RecallResult, ReflectRequest, RetainRequest, LINK_GRAPH_LIMIT,
+};
+use crate::commands::{bank, reflect};
 use crate::config;
+use crate::executor::{self, PlannedWrite};
 use crate::output::{self, OutputFormat};
+use crate::pager;
+use crate::trace;
 use crate::ui;
 
 // Import types from generated client
+use hindsight_client::enums::{Budget, FactTypesItem};
 use hindsight_client::types::{
-    Budget, ChunkIncludeOptions, FactsIncludeOptions, IncludeOptions, ReflectIncludeOptions,
-    TagsMatch,
+    ChunkIncludeOptions, FactsIncludeOptions, IncludeOptions, ReflectIncludeOptions, TagsMatch,
+    ToolCallsIncludeOptions,
 };
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json;
 
 // Local types for serde_json::Value deserialization
@@ -38,7 +49,7 @@ struct EntityRef {
 }
 
 // Helper function to parse budget string to Budget enum
-fn parse_budget(budget: &str) -> Budget {
+pub(crate) fn parse_budget(budget: &str) -> Budget {
     match budget.to_lowercase().as_str() {
         "low" => Budget::Low,
         "high" => Budget::High,
@@ -47,7 +58,7 @@ fn parse_budget(budget: &str) -> Budget {
 }
 
 // Helper function to parse tags_match string to TagsMatch enum
-fn parse_tags_match(tags_match: &Option<String>) -> TagsMatch {
+pub(crate) fn parse_tags_match(tags_match: &Option<String>) -> TagsMatch {
     match tags_match
         .as_deref()
         .unwrap_or("any")
@@ -61,7 +72,140 @@ fn parse_tags_match(tags_match: &Option<String>) -> TagsMatch {
     }
 }
 
+/// Fact types recall can search, in the order the server lists them
+pub(crate) const FACT_TYPES: [FactTypesItem; 3] = [
+    FactTypesItem::World,
+    FactTypesItem::Experience,
+    FactTypesItem::Observation,
+];
+
+/// Parse one `--type`, `--types` or `--exclude-types` value, rejecting unknown fact types
+fn parse_fact_type(value: &str) -> Result<FactTypesItem> {
+    match FactTypesItem::from(value.trim().to_lowercase()) {
+        FactTypesItem::Unknown(value) if value == "opinion" => anyhow::bail!(
+            "Unknown fact type 'opinion': opinions are now observations, use 'observation'"
+        ),
+        FactTypesItem::Unknown(value) => anyhow::bail!(
+            "Unknown fact type '{}': expected world, experience or observation",
+            value
+        ),
+        fact_type => Ok(fact_type),
+    }
+}
+
+/// Validate a `memory add --type` hint, normalized to the name the server uses
+pub(crate) fn parse_fact_type_hint(value: Option<&str>) -> Result<Option<String>> {
+    value
+        .map(|value| parse_fact_type(value).map(|t| t.to_string()))
+        .transpose()
+}
+
+/// The fact types a recall sends, from `--types` or everything but `--exclude-types`.
+///
+/// Empty means neither flag was given, leaving the choice to the server.
+pub(crate) fn resolve_fact_types(types: &[String], exclude: &[String]) -> Result<Vec<String>> {
+    let parse_all = |values: &[String]| -> Result<Vec<FactTypesItem>> {
+        values.iter().map(|value| parse_fact_type(value)).collect()
+    };
+    let types = parse_all(types)?;
+    let exclude = parse_all(exclude)?;
+    let selected: Vec<FactTypesItem> = if exclude.is_empty() {
+        types
+    } else {
+        let remaining: Vec<_> = FACT_TYPES
+            .into_iter()
+            .filter(|t| !exclude.contains(t))
+            .collect();
+        if remaining.is_empty() {
+            anyhow::bail!("--exclude-types leaves no fact types to search");
+        }
+        remaining
+    };
+    let mut resolved: Vec<String> = Vec::new();
+    for fact_type in selected {
+        if !resolved.iter().any(|t| t == fact_type.as_str()) {
+            resolved.push(fact_type.to_string());
+        }
+    }
+    Ok(resolved)
+}
+
+/// Page size used when `--all` walks every page of a bank
+const LIST_ALL_PAGE_SIZE: i64 = 500;
+
+/// Client-side filters for memory listings; the list endpoint only filters by type and text.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MemoryFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub document_id: Option<String>,
+    pub contains: Option<String>,
+}
+
+impl MemoryFilter {
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none()
+            && self.until.is_none()
+            && self.document_id.is_none()
+            && self.contains.is_none()
+    }
+
+    pub fn matches(&self, bank_id: &str, item: &MemoryListItem) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Some(ts) = memory_timestamp(item) else {
+                return false;
+            };
+            if self.since.is_some_and(|since| ts < since) || self.until.is_some_and(|until| ts > until) {
+                return false;
+            }
+        }
+        if let Some(doc_id) = &self.document_id {
+            let direct = item.document_id.as_deref();
+            // List items don't always carry document_id, but chunk IDs are
+            // "<bank>_<document>_<index>" so they identify the source document.
+            let via_chunk = item
+                .chunk_id
+                .as_deref()
+                .and_then(|c| c.strip_prefix(&format!("{}_{}_", bank_id, doc_id)))
+                .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()));
+            if direct != Some(doc_id.as_str()) && !via_chunk {
+                return false;
+            }
+        }
+        if let Some(needle) = &self.contains {
+            if !item.text.to_lowercase().contains(&needle.to_lowercase()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Best-effort timestamp of a listed memory (mention time, falling back to event dates)
+pub(crate) fn memory_timestamp(item: &MemoryListItem) -> Option<DateTime<Utc>> {
+    [&item.mentioned_at, &item.created_at, &item.occurred_start, &item.date]
+        .into_iter()
+        .filter_map(|field| field.as_deref())
+        .find_map(parse_timestamp)
+}
+
+/// Memory fields holding timestamps, shown in the `--dates` style in pretty output
+const MEMORY_DATE_FIELDS: &[&str] =
+    &["created_at", "mentioned_at", "occurred_start", "occurred_end"];
+
+/// Default columns of `memory list` tables
+const MEMORY_LIST_COLUMNS: &[&str] = &["fact_type", "mentioned_at", "text"];
+
+/// Parse an API timestamp (RFC 3339, or naive ISO 8601 taken as UTC)
+pub(crate) fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").map(|dt| dt.and_utc()))
+        .ok()
+}
+
 /// List memory units with pagination and optional filters
+#[allow(clippy::too_many_arguments)]
 pub fn list(
     client: &ApiClient,
     bank_id: &str,
@@ -69,84 +213,629 @@ pub fn list(
     query: Option<String>,
     limit: i64,
     offset: i64,
+    since: Option<String>,
+    until: Option<String>,
+    document_id: Option<String>,
+    contains: Option<String>,
+    all: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
+    let filter = MemoryFilter {
+        since: since.as_deref().map(crate::utils::parse_datetime_arg).transpose()?,
+        until: until.as_deref().map(crate::utils::parse_datetime_arg).transpose()?,
+        document_id,
+        contains,
+    };
+
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Fetching memories..."))
     } else {
         None
     };
 
-    let response = client.list_memories(
-        bank_id,
-        type_filter.as_deref(),
-        query.as_deref(),
-        Some(limit),
-        Some(offset),
-        verbose,
-    );
+    // Without client-side filters or --all a single page is exactly what was asked for;
+    // otherwise keep paging until enough matches are collected or the bank is exhausted.
+    // NDJSON writes each page's matches as soon as it arrives instead of collecting them.
+    let page_size = if all { LIST_ALL_PAGE_SIZE } else { limit };
+    let streaming = output_format == OutputFormat::Ndjson;
+    let mut items: Vec<MemoryListItem> = Vec::new();
+    let mut streamed = 0;
+    let mut total = 0;
+    let mut page_offset = offset;
+    let result: Result<()> = loop {
+        let page = match client.list_memories(
+            bank_id,
+            type_filter.as_deref(),
+            query.as_deref(),
+            Some(page_size),
+            Some(page_offset),
+            verbose,
+        ) {
+            Ok(page) => page,
+            Err(e) => break Err(e),
+        };
+        total = page.total;
+        let fetched = page.items.len() as i64;
+        // Filter every page rather than stopping at the first item before --since. The
+        // server sorts by mentioned_at, but --since compares a timestamp that falls back to
+        // other dates when mentioned_at is missing, so the pages aren't ordered by it
+        let mut matched: Vec<_> =
+            page.items.into_iter().filter(|item| filter.matches(bank_id, item)).collect();
+        page_offset += fetched;
+        if streaming {
+            if !all {
+                matched.truncate((limit - streamed).max(0) as usize);
+            }
+            if let Err(e) = output::write_ndjson(&matched) {
+                break Err(e);
+            }
+            streamed += matched.len() as i64;
+        } else {
+            items.extend(matched);
+        }
+
+        let collected = if streaming { streamed } else { items.len() as i64 };
+        if !all && (filter.is_empty() || collected >= limit) {
+            items.truncate(limit.max(0) as usize);
+            break Ok(());
+        }
+        if fetched < page_size || page_offset >= total {
+            break Ok(());
+        }
+    };
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
+    result?;
 
-    match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_section_header(&format!(
-                    "Memories: {} (showing {}-{})",
-                    bank_id,
-                    offset + 1,
-                    offset + result.items.len() as i64
-                ));
-
-                if result.items.is_empty() {
-                    println!("  {}", ui::dim("No memories found."));
+    if streaming {
+        return Ok(());
+    }
+    if output_format == OutputFormat::Table {
+        output::print_table(&items, MEMORY_LIST_COLUMNS)?;
+    } else if output_format == OutputFormat::Pretty {
+        pager::page(|| {
+            ui::print_section_header(&format!(
+                "Memories: {} (showing {}-{})",
+                bank_id,
+                if items.is_empty() { offset } else { offset + 1 },
+                offset + items.len() as i64
+            ));
+
+            if items.is_empty() {
+                println!("  {}", ui::dim("No memories found."));
+            } else {
+                let rows = ui::with_display_dates(&items, MEMORY_DATE_FIELDS);
+                output::print_table(&rows, MEMORY_LIST_COLUMNS)?;
+                println!();
+                if filter.is_empty() {
+                    println!("  {} {} total", ui::dim("Total:"), total);
                 } else {
-                    for item in &result.items {
-                        let fact_type = item
-                            .get("fact_type")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("unknown");
-                        let type_t = match fact_type {
-                            "world" => 0.0,
-                            "experience" => 0.5,
-                            "opinion" => 1.0,
-                            "observation" => 0.25,
-                            _ => 0.5,
-                        };
-
-                        let id = item.get("id").and_then(|v| v.as_str()).unwrap_or("unknown");
-
-                        println!(
-                            "  {} {}",
-                            ui::gradient(&format!("[{}]", fact_type.to_uppercase()), type_t),
-                            ui::dim(id)
-                        );
-
-                        // Truncate text if too long
-                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                            let text_preview: String = text.chars().take(100).collect();
-                            let ellipsis = if text.len() > 100 { "..." } else { "" };
-                            println!("    {}{}", text_preview, ellipsis);
-                        }
+                    println!(
+                        "  {} {} matching ({} in bank)",
+                        ui::dim("Total:"),
+                        items.len(),
+                        total
+                    );
+                }
+            }
+            Ok(())
+        })?;
+    } else {
+        let listing = serde_json::json!({
+            "items": items,
+            "total": total,
+            "limit": if all { None } else { Some(limit) },
+            "offset": offset,
+        });
+        output::print_output(&listing, output_format)?;
+    }
+    Ok(())
+}
 
-                        if let Some(doc_id) = item.get("document_id").and_then(|v| v.as_str()) {
-                            println!("    {} {}", ui::dim("doc:"), ui::dim(doc_id));
-                        }
-                        println!();
-                    }
+/// Fetch every memory in a bank that passes the type and client-side filters
+pub(crate) fn fetch_matching_memories(
+    client: &ApiClient,
+    bank_id: &str,
+    type_filter: Option<&str>,
+    filter: &MemoryFilter,
+    verbose: bool,
+) -> Result<Vec<MemoryListItem>> {
+    let mut items = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = client.list_memories(
+            bank_id,
+            type_filter,
+            None,
+            Some(LIST_ALL_PAGE_SIZE),
+            Some(offset),
+            verbose,
+        )?;
+        let fetched = page.items.len() as i64;
+        // Like `list`, filter every page rather than stopping at the first item before --since
+        items.extend(page.items.into_iter().filter(|item| filter.matches(bank_id, item)));
+        offset += fetched;
+        if fetched < LIST_ALL_PAGE_SIZE || offset >= page.total {
+            break;
+        }
+    }
+    Ok(items)
+}
 
-                    println!("  {} {} total", ui::dim("Total:"), result.total);
-                }
+/// Source document of a listed memory, from `document_id` or its
+/// `<bank>_<document>_<index>` chunk ID
+pub(crate) fn memory_document_id(bank_id: &str, item: &MemoryListItem) -> Option<String> {
+    if let Some(doc_id) = &item.document_id {
+        return Some(doc_id.clone());
+    }
+    let chunk_id = item.chunk_id.as_deref()?;
+    let (doc_id, index) = chunk_id
+        .strip_prefix(&format!("{}_", bank_id))?
+        .rsplit_once('_')?;
+    (!doc_id.is_empty() && index.chars().all(|c| c.is_ascii_digit())).then(|| doc_id.to_string())
+}
+
+/// Number of memories retained per request when copying
+const COPY_BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Default, Serialize)]
+struct CopySummary {
+    from: String,
+    to: String,
+    matched: usize,
+    copied: usize,
+    skipped_duplicates: usize,
+    skipped_empty: usize,
+    failed: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+    /// What `--move` deleted from the source bank
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_removal: Option<SourceRemoval>,
+}
+
+#[derive(Debug, Serialize)]
+struct SourceRemoval {
+    #[serde(flatten)]
+    deleted: bank::PruneSummary,
+    /// Copied memories left in the source: their document also holds memories that
+    /// weren't copied, or they have no document
+    kept_in_source: usize,
+}
+
+/// The memories a copy writes and the ones it skips
+struct CopyPlan<'a> {
+    /// Oldest first, so the destination sees memories in their original order
+    to_copy: Vec<&'a MemoryListItem>,
+    /// Memories whose text is in the destination once the copy is done, written or not
+    in_destination: Vec<MemoryListItem>,
+    skipped_duplicates: usize,
+    skipped_empty: usize,
+}
+
+/// Skip memories with no text and ones whose text is already in `existing` or earlier
+/// in `source`
+fn plan_copy<'a>(source: &'a [MemoryListItem], existing: &[MemoryListItem]) -> CopyPlan<'a> {
+    let mut known: HashSet<String> = existing
+        .iter()
+        .map(|item| crate::utils::text_hash(&item.text))
+        .collect();
+    let mut plan = CopyPlan {
+        to_copy: Vec::new(),
+        in_destination: Vec::new(),
+        skipped_duplicates: 0,
+        skipped_empty: 0,
+    };
+    for item in source.iter().rev() {
+        if item.text.trim().is_empty() {
+            plan.skipped_empty += 1;
+            continue;
+        }
+        if known.insert(crate::utils::text_hash(&item.text)) {
+            plan.to_copy.push(item);
+        } else {
+            plan.skipped_duplicates += 1;
+        }
+        plan.in_destination.push(item.clone());
+    }
+    plan
+}
+
+/// Copy filtered memories from one bank into another.
+///
+/// Memories are re-retained in the destination with their context and occurred
+/// date. Text already present in the destination is skipped. With `--move`, once
+/// every memory has been copied, the source documents whose memories were all copied
+/// are deleted, as `bank prune` does; the server cannot delete single memories.
+#[allow(clippy::too_many_arguments)]
+pub fn copy(
+    client: &ApiClient,
+    from: &str,
+    to: &str,
+    type_filter: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    contains: Option<String>,
+    move_memories: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let dry_run = executor::get().is_dry_run();
+    if from == to {
+        anyhow::bail!("Source and destination bank are the same");
+    }
+    let filter = MemoryFilter {
+        since: since.as_deref().map(crate::utils::parse_datetime_arg).transpose()?,
+        until: until.as_deref().map(crate::utils::parse_datetime_arg).transpose()?,
+        document_id: None,
+        contains,
+    };
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Scanning source and destination banks..."))
+    } else {
+        None
+    };
+    let scanned = fetch_matching_memories(client, from, type_filter.as_deref(), &filter, verbose)
+        .and_then(|source| {
+            let existing = fetch_matching_memories(client, to, None, &MemoryFilter::default(), verbose)?;
+            Ok((source, existing))
+        });
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let (source, existing) = scanned?;
+
+    let CopyPlan {
+        to_copy,
+        in_destination,
+        skipped_duplicates,
+        skipped_empty,
+    } = plan_copy(&source, &existing);
+    let mut summary = CopySummary {
+        from: from.to_string(),
+        to: to.to_string(),
+        matched: source.len(),
+        skipped_duplicates,
+        skipped_empty,
+        ..Default::default()
+    };
+
+    // Only documents left with nothing uncopied can be removed by a move
+    let removal = if move_memories {
+        Some(bank::plan_prune(client, from, &in_destination, verbose)?)
+    } else {
+        None
+    };
+    if let Some(removal) = &removal {
+        if !removal.documents.is_empty() {
+            let message = format!(
+                "Copy {} memories to '{}', then delete {} documents ({} memories) from '{}'? \
+                 This cannot be undone.",
+                to_copy.len(),
+                to,
+                removal.documents.len(),
+                removal.memories(),
+                from
+            );
+            if !ui::prompt_confirmation(&message)? {
+                ui::print_info("Operation cancelled");
+                return Ok(());
+            }
+        }
+    }
+
+    if dry_run && output_format == OutputFormat::Pretty {
+        ui::print_section_header(&format!("Dry run: copy {} → {}", from, to));
+        for item in &to_copy {
+            let fact_type = item.fact_type.as_deref().unwrap_or("unknown");
+            let text = output::truncate_to_width(&item.text, 100);
+            println!("  [{}] {}", fact_type.to_uppercase(), text);
+        }
+        println!();
+        println!(
+            "  {} {} would be copied, {} duplicate(s) and {} empty skipped",
+            ui::dim("Summary:"),
+            to_copy.len(),
+            summary.skipped_duplicates,
+            summary.skipped_empty
+        );
+        if let Some(removal) = &removal {
+            println!(
+                "  {} {} source document(s) ({} memories) would be deleted",
+                ui::dim("Move:   "),
+                removal.documents.len(),
+                removal.memories()
+            );
+        }
+    }
+
+    let pb = if output_format == OutputFormat::Pretty {
+        ui::create_progress_bar(to_copy.len() as u64, "Copying")
+    } else {
+        indicatif::ProgressBar::hidden()
+    };
+    let doc_prefix = format!("{}_copy", config::generate_doc_id());
+
+    for (batch_index, batch) in to_copy.chunks(COPY_BATCH_SIZE).enumerate() {
+        let items: Result<Vec<MemoryItem>> = batch
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                build_memory_item(
+                    &item.text,
+                    item.context.as_deref(),
+                    item.occurred_start.as_deref(),
+                    &format!("{}_{}", doc_prefix, batch_index * COPY_BATCH_SIZE + i),
+                    item.fact_type.as_deref(),
+                )
+            })
+            .collect();
+        let ids: Vec<String> = batch.iter().map(|item| item.id.clone()).collect();
+        let written = items.and_then(|items| {
+            let request = RetainRequest {
+                items,
+                async_: false,
+                document_tags: None,
+            };
+            let plan = PlannedWrite::new("retain", to).count(batch.len()).ids(ids);
+            executor::get().execute(plan, || client.retain(to, &request, false, verbose))
+        });
+        pb.inc(batch.len() as u64);
+
+        match written {
+            Ok(Some(_)) => summary.copied += batch.len(),
+            Ok(None) => {}
+            Err(e) => {
+                summary.failed += batch.len();
+                summary.errors.push(format!("{:#}", e));
+            }
+        }
+    }
+    pb.finish_and_clear();
+
+    // A partial copy leaves the source untouched, so nothing is lost
+    if let Some(removal) = removal.filter(|_| summary.failed == 0) {
+        let in_destination: Vec<&MemoryListItem> = in_destination.iter().collect();
+        summary.source_removal = Some(SourceRemoval {
+            deleted: bank::delete_prune_documents(
+                client,
+                from,
+                &removal.documents,
+                &in_destination,
+                "Removing from source",
+                verbose,
+                output_format,
+            ),
+            kept_in_source: removal.kept_partial_documents + removal.kept_no_document,
+        });
+    }
+
+    // The planned writes are printed instead
+    if dry_run {
+        return Ok(());
+    }
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_section_header(&format!("Copy Summary: {} → {}", from, to));
+        println!("  {} {}", ui::dim("Matched:           "), summary.matched);
+        println!("  {} {}", ui::dim("Copied:            "), summary.copied);
+        println!(
+            "  {} {}",
+            ui::dim("Skipped duplicates:"),
+            summary.skipped_duplicates
+        );
+        println!(
+            "  {} {}",
+            ui::dim("Skipped empty:     "),
+            summary.skipped_empty
+        );
+        println!("  {} {}", ui::dim("Failed:            "), summary.failed);
+        for error in &summary.errors {
+            ui::print_error(error);
+        }
+        if let Some(removal) = &summary.source_removal {
+            bank::print_prune_summary(&removal.deleted);
+            if removal.kept_in_source > 0 {
+                println!(
+                    "  {} {} (their documents also hold memories that weren't copied, \
+                     or they have none)",
+                    ui::dim("Kept in source:    "),
+                    removal.kept_in_source
+                );
+            }
+        } else if move_memories {
+            ui::print_warning("Nothing was removed from the source because some memories failed");
+        }
+        println!();
+    } else {
+        output::print_output(&summary, output_format)?;
+    }
+
+    if summary.failed > 0 {
+        anyhow::bail!("{} memory(s) failed to copy", summary.failed);
+    }
+    if let Some(removal) = &summary.source_removal {
+        if removal.deleted.failed > 0 {
+            anyhow::bail!(
+                "{} copied memories could not be removed from '{}'",
+                removal.deleted.failed,
+                from
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Page size used by `memory tail` polls
+const TAIL_PAGE_SIZE: i64 = 100;
+/// Upper bound on pages fetched per poll when a burst of memories arrives
+const TAIL_MAX_PAGES: i64 = 10;
+
+/// Tracks which memories `memory tail` has already reported
+#[derive(Default)]
+struct TailCursor {
+    newest: Option<DateTime<Utc>>,
+    seen: std::collections::HashSet<String>,
+}
+
+impl TailCursor {
+    /// Record a poll's items and return the ones not seen before, oldest first.
+    ///
+    /// Items older than the newest timestamp already reported are ignored, so
+    /// pruning `seen` never causes old memories to be re-reported.
+    fn advance(&mut self, items: Vec<MemoryListItem>) -> Vec<MemoryListItem> {
+        let mut fresh: Vec<MemoryListItem> = items
+            .into_iter()
+            .filter(|item| {
+                let recent = match (self.newest, memory_timestamp(item)) {
+                    (Some(newest), Some(ts)) => ts >= newest,
+                    _ => true,
+                };
+                recent && !self.seen.contains(&item.id)
+            })
+            .collect();
+        fresh.sort_by_key(memory_timestamp);
+
+        if let Some(latest) = fresh.iter().filter_map(memory_timestamp).max() {
+            if self.newest < Some(latest) {
+                self.newest = Some(latest);
+                // Only IDs at the watermark can be returned again
+                self.seen.clear();
+            }
+        }
+        let newest = self.newest;
+        self.seen.extend(
+            fresh
+                .iter()
+                .filter(|item| match memory_timestamp(item) {
+                    // Undated items can't be ordered, so remember them all
+                    None => true,
+                    ts => ts == newest,
+                })
+                .map(|item| item.id.clone()),
+        );
+        fresh
+    }
+}
+
+/// Fetch the newest memories, paging back until the cursor's watermark is reached
+fn fetch_recent_memories(
+    client: &ApiClient,
+    bank_id: &str,
+    type_filter: Option<&str>,
+    newest: Option<DateTime<Utc>>,
+    verbose: bool,
+) -> Result<Vec<MemoryListItem>> {
+    let mut items = Vec::new();
+    for page in 0..TAIL_MAX_PAGES {
+        let response = client.list_memories(
+            bank_id,
+            type_filter,
+            None,
+            Some(TAIL_PAGE_SIZE),
+            Some(page * TAIL_PAGE_SIZE),
+            verbose,
+        )?;
+        let fetched = response.items.len() as i64;
+        // Results are newest first, so stop once a page reaches already-seen history
+        let reached_seen = match newest {
+            Some(newest) => response
+                .items
+                .iter()
+                .any(|item| memory_timestamp(item).is_some_and(|ts| ts < newest)),
+            None => true,
+        };
+        items.extend(response.items);
+        if reached_seen || fetched < TAIL_PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+fn print_tail_line(item: &MemoryListItem, full: bool) {
+    let fact_type = item.fact_type.as_deref().unwrap_or("unknown");
+    let created = memory_timestamp(item)
+        .map(|ts| ts.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let text = item.text.replace(['\n', '\r'], " ");
+    let prefix = format!("[{}] {}  ", fact_type.to_uppercase(), created);
+    let text = if full {
+        text
+    } else {
+        let width = crossterm::terminal::size().map(|(w, _)| w as usize).unwrap_or(120);
+        output::truncate_to_width(&text, width.saturating_sub(prefix.chars().count()).max(20))
+    };
+    println!(
+        "{} {}  {}",
+        ui::gradient_start(&format!("[{}]", fact_type.to_uppercase())),
+        ui::dim(&created),
+        text
+    );
+}
+
+/// Follow a bank and print memories as they appear, like `tail -f`
+pub fn tail(
+    client: &ApiClient,
+    bank_id: &str,
+    type_filter: Option<String>,
+    interval_secs: u64,
+    full: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let interval = std::time::Duration::from_secs(interval_secs.max(1));
+    let stop = crate::utils::install_interrupt_flag();
+    let mut cursor = TailCursor::default();
+
+    // Existing memories only set the starting point; they are not printed
+    let initial = fetch_recent_memories(client, bank_id, type_filter.as_deref(), None, verbose)?;
+    cursor.advance(initial);
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_info(&format!(
+            "Watching bank '{}' for new memories every {}s (Ctrl+C to exit)",
+            bank_id,
+            interval.as_secs()
+        ));
+    }
+
+    loop {
+        crate::utils::sleep_unless_stopped(interval, &stop);
+        if stop.load(std::sync::atomic::Ordering::SeqCst) {
+            break;
+        }
+
+        let items = match fetch_recent_memories(
+            client,
+            bank_id,
+            type_filter.as_deref(),
+            cursor.newest,
+            verbose,
+        ) {
+            Ok(items) => items,
+            // Keep following through transient failures (e.g. a server restart)
+            Err(e) => {
+                ui::print_warning(&format!("Failed to fetch memories: {:#}", e));
+                continue;
+            }
+        };
+
+        for item in cursor.advance(items) {
+            if output_format == OutputFormat::Pretty || output_format == OutputFormat::Table {
+                print_tail_line(&item, full);
             } else {
-                output::print_output(&result, output_format)?;
+                println!("{}", serde_json::to_string(&item)?);
             }
-            Ok(())
         }
-        Err(e) => Err(e),
+        std::io::Write::flush(&mut std::io::stdout())?;
     }
+
+    Ok(())
 }
 
 /// Get a specific memory unit by ID
@@ -248,8 +937,165 @@ pub fn get(
     }
 }
 
+/// A memory in a link tree, reached through `link_type` from its parent
+#[derive(Debug, Serialize)]
+struct LinkNode {
+    memory_id: String,
+    link_type: String,
+    weight: f64,
+    fact_type: Option<String>,
+    text: Option<String>,
+    children: Vec<LinkNode>,
+}
+
+/// Walk links breadth-first from `root` up to `depth` hops, keeping at most `max_nodes`
+/// memories. Each memory appears once, under the first parent that reached it, so
+/// cycles end there. Returns the tree and whether `max_nodes` cut it short.
+fn link_tree(
+    graph: &GraphExport,
+    root: &str,
+    link_type: Option<&str>,
+    depth: usize,
+    max_nodes: usize,
+) -> (Vec<LinkNode>, bool) {
+    let mut visited = HashSet::from([root.to_string()]);
+    let mut children: HashMap<String, Vec<LinkItem>> = HashMap::new();
+    let mut frontier = vec![root.to_string()];
+    let mut truncated = false;
+
+    'levels: for _ in 0..depth {
+        let mut next = Vec::new();
+        for id in &frontier {
+            for link in graph.links_of(id) {
+                if link_type.is_some_and(|t| t != link.link_type)
+                    || visited.contains(&link.target_memory_id)
+                {
+                    continue;
+                }
+                if visited.len() > max_nodes {
+                    truncated = true;
+                    break 'levels;
+                }
+                visited.insert(link.target_memory_id.clone());
+                next.push(link.target_memory_id.clone());
+                children.entry(id.clone()).or_default().push(link);
+            }
+        }
+        frontier = next;
+    }
+
+    let nodes: HashMap<&str, &GraphNode> =
+        graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    fn build(
+        id: &str,
+        children: &mut HashMap<String, Vec<LinkItem>>,
+        nodes: &HashMap<&str, &GraphNode>,
+    ) -> Vec<LinkNode> {
+        children
+            .remove(id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|link| {
+                let node = nodes.get(link.target_memory_id.as_str());
+                LinkNode {
+                    children: build(&link.target_memory_id, children, nodes),
+                    fact_type: node.and_then(|n| n.fact_type.clone()),
+                    text: node.map(|n| n.text.clone()).filter(|t| !t.is_empty()),
+                    memory_id: link.target_memory_id,
+                    link_type: link.link_type,
+                    weight: link.weight,
+                }
+            })
+            .collect()
+    }
+    (build(root, &mut children, &nodes), truncated)
+}
+
+fn print_link_nodes(nodes: &[LinkNode], prefix: &str) {
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i + 1 == nodes.len();
+        let branch = if last { "└─ " } else { "├─ " };
+        let label = format!("{} {:.2}", node.link_type, node.weight);
+        let used = format!("{}{}{}  {}  ", prefix, branch, label, node.memory_id)
+            .chars()
+            .count();
+        let room = ui::terminal_width().saturating_sub(used).max(20);
+        let text = node.text.as_deref().unwrap_or("");
+        println!(
+            "{}{}{}  {}  {}",
+            prefix,
+            branch,
+            ui::dim(&label),
+            node.memory_id,
+            output::truncate_to_width(text, room)
+        );
+        let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+        print_link_nodes(&node.children, &child_prefix);
+    }
+}
+
+/// Show the memories linked to `memory_id`, `depth` hops out, as a tree
+#[allow(clippy::too_many_arguments)]
+pub fn links(
+    client: &ApiClient,
+    bank_id: &str,
+    memory_id: &str,
+    link_type: Option<&str>,
+    depth: usize,
+    max_nodes: usize,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Fetching links..."))
+    } else {
+        None
+    };
+    // One graph fetch covers every hop and the previews of every linked memory
+    let response = client.get_graph_export(bank_id, None, LINK_GRAPH_LIMIT, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+    let graph = response?;
+
+    let Some(root) = graph.nodes.iter().find(|n| n.id == memory_id) else {
+        // Fails with not found if the memory doesn't exist at all
+        client.get_memory(bank_id, memory_id, verbose)?;
+        anyhow::bail!(
+            "Memory '{}' is not among the {} most recent memories the graph endpoint \
+             returns, so its links can't be listed",
+            memory_id,
+            LINK_GRAPH_LIMIT
+        );
+    };
+
+    let (tree, truncated) = link_tree(&graph, memory_id, link_type, depth, max_nodes);
+    if truncated {
+        ui::print_warning(&format!(
+            "Stopped at {} linked memories; raise --max-nodes to see more",
+            max_nodes
+        ));
+    }
+
+    if output_format == OutputFormat::Pretty {
+        println!("{}  {}", ui::dim(memory_id), root.text);
+        if tree.is_empty() {
+            println!("  {}", ui::dim("No links"));
+        }
+        print_link_nodes(&tree, "");
+    } else {
+        let result = serde_json::json!({
+            "memory_id": memory_id,
+            "links": tree,
+            "truncated": truncated,
+        });
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
 // Helper function to check if a file is supported by the file converter (markitdown)
-fn is_supported_file(path: &std::path::Path) -> bool {
+pub(crate) fn is_supported_file(path: &std::path::Path) -> bool {
     const SUPPORTED_EXTENSIONS: &[&str] = &[
         // Documents
         "pdf", "docx", "doc", "pptx", "ppt", "xlsx", "xls", // Images (OCR)
@@ -265,13 +1111,49 @@ fn is_supported_file(path: &std::path::Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Infer an upload content type from the file extension
+pub(crate) fn content_type_for(path: &std::path::Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "doc" => "application/msword",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "xls" => "application/vnd.ms-excel",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        "tiff" => "image/tiff",
+        "html" | "htm" => "text/html",
+        "txt" | "log" | "rst" | "adoc" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "xml" => "application/xml",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Build a `RecallRequest` from CLI flags (shared by `memory recall` and `recall`)
 #[allow(clippy::too_many_arguments)]
-pub fn recall(
-    client: &ApiClient,
-    agent_id: &str,
+pub(crate) fn build_recall_request(
     query: String,
     fact_type: Vec<String>,
-    budget: String,
+    budget: &str,
     max_tokens: i64,
     trace: bool,
     include_chunks: bool,
@@ -279,15 +1161,7 @@ pub fn recall(
     tags: Vec<String>,
     tags_match: Option<String>,
     query_timestamp: Option<String>,
-    verbose: bool,
-    output_format: OutputFormat,
-) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Recalling memories..."))
-    } else {
-        None
-    };
-
+) -> RecallRequest {
     // Build include options if chunks are requested
     let include = if include_chunks {
         Some(IncludeOptions {
@@ -301,14 +1175,14 @@ pub fn recall(
         None
     };
 
-    let request = RecallRequest {
+    RecallRequest {
         query,
         types: if fact_type.is_empty() {
             None
         } else {
             Some(fact_type)
         },
-        budget: Some(parse_budget(&budget)),
+        budget: Some(parse_budget(budget)),
         max_tokens,
         trace,
         query_timestamp,
@@ -316,8 +1190,150 @@ pub fn recall(
         tags: if tags.is_empty() { None } else { Some(tags) },
         tags_match: parse_tags_match(&tags_match),
         tag_groups: None,
+    }
+}
+
+/// Event-date range and recency bias for recall results.
+///
+/// The recall endpoint has no occurred-range or recency options, so these are applied to the
+/// returned results: results whose event dates fall outside the range are dropped, and the
+/// rest can be re-ranked towards newer events.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct RecallWindow {
+    pub occurred_after: Option<DateTime<Utc>>,
+    pub occurred_before: Option<DateTime<Utc>>,
+    /// 0 keeps the server's relevance order, 1 orders purely by event date
+    pub recency_weight: Option<f32>,
+}
+
+impl RecallWindow {
+    /// Parse `--after`, `--before` and `--recency`, rejecting invalid combinations up front
+    pub fn from_args(
+        after: Option<&str>,
+        before: Option<&str>,
+        recency: Option<f32>,
+    ) -> Result<Self> {
+        let window = RecallWindow {
+            occurred_after: after.map(crate::utils::parse_datetime_arg).transpose()?,
+            occurred_before: before.map(crate::utils::parse_datetime_arg).transpose()?,
+            recency_weight: recency,
+        };
+        window.validate()?;
+        Ok(window)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(weight) = self.recency_weight {
+            if !(0.0..=1.0).contains(&weight) {
+                anyhow::bail!("--recency must be between 0 and 1, got {}", weight);
+            }
+        }
+        if let (Some(after), Some(before)) = (self.occurred_after, self.occurred_before) {
+            if after > before {
+                anyhow::bail!(
+                    "--after ({}) is later than --before ({})",
+                    after.to_rfc3339(),
+                    before.to_rfc3339()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Filter and re-rank results in place
+    pub fn apply(&self, results: &mut Vec<RecallResult>) {
+        if self.occurred_after.is_some() || self.occurred_before.is_some() {
+            results.retain(|result| {
+                let Some((start, end)) = result_event_span(result) else {
+                    return false;
+                };
+                !self.occurred_after.is_some_and(|after| end < after)
+                    && !self.occurred_before.is_some_and(|before| start > before)
+            });
+        }
+
+        let weight = match self.recency_weight {
+            Some(weight) if weight > 0.0 && results.len() > 1 => weight as f64,
+            _ => return,
+        };
+        let times: Vec<Option<i64>> = results
+            .iter()
+            .map(|result| result_event_span(result).map(|(_, end)| end.timestamp()))
+            .collect();
+        let oldest = times.iter().flatten().min().copied().unwrap_or_default();
+        let newest = times.iter().flatten().max().copied().unwrap_or_default();
+        let last = (results.len() - 1) as f64;
+        let scores: Vec<f64> = times
+            .iter()
+            .enumerate()
+            .map(|(rank, time)| {
+                let relevance = 1.0 - rank as f64 / last;
+                let recency = match time {
+                    Some(_) if newest == oldest => 1.0,
+                    Some(t) => (t - oldest) as f64 / (newest - oldest) as f64,
+                    None => 0.0,
+                };
+                (1.0 - weight) * relevance + weight * recency
+            })
+            .collect();
+
+        let mut ranked: Vec<(f64, RecallResult)> =
+            scores.into_iter().zip(results.drain(..)).collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        results.extend(ranked.into_iter().map(|(_, result)| result));
+    }
+}
+
+/// When a recalled fact happened: its occurred range, or the mention time for undated facts
+fn result_event_span(result: &RecallResult) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let parse = |field: &Option<String>| field.as_deref().and_then(parse_timestamp);
+    let start = parse(&result.occurred_start);
+    let end = parse(&result.occurred_end);
+    match (start, end) {
+        (Some(start), Some(end)) => Some((start.min(end), start.max(end))),
+        (Some(at), None) | (None, Some(at)) => Some((at, at)),
+        (None, None) => parse(&result.mentioned_at).map(|at| (at, at)),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn recall(
+    client: &ApiClient,
+    agent_id: &str,
+    query: String,
+    fact_type: Vec<String>,
+    budget: String,
+    max_tokens: i64,
+    trace: bool,
+    include_chunks: bool,
+    chunk_max_tokens: i64,
+    tags: Vec<String>,
+    tags_match: Option<String>,
+    query_timestamp: Option<String>,
+    window: RecallWindow,
+    group_by: Option<ui::ResultGrouping>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Recalling memories..."))
+    } else {
+        None
     };
 
+    let request = build_recall_request(
+        query,
+        fact_type,
+        &budget,
+        max_tokens,
+        trace,
+        include_chunks,
+        chunk_max_tokens,
+        tags,
+        tags_match,
+        query_timestamp,
+    );
+
     let response = client.recall(agent_id, &request, verbose);
 
     if let Some(mut sp) = spinner {
@@ -325,9 +1341,15 @@ pub fn recall(
     }
 
     match response {
-        Ok(result) => {
+        Ok(mut result) => {
+            window.apply(&mut result.results);
             if output_format == OutputFormat::Pretty {
-                ui::print_search_results(&result, trace, include_chunks);
+                pager::page(|| {
+                    ui::print_search_results(&result, trace, include_chunks, group_by, None);
+                    Ok(())
+                })?;
+            } else if trace {
+                output::print_output(&trace::response_with_report(&result)?, output_format)?;
             } else {
                 output::print_output(&result, output_format)?;
             }
@@ -337,30 +1359,21 @@ pub fn recall(
     }
 }
 
+/// Build a `ReflectRequest` from CLI flags (shared by `memory reflect` and `reflect`)
 #[allow(clippy::too_many_arguments)]
-pub fn reflect(
-    client: &ApiClient,
-    agent_id: &str,
+pub(crate) fn build_reflect_request(
     query: String,
-    budget: String,
+    budget: &str,
     context: Option<String>,
     max_tokens: Option<i64>,
     schema_path: Option<PathBuf>,
     tags: Vec<String>,
     tags_match: Option<String>,
-    include_facts: bool,
+    include: &[String],
     fact_types: Option<Vec<String>>,
     exclude_mental_models: bool,
     exclude_mental_model_ids: Option<Vec<String>>,
-    verbose: bool,
-    output_format: OutputFormat,
-) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Reflecting..."))
-    } else {
-        None
-    };
-
+) -> Result<ReflectRequest> {
     // Load and parse schema if provided
     let response_schema = if let Some(path) = schema_path {
         let schema_content = fs::read_to_string(&path)
@@ -373,13 +1386,26 @@ pub fn reflect(
         None
     };
 
-    let include = if include_facts {
-        Some(ReflectIncludeOptions {
-            facts: Some(FactsIncludeOptions(serde_json::Map::new())),
-            tool_calls: None,
-        })
-    } else {
+    let mut include_options = ReflectIncludeOptions {
+        facts: None,
+        tool_calls: None,
+    };
+    for option in include {
+        match option.to_lowercase().as_str() {
+            "facts" => include_options.facts = Some(FactsIncludeOptions(serde_json::Map::new())),
+            "tool_calls" | "tool-calls" => {
+                include_options.tool_calls = Some(ToolCallsIncludeOptions { output: true })
+            }
+            other => anyhow::bail!(
+                "Unknown include option '{}'. Valid options: facts, tool_calls",
+                other
+            ),
+        }
+    }
+    let include = if include.is_empty() {
         None
+    } else {
+        Some(include_options)
     };
 
     // Map the CLI fact-type strings (world, experience, observation) into the
@@ -389,17 +1415,17 @@ pub fn reflect(
         types
             .iter()
             .filter_map(|t| match t.to_lowercase().as_str() {
-                "world" => Some(hindsight_client::types::FactTypesItem::World),
-                "experience" => Some(hindsight_client::types::FactTypesItem::Experience),
-                "observation" => Some(hindsight_client::types::FactTypesItem::Observation),
+                "world" => Some(FactTypesItem::World),
+                "experience" => Some(FactTypesItem::Experience),
+                "observation" => Some(FactTypesItem::Observation),
                 _ => None,
             })
             .collect::<Vec<_>>()
     });
 
-    let request = ReflectRequest {
+    Ok(ReflectRequest {
         query,
-        budget: Some(parse_budget(&budget)),
+        budget: Some(parse_budget(budget)),
         context,
         max_tokens: max_tokens.unwrap_or(4096),
         include,
@@ -410,9 +1436,60 @@ pub fn reflect(
         fact_types: mapped_fact_types,
         exclude_mental_models,
         exclude_mental_model_ids,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn reflect(
+    client: &ApiClient,
+    agent_id: &str,
+    query: String,
+    budget: String,
+    context: Option<String>,
+    max_tokens: Option<i64>,
+    schema_path: Option<PathBuf>,
+    tags: Vec<String>,
+    tags_match: Option<String>,
+    include_facts: bool,
+    fact_types: Option<Vec<String>>,
+    exclude_mental_models: bool,
+    exclude_mental_model_ids: Option<Vec<String>>,
+    fetch_citations: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Reflecting..."))
+    } else {
+        None
     };
 
+    let include = if include_facts {
+        vec!["facts".to_string()]
+    } else {
+        Vec::new()
+    };
+    let request = build_reflect_request(
+        query,
+        &budget,
+        context,
+        max_tokens,
+        schema_path,
+        tags,
+        tags_match,
+        &include,
+        fact_types,
+        exclude_mental_models,
+        exclude_mental_model_ids,
+    )?;
+
     let response = client.reflect(agent_id, &request, verbose);
+    let citations = match &response {
+        Ok(result) => {
+            reflect::collect_citations(client, agent_id, result, fetch_citations, verbose)
+        }
+        Err(_) => Vec::new(),
+    };
 
     if let Some(mut sp) = spinner {
         sp.finish();
@@ -421,9 +1498,14 @@ pub fn reflect(
     match response {
         Ok(result) => {
             if output_format == OutputFormat::Pretty {
-                ui::print_think_response(&result);
+                ui::print_think_response(&result, &citations);
             } else {
-                output::print_output(&result, output_format)?;
+                let cited = reflect::CitedReflection {
+                    response: &result,
+                    citations,
+                    saved: None,
+                };
+                output::print_output(&cited, output_format)?;
             }
             Ok(())
         }
@@ -498,6 +1580,174 @@ pub fn retain(
     }
 }
 
+/// A memory queued by `memory add`, echoed back so scripts can track what was sent
+#[derive(Debug, Serialize)]
+struct AddedMemory {
+    document_id: String,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fact_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    occurred: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AddResult {
+    bank_id: String,
+    memories: Vec<AddedMemory>,
+    result: crate::api::MemoryPutResult,
+}
+
+/// Read the raw input for `memory add`: inline text, a file, or stdin when either is `-`
+fn read_add_input(text: Option<String>, file: Option<PathBuf>) -> Result<String> {
+    match (text, file) {
+        (Some(_), Some(_)) => anyhow::bail!("Use either --text or --file, not both"),
+        (Some(text), None) if text != "-" => Ok(text),
+        (None, Some(path)) if path.as_os_str() != "-" => fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read file: {}", path.display())),
+        (None, None) if std::io::stdin().is_terminal() => {
+            anyhow::bail!("Provide memory text with --text, --file, or pipe it via stdin (-)")
+        }
+        _ => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read memory text from stdin")?;
+            Ok(buf)
+        }
+    }
+}
+
+/// Split raw input into individual memory texts, dropping blank entries
+fn split_memories(input: &str, delimiter: Option<&str>) -> Vec<String> {
+    let parts: Vec<&str> = match delimiter {
+        Some(d) if !d.is_empty() => input.split(d).collect(),
+        _ => vec![input],
+    };
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Build a single retain item for directly-added memories.
+///
+/// The server's extraction assigns the final fact type; a requested type is
+/// passed along as a `fact_type` metadata hint.
+pub(crate) fn build_memory_item(
+    content: &str,
+    context: Option<&str>,
+    occurred: Option<&str>,
+    document_id: &str,
+    fact_type: Option<&str>,
+) -> Result<MemoryItem> {
+    let metadata = fact_type.map(|t| serde_json::json!({ "fact_type": t }));
+    serde_json::from_value(serde_json::json!({
+        "content": content,
+        "context": context,
+        "timestamp": occurred,
+        "document_id": document_id,
+        "metadata": metadata,
+    }))
+    .context("Failed to build memory item")
+}
+
+/// Add one or more memories from inline text, a file, or stdin
+#[allow(clippy::too_many_arguments)]
+pub fn add(
+    client: &ApiClient,
+    bank_id: &str,
+    text: Option<String>,
+    file: Option<PathBuf>,
+    delimiter: Option<String>,
+    fact_type: Option<String>,
+    occurred: Option<String>,
+    context: Option<String>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let contents = split_memories(&read_add_input(text, file)?, delimiter.as_deref());
+    if contents.is_empty() {
+        anyhow::bail!("No memory text provided");
+    }
+    let fact_type = parse_fact_type_hint(fact_type.as_deref())?;
+
+    let occurred = occurred
+        .as_deref()
+        .map(crate::utils::parse_datetime_arg)
+        .transpose()?
+        .map(|dt| dt.to_rfc3339());
+
+    let base_doc_id = config::generate_doc_id();
+    let mut memories = Vec::with_capacity(contents.len());
+    let mut items = Vec::with_capacity(contents.len());
+    for (i, content) in contents.into_iter().enumerate() {
+        let document_id = if i == 0 {
+            base_doc_id.clone()
+        } else {
+            format!("{}_{}", base_doc_id, i)
+        };
+        items.push(build_memory_item(
+            &content,
+            context.as_deref(),
+            occurred.as_deref(),
+            &document_id,
+            fact_type.as_deref(),
+        )?);
+        memories.push(AddedMemory {
+            document_id,
+            content,
+            fact_type: fact_type.clone(),
+            occurred: occurred.clone(),
+            context: context.clone(),
+        });
+    }
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Adding memory..."))
+    } else {
+        None
+    };
+
+    let request = RetainRequest {
+        items,
+        async_: false,
+        document_tags: None,
+    };
+    let response = client.retain(bank_id, &request, false, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let result = response?;
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!(
+            "Added {} memor{} to bank '{}'",
+            memories.len(),
+            if memories.len() == 1 { "y" } else { "ies" },
+            bank_id
+        ));
+        for memory in &memories {
+            println!("  {} {}", ui::dim("Document:"), memory.document_id);
+        }
+        println!("  {} {}", ui::dim("Stored units:"), result.items_count);
+    } else {
+        output::print_output(
+            &AddResult {
+                bank_id: bank_id.to_string(),
+                memories,
+                result,
+            },
+            output_format,
+        )?;
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn retain_files(
     client: &ApiClient,
@@ -553,10 +1803,15 @@ pub fn retain_files(
     let batches: Vec<&[PathBuf]> = file_paths.chunks(BATCH_SIZE).collect();
     let mut all_operation_ids: Vec<String> = Vec::new();
 
-    let pb = ui::create_progress_bar(file_paths.len() as u64, "Uploading files");
+    let progress = ui::create_transfer_progress(file_paths.len() as u64, "Uploading", true);
 
     for batch in &batches {
-        let mut file_data: Vec<(String, Vec<u8>)> = Vec::new();
+        let first = batch[0].display().to_string();
+        let worker = progress.start(&match batch.len() {
+            1 => first,
+            n => format!("{} and {} more", first, n - 1),
+        });
+        let mut file_data: Vec<(String, Vec<u8>, String)> = Vec::new();
         for file_path in *batch {
             let filename = file_path
                 .file_name()
@@ -565,16 +1820,17 @@ pub fn retain_files(
                 .unwrap_or_else(|| "file".to_string());
             let content = fs::read(file_path)
                 .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
-            file_data.push((filename, content));
-            pb.inc(1);
+            file_data.push((filename, content, content_type_for(file_path).to_string()));
         }
 
+        let bytes = file_data.iter().map(|(_, content, _)| content.len() as u64).sum();
         let result =
             client.file_retain(agent_id, file_data, context.clone(), strategy.clone(), verbose)?;
         all_operation_ids.extend(result.operation_ids);
+        progress.complete(worker, batch.len() as u64, bytes);
     }
 
-    pb.finish_with_message("Files uploaded");
+    progress.finish();
 
     if r#async {
         if output_format == OutputFormat::Pretty {
@@ -645,14 +1901,16 @@ pub fn delete(
         None
     };
 
-    let response = client.delete_memory(agent_id, unit_id, verbose);
+    let plan = PlannedWrite::new("delete_memory", agent_id).target(unit_id);
+    let response = executor::get().execute(plan, || client.delete_memory(agent_id, unit_id, verbose));
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
     match response {
-        Ok(result) => {
+        Ok(None) => Ok(()),
+        Ok(Some(result)) => {
             if output_format == OutputFormat::Pretty {
                 if result.success {
                     ui::print_success("Memory unit deleted successfully");
@@ -710,14 +1968,21 @@ pub fn clear(
         None
     };
 
-    let response = client.clear_memories(agent_id, fact_type.as_deref(), verbose);
+    let mut plan = PlannedWrite::new("clear_memories", agent_id);
+    if let Some(ft) = &fact_type {
+        plan = plan.target(ft);
+    }
+    let response = executor::get().execute(plan, || {
+        client.clear_memories(agent_id, fact_type.as_deref(), verbose)
+    });
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
     match response {
-        Ok(result) => {
+        Ok(None) => Ok(()),
+        Ok(Some(result)) => {
             if output_format == OutputFormat::Pretty {
                 if result.success {
                     let msg = if fact_type.is_some() {
@@ -818,6 +2083,50 @@ mod tests {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn test_link_tree_stops_at_cycles_and_caps_nodes() {
+        let node = |id: &str| GraphNode {
+            id: id.to_string(),
+            text: format!("memory {}", id),
+            fact_type: Some("world".to_string()),
+            mentioned_at: None,
+            occurred_start: None,
+        };
+        let link = |source: &str, target: &str, link_type: &str| crate::api::GraphLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            link_type: link_type.to_string(),
+            weight: 0.5,
+            entity_name: None,
+        };
+        // a - b - c - a is a cycle; d hangs off c
+        let graph = GraphExport {
+            nodes: ["a", "b", "c", "d"].into_iter().map(node).collect(),
+            links: vec![
+                link("a", "b", "semantic"),
+                link("b", "c", "semantic"),
+                link("c", "a", "temporal"),
+                link("c", "d", "entity"),
+            ],
+            total_units: 4,
+        };
+
+        let (tree, truncated) = link_tree(&graph, "a", None, 3, 10);
+        assert!(!truncated);
+        let ids: Vec<&str> = tree.iter().map(|n| n.memory_id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert!(tree[0].children.is_empty());
+        assert_eq!(tree[1].children[0].memory_id, "d");
+        assert_eq!(tree[1].children[0].text.as_deref(), Some("memory d"));
+
+        let (tree, _) = link_tree(&graph, "a", Some("semantic"), 1, 10);
+        assert_eq!(tree.len(), 1);
+
+        let (tree, truncated) = link_tree(&graph, "a", None, 3, 1);
+        assert!(truncated);
+        assert_eq!(tree.len(), 1);
+    }
+
     #[test]
     fn test_is_supported_file_text_extensions() {
         let supported = [
@@ -922,4 +2231,200 @@ mod tests {
         assert!(matches!(parse_budget(""), Budget::Mid));
         assert!(matches!(parse_budget("unknown"), Budget::Mid));
     }
+
+    #[test]
+    fn test_resolve_fact_types_includes_or_excludes() {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert!(resolve_fact_types(&[], &[]).unwrap().is_empty());
+        assert_eq!(
+            resolve_fact_types(&strings(&["World", "experience", "world"]), &[]).unwrap(),
+            strings(&["world", "experience"])
+        );
+        assert_eq!(
+            resolve_fact_types(&[], &strings(&["observation"])).unwrap(),
+            strings(&["world", "experience"])
+        );
+
+        let error = resolve_fact_types(&strings(&["world", "agent"]), &[]).unwrap_err();
+        assert!(error.to_string().contains("Unknown fact type 'agent'"));
+        let error = resolve_fact_types(&[], &strings(&["opinion"])).unwrap_err();
+        assert!(error.to_string().contains("use 'observation'"));
+        let all = strings(&["world", "experience", "observation"]);
+        assert!(resolve_fact_types(&[], &all).is_err());
+    }
+
+    #[test]
+    fn test_fact_type_hint_accepts_server_types() {
+        assert_eq!(parse_fact_type_hint(None).unwrap(), None);
+        assert_eq!(
+            parse_fact_type_hint(Some("Experience")).unwrap().as_deref(),
+            Some("experience")
+        );
+        let error = parse_fact_type_hint(Some("agent")).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("expected world, experience or observation"));
+        assert!(parse_fact_type_hint(Some("opinion")).is_err());
+    }
+
+    #[test]
+    fn test_split_memories_with_delimiter() {
+        let parts = split_memories("first\n---\nsecond\n---\n\n", Some("---"));
+        assert_eq!(parts, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn test_split_memories_without_delimiter() {
+        let parts = split_memories("  one memory\nwith two lines  ", None);
+        assert_eq!(parts, vec!["one memory\nwith two lines".to_string()]);
+        assert!(split_memories("   ", None).is_empty());
+    }
+
+    fn memory(json: serde_json::Value) -> MemoryListItem {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_plan_copy_counts_empty_and_duplicate_memories_apart() {
+        let item = |id: &str, text: &str| memory(serde_json::json!({"id": id, "text": text}));
+        // Listed newest first
+        let source = vec![
+            item("m4", "Alice likes tea"),
+            item("m3", "  "),
+            item("m2", "alice   LIKES tea"),
+            item("m1", "Bob hikes"),
+        ];
+        let existing = vec![item("d1", "Bob hikes")];
+        let plan = plan_copy(&source, &existing);
+        let copied: Vec<&str> = plan.to_copy.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(copied, vec!["m2"]);
+        assert_eq!(plan.skipped_duplicates, 2);
+        assert_eq!(plan.skipped_empty, 1);
+        let present: Vec<&str> = plan.in_destination.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(present, vec!["m1", "m2", "m4"]);
+    }
+
+    #[test]
+    fn test_memory_filter_dates_and_contains() {
+        let item = memory(serde_json::json!({
+            "id": "m1",
+            "text": "Alice moved to Berlin",
+            "mentioned_at": "2024-06-10T12:00:00+00:00",
+        }));
+        let mut filter = MemoryFilter {
+            since: Some(crate::utils::parse_datetime_arg("2024-06-01").unwrap()),
+            until: Some(crate::utils::parse_datetime_arg("2024-06-30").unwrap()),
+            contains: Some("berlin".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches("bank", &item));
+
+        filter.until = Some(crate::utils::parse_datetime_arg("2024-06-05").unwrap());
+        assert!(!filter.matches("bank", &item));
+    }
+
+    #[test]
+    fn test_memory_filter_document_via_chunk_id() {
+        let item = memory(serde_json::json!({
+            "id": "m1",
+            "text": "note",
+            "chunk_id": "bank_doc-1_3",
+        }));
+        let filter = MemoryFilter {
+            document_id: Some("doc-1".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches("bank", &item));
+
+        let other = MemoryFilter {
+            document_id: Some("doc".to_string()),
+            ..Default::default()
+        };
+        assert!(!other.matches("bank", &item));
+    }
+
+    #[test]
+    fn test_tail_cursor_reports_only_new_memories() {
+        let at = |id: &str, ts: &str| {
+            memory(serde_json::json!({"id": id, "text": id, "mentioned_at": ts}))
+        };
+        let mut cursor = TailCursor::default();
+        let initial = cursor.advance(vec![
+            at("m2", "2024-06-10T12:00:00+00:00"),
+            at("m1", "2024-06-10T11:00:00+00:00"),
+        ]);
+        assert_eq!(initial.len(), 2);
+
+        // Same page again plus one newer memory and one at the watermark
+        let fresh = cursor.advance(vec![
+            at("m4", "2024-06-10T13:00:00+00:00"),
+            at("m3", "2024-06-10T12:00:00+00:00"),
+            at("m2", "2024-06-10T12:00:00+00:00"),
+            at("m1", "2024-06-10T11:00:00+00:00"),
+        ]);
+        let ids: Vec<&str> = fresh.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m3", "m4"]);
+
+        assert!(cursor
+            .advance(vec![at("m4", "2024-06-10T13:00:00+00:00")])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_memory_document_id() {
+        let listed = |extra: serde_json::Value| {
+            let mut json = serde_json::json!({"id": "m1", "text": "note"});
+            json.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            memory(json)
+        };
+        let via_chunk = listed(serde_json::json!({"chunk_id": "bank_doc_a_12"}));
+        assert_eq!(memory_document_id("bank", &via_chunk).as_deref(), Some("doc_a"));
+
+        let direct = listed(serde_json::json!({"document_id": "notes"}));
+        assert_eq!(memory_document_id("bank", &direct).as_deref(), Some("notes"));
+
+        let other_bank = listed(serde_json::json!({"chunk_id": "other_doc_1"}));
+        assert_eq!(memory_document_id("bank", &other_bank), None);
+        assert_eq!(memory_document_id("bank", &listed(serde_json::json!({}))), None);
+    }
+
+    fn recalled(id: &str, occurred_start: Option<&str>) -> RecallResult {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "text": id,
+            "occurred_start": occurred_start,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_recall_window_validation() {
+        assert!(RecallWindow::from_args(Some("2024-06-01"), Some("2024-06-30"), Some(0.5)).is_ok());
+
+        let err = RecallWindow::from_args(None, None, Some(1.5)).unwrap_err().to_string();
+        assert!(err.contains("between 0 and 1"), "{}", err);
+
+        let err = RecallWindow::from_args(Some("2024-06-30"), Some("2024-06-01"), None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("later than --before"), "{}", err);
+    }
+
+    #[test]
+    fn test_recall_window_filters_and_ranks_by_event_date() {
+        let mut results = vec![
+            recalled("old", Some("2024-05-01T00:00:00Z")),
+            recalled("undated", None),
+            recalled("mid", Some("2024-06-10T00:00:00Z")),
+            recalled("new", Some("2024-06-20T00:00:00Z")),
+        ];
+        let window = RecallWindow::from_args(Some("2024-06-01"), None, None).unwrap();
+        window.apply(&mut results);
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["mid", "new"]);
+
+        RecallWindow { recency_weight: Some(1.0), ..Default::default() }.apply(&mut results);
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "mid"]);
+    }
 }
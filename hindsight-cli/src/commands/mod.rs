@@ -1,13 +1,21 @@
 pub mod audit;
+pub mod auth;
 pub mod bank;
+pub mod bench;
+pub mod chat;
 pub mod chunk;
+pub mod config;
 pub mod directive;
 pub mod document;
 pub mod entity;
 pub mod explore;
+pub mod graph;
 pub mod health;
+pub mod import;
 pub mod memory;
 pub mod mental_model;
 pub mod operation;
+pub mod recall;
+pub mod reflect;
 pub mod tag;
 pub mod webhook;
@@ -1,52 +1,175 @@
-use crate::api::ApiClient;
+use crate::api::{ApiClient, Operation, OperationsResponse};
+use crate::executor::{self, PlannedWrite};
 use crate::output::{self, OutputFormat};
 use crate::ui;
+use crate::utils;
 use anyhow::Result;
+use chrono::{Local, Utc};
+use crossterm::cursor;
+use crossterm::execute;
+use crossterm::terminal::{Clear, ClearType};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
+const OPERATION_COLUMNS: &[&str] = &["id", "kind", "status", "created_at", "error"];
+
+/// Largest page the operations endpoint accepts
+const OPERATIONS_PAGE_SIZE: u64 = 100;
+
+/// Map the CLI's status names onto the API's (`done` is an alias for `completed`)
+fn api_status(status: &str) -> &str {
+    match status {
+        "done" => "completed",
+        other => other,
+    }
+}
+
+fn operation_row(op: &Operation) -> Value {
+    json!({
+        "id": op.id,
+        "kind": op.task_type,
+        "status": op.status,
+        "created_at": op.created_at,
+        "error": op.error_message,
+    })
+}
+
+fn print_operations(
+    bank_id: &str,
+    response: &OperationsResponse,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let rows: Vec<Value> = response.operations.iter().map(operation_row).collect();
+    if output_format == OutputFormat::Pretty {
+        if rows.is_empty() {
+            ui::print_info(&format!("No operations found for bank '{}'", bank_id));
+            return Ok(());
+        }
+        ui::print_info(&format!(
+            "Operations for bank '{}' ({} of {})",
+            bank_id,
+            rows.len(),
+            response.total.max(rows.len() as i64)
+        ));
+    }
+    output::print_table(&rows, OPERATION_COLUMNS)
+}
+
+/// List a bank's async operations, optionally re-polling every `watch` seconds
 pub fn list(
     client: &ApiClient,
     agent_id: &str,
+    status: Option<String>,
+    limit: u64,
+    watch: Option<u64>,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
+    let status = status.as_deref().map(api_status);
+    if let Some(interval) = watch {
+        return list_watch(client, agent_id, status, limit, interval, verbose, output_format);
+    }
+
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Fetching operations..."))
     } else {
         None
     };
 
-    let response = client.list_operations(agent_id, verbose);
+    let response = client.list_operations(agent_id, status, Some(limit), None, verbose);
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
-    match response {
-        Ok(ops_response) => {
-            if output_format == OutputFormat::Pretty {
-                if ops_response.operations.is_empty() {
-                    ui::print_info("No operations found");
-                } else {
-                    ui::print_info(&format!(
-                        "Found {} operation(s)",
-                        ops_response.operations.len()
-                    ));
-                    for op in &ops_response.operations {
-                        println!("\n  Operation ID: {}", op.id);
-                        println!("    Type: {}", op.task_type);
-                        println!("    Status: {}", op.status);
-                        println!("    Items: {}", op.items_count);
-                        if let Some(doc_id) = &op.document_id {
-                            println!("    Document ID: {}", doc_id);
-                        }
-                    }
+    let ops_response = response?;
+    match output_format {
+        OutputFormat::Pretty | OutputFormat::Table => {
+            print_operations(agent_id, &ops_response, output_format)?
+        }
+        _ => output::print_output(&ops_response, output_format)?,
+    }
+    Ok(())
+}
+
+/// Re-list operations every `interval_secs` seconds, like `bank stats --watch`.
+///
+/// On a terminal with pretty output the table is redrawn in place; otherwise one
+/// JSON object (the full list response) is printed per sample.
+fn list_watch(
+    client: &ApiClient,
+    bank_id: &str,
+    status: Option<&str>,
+    limit: u64,
+    interval_secs: u64,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let redraw = output_format == OutputFormat::Pretty && std::io::stdout().is_terminal();
+    let stop = utils::install_interrupt_flag();
+    let mut stdout = std::io::stdout();
+
+    if redraw {
+        execute!(stdout, cursor::Hide)?;
+    }
+
+    while !stop.load(Ordering::SeqCst) {
+        match client.list_operations(bank_id, status, Some(limit), None, verbose) {
+            Ok(response) if redraw => {
+                execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                print_operations(bank_id, &response, output_format)?;
+                println!();
+                println!(
+                    "  {}",
+                    ui::dim(&format!(
+                        "{} · every {}s · Ctrl+C to exit",
+                        Local::now().format("%H:%M:%S"),
+                        interval.as_secs()
+                    ))
+                );
+            }
+            Ok(response) => {
+                let mut sample = serde_json::to_value(&response)?;
+                if let Some(obj) = sample.as_object_mut() {
+                    obj.insert("sampled_at".to_string(), json!(Utc::now().to_rfc3339()));
                 }
-            } else {
-                output::print_output(&ops_response, output_format)?;
+                writeln!(stdout, "{}", serde_json::to_string(&sample)?)?;
+                stdout.flush()?;
             }
-            Ok(())
+            // Keep watching through transient failures (e.g. a server restart)
+            Err(e) if redraw => ui::print_warning(&format!("Failed to fetch operations: {:#}", e)),
+            Err(e) => eprintln!("Failed to fetch operations: {:#}", e),
+        }
+        utils::sleep_unless_stopped(interval, &stop);
+    }
+
+    if redraw {
+        execute!(stdout, cursor::Show)?;
+        println!();
+    }
+    Ok(())
+}
+
+/// IDs of every failed operation in the bank, collected before any are retried
+fn failed_operation_ids(client: &ApiClient, bank_id: &str, verbose: bool) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    loop {
+        let page = client.list_operations(
+            bank_id,
+            Some("failed"),
+            Some(OPERATIONS_PAGE_SIZE),
+            Some(ids.len() as u64),
+            verbose,
+        )?;
+        let fetched = page.operations.len();
+        ids.extend(page.operations.into_iter().map(|op| op.id));
+        if (fetched as u64) < OPERATIONS_PAGE_SIZE || ids.len() as i64 >= page.total {
+            return Ok(ids);
         }
-        Err(e) => Err(e),
     }
 }
 
@@ -75,7 +198,7 @@ pub fn get(
             if output_format == OutputFormat::Pretty {
                 ui::print_section_header(&format!("Operation: {}", operation_id));
 
-                use hindsight_client::types::Status;
+                use hindsight_client::enums::Status;
                 let status_str = match &result.status {
                     Status::Completed => ui::gradient_start("completed"),
                     Status::Pending => ui::gradient_mid("pending"),
@@ -83,6 +206,7 @@ pub fn get(
                     Status::Failed => ui::gradient_end("failed"),
                     Status::Cancelled => ui::dim("cancelled"),
                     Status::NotFound => ui::gradient_end("not_found"),
+                    Status::Unknown(status) => ui::dim(status),
                 };
 
                 println!("  {} {}", ui::dim("Status:"), status_str);
@@ -114,59 +238,139 @@ pub fn cancel(
         None
     };
 
-    let response = client.cancel_operation(agent_id, operation_id, verbose);
+    let plan = PlannedWrite::new("cancel_operation", agent_id).target(operation_id);
+    let response = executor::get().execute(plan, || {
+        client.cancel_operation(agent_id, operation_id, verbose)
+    });
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
-    match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                if result.success {
-                    ui::print_success("Operation cancelled successfully");
-                } else {
-                    ui::print_error("Failed to cancel operation");
-                }
-            } else {
-                output::print_output(&result, output_format)?;
-            }
-            Ok(())
+    // Under --dry-run the planned cancel is reported instead
+    let Some(result) = response? else {
+        return Ok(());
+    };
+    if output_format == OutputFormat::Pretty {
+        if result.success {
+            ui::print_success("Operation cancelled successfully");
+        } else {
+            ui::print_error("Failed to cancel operation");
         }
-        Err(e) => Err(e),
+    } else {
+        output::print_output(&result, output_format)?;
     }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct RetryOutcome {
+    operation_id: String,
+    success: bool,
+    message: String,
 }
 
-/// Retry a failed async operation
+/// Retry failed async operations, either the given IDs or every failed one
 pub fn retry(
     client: &ApiClient,
     agent_id: &str,
-    operation_id: &str,
+    operation_ids: Vec<String>,
+    all_failed: bool,
     verbose: bool,
     output_format: OutputFormat,
 ) -> Result<()> {
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Retrying operation..."))
+    let operation_ids = if all_failed {
+        let spinner = if output_format == OutputFormat::Pretty {
+            Some(ui::create_spinner("Finding failed operations..."))
+        } else {
+            None
+        };
+        let ids = failed_operation_ids(client, agent_id, verbose);
+        if let Some(mut sp) = spinner {
+            sp.finish();
+        }
+        let ids = ids?;
+        if ids.is_empty() {
+            if output_format == OutputFormat::Pretty {
+                ui::print_info(&format!("No failed operations in bank '{}'", agent_id));
+            } else {
+                output::print_output(&Vec::<RetryOutcome>::new(), output_format)?;
+            }
+            return Ok(());
+        }
+        ids
     } else {
-        None
+        operation_ids
     };
 
-    let response = client.retry_operation(agent_id, operation_id, verbose);
+    let mut outcomes = Vec::new();
+    for operation_id in &operation_ids {
+        let plan = PlannedWrite::new("retry_operation", agent_id).target(operation_id);
+        let outcome = match executor::get()
+            .execute(plan, || client.retry_operation(agent_id, operation_id, verbose))
+        {
+            Ok(None) => continue,
+            Ok(Some(result)) => RetryOutcome {
+                operation_id: operation_id.clone(),
+                success: result.success,
+                message: result.message,
+            },
+            // Keep going so one bad ID doesn't block the rest
+            Err(e) => RetryOutcome {
+                operation_id: operation_id.clone(),
+                success: false,
+                message: format!("{:#}", e),
+            },
+        };
+        if output_format == OutputFormat::Pretty {
+            if outcome.success {
+                ui::print_success(&format!("Operation '{}' retried", outcome.operation_id));
+            } else {
+                ui::print_error(&format!(
+                    "Failed to retry operation '{}': {}",
+                    outcome.operation_id, outcome.message
+                ));
+            }
+        }
+        outcomes.push(outcome);
+    }
 
-    if let Some(mut sp) = spinner {
-        sp.finish();
+    // Under --dry-run the planned retries are reported instead
+    if output_format != OutputFormat::Pretty && !executor::get().is_dry_run() {
+        output::print_output(&outcomes, output_format)?;
     }
 
-    let result = response?;
-    if output_format == OutputFormat::Pretty {
-        ui::print_success(&format!("Operation '{}' retried", operation_id));
-        let json = serde_json::to_value(&result)?;
-        println!(
-            "  {}",
-            serde_json::to_string_pretty(&json).unwrap_or_default()
-        );
-    } else {
-        output::print_output(&result, output_format)?;
+    let failed = outcomes.iter().filter(|o| !o.success).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} operation retries failed", failed, outcomes.len());
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_row_uses_table_columns() {
+        let op = Operation {
+            id: "op-1".to_string(),
+            task_type: "retain".to_string(),
+            items_count: 3,
+            document_id: None,
+            created_at: "2024-01-15T10:30:00Z".to_string(),
+            status: "failed".to_string(),
+            error_message: Some("LLM timeout".to_string()),
+            retry_count: Some(1),
+            next_retry_at: None,
+        };
+        let row = operation_row(&op);
+        for column in OPERATION_COLUMNS {
+            assert!(row.get(*column).is_some(), "missing column {}", column);
+        }
+        assert_eq!(row["kind"], "retain");
+        assert_eq!(row["error"], "LLM timeout");
+        assert_eq!(api_status("done"), "completed");
+        assert_eq!(api_status("failed"), "failed");
+    }
+}
@@ -0,0 +1,454 @@
+use anyhow::Result;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::path::PathBuf;
+
+use crate::api::{ApiClient, MergeStrategy, RecallRequest};
+use crate::commands::memory;
+use crate::errors::EmptyResult;
+use crate::output::{self, OutputFormat};
+use crate::pager;
+use crate::trace;
+use crate::ui;
+use crate::utils;
+
+/// Error for `--fail-empty` when a recall returns nothing
+const NO_RESULTS: &str = "No results found (--fail-empty)";
+
+/// Run a recall from the top-level `hindsight recall` command.
+///
+/// Mirrors `memory recall` but reads long queries from a file or stdin, can fold
+/// near-duplicate results (`dedupe` is the similarity threshold), and can fail on
+/// empty results for CI checks.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    client: &ApiClient,
+    bank_id: &str,
+    query: Option<String>,
+    query_file: Option<PathBuf>,
+    types: Vec<String>,
+    budget: String,
+    max_tokens: i64,
+    trace: bool,
+    include_chunks: bool,
+    chunk_max_tokens: i64,
+    tags: Vec<String>,
+    tags_match: Option<String>,
+    query_timestamp: Option<String>,
+    window: memory::RecallWindow,
+    fail_empty: bool,
+    show_request: bool,
+    group_by: Option<ui::ResultGrouping>,
+    dedupe: Option<f64>,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if dedupe.is_some_and(|threshold| !(0.0..=1.0).contains(&threshold)) {
+        anyhow::bail!("--dedupe-threshold must be between 0 and 1");
+    }
+    let query = utils::resolve_query(query, query_file.as_deref())?;
+
+    let request = memory::build_recall_request(
+        query,
+        types,
+        &budget,
+        max_tokens,
+        trace,
+        include_chunks,
+        chunk_max_tokens,
+        tags,
+        tags_match,
+        query_timestamp,
+    );
+    if show_request {
+        print_request(&request)?;
+    }
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Recalling memories..."))
+    } else {
+        None
+    };
+
+    let response = client.recall(bank_id, &request, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let mut result = response?;
+    window.apply(&mut result.results);
+    let folded = match dedupe {
+        Some(threshold) => {
+            let deduped = result.deduplicated(threshold);
+            result = deduped.response;
+            Some(deduped.folded)
+        }
+        None => None,
+    };
+    if output_format == OutputFormat::Pretty {
+        pager::page(|| {
+            ui::print_search_results(&result, trace, include_chunks, group_by, folded.as_ref());
+            Ok(())
+        })?;
+    } else {
+        let mut value = if trace {
+            trace::response_with_report(&result)?
+        } else {
+            serde_json::to_value(&result)?
+        };
+        if let (Some(folded), Some(object)) = (&folded, value.as_object_mut()) {
+            object.insert("folded".to_string(), serde_json::to_value(folded)?);
+        }
+        output::print_output(&value, output_format)?;
+    }
+
+    if fail_empty && result.results.is_empty() {
+        return Err(EmptyResult(NO_RESULTS).into());
+    }
+    Ok(())
+}
+
+/// Send one recall to several banks (`hindsight recall --banks a,b,c`) and merge the
+/// results, naming each result's bank. Banks that fail are reported after the results.
+#[allow(clippy::too_many_arguments)]
+pub fn run_multi(
+    client: &ApiClient,
+    bank_ids: &[String],
+    query: Option<String>,
+    query_file: Option<PathBuf>,
+    types: Vec<String>,
+    budget: String,
+    max_tokens: i64,
+    tags: Vec<String>,
+    tags_match: Option<String>,
+    query_timestamp: Option<String>,
+    merge: MergeStrategy,
+    fail_empty: bool,
+    show_request: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let query = utils::resolve_query(query, query_file.as_deref())?;
+
+    let request = memory::build_recall_request(
+        query,
+        types,
+        &budget,
+        max_tokens,
+        false,
+        false,
+        0,
+        tags,
+        tags_match,
+        query_timestamp,
+    );
+    if show_request {
+        print_request(&request)?;
+    }
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner(&format!(
+            "Recalling memories from {} banks...",
+            bank_ids.len()
+        )))
+    } else {
+        None
+    };
+
+    let response = client.recall_multi(bank_ids, &request, merge, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let result = response?;
+    if output_format == OutputFormat::Pretty {
+        pager::page(|| {
+            ui::print_multi_search_results(&result);
+            Ok(())
+        })?;
+    } else {
+        output::print_output(&result, output_format)?;
+    }
+
+    if fail_empty && result.results.is_empty() {
+        return Err(EmptyResult(NO_RESULTS).into());
+    }
+    Ok(())
+}
+
+/// Print the request body for `--show-request`, on stderr so stdout stays parseable
+fn print_request(request: &RecallRequest) -> Result<()> {
+    eprintln!("{}", serde_json::to_string_pretty(request)?);
+    Ok(())
+}
+
+/// Settings that REPL commands (`:budget`, `:limit`, ...) can change between queries
+#[derive(Debug, Clone, PartialEq)]
+struct ReplSettings {
+    bank_id: String,
+    types: Vec<String>,
+    budget: String,
+    max_tokens: i64,
+    limit: Option<usize>,
+    trace: bool,
+    include_chunks: bool,
+    chunk_max_tokens: i64,
+}
+
+/// What the REPL should do with one line of input
+#[derive(Debug, PartialEq)]
+enum ReplAction {
+    Query(String),
+    /// A `:command` was applied; the message describes the new setting
+    Updated(String),
+    Help,
+    Quit,
+    Skip,
+}
+
+const REPL_HELP: &str = "\
+  <query>            run a recall
+  :budget low|mid|high
+  :limit <n>|off     show at most n results
+  :trace on|off
+  :chunks on|off
+  :bank <id>         switch bank
+  :settings          show current settings
+  :quit              exit (or Ctrl+D)";
+
+fn parse_toggle(value: &str) -> Result<bool> {
+    match value {
+        "on" | "true" | "yes" => Ok(true),
+        "off" | "false" | "no" => Ok(false),
+        other => anyhow::bail!("Expected on or off, got '{}'", other),
+    }
+}
+
+impl ReplSettings {
+    fn describe(&self) -> String {
+        format!(
+            "bank={} budget={} limit={} trace={} chunks={}",
+            self.bank_id,
+            self.budget,
+            self.limit.map_or("off".to_string(), |l| l.to_string()),
+            if self.trace { "on" } else { "off" },
+            if self.include_chunks { "on" } else { "off" },
+        )
+    }
+
+    /// Interpret one input line, applying any `:command` to the settings
+    fn handle(&mut self, line: &str) -> Result<ReplAction> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(ReplAction::Skip);
+        }
+        let Some(command) = line.strip_prefix(':') else {
+            return Ok(ReplAction::Query(line.to_string()));
+        };
+        let mut parts = command.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let value = parts.next();
+        match (name, value) {
+            ("q" | "quit" | "exit", _) => return Ok(ReplAction::Quit),
+            ("h" | "help", _) => return Ok(ReplAction::Help),
+            ("settings", _) => {}
+            ("budget", Some(budget @ ("low" | "mid" | "high"))) => self.budget = budget.to_string(),
+            ("budget", _) => anyhow::bail!("Usage: :budget low|mid|high"),
+            ("limit", Some("off")) => self.limit = None,
+            ("limit", Some(n)) => {
+                let n: usize = n
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Usage: :limit <n>|off"))?;
+                self.limit = Some(n);
+            }
+            ("trace", Some(v)) => self.trace = parse_toggle(v)?,
+            ("chunks", Some(v)) => self.include_chunks = parse_toggle(v)?,
+            ("bank", Some(bank_id)) => self.bank_id = bank_id.to_string(),
+            ("limit" | "trace" | "chunks" | "bank", None) => {
+                anyhow::bail!("Missing value for :{}", name)
+            }
+            _ => anyhow::bail!("Unknown command ':{}' (try :help)", name),
+        }
+        Ok(ReplAction::Updated(self.describe()))
+    }
+}
+
+/// `~/.local/share/hindsight/history`
+fn history_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".local").join("share").join("hindsight").join("history"))
+}
+
+fn run_repl_query(
+    client: &ApiClient,
+    settings: &ReplSettings,
+    query: String,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let request = memory::build_recall_request(
+        query,
+        settings.types.clone(),
+        &settings.budget,
+        settings.max_tokens,
+        settings.trace,
+        settings.include_chunks,
+        settings.chunk_max_tokens,
+        Vec::new(),
+        None,
+        None,
+    );
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Recalling memories..."))
+    } else {
+        None
+    };
+    let response = client.recall(&settings.bank_id, &request, verbose);
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let mut result = response?;
+    if let Some(limit) = settings.limit {
+        result.results.truncate(limit);
+    }
+    if output_format == OutputFormat::Pretty {
+        ui::print_search_results(&result, settings.trace, settings.include_chunks, None, None);
+    } else if settings.trace {
+        output::print_output(&trace::response_with_report(&result)?, output_format)?;
+    } else {
+        output::print_output(&result, output_format)?;
+    }
+    Ok(())
+}
+
+/// Interactive recall loop for `hindsight recall <bank> --interactive`.
+///
+/// Each line runs a recall; `:` commands adjust settings. Errors are printed
+/// and the session continues. Ctrl+D exits.
+#[allow(clippy::too_many_arguments)]
+pub fn interactive(
+    client: &ApiClient,
+    bank_id: &str,
+    types: Vec<String>,
+    budget: String,
+    max_tokens: i64,
+    trace: bool,
+    include_chunks: bool,
+    chunk_max_tokens: i64,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut settings = ReplSettings {
+        bank_id: bank_id.to_string(),
+        types,
+        budget,
+        max_tokens,
+        limit: None,
+        trace,
+        include_chunks,
+        chunk_max_tokens,
+    };
+
+    let mut editor = DefaultEditor::new()?;
+    let history = history_path();
+    if let Some(path) = &history {
+        // Missing history on first run is expected
+        let _ = editor.load_history(path);
+    }
+
+    ui::print_info(&format!(
+        "Interactive recall ({}). Type :help for commands, Ctrl+D to exit.",
+        settings.describe()
+    ));
+
+    loop {
+        let prompt = format!("{}> ", settings.bank_id);
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            // Ctrl+C abandons the current line, like a shell
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if !line.trim().is_empty() {
+            let _ = editor.add_history_entry(line.as_str());
+        }
+
+        match settings.handle(&line) {
+            Ok(ReplAction::Query(query)) => {
+                if let Err(e) = run_repl_query(client, &settings, query, verbose, output_format) {
+                    ui::print_error(&format!("{:#}", e));
+                }
+            }
+            Ok(ReplAction::Updated(message)) => println!("  {}", ui::dim(&message)),
+            Ok(ReplAction::Help) => println!("{}", REPL_HELP),
+            Ok(ReplAction::Quit) => break,
+            Ok(ReplAction::Skip) => {}
+            Err(e) => ui::print_error(&format!("{:#}", e)),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(e) = editor.save_history(path) {
+            ui::print_warning(&format!("Could not save history to {}: {}", path.display(), e));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> ReplSettings {
+        ReplSettings {
+            bank_id: "alice".to_string(),
+            types: vec!["world".to_string()],
+            budget: "mid".to_string(),
+            max_tokens: 4096,
+            limit: None,
+            trace: false,
+            include_chunks: false,
+            chunk_max_tokens: 8192,
+        }
+    }
+
+    #[test]
+    fn test_repl_commands_update_settings() {
+        let mut s = settings();
+        assert!(matches!(s.handle(":budget high").unwrap(), ReplAction::Updated(_)));
+        s.handle(":limit 10").unwrap();
+        s.handle(":trace on").unwrap();
+        s.handle(":chunks on").unwrap();
+        s.handle(":bank bob").unwrap();
+        assert_eq!(s.budget, "high");
+        assert_eq!(s.limit, Some(10));
+        assert!(s.trace && s.include_chunks);
+        assert_eq!(s.bank_id, "bob");
+
+        s.handle(":limit off").unwrap();
+        assert_eq!(s.limit, None);
+    }
+
+    #[test]
+    fn test_repl_queries_and_errors() {
+        let mut s = settings();
+        assert_eq!(
+            s.handle("  where does alice work? ").unwrap(),
+            ReplAction::Query("where does alice work?".to_string())
+        );
+        assert_eq!(s.handle("").unwrap(), ReplAction::Skip);
+        assert_eq!(s.handle(":quit").unwrap(), ReplAction::Quit);
+        assert!(s.handle(":budget extreme").is_err());
+        assert!(s.handle(":limit many").is_err());
+        assert!(s.handle(":bank").is_err());
+        assert!(s.handle(":frobnicate").is_err());
+        assert_eq!(s, settings());
+    }
+}
@@ -0,0 +1,280 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::{ApiClient, ReflectResponse, RetainRequest};
+use crate::commands::memory;
+use crate::config;
+use crate::output::{self, OutputFormat};
+use crate::ui;
+use crate::utils;
+
+/// A prior conversation turn supplied with `--context-file`
+#[derive(Debug, Deserialize)]
+struct ConversationTurn {
+    #[serde(alias = "speaker")]
+    role: String,
+    #[serde(alias = "text")]
+    content: String,
+}
+
+/// Load prior turns for the reflect context.
+///
+/// Accepts a JSON array of `{"role", "content"}` objects (or `{"messages": [...]}`);
+/// any other file is passed through as plain text.
+fn load_context_file(path: &Path) -> Result<String> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read context file: {}", path.display()))?;
+    Ok(format_context(&raw))
+}
+
+fn format_context(raw: &str) -> String {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Conversation {
+        Turns(Vec<ConversationTurn>),
+        Wrapped { messages: Vec<ConversationTurn> },
+    }
+
+    match serde_json::from_str::<Conversation>(raw) {
+        Ok(Conversation::Turns(turns)) | Ok(Conversation::Wrapped { messages: turns }) => turns
+            .iter()
+            .map(|t| format!("{}: {}", t.role, t.content.trim()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(_) => raw.trim().to_string(),
+    }
+}
+
+/// Memories looked up at once when resolving citations
+const CITATION_FETCH_BATCH: usize = 8;
+
+/// A reflect response with the memories it cites, for structured output
+#[derive(Debug, Serialize)]
+pub(crate) struct CitedReflection<'a> {
+    #[serde(flatten)]
+    pub response: &'a ReflectResponse,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<ui::Citation>,
+    /// Where `--save-as-memory` stored the reflection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saved: Option<SavedReflection>,
+}
+
+/// One citation per memory in `based_on`, numbered in the order the server listed them.
+/// With `fetch`, each memory is looked up (a batch at a time) for its date and full
+/// text; one that can't be fetched is marked unavailable instead of failing the command.
+pub(crate) fn collect_citations(
+    client: &ApiClient,
+    bank_id: &str,
+    response: &ReflectResponse,
+    fetch: bool,
+    verbose: bool,
+) -> Vec<ui::Citation> {
+    let Some(based_on) = &response.based_on else {
+        return Vec::new();
+    };
+    let mut citations: Vec<ui::Citation> = based_on
+        .memories
+        .iter()
+        .map(|fact| ui::Citation::from_memory(&serde_json::to_value(fact).unwrap_or_default()))
+        .collect();
+    if !fetch {
+        return citations;
+    }
+
+    let ids: Vec<(usize, String)> = citations
+        .iter()
+        .enumerate()
+        .filter_map(|(i, citation)| Some((i, citation.id.clone()?)))
+        .collect();
+    for batch in ids.chunks(CITATION_FETCH_BATCH) {
+        let fetched: Vec<Option<serde_json::Value>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .iter()
+                .map(|(_, id)| scope.spawn(move || client.get_memory(bank_id, id, verbose)))
+                .collect();
+            handles.into_iter().map(|h| h.join().ok().and_then(|r| r.ok())).collect()
+        });
+        for ((i, id), memory) in batch.iter().zip(fetched) {
+            let cited = &mut citations[*i];
+            *cited = match memory {
+                Some(memory) => {
+                    let found = ui::Citation::from_memory(&memory);
+                    ui::Citation {
+                        id: Some(id.clone()),
+                        fact_type: found.fact_type.or(cited.fact_type.take()),
+                        date: found.date.or(cited.date.take()),
+                        text: found.text.or(cited.text.take()),
+                        available: true,
+                    }
+                }
+                None => ui::Citation::unavailable(id),
+            };
+        }
+    }
+    citations
+}
+
+/// The memory `--save-as-memory` created, identified by its document like `memory add`
+#[derive(Debug, Serialize)]
+pub(crate) struct SavedReflection {
+    document_id: String,
+    items_count: i64,
+}
+
+const SAVE_FAILED: &str = "Reflection succeeded but saving it as a memory failed";
+
+/// Run a reflect from the top-level `hindsight reflect` command
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    client: &ApiClient,
+    bank_id: &str,
+    query: Option<String>,
+    query_file: Option<PathBuf>,
+    budget: String,
+    context: Option<String>,
+    context_file: Option<PathBuf>,
+    max_tokens: Option<i64>,
+    schema: Option<PathBuf>,
+    tags: Vec<String>,
+    tags_match: Option<String>,
+    include: Vec<String>,
+    fact_types: Option<Vec<String>>,
+    exclude_mental_models: bool,
+    exclude_mental_model_ids: Option<Vec<String>>,
+    save_as_memory: bool,
+    fetch_citations: bool,
+    verbose: bool,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let query = utils::resolve_query(query, query_file.as_deref())?;
+
+    // Inline --context and the file's prior turns are combined, file first
+    let context = match (context_file, context) {
+        (Some(path), inline) => {
+            let turns = load_context_file(&path)?;
+            Some(match inline {
+                Some(extra) => format!("{}\n\n{}", turns, extra),
+                None => turns,
+            })
+        }
+        (None, inline) => inline,
+    };
+
+    let request = memory::build_reflect_request(
+        query.clone(),
+        &budget,
+        context,
+        max_tokens,
+        schema,
+        tags,
+        tags_match,
+        &include,
+        fact_types,
+        exclude_mental_models,
+        exclude_mental_model_ids,
+    )?;
+
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Reflecting..."))
+    } else {
+        None
+    };
+
+    let response = client.reflect(bank_id, &request, verbose);
+    let citations = match &response {
+        Ok(result) => collect_citations(client, bank_id, result, fetch_citations, verbose),
+        Err(_) => Vec::new(),
+    };
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let result = response?;
+    if output_format == OutputFormat::Pretty {
+        ui::print_think_response(&result, &citations);
+        if save_as_memory {
+            // The reflection is already on screen; a failed save only reports the error
+            let saved = save_reflection(client, bank_id, &query, &result.text, verbose)
+                .context(SAVE_FAILED)?;
+            ui::print_success(&format!(
+                "Saved reflection as memory (document: {})",
+                saved.document_id
+            ));
+        }
+        return Ok(());
+    }
+
+    // Saved before printing so stdout stays a single document; a failed save still
+    // prints the reflection and then reports the error
+    let (saved, save_error) = if save_as_memory {
+        match save_reflection(client, bank_id, &query, &result.text, verbose) {
+            Ok(saved) => (Some(saved), None),
+            Err(e) => (None, Some(e)),
+        }
+    } else {
+        (None, None)
+    };
+    let cited = CitedReflection {
+        response: &result,
+        citations,
+        saved,
+    };
+    output::print_output(&cited, output_format)?;
+    match save_error {
+        Some(e) => Err(e.context(SAVE_FAILED)),
+        None => Ok(()),
+    }
+}
+
+fn save_reflection(
+    client: &ApiClient,
+    bank_id: &str,
+    query: &str,
+    text: &str,
+    verbose: bool,
+) -> Result<SavedReflection> {
+    let document_id = format!("{}_reflect", config::generate_doc_id());
+    let item = memory::build_memory_item(
+        text,
+        Some(&format!("Reflection on: {}", query)),
+        None,
+        &document_id,
+        Some("agent"),
+    )?;
+    let request = RetainRequest {
+        items: vec![item],
+        async_: false,
+        document_tags: None,
+    };
+    let result = client.retain(bank_id, &request, false, verbose)?;
+    Ok(SavedReflection {
+        document_id,
+        items_count: result.items_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_context_turns() {
+        let raw = r#"[{"role": "user", "content": "Hi"}, {"role": "assistant", "content": " Hello! "}]"#;
+        assert_eq!(format_context(raw), "user: Hi\nassistant: Hello!");
+    }
+
+    #[test]
+    fn test_format_context_wrapped_messages() {
+        let raw = r#"{"messages": [{"speaker": "alice", "text": "Where is Bob?"}]}"#;
+        assert_eq!(format_context(raw), "alice: Where is Bob?");
+    }
+
+    #[test]
+    fn test_format_context_plain_text() {
+        assert_eq!(format_context("  just some notes\n"), "just some notes");
+    }
+}
@@ -3,20 +3,214 @@ use std::env;
 use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::credentials;
+use crate::output::OutputFormat;
 
 const DEFAULT_API_URL: &str = "http://localhost:8888";
 const CONFIG_FILE_NAME: &str = "config";
 const CONFIG_DIR_NAME: &str = ".hindsight";
 const PROFILE_DIR_NAME: &str = "cli-profiles";
 const PROFILE_ENV_VAR: &str = "HINDSIGHT_PROFILE";
+/// Key in the local config file naming the profile chosen with `config use-context`
+const CURRENT_PROFILE_KEY: &str = "current_profile";
+
+/// What a setting's value must look like
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingKind {
+    Url,
+    /// Masked whenever it is shown
+    Secret,
+    Text,
+    Bool,
+    /// A whole number in the inclusive range
+    Number(u64, u64),
+    Choice(&'static [&'static str]),
+}
+
+/// A key `hindsight config set` can write to a profile or the local config file
+#[derive(Debug)]
+pub struct SettingKey {
+    pub name: &'static str,
+    pub kind: SettingKind,
+    /// Value used when the key is set nowhere
+    pub default: Option<&'static str>,
+    pub description: &'static str,
+}
+
+pub const SETTING_KEYS: &[SettingKey] = &[
+    SettingKey {
+        name: "api_url",
+        kind: SettingKind::Url,
+        default: Some(DEFAULT_API_URL),
+        description: "Hindsight API URL",
+    },
+    SettingKey {
+        name: "api_key",
+        kind: SettingKind::Secret,
+        default: None,
+        description: "API key",
+    },
+    SettingKey {
+        name: "default_bank",
+        kind: SettingKind::Text,
+        default: None,
+        description: "Bank used when a command's bank ID is omitted",
+    },
+    SettingKey {
+        name: "pager",
+        kind: SettingKind::Text,
+        default: None,
+        description: "Pager command for long output ($PAGER if unset), or false to never page",
+    },
+    SettingKey {
+        name: "dates",
+        kind: SettingKind::Choice(&["relative", "absolute", "iso"]),
+        default: Some("relative"),
+        description: "How pretty output shows timestamps",
+    },
+    SettingKey {
+        name: "theme",
+        kind: SettingKind::Choice(&["dark", "light", "monochrome"]),
+        default: Some("dark"),
+        description: "Explorer color theme",
+    },
+    SettingKey {
+        name: "refresh_secs",
+        kind: SettingKind::Number(0, 86_400),
+        default: Some("5"),
+        description: "Seconds between explorer auto-refreshes, 0 for none",
+    },
+    SettingKey {
+        name: "page_size",
+        kind: SettingKind::Number(25, 500),
+        default: Some("100"),
+        description: "Rows the explorer loads per page",
+    },
+    SettingKey {
+        name: "verbose_loads",
+        kind: SettingKind::Bool,
+        default: Some("false"),
+        description: "Load explorer lists in verbose mode",
+    },
+    SettingKey {
+        name: "recall_chunks",
+        kind: SettingKind::Bool,
+        default: Some("true"),
+        description: "Include source chunks in explorer recalls",
+    },
+    SettingKey {
+        name: "reflect_max_tokens",
+        kind: SettingKind::Number(1, 1_000_000),
+        default: Some("4096"),
+        description: "Max tokens of a reflect answer when --max-tokens is not given",
+    },
+];
+
+/// Look up a settable key; the error lists every valid one
+pub fn setting_key(name: &str) -> Result<&'static SettingKey> {
+    SETTING_KEYS.iter().find(|key| key.name == name).ok_or_else(|| {
+        let valid: Vec<String> = SETTING_KEYS
+            .iter()
+            .map(|key| format!("{:14} {}", key.name, key.description))
+            .collect();
+        anyhow::anyhow!("Unknown config key '{}'. Valid keys:\n  {}", name, valid.join("\n  "))
+    })
+}
+
+impl SettingKey {
+    /// Check `value` and render it as a TOML value
+    pub fn toml_value(&self, value: &str) -> Result<String> {
+        let value = value.trim();
+        let invalid = |expected: String| {
+            anyhow::anyhow!("Invalid value for {}: {:?} (expected {})", self.name, value, expected)
+        };
+        match self.kind {
+            SettingKind::Url if !value.starts_with("http://") && !value.starts_with("https://") => {
+                Err(invalid("a URL starting with http:// or https://".to_string()))
+            }
+            SettingKind::Bool => match value {
+                "true" | "false" => Ok(value.to_string()),
+                _ => Err(invalid("true or false".to_string())),
+            },
+            SettingKind::Number(min, max) => match value.parse::<u64>() {
+                Ok(n) if (min..=max).contains(&n) => Ok(n.to_string()),
+                _ => Err(invalid(format!("a whole number from {} to {}", min, max))),
+            },
+            SettingKind::Choice(choices) if !choices.contains(&value) => {
+                Err(invalid(format!("one of {}", choices.join(", "))))
+            }
+            _ if value.is_empty() || value.contains(['"', '\n']) => {
+                Err(invalid("a non-empty single-line value without quotes".to_string()))
+            }
+            _ => Ok(format!("\"{}\"", value)),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Config {
     pub api_url: String,
     pub api_key: Option<String>,
+    /// Request timeout from `HINDSIGHT_TIMEOUT_SECS`
+    pub timeout: Option<Duration>,
     pub source: ConfigSource,
 }
 
+/// Settings taken from `HINDSIGHT_*` environment variables. Each one ranks
+/// below its explicit command-line flag.
+#[derive(Debug, Default)]
+pub struct EnvSettings {
+    /// `HINDSIGHT_URL`, or the older `HINDSIGHT_API_URL`
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+    pub timeout: Option<Duration>,
+    pub default_bank: Option<String>,
+    /// `HINDSIGHT_OUTPUT`, used when `-o/--output` is not given
+    pub output: Option<OutputFormat>,
+}
+
+impl EnvSettings {
+    pub fn load() -> Result<Self> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Read settings through `lookup`; every invalid variable is reported in one error.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let (client_env, mut problems) = match hindsight_client::EnvConfig::from_lookup(&lookup) {
+            Ok(client_env) => (Some(client_env), Vec::new()),
+            Err(hindsight_client::FromEnvError::InvalidVars(problems)) => (None, problems),
+            Err(e) => return Err(e.into()),
+        };
+
+        let output = lookup("HINDSIGHT_OUTPUT")
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .and_then(|value| {
+                let format = OutputFormat::from_str(&value);
+                if format.is_none() {
+                    problems.push(format!(
+                        "HINDSIGHT_OUTPUT={:?}: expected pretty, json, yaml, table, or csv",
+                        value
+                    ));
+                }
+                format
+            });
+
+        match client_env {
+            Some(client_env) if problems.is_empty() => Ok(EnvSettings {
+                api_url: client_env.base_url,
+                api_key: client_env.api_key,
+                timeout: client_env.timeout,
+                default_bank: client_env.default_bank,
+                output,
+            }),
+            _ => anyhow::bail!("Invalid environment configuration:\n  {}", problems.join("\n  ")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConfigSource {
     LocalFile,
@@ -43,38 +237,46 @@ impl Config {
     }
 
     /// Load configuration with the following priority:
-    /// 1. Environment variable (HINDSIGHT_API_URL/HINDSIGHT_API_KEY) - highest priority
+    /// 1. Environment variable (HINDSIGHT_URL or HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority
     /// 2. Named profile (from `profile_name` arg, else `$HINDSIGHT_PROFILE`)
     ///    at `~/.hindsight/cli-profiles/<name>.toml`
     /// 3. Local config file (`~/.hindsight/config`)
     /// 4. Default (http://localhost:8888)
+    ///
+    /// The API key is resolved separately: `HINDSIGHT_API_KEY`, then the key stored
+    /// with `hindsight auth login` for the profile, then the file's `api_key`.
     pub fn load_with_profile(profile_name: Option<&str>) -> Result<Self> {
-        let env_api_key = env::var("HINDSIGHT_API_KEY").ok();
+        let env_settings = EnvSettings::load()?;
+        let resolved_profile = Self::active_profile(profile_name);
+        // A keyring that can't be reached just means no stored key
+        let key_override = env_settings.api_key.or_else(|| {
+            credentials::load(credentials::account_name(resolved_profile.as_deref()))
+                .ok()
+                .flatten()
+                .map(|(key, _)| key)
+        });
+        let timeout = env_settings.timeout;
 
         // 1. Environment variable takes highest priority
-        if let Ok(api_url) = env::var("HINDSIGHT_API_URL") {
-            return Self::validate_and_create(api_url, env_api_key, ConfigSource::Environment);
+        if let Some(api_url) = env_settings.api_url {
+            return Self::validate_and_create(api_url, key_override, timeout, ConfigSource::Environment);
         }
 
         // 2. Named profile (explicit flag takes precedence over env var)
-        let resolved_profile: Option<String> = profile_name
-            .map(|s| s.to_string())
-            .or_else(|| env::var(PROFILE_ENV_VAR).ok().filter(|s| !s.is_empty()));
-
         if let Some(name) = resolved_profile {
             let (api_url, file_api_key) = Self::load_profile(&name)?;
-            let api_key = env_api_key.or(file_api_key);
-            return Self::validate_and_create(api_url, api_key, ConfigSource::Profile(name));
+            let api_key = key_override.or(file_api_key);
+            return Self::validate_and_create(api_url, api_key, timeout, ConfigSource::Profile(name));
         }
 
         // 3. Local config file
         if let Some((api_url, file_api_key)) = Self::load_from_file()? {
-            let api_key = env_api_key.or(file_api_key);
-            return Self::validate_and_create(api_url, api_key, ConfigSource::LocalFile);
+            let api_key = key_override.or(file_api_key);
+            return Self::validate_and_create(api_url, api_key, timeout, ConfigSource::LocalFile);
         }
 
         // 4. Fall back to default
-        Self::validate_and_create(DEFAULT_API_URL.to_string(), env_api_key, ConfigSource::Default)
+        Self::validate_and_create(DEFAULT_API_URL.to_string(), key_override, timeout, ConfigSource::Default)
     }
 
     /// Legacy method for backwards compatibility
@@ -82,14 +284,24 @@ impl Config {
         Self::load()
     }
 
-    fn validate_and_create(api_url: String, api_key: Option<String>, source: ConfigSource) -> Result<Self> {
+    fn validate_and_create(
+        api_url: String,
+        api_key: Option<String>,
+        timeout: Option<Duration>,
+        source: ConfigSource,
+    ) -> Result<Self> {
         if !api_url.starts_with("http://") && !api_url.starts_with("https://") {
             anyhow::bail!(
                 "Invalid API URL: {}. Must start with http:// or https://",
                 api_url
             );
         }
-        Ok(Config { api_url, api_key, source })
+        Ok(Config {
+            api_url,
+            api_key,
+            timeout,
+            source,
+        })
     }
 
     fn config_dir() -> Option<PathBuf> {
@@ -172,8 +384,146 @@ impl Config {
         &self.api_url
     }
 
+    /// `default_bank` from the active profile (or the local config file when no
+    /// profile is selected). Used to pre-select an entry in the bank picker.
+    pub fn default_bank(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "default_bank")
+    }
+
+    /// `pager` from the active profile or local config file: a pager command, or
+    /// `false` to never page long pretty output
+    pub fn pager(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "pager")
+    }
+
+    /// `dates` from the active profile or local config file: `relative`, `absolute` or `iso`
+    pub fn dates(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "dates")
+    }
+
+    /// `theme` from the active profile or local config file: `dark`, `light` or `monochrome`
+    pub fn theme(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "theme")
+    }
+
+    /// `refresh_secs` from the active profile or local config file: seconds between
+    /// auto-refreshes in the explorer, 0 to turn them off
+    pub fn refresh_secs(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "refresh_secs")
+    }
+
+    /// `page_size` from the active profile or local config file: rows the explorer loads
+    /// per page of memories, entities and documents (25 to 500)
+    pub fn page_size(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "page_size")
+    }
+
+    /// `verbose_loads` from the active profile or local config file: `true` to load the
+    /// explorer's lists in verbose mode
+    pub fn verbose_loads(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "verbose_loads")
+    }
+
+    /// `recall_chunks` from the active profile or local config file: `false` to leave the
+    /// source chunks out of explorer recalls until a result is opened
+    pub fn recall_chunks(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "recall_chunks")
+    }
+
+    /// `reflect_max_tokens` from the active profile or local config file: the longest
+    /// reflect answer to ask for when `--max-tokens` is not given
+    pub fn reflect_max_tokens(profile_name: Option<&str>) -> Option<String> {
+        Self::setting(profile_name, "reflect_max_tokens")
+    }
+
+    /// `[explorer.keys]` from the active profile or local config file: action
+    /// names with the keys bound to them, as written
+    pub fn explorer_keys(profile_name: Option<&str>) -> Vec<(String, String)> {
+        Self::settings_content(profile_name)
+            .map(|content| parse_config_section(&content, "explorer.keys"))
+            .unwrap_or_default()
+    }
+
+    fn setting(profile_name: Option<&str>, key: &str) -> Option<String> {
+        let content = Self::settings_content(profile_name)?;
+        content.lines().find_map(|line| parse_config_value(line, key))
+    }
+
+    fn settings_content(profile_name: Option<&str>) -> Option<String> {
+        let path = match Self::active_profile(profile_name) {
+            Some(name) => {
+                validate_profile_name(&name).ok()?;
+                Self::profile_file_path(&name)?
+            }
+            None => Self::config_file_path()?,
+        };
+        fs::read_to_string(path).ok()
+    }
+
     // ---------- profile support ----------
 
+    /// The profile in effect: the `-p/--profile` flag, else `$HINDSIGHT_PROFILE`, else
+    /// the one chosen with `hindsight config use-context`
+    pub fn active_profile(profile_name: Option<&str>) -> Option<String> {
+        profile_name
+            .map(|s| s.to_string())
+            .or_else(|| env::var(PROFILE_ENV_VAR).ok().filter(|s| !s.is_empty()))
+            .or_else(Self::current_profile)
+    }
+
+    /// The profile chosen with `hindsight config use-context`
+    pub fn current_profile() -> Option<String> {
+        let content = fs::read_to_string(Self::config_file_path()?).ok()?;
+        content
+            .lines()
+            .find_map(|line| parse_config_value(line, CURRENT_PROFILE_KEY))
+    }
+
+    /// Make `profile` the default for commands run without `-p/--profile` or
+    /// `$HINDSIGHT_PROFILE`, or clear the choice with `None`
+    pub fn use_profile(profile: Option<&str>) -> Result<PathBuf> {
+        if let Some(name) = profile {
+            Self::load_profile(name)?;
+        }
+        let path = Self::config_file_path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+        let value = profile.map(|name| format!("\"{}\"", name));
+        update_config_file(&path, CURRENT_PROFILE_KEY, value.as_deref(), false)?;
+        Ok(path)
+    }
+
+    /// The file `hindsight config set` writes to: the active profile's, else the
+    /// local config file
+    pub fn settings_path(profile_name: Option<&str>) -> Result<PathBuf> {
+        let path = match Self::active_profile(profile_name) {
+            Some(name) => {
+                validate_profile_name(&name)?;
+                Self::profile_file_path(&name)
+            }
+            None => Self::config_file_path(),
+        };
+        path.ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))
+    }
+
+    /// Check `value` for `key` and write it to the settings file, keeping the
+    /// file's other lines and comments
+    pub fn set_setting(profile_name: Option<&str>, key: &str, value: &str) -> Result<PathBuf> {
+        let key = setting_key(key)?;
+        let value = key.toml_value(value)?;
+        let is_profile = Self::active_profile(profile_name).is_some();
+        let path = Self::settings_path(profile_name)?;
+        if is_profile && !path.exists() {
+            anyhow::bail!("profile file {} not found", path.display());
+        }
+        update_config_file(&path, key.name, Some(&value), is_profile)?;
+        Ok(path)
+    }
+
+    /// `key` as written in the active profile or local config file
+    pub fn file_setting(profile_name: Option<&str>, key: &str) -> Option<String> {
+        Self::setting(profile_name, key)
+    }
+
     pub fn profile_dir() -> Option<PathBuf> {
         Self::config_dir().map(|dir| dir.join(PROFILE_DIR_NAME))
     }
@@ -340,6 +690,96 @@ fn validate_profile_name(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Set `key` to `value` (already rendered as TOML) in the file at `path`, or remove
+/// it when `value` is `None`, and replace the file atomically
+fn update_config_file(
+    path: &std::path::Path,
+    key: &str,
+    value: Option<&str>,
+    private: bool,
+) -> Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+    let updated = set_config_value(&content, key, value);
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid config path: {}", path.display()))?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+    write_atomic(path, &updated, private)
+}
+
+/// Write `content` to a temporary file next to `path`, then rename it over `path`,
+/// so readers never see a half-written file
+fn write_atomic(path: &std::path::Path, content: &str, private: bool) -> Result<()> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("config");
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    fs::write(&tmp, content)
+        .with_context(|| format!("Failed to write config file: {}", tmp.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // Keep the permissions of the file being replaced; profiles are always 0600
+        let mode = match fs::metadata(path) {
+            _ if private => 0o600,
+            Ok(meta) => meta.permissions().mode() & 0o777,
+            Err(_) => 0o644,
+        };
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(mode))
+            .with_context(|| format!("Failed to set permissions on {}", tmp.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        anyhow::Error::new(e).context(format!("Failed to replace {}", path.display()))
+    })
+}
+
+/// `content` with the top-level `key = ...` line replaced by `key = value`, or
+/// removed for `None`. A new key goes after the last top-level line, before any
+/// `[section]`. Comments and other lines are kept.
+fn set_config_value(content: &str, key: &str, value: Option<&str>) -> String {
+    let is_key = |line: &str| {
+        line.split_once('=')
+            .is_some_and(|(name, _)| name.trim() == key && !line.trim_start().starts_with('#'))
+    };
+    let section_start = content
+        .lines()
+        .position(|line| line.trim_start().starts_with('['))
+        .unwrap_or(usize::MAX);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let existing = lines
+        .iter()
+        .take(section_start)
+        .position(|line| is_key(line));
+    let new_line = value.map(|value| format!("{} = {}", key, value));
+    match (existing, new_line) {
+        (Some(index), Some(line)) => lines[index] = line,
+        (Some(index), None) => {
+            lines.remove(index);
+        }
+        (None, Some(line)) => {
+            // After the last non-blank top-level line
+            let top = section_start.min(lines.len());
+            let at = lines[..top]
+                .iter()
+                .rposition(|l| !l.trim().is_empty())
+                .map_or(0, |i| i + 1);
+            lines.insert(at, line);
+        }
+        (None, None) => {}
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
 /// Prompt user for API URL interactively
 pub fn prompt_api_url(current_url: Option<&str>) -> Result<String> {
     let default = current_url.unwrap_or(DEFAULT_API_URL);
@@ -375,6 +815,22 @@ pub fn parse_config_value(line: &str, key: &str) -> Option<String> {
     }).filter(|v| !v.is_empty())
 }
 
+/// The `key = value` lines under a `[section]` header, up to the next header.
+/// Values are left as written, quotes and all.
+pub fn parse_config_section(content: &str, section: &str) -> Vec<(String, String)> {
+    let header = format!("[{}]", section);
+    content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != header)
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,6 +859,30 @@ mod tests {
         dir
     }
 
+    #[test]
+    fn test_set_config_value_keeps_comments_and_sections() {
+        let content =
+            "# my settings\napi_url = \"http://a:8888\"\n\n[explorer.keys]\nquit = \"q\"\n";
+        let updated = set_config_value(content, "api_url", Some("\"http://b:8888\""));
+        assert_eq!(
+            updated,
+            "# my settings\napi_url = \"http://b:8888\"\n\n[explorer.keys]\nquit = \"q\"\n"
+        );
+
+        let added = set_config_value(&updated, "page_size", Some("100"));
+        assert!(added.contains("api_url = \"http://b:8888\"\npage_size = 100\n\n[explorer"));
+
+        let removed = set_config_value(&added, "page_size", None);
+        assert_eq!(removed, updated);
+        assert_eq!(set_config_value("", "dates", Some("\"iso\"")), "dates = \"iso\"\n");
+
+        assert!(setting_key("colour").unwrap_err().to_string().contains("Valid keys:\n  api_url"));
+        let page_size = setting_key("page_size").unwrap();
+        assert_eq!(page_size.toml_value("100").unwrap(), "100");
+        assert!(page_size.toml_value("5").is_err());
+        assert!(setting_key("api_url").unwrap().toml_value("localhost").is_err());
+    }
+
     #[test]
     fn test_validate_profile_name_ok() {
         assert!(validate_profile_name("prod").is_ok());
@@ -603,13 +1083,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_config_section() {
+        let content = "api_url = \"http://localhost:8888\"\n\
+                       [explorer.keys]\n\
+                       # vim-style delete\n\
+                       delete = \"dd\"\n\
+                       \n\
+                       query = [\"/\", \"ctrl+f\"]\n\
+                       [other]\n\
+                       quit = \"Q\"\n";
+        assert_eq!(
+            parse_config_section(content, "explorer.keys"),
+            vec![
+                ("delete".to_string(), "\"dd\"".to_string()),
+                ("query".to_string(), "[\"/\", \"ctrl+f\"]".to_string()),
+            ]
+        );
+        assert!(parse_config_section(content, "missing").is_empty());
+    }
+
     #[test]
     fn test_config_api_url_accessor() {
         let config = Config {
             api_url: "http://test:8080".to_string(),
             api_key: None,
+            timeout: None,
             source: ConfigSource::Default,
         };
         assert_eq!(config.api_url(), "http://test:8080");
     }
+
+    const ENV_VARS: [&str; 6] = [
+        "HINDSIGHT_URL",
+        "HINDSIGHT_API_URL",
+        "HINDSIGHT_API_KEY",
+        "HINDSIGHT_TIMEOUT_SECS",
+        "HINDSIGHT_DEFAULT_BANK",
+        "HINDSIGHT_OUTPUT",
+    ];
+
+    /// Run `f` with exactly `vars` set among the Hindsight variables
+    fn with_env<R>(vars: &[(&str, &str)], f: impl FnOnce() -> R) -> R {
+        let scoped: Vec<(&str, Option<&str>)> = ENV_VARS
+            .iter()
+            .map(|name| (*name, vars.iter().find(|(k, _)| k == name).map(|(_, v)| *v)))
+            .collect();
+        temp_env::with_vars(scoped, f)
+    }
+
+    #[test]
+    fn test_env_settings_reads_variables() {
+        let settings = with_env(
+            &[
+                ("HINDSIGHT_API_URL", "http://legacy:8888"),
+                ("HINDSIGHT_TIMEOUT_SECS", "45"),
+                ("HINDSIGHT_DEFAULT_BANK", "alice"),
+                ("HINDSIGHT_OUTPUT", "JSON"),
+            ],
+            EnvSettings::load,
+        )
+        .unwrap();
+        assert_eq!(settings.api_url.as_deref(), Some("http://legacy:8888"));
+        assert_eq!(settings.timeout, Some(Duration::from_secs(45)));
+        assert_eq!(settings.default_bank.as_deref(), Some("alice"));
+        assert_eq!(settings.output, Some(OutputFormat::Json));
+
+        let empty = with_env(&[], EnvSettings::load).unwrap();
+        assert!(empty.api_url.is_none() && empty.output.is_none());
+    }
+
+    #[test]
+    fn test_env_settings_aggregates_errors() {
+        let err = with_env(
+            &[
+                ("HINDSIGHT_URL", "not a url"),
+                ("HINDSIGHT_TIMEOUT_SECS", "-1"),
+                ("HINDSIGHT_OUTPUT", "xml"),
+            ],
+            EnvSettings::load,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("HINDSIGHT_URL="));
+        assert!(err.contains("HINDSIGHT_TIMEOUT_SECS="));
+        assert!(err.contains("HINDSIGHT_OUTPUT="));
+    }
+
+    #[test]
+    fn test_env_url_overrides_config_file() {
+        let config = with_env(&[("HINDSIGHT_URL", "https://env.example.com")], || {
+            Config::load_with_profile(None)
+        })
+        .unwrap();
+        assert_eq!(config.api_url(), "https://env.example.com");
+        assert_eq!(config.source, ConfigSource::Environment);
+    }
 }
@@ -0,0 +1,308 @@
+//! API key storage for `hindsight auth login`.
+//!
+//! Keys go to the OS keyring (macOS Keychain, Windows Credential Manager, Secret
+//! Service on Linux). When no keyring is reachable, e.g. on a headless Linux box
+//! without D-Bus, they fall back to `~/.hindsight/credentials`, a file readable only
+//! by its owner. Entries are sealed with a key derived from the machine ID and home
+//! path, both of which any local user can read. That is obfuscation, not encryption
+//! with a secret: it keeps keys out of plain-text config and out of casual view, but
+//! anyone who can read the file on this machine can recover them.
+
+use anyhow::{anyhow, Context, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const KEYRING_SERVICE: &str = "hindsight-cli";
+const CREDENTIALS_FILE_NAME: &str = "credentials";
+const NONCE_LEN: usize = 12;
+
+/// Account name used when no profile is selected
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    Keyring,
+    ObfuscatedFile(PathBuf),
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::Keyring => write!(f, "OS keyring"),
+            Backend::ObfuscatedFile(path) => write!(f, "obfuscated file {}", path.display()),
+        }
+    }
+}
+
+/// Keyring account for a profile (`None` is the default configuration)
+pub fn account_name(profile: Option<&str>) -> &str {
+    profile.unwrap_or(DEFAULT_ACCOUNT)
+}
+
+fn keyring_entry(account: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, account)
+}
+
+/// Store `api_key` for `account`, preferring the OS keyring
+pub fn store(account: &str, api_key: &str) -> Result<Backend> {
+    match keyring_entry(account).and_then(|entry| entry.set_password(api_key)) {
+        Ok(()) => Ok(Backend::Keyring),
+        Err(_) => {
+            let store = FileStore::open_default()?;
+            store.set(account, api_key)?;
+            Ok(Backend::ObfuscatedFile(store.path))
+        }
+    }
+}
+
+/// The stored key for `account` and where it was found; the keyring is checked first
+pub fn load(account: &str) -> Result<Option<(String, Backend)>> {
+    if let Ok(key) = keyring_entry(account).and_then(|entry| entry.get_password()) {
+        return Ok(Some((key, Backend::Keyring)));
+    }
+    let store = FileStore::open_default()?;
+    Ok(store
+        .get(account)?
+        .map(|key| (key, Backend::ObfuscatedFile(store.path))))
+}
+
+/// Remove the key for `account` from every backend; returns the backends it was removed from
+pub fn delete(account: &str) -> Result<Vec<Backend>> {
+    let mut removed = Vec::new();
+    if keyring_entry(account)
+        .and_then(|entry| entry.delete_credential())
+        .is_ok()
+    {
+        removed.push(Backend::Keyring);
+    }
+    let store = FileStore::open_default()?;
+    if store.delete(account)? {
+        removed.push(Backend::ObfuscatedFile(store.path));
+    }
+    Ok(removed)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CredentialsFile {
+    /// Account → hex(nonce || ciphertext)
+    entries: BTreeMap<String, String>,
+}
+
+/// Obfuscated fallback store, one ChaCha20-Poly1305 sealed entry per account.
+///
+/// The key isn't secret (see `machine_secret`), so the file's owner-only mode is the
+/// real protection.
+struct FileStore {
+    path: PathBuf,
+    secret: [u8; 32],
+}
+
+impl FileStore {
+    fn open_default() -> Result<Self> {
+        let path = dirs::home_dir()
+            .ok_or_else(|| anyhow!("Could not determine home directory"))?
+            .join(".hindsight")
+            .join(CREDENTIALS_FILE_NAME);
+        Ok(Self {
+            path,
+            secret: machine_secret(),
+        })
+    }
+
+    fn read(&self) -> Result<CredentialsFile> {
+        if !self.path.exists() {
+            return Ok(CredentialsFile::default());
+        }
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Corrupt credentials file: {}", self.path.display()))
+    }
+
+    fn write(&self, file: &CredentialsFile) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        // Write a temporary file that is owner-only from the moment it exists, then
+        // rename it over the old one, so the entries are never readable by other users
+        let temp = self.path.with_extension("tmp");
+        let _ = fs::remove_file(&temp);
+        let written = write_private(&temp, serde_json::to_string_pretty(file)?.as_bytes())
+            .and_then(|()| fs::rename(&temp, &self.path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        written.with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.secret))
+    }
+
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        let file = self.read()?;
+        let Some(sealed) = file.entries.get(account) else {
+            return Ok(None);
+        };
+        let bytes = hex_decode(sealed)
+            .filter(|b| b.len() > NONCE_LEN)
+            .ok_or_else(|| anyhow!("Corrupt credentials entry '{}'", account))?;
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                anyhow!(
+                    "Could not decrypt the stored key for '{}' (was {} copied from another machine?)",
+                    account,
+                    self.path.display()
+                )
+            })?;
+        Ok(Some(String::from_utf8(plaintext)?))
+    }
+
+    fn set(&self, account: &str, api_key: &str) -> Result<()> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, api_key.as_bytes())
+            .map_err(|_| anyhow!("Failed to encrypt API key"))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+
+        let mut file = self.read()?;
+        file.entries.insert(account.to_string(), hex_encode(&sealed));
+        self.write(&file)
+    }
+
+    fn delete(&self, account: &str) -> Result<bool> {
+        let mut file = self.read()?;
+        if file.entries.remove(account).is_none() {
+            return Ok(false);
+        }
+        self.write(&file)?;
+        Ok(true)
+    }
+}
+
+/// Key for the file store, derived from the machine ID and home directory. Neither is
+/// secret from local users, so this only ties the file to the machine.
+fn machine_secret() -> [u8; 32] {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let home = dirs::home_dir().unwrap_or_default();
+    derive_secret(machine_id.trim(), &home)
+}
+
+fn derive_secret(machine_id: &str, home: &Path) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"hindsight-cli credentials v1\0");
+    hasher.update(machine_id.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(home.to_string_lossy().as_bytes());
+    hasher.finalize().into()
+}
+
+/// Create `path` with mode 0600 (where the platform has modes) and write `contents`
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(text: &str) -> Option<Vec<u8>> {
+    let digit = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [hi, lo] => Some((digit(*hi)? << 4) | digit(*lo)?),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(machine_id: &str) -> FileStore {
+        let dir = std::env::temp_dir().join(format!(
+            "hindsight-credentials-test-{}-{}",
+            std::process::id(),
+            machine_id
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        FileStore {
+            path: dir.join(CREDENTIALS_FILE_NAME),
+            secret: derive_secret(machine_id, &dir),
+        }
+    }
+
+    #[test]
+    fn test_file_store_roundtrip_is_encrypted() {
+        let store = temp_store("roundtrip");
+        store.set("prod", "hs-secret-key").unwrap();
+        store.set("staging", "other-key").unwrap();
+
+        assert_eq!(store.get("prod").unwrap().as_deref(), Some("hs-secret-key"));
+        assert_eq!(store.get("missing").unwrap(), None);
+        let raw = fs::read_to_string(&store.path).unwrap();
+        assert!(!raw.contains("hs-secret-key"));
+
+        assert!(store.delete("prod").unwrap());
+        assert!(!store.delete("prod").unwrap());
+        assert_eq!(store.get("staging").unwrap().as_deref(), Some("other-key"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_store_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let store = temp_store("permissions");
+        store.set("default", "hs-key").unwrap();
+        store.set("default", "hs-key-2").unwrap();
+        let mode = fs::metadata(&store.path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!store.path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_file_store_rejects_other_machine_secret() {
+        let store = temp_store("machine-a");
+        store.set("default", "hs-key").unwrap();
+        let copied = FileStore {
+            path: store.path.clone(),
+            secret: derive_secret("machine-b", Path::new("/home/other")),
+        };
+        assert!(copied.get("default").is_err());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let bytes = vec![0u8, 15, 16, 255];
+        assert_eq!(hex_encode(&bytes), "000f10ff");
+        assert_eq!(hex_decode("000f10ff"), Some(bytes));
+        assert_eq!(hex_decode("abc"), None);
+        assert_eq!(hex_decode("zz"), None);
+    }
+}
@@ -1,10 +1,215 @@
+use crate::api::{failed_request_ids, status_in_message, ApiError};
+use crate::output::OutputFormat;
+use crate::ui;
 use colored::*;
+use serde::Serialize;
+use std::sync::OnceLock;
 
-pub fn handle_api_error(err: anyhow::Error, api_url: &str) -> ! {
-    eprintln!("{}", format_error_message(&err, api_url));
+/// Exit code used when the server could not be reached or did not answer in time
+pub const EXIT_UNREACHABLE: i32 = 3;
+
+/// Exit code used when a requested resource does not exist
+pub const EXIT_NOT_FOUND: i32 = 4;
+
+/// Exit code used when the server rejected the credentials (401 or 403)
+pub const EXIT_AUTH: i32 = 5;
+
+/// Output format of the running command, deciding how failures are reported
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Set the output format failures are reported in. Call once, at startup.
+pub fn init(output_format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(output_format);
+}
+
+/// Whether failures are reported as a JSON envelope instead of a human message
+fn structured() -> bool {
+    matches!(
+        OUTPUT_FORMAT.get(),
+        Some(
+            OutputFormat::Json
+                | OutputFormat::JsonPretty
+                | OutputFormat::Ndjson
+                | OutputFormat::Csv
+                | OutputFormat::Yaml
+        )
+    )
+}
+
+/// Category of a failure, the `kind` of the JSON error envelope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    NotFound,
+    Unauthorized,
+    Forbidden,
+    InvalidRequest,
+    Conflict,
+    RateLimited,
+    ServerError,
+    Unreachable,
+    Timeout,
+    InvalidResponse,
+    Config,
+    /// Nothing matched and the command was asked to fail then (`--fail-empty`)
+    EmptyResult,
+    Error,
+}
+
+impl ErrorKind {
+    fn from_status(status: u16) -> Self {
+        match status {
+            401 => ErrorKind::Unauthorized,
+            403 => ErrorKind::Forbidden,
+            404 => ErrorKind::NotFound,
+            409 => ErrorKind::Conflict,
+            429 => ErrorKind::RateLimited,
+            400..=499 => ErrorKind::InvalidRequest,
+            500..=599 => ErrorKind::ServerError,
+            _ => ErrorKind::Error,
+        }
+    }
+
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::NotFound => EXIT_NOT_FOUND,
+            ErrorKind::Unauthorized | ErrorKind::Forbidden => EXIT_AUTH,
+            ErrorKind::Unreachable | ErrorKind::Timeout => EXIT_UNREACHABLE,
+            _ => 1,
+        }
+    }
+}
+
+/// A failure as reported in the JSON error envelope
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub status: Option<u16>,
+    /// The `X-Request-Id` the failed request was sent with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// The request or trace ID the server returned, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_request_id: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        let api_error = err.chain().find_map(|cause| cause.downcast_ref::<ApiError>());
+        let status = api_error
+            .map(|e| e.status.as_u16())
+            .or_else(|| status_in_message(&message));
+        let kind = match status {
+            _ if err.is::<EmptyResult>() => ErrorKind::EmptyResult,
+            Some(status) => ErrorKind::from_status(status),
+            None if is_timeout(err) => ErrorKind::Timeout,
+            None if is_unreachable(err) => ErrorKind::Unreachable,
+            None if message.contains("Failed to parse") || message.contains("error decoding") => {
+                ErrorKind::InvalidResponse
+            }
+            None => ErrorKind::Error,
+        };
+        // A failure that sent no request (config, local files) has no IDs
+        let failed = failed_request_ids().filter(|_| kind != ErrorKind::Error || status.is_some());
+        let request_id = api_error
+            .and_then(|e| e.request_id.clone())
+            .or_else(|| failed.as_ref().map(|ids| ids.sent.clone()));
+        let server_request_id = api_error
+            .and_then(|e| e.server_request_id.clone())
+            .or_else(|| failed.and_then(|ids| ids.server));
+        ErrorReport { kind, message, status, request_id, server_request_id }
+    }
+
+    /// Print as `{"error": {...}}` on stderr
+    fn print(&self) {
+        let envelope = serde_json::json!({ "error": self });
+        eprintln!("{}", envelope);
+    }
+}
+
+/// A command came back empty and was told to treat that as a failure
+#[derive(Debug)]
+pub struct EmptyResult(pub &'static str);
+
+impl std::fmt::Display for EmptyResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for EmptyResult {}
+
+fn is_timeout(err: &anyhow::Error) -> bool {
+    let err_str = err.to_string();
+    ["timeout", "Timeout", "timed out"]
+        .iter()
+        .any(|needle| err_str.contains(needle))
+}
+
+/// Report a failure that isn't an API error and exit: a red message in pretty
+/// mode, the JSON envelope in machine-readable formats
+pub fn exit_with_error(err: &anyhow::Error) -> ! {
+    let report = ErrorReport::from_error(err);
+    if structured() {
+        report.print();
+    } else {
+        ui::print_error(&report.message);
+    }
+    std::process::exit(report.kind.exit_code());
+}
+
+/// Report a configuration problem and exit
+pub fn handle_config_error(err: &anyhow::Error) -> ! {
+    let message = format!("Configuration error: {}", err);
+    if structured() {
+        let kind = ErrorKind::Config;
+        ErrorReport { kind, message, status: None, request_id: None, server_request_id: None }
+            .print();
+    } else {
+        ui::print_error(&message);
+        print_config_help();
+    }
     std::process::exit(1);
 }
 
+/// Whether an API error was a 404 for the requested resource
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    err.to_string().contains("(404")
+}
+
+/// Whether a request failed without reaching the server (refused, timed out, DNS)
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    let err_str = err.to_string();
+    [
+        "Connection refused",
+        "tcp connect error",
+        "error sending request",
+        "timeout",
+        "Timeout",
+        "timed out",
+        "dns error",
+        "failed to lookup",
+    ]
+    .iter()
+    .any(|needle| err_str.contains(needle))
+}
+
+pub fn handle_api_error(err: anyhow::Error, api_url: &str) -> ! {
+    // Not a failed request, so none of the connection advice applies
+    if err.is::<EmptyResult>() {
+        exit_with_error(&err);
+    }
+    let report = ErrorReport::from_error(&err);
+    if structured() {
+        report.print();
+    } else {
+        eprintln!("{}", format_error_message(&err, api_url));
+    }
+    std::process::exit(report.kind.exit_code());
+}
+
 fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     let err_str = err.to_string();
 
@@ -12,7 +217,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("Connection refused") || err_str.contains("tcp connect error") || err_str.contains("error sending request") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Cannot connect to Hindsight API".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -29,7 +234,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("timeout") || err_str.contains("Timeout") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  • {}\n  • {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Request timed out".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -46,7 +251,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("dns") || err_str.contains("DNS") || err_str.contains("failed to lookup") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Cannot resolve API hostname".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -63,7 +268,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
         if err_str.contains("Bank configuration API is disabled") {
             return format!(
                 "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  {}",
-                "✗".bright_red().bold(),
+                ui::icon("✗", "x").bright_red().bold(),
                 "Bank configuration API is disabled".bright_red().bold(),
                 "API URL:".bright_yellow(),
                 api_url.bright_white(),
@@ -76,7 +281,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
 
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "API endpoint not found (404)".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -92,7 +297,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("401") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Authentication failed".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -108,7 +313,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("403") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Permission denied (403)".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -124,7 +329,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("500") || err_str.contains("502") || err_str.contains("503") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  • {}\n  • {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "API server error".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -141,7 +346,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     if err_str.contains("invalid URL") || err_str.contains("InvalidUri") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Invalid API URL".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -168,7 +373,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
 
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}{}\n\n{}\n  • {}\n  • {}",
-            "✗".bright_red().bold(),
+            ui::icon("✗", "x").bright_red().bold(),
             "Invalid API response format".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
@@ -186,7 +391,7 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
     // Generic error with the full error message
     format!(
         "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}",
-        "✗".bright_red().bold(),
+        ui::icon("✗", "x").bright_red().bold(),
         "API request failed".bright_red().bold(),
         "API URL:".bright_yellow(),
         api_url.bright_white(),
@@ -208,8 +413,50 @@ pub fn print_config_help() {
     println!("  {}", "hindsight configure --api-url http://your-api:8888".bright_white());
     println!();
     println!("  {}", "Configuration priority:".bright_yellow());
-    println!("    1. Environment variable (HINDSIGHT_API_URL) - highest priority");
+    println!("    1. Environment variable (HINDSIGHT_URL or HINDSIGHT_API_URL) - highest priority");
     println!("    2. Config file (~/.hindsight/config)");
     println!("    3. Default (http://localhost:8888)");
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_report_classifies_api_errors() {
+        let err: anyhow::Error = ApiError {
+            status: reqwest::StatusCode::NOT_FOUND,
+            body: r#"{"detail":"Bank 'foo' not found"}"#.to_string(),
+            request_id: Some("req-123".to_string()),
+            server_request_id: None,
+        }
+        .into();
+        let report = ErrorReport::from_error(&err.context("Failed to get bank"));
+        assert_eq!(report.kind, ErrorKind::NotFound);
+        assert_eq!(report.status, Some(404));
+        assert_eq!(report.request_id.as_deref(), Some("req-123"));
+        assert_eq!(report.kind.exit_code(), EXIT_NOT_FOUND);
+
+        let envelope = serde_json::json!({ "error": report });
+        assert_eq!(envelope["error"]["kind"], "not_found");
+        assert!(envelope["error"]["message"].as_str().unwrap().starts_with("Failed to get bank: "));
+
+        let refused =
+            anyhow::anyhow!("error sending request: tcp connect error: Connection refused");
+        let report = ErrorReport::from_error(&refused);
+        assert_eq!(report.kind, ErrorKind::Unreachable);
+        assert_eq!(report.status, None);
+        assert_eq!(report.kind.exit_code(), EXIT_UNREACHABLE);
+
+        let validation = anyhow::anyhow!("Error Response: status: 422 Unprocessable Entity; ...");
+        assert_eq!(ErrorReport::from_error(&validation).kind, ErrorKind::InvalidRequest);
+
+        let empty = anyhow::Error::new(EmptyResult("No results found (--fail-empty)"));
+        let report = ErrorReport::from_error(&empty);
+        assert_eq!(report.kind, ErrorKind::EmptyResult);
+        assert_eq!(report.message, "No results found (--fail-empty)");
+        assert_eq!(report.kind.exit_code(), 1);
+        assert_eq!(serde_json::json!(report)["kind"], "empty_result");
+    }
+}
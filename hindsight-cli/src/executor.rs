@@ -0,0 +1,201 @@
+//! Gate for write requests, so the global `--dry-run` flag is enforced in one place.
+//!
+//! Commands wrap each mutating API call in [`Executor::execute`]. Under dry-run
+//! the call is recorded as a [`PlannedWrite`] instead of being sent, and the plan
+//! is printed once the command finishes. `ApiClient` write methods also call
+//! [`ensure_writes_allowed`], so a command that forgets the executor fails
+//! instead of writing.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+/// A write that was skipped because of `--dry-run`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedWrite {
+    pub action: String,
+    pub bank_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Number of memories or records affected, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// IDs of the affected memories or documents, when known
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<String>,
+}
+
+impl PlannedWrite {
+    pub fn new(action: &str, bank_id: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            bank_id: bank_id.to_string(),
+            target: None,
+            count: None,
+            ids: Vec::new(),
+        }
+    }
+
+    pub fn target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn ids(mut self, ids: Vec<String>) -> Self {
+        self.ids = ids;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Executor {
+    dry_run: bool,
+    planned: Mutex<Vec<PlannedWrite>>,
+}
+
+impl Executor {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            planned: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Run `write`, or under dry-run record `plan` and return `Ok(None)` without calling it
+    pub fn execute<T>(&self, plan: PlannedWrite, write: impl FnOnce() -> Result<T>) -> Result<Option<T>> {
+        if self.dry_run {
+            self.planned.lock().unwrap().push(plan);
+            return Ok(None);
+        }
+        write().map(Some)
+    }
+
+    pub fn planned(&self) -> Vec<PlannedWrite> {
+        self.planned.lock().unwrap().clone()
+    }
+}
+
+static EXECUTOR: OnceLock<Executor> = OnceLock::new();
+
+/// Install the executor for this process; later calls are ignored
+pub fn init(dry_run: bool) {
+    let _ = EXECUTOR.set(Executor::new(dry_run));
+}
+
+pub fn get() -> &'static Executor {
+    EXECUTOR.get_or_init(Executor::default)
+}
+
+/// Called by every `ApiClient` write method as a backstop for commands that
+/// don't route through [`Executor::execute`]
+pub fn ensure_writes_allowed(action: &str) -> Result<()> {
+    if get().is_dry_run() {
+        anyhow::bail!(
+            "--dry-run is not supported for this command; refusing to send {} request",
+            action
+        );
+    }
+    Ok(())
+}
+
+/// Print the writes skipped under dry-run (nothing when not in dry-run)
+pub fn print_plan(output_format: OutputFormat) -> Result<()> {
+    let executor = get();
+    if !executor.is_dry_run() {
+        return Ok(());
+    }
+    let planned = executor.planned();
+
+    if output_format != OutputFormat::Pretty {
+        #[derive(Serialize)]
+        struct DryRunReport {
+            dry_run: bool,
+            planned_writes: Vec<PlannedWrite>,
+        }
+        return output::print_output(
+            &DryRunReport {
+                dry_run: true,
+                planned_writes: planned,
+            },
+            output_format,
+        );
+    }
+
+    ui::print_section_header(&format!("Dry run: {} planned write(s), none sent", planned.len()));
+    for write in &planned {
+        let mut line = format!("{} {}", write.action, write.bank_id);
+        if let Some(target) = &write.target {
+            line.push_str(&format!(" {}", target));
+        }
+        if let Some(count) = write.count {
+            line.push_str(&format!(" ({})", count));
+        }
+        println!("  {}", line);
+        for id in &write.ids {
+            println!("    {}", ui::dim(id));
+        }
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Stands in for `ApiClient`, counting write calls
+    #[derive(Default)]
+    struct MockClient {
+        writes: Cell<usize>,
+    }
+
+    impl MockClient {
+        fn delete_document(&self, document_id: &str) -> Result<String> {
+            self.writes.set(self.writes.get() + 1);
+            Ok(document_id.to_string())
+        }
+    }
+
+    #[test]
+    fn test_dry_run_sends_no_writes() {
+        let client = MockClient::default();
+        let executor = Executor::new(true);
+        for doc in ["doc-1", "doc-2"] {
+            let result = executor
+                .execute(PlannedWrite::new("delete_document", "alice").target(doc), || {
+                    client.delete_document(doc)
+                })
+                .unwrap();
+            assert_eq!(result, None);
+        }
+        assert_eq!(client.writes.get(), 0);
+        let planned = executor.planned();
+        assert_eq!(planned.len(), 2);
+        assert_eq!(planned[1].target.as_deref(), Some("doc-2"));
+    }
+
+    #[test]
+    fn test_execute_runs_writes_without_dry_run() {
+        let client = MockClient::default();
+        let executor = Executor::new(false);
+        let result = executor
+            .execute(PlannedWrite::new("delete_document", "alice"), || client.delete_document("doc-1"))
+            .unwrap();
+        assert_eq!(result.as_deref(), Some("doc-1"));
+        assert_eq!(client.writes.get(), 1);
+        assert!(executor.planned().is_empty());
+    }
+}
@@ -0,0 +1,769 @@
+//! Key bindings of the explorer (`hindsight explore`).
+//!
+//! Keys pressed while browsing are looked up in a [`Keymap`] and turned into an
+//! [`Action`] before anything happens, so the bindings can be changed in the
+//! `[explorer.keys]` section of the config file or a profile:
+//!
+//! ```toml
+//! [explorer.keys]
+//! delete = "dd"
+//! documents = "alt+d"
+//! filter = "/"
+//! query = ["ctrl+f", "F5"]
+//! ```
+//!
+//! A binding replaces the action's default keys, and an empty list (`[]`)
+//! unbinds it. Text inputs, popups and confirmations keep their fixed keys, as
+//! do `Ctrl+C` (quit) and `Alt+1`..`Alt+9` (go to a tab).
+
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+/// Something a key can do in the explorer's views
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    Down,
+    Up,
+    Left,
+    Right,
+    Open,
+    Back,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    First,
+    Last,
+    NextPage,
+    PrevPage,
+    Filter,
+    Refresh,
+    Export,
+    Query,
+    Recall,
+    Reflect,
+    Operations,
+    Profile,
+    Stats,
+    Memories,
+    Entities,
+    Documents,
+    Options,
+    Inspect,
+    Copy,
+    CopyAll,
+    Theme,
+    NewTab,
+    NextTab,
+    PrevTab,
+    CloseTab,
+    NewBank,
+    RenameBank,
+    DeleteBank,
+    AddMemory,
+    Follow,
+    Sort,
+    Mark,
+    Delete,
+    RetryOperation,
+    CancelOperation,
+    EditProfile,
+    Mode,
+    Budget,
+    Types,
+    MoreTokens,
+    FewerTokens,
+    Chunk,
+    Trace,
+    CollapseTrace,
+    RawTrace,
+    Sources,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Quit,
+        Action::Help,
+        Action::Down,
+        Action::Up,
+        Action::Left,
+        Action::Right,
+        Action::Open,
+        Action::Back,
+        Action::PageDown,
+        Action::PageUp,
+        Action::HalfPageDown,
+        Action::HalfPageUp,
+        Action::First,
+        Action::Last,
+        Action::NextPage,
+        Action::PrevPage,
+        Action::Filter,
+        Action::Refresh,
+        Action::Export,
+        Action::Query,
+        Action::Recall,
+        Action::Reflect,
+        Action::Operations,
+        Action::Profile,
+        Action::Stats,
+        Action::Memories,
+        Action::Entities,
+        Action::Documents,
+        Action::Options,
+        Action::Inspect,
+        Action::Copy,
+        Action::CopyAll,
+        Action::Theme,
+        Action::NewTab,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::CloseTab,
+        Action::NewBank,
+        Action::RenameBank,
+        Action::DeleteBank,
+        Action::AddMemory,
+        Action::Follow,
+        Action::Sort,
+        Action::Mark,
+        Action::Delete,
+        Action::RetryOperation,
+        Action::CancelOperation,
+        Action::EditProfile,
+        Action::Mode,
+        Action::Budget,
+        Action::Types,
+        Action::MoreTokens,
+        Action::FewerTokens,
+        Action::Chunk,
+        Action::Trace,
+        Action::CollapseTrace,
+        Action::RawTrace,
+        Action::Sources,
+    ];
+
+    /// Name used in `[explorer.keys]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Open => "open",
+            Action::Back => "back",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::HalfPageDown => "half_page_down",
+            Action::HalfPageUp => "half_page_up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::NextPage => "next_page",
+            Action::PrevPage => "prev_page",
+            Action::Filter => "filter",
+            Action::Refresh => "refresh",
+            Action::Export => "export",
+            Action::Query => "query",
+            Action::Recall => "recall",
+            Action::Reflect => "reflect",
+            Action::Operations => "operations",
+            Action::Profile => "profile",
+            Action::Stats => "stats",
+            Action::Memories => "memories",
+            Action::Entities => "entities",
+            Action::Documents => "documents",
+            Action::Options => "options",
+            Action::Inspect => "inspect",
+            Action::Copy => "copy",
+            Action::CopyAll => "copy_all",
+            Action::Theme => "theme",
+            Action::NewTab => "new_tab",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::CloseTab => "close_tab",
+            Action::NewBank => "new_bank",
+            Action::RenameBank => "rename_bank",
+            Action::DeleteBank => "delete_bank",
+            Action::AddMemory => "add_memory",
+            Action::Follow => "follow",
+            Action::Sort => "sort",
+            Action::Mark => "mark",
+            Action::Delete => "delete",
+            Action::RetryOperation => "retry_operation",
+            Action::CancelOperation => "cancel_operation",
+            Action::EditProfile => "edit_profile",
+            Action::Mode => "mode",
+            Action::Budget => "budget",
+            Action::Types => "types",
+            Action::MoreTokens => "more_tokens",
+            Action::FewerTokens => "fewer_tokens",
+            Action::Chunk => "chunk",
+            Action::Trace => "trace",
+            Action::CollapseTrace => "collapse_trace",
+            Action::RawTrace => "raw_trace",
+            Action::Sources => "sources",
+        }
+    }
+
+    /// Views the action works in; actions of views that never show together may share keys
+    pub fn scope(self) -> Scope {
+        match self {
+            Action::NextPage | Action::PrevPage => Scope(
+                Scope::MEMORIES.0 | Scope::ENTITIES.0 | Scope::DOCUMENTS.0 | Scope::OPERATIONS.0,
+            ),
+            // Tab switches reflect sources in the Query view
+            Action::NextTab => Scope(Scope::ALL.0 & !Scope::QUERY.0),
+            Action::NewBank | Action::RenameBank | Action::DeleteBank => Scope::BANKS,
+            Action::AddMemory | Action::Follow => Scope::MEMORIES,
+            Action::Mark | Action::Delete | Action::Sort => {
+                Scope(Scope::MEMORIES.0 | Scope::DOCUMENTS.0)
+            }
+            // `s` sorts the memory and document tables
+            Action::Stats => Scope(Scope::ALL.0 & !(Scope::MEMORIES.0 | Scope::DOCUMENTS.0)),
+            // `m` switches the query mode and `e` edits the profile
+            Action::Memories => Scope(Scope::ALL.0 & !Scope::QUERY.0),
+            // `t` cycles the recall fact types
+            Action::Theme => Scope(Scope::ALL.0 & !Scope::QUERY.0),
+            Action::Entities => Scope(Scope::ALL.0 & !Scope::PROFILE.0),
+            Action::RetryOperation | Action::CancelOperation => Scope::OPERATIONS,
+            Action::EditProfile => Scope::PROFILE,
+            Action::Mode
+            | Action::Budget
+            | Action::Types
+            | Action::MoreTokens
+            | Action::FewerTokens
+            | Action::Chunk
+            | Action::Trace
+            | Action::CollapseTrace
+            | Action::RawTrace
+            | Action::Sources => Scope::QUERY,
+            _ => Scope::ALL,
+        }
+    }
+
+    fn defaults(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Help => &["?"],
+            Action::Down => &["j", "down"],
+            Action::Up => &["k", "up"],
+            Action::Left => &["left", "h"],
+            Action::Right => &["right", "l"],
+            Action::Open => &["enter"],
+            Action::Back => &["esc"],
+            Action::PageDown => &["pagedown"],
+            Action::PageUp => &["pageup"],
+            Action::HalfPageDown => &["ctrl+d"],
+            Action::HalfPageUp => &["ctrl+u"],
+            Action::First => &["g", "home"],
+            Action::Last => &["G", "end"],
+            Action::NextPage => &["]", "n"],
+            Action::PrevPage => &["["],
+            Action::Filter => &["f"],
+            Action::Refresh => &["R"],
+            Action::Export => &["E"],
+            Action::Query => &["/"],
+            Action::Recall | Action::Reflect => &[],
+            Action::Operations => &["o"],
+            Action::Profile => &["p"],
+            Action::Stats => &["s"],
+            Action::Memories => &["m"],
+            Action::Entities => &["e"],
+            Action::Documents => &["d"],
+            Action::Options => &["O"],
+            Action::Inspect => &["J"],
+            Action::Copy => &["y"],
+            Action::CopyAll => &["Y"],
+            Action::Theme => &["t"],
+            Action::NewTab => &["ctrl+t"],
+            Action::NextTab => &["tab"],
+            Action::PrevTab => &["shift+tab"],
+            Action::CloseTab => &["ctrl+w"],
+            Action::NewBank => &["n"],
+            Action::RenameBank => &["f2"],
+            Action::DeleteBank => &["D"],
+            Action::AddMemory => &["a"],
+            Action::Follow => &["F"],
+            Action::Sort => &["s"],
+            Action::Mark => &["space"],
+            Action::Delete => &["delete", "x"],
+            Action::RetryOperation => &["r"],
+            Action::CancelOperation => &["x"],
+            Action::EditProfile => &["e"],
+            Action::Mode => &["m"],
+            Action::Budget => &["b"],
+            Action::Types => &["t"],
+            Action::MoreTokens => &["+", "="],
+            Action::FewerTokens => &["-"],
+            Action::Chunk => &["c"],
+            Action::Trace => &["T"],
+            Action::CollapseTrace => &["z"],
+            Action::RawTrace => &["r"],
+            Action::Sources => &["tab"],
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|action| action.name() == name)
+    }
+}
+
+/// A set of explorer views, as bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scope(u8);
+
+impl Scope {
+    pub const BANKS: Scope = Scope(1);
+    pub const MEMORIES: Scope = Scope(1 << 1);
+    pub const ENTITIES: Scope = Scope(1 << 2);
+    pub const DOCUMENTS: Scope = Scope(1 << 3);
+    pub const QUERY: Scope = Scope(1 << 4);
+    pub const OPERATIONS: Scope = Scope(1 << 5);
+    pub const PROFILE: Scope = Scope(1 << 6);
+    pub const STATS: Scope = Scope(1 << 7);
+    pub const ALL: Scope = Scope(u8::MAX);
+
+    fn overlaps(self, other: Scope) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+/// One key press with its modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is part of the character (`G`, `?`) and of BackTab already
+        let kept = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => KeyModifiers::CONTROL | KeyModifiers::ALT,
+            _ => KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT,
+        };
+        let modifiers = modifiers & kept;
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    pub fn event(&self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+
+    /// Parse a chord such as `x`, `G`, `ctrl+d`, `shift+tab`, `PgDn` or `F2`.
+    /// Names are case-insensitive; a single character is taken as typed.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (modifier_text, key) = match text.strip_suffix('+') {
+            // `+` itself, alone or after modifiers as in `ctrl++`
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(""), "+")
+            }
+            _ => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_text.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("unknown modifier '{}' in '{}'", name, text),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!("unknown key '{}'", text),
+                },
+            },
+        };
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            // Terminals report Ctrl+D as `d`, whatever the case
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(c) if c != ' ') && self.modifiers.is_empty()
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// The chords pressed one after another to run an action, such as `dd`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding(Vec<KeyChord>);
+
+impl Binding {
+    /// Parse a binding: one chord, chords separated by spaces (`g g`), or a run
+    /// of plain characters (`dd`)
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            bail!("empty key");
+        }
+        let chords = if text.contains(' ') {
+            text.split_whitespace().map(KeyChord::parse).collect::<Result<Vec<_>>>()?
+        } else {
+            match KeyChord::parse(text) {
+                Ok(chord) => vec![chord],
+                Err(_) if !text.contains('+') => text
+                    .chars()
+                    .map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE))
+                    .collect(),
+                Err(e) => return Err(e),
+            }
+        };
+        if let Some(KeyChord {
+            code: KeyCode::Char(c @ '1'..='9'),
+            ..
+        }) = chords.first().filter(|chord| chord.modifiers.is_empty())
+        {
+            bail!("'{}' starts with {}, which is kept for counts like 42G", text, c);
+        }
+        Ok(Self(chords))
+    }
+
+    fn starts_with(&self, prefix: &[KeyChord]) -> bool {
+        self.0.starts_with(prefix)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if self.0.iter().all(KeyChord::is_plain_char) { "" } else { " " };
+        let chords: Vec<String> = self.0.iter().map(KeyChord::to_string).collect();
+        write!(f, "{}", chords.join(separator))
+    }
+}
+
+/// What the keys pressed so far amount to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lookup {
+    Action(Action),
+    /// The start of a longer binding; wait for the next key
+    Prefix,
+    None,
+}
+
+/// The bindings in effect, defaults overridden by `[explorer.keys]`
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Binding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&[]).expect("the default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// The default bindings with `overrides` (action name, binding or list of
+    /// bindings as written in the config file) applied. Unknown actions, bad keys
+    /// and keys claimed by two actions in the same view are errors.
+    pub fn new(overrides: &[(String, String)]) -> Result<Self> {
+        let mut bindings = Vec::new();
+        for &action in Action::ALL {
+            let keys = action.defaults().iter().map(|key| Binding::parse(key));
+            bindings.push((action, keys.collect::<Result<Vec<_>>>()?));
+        }
+
+        let mut errors = Vec::new();
+        for (name, value) in overrides {
+            let Some(action) = Action::from_name(name.trim()) else {
+                let names: Vec<&str> = Action::ALL.iter().map(|a| a.name()).collect();
+                errors.push(format!(
+                    "unknown action '{}' (expected one of: {})",
+                    name.trim(),
+                    names.join(", ")
+                ));
+                continue;
+            };
+            let parsed: Result<Vec<Binding>> = config_keys(value)
+                .iter()
+                .map(|key| Binding::parse(key))
+                .collect();
+            match parsed {
+                Ok(keys) => bindings[action as usize].1 = keys,
+                Err(e) => errors.push(format!("{}: {}", action.name(), e)),
+            }
+        }
+
+        let keymap = Self { bindings };
+        errors.extend(keymap.conflicts());
+        if !errors.is_empty() {
+            bail!("Invalid [explorer.keys] in the config file:\n  {}", errors.join("\n  "));
+        }
+        Ok(keymap)
+    }
+
+    /// Pairs of actions that could both claim the same keys in some view
+    fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (i, (action, keys)) in self.bindings.iter().enumerate() {
+            for (other, other_keys) in &self.bindings[i + 1..] {
+                if !action.scope().overlaps(other.scope()) {
+                    continue;
+                }
+                for key in keys {
+                    for other_key in other_keys {
+                        let clash = if key == other_key {
+                            format!(
+                                "'{}' is bound to both {} and {}",
+                                key,
+                                action.name(),
+                                other.name()
+                            )
+                        } else if other_key.starts_with(&key.0) {
+                            format!(
+                                "'{}' ({}) hides '{}' ({}), which starts with it",
+                                key,
+                                action.name(),
+                                other_key,
+                                other.name()
+                            )
+                        } else if key.starts_with(&other_key.0) {
+                            format!(
+                                "'{}' ({}) hides '{}' ({}), which starts with it",
+                                other_key,
+                                other.name(),
+                                key,
+                                action.name()
+                            )
+                        } else {
+                            continue;
+                        };
+                        conflicts.push(clash);
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// The action the keys pressed so far run in a view of `scope`
+    pub fn lookup(&self, pressed: &[KeyChord], scope: Scope) -> Lookup {
+        let mut prefix = false;
+        for (action, keys) in &self.bindings {
+            if !action.scope().overlaps(scope) {
+                continue;
+            }
+            for key in keys {
+                if key.0 == pressed {
+                    return Lookup::Action(*action);
+                }
+                prefix |= key.starts_with(pressed);
+            }
+        }
+        if prefix {
+            Lookup::Prefix
+        } else {
+            Lookup::None
+        }
+    }
+
+    /// The first key of `action`, for the shortcuts bar; empty when unbound
+    pub fn label(&self, action: Action) -> String {
+        self.bindings[action as usize].1.first().map(Binding::to_string).unwrap_or_default()
+    }
+
+    /// Every key of `action`, for the help screen
+    pub fn labels(&self, action: Action) -> String {
+        let keys: Vec<String> =
+            self.bindings[action as usize].1.iter().map(Binding::to_string).collect();
+        keys.join("/")
+    }
+}
+
+/// The keys in a config value: a quoted or bare string, or a list of quoted strings
+fn config_keys(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let Some(list) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+        return vec![unquote(value).to_string()];
+    };
+    let mut keys = Vec::new();
+    let mut rest = list.trim_start();
+    while let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let Some(end) = rest[1..].find(quote) else {
+            break;
+        };
+        keys.push(rest[1..1 + end].to_string());
+        rest = rest[end + 2..].trim_start().trim_start_matches(',').trim_start();
+    }
+    keys
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(text: &str) -> Vec<KeyChord> {
+        Binding::parse(text).unwrap().0
+    }
+
+    #[test]
+    fn test_parse_chords_and_sequences() {
+        let chord = |code, modifiers| KeyChord::new(code, modifiers);
+        assert_eq!(keys("x"), vec![chord(KeyCode::Char('x'), KeyModifiers::NONE)]);
+        assert_eq!(keys("Ctrl+D"), vec![chord(KeyCode::Char('d'), KeyModifiers::CONTROL)]);
+        assert_eq!(keys("shift+g"), vec![chord(KeyCode::Char('G'), KeyModifiers::NONE)]);
+        assert_eq!(keys("shift+tab"), vec![chord(KeyCode::BackTab, KeyModifiers::NONE)]);
+        assert_eq!(keys("ctrl++"), vec![chord(KeyCode::Char('+'), KeyModifiers::CONTROL)]);
+        assert_eq!(keys("PgDn"), vec![chord(KeyCode::PageDown, KeyModifiers::NONE)]);
+        assert_eq!(keys("dd"), keys("d d"));
+        assert_eq!(keys("g F5").len(), 2);
+        assert!(Binding::parse("hyper+x").is_err());
+        assert!(Binding::parse("ctrl+bogus").is_err());
+        assert!(Binding::parse("4").is_err());
+
+        // Labels read back as keys
+        for text in ["Ctrl+D", "PgDn", "Del", "F2", "Space", "Enter", "dd", "G"] {
+            assert_eq!(Binding::parse(text).unwrap().to_string(), text);
+        }
+        assert_eq!(config_keys(r#"["x", 'delete', ","]"#), vec!["x", "delete", ","]);
+        assert_eq!(config_keys("\"dd\""), vec!["dd"]);
+        assert!(config_keys("[]").is_empty());
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults_and_conflicts_are_listed() {
+        let defaults = Keymap::default();
+        let pressed = |text: &str| keys(text);
+        assert_eq!(
+            defaults.lookup(&pressed("x"), Scope::MEMORIES),
+            Lookup::Action(Action::Delete)
+        );
+        assert_eq!(
+            defaults.lookup(&pressed("x"), Scope::OPERATIONS),
+            Lookup::Action(Action::CancelOperation)
+        );
+        assert_eq!(defaults.lookup(&pressed("n"), Scope::BANKS), Lookup::Action(Action::NewBank));
+        assert_eq!(defaults.lookup(&pressed("x"), Scope::BANKS), Lookup::None);
+        assert_eq!(defaults.labels(Action::Delete), "Del/x");
+
+        let set = |pairs: &[(&str, &str)]| {
+            let pairs: Vec<(String, String)> =
+                pairs.iter().map(|(a, k)| (a.to_string(), k.to_string())).collect();
+            Keymap::new(&pairs)
+        };
+        let custom = set(&[
+            ("delete", "\"dd\""),
+            ("documents", "\"alt+d\""),
+            ("filter", "\"/\""),
+            ("query", "[\"ctrl+f\"]"),
+        ])
+        .unwrap();
+        assert_eq!(
+            custom.lookup(&pressed("d"), Scope::MEMORIES),
+            Lookup::Prefix
+        );
+        assert_eq!(
+            custom.lookup(&pressed("dd"), Scope::MEMORIES),
+            Lookup::Action(Action::Delete)
+        );
+        assert_eq!(custom.lookup(&pressed("x"), Scope::MEMORIES), Lookup::None);
+        assert_eq!(custom.lookup(&pressed("/"), Scope::ALL), Lookup::Action(Action::Filter));
+        assert_eq!(custom.label(Action::Query), "Ctrl+F");
+
+        let error = set(&[("filter", "/"), ("mark", "d"), ("delete", "dd"), ("jump", "J")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unknown action 'jump'"));
+        assert!(error.contains("'/' is bound to both filter and query"));
+        assert!(error.contains("'d' (mark) hides 'dd' (delete)"));
+        // `dd` needs the documents key moved out of the way
+        let error = set(&[("delete", "dd")]).unwrap_err().to_string();
+        assert!(error.contains("'d' (documents) hides 'dd' (delete)"));
+        // Views never shown together may share keys
+        assert!(set(&[("new_bank", "r")]).is_ok());
+        assert_eq!(defaults.lookup(&pressed("m"), Scope::QUERY), Lookup::Action(Action::Mode));
+        assert_eq!(defaults.lookup(&pressed("t"), Scope::QUERY), Lookup::Action(Action::Types));
+        assert_eq!(defaults.lookup(&pressed("t"), Scope::BANKS), Lookup::Action(Action::Theme));
+        assert_eq!(
+            defaults.lookup(&pressed("m"), Scope::ENTITIES),
+            Lookup::Action(Action::Memories)
+        );
+    }
+}
@@ -1,23 +1,35 @@
 mod api;
 mod commands;
 mod config;
+mod credentials;
 mod errors;
+mod executor;
+mod keymap;
 mod output;
+mod pager;
+mod redact;
+mod trace;
 mod ui;
 mod utils;
 
 use anyhow::Result;
 use api::ApiClient;
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use config::Config;
 use output::OutputFormat;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum Format {
     Pretty,
     Json,
+    JsonPretty,
     Yaml,
+    Table,
+    Csv,
+    Markdown,
+    Ndjson,
 }
 
 impl From<Format> for OutputFormat {
@@ -25,7 +37,83 @@ impl From<Format> for OutputFormat {
         match f {
             Format::Pretty => OutputFormat::Pretty,
             Format::Json => OutputFormat::Json,
+            Format::JsonPretty => OutputFormat::JsonPretty,
             Format::Yaml => OutputFormat::Yaml,
+            Format::Table => OutputFormat::Table,
+            Format::Csv => OutputFormat::Csv,
+            Format::Markdown => OutputFormat::Markdown,
+            Format::Ndjson => OutputFormat::Ndjson,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GroupBy {
+    Type,
+    Document,
+}
+
+impl From<GroupBy> for ui::ResultGrouping {
+    fn from(g: GroupBy) -> Self {
+        match g {
+            GroupBy::Type => ui::ResultGrouping::Type,
+            GroupBy::Document => ui::ResultGrouping::Document,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum MergeBy {
+    Interleave,
+    Rank,
+}
+
+impl From<MergeBy> for api::MergeStrategy {
+    fn from(m: MergeBy) -> Self {
+        match m {
+            MergeBy::Interleave => api::MergeStrategy::Interleave,
+            MergeBy::Rank => api::MergeStrategy::Rank,
+        }
+    }
+}
+
+/// How `recall --dedupe` decides two results are the same
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum DedupeMode {
+    /// Identical text, ignoring case and punctuation
+    Exact,
+    /// Similar wording (see --dedupe-threshold)
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GraphFileFormat {
+    Dot,
+    Graphml,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EntityGraphStyle {
+    /// Indented tree of the strongest co-mentions
+    Ascii,
+    /// Graphviz DOT
+    Dot,
+}
+
+impl From<EntityGraphStyle> for commands::entity::EntityGraphFormat {
+    fn from(f: EntityGraphStyle) -> Self {
+        match f {
+            EntityGraphStyle::Ascii => commands::entity::EntityGraphFormat::Ascii,
+            EntityGraphStyle::Dot => commands::entity::EntityGraphFormat::Dot,
+        }
+    }
+}
+
+impl From<GraphFileFormat> for commands::graph::GraphFormat {
+    fn from(f: GraphFileFormat) -> Self {
+        match f {
+            GraphFileFormat::Dot => commands::graph::GraphFormat::Dot,
+            GraphFileFormat::Graphml => commands::graph::GraphFormat::Graphml,
         }
     }
 }
@@ -37,17 +125,87 @@ impl From<Format> for OutputFormat {
 #[command(before_help = get_before_help())]
 #[command(after_help = get_after_help())]
 struct Cli {
-    /// Output format (pretty, json, yaml)
-    #[arg(short = 'o', long, global = true, default_value = "pretty")]
-    output: Format,
+    /// Output format (pretty, json, yaml, table, csv, markdown)
+    /// [default: pretty, or $HINDSIGHT_OUTPUT]
+    #[arg(short = 'o', long, global = true)]
+    output: Option<Format>,
+
+    /// Columns to show (and their order) for table output of list commands,
+    /// e.g. --columns type,created_at,text
+    #[arg(long, global = true, value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// Fields to keep (and their order) in JSON, YAML, CSV and table output,
+    /// with dotted paths into nested objects, e.g. --fields id,created_at,metadata.source
+    #[arg(long, global = true, value_delimiter = ',', conflicts_with = "columns")]
+    fields: Vec<String>,
 
     /// Show verbose output including full requests and responses
     #[arg(short = 'v', long, global = true)]
     verbose: bool,
 
+    /// Answer yes to every confirmation prompt
+    #[arg(short = 'y', long, global = true)]
+    yes: bool,
+
+    /// Only print errors and primary output (no spinners, info or success lines)
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Disable colors and emoji (also honored via the NO_COLOR env var)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// How pretty output shows timestamps [default: relative, or `dates` in the config]
+    #[arg(long, global = true, value_enum)]
+    dates: Option<ui::DateStyle>,
+
+    /// Wrap and truncate pretty output and tables to this many columns
+    /// [default: the terminal's width, or 80 when not a terminal]
+    #[arg(long, global = true, value_name = "COLS")]
+    width: Option<usize>,
+
+    /// Print long pretty output directly instead of through $PAGER
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Do all read-side work and print the writes that would be made, without sending any
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Request timeout in seconds [default: 120, or $HINDSIGHT_TIMEOUT_SECS]
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    timeout: Option<u64>,
+
+    /// Retry reads up to N times after connection errors, timeouts, 429 and 5xx responses
+    #[arg(long, global = true, value_name = "N", default_value_t = 0)]
+    retries: u32,
+
+    /// Delay before the first retry in milliseconds; doubles for each further retry
+    #[arg(long, global = true, value_name = "MS", default_value_t = 500)]
+    retry_delay: u64,
+
+    /// Also retry ingest requests (retain, file uploads); a retried write may be stored twice
+    #[arg(long, global = true)]
+    retry_writes: bool,
+
+    /// Send a duplicate of a read (never a write) that hasn't answered after MS milliseconds,
+    /// and use whichever answers first
+    #[arg(long, global = true, value_name = "MS")]
+    hedge_after: Option<u64>,
+
+    /// Duplicates sent at most per read with --hedge-after
+    #[arg(long, global = true, value_name = "N", default_value_t = 1, requires = "hedge_after")]
+    max_hedges: u32,
+
     /// Named profile to load from ~/.hindsight/cli-profiles/<name>.toml
     /// (env var HINDSIGHT_PROFILE is used if this flag is omitted).
-    /// Environment variables (HINDSIGHT_API_URL / HINDSIGHT_API_KEY) still override profile values.
+    /// Environment variables (HINDSIGHT_URL / HINDSIGHT_API_KEY) still override profile values.
     #[arg(short = 'p', long, global = true, env = "HINDSIGHT_PROFILE")]
     profile: Option<String>,
 
@@ -85,7 +243,7 @@ enum Commands {
     #[command(subcommand)]
     Document(DocumentCommands),
 
-    /// Manage entities (list, get, regenerate)
+    /// Manage entities (list, show, top, get, regenerate)
     #[command(subcommand)]
     Entity(EntityCommands),
 
@@ -97,8 +255,9 @@ enum Commands {
     #[command(subcommand)]
     Chunk(ChunkCommands),
 
-    /// Manage async operations (list, get, cancel)
+    /// Manage async operations (list, get, cancel, retry)
     #[command(subcommand)]
+    #[command(visible_alias = "operations")]
     Operation(OperationCommands),
 
     /// Manage mental models (user-curated summaries)
@@ -117,25 +276,263 @@ enum Commands {
     #[command(subcommand)]
     Audit(AuditCommands),
 
+    /// Benchmark API operations (recall)
+    #[command(subcommand)]
+    Bench(BenchCommands),
+
+    /// Export the memory graph (export)
+    #[command(subcommand)]
+    Graph(GraphCommands),
+
+    /// Recall memories from a bank (full recall request surface)
+    Recall {
+        /// Bank ID
+        #[arg(required_unless_present = "banks")]
+        bank_id: Option<String>,
+
+        /// Search query ("-" reads from stdin)
+        query: Option<String>,
+
+        /// Read the query from a file ("-" reads from stdin)
+        #[arg(long)]
+        query_file: Option<PathBuf>,
+
+        /// Only search these fact types: world, experience, observation [default: the server's]
+        #[arg(short = 't', long, visible_alias = "fact-type", value_delimiter = ',')]
+        types: Vec<String>,
+
+        /// Search every fact type except these (comma-separated)
+        #[arg(long, value_delimiter = ',', conflicts_with = "types")]
+        exclude_types: Vec<String>,
+
+        /// Thinking budget (low, mid, high)
+        #[arg(short = 'b', long, default_value = "mid", value_parser = ["low", "mid", "high"])]
+        budget: String,
+
+        /// Maximum tokens for results
+        #[arg(long, default_value = "4096")]
+        max_tokens: i64,
+
+        /// Show trace information
+        #[arg(long)]
+        trace: bool,
+
+        /// Include source chunks in results
+        #[arg(long)]
+        include_chunks: bool,
+
+        /// Maximum tokens for chunks (only used with --include-chunks)
+        #[arg(long, default_value = "8192")]
+        chunk_max_tokens: i64,
+
+        /// Filter by tags (comma-separated, e.g. user:alice,team)
+        #[arg(long, visible_alias = "filter-tags", value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Tag matching mode: any, all, any_strict, all_strict (default: any)
+        #[arg(long, visible_alias = "filter-tags-match")]
+        tags_match: Option<String>,
+
+        /// Reference timestamp for recall (ISO 8601, e.g. 2023-05-30T23:40:00)
+        #[arg(long, visible_alias = "filter-timestamp")]
+        query_timestamp: Option<String>,
+
+        /// Only results that happened on or after this date (YYYY-MM-DD, RFC 3339, or like 7d)
+        #[arg(long)]
+        after: Option<String>,
+
+        /// Only results that happened on or before this date (YYYY-MM-DD, RFC 3339, or like 7d)
+        #[arg(long)]
+        before: Option<String>,
+
+        /// Favor recent events when ranking results (0 = relevance only, 1 = newest first)
+        #[arg(long, value_name = "WEIGHT")]
+        recency: Option<f32>,
+
+        /// Group pretty output by fact type or source document
+        #[arg(long, value_name = "FIELD")]
+        group_by: Option<GroupBy>,
+
+        /// Exit with status 1 when recall returns no results
+        #[arg(long)]
+        fail_empty: bool,
+
+        /// Print the request as JSON to stderr before sending it
+        #[arg(long)]
+        show_request: bool,
+
+        /// Recall from several banks at once (comma-separated); the query then comes first
+        #[arg(
+            long,
+            value_delimiter = ',',
+            conflicts_with_all = [
+                "trace", "include_chunks", "group_by", "after", "before", "recency"
+            ]
+        )]
+        banks: Vec<String>,
+
+        /// How results from --banks are merged: take turns, or by relative rank in each bank
+        #[arg(long, value_enum, default_value_t = MergeBy::Interleave, requires = "banks")]
+        merge: MergeBy,
+
+        /// Fold near-duplicate results into the highest-ranked one [default mode: fuzzy]
+        #[arg(
+            long,
+            value_enum,
+            value_name = "MODE",
+            num_args = 0..=1,
+            default_missing_value = "fuzzy",
+            conflicts_with = "banks"
+        )]
+        dedupe: Option<DedupeMode>,
+
+        /// Word-pair similarity (0-1) above which --dedupe fuzzy folds two results
+        #[arg(long, default_value_t = hindsight_client::DEFAULT_DEDUPE_THRESHOLD)]
+        dedupe_threshold: f64,
+
+        /// Start a REPL that runs a recall for each entered line
+        #[arg(
+            short = 'i',
+            long,
+            conflicts_with_all = [
+                "query", "query_file", "fail_empty", "after", "before", "recency", "banks",
+                "show_request"
+            ]
+        )]
+        interactive: bool,
+    },
+
+    /// Reflect on a query using the bank's memories and disposition
+    Reflect {
+        /// Bank ID
+        bank_id: String,
+
+        /// Query to reflect on ("-" reads from stdin)
+        query: Option<String>,
+
+        /// Read the query from a file ("-" reads from stdin)
+        #[arg(long)]
+        query_file: Option<PathBuf>,
+
+        /// Thinking budget (low, mid, high)
+        #[arg(short = 'b', long, default_value = "mid", value_parser = ["low", "mid", "high"])]
+        budget: String,
+
+        /// Additional context
+        #[arg(short = 'c', long)]
+        context: Option<String>,
+
+        /// Prior conversation turns (JSON array of {role, content}, or plain text)
+        #[arg(long)]
+        context_file: Option<PathBuf>,
+
+        /// Maximum tokens for the response, at least 1 (default: reflect_max_tokens, or 4096)
+        #[arg(short = 'm', long, value_parser = clap::value_parser!(i64).range(1..))]
+        max_tokens: Option<i64>,
+
+        /// Path to JSON schema file for structured output
+        #[arg(short = 's', long)]
+        schema: Option<PathBuf>,
+
+        /// Filter by tags (comma-separated, e.g. user:alice,team)
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Tag matching mode: any, all, any_strict, all_strict (default: any)
+        #[arg(long)]
+        tags_match: Option<String>,
+
+        /// Extra response sections to include (comma-separated: facts, tool_calls)
+        #[arg(long, value_delimiter = ',')]
+        include: Vec<String>,
+
+        /// Restrict fact retrieval to these fact types (comma-separated: world, experience, observation)
+        #[arg(long, value_delimiter = ',')]
+        fact_types: Option<Vec<String>>,
+
+        /// Exclude all mental models from the reflect loop
+        #[arg(long)]
+        exclude_mental_models: bool,
+
+        /// Exclude specific mental models by ID (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        exclude_mental_model_ids: Option<Vec<String>>,
+
+        /// Store the reflection back into the bank as an agent memory
+        #[arg(long)]
+        save_as_memory: bool,
+
+        /// List cited memories as the server returned them, without looking each one up
+        #[arg(long)]
+        no_fetch_citations: bool,
+    },
+
+    /// Chat with a bank: each message is a reflect that carries the conversation so far
+    Chat {
+        /// Bank ID
+        bank_id: String,
+
+        /// Thinking budget (low, mid, high)
+        #[arg(short = 'b', long, default_value = "mid", value_parser = ["low", "mid", "high"])]
+        budget: String,
+
+        /// Maximum tokens for each response, at least 1 (default: reflect_max_tokens, or 4096)
+        #[arg(short = 'm', long, value_parser = clap::value_parser!(i64).range(1..))]
+        max_tokens: Option<i64>,
+
+        /// File with a standing instruction prepended to the conversation context
+        #[arg(long)]
+        system_file: Option<PathBuf>,
+
+        /// Approximate token budget for conversation history sent with each message
+        #[arg(long, default_value = "4000")]
+        context_tokens: usize,
+    },
+
     /// Check API health status
     Health,
 
     /// Get Prometheus metrics
     Metrics,
 
-    /// Get API version information
-    Version,
+    /// Show CLI, client library, API spec and server versions
+    Version {
+        /// Only show the local versions, without contacting the server
+        #[arg(long)]
+        client_only: bool,
+    },
 
     /// Interactive TUI explorer (k9s-style) for navigating banks, memories, entities, and performing recall/reflect
     #[command(alias = "tui")]
-    Explore,
+    Explore {
+        /// Color theme [default: `theme` in the config, else dark]
+        #[arg(long, value_enum)]
+        theme: Option<commands::explore::ThemeName>,
+
+        /// Leave the mouse to the terminal instead of clicking and scrolling in the explorer
+        #[arg(long)]
+        no_mouse: bool,
+
+        /// Format the export prompt (`E`) starts in [default: json]
+        #[arg(long, value_enum)]
+        export_format: Option<commands::explore::ExportFormat>,
+
+        /// Start at the Banks list instead of restoring the last session
+        #[arg(long)]
+        fresh: bool,
+
+        /// Seconds between auto-refreshes of the current list, 0 to turn it off
+        /// [default: `refresh_secs` in the config, else 5]
+        #[arg(long, value_name = "SECS")]
+        refresh_secs: Option<u64>,
+    },
 
     /// Launch the web-based control plane UI
     Ui,
 
     /// Configure the CLI (API URL, API key, etc.)
     #[command(
-        after_help = "Configuration priority:\n  1. Environment variables (HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority\n  2. Named profile (-p / HINDSIGHT_PROFILE, see 'hindsight profile')\n  3. Config file (~/.hindsight/config)\n  4. Default (http://localhost:8888)"
+        after_help = "Configuration priority:\n  1. Environment variables (HINDSIGHT_URL or HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority\n  2. Named profile (-p / HINDSIGHT_PROFILE, see 'hindsight profile')\n  3. Config file (~/.hindsight/config)\n  4. Default (http://localhost:8888)\n\nAPI keys stored with 'hindsight auth login' rank below HINDSIGHT_API_KEY and above api_key in files."
     )]
     Configure {
         /// API URL to connect to (interactive prompt if not provided)
@@ -149,6 +546,58 @@ enum Commands {
     /// Manage named connection profiles (~/.hindsight/cli-profiles/<name>.toml)
     #[command(subcommand)]
     Profile(ProfileCommands),
+
+    /// Store API keys in the OS keyring (login, logout, status); applies to -p/--profile
+    #[command(subcommand)]
+    Auth(AuthCommands),
+
+    /// Show and change settings (show, get, set, use-context)
+    #[command(subcommand)]
+    Config(ConfigCommands),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Show every setting as resolved, with its source (flag, env, profile, default)
+    Show,
+
+    /// Print one resolved setting
+    Get {
+        /// Setting name, e.g. api_url or page_size
+        key: String,
+    },
+
+    /// Validate a setting and write it to the active profile (or ~/.hindsight/config)
+    Set {
+        /// Setting name; an unknown name lists the valid ones
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Use a profile for commands run without -p/--profile or $HINDSIGHT_PROFILE
+    UseContext {
+        /// Profile name
+        #[arg(required_unless_present = "clear")]
+        profile: Option<String>,
+
+        /// Go back to using no profile by default
+        #[arg(long, conflicts_with = "profile")]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Prompt for an API key, verify it, and store it in the OS keyring
+    Login,
+
+    /// Remove the stored API key
+    Logout,
+
+    /// Show whether an API key is stored and where
+    Status,
 }
 
 #[derive(Subcommand)]
@@ -175,9 +624,6 @@ enum ProfileCommands {
     Delete {
         /// Profile name
         name: String,
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 }
 
@@ -244,10 +690,51 @@ enum BankCommands {
         bank_id: String,
     },
 
+    /// Save a bank's profile to a file or restore it from one
+    Profile {
+        #[command(subcommand)]
+        command: BankProfileCommands,
+    },
+
     /// Get memory statistics for a bank
     Stats {
         /// Bank ID
         bank_id: String,
+
+        /// Refresh every SECONDS (default 2); prints NDJSON when not pretty/TTY
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "2")]
+        watch: Option<u64>,
+    },
+
+    /// Compare statistics and disposition of two banks
+    Compare {
+        /// Baseline bank ID
+        bank_a: String,
+
+        /// Candidate bank ID
+        bank_b: String,
+
+        /// Also run this recall query on both banks and report result overlap
+        #[arg(long)]
+        recall: Option<String>,
+    },
+
+    /// Delete memories older than a cutoff, of a given type, or from one document
+    Prune {
+        /// Bank ID
+        bank_id: String,
+
+        /// Only prune memories older than this (e.g. 90d, 2w, 2024-01-01)
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+
+        /// Only prune this fact type (world, experience, opinion)
+        #[arg(short = 't', long = "type")]
+        fact_type: Option<String>,
+
+        /// Only prune memories extracted from this document
+        #[arg(long)]
+        document_id: Option<String>,
     },
 
     /// Set bank name
@@ -269,13 +756,18 @@ enum BankCommands {
     },
 
     /// Set or merge bank background (deprecated: use mission instead)
-    #[command(hide = true)]
+    #[command(hide = true, args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Background {
+        #[command(subcommand)]
+        command: Option<BackgroundCommands>,
+
         /// Bank ID
-        bank_id: String,
+        #[arg(required = true)]
+        bank_id: Option<String>,
 
         /// Background content
-        content: String,
+        #[arg(required = true)]
+        content: Option<String>,
 
         /// Skip automatic disposition inference
         #[arg(long)]
@@ -301,9 +793,6 @@ enum BankCommands {
         /// Bank ID
         bank_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 
     /// Trigger consolidation to create/update observations
@@ -325,9 +814,6 @@ enum BankCommands {
         /// Bank ID
         bank_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 
     /// Get bank configuration (hierarchical overrides)
@@ -395,9 +881,6 @@ enum BankCommands {
         /// Bank ID
         bank_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 
     /// Set disposition traits directly (1-5 each, via PUT /profile)
@@ -421,6 +904,28 @@ enum BankCommands {
         bank_id: String,
     },
 
+    /// Export memories, documents, chunks and entities as a JSON archive
+    Export {
+        /// Bank ID
+        bank_id: String,
+
+        /// Write the archive to this file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Replace entity names and aliases with placeholders such as PERSON_1
+        #[arg(long, value_parser = ["entities"])]
+        redact: Option<String>,
+
+        /// Also replace matches of this regex, optionally labeled: EMAIL=REGEX (repeatable)
+        #[arg(long = "redact-regex", value_name = "[LABEL=]REGEX")]
+        redact_regex: Vec<String>,
+
+        /// Write the placeholder-to-value mapping to this JSON file
+        #[arg(long, value_name = "FILE")]
+        mapping_out: Option<PathBuf>,
+    },
+
     /// Export a bank template manifest (config + mental models + directives)
     ExportTemplate {
         /// Bank ID
@@ -438,16 +943,51 @@ enum BankCommands {
 
         /// Path to a JSON manifest file
         manifest: PathBuf,
-
-        /// Validate the manifest without applying changes
-        #[arg(long)]
-        dry_run: bool,
     },
 
     /// Print the bank template JSON schema
     TemplateSchema,
 }
 
+#[derive(Subcommand)]
+enum BankProfileCommands {
+    /// Save the bank's name, mission and disposition as JSON
+    Snapshot {
+        /// Bank ID
+        bank_id: String,
+
+        /// Write the snapshot to this file instead of stdout
+        #[arg(short = 'o', long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Write a saved snapshot back to a bank, after showing the changes
+    Restore {
+        /// Bank ID
+        bank_id: String,
+
+        /// Snapshot file written by `bank profile snapshot`
+        #[arg(short = 'f', long)]
+        file: PathBuf,
+
+        /// Only show how the snapshot differs from the live bank
+        #[arg(long)]
+        compare: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackgroundCommands {
+    /// Show how a local file differs from the bank's current background
+    Diff {
+        /// Bank ID
+        bank_id: String,
+
+        /// File holding the background to compare against
+        file: PathBuf,
+    },
+}
+
 #[derive(Subcommand)]
 enum MemoryCommands {
     /// List memory units with pagination
@@ -456,7 +996,7 @@ enum MemoryCommands {
         bank_id: String,
 
         /// Filter by fact type (world, experience, opinion)
-        #[arg(short = 't', long)]
+        #[arg(short = 't', long, visible_alias = "type")]
         fact_type: Option<String>,
 
         /// Full-text search query
@@ -470,6 +1010,75 @@ enum MemoryCommands {
         /// Offset for pagination
         #[arg(short = 's', long, default_value = "0")]
         offset: i64,
+
+        /// Only memories mentioned on or after this date (YYYY-MM-DD, RFC 3339, or relative like 7d)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only memories mentioned on or before this date (YYYY-MM-DD, RFC 3339, or relative like 7d)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only memories extracted from this document
+        #[arg(long)]
+        document_id: Option<String>,
+
+        /// Only memories whose text contains this substring (case-insensitive)
+        #[arg(long)]
+        contains: Option<String>,
+
+        /// Fetch every page instead of stopping after --limit results
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Copy filtered memories from one bank into another
+    Copy {
+        /// Source bank ID
+        #[arg(long)]
+        from: String,
+
+        /// Destination bank ID
+        #[arg(long)]
+        to: String,
+
+        /// Only copy this fact type (world, experience, opinion)
+        #[arg(short = 't', long, visible_alias = "type")]
+        fact_type: Option<String>,
+
+        /// Only memories mentioned on or after this date (YYYY-MM-DD, RFC 3339, or relative like 7d)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only memories mentioned on or before this date (YYYY-MM-DD, RFC 3339, or relative like 7d)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only memories whose text contains this substring (case-insensitive)
+        #[arg(long)]
+        contains: Option<String>,
+
+        /// After a complete copy, delete the source documents whose memories were all copied
+        #[arg(long = "move")]
+        move_memories: bool,
+    },
+
+    /// Follow a bank and print new memories as they appear
+    Tail {
+        /// Bank ID
+        bank_id: String,
+
+        /// Only follow this fact type (world, experience, opinion)
+        #[arg(short = 't', long, visible_alias = "type")]
+        fact_type: Option<String>,
+
+        /// Seconds between polls
+        #[arg(long, default_value = "2")]
+        interval: u64,
+
+        /// Print full memory text instead of truncating to the terminal width
+        #[arg(long)]
+        full: bool,
     },
 
     /// Get a specific memory unit by ID
@@ -481,6 +1090,27 @@ enum MemoryCommands {
         memory_id: String,
     },
 
+    /// Show the memories a memory is linked to, as a tree
+    Links {
+        /// Bank ID
+        bank_id: String,
+
+        /// Memory unit ID
+        memory_id: String,
+
+        /// Only follow links of this type
+        #[arg(short = 't', long = "type", value_parser = ["temporal", "semantic", "entity"])]
+        link_type: Option<String>,
+
+        /// How many hops to follow
+        #[arg(short = 'd', long, default_value = "1")]
+        depth: usize,
+
+        /// Stop after this many linked memories
+        #[arg(long, default_value = "200")]
+        max_nodes: usize,
+    },
+
     /// Recall memories using semantic search
     Recall {
         /// Bank ID
@@ -489,8 +1119,13 @@ enum MemoryCommands {
         /// Search query
         query: String,
 
-        /// Fact types to search (world, experience, opinion)
-        #[arg(short = 't', long, value_delimiter = ',', default_values = &["world", "experience", "opinion"])]
+        /// Fact types to search (world, experience, observation)
+        #[arg(
+            short = 't',
+            long,
+            value_delimiter = ',',
+            default_values = &["world", "experience", "observation"]
+        )]
         fact_type: Vec<String>,
 
         /// Thinking budget (low, mid, high)
@@ -524,6 +1159,22 @@ enum MemoryCommands {
         /// Reference timestamp for recall (ISO 8601, e.g. 2023-05-30T23:40:00)
         #[arg(long)]
         query_timestamp: Option<String>,
+
+        /// Only results that happened on or after this date (YYYY-MM-DD, RFC 3339, or like 7d)
+        #[arg(long)]
+        after: Option<String>,
+
+        /// Only results that happened on or before this date (YYYY-MM-DD, RFC 3339, or like 7d)
+        #[arg(long)]
+        before: Option<String>,
+
+        /// Favor recent events when ranking results (0 = relevance only, 1 = newest first)
+        #[arg(long, value_name = "WEIGHT")]
+        recency: Option<f32>,
+
+        /// Group pretty output by fact type or source document
+        #[arg(long, value_name = "FIELD")]
+        group_by: Option<GroupBy>,
     },
 
     /// Generate answers using bank identity (reflect/reasoning)
@@ -542,8 +1193,8 @@ enum MemoryCommands {
         #[arg(short = 'c', long)]
         context: Option<String>,
 
-        /// Maximum tokens for the response (server default: 4096)
-        #[arg(short = 'm', long)]
+        /// Maximum tokens for the response, at least 1 (default: reflect_max_tokens, or 4096)
+        #[arg(short = 'm', long, value_parser = clap::value_parser!(i64).range(1..))]
         max_tokens: Option<i64>,
 
         /// Path to JSON schema file for structured output
@@ -573,6 +1224,10 @@ enum MemoryCommands {
         /// Exclude specific mental models by ID (comma-separated)
         #[arg(long, value_delimiter = ',')]
         exclude_mental_model_ids: Option<Vec<String>>,
+
+        /// List cited memories as the server returned them, without looking each one up
+        #[arg(long)]
+        no_fetch_citations: bool,
     },
 
     /// Store (retain) a single memory
@@ -600,6 +1255,85 @@ enum MemoryCommands {
         document_tags: Option<Vec<String>>,
     },
 
+    /// Add memories from inline text, a file, or stdin, printing the document ID of each
+    Add {
+        /// Bank ID
+        bank_id: String,
+
+        /// Memory text ("-" reads from stdin)
+        #[arg(long)]
+        text: Option<String>,
+
+        /// Read memory text from a file ("-" reads from stdin)
+        #[arg(short = 'f', long)]
+        file: Option<PathBuf>,
+
+        /// Split the input into several memories on this delimiter (e.g. "---")
+        #[arg(long)]
+        delimiter: Option<String>,
+
+        /// Fact type hint for the new memories (world, experience, observation), sent in their
+        /// metadata; the server still decides the type of each extracted fact
+        #[arg(short = 't', long = "type")]
+        fact_type: Option<String>,
+
+        /// When the memory occurred (YYYY-MM-DD, RFC 3339, or relative like 7d)
+        #[arg(long)]
+        occurred: Option<String>,
+
+        /// Context for the memories
+        #[arg(short = 'c', long)]
+        context: Option<String>,
+    },
+
+    /// Bulk import memories from a JSONL or CSV file
+    #[command(group(clap::ArgGroup::new("source").required(true).args(["jsonl", "csv"])))]
+    Import {
+        /// Bank ID
+        bank_id: String,
+
+        /// JSONL file with one {"text", "context", "occurred", ...} object per line
+        #[arg(long)]
+        jsonl: Option<PathBuf>,
+
+        /// CSV file (RFC 4180); map its columns with --map
+        #[arg(long)]
+        csv: Option<PathBuf>,
+
+        /// CSV column mapping field=column (fields: text, context, occurred, type, document_id, tags).
+        /// Columns are header names or 1-based positions.
+        #[arg(long = "map", requires = "csv")]
+        map: Vec<String>,
+
+        /// chrono format for CSV dates that aren't ISO 8601 (e.g. "%d/%m/%Y")
+        #[arg(long, requires = "csv")]
+        date_format: Option<String>,
+
+        /// Type hint for CSV rows without a mapped type
+        #[arg(long, requires = "csv", value_parser = ["world", "experience", "observation"])]
+        default_type: Option<String>,
+
+        /// Records sent per retain request
+        #[arg(long, default_value = "100")]
+        batch_size: usize,
+
+        /// Continue from the last committed line recorded in <file>.hindsight-state (JSONL only)
+        #[arg(long, requires = "jsonl")]
+        resume: bool,
+
+        /// Write malformed lines or rows to this file instead of only reporting them
+        #[arg(long)]
+        rejects: Option<PathBuf>,
+
+        /// Queue batches for background processing
+        #[arg(long)]
+        r#async: bool,
+
+        /// Skip records already imported with this flag (matched by normalized text)
+        #[arg(long)]
+        skip_duplicates: bool,
+    },
+
     /// Bulk import memories from files (retain)
     RetainFiles {
         /// Bank ID
@@ -643,9 +1377,6 @@ enum MemoryCommands {
         #[arg(short = 't', long, value_parser = ["world", "agent", "opinion"])]
         fact_type: Option<String>,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 
     /// Show the observation history for a memory unit
@@ -665,9 +1396,6 @@ enum MemoryCommands {
         /// Memory unit ID
         memory_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 }
 
@@ -702,15 +1430,89 @@ enum DocumentCommands {
 
         /// Document ID
         document_id: String,
+
+        /// Also show the document's chunk breakdown
+        #[arg(long)]
+        chunks: bool,
+
+        /// Save the original content to a file instead of printing it
+        #[arg(long)]
+        out: Option<PathBuf>,
     },
 
-    /// Delete a document and all its memory units
+    /// Delete one or more documents
     Delete {
         /// Bank ID
         bank_id: String,
 
-        /// Document ID
-        document_id: String,
+        /// Document IDs
+        #[arg(required = true)]
+        document_ids: Vec<String>,
+
+
+        /// Also delete memories derived from the documents (required when they have any)
+        #[arg(long)]
+        with_memories: bool,
+    },
+
+    /// Ingest files, directories, or globs as documents
+    Ingest {
+        /// Bank ID
+        bank_id: String,
+
+        /// Files, directories, or glob patterns (e.g. 'notes/**/*.md')
+        #[arg(required = true)]
+        paths: Vec<String>,
+
+        /// Recurse into directories
+        #[arg(short = 'r', long)]
+        recursive: bool,
+
+        /// Only ingest files matching these patterns (e.g. '*.md'; repeatable)
+        #[arg(long)]
+        include: Vec<String>,
+
+        /// Skip files matching these patterns (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Number of files uploaded concurrently
+        #[arg(short = 'j', long, default_value = "4")]
+        jobs: usize,
+
+        /// Content type for every file (default: inferred from the extension)
+        #[arg(long)]
+        content_type: Option<String>,
+
+        /// Context for all documents
+        #[arg(short = 'c', long)]
+        context: Option<String>,
+
+        /// Keep going after a file fails instead of aborting the run
+        #[arg(long)]
+        continue_on_error: bool,
+    },
+
+    /// Re-extract memories from stored documents without uploading them again
+    Reprocess {
+        /// Bank ID
+        bank_id: String,
+
+        /// Document IDs
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        document_ids: Vec<String>,
+
+        /// Reprocess every document in the bank (asks for confirmation)
+        #[arg(long)]
+        all: bool,
+
+        /// Wait for reprocessing to finish and report the new memory counts
+        #[arg(long)]
+        wait: bool,
+
+        /// Stop at the first document that fails instead of carrying on
+        #[arg(long)]
+        fail_fast: bool,
     },
 
     /// Update a document (currently only supports replacing tags)
@@ -737,6 +1539,63 @@ enum EntityCommands {
         /// Maximum number of results
         #[arg(short = 'l', long, default_value = "100")]
         limit: i64,
+
+        /// Sort order
+        #[arg(long, default_value = "mentions", value_parser = ["mentions", "name"])]
+        sort: String,
+
+        /// Only show entities mentioned at least this many times
+        #[arg(long, default_value = "0")]
+        min_mentions: i64,
+    },
+
+    /// Show an entity with aliases, related memories and a mention timeline
+    Show {
+        /// Bank ID
+        bank_id: String,
+
+        /// Entity ID or name (case-insensitive; partial names must be unambiguous)
+        name_or_id: String,
+
+        /// Maximum number of related memories
+        #[arg(short = 'l', long, default_value = "10")]
+        limit: usize,
+    },
+
+    /// Leaderboard of the most-mentioned entities
+    Top {
+        /// Bank ID
+        bank_id: String,
+
+        /// Number of entities to show
+        #[arg(short = 'n', long = "count", default_value = "20")]
+        count: usize,
+    },
+
+    /// Entities mentioned together in memories, as a tree of co-mention counts or DOT
+    Graph {
+        /// Bank ID
+        bank_id: String,
+
+        /// Start from this entity (ID or name); without it every entity is shown
+        #[arg(long)]
+        root: Option<String>,
+
+        /// How many hops to follow from each starting entity
+        #[arg(long, default_value = "2")]
+        depth: usize,
+
+        /// Leave out pairs mentioned together in fewer memories than this
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(i64).range(1..))]
+        min_count: i64,
+
+        /// Most-mentioned entities to load
+        #[arg(short = 'l', long, default_value = "1000")]
+        limit: i64,
+
+        /// ascii (indented tree) or dot (Graphviz); -o json prints nodes and edges instead
+        #[arg(long, value_enum, default_value = "ascii")]
+        format: EntityGraphStyle,
     },
 
     /// Get detailed information about an entity
@@ -756,6 +1615,101 @@ enum EntityCommands {
         /// Entity ID
         entity_id: String,
     },
+
+    /// Merge entities into a target, moving their mentions onto it
+    Merge {
+        /// Bank ID
+        bank_id: String,
+
+        /// Entity to keep (ID or name)
+        target: String,
+
+        /// Entities to merge into the target (IDs or names)
+        #[arg(required = true)]
+        sources: Vec<String>,
+    },
+
+    /// Change an entity's canonical name, or add another name with --alias
+    Rename {
+        /// Bank ID
+        bank_id: String,
+
+        /// Entity ID or name
+        name_or_id: String,
+
+        /// New canonical name
+        new_name: String,
+
+        /// Add the name as an alias and keep the canonical name
+        #[arg(long)]
+        alias: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BenchCommands {
+    /// Measure recall latency and result counts over a file of queries
+    Recall {
+        /// Bank ID
+        bank_id: String,
+
+        /// File with one query per line (blank lines and # comments are ignored)
+        #[arg(long)]
+        query_file: PathBuf,
+
+        /// Thinking budget (low, mid, high)
+        #[arg(short = 'b', long, default_value = "mid", value_parser = ["low", "mid", "high"])]
+        budget: String,
+
+        /// Run the whole suite once per budget and compare (e.g. low,mid,high)
+        #[arg(long, value_delimiter = ',', value_parser = ["low", "mid", "high"])]
+        compare_budgets: Vec<String>,
+
+        /// Times each query is run
+        #[arg(short = 'n', long, default_value = "5")]
+        iterations: usize,
+
+        /// Number of concurrent requests
+        #[arg(short = 'j', long, default_value = "4")]
+        concurrency: usize,
+
+        /// Maximum tokens for results
+        #[arg(long, default_value = "4096")]
+        max_tokens: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum GraphCommands {
+    /// Write memories and their links as Graphviz DOT or GraphML (e.g. for Gephi)
+    Export {
+        /// Bank ID
+        bank_id: String,
+
+        /// File format
+        #[arg(long, value_enum, default_value = "dot")]
+        format: GraphFileFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Only include this fact type (world, experience, opinion, observation)
+        #[arg(short = 't', long)]
+        fact_type: Option<String>,
+
+        /// Only include these link types (comma-separated: semantic, temporal, entity, causal)
+        #[arg(long, value_delimiter = ',')]
+        link_type: Vec<String>,
+
+        /// Maximum memories to export; a warning is printed when the bank has more
+        #[arg(long, default_value = "5000")]
+        max_nodes: i64,
+
+        /// Only memories mentioned on or after this date (YYYY-MM-DD, RFC 3339, or relative like 30d)
+        #[arg(long)]
+        since: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -764,6 +1718,18 @@ enum OperationCommands {
     List {
         /// Bank ID
         bank_id: String,
+
+        /// Only operations with this status (done is an alias for completed)
+        #[arg(long, value_parser = ["pending", "processing", "completed", "done", "failed", "cancelled"])]
+        status: Option<String>,
+
+        /// Maximum operations to show (the server allows at most 100)
+        #[arg(long, default_value = "20", value_parser = clap::value_parser!(u64).range(1..=100))]
+        limit: u64,
+
+        /// Refresh every SECONDS (default 2); prints NDJSON when not pretty/TTY
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "2")]
+        watch: Option<u64>,
     },
 
     /// Get the status of a specific operation
@@ -784,13 +1750,18 @@ enum OperationCommands {
         operation_id: String,
     },
 
-    /// Retry a failed async operation
+    /// Retry failed async operations
     Retry {
         /// Bank ID
         bank_id: String,
 
-        /// Operation ID
-        operation_id: String,
+        /// Operation IDs
+        #[arg(required_unless_present = "all_failed")]
+        operation_ids: Vec<String>,
+
+        /// Retry every failed operation in the bank
+        #[arg(long, conflicts_with = "operation_ids")]
+        all_failed: bool,
     },
 }
 
@@ -856,9 +1827,6 @@ enum WebhookCommands {
         /// Webhook ID
         webhook_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 
     /// List recent delivery attempts for a webhook
@@ -1038,9 +2006,6 @@ enum MentalModelCommands {
         /// Mental model ID
         mental_model_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 
     /// Refresh a mental model (re-run the source query)
@@ -1128,25 +2093,200 @@ enum DirectiveCommands {
         /// Directive ID
         directive_id: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
     },
 }
 
 fn main() {
     if let Err(e) = run() {
-        ui::print_error(&format!("{:#}", e));
-        std::process::exit(1);
+        errors::exit_with_error(&e);
+    }
+}
+
+/// The CLI definition plus the global `--bank` flag, which only matters when a
+/// command's bank ID argument is omitted (see [`parse_cli`]).
+fn cli_command() -> clap::Command {
+    Cli::command().arg(
+        clap::Arg::new("bank")
+            .long("bank")
+            .global(true)
+            .value_name("BANK_ID")
+            .help("Bank to use when a command's bank ID argument is omitted [env: HINDSIGHT_DEFAULT_BANK]"),
+    )
+}
+
+/// Parse the command line. When a command that takes a bank ID is missing an
+/// argument, the bank is taken from `--bank` or, on a terminal, picked
+/// interactively, and parsing is retried with it in place.
+fn parse_cli() -> Cli {
+    let args: Vec<String> = std::env::args().collect();
+    let err = match cli_command().try_get_matches_from(&args) {
+        Ok(matches) => return Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit()),
+        Err(e) => e,
+    };
+    if err.kind() != clap::error::ErrorKind::MissingRequiredArgument {
+        err.exit();
+    }
+    let Some(position) = bank_arg_position(&args) else {
+        err.exit();
+    };
+
+    // Lenient parse just to read --bank / --profile despite the missing argument
+    let partial = cli_command()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+        .ok();
+    let flag = |name: &str| {
+        partial
+            .as_ref()
+            .and_then(|m| m.try_get_one::<String>(name).ok().flatten().cloned())
+    };
+
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let env_bank = std::env::var("HINDSIGHT_DEFAULT_BANK")
+        .ok()
+        .filter(|b| !b.trim().is_empty());
+    let bank = match flag("bank").or(env_bank) {
+        Some(bank) => bank,
+        None if interactive && missing_bank_id(&err) => {
+            match pick_bank(flag("profile").as_deref()) {
+                Ok(bank) => bank,
+                Err(e) => {
+                    ui::print_error(&format!("{:#}", e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => err.exit(),
+    };
+
+    let mut patched = args;
+    patched.insert(position, bank);
+    match cli_command().try_get_matches_from(&patched) {
+        Ok(matches) => Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit()),
+        Err(_) => err.exit(),
+    }
+}
+
+/// Whether clap reported the `<BANK_ID>` positional as missing
+fn missing_bank_id(err: &clap::Error) -> bool {
+    match err.get(clap::error::ContextKind::InvalidArg) {
+        Some(clap::error::ContextValue::Strings(args)) => {
+            args.iter().any(|a| a.contains("BANK_ID"))
+        }
+        _ => false,
+    }
+}
+
+/// Index in `args` where the bank ID positional of the invoked subcommand goes,
+/// or `None` if that subcommand takes no `bank_id` argument.
+fn bank_arg_position(args: &[String]) -> Option<usize> {
+    let mut root = cli_command();
+    root.build();
+    let takes_value = |cmd: &clap::Command, matches: &dyn Fn(&clap::Arg) -> bool| {
+        cmd.get_arguments()
+            .any(|a| matches(a) && a.get_action().takes_values())
+    };
+
+    let mut current = &root;
+    let mut position = None;
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            break;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            if !long.contains('=') && takes_value(current, &|a| a.get_long() == Some(long)) {
+                i += 1;
+            }
+        } else if let Some(short) = arg.strip_prefix('-').filter(|s| s.chars().count() == 1) {
+            let short = short.chars().next();
+            if takes_value(current, &|a| a.get_short() == short) {
+                i += 1;
+            }
+        } else if arg.starts_with('-') {
+            // Combined short flags or an attached value; nothing to skip
+        } else if let Some(sub) = current.find_subcommand(arg) {
+            current = sub;
+            position = Some(i + 1);
+        } else {
+            break;
+        }
+        i += 1;
+    }
+
+    position.filter(|_| current.get_arguments().any(|a| a.get_id() == "bank_id"))
+}
+
+/// Show the interactive bank picker; the configured default bank is pre-selected
+fn pick_bank(profile: Option<&str>) -> Result<String> {
+    let config = Config::load_with_profile(profile)?;
+    let client = ApiClient::new(config.api_url().to_string(), config.api_key.clone())?;
+    let mut banks = client.list_agents(false)?;
+    if banks.is_empty() {
+        anyhow::bail!("No banks found. Create one with: hindsight bank create <bank_id>");
+    }
+    banks.sort_by(|a, b| a.bank_id.cmp(&b.bank_id));
+
+    let items: Vec<String> = banks
+        .iter()
+        .map(|b| match b.name.as_deref().filter(|n| !n.is_empty()) {
+            Some(name) => format!("{} - {}", b.bank_id, name),
+            None => b.bank_id.clone(),
+        })
+        .collect();
+    let default = Config::default_bank(profile)
+        .and_then(|d| banks.iter().position(|b| b.bank_id == d))
+        .unwrap_or(0);
+
+    match ui::fuzzy_select("Select a bank:", &items, default)? {
+        Some(index) => Ok(banks[index].bank_id.clone()),
+        None => anyhow::bail!("No bank selected"),
     }
 }
 
 fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = parse_cli();
 
-    let output_format: OutputFormat = cli.output.into();
+    // A bad HINDSIGHT_* variable is reported in the format asked for on the command line
+    if let Some(format) = cli.output {
+        errors::init(format.into());
+    }
+    // Explicit flags win over HINDSIGHT_* variables; report every bad variable at once
+    let env_settings =
+        config::EnvSettings::load().unwrap_or_else(|e| errors::handle_config_error(&e));
+    let output_format: OutputFormat = match cli.output {
+        Some(format) => format.into(),
+        None => env_settings.output.unwrap_or(OutputFormat::Pretty),
+    };
     let verbose = cli.verbose;
     let profile = cli.profile.clone();
+    let yes = cli.yes;
+    let dry_run = cli.dry_run;
+    let timeout_override = cli.timeout.map(std::time::Duration::from_secs);
+    let retry_policy = api::RetryPolicy {
+        retries: cli.retries,
+        delay: std::time::Duration::from_millis(cli.retry_delay),
+        retry_writes: cli.retry_writes,
+    };
+    errors::init(output_format);
+    output::set_columns(cli.columns.clone());
+    output::set_fields(cli.fields.clone());
+    let dates = cli
+        .dates
+        .or_else(|| {
+            let configured = Config::dates(profile.as_deref())?;
+            ui::DateStyle::from_str(&configured, true).ok()
+        })
+        .unwrap_or_default();
+    // Nothing is written under --dry-run, so there is nothing to confirm
+    ui::init(
+        ui::UiContext::new(cli.yes || dry_run, cli.quiet, cli.no_color)
+            .with_dates(dates)
+            .with_width(cli.width)
+            .with_output(output_format),
+    );
+    executor::init(dry_run);
+    pager::init(cli.no_pager, Config::pager(profile.as_deref()).as_deref());
 
     // Handle configure command before loading full config (it doesn't need API client)
     if let Commands::Configure { api_url, api_key } = cli.command {
@@ -1155,7 +2295,36 @@ fn run() -> Result<()> {
 
     // Handle profile management commands — no API client required.
     if let Commands::Profile(cmd) = cli.command {
-        return handle_profile(cmd, output_format);
+        return handle_profile(cmd, yes, output_format);
+    }
+
+    // Auth commands build their own client to verify a key before storing it
+    if let Commands::Auth(cmd) = cli.command {
+        return match cmd {
+            AuthCommands::Login => commands::auth::login(profile.as_deref(), output_format),
+            AuthCommands::Logout => commands::auth::logout(profile.as_deref(), output_format),
+            AuthCommands::Status => commands::auth::status(profile.as_deref(), output_format),
+        };
+    }
+
+    // Config commands read and write settings; no API client required
+    if let Commands::Config(cmd) = cli.command {
+        let flags = commands::config::FlagValues {
+            profile: profile.clone(),
+            output: cli.output.and_then(|f| f.to_possible_value()),
+            timeout: cli.timeout,
+            dates: cli.dates.and_then(|d| d.to_possible_value()),
+        };
+        return match cmd {
+            ConfigCommands::Show => commands::config::show(&flags, output_format),
+            ConfigCommands::Get { key } => commands::config::get(&flags, &key, output_format),
+            ConfigCommands::Set { key, value } => {
+                commands::config::set(profile.as_deref(), &key, &value, output_format)
+            }
+            ConfigCommands::UseContext { profile, .. } => {
+                commands::config::use_context(profile.as_deref(), output_format)
+            }
+        };
     }
 
     // Handle ui command - needs config but not API client
@@ -1164,31 +2333,262 @@ fn run() -> Result<()> {
     }
 
     // Load configuration
-    let config = Config::load_with_profile(profile.as_deref()).unwrap_or_else(|e| {
-        ui::print_error(&format!("Configuration error: {}", e));
-        errors::print_config_help();
-        std::process::exit(1);
-    });
+    let config = Config::load_with_profile(profile.as_deref())
+        .unwrap_or_else(|e| errors::handle_config_error(&e));
 
     let api_url = config.api_url().to_string();
     let api_key = config.api_key.clone();
+    let timeout = timeout_override.or(config.timeout).unwrap_or(api::DEFAULT_TIMEOUT);
 
     // Create API client
-    let client = ApiClient::new(api_url.clone(), api_key).unwrap_or_else(|e| {
-        errors::handle_api_error(e, &api_url);
+    let hedge = cli.hedge_after.map(|ms| api::HedgeConfig {
+        delay: std::time::Duration::from_millis(ms),
+        max_hedges: cli.max_hedges,
     });
+    let client = ApiClient::with_timeout(api_url.clone(), api_key, timeout)
+        .map(|client| client.with_retry_policy(retry_policy).with_verbose(verbose))
+        .map(|client| match hedge {
+            Some(hedge) => client.with_hedging(hedge),
+            None => client,
+        })
+        .unwrap_or_else(|e| {
+            errors::handle_api_error(e, &api_url);
+        });
+
+    // A reflect's max tokens: the flag, else `reflect_max_tokens` from the config
+    let reflect_max_tokens = |flag: Option<i64>| {
+        flag.or_else(|| {
+            let configured = Config::reflect_max_tokens(profile.as_deref())?;
+            configured.parse().ok().filter(|&tokens: &i64| tokens > 0)
+        })
+    };
 
     // Execute command and handle errors
     let result: Result<()> = match cli.command {
         Commands::Configure { .. } => unreachable!(), // Handled above
         Commands::Profile(_) => unreachable!(),       // Handled above
         Commands::Ui => unreachable!(),               // Handled above
-        Commands::Explore => commands::explore::run(&client),
+        Commands::Auth(_) => unreachable!(),          // Handled above
+        Commands::Config(_) => unreachable!(),        // Handled above
+        Commands::Explore {
+            theme,
+            no_mouse,
+            export_format,
+            fresh,
+            refresh_secs,
+        } => {
+            let theme = theme
+                .or_else(|| {
+                    let configured = Config::theme(profile.as_deref())?;
+                    commands::explore::ThemeName::from_str(&configured, true).ok()
+                })
+                .unwrap_or_default();
+            let refresh_secs = refresh_secs
+                .or_else(|| Config::refresh_secs(profile.as_deref())?.parse().ok())
+                .unwrap_or(5);
+            let page_size = Config::page_size(profile.as_deref())
+                .and_then(|size| size.parse().ok())
+                .map_or(commands::explore::LIST_PAGE_SIZE, commands::explore::clamp_page_size);
+            let verbose_loads = Config::verbose_loads(profile.as_deref())
+                .and_then(|verbose| verbose.parse().ok())
+                .unwrap_or(false);
+            let recall_chunks = Config::recall_chunks(profile.as_deref())
+                .and_then(|chunks| chunks.parse().ok())
+                .unwrap_or(true);
+            let reflect_max_tokens = reflect_max_tokens(None).unwrap_or(4096);
+            let options = commands::explore::ExploreOptions {
+                theme,
+                mouse: !no_mouse,
+                export_format: export_format.unwrap_or_default(),
+                fresh,
+                keys: keymap::Keymap::new(&Config::explorer_keys(profile.as_deref()))?,
+                refresh_secs,
+                page_size,
+                verbose_loads,
+                recall_chunks,
+                reflect_max_tokens,
+            };
+            commands::explore::run(&client, options)
+        }
+
+        Commands::Recall {
+            bank_id,
+            types,
+            exclude_types,
+            budget,
+            max_tokens,
+            trace,
+            include_chunks,
+            chunk_max_tokens,
+            interactive: true,
+            ..
+        } => commands::recall::interactive(
+            &client,
+            bank_id.as_deref().unwrap_or_default(),
+            commands::memory::resolve_fact_types(&types, &exclude_types)?,
+            budget,
+            max_tokens,
+            trace,
+            include_chunks,
+            chunk_max_tokens,
+            verbose,
+            output_format,
+        ),
+        Commands::Recall {
+            bank_id,
+            query,
+            query_file,
+            types,
+            exclude_types,
+            budget,
+            max_tokens,
+            tags,
+            tags_match,
+            query_timestamp,
+            fail_empty,
+            show_request,
+            banks,
+            merge,
+            ..
+        } if !banks.is_empty() => {
+            // Without a bank ID the first positional holds the query
+            let query = match (bank_id, query) {
+                (Some(_), Some(_)) => anyhow::bail!("Pass either a bank ID or --banks, not both"),
+                (bank_id, query) => query.or(bank_id),
+            };
+            commands::recall::run_multi(
+                &client,
+                &banks,
+                query,
+                query_file,
+                commands::memory::resolve_fact_types(&types, &exclude_types)?,
+                budget,
+                max_tokens,
+                tags,
+                tags_match,
+                query_timestamp,
+                merge.into(),
+                fail_empty,
+                show_request,
+                verbose,
+                output_format,
+            )
+        }
+        Commands::Recall {
+            bank_id,
+            query,
+            query_file,
+            types,
+            exclude_types,
+            budget,
+            max_tokens,
+            trace,
+            include_chunks,
+            chunk_max_tokens,
+            tags,
+            tags_match,
+            query_timestamp,
+            after,
+            before,
+            recency,
+            fail_empty,
+            show_request,
+            group_by,
+            dedupe,
+            dedupe_threshold,
+            ..
+        } => commands::recall::run(
+            &client,
+            bank_id.as_deref().unwrap_or_default(),
+            query,
+            query_file,
+            commands::memory::resolve_fact_types(&types, &exclude_types)?,
+            budget,
+            max_tokens,
+            trace,
+            include_chunks,
+            chunk_max_tokens,
+            tags,
+            tags_match,
+            query_timestamp,
+            commands::memory::RecallWindow::from_args(
+                after.as_deref(),
+                before.as_deref(),
+                recency,
+            )?,
+            fail_empty,
+            show_request,
+            group_by.map(Into::into),
+            dedupe.map(|mode| match mode {
+                DedupeMode::Exact => 1.0,
+                DedupeMode::Fuzzy => dedupe_threshold,
+            }),
+            verbose,
+            output_format,
+        ),
+
+        Commands::Reflect {
+            bank_id,
+            query,
+            query_file,
+            budget,
+            context,
+            context_file,
+            max_tokens,
+            schema,
+            tags,
+            tags_match,
+            include,
+            fact_types,
+            exclude_mental_models,
+            exclude_mental_model_ids,
+            save_as_memory,
+            no_fetch_citations,
+        } => commands::reflect::run(
+            &client,
+            &bank_id,
+            query,
+            query_file,
+            budget,
+            context,
+            context_file,
+            reflect_max_tokens(max_tokens),
+            schema,
+            tags,
+            tags_match,
+            include,
+            fact_types,
+            exclude_mental_models,
+            exclude_mental_model_ids,
+            save_as_memory,
+            !no_fetch_citations,
+            verbose,
+            output_format,
+        ),
+
+        Commands::Chat {
+            bank_id,
+            budget,
+            max_tokens,
+            system_file,
+            context_tokens,
+        } => commands::chat::run(
+            &client,
+            &bank_id,
+            budget,
+            reflect_max_tokens(max_tokens),
+            system_file,
+            context_tokens,
+            verbose,
+            output_format,
+        ),
 
         // Health, Metrics, and Version
         Commands::Health => commands::health::health(&client, verbose, output_format),
         Commands::Metrics => commands::health::metrics(&client, verbose, output_format),
-        Commands::Version => commands::health::version(&client, verbose, output_format),
+        Commands::Version { client_only } => {
+            commands::health::version(&client, client_only, verbose, output_format)
+        }
 
         // Bank commands
         Commands::Bank(bank_cmd) => match bank_cmd {
@@ -1232,9 +2632,45 @@ fn run() -> Result<()> {
             BankCommands::Disposition { bank_id } => {
                 commands::bank::disposition(&client, &bank_id, verbose, output_format)
             }
-            BankCommands::Stats { bank_id } => {
-                commands::bank::stats(&client, &bank_id, verbose, output_format)
-            }
+            BankCommands::Profile {
+                command: BankProfileCommands::Snapshot { bank_id, out },
+            } => commands::bank::profile_snapshot(&client, &bank_id, out, verbose, output_format),
+            BankCommands::Profile {
+                command: BankProfileCommands::Restore { bank_id, file, compare },
+            } => commands::bank::profile_restore(
+                &client,
+                &bank_id,
+                &file,
+                compare,
+                yes,
+                verbose,
+                output_format,
+            ),
+            BankCommands::Stats { bank_id, watch } => match watch {
+                Some(interval) => {
+                    commands::bank::stats_watch(&client, &bank_id, interval, verbose, output_format)
+                }
+                None => commands::bank::stats(&client, &bank_id, verbose, output_format),
+            },
+            BankCommands::Compare {
+                bank_a,
+                bank_b,
+                recall,
+            } => commands::bank::compare(&client, &bank_a, &bank_b, recall, verbose, output_format),
+            BankCommands::Prune {
+                bank_id,
+                older_than,
+                fact_type,
+                document_id,
+            } => commands::bank::prune(
+                &client,
+                &bank_id,
+                older_than,
+                fact_type,
+                document_id,
+                verbose,
+                output_format,
+            ),
             BankCommands::Name { bank_id, name } => {
                 commands::bank::update_name(&client, &bank_id, &name, verbose, output_format)
             }
@@ -1242,13 +2678,18 @@ fn run() -> Result<()> {
                 commands::bank::mission(&client, &bank_id, &mission, verbose, output_format)
             }
             BankCommands::Background {
+                command: Some(BackgroundCommands::Diff { bank_id, file }),
+                ..
+            } => commands::bank::background_diff(&client, &bank_id, &file, verbose, output_format),
+            BankCommands::Background {
+                command: None,
                 bank_id,
                 content,
                 no_update_disposition,
             } => commands::bank::update_background(
                 &client,
-                &bank_id,
-                &content,
+                bank_id.as_deref().unwrap_or_default(),
+                content.as_deref().unwrap_or_default(),
                 no_update_disposition,
                 verbose,
                 output_format,
@@ -1258,7 +2699,7 @@ fn run() -> Result<()> {
                 fact_type,
                 limit,
             } => commands::bank::graph(&client, &bank_id, fact_type, limit, verbose, output_format),
-            BankCommands::Delete { bank_id, yes } => {
+            BankCommands::Delete { bank_id } => {
                 commands::bank::delete(&client, &bank_id, yes, verbose, output_format)
             }
             BankCommands::Consolidate {
@@ -1273,7 +2714,7 @@ fn run() -> Result<()> {
                 verbose,
                 output_format,
             ),
-            BankCommands::ClearObservations { bank_id, yes } => {
+            BankCommands::ClearObservations { bank_id } => {
                 commands::bank::clear_observations(&client, &bank_id, yes, verbose, output_format)
             }
             BankCommands::Config {
@@ -1310,7 +2751,7 @@ fn run() -> Result<()> {
                 verbose,
                 output_format,
             ),
-            BankCommands::ResetConfig { bank_id, yes } => {
+            BankCommands::ResetConfig { bank_id } => {
                 commands::bank::reset_config(&client, &bank_id, yes, verbose, output_format)
             }
             BankCommands::SetDisposition {
@@ -1330,14 +2771,26 @@ fn run() -> Result<()> {
             BankCommands::ConsolidationRecover { bank_id } => {
                 commands::bank::consolidation_recover(&client, &bank_id, verbose, output_format)
             }
+            BankCommands::Export {
+                bank_id,
+                out,
+                redact,
+                redact_regex,
+                mapping_out,
+            } => commands::bank::export(
+                &client,
+                &bank_id,
+                out,
+                redact.is_some(),
+                redact_regex,
+                mapping_out,
+                verbose,
+                output_format,
+            ),
             BankCommands::ExportTemplate { bank_id, out } => {
                 commands::bank::export_template(&client, &bank_id, out, verbose, output_format)
             }
-            BankCommands::ImportTemplate {
-                bank_id,
-                manifest,
-                dry_run,
-            } => commands::bank::import_template(
+            BankCommands::ImportTemplate { bank_id, manifest } => commands::bank::import_template(
                 &client,
                 &bank_id,
                 &manifest,
@@ -1358,6 +2811,11 @@ fn run() -> Result<()> {
                 query,
                 limit,
                 offset,
+                since,
+                until,
+                document_id,
+                contains,
+                all,
             } => commands::memory::list(
                 &client,
                 &bank_id,
@@ -1365,12 +2823,67 @@ fn run() -> Result<()> {
                 query,
                 limit,
                 offset,
+                since,
+                until,
+                document_id,
+                contains,
+                all,
+                verbose,
+                output_format,
+            ),
+            MemoryCommands::Copy {
+                from,
+                to,
+                fact_type,
+                since,
+                until,
+                contains,
+                move_memories,
+            } => commands::memory::copy(
+                &client,
+                &from,
+                &to,
+                fact_type,
+                since,
+                until,
+                contains,
+                move_memories,
+                verbose,
+                output_format,
+            ),
+            MemoryCommands::Tail {
+                bank_id,
+                fact_type,
+                interval,
+                full,
+            } => commands::memory::tail(
+                &client,
+                &bank_id,
+                fact_type,
+                interval,
+                full,
                 verbose,
                 output_format,
             ),
             MemoryCommands::Get { bank_id, memory_id } => {
                 commands::memory::get(&client, &bank_id, &memory_id, verbose, output_format)
             }
+            MemoryCommands::Links {
+                bank_id,
+                memory_id,
+                link_type,
+                depth,
+                max_nodes,
+            } => commands::memory::links(
+                &client,
+                &bank_id,
+                &memory_id,
+                link_type.as_deref(),
+                depth,
+                max_nodes,
+                verbose,
+                output_format,
+            ),
             MemoryCommands::Recall {
                 bank_id,
                 query,
@@ -1383,11 +2896,15 @@ fn run() -> Result<()> {
                 tags,
                 tags_match,
                 query_timestamp,
+                after,
+                before,
+                recency,
+                group_by,
             } => commands::memory::recall(
                 &client,
                 &bank_id,
                 query,
-                fact_type,
+                commands::memory::resolve_fact_types(&fact_type, &[])?,
                 budget,
                 max_tokens,
                 trace,
@@ -1396,6 +2913,12 @@ fn run() -> Result<()> {
                 tags,
                 tags_match,
                 query_timestamp,
+                commands::memory::RecallWindow::from_args(
+                    after.as_deref(),
+                    before.as_deref(),
+                    recency,
+                )?,
+                group_by.map(Into::into),
                 verbose,
                 output_format,
             ),
@@ -1412,13 +2935,14 @@ fn run() -> Result<()> {
                 fact_types,
                 exclude_mental_models,
                 exclude_mental_model_ids,
+                no_fetch_citations,
             } => commands::memory::reflect(
                 &client,
                 &bank_id,
                 query,
                 budget,
                 context,
-                max_tokens,
+                reflect_max_tokens(max_tokens),
                 schema,
                 tags,
                 tags_match,
@@ -1426,9 +2950,74 @@ fn run() -> Result<()> {
                 fact_types,
                 exclude_mental_models,
                 exclude_mental_model_ids,
+                !no_fetch_citations,
                 verbose,
                 output_format,
             ),
+            MemoryCommands::Add {
+                bank_id,
+                text,
+                file,
+                delimiter,
+                fact_type,
+                occurred,
+                context,
+            } => commands::memory::add(
+                &client,
+                &bank_id,
+                text,
+                file,
+                delimiter,
+                fact_type,
+                occurred,
+                context,
+                verbose,
+                output_format,
+            ),
+            MemoryCommands::Import {
+                bank_id,
+                jsonl,
+                csv,
+                map,
+                date_format,
+                default_type,
+                batch_size,
+                resume,
+                rejects,
+                r#async,
+                skip_duplicates,
+            } => {
+                let options = commands::import::IngestOptions { skip_duplicates };
+                match (jsonl, csv) {
+                    (Some(jsonl), _) => commands::import::jsonl(
+                        &client,
+                        &bank_id,
+                        &jsonl,
+                        batch_size,
+                        resume,
+                        rejects,
+                        r#async,
+                        options,
+                        verbose,
+                        output_format,
+                    ),
+                    (None, Some(csv)) => commands::import::csv(
+                        &client,
+                        &bank_id,
+                        &csv,
+                        &map,
+                        date_format,
+                        default_type,
+                        batch_size,
+                        rejects,
+                        r#async,
+                        options,
+                        verbose,
+                        output_format,
+                    ),
+                    (None, None) => unreachable!("clap requires --jsonl or --csv"),
+                }
+            }
             MemoryCommands::Retain {
                 bank_id,
                 content,
@@ -1471,7 +3060,6 @@ fn run() -> Result<()> {
             MemoryCommands::Clear {
                 bank_id,
                 fact_type,
-                yes,
             } => commands::memory::clear(&client, &bank_id, fact_type, yes, verbose, output_format),
             MemoryCommands::History { bank_id, memory_id } => {
                 commands::memory::history(&client, &bank_id, &memory_id, verbose, output_format)
@@ -1479,7 +3067,6 @@ fn run() -> Result<()> {
             MemoryCommands::ClearObservations {
                 bank_id,
                 memory_id,
-                yes,
             } => commands::memory::clear_observations(
                 &client,
                 &bank_id,
@@ -1511,13 +3098,71 @@ fn run() -> Result<()> {
             DocumentCommands::Get {
                 bank_id,
                 document_id,
-            } => commands::document::get(&client, &bank_id, &document_id, verbose, output_format),
+                chunks,
+                out,
+            } => commands::document::get(
+                &client,
+                &bank_id,
+                &document_id,
+                chunks,
+                out,
+                verbose,
+                output_format,
+            ),
             DocumentCommands::Delete {
                 bank_id,
-                document_id,
-            } => {
-                commands::document::delete(&client, &bank_id, &document_id, verbose, output_format)
-            }
+                document_ids,
+                with_memories,
+            } => commands::document::delete(
+                &client,
+                &bank_id,
+                &document_ids,
+                yes,
+                with_memories,
+                verbose,
+                output_format,
+            ),
+            DocumentCommands::Ingest {
+                bank_id,
+                paths,
+                recursive,
+                include,
+                exclude,
+                jobs,
+                content_type,
+                context,
+                continue_on_error,
+            } => commands::document::ingest(
+                &client,
+                &bank_id,
+                paths,
+                recursive,
+                include,
+                exclude,
+                jobs,
+                content_type,
+                context,
+                continue_on_error,
+                verbose,
+                output_format,
+            ),
+            DocumentCommands::Reprocess {
+                bank_id,
+                document_ids,
+                all,
+                wait,
+                fail_fast,
+            } => commands::document::reprocess(
+                &client,
+                &bank_id,
+                &document_ids,
+                all,
+                wait,
+                fail_fast,
+                yes,
+                verbose,
+                output_format,
+            ),
             DocumentCommands::Update {
                 bank_id,
                 document_id,
@@ -1537,15 +3182,80 @@ fn run() -> Result<()> {
 
         // Entity commands
         Commands::Entity(entity_cmd) => match entity_cmd {
-            EntityCommands::List { bank_id, limit } => {
-                commands::entity::list(&client, &bank_id, limit, verbose, output_format)
+            EntityCommands::List {
+                bank_id,
+                limit,
+                sort,
+                min_mentions,
+            } => commands::entity::list(
+                &client,
+                &bank_id,
+                limit,
+                &sort,
+                min_mentions,
+                verbose,
+                output_format,
+            ),
+            EntityCommands::Show {
+                bank_id,
+                name_or_id,
+                limit,
+            } => commands::entity::show(&client, &bank_id, &name_or_id, limit, verbose, output_format),
+            EntityCommands::Top { bank_id, count } => {
+                commands::entity::top(&client, &bank_id, count, verbose, output_format)
             }
+            EntityCommands::Graph {
+                bank_id,
+                root,
+                depth,
+                min_count,
+                limit,
+                format,
+            } => commands::entity::graph(
+                &client,
+                &bank_id,
+                root.as_deref(),
+                depth,
+                min_count,
+                limit,
+                format.into(),
+                verbose,
+                output_format,
+            ),
             EntityCommands::Get { bank_id, entity_id } => {
                 commands::entity::get(&client, &bank_id, &entity_id, verbose, output_format)
             }
             EntityCommands::Regenerate { bank_id, entity_id } => {
                 commands::entity::regenerate(&client, &bank_id, &entity_id, verbose, output_format)
             }
+            EntityCommands::Merge {
+                bank_id,
+                target,
+                sources,
+            } => commands::entity::merge(
+                &client,
+                &bank_id,
+                &target,
+                &sources,
+                yes,
+                verbose,
+                output_format,
+            ),
+            EntityCommands::Rename {
+                bank_id,
+                name_or_id,
+                new_name,
+                alias,
+            } => commands::entity::rename(
+                &client,
+                &bank_id,
+                &name_or_id,
+                &new_name,
+                alias,
+                yes,
+                verbose,
+                output_format,
+            ),
         },
 
         // Tag commands
@@ -1575,9 +3285,20 @@ fn run() -> Result<()> {
 
         // Operation commands
         Commands::Operation(op_cmd) => match op_cmd {
-            OperationCommands::List { bank_id } => {
-                commands::operation::list(&client, &bank_id, verbose, output_format)
-            }
+            OperationCommands::List {
+                bank_id,
+                status,
+                limit,
+                watch,
+            } => commands::operation::list(
+                &client,
+                &bank_id,
+                status,
+                limit,
+                watch,
+                verbose,
+                output_format,
+            ),
             OperationCommands::Get {
                 bank_id,
                 operation_id,
@@ -1594,10 +3315,16 @@ fn run() -> Result<()> {
             ),
             OperationCommands::Retry {
                 bank_id,
-                operation_id,
-            } => {
-                commands::operation::retry(&client, &bank_id, &operation_id, verbose, output_format)
-            }
+                operation_ids,
+                all_failed,
+            } => commands::operation::retry(
+                &client,
+                &bank_id,
+                operation_ids,
+                all_failed,
+                verbose,
+                output_format,
+            ),
         },
 
         // Mental model commands
@@ -1658,7 +3385,6 @@ fn run() -> Result<()> {
             MentalModelCommands::Delete {
                 bank_id,
                 mental_model_id,
-                yes,
             } => commands::mental_model::delete(
                 &client,
                 &bank_id,
@@ -1733,7 +3459,6 @@ fn run() -> Result<()> {
             DirectiveCommands::Delete {
                 bank_id,
                 directive_id,
-                yes,
             } => commands::directive::delete(
                 &client,
                 &bank_id,
@@ -1786,7 +3511,6 @@ fn run() -> Result<()> {
             WebhookCommands::Delete {
                 bank_id,
                 webhook_id,
-                yes,
             } => commands::webhook::delete(
                 &client,
                 &bank_id,
@@ -1839,6 +3563,53 @@ fn run() -> Result<()> {
                 period,
             } => commands::audit::stats(&client, &bank_id, action, period, verbose, output_format),
         },
+
+        // Bench commands
+        Commands::Bench(bench_cmd) => match bench_cmd {
+            BenchCommands::Recall {
+                bank_id,
+                query_file,
+                budget,
+                compare_budgets,
+                iterations,
+                concurrency,
+                max_tokens,
+            } => commands::bench::recall(
+                &client,
+                &bank_id,
+                &query_file,
+                budget,
+                compare_budgets,
+                iterations,
+                concurrency,
+                max_tokens,
+                verbose,
+                output_format,
+            ),
+        },
+
+        Commands::Graph(graph_cmd) => match graph_cmd {
+            GraphCommands::Export {
+                bank_id,
+                format,
+                out,
+                fact_type,
+                link_type,
+                max_nodes,
+                since,
+            } => commands::graph::export(
+                &client,
+                &bank_id,
+                format.into(),
+                out,
+                fact_type,
+                link_type,
+                max_nodes,
+                since,
+                verbose,
+                output_format,
+            ),
+        },
     };
 
     // Handle API errors with nice messages
@@ -1846,7 +3617,7 @@ fn run() -> Result<()> {
         errors::handle_api_error(e, &api_url);
     }
 
-    Ok(())
+    executor::print_plan(output_format)
 }
 
 fn handle_configure(
@@ -1916,7 +3687,7 @@ fn handle_configure(
             println!("  API Key: {}", masked);
         }
         println!();
-        println!("Note: Environment variables HINDSIGHT_API_URL and HINDSIGHT_API_KEY will override these settings.");
+        println!("Note: Environment variables HINDSIGHT_URL (or HINDSIGHT_API_URL) and HINDSIGHT_API_KEY will override these settings.");
     } else {
         let result = serde_json::json!({
             "api_url": new_api_url,
@@ -1984,7 +3755,7 @@ fn mask_api_key(key: &str) -> String {
     }
 }
 
-fn handle_profile(cmd: ProfileCommands, output_format: OutputFormat) -> Result<()> {
+fn handle_profile(cmd: ProfileCommands, yes: bool, output_format: OutputFormat) -> Result<()> {
     match cmd {
         ProfileCommands::Create {
             name,
@@ -2057,7 +3828,7 @@ fn handle_profile(cmd: ProfileCommands, output_format: OutputFormat) -> Result<(
             }
             Ok(())
         }
-        ProfileCommands::Delete { name, yes } => {
+        ProfileCommands::Delete { name } => {
             let path = Config::profile_file_path(&name)
                 .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
             if !path.exists() {
@@ -2090,3 +3861,21 @@ fn handle_profile(cmd: ProfileCommands, output_format: OutputFormat) -> Result<(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_recall_default_types_are_accepted() {
+        let cli = Cli::try_parse_from(["hindsight", "memory", "recall", "my-bank", "query"])
+            .expect("plain memory recall should parse");
+        let Commands::Memory(MemoryCommands::Recall { fact_type, .. }) = cli.command else {
+            panic!("expected memory recall");
+        };
+        assert_eq!(
+            commands::memory::resolve_fact_types(&fact_type, &[]).unwrap(),
+            ["world", "experience", "observation"]
+        );
+    }
+}
@@ -1,20 +1,50 @@
+use crate::ui::Citation;
 use anyhow::Result;
 use serde::Serialize;
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use std::sync::OnceLock;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputFormat {
     Pretty,
     Json,
+    /// JSON with syntax highlighting when stdout is a terminal and color is on
+    JsonPretty,
     Yaml,
+    Table,
+    Csv,
+    /// Recall results and reflect answers laid out for pasting into docs and PRs
+    Markdown,
+    /// One compact JSON object per line, for list commands
+    Ndjson,
 }
 
+/// Columns requested with the global `--columns` flag (empty = command defaults)
+static COLUMNS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Fields requested with the global `--fields` flag (empty = no projection)
+static FIELDS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Terminal width used when stdout is a TTY but its size can't be queried
+const FALLBACK_TABLE_WIDTH: usize = 120;
+
+/// Minimum width a column is shrunk to before we stop truncating
+const MIN_COLUMN_WIDTH: usize = 4;
+
 impl OutputFormat {
     /// Parse output format from string
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "json" => Some(OutputFormat::Json),
+            "json-pretty" => Some(OutputFormat::JsonPretty),
             "yaml" | "yml" => Some(OutputFormat::Yaml),
             "pretty" | "text" => Some(OutputFormat::Pretty),
+            "table" => Some(OutputFormat::Table),
+            "csv" => Some(OutputFormat::Csv),
+            "markdown" | "md" => Some(OutputFormat::Markdown),
+            "ndjson" | "jsonl" => Some(OutputFormat::Ndjson),
             _ => None,
         }
     }
@@ -25,19 +55,139 @@ pub fn to_json<T: Serialize>(data: &T) -> Result<String> {
     Ok(serde_json::to_string_pretty(data)?)
 }
 
+/// Format data as JSON with a 2-space indent, coloring keys, strings, numbers, booleans
+/// and nulls when `highlight` is set. Without it this matches `to_json`.
+pub fn to_json_pretty(value: &Value, highlight: bool) -> String {
+    let mut out = String::new();
+    write_json(&mut out, value, 0, highlight);
+    out
+}
+
+/// Whether `--output json-pretty` should color its output: only on a terminal, and
+/// never with `--no-color` or NO_COLOR
+fn json_highlighting() -> bool {
+    crate::ui::context().color && std::io::stdout().is_terminal()
+}
+
+fn highlighted_json<T: Serialize>(data: &T) -> Result<String> {
+    Ok(to_json_pretty(&serde_json::to_value(data)?, json_highlighting()))
+}
+
+const JSON_KEY: &str = "\x1b[1;34m";
+const JSON_STRING: &str = "\x1b[32m";
+const JSON_NUMBER: &str = "\x1b[33m";
+const JSON_BOOL: &str = "\x1b[35m";
+const JSON_NULL: &str = "\x1b[2m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+fn write_json(out: &mut String, value: &Value, depth: usize, highlight: bool) {
+    match value {
+        Value::Null => paint(out, JSON_NULL, "null", highlight),
+        Value::Bool(b) => paint(out, JSON_BOOL, &b.to_string(), highlight),
+        Value::Number(n) => paint(out, JSON_NUMBER, &n.to_string(), highlight),
+        Value::String(text) => paint(out, JSON_STRING, &quoted(text), highlight),
+        Value::Array(items) => write_block(out, ('[', ']'), items, depth, |out, item| {
+            write_json(out, item, depth + 1, highlight)
+        }),
+        Value::Object(map) => write_block(out, ('{', '}'), map, depth, |out, (key, item)| {
+            write_entry(out, key, item, depth + 1, highlight)
+        }),
+    }
+}
+
+fn write_entry(out: &mut String, key: &str, value: &Value, depth: usize, highlight: bool) {
+    paint(out, JSON_KEY, &quoted(key), highlight);
+    out.push_str(": ");
+    write_json(out, value, depth, highlight);
+}
+
+/// Write `items` one per line between `brackets`, indented one level past `depth`
+fn write_block<I: IntoIterator>(
+    out: &mut String,
+    brackets: (char, char),
+    items: I,
+    depth: usize,
+    mut write_item: impl FnMut(&mut String, I::Item),
+) {
+    let mut items = items.into_iter().peekable();
+    out.push(brackets.0);
+    if items.peek().is_some() {
+        out.push('\n');
+        while let Some(item) = items.next() {
+            out.push_str(&"  ".repeat(depth + 1));
+            write_item(out, item);
+            out.push_str(if items.peek().is_some() { ",\n" } else { "\n" });
+        }
+        out.push_str(&"  ".repeat(depth));
+    }
+    out.push(brackets.1);
+}
+
+fn paint(out: &mut String, color: &str, text: &str, highlight: bool) {
+    if highlight {
+        out.push_str(color);
+        out.push_str(text);
+        out.push_str(ANSI_RESET);
+    } else {
+        out.push_str(text);
+    }
+}
+
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+/// `--output json-pretty` for projected rows, keeping the keys in `--fields` order
+fn highlighted_rows(rows: &[OrderedRow], single: bool) -> String {
+    let highlight = json_highlighting();
+    let write_row = |out: &mut String, row: &OrderedRow, depth: usize| {
+        let entries = row.fields.iter().zip(row.values);
+        write_block(out, ('{', '}'), entries, depth, |out, (key, value)| {
+            write_entry(out, key, value, depth + 1, highlight)
+        });
+    };
+    let mut out = String::new();
+    match rows.first().filter(|_| single) {
+        Some(row) => write_row(&mut out, row, 0),
+        None => write_block(&mut out, ('[', ']'), rows, 0, |out, row| write_row(out, row, 1)),
+    }
+    out
+}
+
 /// Format data as YAML string
 pub fn to_yaml<T: Serialize>(data: &T) -> Result<String> {
     Ok(serde_yaml::to_string(data)?)
 }
 
 pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()> {
+    if let Some(fields) = selected_fields() {
+        if format != OutputFormat::Pretty {
+            return print_projected(&project(&serde_json::to_value(data)?, fields)?, format);
+        }
+    }
     match format {
         OutputFormat::Json => {
             println!("{}", to_json(data)?);
         }
+        OutputFormat::JsonPretty => {
+            println!("{}", highlighted_json(data)?);
+        }
         OutputFormat::Yaml => {
             println!("{}", to_yaml(data)?);
         }
+        OutputFormat::Table => {
+            print_value_as_table(&serde_json::to_value(data)?)?;
+        }
+        OutputFormat::Csv => {
+            print!("{}", to_csv(&serde_json::to_value(data)?));
+        }
+        OutputFormat::Markdown => {
+            print!("{}", to_markdown(&serde_json::to_value(data)?)?);
+        }
+        OutputFormat::Ndjson => {
+            let value = serde_json::to_value(data)?;
+            write_ndjson(list_items(&value).ok_or_else(ndjson_needs_a_list)?)?;
+        }
         OutputFormat::Pretty => {
             // This should not be called - pretty printing is handled in ui.rs
             unreachable!("Pretty format should be handled separately")
@@ -46,6 +196,587 @@ pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()>
     Ok(())
 }
 
+/// Write `items` as NDJSON: one compact JSON object per line, flushed right away so
+/// list commands can stream each page as it arrives. `--fields` applies to every item.
+pub fn write_ndjson<T: Serialize>(items: &[T]) -> Result<()> {
+    let values = items.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+    if let Some(fields) = selected_fields() {
+        return print_projected(&project(&Value::Array(values), fields)?, OutputFormat::Ndjson);
+    }
+    let mut stdout = std::io::stdout().lock();
+    for value in &values {
+        write_ndjson_line(&mut stdout, value)?;
+    }
+    Ok(())
+}
+
+fn write_ndjson_line<T: Serialize>(out: &mut impl Write, item: &T) -> Result<()> {
+    serde_json::to_writer(&mut *out, item)?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(())
+}
+
+fn ndjson_needs_a_list() -> anyhow::Error {
+    anyhow::anyhow!("NDJSON output is only available for list commands; use --output json instead")
+}
+
+/// Format data as Markdown. Recall and reflect responses get a layout of their own;
+/// anything else becomes a fenced JSON block.
+pub fn to_markdown(value: &Value) -> Result<String> {
+    if let Some(results) = value.get("results").and_then(Value::as_array) {
+        return Ok(recall_markdown(results, value.get("chunks")));
+    }
+    if let Some(text) = value.get("text").and_then(Value::as_str).filter(|_| is_reflection(value)) {
+        return Ok(reflect_markdown(text, value));
+    }
+    Ok(fenced(&to_json(value)?, "json"))
+}
+
+/// Fields of a reflect response; empty ones are left out when it is serialized
+const REFLECT_FIELDS: &[&str] =
+    &["text", "based_on", "structured_output", "usage", "trace", "citations"];
+
+/// Whether `value` is a reflect response rather than another object with a `text`
+fn is_reflection(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|object| object.keys().all(|key| REFLECT_FIELDS.contains(&key.as_str())))
+}
+
+/// One `##` section per recall result: type and date, the text, and the source chunk
+/// folded away in a `<details>` block
+fn recall_markdown(results: &[Value], chunks: Option<&Value>) -> String {
+    if results.is_empty() {
+        return "_No results._\n".to_string();
+    }
+    let mut out = String::new();
+    for (i, result) in results.iter().enumerate() {
+        let field = |key: &str| result.get(key).and_then(Value::as_str).filter(|s| !s.is_empty());
+        let mut heading = format!("## Result {}", i + 1);
+        if let Some(fact_type) = field("type") {
+            heading.push_str(&format!(" · `{}`", fact_type));
+        }
+        // The day is enough here, and it reads the same in every time zone
+        let date = field("occurred_start").or_else(|| field("mentioned_at"));
+        if let Some(day) = date.map(|date| date.get(..10).unwrap_or(date)) {
+            heading.push_str(&format!(" · {}", day));
+        }
+        out.push_str(&heading);
+        out.push_str("\n\n");
+        out.push_str(field("text").unwrap_or_default().trim());
+        out.push_str("\n\n");
+
+        let chunk = field("chunk_id").and_then(|id| chunks?.get(id));
+        if let Some(chunk_text) = chunk.and_then(|c| c.get("text")).and_then(Value::as_str) {
+            out.push_str("<details>\n<summary>Source chunk</summary>\n\n");
+            out.push_str(&fenced(chunk_text.trim(), "text"));
+            out.push_str("\n</details>\n\n");
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// The answer, then the IDs of the memories it was based on
+fn reflect_markdown(text: &str, response: &Value) -> String {
+    let mut out = format!("{}\n", text.trim());
+    // Fetched citations when the command resolved them, else the facts as returned
+    let cited = response
+        .get("citations")
+        .or_else(|| response.pointer("/based_on/memories"))
+        .and_then(Value::as_array);
+    let citations: Vec<Citation> = cited.into_iter().flatten().map(Citation::from_memory).collect();
+    if !citations.is_empty() {
+        let markers: Vec<String> = (1..=citations.len()).map(|n| format!("[^{}]", n)).collect();
+        out.push_str(&format!("\nSources: {}\n\n", markers.join(" ")));
+        for (i, citation) in citations.iter().enumerate() {
+            out.push_str(&format!("[^{}]: {}\n", i + 1, footnote(citation)));
+        }
+    }
+    if let Some(structured) = response.get("structured_output").filter(|v| !v.is_null()) {
+        out.push_str("\n### Structured output\n\n");
+        let json = serde_json::to_string_pretty(structured).unwrap_or_default();
+        out.push_str(&fenced(&json, "json"));
+    }
+    out
+}
+
+/// Length a footnote's text preview is cut to
+const FOOTNOTE_PREVIEW_WIDTH: usize = 100;
+
+/// One citation as footnote text: type, day and a text preview, then the memory ID
+fn footnote(citation: &Citation) -> String {
+    let id = citation.id.as_deref().map(|id| format!("`{}`", id));
+    if !citation.available {
+        return format!("{} (unavailable)", id.unwrap_or_default());
+    }
+    let mut parts = Vec::new();
+    if let Some(fact_type) = &citation.fact_type {
+        parts.push(format!("**{}**", fact_type));
+    }
+    if let Some(date) = &citation.date {
+        parts.push(date.get(..10).unwrap_or(date).to_string());
+    }
+    let preview = citation.preview();
+    if !preview.is_empty() {
+        parts.push(truncate_to_width(&preview, FOOTNOTE_PREVIEW_WIDTH));
+    }
+    let mut text = parts.join(" · ");
+    if let Some(id) = id {
+        text.push_str(&format!(" ({})", id));
+    }
+    text
+}
+
+/// A fenced code block, with a fence longer than any run of backticks in `text`
+fn fenced(text: &str, language: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{}{}\n{}\n{}\n", fence, language, text, fence)
+}
+
+/// Set the columns selected with `--columns` (called once from main)
+pub fn set_columns(columns: Vec<String>) {
+    let _ = COLUMNS.set(columns);
+}
+
+fn selected_columns() -> Option<&'static [String]> {
+    COLUMNS.get().filter(|c| !c.is_empty()).map(|c| c.as_slice())
+}
+
+/// Set the fields selected with `--fields` (called once from main)
+pub fn set_fields(fields: Vec<String>) {
+    let _ = FIELDS.set(fields);
+}
+
+fn selected_fields() -> Option<&'static [String]> {
+    FIELDS.get().filter(|f| !f.is_empty()).map(|f| f.as_slice())
+}
+
+/// Look up a dotted path such as `metadata.source` or `tags.0`
+fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |current, segment| match current {
+        Value::Object(map) => map.get(segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// Field paths of a sample item (top-level keys plus one level of nesting)
+fn available_fields(item: &Value) -> Vec<String> {
+    let mut fields = Vec::new();
+    if let Value::Object(map) = item {
+        for (key, value) in map {
+            fields.push(key.clone());
+            if let Value::Object(nested) = value {
+                fields.extend(nested.keys().map(|k| format!("{}.{}", key, k)));
+            }
+        }
+    }
+    fields
+}
+
+/// Items of a list-like value: an array, `{"items": [...]}`, or an object whose
+/// only array field holds the records (e.g. `{"bank_id": .., "operations": [...]}`)
+fn list_items(value: &Value) -> Option<&Vec<Value>> {
+    match value {
+        Value::Array(items) => Some(items),
+        Value::Object(map) => map.get("items").and_then(|v| v.as_array()).or_else(|| {
+            let mut arrays = map
+                .values()
+                .filter_map(|v| v.as_array())
+                .filter(|a| a.iter().all(|v| v.is_object()));
+            match (arrays.next(), arrays.next()) {
+                (Some(items), None) => Some(items),
+                _ => None,
+            }
+        }),
+        _ => None,
+    }
+}
+
+/// Rows projected to `--fields`, each holding the fields in the requested order
+#[derive(Debug, PartialEq)]
+struct Projection {
+    fields: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    /// False when the value was a single object rather than a list
+    list: bool,
+}
+
+/// Serializes one projected row as an object with keys in `--fields` order
+struct OrderedRow<'a> {
+    fields: &'a [String],
+    values: &'a [Value],
+}
+
+impl Serialize for OrderedRow<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for (field, value) in self.fields.iter().zip(self.values) {
+            map.serialize_entry(field, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Project a serialized response down to `fields`. A field no item has is an error
+/// naming the fields available on the first item.
+fn project(value: &Value, fields: &[String]) -> Result<Projection> {
+    let (items, list) = match list_items(value) {
+        Some(items) => (items.as_slice(), true),
+        None => (std::slice::from_ref(value), false),
+    };
+    let unknown: Vec<&str> = fields
+        .iter()
+        .filter(|f| !items.is_empty() && items.iter().all(|item| lookup_path(item, f).is_none()))
+        .map(|f| f.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        anyhow::bail!(
+            "Unknown field(s) for --fields: {}. Available fields: {}",
+            unknown.join(", "),
+            available_fields(&items[0]).join(", ")
+        );
+    }
+    let rows = items
+        .iter()
+        .map(|item| {
+            fields
+                .iter()
+                .map(|f| lookup_path(item, f).cloned().unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    Ok(Projection {
+        fields: fields.to_vec(),
+        rows,
+        list,
+    })
+}
+
+fn print_projected(projection: &Projection, format: OutputFormat) -> Result<()> {
+    let rows: Vec<OrderedRow> = projection
+        .rows
+        .iter()
+        .map(|values| OrderedRow {
+            fields: &projection.fields,
+            values,
+        })
+        .collect();
+    match format {
+        OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Yaml => {
+            let single = rows.first().filter(|_| !projection.list);
+            let text = match (format, single) {
+                (OutputFormat::Json, Some(row)) => to_json(row)?,
+                (OutputFormat::Json, None) => to_json(&rows)?,
+                (OutputFormat::JsonPretty, single) => highlighted_rows(&rows, single.is_some()),
+                (_, Some(row)) => to_yaml(row)?,
+                (_, None) => to_yaml(&rows)?,
+            };
+            println!("{}", text);
+        }
+        OutputFormat::Csv => {
+            let header: Vec<String> = projection.fields.iter().map(|f| csv_escape(f)).collect();
+            println!("{}", header.join(","));
+            for values in &projection.rows {
+                let row: Vec<String> = values.iter().map(|v| csv_cell(Some(v))).collect();
+                println!("{}", row.join(","));
+            }
+        }
+        OutputFormat::Markdown => {
+            let single = rows.first().filter(|_| !projection.list);
+            let text = match single {
+                Some(row) => to_json(row)?,
+                None => to_json(&rows)?,
+            };
+            print!("{}", fenced(&text, "json"));
+        }
+        OutputFormat::Ndjson => {
+            if !projection.list {
+                return Err(ndjson_needs_a_list());
+            }
+            let mut stdout = std::io::stdout().lock();
+            for row in &rows {
+                write_ndjson_line(&mut stdout, row)?;
+            }
+        }
+        OutputFormat::Table | OutputFormat::Pretty => {
+            let headers: Vec<String> = projection.fields.iter().map(|f| f.to_uppercase()).collect();
+            let rows: Vec<Vec<String>> = projection
+                .rows
+                .iter()
+                .map(|values| values.iter().map(value_cell).collect())
+                .collect();
+            print!("{}", layout_table(&headers, &rows, terminal_width()));
+        }
+    }
+    Ok(())
+}
+
+/// Map a user-facing column name to a header and the item keys it may be stored under.
+///
+/// Lets `--columns type,created_at,text` work across memories, entities and banks
+/// even though the API names those fields differently.
+fn resolve_column(name: &str) -> (String, Vec<String>) {
+    let lower = name.to_lowercase();
+    let (header, keys): (&str, &[&str]) = match lower.as_str() {
+        "type" | "fact_type" => ("TYPE", &["fact_type", "type"]),
+        "created" | "created_at" | "date" => ("CREATED", &["created_at", "mentioned_at", "date"]),
+        "name" | "canonical_name" => ("NAME", &["canonical_name", "name"]),
+        "mentions" | "mention_count" => ("MENTIONS", &["mention_count"]),
+        "id" | "bank_id" => ("ID", &["id", "bank_id"]),
+        _ => return (lower.to_uppercase(), vec![lower]),
+    };
+    let mut candidates = vec![lower.clone()];
+    candidates.extend(keys.iter().map(|k| k.to_string()));
+    (header.to_string(), candidates)
+}
+
+fn cell_text(item: &Value, keys: &[String]) -> String {
+    match keys.iter().find_map(|k| item.get(k).filter(|v| !v.is_null())) {
+        Some(value) => value_cell(value),
+        None => String::new(),
+    }
+}
+
+fn value_cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    // Cells are single-line; tabs would also break TSV output
+    text.replace(['\n', '\r', '\t'], " ")
+}
+
+/// Width for tables: `--width`, else the terminal's, or None when stdout is not a TTY (TSV)
+fn terminal_width() -> Option<usize> {
+    if let Some(width) = crate::ui::context().width {
+        return Some(width);
+    }
+    if std::io::stdout().is_terminal() || crate::pager::is_capturing() {
+        Some(
+            crossterm::terminal::size()
+                .map(|(w, _)| w as usize)
+                .unwrap_or(FALLBACK_TABLE_WIDTH),
+        )
+    } else {
+        None
+    }
+}
+
+/// Render serialized items as a table using `default_columns` unless `--columns`
+/// (or `--fields`) was given.
+pub fn print_table<T: Serialize>(items: &[T], default_columns: &[&str]) -> Result<()> {
+    let values = items
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(fields) = selected_fields() {
+        return print_projected(&project(&Value::Array(values), fields)?, OutputFormat::Table);
+    }
+    let columns: Vec<String> = match selected_columns() {
+        Some(cols) => cols.to_vec(),
+        None => default_columns.iter().map(|c| c.to_string()).collect(),
+    };
+    print!("{}", render_table(&values, &columns, terminal_width()));
+    Ok(())
+}
+
+/// Fallback for commands without bespoke table columns: list-like values become a
+/// table of the first item's scalar fields, anything else is printed as YAML.
+fn print_value_as_table(value: &Value) -> Result<()> {
+    let items = match value {
+        Value::Array(items) => Some(items),
+        Value::Object(map) => map.get("items").and_then(|v| v.as_array()),
+        _ => None,
+    };
+    match items {
+        Some(items) => {
+            let defaults: Vec<String> = items
+                .first()
+                .and_then(|v| v.as_object())
+                .map(|obj| {
+                    obj.iter()
+                        .filter(|(_, v)| !v.is_object() && !v.is_array())
+                        .map(|(k, _)| k.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let defaults: Vec<&str> = defaults.iter().map(|s| s.as_str()).collect();
+            print_table(items, &defaults)
+        }
+        None => {
+            println!("{}", to_yaml(value)?);
+            Ok(())
+        }
+    }
+}
+
+/// Quote a CSV field per RFC 4180 when it contains separators, quotes, or line breaks
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => csv_escape(s),
+        Some(other) => csv_escape(&other.to_string()),
+    }
+}
+
+/// Render a value as CSV.
+///
+/// Lists (or `{"items": [...]}` responses) become one row per item with every field of
+/// the first item as a column (or the `--columns` selection); other values become
+/// `field,value` rows. Nested values are embedded as JSON.
+pub fn to_csv(value: &Value) -> String {
+    let items = match value {
+        Value::Array(items) => Some(items),
+        Value::Object(map) => map.get("items").and_then(|v| v.as_array()),
+        _ => None,
+    };
+    let mut out = String::new();
+    match items {
+        Some(items) => {
+            let columns: Vec<(String, Vec<String>)> = match selected_columns() {
+                Some(cols) => cols
+                    .iter()
+                    .map(|c| {
+                        let (_, keys) = resolve_column(c);
+                        (c.clone(), keys)
+                    })
+                    .collect(),
+                None => items
+                    .first()
+                    .and_then(|v| v.as_object())
+                    .map(|obj| obj.keys().map(|k| (k.clone(), vec![k.clone()])).collect())
+                    .unwrap_or_default(),
+            };
+            let header: Vec<String> = columns.iter().map(|(c, _)| csv_escape(c)).collect();
+            out.push_str(&header.join(","));
+            out.push('\n');
+            for item in items {
+                let row: Vec<String> = columns
+                    .iter()
+                    .map(|(_, keys)| csv_cell(keys.iter().find_map(|k| item.get(k))))
+                    .collect();
+                out.push_str(&row.join(","));
+                out.push('\n');
+            }
+        }
+        None => {
+            out.push_str("field,value\n");
+            if let Value::Object(map) = value {
+                for (key, v) in map {
+                    out.push_str(&format!("{},{}\n", csv_escape(key), csv_cell(Some(v))));
+                }
+            } else {
+                out.push_str(&format!("value,{}\n", csv_cell(Some(value))));
+            }
+        }
+    }
+    out
+}
+
+/// Render rows as an aligned table, or as tab-separated values when `width` is None.
+pub fn render_table(items: &[Value], columns: &[String], width: Option<usize>) -> String {
+    let resolved: Vec<(String, Vec<String>)> = columns.iter().map(|c| resolve_column(c)).collect();
+    let headers: Vec<String> = resolved.iter().map(|(h, _)| h.clone()).collect();
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| resolved.iter().map(|(_, keys)| cell_text(item, keys)).collect())
+        .collect();
+    layout_table(&headers, &rows, width)
+}
+
+/// Align `rows` under `headers` within `width`, or join them with tabs when `width` is None
+fn layout_table(headers: &[String], rows: &[Vec<String>], width: Option<usize>) -> String {
+    let mut out = String::new();
+    let Some(max_width) = width else {
+        out.push_str(&headers.join("\t"));
+        out.push('\n');
+        for row in rows {
+            out.push_str(&row.join("\t"));
+            out.push('\n');
+        }
+        return out;
+    };
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.width()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.width());
+        }
+    }
+
+    // Shrink the widest column until the table fits (two spaces between columns)
+    let separators = 2 * widths.len().saturating_sub(1);
+    while widths.iter().sum::<usize>() + separators > max_width {
+        let (idx, &widest) = match widths.iter().enumerate().max_by_key(|(_, w)| **w) {
+            Some(w) => w,
+            None => break,
+        };
+        if widest <= MIN_COLUMN_WIDTH {
+            break;
+        }
+        widths[idx] -= 1;
+    }
+
+    let render_row = |cells: &[String]| -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, w)| pad_to_width(&truncate_to_width(cell, *w), *w))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    out.push_str(&render_row(headers));
+    for row in rows {
+        out.push_str(&render_row(row));
+    }
+    out
+}
+
+/// Truncate to a display width, ending with an ellipsis when text was cut.
+/// Uses display width so CJK and emoji don't overflow their column.
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let w = ch.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        result.push(ch);
+        used += w;
+    }
+    result.push('…');
+    result
+}
+
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +796,52 @@ mod tests {
         assert_eq!(OutputFormat::from_str("Json"), Some(OutputFormat::Json));
     }
 
+    #[test]
+    fn test_json_pretty_is_plain_unless_highlighting() {
+        let value = serde_json::json!({
+            "id": "m1",
+            "score": 0.5,
+            "tags": [],
+            "meta": {"pinned": true, "source": null},
+            "chunks": [{"text": "quote \" and \\ slash"}]
+        });
+        let plain = to_json_pretty(&value, false);
+        assert_eq!(plain, serde_json::to_string_pretty(&value).unwrap());
+        assert!(!plain.contains('\x1b'));
+        // With the test run's stdout piped, highlighting stays off
+        if !std::io::stdout().is_terminal() {
+            assert_eq!(to_json_pretty(&value, json_highlighting()), plain);
+        }
+
+        let colored = to_json_pretty(&value, true);
+        assert!(colored.contains("\x1b[1;34m\"score\"\x1b[0m: \x1b[33m0.5\x1b[0m"));
+        assert!(colored.contains("\x1b[35mtrue\x1b[0m"));
+        assert!(colored.contains("\x1b[2mnull\x1b[0m"));
+        let stripped = colored
+            .split('\x1b')
+            .enumerate()
+            .map(|(i, part)| if i == 0 { part } else { part.split_once('m').unwrap().1 })
+            .collect::<String>();
+        assert_eq!(stripped, plain);
+    }
+
+    #[test]
+    fn test_ndjson_writes_one_object_per_line_and_needs_a_list() {
+        let mut out = Vec::new();
+        write_ndjson_line(&mut out, &serde_json::json!({"id": "m1", "tags": ["a", "b"]})).unwrap();
+        write_ndjson_line(&mut out, &serde_json::json!({"id": "m2", "text": "two\nlines"}))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"id\":\"m1\",\"tags\":[\"a\",\"b\"]}\n{\"id\":\"m2\",\"text\":\"two\\nlines\"}\n"
+        );
+        assert_eq!(OutputFormat::from_str("jsonl"), Some(OutputFormat::Ndjson));
+
+        let bank = serde_json::json!({"bank_id": "b", "name": "n"});
+        let err = print_output(&bank, OutputFormat::Ndjson).unwrap_err();
+        assert!(err.to_string().contains("use --output json"));
+    }
+
     #[test]
     fn test_output_format_from_str_yaml() {
         assert_eq!(OutputFormat::from_str("yaml"), Some(OutputFormat::Yaml));
@@ -80,10 +857,21 @@ mod tests {
         assert_eq!(OutputFormat::from_str("text"), Some(OutputFormat::Pretty));
     }
 
+    #[test]
+    fn test_output_format_from_str_table() {
+        assert_eq!(OutputFormat::from_str("table"), Some(OutputFormat::Table));
+        assert_eq!(OutputFormat::from_str("TABLE"), Some(OutputFormat::Table));
+    }
+
+    #[test]
+    fn test_output_format_from_str_csv() {
+        assert_eq!(OutputFormat::from_str("csv"), Some(OutputFormat::Csv));
+        assert_eq!(OutputFormat::from_str("CSV"), Some(OutputFormat::Csv));
+    }
+
     #[test]
     fn test_output_format_from_str_invalid() {
         assert_eq!(OutputFormat::from_str("xml"), None);
-        assert_eq!(OutputFormat::from_str("csv"), None);
         assert_eq!(OutputFormat::from_str(""), None);
     }
 
@@ -162,4 +950,193 @@ mod tests {
         assert!(json.contains("\\\\"));
         assert!(json.contains("\\n"));
     }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let items = vec![
+            serde_json::json!({"fact_type": "world", "text": "short"}),
+            serde_json::json!({"fact_type": "opinion", "text": "a bit longer"}),
+        ];
+        let columns = vec!["type".to_string(), "text".to_string()];
+        let table = render_table(&items, &columns, Some(80));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "TYPE     TEXT");
+        assert_eq!(lines[1], "world    short");
+        assert_eq!(lines[2], "opinion  a bit longer");
+    }
+
+    #[test]
+    fn test_render_table_wide_chars_keep_alignment() {
+        let items = vec![
+            serde_json::json!({"name": "東京", "mention_count": 3}),
+            serde_json::json!({"name": "Paris", "mention_count": 12}),
+        ];
+        let columns = vec!["name".to_string(), "mentions".to_string()];
+        let table = render_table(&items, &columns, Some(80));
+        let lines: Vec<&str> = table.lines().collect();
+        // "東京" is 4 columns wide, so MENTIONS starts at the same display column
+        assert_eq!(lines[1], "東京   3");
+        assert_eq!(lines[2], "Paris  12");
+    }
+
+    #[test]
+    fn test_render_table_truncates_to_width() {
+        let items = vec![serde_json::json!({"fact_type": "world", "text": "x".repeat(200)})];
+        let columns = vec!["type".to_string(), "text".to_string()];
+        let table = render_table(&items, &columns, Some(40));
+        for line in table.lines() {
+            assert!(line.width() <= 40, "line too wide: {}", line);
+        }
+        assert!(table.contains('…'));
+    }
+
+    #[test]
+    fn test_render_table_tsv_when_not_tty() {
+        let items = vec![serde_json::json!({"bank_id": "b1", "name": "One\tTab"})];
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let table = render_table(&items, &columns, None);
+        assert_eq!(table, "ID\tNAME\nb1\tOne Tab\n");
+    }
+
+    #[test]
+    fn test_truncate_to_width_cjk() {
+        assert_eq!(truncate_to_width("日本語テキスト", 7), "日本語…");
+        assert_eq!(truncate_to_width("abc", 3), "abc");
+    }
+
+    #[test]
+    fn test_to_csv_list_quotes_fields() {
+        let value = serde_json::json!({
+            "items": [
+                {"name": "Smith, J", "count": 2},
+                {"name": "say \"hi\"", "count": null},
+            ],
+            "total": 2,
+        });
+        assert_eq!(
+            to_csv(&value),
+            // serde_json maps are key-sorted, so columns come out alphabetically
+            "count,name\n2,\"Smith, J\"\n,\"say \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_object() {
+        let value = serde_json::json!({"status": "healthy"});
+        assert_eq!(to_csv(&value), "field,value\nstatus,healthy\n");
+    }
+
+    #[test]
+    fn test_project_keeps_field_order_and_dotted_paths() {
+        let value = serde_json::json!({
+            "total": 2,
+            "items": [
+                {"id": "m1", "text": "hello", "metadata": {"source": "slack"}, "tags": ["a"]},
+                {"id": "m2", "text": "bye"}
+            ]
+        });
+        let fields = vec!["text".to_string(), "metadata.source".to_string(), "id".to_string()];
+        let projection = project(&value, &fields).unwrap();
+        assert!(projection.list);
+        assert_eq!(
+            projection.rows,
+            vec![
+                vec![Value::from("hello"), Value::from("slack"), Value::from("m1")],
+                vec![Value::from("bye"), Value::Null, Value::from("m2")],
+            ]
+        );
+        let row = OrderedRow {
+            fields: &projection.fields,
+            values: &projection.rows[0],
+        };
+        assert_eq!(
+            serde_json::to_string(&row).unwrap(),
+            r#"{"text":"hello","metadata.source":"slack","id":"m1"}"#
+        );
+        assert_eq!(lookup_path(&value, "items.0.tags.0"), Some(&serde_json::json!("a")));
+    }
+
+    #[test]
+    fn test_project_unknown_field_lists_available() {
+        let value = serde_json::json!({
+            "bank_id": "b",
+            "operations": [{"id": "op", "status": "failed"}]
+        });
+        let err = project(&value, &["id".to_string(), "nope".to_string()]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("nope"));
+        assert!(message.contains("Available fields: id, status"));
+    }
+
+    #[test]
+    fn test_markdown_matches_the_golden_files() {
+        let recall = serde_json::json!({
+            "results": [
+                {
+                    "id": "m1",
+                    "text": "Alice works at Google on the AI team",
+                    "type": "world",
+                    "occurred_start": "2024-01-15T10:30:00Z",
+                    "chunk_id": "c1"
+                },
+                {
+                    "id": "m2",
+                    "text": "Bob prefers code reviews in the morning",
+                    "type": "opinion",
+                    "mentioned_at": "2024-02-03T08:00:00Z"
+                }
+            ],
+            "chunks": {
+                "c1": {
+                    "id": "c1",
+                    "text": "Alice: I joined the AI team at Google last week.\n\
+                             Ask her about `gemini`.",
+                    "chunk_index": 0,
+                    "truncated": false
+                }
+            }
+        });
+        let golden = include_str!("../tests/golden/recall.md");
+        assert_eq!(to_markdown(&recall).unwrap(), golden);
+
+        let reflect = serde_json::json!({
+            "text": "Alice works at Google, on the AI team.\n",
+            "based_on": {
+                "memories": [
+                    {
+                        "id": "m1",
+                        "text": "Alice joined the AI team\nat Google",
+                        "type": "world",
+                        "occurred_start": "2024-01-15T10:30:00Z"
+                    },
+                    {"text": "no id"},
+                    {"id": "m2", "text": "Alice mentioned Google", "type": "experience"}
+                ],
+                "mental_models": [],
+                "directives": []
+            }
+        });
+        assert_eq!(to_markdown(&reflect).unwrap(), include_str!("../tests/golden/reflect.md"));
+
+        // Citations the command looked up take the place of the facts as returned
+        let cited = serde_json::json!({
+            "text": "Alice works at Google.",
+            "citations": [
+                {"id": "m1", "type": "world", "date": "2024-01-15T10:30:00Z", "text": "Alice",
+                 "available": true},
+                {"id": "m9", "type": null, "date": null, "text": null, "available": false}
+            ]
+        });
+        assert_eq!(
+            to_markdown(&cited).unwrap(),
+            "Alice works at Google.\n\nSources: [^1] [^2]\n\n\
+             [^1]: **world** · 2024-01-15 · Alice (`m1`)\n[^2]: `m9` (unavailable)\n"
+        );
+
+        // Other responses fall back to fenced JSON
+        let memory = serde_json::json!({"id": "m1", "text": "hi"});
+        let fenced_json = format!("```json\n{}\n```\n", to_json(&memory).unwrap());
+        assert_eq!(to_markdown(&memory).unwrap(), fenced_json);
+        assert_eq!(fenced("a ``` b", "text"), "````text\na ``` b\n````\n");
+    }
 }
@@ -0,0 +1,207 @@
+//! Page long pretty output through `$PAGER`.
+//!
+//! Commands with potentially long listings wrap their pretty printing in
+//! [`page`]. The output is captured, and when it is taller than the terminal it
+//! is handed to the pager (`less -R` unless `$PAGER` or the `pager` config
+//! setting says otherwise); shorter output is printed as usual. Nothing is
+//! paged when stdout is not a terminal, with `--no-pager`, or with
+//! `pager = false` in the config.
+
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+const DEFAULT_PAGER: &str = "less -R";
+
+/// The pager command, or `None` when paging is disabled
+static PAGER: OnceLock<Option<String>> = OnceLock::new();
+
+/// Set while output is being captured for the pager
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+/// Whether stdout is currently captured for the pager; output should still be
+/// laid out for the terminal
+pub fn is_capturing() -> bool {
+    CAPTURING.load(Ordering::Relaxed)
+}
+
+/// Resolve the pager from `--no-pager`, the `pager` config setting and `$PAGER`
+/// (called once from main). Config values `false`, `off` and `never` disable
+/// paging; `true`, `on` and `auto` keep the default; anything else is the command.
+pub fn init(no_pager: bool, config_setting: Option<&str>) {
+    let env_pager = std::env::var("PAGER").ok();
+    let _ = PAGER.set(resolve(no_pager, config_setting, env_pager.as_deref()));
+}
+
+fn resolve(
+    no_pager: bool,
+    config_setting: Option<&str>,
+    env_pager: Option<&str>,
+) -> Option<String> {
+    if no_pager {
+        return None;
+    }
+    let configured = match config_setting.map(str::trim) {
+        Some("false" | "off" | "never" | "no") => return None,
+        Some("true" | "on" | "auto" | "yes" | "") | None => None,
+        Some(command) => Some(command),
+    };
+    let command = configured
+        .or(env_pager.map(str::trim).filter(|p| !p.is_empty()))
+        .unwrap_or(DEFAULT_PAGER);
+    Some(command.to_string())
+}
+
+/// Run `print`, paging what it writes to stdout when that is taller than the terminal
+pub fn page<F: FnOnce() -> Result<()>>(print: F) -> Result<()> {
+    let Some(command) = PAGER.get().cloned().flatten() else {
+        return print();
+    };
+    if !io::stdout().is_terminal() {
+        return print();
+    }
+    let Ok((width, height)) = crossterm::terminal::size() else {
+        return print();
+    };
+
+    // The pager shows our colors with -R; stdout stops being a terminal while captured
+    if crate::ui::context().color {
+        colored::control::set_override(true);
+    }
+    CAPTURING.store(true, Ordering::Relaxed);
+    let captured = capture::run(print);
+    CAPTURING.store(false, Ordering::Relaxed);
+    let (captured, result) = captured?;
+
+    if rendered_rows(&String::from_utf8_lossy(&captured), width as usize) < height as usize
+        || !run_pager(&command, &captured)
+    {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&captured)?;
+        stdout.flush()?;
+    }
+    result
+}
+
+/// Spawn the pager and feed it `output`; false if it could not be started
+fn run_pager(command: &str, output: &[u8]) -> bool {
+    let mut parts = command.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+    let Ok(mut child) = Command::new(program).args(parts).stdin(Stdio::piped()).spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // A broken pipe just means the user quit the pager early
+        let _ = stdin.write_all(output);
+    }
+    let _ = child.wait();
+    true
+}
+
+/// Terminal rows `text` takes up at `width` columns, ignoring ANSI escapes
+fn rendered_rows(text: &str, width: usize) -> usize {
+    let width = width.max(1);
+    text.lines()
+        .map(|line| {
+            let visible = unicode_width::UnicodeWidthStr::width(strip_ansi(line).as_str());
+            visible.div_ceil(width).max(1)
+        })
+        .sum()
+}
+
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequence: ESC [ parameters final-byte
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(unix)]
+mod capture {
+    use anyhow::Result;
+    use std::fs::File;
+    use std::io::{self, Read, Write};
+    use std::os::fd::FromRawFd;
+
+    /// Run `print` with stdout redirected into a pipe; returns what it wrote
+    pub fn run<F: FnOnce() -> Result<()>>(print: F) -> Result<(Vec<u8>, Result<()>)> {
+        let mut fds = [0; 2];
+        io::stdout().flush()?;
+        // SAFETY: plain POSIX calls on descriptors owned by this function
+        let saved = unsafe {
+            if libc::pipe(fds.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error().into());
+            }
+            let saved = libc::dup(libc::STDOUT_FILENO);
+            libc::dup2(fds[1], libc::STDOUT_FILENO);
+            libc::close(fds[1]);
+            saved
+        };
+
+        // Drain the pipe concurrently so large output cannot fill it and block
+        let read_fd = fds[0];
+        let reader = std::thread::spawn(move || {
+            // SAFETY: the read end is only used by this thread, which closes it
+            let mut pipe = unsafe { File::from_raw_fd(read_fd) };
+            let mut captured = Vec::new();
+            let _ = pipe.read_to_end(&mut captured);
+            captured
+        });
+
+        let result = print();
+        let _ = io::stdout().flush();
+        // SAFETY: restoring stdout closes the last write end, ending the reader
+        unsafe {
+            libc::dup2(saved, libc::STDOUT_FILENO);
+            libc::close(saved);
+        }
+        let captured = reader.join().unwrap_or_default();
+        Ok((captured, result))
+    }
+}
+
+#[cfg(not(unix))]
+mod capture {
+    use anyhow::Result;
+
+    /// Output cannot be captured here; print directly and report nothing to page
+    pub fn run<F: FnOnce() -> Result<()>>(print: F) -> Result<(Vec<u8>, Result<()>)> {
+        Ok((Vec::new(), print()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_pager_precedence() {
+        assert_eq!(resolve(true, Some("most"), Some("more")), None);
+        assert_eq!(resolve(false, Some("off"), Some("more")), None);
+        assert_eq!(resolve(false, Some("most"), Some("more")).as_deref(), Some("most"));
+        assert_eq!(resolve(false, Some("auto"), Some("more")).as_deref(), Some("more"));
+        assert_eq!(resolve(false, None, Some(" ")).as_deref(), Some(DEFAULT_PAGER));
+    }
+
+    #[test]
+    fn test_rendered_rows_ignores_colors_and_wraps() {
+        let colored = "\x1b[38;2;0;116;217mabcd\x1b[0m";
+        assert_eq!(rendered_rows(colored, 4), 1);
+        assert_eq!(rendered_rows("abcdefghi\n\nxy", 4), 3 + 1 + 1);
+    }
+}
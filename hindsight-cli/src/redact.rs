@@ -0,0 +1,249 @@
+//! Replace entity names and custom patterns with stable placeholders.
+//!
+//! Used by `bank export --redact` so archives can leave the environment without
+//! PII. Every distinct value gets one placeholder (`PERSON_1`, `EMAIL_2`, ...) for
+//! the whole export, and the placeholder → value table can be written out so an
+//! authorized party can reverse the redaction.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Placeholder prefix for `--redact-regex` patterns without an explicit label
+const DEFAULT_LABEL: &str = "REDACTED";
+
+/// Placeholder prefix for entities whose metadata carries no type
+const DEFAULT_ENTITY_LABEL: &str = "ENTITY";
+
+/// One entity to redact: its canonical name plus aliases, all mapped to one placeholder
+#[derive(Debug, Clone)]
+pub struct EntityTerm {
+    pub canonical_name: String,
+    pub aliases: Vec<String>,
+    /// Entity type from metadata (`person`, `organization`, ...), if the server recorded one
+    pub kind: Option<String>,
+}
+
+struct Pattern {
+    label: String,
+    regex: Regex,
+}
+
+pub struct Redactor {
+    patterns: Vec<Pattern>,
+    entities: Option<Regex>,
+    /// Lowercased entity name or alias → placeholder
+    entity_placeholders: HashMap<String, String>,
+    /// Matched value → placeholder, for `--redact-regex` matches
+    values: HashMap<String, String>,
+    counters: HashMap<String, usize>,
+    /// Placeholder → original value, written with `--mapping-out`
+    mapping: BTreeMap<String, String>,
+}
+
+/// Short placeholder prefix for an entity type (`organization` → `ORG`)
+fn entity_label(kind: Option<&str>) -> String {
+    match kind.map(|k| k.trim().to_lowercase()).as_deref() {
+        Some("person" | "people" | "per") => "PERSON".to_string(),
+        Some("organization" | "organisation" | "org" | "company") => "ORG".to_string(),
+        Some("location" | "place" | "loc" | "gpe") => "LOCATION".to_string(),
+        Some(other) if !other.is_empty() => other
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect(),
+        _ => DEFAULT_ENTITY_LABEL.to_string(),
+    }
+}
+
+fn is_label(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Parse a `--redact-regex` value: `LABEL=PATTERN` or a bare pattern
+fn parse_pattern(spec: &str) -> Result<Pattern> {
+    let (label, pattern) = match spec.split_once('=') {
+        Some((label, pattern)) if is_label(label) => (label.to_string(), pattern),
+        _ => (DEFAULT_LABEL.to_string(), spec),
+    };
+    let regex = Regex::new(pattern).with_context(|| format!("Invalid --redact-regex '{}'", spec))?;
+    Ok(Pattern { label, regex })
+}
+
+/// Case-insensitive alternation of all names, longest first so "Acme Corp" wins over "Acme".
+/// Word boundaries are only required where the name itself starts or ends with a word character.
+fn entity_regex(names: &[&str]) -> Result<Option<Regex>> {
+    let mut names: Vec<&str> = names.iter().copied().filter(|n| !n.trim().is_empty()).collect();
+    if names.is_empty() {
+        return Ok(None);
+    }
+    names.sort_by_key(|n| std::cmp::Reverse(n.chars().count()));
+    names.dedup();
+    let alternatives: Vec<String> = names
+        .iter()
+        .map(|name| {
+            let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+            format!(
+                "{}{}{}",
+                if word(name.chars().next()) { r"\b" } else { "" },
+                regex::escape(name),
+                if word(name.chars().last()) { r"\b" } else { "" }
+            )
+        })
+        .collect();
+    Ok(Some(Regex::new(&format!("(?i)(?:{})", alternatives.join("|")))?))
+}
+
+impl Redactor {
+    pub fn new(entities: &[EntityTerm], patterns: &[String]) -> Result<Self> {
+        let mut redactor = Self {
+            patterns: patterns.iter().map(|p| parse_pattern(p)).collect::<Result<_>>()?,
+            entities: None,
+            entity_placeholders: HashMap::new(),
+            values: HashMap::new(),
+            counters: HashMap::new(),
+            mapping: BTreeMap::new(),
+        };
+
+        // Number entities in name order so placeholders don't depend on API ordering
+        let mut sorted: Vec<&EntityTerm> = entities.iter().collect();
+        sorted.sort_by_key(|e| e.canonical_name.to_lowercase());
+        let mut names = Vec::new();
+        for entity in sorted {
+            let key = entity.canonical_name.to_lowercase();
+            if key.trim().is_empty() || redactor.entity_placeholders.contains_key(&key) {
+                continue;
+            }
+            let placeholder = redactor.next_placeholder(&entity_label(entity.kind.as_deref()));
+            redactor.mapping.insert(placeholder.clone(), entity.canonical_name.clone());
+            for name in std::iter::once(&entity.canonical_name).chain(&entity.aliases) {
+                redactor
+                    .entity_placeholders
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| placeholder.clone());
+                names.push(name.as_str());
+            }
+        }
+        redactor.entities = entity_regex(&names)?;
+        Ok(redactor)
+    }
+
+    fn next_placeholder(&mut self, label: &str) -> String {
+        let counter = self.counters.entry(label.to_string()).or_insert(0);
+        *counter += 1;
+        format!("{}_{}", label, counter)
+    }
+
+    /// Redact one text. Custom patterns run first so an email such as
+    /// `alice@acme.com` is replaced whole before entity names are matched.
+    pub fn redact(&mut self, text: &str) -> String {
+        let mut text = text.to_string();
+        // Cloned (cheap, reference-counted) so the replacer can update counters
+        let patterns: Vec<(Regex, String)> =
+            self.patterns.iter().map(|p| (p.regex.clone(), p.label.clone())).collect();
+        for (regex, label) in patterns {
+            text = regex
+                .replace_all(&text, |caps: &regex::Captures| {
+                    let value = caps[0].to_string();
+                    if let Some(placeholder) = self.values.get(&value) {
+                        return placeholder.clone();
+                    }
+                    let placeholder = self.next_placeholder(&label);
+                    self.values.insert(value.clone(), placeholder.clone());
+                    self.mapping.insert(placeholder.clone(), value);
+                    placeholder
+                })
+                .into_owned();
+        }
+        if let Some(regex) = &self.entities {
+            text = regex
+                .replace_all(&text, |caps: &regex::Captures| {
+                    self.entity_placeholders
+                        .get(&caps[0].to_lowercase())
+                        .cloned()
+                        .unwrap_or_else(|| caps[0].to_string())
+                })
+                .into_owned();
+        }
+        text
+    }
+
+    /// Redact the fields `keys` of a JSON object in place (strings and lists of strings)
+    pub fn redact_fields(&mut self, object: &mut Map<String, Value>, keys: &[&str]) {
+        for key in keys {
+            match object.get_mut(*key) {
+                Some(Value::String(text)) => *text = self.redact(text),
+                Some(Value::Array(items)) => {
+                    for item in items {
+                        if let Value::String(text) = item {
+                            *text = self.redact(text);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Placeholder → original value for every replacement made so far
+    pub fn mapping(&self) -> &BTreeMap<String, String> {
+        &self.mapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(name: &str, aliases: &[&str], kind: Option<&str>) -> EntityTerm {
+        EntityTerm {
+            canonical_name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            kind: kind.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_entities_and_aliases_share_stable_placeholders() {
+        let entities = vec![
+            entity("Bob Jones", &["Bobby"], Some("person")),
+            entity("Acme Corp", &["Acme"], Some("organization")),
+            entity("Alice", &[], Some("person")),
+        ];
+        let mut redactor = Redactor::new(&entities, &[]).unwrap();
+        assert_eq!(
+            redactor.redact("Alice met bobby at Acme Corp; Bob Jones left Acme."),
+            "PERSON_1 met PERSON_2 at ORG_1; PERSON_2 left ORG_1."
+        );
+        // No partial-word matches
+        assert_eq!(redactor.redact("Alicent"), "Alicent");
+        assert_eq!(redactor.mapping().get("PERSON_2").map(String::as_str), Some("Bob Jones"));
+    }
+
+    #[test]
+    fn test_regex_patterns_run_before_entities() {
+        let entities = vec![entity("alice", &[], None)];
+        let patterns = vec![
+            r"EMAIL=[\w.+-]+@[\w-]+\.[\w.]+".to_string(),
+            r"\d{3}-\d{4}".to_string(),
+        ];
+        let mut redactor = Redactor::new(&entities, &patterns).unwrap();
+        assert_eq!(
+            redactor.redact("Mail alice@example.com or call 555-1234, alice."),
+            "Mail EMAIL_1 or call REDACTED_1, ENTITY_1."
+        );
+        assert_eq!(redactor.redact("again alice@example.com"), "again EMAIL_1");
+        assert_eq!(
+            redactor.mapping().get("EMAIL_1").map(String::as_str),
+            Some("alice@example.com")
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_an_error() {
+        assert!(Redactor::new(&[], &["(unclosed".to_string()]).is_err());
+    }
+}
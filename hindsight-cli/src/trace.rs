@@ -0,0 +1,341 @@
+//! Typed view of the recall trace (`memory recall --trace`).
+//!
+//! The API returns the trace as a free-form object. [`TraceReport`] picks out
+//! the parts worth reading at a glance: per-stage timings, how many candidates
+//! survived each step, and the budget settings in effect. Keys it doesn't know
+//! are kept in `other` so fields added by newer servers still show up.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::api::RecallResponse;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TraceReport {
+    pub total_ms: Option<f64>,
+    pub stages: Vec<TraceStage>,
+    /// Candidates left after each retrieval, merge and filter step, in order
+    pub candidates: Vec<CandidateCount>,
+    pub search: SearchCounts,
+    pub budget: BudgetSettings,
+    /// Trace keys this version doesn't understand, as sent
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraceStage {
+    pub name: String,
+    pub duration_ms: f64,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CandidateCount {
+    pub stage: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SearchCounts {
+    pub nodes_visited: Option<i64>,
+    pub nodes_pruned: Option<i64>,
+    pub entry_points: Option<i64>,
+    pub temporal_links_followed: Option<i64>,
+    pub semantic_links_followed: Option<i64>,
+    pub entity_links_followed: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BudgetSettings {
+    pub budget: Option<i64>,
+    pub budget_used: Option<i64>,
+    pub budget_remaining: Option<i64>,
+    pub max_tokens: Option<i64>,
+    pub tags: Vec<String>,
+    pub tags_match: Option<String>,
+    pub temporal_start: Option<String>,
+    pub temporal_end: Option<String>,
+}
+
+/// Query keys that only echo the request back; not worth listing as unknown
+const QUERY_ECHO_KEYS: &[&str] = &["query_text", "query_embedding", "timestamp"];
+
+// Wire format of the trace. Every field is optional so older and newer servers
+// both parse; unknown keys are collected by the flattened maps.
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawTrace {
+    query: Option<RawQuery>,
+    retrieval_results: Vec<RawRetrieval>,
+    rrf_merged: Vec<Value>,
+    reranked: Vec<Value>,
+    entry_points: Vec<Value>,
+    visits: Vec<Value>,
+    pruned: Vec<Value>,
+    summary: Option<RawSummary>,
+    final_results: Vec<Value>,
+    /// Milliseconds, sent by servers predating `summary`
+    total_time: Option<f64>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawQuery {
+    budget: Option<i64>,
+    max_tokens: Option<i64>,
+    tags: Option<Vec<String>>,
+    tags_match: Option<String>,
+    temporal_constraint: Option<RawTemporal>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawTemporal {
+    start: Option<String>,
+    end: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawRetrieval {
+    method_name: String,
+    fact_type: Option<String>,
+    results: Vec<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawSummary {
+    total_nodes_visited: Option<i64>,
+    total_nodes_pruned: Option<i64>,
+    entry_points_found: Option<i64>,
+    budget_used: Option<i64>,
+    budget_remaining: Option<i64>,
+    total_duration_seconds: Option<f64>,
+    results_returned: Option<i64>,
+    temporal_links_followed: Option<i64>,
+    semantic_links_followed: Option<i64>,
+    entity_links_followed: Option<i64>,
+    phase_metrics: Vec<RawPhase>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawPhase {
+    phase_name: String,
+    duration_seconds: f64,
+    details: Map<String, Value>,
+}
+
+impl TraceReport {
+    /// Build the report from the response's trace map. A trace whose known
+    /// fields have unexpected shapes is reported entirely under `other`.
+    pub fn from_trace(trace: &Map<String, Value>) -> Self {
+        match serde_json::from_value::<RawTrace>(Value::Object(trace.clone())) {
+            Ok(raw) => Self::from_raw(raw),
+            Err(_) => Self {
+                other: trace.clone(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn from_raw(raw: RawTrace) -> Self {
+        let query = raw.query.unwrap_or_default();
+        let summary = raw.summary.unwrap_or_default();
+
+        let stages: Vec<TraceStage> = summary
+            .phase_metrics
+            .into_iter()
+            .map(|phase| TraceStage {
+                name: phase.phase_name,
+                duration_ms: phase.duration_seconds * 1000.0,
+                details: phase.details,
+            })
+            .collect();
+
+        let mut candidates: Vec<CandidateCount> = raw
+            .retrieval_results
+            .iter()
+            .map(|retrieval| CandidateCount {
+                stage: match &retrieval.fact_type {
+                    Some(fact_type) => format!("{} ({})", retrieval.method_name, fact_type),
+                    None => retrieval.method_name.clone(),
+                },
+                count: retrieval.results.len() as i64,
+            })
+            .collect();
+        let mut push = |stage: &str, count: Option<i64>| {
+            if let Some(count) = count {
+                candidates.push(CandidateCount {
+                    stage: stage.to_string(),
+                    count,
+                });
+            }
+        };
+        let non_empty = |items: &[Value]| (!items.is_empty()).then_some(items.len() as i64);
+        push("rrf merge", non_empty(&raw.rrf_merged));
+        push("reranked", non_empty(&raw.reranked));
+        push(
+            "token filter",
+            stages
+                .iter()
+                .find(|s| s.name == "token_filtering")
+                .and_then(|s| s.details.get("results_selected"))
+                .and_then(Value::as_i64),
+        );
+        push(
+            "returned",
+            summary
+                .results_returned
+                .or_else(|| non_empty(&raw.final_results)),
+        );
+
+        let mut other = raw.other;
+        for (key, value) in query.other {
+            if QUERY_ECHO_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            other.insert(format!("query.{}", key), value);
+        }
+        for (key, value) in summary.other {
+            other.insert(format!("summary.{}", key), value);
+        }
+        let temporal = query.temporal_constraint.unwrap_or_default();
+
+        Self {
+            total_ms: summary
+                .total_duration_seconds
+                .map(|s| s * 1000.0)
+                .or(raw.total_time),
+            stages,
+            candidates,
+            search: SearchCounts {
+                nodes_visited: summary
+                    .total_nodes_visited
+                    .or_else(|| non_empty(&raw.visits)),
+                nodes_pruned: summary.total_nodes_pruned.or_else(|| non_empty(&raw.pruned)),
+                entry_points: summary
+                    .entry_points_found
+                    .or_else(|| non_empty(&raw.entry_points)),
+                temporal_links_followed: summary.temporal_links_followed,
+                semantic_links_followed: summary.semantic_links_followed,
+                entity_links_followed: summary.entity_links_followed,
+            },
+            budget: BudgetSettings {
+                budget: query.budget,
+                budget_used: summary.budget_used,
+                budget_remaining: summary.budget_remaining,
+                max_tokens: query.max_tokens,
+                tags: query.tags.unwrap_or_default(),
+                tags_match: query.tags_match,
+                temporal_start: temporal.start,
+                temporal_end: temporal.end,
+            },
+            other,
+        }
+    }
+}
+
+/// The recall response as JSON, with the raw trace replaced by its [`TraceReport`]
+pub fn response_with_report(response: &RecallResponse) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(response)?;
+    if let (Some(trace), Some(object)) = (&response.trace, value.as_object_mut()) {
+        object.insert(
+            "trace".to_string(),
+            serde_json::to_value(TraceReport::from_trace(trace))?,
+        );
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trace(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_report_from_server_trace() {
+        let report = TraceReport::from_trace(&trace(json!({
+            "query": {
+                "query_text": "alice",
+                "query_embedding": [0.1, 0.2],
+                "budget": 100,
+                "max_tokens": 4096,
+                "tags": ["team"],
+                "tags_match": "any",
+                "rerank_model": "new"
+            },
+            "retrieval_results": [
+                {"method_name": "semantic", "fact_type": "world", "results": [{}, {}, {}]},
+                {"method_name": "bm25", "results": [{}]}
+            ],
+            "rrf_merged": [{}, {}, {}],
+            "reranked": [{}, {}, {}],
+            "summary": {
+                "total_duration_seconds": 0.25,
+                "results_returned": 2,
+                "budget_used": 3,
+                "budget_remaining": 97,
+                "phase_metrics": [
+                    {"phase_name": "parallel_retrieval", "duration_seconds": 0.2,
+                     "details": {"semantic_count": 3}},
+                    {"phase_name": "token_filtering", "duration_seconds": 0.001,
+                     "details": {"results_selected": 2}}
+                ],
+                "cache_hits": 4
+            },
+            "final_results": [{}, {}],
+            "experimental": {"x": 1}
+        })));
+
+        assert_eq!(report.total_ms, Some(250.0));
+        assert_eq!(report.stages[0].name, "parallel_retrieval");
+        assert_eq!(report.stages[0].duration_ms, 200.0);
+        let counts: Vec<(&str, i64)> =
+            report.candidates.iter().map(|c| (c.stage.as_str(), c.count)).collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("semantic (world)", 3),
+                ("bm25", 1),
+                ("rrf merge", 3),
+                ("reranked", 3),
+                ("token filter", 2),
+                ("returned", 2)
+            ]
+        );
+        assert_eq!(report.budget.budget, Some(100));
+        assert_eq!(report.budget.tags, vec!["team".to_string()]);
+        let unknown: Vec<&str> = report.other.keys().map(String::as_str).collect();
+        assert_eq!(unknown, vec!["experimental", "query.rerank_model", "summary.cache_hits"]);
+    }
+
+    #[test]
+    fn test_legacy_and_malformed_traces() {
+        let legacy = TraceReport::from_trace(&trace(json!({
+            "total_time": 12.5,
+            "activation_count": 7
+        })));
+        assert_eq!(legacy.total_ms, Some(12.5));
+        assert_eq!(legacy.other.get("activation_count"), Some(&json!(7)));
+
+        let malformed = trace(json!({"summary": "not an object", "total_time": 1.0}));
+        let report = TraceReport::from_trace(&malformed);
+        assert_eq!(report.total_ms, None);
+        assert_eq!(report.other, malformed);
+    }
+}
@@ -1,8 +1,15 @@
-use crate::api::{BankProfileResponse, RecallResult, RecallResponse, ReflectResponse};
+use crate::api::{
+    BankProfileResponse, MultiRecallResponse, RecallResult, RecallResponse, ReflectResponse,
+};
+use crate::commands::memory::parse_timestamp;
+use crate::trace::TraceReport;
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use colored::*;
 use hindsight_client::types::ChunkData;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::io::{self, Write};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// The logo as ANSI-colored text, generated by test-logo.py
 const LOGO: &str = include_str!("logo.ansi");
@@ -11,6 +18,184 @@ const LOGO: &str = include_str!("logo.ansi");
 const GRADIENT_START: (u8, u8, u8) = (0, 116, 217);  // #0074d9
 const GRADIENT_END: (u8, u8, u8) = (0, 146, 150);    // #009296
 
+/// Terminal behaviour shared by every command, set once from the global flags
+#[derive(Debug, Clone, Copy)]
+pub struct UiContext {
+    /// Auto-accept every confirmation prompt (`--yes`)
+    pub assume_yes: bool,
+    /// Suppress spinners, info and success lines (`--quiet`)
+    pub quiet: bool,
+    /// Emit ANSI colors and emoji (off with `--no-color` or `NO_COLOR`)
+    pub color: bool,
+    /// Draw spinners and progress bars: stderr is a terminal, and neither `--quiet`
+    /// nor a CI run (`CI`) asked for plain output
+    pub progress: bool,
+    /// Stdout carries data in a machine format, so status lines go to stderr
+    pub structured: bool,
+    /// How timestamps are shown in pretty output (`--dates`)
+    pub dates: DateStyle,
+    /// Columns to lay pretty output out for instead of the detected width (`--width`)
+    pub width: Option<usize>,
+}
+
+impl UiContext {
+    pub fn new(assume_yes: bool, quiet: bool, no_color: bool) -> Self {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self {
+            assume_yes,
+            quiet,
+            color: !(no_color || no_color_env),
+            progress: draws_progress(
+                quiet,
+                io::stderr().is_terminal(),
+                std::env::var("CI").ok().as_deref(),
+            ),
+            structured: false,
+            dates: DateStyle::default(),
+            width: None,
+        }
+    }
+
+    pub fn with_dates(mut self, dates: DateStyle) -> Self {
+        self.dates = dates;
+        self
+    }
+
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width.filter(|w| *w > 0);
+        self
+    }
+
+    pub fn with_output(mut self, format: crate::output::OutputFormat) -> Self {
+        self.structured = format != crate::output::OutputFormat::Pretty;
+        self
+    }
+}
+
+/// Whether spinners and progress bars are drawn: only on a terminal stderr, and not
+/// under `--quiet` or in CI (`CI` set to anything but empty, `0` or `false`)
+fn draws_progress(quiet: bool, stderr_is_terminal: bool, ci: Option<&str>) -> bool {
+    let in_ci = ci.is_some_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"));
+    !quiet && stderr_is_terminal && !in_ci
+}
+
+/// Timestamp rendering for pretty output (`--dates`, or `dates` in the config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DateStyle {
+    /// "3h ago" within the last week, local date and time before that
+    #[default]
+    Relative,
+    /// Local date and time
+    Absolute,
+    /// The timestamp exactly as the server sent it
+    Iso,
+}
+
+impl Default for UiContext {
+    fn default() -> Self {
+        Self::new(false, false, false)
+    }
+}
+
+static UI_CONTEXT: OnceLock<UiContext> = OnceLock::new();
+
+/// Install the UI context for this process; later calls are ignored
+pub fn init(ctx: UiContext) {
+    if !ctx.color {
+        colored::control::set_override(false);
+    }
+    let _ = UI_CONTEXT.set(ctx);
+}
+
+pub fn context() -> UiContext {
+    *UI_CONTEXT.get_or_init(UiContext::default)
+}
+
+/// Width assumed for pretty output when stdout is not a terminal
+const FALLBACK_WIDTH: usize = 80;
+
+/// Columns available to pretty output: `--width`, else the terminal's width, else 80
+pub fn terminal_width() -> usize {
+    if let Some(width) = context().width {
+        return width;
+    }
+    if io::stdout().is_terminal() || crate::pager::is_capturing() {
+        if let Ok((cols, _)) = crossterm::terminal::size() {
+            if cols > 0 {
+                return cols as usize;
+            }
+        }
+    }
+    FALLBACK_WIDTH
+}
+
+/// Word-wrap `text` to `width` display columns, prefixing every line with `indent`
+/// spaces so continuation lines hang under the first. Existing line breaks are kept,
+/// a line's own leading indentation is kept on its first row, and words wider than a
+/// line are broken wherever they run out of room.
+pub fn wrap_text(text: &str, width: usize, indent: usize) -> Vec<String> {
+    let prefix = " ".repeat(indent);
+    // Never squeeze the text itself below a handful of columns
+    let available = width.saturating_sub(indent).max(10);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let body = paragraph.trim_start();
+        let mut line = paragraph[..paragraph.len() - body.len()].replace('\t', "    ");
+        let mut used = line.width();
+        let mut has_word = false;
+        for word in body.split_whitespace() {
+            let word_width = word.width();
+            let gap = usize::from(has_word);
+            if used + gap + word_width <= available {
+                if has_word {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                used += gap + word_width;
+                has_word = true;
+                continue;
+            }
+            // Start a fresh row (dropping the indentation if even the first word won't fit)
+            if has_word {
+                lines.push(format!("{}{}", prefix, line));
+            }
+            line.clear();
+            used = 0;
+            has_word = true;
+            if word_width <= available {
+                line.push_str(word);
+                used = word_width;
+                continue;
+            }
+            for ch in word.chars() {
+                let ch_width = ch.width().unwrap_or(0);
+                if used + ch_width > available && used > 0 {
+                    lines.push(format!("{}{}", prefix, line));
+                    line.clear();
+                    used = 0;
+                }
+                line.push(ch);
+                used += ch_width;
+            }
+        }
+        lines.push(format!("{}{}", prefix, line).trim_end().to_string());
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Pick an emoji/symbol, or its plain fallback when color is disabled
+pub fn icon<'a>(fancy: &'a str, plain: &'a str) -> &'a str {
+    if context().color {
+        fancy
+    } else {
+        plain
+    }
+}
+
 /// Interpolate between two RGB colors
 fn interpolate_color(start: (u8, u8, u8), end: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
     (
@@ -22,6 +207,9 @@ fn interpolate_color(start: (u8, u8, u8), end: (u8, u8, u8), t: f32) -> (u8, u8,
 
 /// Color text using gradient position (0.0 = start, 1.0 = end)
 pub fn gradient(text: &str, t: f32) -> String {
+    if !context().color {
+        return text.to_string();
+    }
     let (r, g, b) = interpolate_color(GRADIENT_START, GRADIENT_END, t);
     format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text)
 }
@@ -43,6 +231,9 @@ pub fn gradient_mid(text: &str) -> String {
 
 /// Apply gradient across entire text string
 pub fn gradient_text(text: &str) -> String {
+    if !context().color {
+        return text.to_string();
+    }
     let chars: Vec<char> = text.chars().collect();
     let len = chars.len();
     if len == 0 {
@@ -64,6 +255,9 @@ pub fn gradient_text(text: &str) -> String {
 
 /// Dim/gray text
 pub fn dim(text: &str) -> String {
+    if !context().color {
+        return text.to_string();
+    }
     format!("\x1b[38;2;128;128;128m{}\x1b[0m", text)
 }
 
@@ -71,6 +265,86 @@ pub fn get_logo() -> &'static str {
     LOGO
 }
 
+/// Timestamps less than this far from now are shown relative ("3h ago", "in 2d")
+const RELATIVE_DATE_DAYS: i64 = 7;
+
+const LOCAL_DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Render an API timestamp in the selected `--dates` style. Missing or
+/// unparseable values are returned unchanged.
+pub fn format_date(raw: &str) -> String {
+    format_date_at(raw, context().dates, Utc::now(), &chrono::Local)
+}
+
+fn format_date_at<Tz: TimeZone>(
+    raw: &str,
+    style: DateStyle,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    if style == DateStyle::Iso {
+        return raw.to_string();
+    }
+    let Some(time) = parse_timestamp(raw.trim()) else {
+        return raw.to_string();
+    };
+    if style == DateStyle::Relative {
+        if let Some(relative) = relative_date(time, now) {
+            return relative;
+        }
+    }
+    time.with_timezone(tz).format(LOCAL_DATE_FORMAT).to_string()
+}
+
+/// "just now", "59m ago", "1h ago", "6d ago" (or "in 3h"); `None` a week or more away.
+/// Elapsed time is measured between instants, so DST changes don't shift it.
+fn relative_date(time: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+    let elapsed = now.signed_duration_since(time);
+    let span = elapsed.abs();
+    let amount = if span >= Duration::days(RELATIVE_DATE_DAYS) {
+        return None;
+    } else if span < Duration::minutes(1) {
+        return Some("just now".to_string());
+    } else if span < Duration::hours(1) {
+        format!("{}m", span.num_minutes())
+    } else if span < Duration::days(1) {
+        format!("{}h", span.num_hours())
+    } else {
+        format!("{}d", span.num_days())
+    };
+    Some(if elapsed > Duration::zero() {
+        format!("{} ago", amount)
+    } else {
+        format!("in {}", amount)
+    })
+}
+
+/// JSON copies of `items` with the date fields `keys` rendered by [`format_date`], for
+/// pretty tables
+pub fn with_display_dates<T: serde::Serialize>(
+    items: &[T],
+    keys: &[&str],
+) -> Vec<serde_json::Map<String, serde_json::Value>> {
+    items
+        .iter()
+        .filter_map(|item| match serde_json::to_value(item) {
+            Ok(serde_json::Value::Object(item)) => Some(item),
+            _ => None,
+        })
+        .map(|mut item| {
+            for key in keys {
+                if let Some(serde_json::Value::String(raw)) = item.get_mut(*key) {
+                    *raw = format_date(raw);
+                }
+            }
+            item
+        })
+        .collect()
+}
+
 pub fn print_section_header(title: &str) {
     println!();
     println!("{}", gradient_text(&format!("━━━ {} ━━━", title)));
@@ -91,19 +365,25 @@ pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
     };
 
     println!("{}", gradient(&format!("[{}]", fact_type.to_uppercase()), type_t));
-    println!("  {}", fact.text);
+    let width = terminal_width();
+    for line in wrap_text(&fact.text, width, 2) {
+        println!("{}", line);
+    }
 
     // Show context if available
     if let Some(context) = &fact.context {
-        println!("  {} {}", dim("context:"), dim(context));
+        for line in wrap_text(&format!("context: {}", context), width, 2) {
+            println!("{}", dim(&line));
+        }
     }
 
     // Show temporal information
     if let Some(occurred_start) = &fact.occurred_start {
+        let start = format_date(occurred_start);
         if let Some(occurred_end) = &fact.occurred_end {
-            println!("  {} {} - {}", dim("date:"), dim(occurred_start), dim(occurred_end));
+            println!("  {} {} - {}", dim("date:"), dim(&start), dim(&format_date(occurred_end)));
         } else {
-            println!("  {} {}", dim("date:"), dim(occurred_start));
+            println!("  {} {}", dim("date:"), dim(&start));
         }
     }
 
@@ -112,15 +392,30 @@ pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
         println!("  {} {}", dim("document:"), dim(document_id));
     }
 
+    if let Some(metadata) = metadata_pairs(fact) {
+        for line in wrap_text(&format!("metadata: {}", metadata), width, 2) {
+            println!("{}", dim(&line));
+        }
+    }
+
     println!();
 }
 
+/// A result's metadata as `key=value` pairs sorted by key, or None when it has none
+fn metadata_pairs(fact: &RecallResult) -> Option<String> {
+    let metadata = fact.metadata.as_ref().filter(|m| !m.is_empty())?;
+    let mut pairs: Vec<_> = metadata.iter().collect();
+    pairs.sort();
+    let pairs: Vec<String> = pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    Some(pairs.join(", "))
+}
+
 pub fn print_chunk(chunk: &ChunkData) {
     println!("  {}", gradient_mid("─── Source Chunk ───"));
 
-    // Split text into lines and indent each line
-    for line in chunk.text.lines() {
-        println!("  {}", line);
+    // Wrap each line of the chunk to the terminal, indented under the header
+    for line in wrap_text(&chunk.text, terminal_width(), 2) {
+        println!("{}", line);
     }
 
     if chunk.truncated {
@@ -137,27 +432,91 @@ pub fn print_chunk(chunk: &ChunkData) {
     println!();
 }
 
-pub fn print_search_results(response: &RecallResponse, show_trace: bool, show_chunks: bool) {
+/// What `--group-by` buckets recall results by in pretty output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResultGrouping {
+    Type,
+    Document,
+}
+
+/// Recall results sharing a fact type or source document, with their 1-based rank
+#[derive(Debug, PartialEq)]
+pub struct ResultGroup<'a> {
+    pub label: String,
+    pub results: Vec<(usize, &'a RecallResult)>,
+}
+
+/// Bucket results by `grouping`. Groups come in the order of their best-ranked result
+/// and keep rank order inside, so the numbers match the ungrouped listing.
+pub fn group_results(results: &[RecallResult], grouping: ResultGrouping) -> Vec<ResultGroup<'_>> {
+    let mut groups: Vec<ResultGroup> = Vec::new();
+    for (i, result) in results.iter().enumerate() {
+        let label = match grouping {
+            ResultGrouping::Type => result.type_.as_deref().unwrap_or("unknown"),
+            ResultGrouping::Document => result.document_id.as_deref().unwrap_or("no document"),
+        };
+        match groups.iter_mut().find(|group| group.label == label) {
+            Some(group) => group.results.push((i + 1, result)),
+            None => groups.push(ResultGroup {
+                label: label.to_string(),
+                results: vec![(i + 1, result)],
+            }),
+        }
+    }
+    groups
+}
+
+/// `folded` maps a result ID to the near-duplicates `recall --dedupe` folded into it
+pub fn print_search_results(
+    response: &RecallResponse,
+    show_trace: bool,
+    show_chunks: bool,
+    group_by: Option<ResultGrouping>,
+    folded: Option<&std::collections::BTreeMap<String, Vec<String>>>,
+) {
     let results = &response.results;
     print_section_header(&format!("Search Results ({})", results.len()));
 
+    let print_result = |rank: usize, fact: &RecallResult| {
+        let similar = folded.and_then(|folded| folded.get(&fact.id)).map_or(0, Vec::len);
+        if similar > 0 {
+            let note = gradient_end(&format!("(+{} similar)", similar));
+            println!("  {} {}", dim(&format!("Result #{}", rank)), note);
+        } else {
+            println!("  {}", dim(&format!("Result #{}", rank)));
+        }
+        print_fact(fact, true);
+
+        // Show chunk if available and requested
+        if show_chunks {
+            if let Some(chunk_id) = &fact.chunk_id {
+                if let Some(chunks) = &response.chunks {
+                    if let Some(chunk) = chunks.get(chunk_id) {
+                        print_chunk(chunk);
+                    }
+                }
+            }
+        }
+    };
+
     if results.is_empty() {
         println!("  {}", dim("No results found."));
+    } else if let Some(grouping) = group_by {
+        for group in group_results(results, grouping) {
+            let label = match grouping {
+                ResultGrouping::Type => group.label.to_uppercase(),
+                ResultGrouping::Document => group.label.clone(),
+            };
+            let header = format!("─── {} ({}) ───", label, group.results.len());
+            println!("{}", gradient_text(&header));
+            println!();
+            for (rank, fact) in group.results {
+                print_result(rank, fact);
+            }
+        }
     } else {
         for (i, fact) in results.iter().enumerate() {
-            println!("  {}", dim(&format!("Result #{}", i + 1)));
-            print_fact(fact, true);
-
-            // Show chunk if available and requested
-            if show_chunks {
-                if let Some(chunk_id) = &fact.chunk_id {
-                    if let Some(chunks) = &response.chunks {
-                        if let Some(chunk) = chunks.get(chunk_id) {
-                            print_chunk(chunk);
-                        }
-                    }
-                }
-            }
+            print_result(i + 1, fact);
         }
     }
 
@@ -168,13 +527,80 @@ pub fn print_search_results(response: &RecallResponse, show_trace: bool, show_ch
     }
 }
 
-pub fn print_think_response(response: &ReflectResponse) {
+/// Pretty output of a recall across several banks, naming each result's bank
+pub fn print_multi_search_results(response: &MultiRecallResponse) {
+    print_section_header(&format!("Search Results ({})", response.results.len()));
+    if response.results.is_empty() {
+        println!("  {}", dim("No results found."));
+    }
+    for (i, merged) in response.results.iter().enumerate() {
+        println!("  {}", dim(&format!("Result #{} · {}", i + 1, merged.bank_id)));
+        print_fact(&merged.result, true);
+    }
+    for failed in &response.errors {
+        print_warning(&format!("Bank {} failed: {}", failed.bank_id, failed.error));
+    }
+}
+
+/// A memory a reflect answer was based on, numbered by its position in `based_on`
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Citation {
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub fact_type: Option<String>,
+    /// When it happened (or was mentioned or stored), as the server sent it
+    pub date: Option<String>,
+    pub text: Option<String>,
+    /// False when the memory could not be fetched, e.g. because it was deleted
+    pub available: bool,
+}
+
+impl Citation {
+    /// Build a citation from a memory object: a `based_on` fact, a fetched memory, or a
+    /// serialized citation
+    pub fn from_memory(memory: &serde_json::Value) -> Self {
+        let field = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| memory.get(*key).and_then(|v| v.as_str()))
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            id: field(&["id"]),
+            fact_type: field(&["type", "fact_type"]),
+            date: field(&["occurred_start", "mentioned_at", "created_at", "date"]),
+            text: field(&["text"]),
+            available: memory.get("available").and_then(|v| v.as_bool()).unwrap_or(true),
+        }
+    }
+
+    pub fn unavailable(id: &str) -> Self {
+        Self {
+            id: Some(id.to_string()),
+            fact_type: None,
+            date: None,
+            text: None,
+            available: false,
+        }
+    }
+
+    /// First line of the memory's text with whitespace collapsed
+    pub fn preview(&self) -> String {
+        let text = self.text.as_deref().unwrap_or_default();
+        let line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+        line.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+pub fn print_think_response(response: &ReflectResponse, citations: &[Citation]) {
     print_section_header("Reflection");
 
     println!("{}", response.text);
     println!();
 
-    if let Some(based_on) = &response.based_on {
+    if !citations.is_empty() {
+        print_citations(citations);
+    } else if let Some(based_on) = &response.based_on {
         let count = based_on.memories.len();
         if count > 0 {
             println!("{}", dim(&format!("Based on {} memory units", count)));
@@ -192,22 +618,167 @@ pub fn print_think_response(response: &ReflectResponse) {
     }
 }
 
+/// Numbered list of the memories behind a reflect answer, one line each
+fn print_citations(citations: &[Citation]) {
+    println!("{}", gradient_text(&format!("─── Sources ({}) ───", citations.len())));
+    let width = terminal_width();
+    for (i, citation) in citations.iter().enumerate() {
+        let marker = format!("[{}]", i + 1);
+        if !citation.available {
+            let id = citation.id.as_deref().unwrap_or("?");
+            println!("  {} {} {}", gradient_start(&marker), id, dim("(unavailable)"));
+            continue;
+        }
+        let mut label = citation.fact_type.as_deref().unwrap_or("memory").to_uppercase();
+        if let Some(date) = &citation.date {
+            label.push_str(&format!(" · {}", format_date(date)));
+        }
+        // Whatever the marker and label leave on the line goes to the preview
+        let used = 2 + marker.width() + 1 + label.width() + 2;
+        let preview = crate::output::truncate_to_width(
+            &citation.preview(),
+            width.saturating_sub(used).max(20),
+        );
+        println!("  {} {}  {}", gradient_start(&marker), dim(&label), preview);
+    }
+}
+
+/// Width of the longest stage bar in the trace breakdown
+const TRACE_BAR_WIDTH: usize = 30;
+
+/// Unknown trace values are shown as one line, cut at this many columns
+const TRACE_VALUE_WIDTH: usize = 100;
+
 pub fn print_trace_info(trace: &serde_json::Map<String, serde_json::Value>) {
+    print_trace_report(&TraceReport::from_trace(trace));
+}
+
+pub fn print_trace_report(report: &TraceReport) {
     print_section_header("Trace");
 
-    if let Some(time) = trace.get("total_time").and_then(|v| v.as_f64()) {
-        println!("  {} {}", dim("total time:"), gradient_start(&format!("{:.2}ms", time)));
+    if let Some(total) = report.total_ms {
+        println!("  {} {}", dim("total time:"), gradient_start(&format!("{:.2}ms", total)));
+        println!();
     }
 
-    if let Some(count) = trace.get("activation_count").and_then(|v| v.as_i64()) {
-        println!("  {} {}", dim("activation count:"), gradient_end(&count.to_string()));
+    if !report.stages.is_empty() {
+        let name_width = report.stages.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        let slowest = report
+            .stages
+            .iter()
+            .map(|s| s.duration_ms)
+            .fold(0.0_f64, f64::max);
+        println!("{}", gradient_text("─── Stages ───"));
+        for stage in &report.stages {
+            let bar_len = if slowest > 0.0 {
+                ((stage.duration_ms / slowest) * TRACE_BAR_WIDTH as f64).round() as usize
+            } else {
+                0
+            };
+            println!(
+                "  {:<width$}  {:>10}  {}",
+                stage.name,
+                format!("{:.1}ms", stage.duration_ms),
+                gradient_mid(&"█".repeat(bar_len.max(1))),
+                width = name_width
+            );
+        }
+        println!();
     }
 
-    println!();
+    if !report.candidates.is_empty() {
+        let stage_width = report.candidates.iter().map(|c| c.stage.len()).max().unwrap_or(0);
+        println!("{}", gradient_text("─── Candidates ───"));
+        for candidate in &report.candidates {
+            println!(
+                "  {:<width$}  {:>6}",
+                candidate.stage,
+                candidate.count,
+                width = stage_width
+            );
+        }
+        println!();
+    }
+
+    let search = &report.search;
+    let search_counts = [
+        ("nodes visited", search.nodes_visited),
+        ("nodes pruned", search.nodes_pruned),
+        ("entry points", search.entry_points),
+        ("temporal links", search.temporal_links_followed),
+        ("semantic links", search.semantic_links_followed),
+        ("entity links", search.entity_links_followed),
+    ];
+    if search_counts.iter().any(|(_, count)| count.is_some()) {
+        println!("{}", gradient_text("─── Search ───"));
+        for (label, count) in search_counts {
+            if let Some(count) = count {
+                println!("  {} {}", dim(&format!("{}:", label)), count);
+            }
+        }
+        println!();
+    }
+
+    let budget = &report.budget;
+    let mut knobs: Vec<(&str, String)> = Vec::new();
+    if let Some(limit) = budget.budget {
+        let usage = match (budget.budget_used, budget.budget_remaining) {
+            (Some(used), Some(remaining)) => format!(" (used {}, {} left)", used, remaining),
+            (Some(used), None) => format!(" (used {})", used),
+            _ => String::new(),
+        };
+        knobs.push(("budget", format!("{}{}", limit, usage)));
+    }
+    if let Some(max_tokens) = budget.max_tokens {
+        knobs.push(("max tokens", max_tokens.to_string()));
+    }
+    if !budget.tags.is_empty() {
+        let mode = budget.tags_match.as_deref().unwrap_or("any");
+        knobs.push(("tags", format!("{} ({})", budget.tags.join(", "), mode)));
+    }
+    if budget.temporal_start.is_some() || budget.temporal_end.is_some() {
+        let bound = |d: Option<&str>| d.map(format_date).unwrap_or_else(|| "…".to_string());
+        let start = bound(budget.temporal_start.as_deref());
+        let end = bound(budget.temporal_end.as_deref());
+        knobs.push(("time range", format!("{} → {}", start, end)));
+    }
+    if !knobs.is_empty() {
+        println!("{}", gradient_text("─── Budget ───"));
+        for (label, value) in knobs {
+            println!("  {} {}", dim(&format!("{}:", label)), value);
+        }
+        println!();
+    }
+
+    if !report.other.is_empty() {
+        println!("{}", gradient_text("─── Other Fields ───"));
+        for (key, value) in &report.other {
+            let text = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let text = crate::output::truncate_to_width(&text, TRACE_VALUE_WIDTH);
+            println!("  {} {}", dim(&format!("{}:", key)), text);
+        }
+        println!();
+    }
+}
+
+/// Print a status line: with the output on stdout in pretty mode, on stderr when stdout
+/// carries JSON, YAML or another machine format
+fn print_status(line: &str) {
+    if context().structured {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
 }
 
 pub fn print_success(message: &str) {
-    println!("{}", gradient_start(message));
+    if context().quiet {
+        return;
+    }
+    print_status(&gradient_start(message));
 }
 
 pub fn print_error(message: &str) {
@@ -215,40 +786,93 @@ pub fn print_error(message: &str) {
 }
 
 pub fn print_warning(message: &str) {
-    println!("{} {}", gradient_end("warning:"), message);
+    print_status(&format!("{} {}", gradient_end("warning:"), message));
 }
 
 pub fn print_info(message: &str) {
-    println!("{}", gradient_start(message));
+    if context().quiet {
+        return;
+    }
+    print_status(&gradient_start(message));
+}
+
+/// Message of the spinner currently drawn, so API retries can report on it
+static ACTIVE_SPINNER: std::sync::Mutex<Option<std::sync::Arc<std::sync::Mutex<String>>>> =
+    std::sync::Mutex::new(None);
+
+/// Show retry progress ("Retrying (2/3)...") on the active spinner, or on stderr
+/// when no spinner is drawn
+pub fn report_retry(message: &str) {
+    let active = ACTIVE_SPINNER.lock().ok().and_then(|active| active.clone());
+    match active {
+        Some(text) => {
+            if let Ok(mut text) = text.lock() {
+                *text = message.to_string();
+            }
+        }
+        None if !context().quiet => eprintln!("{}", dim(message)),
+        None => {}
+    }
 }
 
 /// Animated gradient spinner that shows text with moving gradient colors
 pub struct GradientSpinner {
-    message: String,
+    message: std::sync::Arc<std::sync::Mutex<String>>,
     running: std::sync::Arc<std::sync::atomic::AtomicBool>,
     handle: Option<std::thread::JoinHandle<()>>,
 }
 
 impl GradientSpinner {
+    /// A spinner that draws nothing (quiet mode, CI, or non-terminal stderr)
+    fn hidden(message: &str) -> Self {
+        Self {
+            message: std::sync::Arc::new(std::sync::Mutex::new(message.to_string())),
+            running: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            handle: None,
+        }
+    }
+
     pub fn new(message: &str) -> Self {
-        let message = message.to_string();
+        let ctx = context();
+        if !ctx.progress {
+            return Self::hidden(message);
+        }
+
+        let message = std::sync::Arc::new(std::sync::Mutex::new(message.to_string()));
         let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+        if let Ok(mut active) = ACTIVE_SPINNER.lock() {
+            *active = Some(message.clone());
+        }
 
         let msg_clone = message.clone();
         let running_clone = running.clone();
 
         let handle = std::thread::spawn(move || {
-            let chars: Vec<char> = msg_clone.chars().collect();
-            let len = chars.len();
             let num_frames = 30;
             let mut current_frame = 0usize;
+            let mut drawn_len = 0usize;
 
             while running_clone.load(std::sync::atomic::Ordering::Relaxed) {
                 current_frame = (current_frame + 1) % num_frames;
                 let offset = current_frame as f32 / num_frames as f32;
+                // Re-read each frame: the message changes while a request is retried
+                let text = msg_clone.lock().map(|m| m.clone()).unwrap_or_default();
+                let chars: Vec<char> = text.chars().collect();
+                let len = chars.len();
+                // Blank out the rest of a longer previous message
+                let padding = " ".repeat(drawn_len.saturating_sub(len));
+                drawn_len = len;
 
                 // Build the gradient string
                 let mut result = String::from("\r");
+                if !ctx.color {
+                    result.push_str(&text);
+                    result.push_str(&padding);
+                    eprint!("{}", result);
+                    let _ = io::stderr().flush();
+                    std::thread::sleep(std::time::Duration::from_millis(80));
+                    continue;
+                }
                 for (i, ch) in chars.iter().enumerate() {
                     if *ch == ' ' {
                         result.push(' ');
@@ -260,9 +884,11 @@ impl GradientSpinner {
                     }
                 }
                 result.push_str("\x1b[0m");
+                result.push_str(&padding);
 
-                print!("{}", result);
-                let _ = io::stdout().flush();
+                // On stderr, so the spinner never mixes into the command's output
+                eprint!("{}", result);
+                let _ = io::stderr().flush();
 
                 std::thread::sleep(std::time::Duration::from_millis(80));
             }
@@ -277,12 +903,20 @@ impl GradientSpinner {
 
     pub fn finish(&mut self) {
         self.running.store(false, std::sync::atomic::Ordering::Relaxed);
-        if let Some(handle) = self.handle.take() {
-            let _ = handle.join();
+        let Some(handle) = self.handle.take() else {
+            // Hidden or already finished: nothing was drawn
+            return;
+        };
+        let _ = handle.join();
+        if let Ok(mut active) = ACTIVE_SPINNER.lock() {
+            if active.as_ref().is_some_and(|m| std::sync::Arc::ptr_eq(m, &self.message)) {
+                *active = None;
+            }
         }
         // Clear the line
-        print!("\r{}\r", " ".repeat(self.message.len() + 10));
-        let _ = io::stdout().flush();
+        let len = self.message.lock().map(|m| m.chars().count()).unwrap_or(0);
+        eprint!("\r{}\r", " ".repeat(len + 10));
+        let _ = io::stderr().flush();
     }
 }
 
@@ -299,6 +933,9 @@ pub fn create_spinner(message: &str) -> GradientSpinner {
 }
 
 pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
+    if !context().progress {
+        return ProgressBar::hidden();
+    }
     let pb = ProgressBar::new(total);
     pb.set_style(
         ProgressStyle::default_bar()
@@ -310,7 +947,273 @@ pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
     pb
 }
 
+/// How often plain-text transfer progress is reported off a terminal
+const PLAIN_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Progress of a pool of workers uploading files. On a terminal this is one overall bar
+/// (files done, ETA, aggregate MB/s) with a spinner per worker naming the file it is
+/// sending; finished workers' lines are removed. Off a terminal, or under `--quiet`,
+/// a plain progress line goes to stderr every few seconds instead.
+pub struct TransferProgress {
+    label: String,
+    /// Files to send in all
+    total: u64,
+    /// None when reporting with plain lines (or not at all)
+    multi: Option<MultiProgress>,
+    overall: ProgressBar,
+    /// False when nothing should be shown, e.g. for structured output
+    visible: bool,
+    state: std::sync::Mutex<TransferState>,
+}
+
+struct TransferState {
+    files: u64,
+    bytes: u64,
+    started: std::time::Instant,
+    last_report: std::time::Instant,
+}
+
+/// A worker's line in a `TransferProgress`, held while it sends one file or batch
+pub struct TransferWorker {
+    bar: Option<ProgressBar>,
+}
+
+pub fn create_transfer_progress(total: u64, message: &str, visible: bool) -> TransferProgress {
+    let ctx = context();
+    let fancy = visible && ctx.progress;
+    let now = std::time::Instant::now();
+    let state = TransferState { files: 0, bytes: 0, started: now, last_report: now };
+    if !fancy {
+        return TransferProgress {
+            label: message.to_string(),
+            total,
+            multi: None,
+            overall: ProgressBar::hidden(),
+            visible,
+            state: std::sync::Mutex::new(state),
+        };
+    }
+
+    let multi = MultiProgress::new();
+    let overall = multi.add(ProgressBar::new(total));
+    overall.set_style(
+        ProgressStyle::default_bar()
+            .template("{prefix} [{bar:40.cyan/blue}] {pos}/{len} files, {eta} left {msg}")
+            .unwrap()
+            .progress_chars("█▓▒░ "),
+    );
+    overall.set_prefix(message.to_string());
+    TransferProgress {
+        label: message.to_string(),
+        total,
+        multi: Some(multi),
+        overall,
+        visible,
+        state: std::sync::Mutex::new(state),
+    }
+}
+
+impl TransferProgress {
+    /// Show a worker line naming what it is about to send
+    pub fn start(&self, name: &str) -> TransferWorker {
+        let bar = self.multi.as_ref().map(|multi| {
+            let bar = multi.add(ProgressBar::new_spinner());
+            bar.set_style(ProgressStyle::default_spinner().template("  {spinner} {msg}").unwrap());
+            bar.set_message(name.to_string());
+            bar.enable_steady_tick(std::time::Duration::from_millis(100));
+            bar
+        });
+        TransferWorker { bar }
+    }
+
+    /// Turn a worker's line into a byte bar with transfer rate, at `sent` of `total`.
+    /// Going back to zero, as when an upload is retried, starts the rate over.
+    pub fn bytes_sent(&self, worker: &TransferWorker, sent: u64, total: u64) {
+        let Some(bar) = &worker.bar else {
+            return;
+        };
+        if bar.length() != Some(total) {
+            bar.set_length(total);
+            bar.set_style(
+                ProgressStyle::default_bar()
+                    .template("  {msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {bytes_per_sec}")
+                    .unwrap()
+                    .progress_chars("█▓▒░ "),
+            );
+        }
+        if sent == 0 {
+            bar.reset();
+        }
+        bar.set_position(sent);
+    }
+
+    /// Record a worker's `files` as sent (`bytes` in total) and remove its line
+    pub fn complete(&self, worker: TransferWorker, files: u64, bytes: u64) {
+        if let (Some(multi), Some(bar)) = (&self.multi, worker.bar) {
+            bar.finish_and_clear();
+            multi.remove(&bar);
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.files += files;
+        state.bytes += bytes;
+        let elapsed = state.started.elapsed();
+        self.overall.inc(files);
+        self.overall.set_message(format!("({})", format_rate(state.bytes, elapsed)));
+
+        let finished = state.files >= self.total;
+        let due = state.last_report.elapsed() >= PLAIN_PROGRESS_INTERVAL;
+        if self.multi.is_none() && self.visible && (due || finished) {
+            state.last_report = std::time::Instant::now();
+            let (files, bytes) = (state.files, state.bytes);
+            eprintln!("{}", transfer_status(&self.label, files, self.total, bytes, elapsed));
+        }
+    }
+
+    /// Print a line above the progress display without tearing it
+    pub fn println(&self, message: &str) {
+        match &self.multi {
+            Some(multi) => {
+                let _ = multi.println(message);
+            }
+            None if self.visible => eprintln!("{}", message),
+            None => {}
+        }
+    }
+
+    pub fn finish(&self) {
+        self.overall.finish_and_clear();
+        if let Some(multi) = &self.multi {
+            let _ = multi.clear();
+        }
+    }
+}
+
+/// Bytes per second as MB/s
+fn format_rate(bytes: u64, elapsed: std::time::Duration) -> String {
+    let secs = elapsed.as_secs_f64().max(0.001);
+    format!("{:.1} MB/s", bytes as f64 / 1_000_000.0 / secs)
+}
+
+/// One plain-text progress report, e.g. "Ingesting: 12/40 files (30%), 4.2 MB, 1.1 MB/s, ~25s left"
+fn transfer_status(
+    label: &str,
+    files: u64,
+    total: u64,
+    bytes: u64,
+    elapsed: std::time::Duration,
+) -> String {
+    let percent = if total == 0 { 100 } else { files * 100 / total };
+    let mut status = format!(
+        "{}: {}/{} files ({}%), {:.1} MB, {}",
+        label,
+        files,
+        total,
+        percent,
+        bytes as f64 / 1_000_000.0,
+        format_rate(bytes, elapsed)
+    );
+    if files > 0 && files < total {
+        let remaining = elapsed.as_secs_f64() / files as f64 * (total - files) as f64;
+        status.push_str(&format!(", ~{}s left", remaining.round() as u64));
+    }
+    status
+}
+
+/// Case-insensitive subsequence match used to filter picker entries
+pub fn fuzzy_matches(query: &str, candidate: &str) -> bool {
+    let mut chars = candidate.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| chars.any(|c| c == q))
+}
+
+/// Minimal inline selector drawn on stderr: type to filter, ↑/↓ to move,
+/// Enter to pick, Esc to cancel. Returns the index of the chosen item.
+pub fn fuzzy_select(prompt: &str, items: &[String], default: usize) -> io::Result<Option<usize>> {
+    crossterm::terminal::enable_raw_mode()?;
+    let result = run_fuzzy_select(prompt, items, default);
+    crossterm::terminal::disable_raw_mode()?;
+    result
+}
+
+fn run_fuzzy_select(prompt: &str, items: &[String], default: usize) -> io::Result<Option<usize>> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal::{Clear, ClearType};
+    use crossterm::{cursor, queue};
+
+    const VISIBLE_ROWS: usize = 10;
+    let mut stderr = io::stderr();
+    let mut query = String::new();
+    let mut selected = default;
+
+    loop {
+        let filtered: Vec<usize> = (0..items.len())
+            .filter(|&i| fuzzy_matches(&query, &items[i]))
+            .collect();
+        selected = selected.min(filtered.len().saturating_sub(1));
+        let start = selected.saturating_sub(VISIBLE_ROWS - 1);
+
+        queue!(stderr, cursor::MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+        write!(stderr, "{} {}\r\n", gradient_start(prompt), query)?;
+        let mut lines = 1;
+        if filtered.is_empty() {
+            write!(stderr, "  {}\r\n", dim("(no matches)"))?;
+            lines += 1;
+        }
+        for (row, &index) in filtered.iter().enumerate().skip(start).take(VISIBLE_ROWS) {
+            if row == selected {
+                write!(stderr, "{} {}\r\n", gradient_start(">"), gradient_start(&items[index]))?;
+            } else {
+                write!(stderr, "  {}\r\n", items[index])?;
+            }
+            lines += 1;
+        }
+        queue!(stderr, cursor::MoveUp(lines as u16))?;
+        stderr.flush()?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let chosen = match key.code {
+            KeyCode::Up => {
+                selected = selected.saturating_sub(1);
+                continue;
+            }
+            KeyCode::Down => {
+                selected += 1;
+                continue;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                continue;
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => None,
+            KeyCode::Char(c) => {
+                query.push(c);
+                selected = 0;
+                continue;
+            }
+            KeyCode::Enter if !filtered.is_empty() => Some(filtered[selected]),
+            KeyCode::Esc => None,
+            _ => continue,
+        };
+
+        queue!(stderr, cursor::MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+        stderr.flush()?;
+        return Ok(chosen);
+    }
+}
+
 pub fn prompt_confirmation(message: &str) -> io::Result<bool> {
+    if context().assume_yes {
+        return Ok(true);
+    }
     print!("{} [y/N]: ", gradient_start(message));
     io::stdout().flush()?;
 
@@ -330,7 +1233,7 @@ pub fn print_disposition(profile: &BankProfileResponse) {
     // Print mission if available
     if !profile.mission.is_empty() {
         println!("{}", gradient_mid("Mission:"));
-        for line in profile.mission.lines() {
+        for line in wrap_text(&profile.mission, terminal_width(), 0) {
             println!("{}", line);
         }
         println!();
@@ -347,10 +1250,11 @@ pub fn print_disposition(profile: &BankProfileResponse) {
         ("Empathy", profile.disposition.empathy.get() as i64, 1.0, "1=detached, 5=empathetic"),
     ];
 
+    // The bar takes whatever the name, brackets and score leave (at most 40 columns)
+    let bar_length = trait_bar_length(terminal_width());
     for (name, value, t, desc) in &traits {
-        // Scale 1-5 to bar visualization (each point = 8 chars, total 40)
-        let bar_length = 40;
-        let filled = ((*value - 1) * 10) as usize; // 1->0, 2->10, 3->20, 4->30, 5->40
+        // Scale 1-5 to the bar: 1 is empty, 5 is full
+        let filled = (bar_length * (*value).clamp(1, 5) as usize - bar_length) / 4;
         let empty = bar_length - filled;
 
         let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
@@ -365,3 +1269,234 @@ pub fn print_disposition(profile: &BankProfileResponse) {
 
     println!();
 }
+
+/// Length of a disposition trait bar on a terminal `width` columns wide
+fn trait_bar_length(width: usize) -> usize {
+    // "  Skepticism   [" and "] 5/5" take 20 columns around the bar
+    width.saturating_sub(20).clamp(8, 40)
+}
+
+/// Lines of context kept around each change in a text diff
+const DIFF_CONTEXT: usize = 3;
+
+/// A line-by-line unified diff from `old` to `new`: `@@` hunk headers, then changed
+/// lines marked `-`/`+` among unchanged ones marked with a space. Empty when equal.
+pub fn text_diff(old: &str, new: &str) -> Vec<String> {
+    let diff = similar::TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+    for hunk in diff.unified_diff().context_radius(DIFF_CONTEXT).iter_hunks() {
+        lines.push(hunk.header().to_string());
+        for change in hunk.iter_changes() {
+            let marker = match change.tag() {
+                similar::ChangeTag::Delete => '-',
+                similar::ChangeTag::Insert => '+',
+                similar::ChangeTag::Equal => ' ',
+            };
+            let text = change.value();
+            lines.push(format!("{}{}", marker, text.strip_suffix('\n').unwrap_or(text)));
+        }
+    }
+    lines
+}
+
+/// Print `text_diff(old, new)` with removed lines red and added lines green; without
+/// color only the `-`/`+` markers set them apart
+pub fn print_text_diff(old: &str, new: &str) {
+    let lines = text_diff(old, new);
+    if lines.is_empty() {
+        println!("{}", dim("(no changes)"));
+        return;
+    }
+    for line in lines {
+        match line.chars().next() {
+            Some('-') => println!("{}", line.red()),
+            Some('+') => println!("{}", line.green()),
+            Some('@') => println!("{}", line.cyan()),
+            _ => println!("{}", line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_drawn_only_on_a_terminal_outside_ci() {
+        assert!(draws_progress(false, true, None));
+        assert!(!draws_progress(true, true, None));
+        assert!(!draws_progress(false, false, None));
+        assert!(!draws_progress(false, true, Some("true")));
+        assert!(!draws_progress(false, true, Some("1")));
+        assert!(draws_progress(false, true, Some("false")));
+        assert!(draws_progress(false, true, Some("")));
+    }
+
+    #[test]
+    fn test_wrap_text_hangs_continuation_lines_at_several_widths() {
+        let text = "the quick brown fox jumps over the lazy dog";
+        assert_eq!(
+            wrap_text(text, 20, 2),
+            vec!["  the quick brown", "  fox jumps over the", "  lazy dog"]
+        );
+        assert_eq!(wrap_text(text, 80, 2), vec![format!("  {}", text)]);
+        // Narrow terminals still get ten columns of text after the indent
+        assert_eq!(wrap_text("abcdefghijklmno", 4, 2), vec!["  abcdefghij", "  klmno"]);
+        // Paragraph breaks and a line's own indentation survive
+        assert_eq!(
+            wrap_text("first line\n\n    indented line", 40, 2),
+            vec!["  first line", "", "      indented line"]
+        );
+        assert_eq!(wrap_text("", 40, 2), vec![""]);
+
+        // Wide characters count two columns each, so every row stays within the width
+        let cjk = wrap_text("記憶は重要です 😀😀😀😀😀😀", 14, 2);
+        assert_eq!(cjk, vec!["  記憶は重要で", "  す", "  😀😀😀😀😀😀"]);
+        assert!(cjk.iter().all(|line| line.width() <= 14));
+    }
+
+    #[test]
+    fn test_text_diff_marks_changes_with_context() {
+        let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\n";
+        let new = "one\ntwo\nthree\nfour\nfive\nsix\nseven\nEIGHT\nnine\nten";
+        assert_eq!(
+            text_diff(old, new),
+            vec![
+                "@@ -5,5 +5,6 @@",
+                " five",
+                " six",
+                " seven",
+                "-eight",
+                "+EIGHT",
+                " nine",
+                "+ten",
+            ]
+        );
+        assert!(text_diff(old, old).is_empty());
+        assert_eq!(text_diff("", "new mission"), vec!["@@ -0,0 +1 @@", "+new mission"]);
+    }
+
+    #[test]
+    fn test_transfer_status_reports_rate_and_time_left() {
+        let elapsed = std::time::Duration::from_secs(10);
+        assert_eq!(
+            transfer_status("Ingesting", 10, 40, 20_000_000, elapsed),
+            "Ingesting: 10/40 files (25%), 20.0 MB, 2.0 MB/s, ~30s left"
+        );
+        assert_eq!(
+            transfer_status("Ingesting", 40, 40, 20_000_000, elapsed),
+            "Ingesting: 40/40 files (100%), 20.0 MB, 2.0 MB/s"
+        );
+    }
+
+    #[test]
+    fn test_trait_bars_scale_with_the_terminal() {
+        assert_eq!(trait_bar_length(120), 40);
+        assert_eq!(trait_bar_length(50), 30);
+        assert_eq!(trait_bar_length(20), 8);
+    }
+
+    #[test]
+    fn test_group_results_keeps_ranks_and_first_seen_order() {
+        let results: Vec<RecallResult> = serde_json::from_value(serde_json::json!([
+            {"id": "1", "text": "a", "type": "opinion", "document_id": "notes"},
+            {"id": "2", "text": "b", "type": "world"},
+            {"id": "3", "text": "c", "type": "opinion", "document_id": "notes"},
+            {"id": "4", "text": "d", "document_id": "chat"}
+        ]))
+        .unwrap();
+        let summary = |groups: Vec<ResultGroup>| -> Vec<(String, Vec<usize>)> {
+            groups
+                .into_iter()
+                .map(|group| (group.label, group.results.iter().map(|(rank, _)| *rank).collect()))
+                .collect()
+        };
+
+        assert_eq!(
+            summary(group_results(&results, ResultGrouping::Type)),
+            vec![
+                ("opinion".to_string(), vec![1, 3]),
+                ("world".to_string(), vec![2]),
+                ("unknown".to_string(), vec![4]),
+            ]
+        );
+        assert_eq!(
+            summary(group_results(&results, ResultGrouping::Document)),
+            vec![
+                ("notes".to_string(), vec![1, 3]),
+                ("no document".to_string(), vec![2]),
+                ("chat".to_string(), vec![4]),
+            ]
+        );
+        assert!(group_results(&[], ResultGrouping::Type).is_empty());
+    }
+
+    #[test]
+    fn test_metadata_pairs_sorted_by_key() {
+        let results: Vec<RecallResult> = serde_json::from_value(serde_json::json!([
+            {"id": "1", "text": "a", "metadata": {"reviewed": "true", "project": "atlas"}},
+            {"id": "2", "text": "b", "metadata": {}},
+            {"id": "3", "text": "c"}
+        ]))
+        .unwrap();
+        assert_eq!(
+            metadata_pairs(&results[0]).as_deref(),
+            Some("project=atlas, reviewed=true")
+        );
+        assert_eq!(metadata_pairs(&results[1]), None);
+        assert_eq!(metadata_pairs(&results[2]), None);
+    }
+
+    #[test]
+    fn test_fuzzy_matches() {
+        assert!(fuzzy_matches("", "anything"));
+        assert!(fuzzy_matches("prd", "Production - main"));
+        assert!(fuzzy_matches("MAIN", "production - main"));
+        assert!(!fuzzy_matches("dp", "production"));
+    }
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        parse_timestamp(text).unwrap()
+    }
+
+    #[test]
+    fn test_relative_date_boundaries() {
+        let now = utc("2024-06-10T12:00:00Z");
+        let relative = |text: &str| format_date_at(text, DateStyle::Relative, now, &Utc);
+        assert_eq!(relative("2024-06-10T11:59:30Z"), "just now");
+        assert_eq!(relative("2024-06-10T11:01:00Z"), "59m ago");
+        assert_eq!(relative("2024-06-10T11:00:01Z"), "59m ago");
+        assert_eq!(relative("2024-06-10T11:00:00Z"), "1h ago");
+        assert_eq!(relative("2024-06-09T12:00:01Z"), "23h ago");
+        assert_eq!(relative("2024-06-09T12:00:00Z"), "1d ago");
+        assert_eq!(relative("2024-06-03T12:00:01Z"), "6d ago");
+        assert_eq!(relative("2024-06-03T12:00:00Z"), "2024-06-03 12:00");
+        assert_eq!(relative("2024-06-10T15:00:00Z"), "in 3h");
+    }
+
+    #[test]
+    fn test_dates_across_dst_transition() {
+        let berlin = chrono_tz::Europe::Berlin;
+        // Clocks jump from 02:00 CET to 03:00 CEST at 01:00 UTC on 2024-03-31
+        let now = utc("2024-03-31T02:00:00Z");
+        let before = "2024-03-31T00:30:00Z";
+        // 1h30 elapsed, although the wall clock moved from 01:30 to 04:00
+        assert_eq!(format_date_at(before, DateStyle::Relative, now, &berlin), "1h ago");
+        assert_eq!(format_date_at(before, DateStyle::Absolute, now, &berlin), "2024-03-31 01:30");
+        assert_eq!(
+            format_date_at("2024-03-31T01:30:00Z", DateStyle::Absolute, now, &berlin),
+            "2024-03-31 03:30"
+        );
+    }
+
+    #[test]
+    fn test_unparseable_dates_fall_back_to_raw() {
+        let now = utc("2024-06-10T12:00:00Z");
+        assert_eq!(format_date_at("unknown", DateStyle::Relative, now, &Utc), "unknown");
+        assert_eq!(format_date_at("", DateStyle::Absolute, now, &Utc), "");
+        assert_eq!(
+            format_date_at("2024-06-10T11:00:00Z", DateStyle::Iso, now, &Utc),
+            "2024-06-10T11:00:00Z"
+        );
+    }
+}
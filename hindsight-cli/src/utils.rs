@@ -1,7 +1,13 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use crate::api::ApiClient;
 use crate::config::Config;
 use crate::output::OutputFormat;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
 
 /// Get API client from config
 pub fn get_client(config: &Config) -> Result<ApiClient> {
@@ -13,3 +19,162 @@ pub fn get_client(config: &Config) -> Result<ApiClient> {
 pub fn get_output_format(cli_format: Option<OutputFormat>, _config: &Config) -> OutputFormat {
     cli_format.unwrap_or(OutputFormat::Pretty)
 }
+
+/// Parse a user-supplied date argument into a UTC timestamp.
+///
+/// Accepts RFC 3339 timestamps, plain `YYYY-MM-DD` dates (midnight UTC), and
+/// relative offsets into the past such as `30m`, `12h`, `7d` or `2w`.
+pub fn parse_datetime_arg(input: &str) -> Result<DateTime<Utc>> {
+    parse_datetime_arg_at(input, Utc::now())
+}
+
+fn parse_datetime_arg_at(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    if let Some(unit) = input.chars().last() {
+        if let Ok(amount) = input[..input.len() - unit.len_utf8()].parse::<i64>() {
+            let offset = match unit {
+                'm' => Some(Duration::try_minutes(amount)),
+                'h' => Some(Duration::try_hours(amount)),
+                'd' => Some(Duration::try_days(amount)),
+                'w' => Some(Duration::try_weeks(amount)),
+                _ => None,
+            };
+            if let Some(offset) = offset {
+                if amount <= 0 {
+                    anyhow::bail!(
+                        "Invalid date '{}': a relative offset must be positive",
+                        input
+                    );
+                }
+                return offset
+                    .and_then(|offset| now.checked_sub_signed(offset))
+                    .with_context(|| format!("Invalid date '{}': offset is too large", input));
+            }
+        }
+    }
+    anyhow::bail!(
+        "Invalid date '{}'. Use YYYY-MM-DD, an RFC 3339 timestamp, or a relative offset like 7d, 12h, 30m",
+        input
+    )
+}
+
+/// SHA-256 (hex) of whitespace- and case-normalized text, used for duplicate detection
+pub fn text_hash(text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Rough token count (about four characters per token), good enough for budgeting context
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Resolve a query given inline, via `--query-file`, or on stdin (`-` for either).
+///
+/// Reading from a file keeps long prompts intact instead of relying on shell quoting.
+pub fn resolve_query(query: Option<String>, query_file: Option<&Path>) -> Result<String> {
+    let text = match (query, query_file) {
+        (Some(_), Some(_)) => anyhow::bail!("Pass the query either inline or with --query-file, not both"),
+        (Some(q), None) if q != "-" => q,
+        (None, Some(path)) if path.as_os_str() != "-" => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read query file: {}", path.display()))?,
+        (None, None) => anyhow::bail!("Missing query: pass it as an argument, with --query-file, or '-' for stdin"),
+        _ => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("Failed to read query from stdin")?;
+            buf
+        }
+    };
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        anyhow::bail!("Query is empty");
+    }
+    Ok(text)
+}
+
+/// Flag set once Ctrl+C is pressed, so polling loops can clean up before exiting
+pub fn install_interrupt_flag() -> Arc<AtomicBool> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let flag = stop.clone();
+    std::thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread().enable_all().build() else {
+            return;
+        };
+        if runtime.block_on(tokio::signal::ctrl_c()).is_ok() {
+            flag.store(true, Ordering::SeqCst);
+        }
+    });
+    stop
+}
+
+/// Sleep for `interval`, waking early if the stop flag is set
+pub fn sleep_unless_stopped(interval: StdDuration, stop: &AtomicBool) {
+    let deadline = Instant::now() + interval;
+    while !stop.load(Ordering::SeqCst) && Instant::now() < deadline {
+        std::thread::sleep(StdDuration::from_millis(100));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-15T12:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_datetime_arg_date() {
+        let dt = parse_datetime_arg_at("2024-06-01", fixed_now()).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-06-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_datetime_arg_rfc3339() {
+        let dt = parse_datetime_arg_at("2024-06-01T10:30:00+02:00", fixed_now()).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-06-01T08:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_datetime_arg_relative() {
+        let now = fixed_now();
+        assert_eq!(parse_datetime_arg_at("7d", now).unwrap(), now - Duration::days(7));
+        assert_eq!(parse_datetime_arg_at("12h", now).unwrap(), now - Duration::hours(12));
+        assert_eq!(parse_datetime_arg_at("30m", now).unwrap(), now - Duration::minutes(30));
+        assert_eq!(parse_datetime_arg_at("2w", now).unwrap(), now - Duration::weeks(2));
+    }
+
+    #[test]
+    fn test_parse_datetime_arg_invalid() {
+        assert!(parse_datetime_arg_at("yesterday-ish", fixed_now()).is_err());
+        assert!(parse_datetime_arg_at("7y", fixed_now()).is_err());
+        assert!(parse_datetime_arg_at("", fixed_now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_datetime_arg_rejects_out_of_range_offsets() {
+        let error = parse_datetime_arg_at("99999999999999d", fixed_now()).unwrap_err();
+        assert!(error.to_string().contains("too large"));
+        assert!(parse_datetime_arg_at("99999999999999w", fixed_now()).is_err());
+        assert!(parse_datetime_arg_at("9223372036854775807m", fixed_now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_datetime_arg_rejects_non_positive_offsets() {
+        let error = parse_datetime_arg_at("-3d", fixed_now()).unwrap_err();
+        assert!(error.to_string().contains("must be positive"));
+        assert!(parse_datetime_arg_at("0h", fixed_now()).is_err());
+    }
+}
@@ -133,6 +133,32 @@ fn test_bank_list_json_output() {
     }
 }
 
+#[test]
+fn test_machine_output_keeps_stdout_pure() {
+    skip_if_no_server!();
+
+    let api_url =
+        env::var("HINDSIGHT_API_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    for ci in ["true", ""] {
+        let output = Command::new(hindsight_binary())
+            .env("HINDSIGHT_API_URL", &api_url)
+            .env("CI", ci)
+            .args(["bank", "list", "-o", "json"])
+            .output()
+            .expect("Failed to execute hindsight command");
+        assert!(output.status.success());
+
+        // Nothing but the JSON document on stdout: no spinner frames, colors or status lines
+        let stdout = String::from_utf8(output.stdout).expect("stdout is not UTF-8");
+        serde_json::from_str::<serde_json::Value>(&stdout)
+            .unwrap_or_else(|e| panic!("stdout is not pure JSON ({}): {:?}", e, stdout));
+        assert!(!stdout.contains('\x1b'), "escape sequence on stdout");
+        assert!(!stdout.contains('\r'), "carriage return on stdout");
+        // stderr is not a terminal here, so no spinner is drawn there either
+        assert!(!output.stderr.contains(&b'\r'), "spinner frames on stderr");
+    }
+}
+
 #[test]
 fn test_bank_create_and_delete() {
     skip_if_no_server!();
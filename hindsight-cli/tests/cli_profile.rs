@@ -56,6 +56,7 @@ fn run_with_home(home: &std::path::Path, args: &[&str]) -> Output {
         // Unset anything that would bypass the profile/config-file resolution
         // we're trying to exercise here.
         .env_remove("HINDSIGHT_API_URL")
+        .env_remove("HINDSIGHT_URL")
         .env_remove("HINDSIGHT_API_KEY")
         .env_remove("HINDSIGHT_PROFILE")
         .args(args)
@@ -121,6 +122,30 @@ fn profile_create_writes_toml_and_json_output() {
     }
 }
 
+#[test]
+fn json_pretty_output_has_no_color_when_piped() {
+    let home = unique_tempdir("json-pretty");
+    let out = run_with_home(
+        &home,
+        &[
+            "--output",
+            "json-pretty",
+            "profile",
+            "create",
+            "prod",
+            "--api-url",
+            "https://api.example.com",
+        ],
+    );
+    assert_success(&out);
+
+    let text = stdout(&out);
+    assert!(!text.contains('\x1b'), "piped output contains ANSI escapes: {:?}", text);
+    let payload: serde_json::Value = serde_json::from_str(&text).expect("expected JSON output");
+    assert_eq!(payload["name"], "prod");
+    assert!(text.contains("\n  \"name\": \"prod\""));
+}
+
 #[test]
 fn profile_create_rejects_invalid_api_url() {
     let home = unique_tempdir("invalid-url");
@@ -309,6 +334,7 @@ fn hindsight_profile_env_var_is_honored() {
     let out = Command::new(hindsight_binary())
         .env("HOME", &home)
         .env_remove("HINDSIGHT_API_URL")
+        .env_remove("HINDSIGHT_URL")
         .env_remove("HINDSIGHT_API_KEY")
         .env("HINDSIGHT_PROFILE", "staging")
         .args(["version"])
@@ -34,6 +34,7 @@ fn test_ui_command_without_config() {
     let output = Command::new("cargo")
         .args(["run", "--", "ui"])
         .env_remove("HINDSIGHT_API_URL")
+        .env_remove("HINDSIGHT_URL")
         .env_remove("HINDSIGHT_API_KEY")
         .env("HOME", &temp_dir)
         .output()
@@ -43,6 +43,115 @@ fn filter_multipart_endpoints(spec: &mut serde_json::Value) {
     }
 }
 
+/// Schema of an object with `required` fields; anything else goes to a flattened map.
+///
+/// `additionalProperties: {}` (rather than `true`) is what makes typify collect unknown
+/// fields into an `extra` map, so raw output keeps fields newer servers add.
+fn open_object(
+    title: &str,
+    description: &str,
+    required: &[&str],
+    properties: serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "title": title,
+        "description": description,
+        "required": required,
+        "properties": properties,
+        "additionalProperties": {},
+    })
+}
+
+/// Typed item of memory listings. Only `id` and `text` are required so older servers,
+/// which send fewer fields, still deserialize.
+fn memory_list_item() -> serde_json::Value {
+    let optional_string = serde_json::json!({"type": "string", "nullable": true});
+    let mut properties = serde_json::Map::new();
+    properties.insert("id".to_string(), serde_json::json!({"type": "string"}));
+    properties.insert("text".to_string(), serde_json::json!({"type": "string"}));
+    for name in [
+        "fact_type",
+        "context",
+        "date",
+        "created_at",
+        "mentioned_at",
+        "occurred_start",
+        "occurred_end",
+        "document_id",
+        "chunk_id",
+    ] {
+        properties.insert(name.to_string(), optional_string.clone());
+    }
+    properties.insert(
+        "metadata".to_string(),
+        serde_json::json!({"type": "object", "additionalProperties": true, "nullable": true}),
+    );
+    open_object(
+        "MemoryListItem",
+        "One memory as returned by the list memory units endpoint.",
+        &["id", "text"],
+        properties,
+    )
+}
+
+/// Typed item of document listings. Only `id` is required; the size is `text_length`, a
+/// count of characters, and the content type lives in the metadata of the retain call.
+fn document_list_item() -> serde_json::Value {
+    let optional_string = serde_json::json!({"type": "string", "nullable": true});
+    let optional_integer = serde_json::json!({"type": "integer", "nullable": true});
+    let optional_object =
+        serde_json::json!({"type": "object", "additionalProperties": true, "nullable": true});
+    let mut properties = serde_json::Map::new();
+    properties.insert("id".to_string(), serde_json::json!({"type": "string"}));
+    for name in ["bank_id", "content_hash", "created_at", "updated_at"] {
+        properties.insert(name.to_string(), optional_string.clone());
+    }
+    for name in ["text_length", "memory_unit_count"] {
+        properties.insert(name.to_string(), optional_integer.clone());
+    }
+    for name in ["document_metadata", "retain_params"] {
+        properties.insert(name.to_string(), optional_object.clone());
+    }
+    properties.insert(
+        "tags".to_string(),
+        serde_json::json!({"type": "array", "items": {"type": "string"}, "nullable": true}),
+    );
+    open_object(
+        "DocumentListItem",
+        "One document as returned by the list documents endpoint.",
+        &["id"],
+        properties,
+    )
+}
+
+/// Point the `items` of the list response schema `response` at the named schema `item`.
+///
+/// The list endpoints declare their items as free-form objects, which would leave callers
+/// digging fields out of a JSON map.
+fn type_list_items(spec: &mut serde_json::Value, response: &str, item: serde_json::Value) {
+    let Some(name) = item.get("title").and_then(|v| v.as_str()).map(str::to_string) else {
+        return;
+    };
+    let Some(schemas) = spec.pointer_mut("/components/schemas").and_then(|v| v.as_object_mut())
+    else {
+        return;
+    };
+    let Some(items) = schemas
+        .get_mut(response)
+        .and_then(|response| response.pointer_mut("/properties/items"))
+    else {
+        return;
+    };
+    items["items"] = serde_json::json!({"$ref": format!("#/components/schemas/{}", name)});
+    schemas.insert(name, item);
+}
+
+/// Enums replaced by the hand-written ones in `crate::enums`, which take values a newer
+/// server adds instead of failing to deserialize, and whether they implement `Default`.
+const OPEN_ENUMS: [(&str, bool); 3] =
+    [("Budget", true), ("FactTypesItem", false), ("Status", false)];
+
 fn convert_anyof_to_nullable(value: &mut serde_json::Value) {
     match value {
         serde_json::Value::Object(obj) => {
@@ -124,6 +233,13 @@ fn main() {
     let mut spec_json: serde_json::Value = serde_json::from_str(&spec_content)
         .expect("Failed to parse openapi.json");
 
+    // Expose the spec revision as hindsight_client::SPEC_VERSION
+    let spec_version = spec_json
+        .pointer("/info/version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    println!("cargo:rustc-env=HINDSIGHT_SPEC_VERSION={}", spec_version);
+
     // Convert OpenAPI 3.1.0 to 3.0.3 for progenitor compatibility
     if let Some(version) = spec_json.get("openapi").and_then(|v| v.as_str()) {
         if version.starts_with("3.1") {
@@ -135,12 +251,24 @@ fn main() {
     // Filter out multipart/form-data endpoints (progenitor doesn't support them)
     filter_multipart_endpoints(&mut spec_json);
 
+    // Type the items of memory and document listings instead of leaving them as JSON maps
+    type_list_items(&mut spec_json, "ListMemoryUnitsResponse", memory_list_item());
+    type_list_items(&mut spec_json, "ListDocumentsResponse", document_list_item());
+
     // Now parse as OpenAPI struct
     let spec: openapiv3::OpenAPI = serde_json::from_value(spec_json)
         .expect("Failed to parse converted OpenAPI spec");
 
-    // Generate the client
-    let mut generator = progenitor::Generator::default();
+    // Generate the client, with enums that keep unknown values in place of the generated ones
+    let mut settings = progenitor::GenerationSettings::default();
+    for (name, has_default) in OPEN_ENUMS {
+        let mut impls = vec![progenitor::TypeImpl::Display, progenitor::TypeImpl::FromStr];
+        if has_default {
+            impls.push(progenitor::TypeImpl::Default);
+        }
+        settings.with_replacement(name, format!("crate::enums::{}", name), impls.into_iter());
+    }
+    let mut generator = progenitor::Generator::new(&settings);
 
     // Generate code
     let tokens = generator.generate_tokens(&spec)
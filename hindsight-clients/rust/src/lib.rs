@@ -26,6 +26,10 @@ include!(concat!(env!("OUT_DIR"), "/hindsight_client_generated.rs"));
 /// wrappers when a coordinated release is cut.
 pub const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// `info.version` of the OpenAPI spec this client was generated from, for checking
+/// a server's reported version against.
+pub const SPEC_VERSION: &str = env!("HINDSIGHT_SPEC_VERSION");
+
 /// Default `User-Agent` header sent on every request unless overridden.
 pub const DEFAULT_USER_AGENT: &str = concat!("hindsight-client-rust/", env!("CARGO_PKG_VERSION"));
 
@@ -61,10 +65,520 @@ pub fn default_client(base_url: &str) -> Result<Client, reqwest::Error> {
     client_with_user_agent(base_url, DEFAULT_USER_AGENT)
 }
 
+/// Header carrying the ID of every request. Each request gets a new UUID v4 unless
+/// the call runs inside [`with_request_id`].
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Response headers a server or proxy may identify a request by, most specific first
+const SERVER_REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-trace-id", "traceparent"];
+
+static REQUEST_ID_PREFIX: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Start every generated request ID with `prefix` (`<prefix>-<uuid>`), so the
+/// requests of one service stand out in shared traces. Set it once at startup;
+/// returns `false` if a prefix was already set.
+pub fn set_request_id_prefix(prefix: impl Into<String>) -> bool {
+    REQUEST_ID_PREFIX.set(prefix.into()).is_ok()
+}
+
+/// A new request ID: a UUID v4, after the prefix if one is set
+pub fn new_request_id() -> String {
+    let id = uuid::Uuid::new_v4();
+    match REQUEST_ID_PREFIX.get() {
+        Some(prefix) => format!("{}-{}", prefix, id),
+        None => id.to_string(),
+    }
+}
+
+/// The IDs of a request made inside [`with_request_id`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestIds {
+    /// Sent as the `X-Request-Id` header
+    pub sent: String,
+    /// The request or trace ID the server returned, if any
+    pub server: Option<String>,
+}
+
+tokio::task_local! {
+    static REQUEST_SCOPE: std::sync::Arc<std::sync::Mutex<RequestIds>>;
+}
+
+/// Run `call`, sending `request_id` (or a new ID if `None`) with every request it
+/// makes, and return its output with the request IDs. When `call` sends several
+/// requests, `server` is from the last response.
+///
+/// ```rust,no_run
+/// # async fn example(client: &hindsight_client::Client) {
+/// let (result, ids) =
+///     hindsight_client::with_request_id(None, client.list_banks(None)).await;
+/// if result.is_err() {
+///     eprintln!("request {} failed (server ID: {:?})", ids.sent, ids.server);
+/// }
+/// # }
+/// ```
+pub async fn with_request_id<F: std::future::Future>(
+    request_id: Option<String>,
+    call: F,
+) -> (F::Output, RequestIds) {
+    let sent = request_id.unwrap_or_else(new_request_id);
+    let scope = std::sync::Arc::new(std::sync::Mutex::new(RequestIds { sent, server: None }));
+    let output = REQUEST_SCOPE.scope(scope.clone(), call).await;
+    let ids = scope.lock().unwrap().clone();
+    (output, ids)
+}
+
+/// The request ID of the enclosing [`with_request_id`] call, for requests sent
+/// outside the generated client
+pub fn current_request_id() -> Option<String> {
+    REQUEST_SCOPE.try_with(|ids| ids.lock().unwrap().sent.clone()).ok()
+}
+
+/// The request or trace ID a server returned in `headers`
+pub fn server_request_id(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    SERVER_REQUEST_ID_HEADERS.iter().find_map(|name| {
+        let value = headers.get(*name)?.to_str().ok()?;
+        Some(value.to_string())
+    })
+}
+
+// Overrides the generated `&Client` hooks (progenitor's auto-ref specialization):
+// every request carries an ID and runs in a span that records both IDs.
+impl ClientHooks for Client {
+    async fn exec(
+        &self,
+        mut request: reqwest::Request,
+        info: &OperationInfo,
+    ) -> reqwest::Result<reqwest::Response> {
+        let sent = current_request_id().unwrap_or_else(new_request_id);
+        if let Ok(value) = reqwest::header::HeaderValue::from_str(&sent) {
+            request.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+        let span = tracing::debug_span!(
+            "hindsight_request",
+            operation = info.operation_id,
+            request_id = %sent,
+            server_request_id = tracing::field::Empty,
+        );
+        let result =
+            tracing::Instrument::instrument(self.client.execute(request), span.clone()).await;
+        let server = result.as_ref().ok().and_then(|r| server_request_id(r.headers()));
+        if let Some(server) = &server {
+            span.record("server_request_id", server.as_str());
+        }
+        let _ = REQUEST_SCOPE.try_with(|ids| ids.lock().unwrap().server = server);
+        result
+    }
+}
+
+/// Similarity above which the CLI's `--dedupe fuzzy` folds two recall results; see
+/// [`types::RecallResponse::deduplicated`]
+pub const DEFAULT_DEDUPE_THRESHOLD: f64 = 0.6;
+
+/// A recall response with near-duplicate results folded together
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DedupedRecall {
+    #[serde(flatten)]
+    pub response: types::RecallResponse,
+    /// Kept result ID → IDs of the lower-ranked results folded into it, in rank order.
+    /// Results that absorbed nothing are left out.
+    pub folded: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+impl types::RecallResponse {
+    /// Fold results whose normalized text is identical, or whose Jaccard similarity
+    /// over word-pair shingles exceeds `threshold`, into the highest-ranked of them.
+    /// A `threshold` of 1.0 folds exact duplicates only.
+    pub fn deduplicated(&self, threshold: f64) -> DedupedRecall {
+        let mut results: Vec<types::RecallResult> = Vec::new();
+        let mut kept: Vec<(String, std::collections::HashSet<String>)> = Vec::new();
+        let mut folded = std::collections::BTreeMap::<String, Vec<String>>::new();
+        for result in &self.results {
+            let normalized = normalize_text(&result.text);
+            let shingles = word_shingles(&normalized);
+            let duplicate_of = kept.iter().position(|(text, other)| {
+                *text == normalized || jaccard(&shingles, other) > threshold
+            });
+            match duplicate_of {
+                Some(index) => folded
+                    .entry(results[index].id.clone())
+                    .or_default()
+                    .push(result.id.clone()),
+                None => {
+                    results.push(result.clone());
+                    kept.push((normalized, shingles));
+                }
+            }
+        }
+        let mut response = self.clone();
+        response.results = results;
+        DedupedRecall { response, folded }
+    }
+}
+
+/// Lowercase words without punctuation, separated by single spaces
+fn normalize_text(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Pairs of adjacent words; single words for texts shorter than two words
+fn word_shingles(normalized: &str) -> std::collections::HashSet<String> {
+    let words: Vec<&str> = normalized.split(' ').filter(|w| !w.is_empty()).collect();
+    if words.len() < 2 {
+        return words.into_iter().map(str::to_string).collect();
+    }
+    words.windows(2).map(|pair| pair.join(" ")).collect()
+}
+
+fn jaccard(a: &std::collections::HashSet<String>, b: &std::collections::HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Default server URL used when neither `HINDSIGHT_URL` nor `HINDSIGHT_API_URL` is set.
+pub const DEFAULT_BASE_URL: &str = "http://localhost:8888";
+
+/// Connection settings read from `HINDSIGHT_*` environment variables.
+///
+/// | Variable | Meaning |
+/// |---|---|
+/// | `HINDSIGHT_URL` (or `HINDSIGHT_API_URL`) | Server base URL |
+/// | `HINDSIGHT_API_KEY` | Bearer token |
+/// | `HINDSIGHT_TIMEOUT_SECS` | Request timeout in whole seconds |
+/// | `HINDSIGHT_DEFAULT_BANK` | Bank to use when the caller doesn't name one |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvConfig {
+    /// `None` when no URL variable is set; see [`EnvConfig::base_url`]
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub timeout: Option<std::time::Duration>,
+    pub default_bank: Option<String>,
+}
+
+/// Error returned by [`EnvConfig::from_env`] and [`Client::from_env`].
+#[derive(Debug, thiserror::Error)]
+pub enum FromEnvError {
+    /// Every invalid variable, so they can all be fixed at once
+    #[error("invalid Hindsight environment configuration:\n  {}", .0.join("\n  "))]
+    InvalidVars(Vec<String>),
+    #[error("failed to build HTTP client: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+impl EnvConfig {
+    /// Read the configuration from the process environment.
+    pub fn from_env() -> Result<Self, FromEnvError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read the configuration through `lookup`, treating empty values as unset.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, FromEnvError> {
+        let get = |name: &str| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let mut problems = Vec::new();
+
+        let base_url = get("HINDSIGHT_URL")
+            .map(|v| ("HINDSIGHT_URL", v))
+            .or_else(|| get("HINDSIGHT_API_URL").map(|v| ("HINDSIGHT_API_URL", v)));
+        let base_url = base_url.map(|(name, value)| {
+            match url::Url::parse(&value) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                Ok(_) => problems.push(format!("{}={:?}: must start with http:// or https://", name, value)),
+                Err(e) => problems.push(format!("{}={:?}: {}", name, value, e)),
+            }
+            value
+        });
+
+        let timeout = get("HINDSIGHT_TIMEOUT_SECS").and_then(|value| match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Some(std::time::Duration::from_secs(secs)),
+            _ => {
+                problems.push(format!(
+                    "HINDSIGHT_TIMEOUT_SECS={:?}: expected a positive whole number of seconds",
+                    value
+                ));
+                None
+            }
+        });
+
+        if !problems.is_empty() {
+            return Err(FromEnvError::InvalidVars(problems));
+        }
+        Ok(EnvConfig {
+            base_url,
+            api_key: get("HINDSIGHT_API_KEY"),
+            timeout,
+            default_bank: get("HINDSIGHT_DEFAULT_BANK"),
+        })
+    }
+
+    /// The configured base URL, or [`DEFAULT_BASE_URL`]
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
+    }
+
+    /// Build a [`reqwest::Client`] with the default `User-Agent`, the API key
+    /// as a bearer token, and the configured timeout.
+    pub fn http_client(&self) -> Result<reqwest::Client, FromEnvError> {
+        let mut builder = reqwest::Client::builder().user_agent(DEFAULT_USER_AGENT);
+        if let Some(key) = &self.api_key {
+            let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", key))
+                .map_err(|_| {
+                    FromEnvError::InvalidVars(vec![
+                        "HINDSIGHT_API_KEY: contains characters not allowed in an HTTP header".to_string(),
+                    ])
+                })?;
+            value.set_sensitive(true);
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+            builder = builder.default_headers(headers);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl Client {
+    /// Construct a client from `HINDSIGHT_*` environment variables (see [`EnvConfig`]).
+    ///
+    /// All invalid variables are reported together in [`FromEnvError::InvalidVars`].
+    pub fn from_env() -> Result<Client, FromEnvError> {
+        let config = EnvConfig::from_env()?;
+        let http = config.http_client()?;
+        Ok(Client::new_with_client(config.base_url(), http))
+    }
+}
+
+/// Declare a string enum that keeps values it does not know in an `Unknown` variant.
+///
+/// Serializes to, and parses from, exactly the listed strings, so a value a newer
+/// server adds degrades to `Unknown` instead of failing the whole response.
+macro_rules! open_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident { $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)+ }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            /// A value this client does not know, as the server sent it
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value as it goes over the wire
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)+
+                    Self::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => Self::$variant,)+
+                    other => Self::Unknown(other.to_string()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self::from(value.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Ok(Self::from(value))
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
+
+/// Enums of the API that tolerate values newer servers add.
+///
+/// The generated [`types`] use these in place of the enums of the same name (see
+/// `build.rs`), so a novel fact type, budget or operation status reads as `Unknown`.
+pub mod enums {
+    open_enum! {
+        /// How much effort recall and reflect spend
+        Budget {
+            Low = "low",
+            Mid = "mid",
+            High = "high",
+        }
+    }
+
+    impl Default for Budget {
+        /// The server's default
+        fn default() -> Self {
+            Budget::Mid
+        }
+    }
+
+    open_enum! {
+        /// Kind of a memory: a fact about the world, the bank's own experience, or an
+        /// observation consolidated from other memories
+        FactTypesItem {
+            World = "world",
+            Experience = "experience",
+            Observation = "observation",
+        }
+    }
+
+    open_enum! {
+        /// State of an asynchronous operation
+        Status {
+            Pending = "pending",
+            Processing = "processing",
+            Completed = "completed",
+            Failed = "failed",
+            Cancelled = "cancelled",
+            NotFound = "not_found",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const ENV_VARS: [&str; 5] = [
+        "HINDSIGHT_URL",
+        "HINDSIGHT_API_URL",
+        "HINDSIGHT_API_KEY",
+        "HINDSIGHT_TIMEOUT_SECS",
+        "HINDSIGHT_DEFAULT_BANK",
+    ];
+
+    /// Run `f` with exactly `vars` set among the Hindsight variables
+    fn with_env<R>(vars: &[(&str, &str)], f: impl FnOnce() -> R) -> R {
+        let scoped: Vec<(&str, Option<&str>)> = ENV_VARS
+            .iter()
+            .map(|name| (*name, vars.iter().find(|(k, _)| k == name).map(|(_, v)| *v)))
+            .collect();
+        temp_env::with_vars(scoped, f)
+    }
+
+    #[test]
+    fn test_env_config_defaults() {
+        let config = with_env(&[], EnvConfig::from_env).unwrap();
+        assert_eq!(config.base_url, None);
+        assert_eq!(config.base_url(), DEFAULT_BASE_URL);
+        assert_eq!(config.api_key, None);
+        assert_eq!(config.timeout, None);
+        assert_eq!(config.default_bank, None);
+    }
+
+    #[test]
+    fn test_env_config_reads_all_variables() {
+        let config = with_env(
+            &[
+                ("HINDSIGHT_URL", "https://hindsight.example.com"),
+                ("HINDSIGHT_API_URL", "http://ignored:8888"),
+                ("HINDSIGHT_API_KEY", "secret"),
+                ("HINDSIGHT_TIMEOUT_SECS", "30"),
+                ("HINDSIGHT_DEFAULT_BANK", "alice"),
+            ],
+            EnvConfig::from_env,
+        )
+        .unwrap();
+        assert_eq!(config.base_url(), "https://hindsight.example.com");
+        assert_eq!(config.api_key.as_deref(), Some("secret"));
+        assert_eq!(config.timeout, Some(std::time::Duration::from_secs(30)));
+        assert_eq!(config.default_bank.as_deref(), Some("alice"));
+        assert!(with_env(&[("HINDSIGHT_API_KEY", "secret")], Client::from_env).is_ok());
+    }
+
+    #[test]
+    fn test_env_config_reports_every_invalid_variable() {
+        let err = with_env(
+            &[("HINDSIGHT_URL", "localhost:8888"), ("HINDSIGHT_TIMEOUT_SECS", "soon")],
+            EnvConfig::from_env,
+        )
+        .unwrap_err();
+        match err {
+            FromEnvError::InvalidVars(problems) => {
+                assert_eq!(problems.len(), 2);
+                assert!(problems[0].starts_with("HINDSIGHT_URL="));
+                assert!(problems[1].starts_with("HINDSIGHT_TIMEOUT_SECS="));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_open_enums_round_trip_and_keep_unknown_values() {
+        use enums::{Budget, FactTypesItem, Status};
+
+        for (value, text) in [(Budget::Low, "low"), (Budget::Mid, "mid"), (Budget::High, "high")] {
+            assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::json!(text));
+            assert_eq!(serde_json::from_value::<Budget>(serde_json::json!(text)).unwrap(), value);
+        }
+        for (value, text) in [
+            (FactTypesItem::World, "world"),
+            (FactTypesItem::Experience, "experience"),
+            (FactTypesItem::Observation, "observation"),
+        ] {
+            assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::json!(text));
+            let parsed: FactTypesItem = serde_json::from_value(serde_json::json!(text)).unwrap();
+            assert_eq!(parsed, value);
+        }
+        for (value, text) in [
+            (Status::Pending, "pending"),
+            (Status::Processing, "processing"),
+            (Status::Completed, "completed"),
+            (Status::Failed, "failed"),
+            (Status::Cancelled, "cancelled"),
+            (Status::NotFound, "not_found"),
+        ] {
+            assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::json!(text));
+            assert_eq!(value.to_string(), text);
+            assert_eq!(text.parse::<Status>().unwrap(), value);
+        }
+
+        // A value from a newer server survives a round trip unchanged
+        let novel: FactTypesItem = serde_json::from_value(serde_json::json!("opinion")).unwrap();
+        assert_eq!(novel, FactTypesItem::Unknown("opinion".to_string()));
+        assert_eq!(serde_json::to_value(&novel).unwrap(), serde_json::json!("opinion"));
+        let response: types::OperationStatusResponse = serde_json::from_value(serde_json::json!({
+            "operation_id": "op-1",
+            "status": "paused",
+        }))
+        .unwrap();
+        assert_eq!(response.status, Status::Unknown("paused".to_string()));
+    }
+
     #[test]
     fn test_client_creation() {
         let _client = Client::new("http://localhost:8888");
@@ -72,6 +586,120 @@ mod tests {
         assert!(true);
     }
 
+    #[test]
+    fn test_memory_list_item_tolerates_missing_and_unknown_fields() {
+        let response: types::ListMemoryUnitsResponse = serde_json::from_value(serde_json::json!({
+            "items": [
+                {"id": "m1", "text": "Alice works at Google"},
+                {
+                    "id": "m2",
+                    "text": "Bob likes tea",
+                    "fact_type": "world",
+                    "occurred_start": null,
+                    "entities": "Bob (PERSON)",
+                    "proof_count": 2
+                }
+            ],
+            "total": 2,
+            "limit": 100,
+            "offset": 0
+        }))
+        .unwrap();
+
+        let old = &response.items[0];
+        assert_eq!(old.id, "m1");
+        assert_eq!(old.fact_type, None);
+        assert!(old.extra.is_empty());
+
+        let new = &response.items[1];
+        assert_eq!(new.fact_type.as_deref(), Some("world"));
+        assert_eq!(new.occurred_start, None);
+        assert_eq!(new.extra.get("entities"), Some(&serde_json::json!("Bob (PERSON)")));
+        assert_eq!(serde_json::to_value(new).unwrap()["proof_count"], 2);
+    }
+
+    #[test]
+    fn test_document_list_item_keeps_unknown_fields() {
+        let item: types::DocumentListItem = serde_json::from_value(serde_json::json!({
+            "id": "notes",
+            "text_length": 5420,
+            "memory_unit_count": 15,
+            "content_hash": null,
+            "retain_params": {"content_type": "text/markdown"},
+            "source_url": "https://example.com/notes"
+        }))
+        .unwrap();
+        assert_eq!(item.text_length, Some(5420));
+        assert_eq!(item.created_at, None);
+        assert_eq!(item.retain_params.unwrap()["content_type"], "text/markdown");
+        assert_eq!(item.extra["source_url"], "https://example.com/notes");
+    }
+
+    fn recall_response(texts: &[(&str, &str)]) -> types::RecallResponse {
+        let results: Vec<serde_json::Value> = texts
+            .iter()
+            .map(|(id, text)| serde_json::json!({"id": id, "text": text}))
+            .collect();
+        serde_json::from_value(serde_json::json!({ "results": results })).unwrap()
+    }
+
+    fn ids(response: &types::RecallResponse) -> Vec<&str> {
+        response.results.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_deduplicated_folds_exact_duplicates_into_the_top_result() {
+        let response = recall_response(&[
+            ("a", "Alice works at Google."),
+            ("b", "Bob likes tea"),
+            ("c", "alice works at  google"),
+            ("d", "ALICE WORKS AT GOOGLE!"),
+        ]);
+        let deduped = response.deduplicated(1.0);
+        assert_eq!(ids(&deduped.response), ["a", "b"]);
+        assert_eq!(deduped.folded.len(), 1);
+        assert_eq!(deduped.folded["a"], ["c", "d"]);
+        assert_eq!(response.results.len(), 4);
+    }
+
+    #[test]
+    fn test_deduplicated_fuzzy_uses_the_threshold() {
+        let response = recall_response(&[
+            ("a", "Alice has worked at Google since 2019 as a software engineer"),
+            ("b", "Alice has worked at Google since 2019 as a senior software engineer"),
+            ("c", "Bob moved to Berlin in March"),
+            ("d", "Alice enjoys hiking on weekends"),
+        ]);
+        let fuzzy = response.deduplicated(DEFAULT_DEDUPE_THRESHOLD);
+        assert_eq!(ids(&fuzzy.response), ["a", "c", "d"]);
+        assert_eq!(fuzzy.folded["a"], ["b"]);
+
+        let exact = response.deduplicated(1.0);
+        assert_eq!(exact.response.results.len(), 4);
+        assert!(exact.folded.is_empty());
+
+        let json = serde_json::to_value(&fuzzy).unwrap();
+        assert_eq!(json["results"].as_array().unwrap().len(), 3);
+        assert_eq!(json["folded"]["a"][0], "b");
+    }
+
+    #[tokio::test]
+    async fn test_with_request_id_scopes_the_id() {
+        assert_eq!(current_request_id(), None);
+        let ((), ids) = with_request_id(Some("caller-1".to_string()), async {
+            assert_eq!(current_request_id().as_deref(), Some("caller-1"));
+        })
+        .await;
+        assert_eq!(ids, RequestIds { sent: "caller-1".to_string(), server: None });
+
+        let ((), generated) = with_request_id(None, async {}).await;
+        assert!(uuid::Uuid::parse_str(&generated.sent).is_ok());
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-trace-id", "trace-9".parse().unwrap());
+        assert_eq!(server_request_id(&headers).as_deref(), Some("trace-9"));
+    }
+
     #[tokio::test]
     async fn test_memory_lifecycle() {
         let api_url = std::env::var("HINDSIGHT_API_URL")